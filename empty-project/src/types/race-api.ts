@@ -161,6 +161,8 @@ export interface LapHistory {
     laps_in_cycle: number[];
     average_boost: number;
   }>;
+  /** Cursor for the next page of laps when `limit` was passed; null on the last page */
+  next_cursor: string | null;
 }
 
 // ============================================================================
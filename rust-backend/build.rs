@@ -0,0 +1,20 @@
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=proto");
+
+    #[cfg(feature = "grpc")]
+    if let Err(e) = compile_protos() {
+        panic!("Failed to generate the gRPC service: {e}");
+    }
+}
+
+/// The gRPC service is generated from the protobuf definitions, with the
+/// vendored protoc so no system install is needed
+#[cfg(feature = "grpc")]
+fn compile_protos() -> Result<(), Box<dyn std::error::Error>> {
+    std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+    tonic_build::configure()
+        .build_client(true)
+        .compile_protos(&["proto/boardurance/race/v1/race.proto"], &["proto"])?;
+    Ok(())
+}
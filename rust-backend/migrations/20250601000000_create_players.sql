@@ -0,0 +1,10 @@
+-- Players are stored as their full JSON document, with the lookup keys
+-- duplicated into columns so they can be indexed and kept unique.
+CREATE TABLE players (
+    uuid UUID PRIMARY KEY,
+    email TEXT NOT NULL UNIQUE,
+    wallet_address TEXT UNIQUE,
+    document JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
@@ -0,0 +1,21 @@
+CREATE TABLE races (
+    uuid UUID PRIMARY KEY,
+    name TEXT NOT NULL,
+    status TEXT NOT NULL,
+    document JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX races_status_idx ON races (status);
+
+-- One row per entrant, rewritten whenever the race document is saved
+CREATE TABLE race_participants (
+    race_uuid UUID NOT NULL REFERENCES races (uuid) ON DELETE CASCADE,
+    player_uuid UUID NOT NULL,
+    car_uuid UUID NOT NULL,
+    pilot_uuid UUID NOT NULL,
+    PRIMARY KEY (race_uuid, player_uuid)
+);
+
+CREATE INDEX race_participants_pilot_idx ON race_participants (pilot_uuid);
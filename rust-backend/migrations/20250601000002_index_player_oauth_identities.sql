@@ -0,0 +1,3 @@
+-- Finds the player a Google or Discord account is linked to.
+CREATE INDEX players_oauth_identities_idx
+    ON players USING GIN ((document->'oauth_identities') jsonb_path_ops);
@@ -1,16 +1,30 @@
 use std::sync::Arc;
 
 use crate::repositories::{PlayerRepository, RaceRepository, SessionRepository};
-use crate::services::{JwtService, SessionManager};
+use crate::services::{JwtService, LoginThrottle, SessionManager};
 
 /// Application state that holds shared services
-#[derive(Clone)]
 pub struct AppState<P: PlayerRepository, R: RaceRepository, S: SessionRepository> {
     pub player_repository: Arc<P>,
     pub race_repository: Arc<R>,
     pub session_repository: Arc<S>,
     pub jwt_service: Arc<JwtService>,
     pub session_manager: Arc<SessionManager<S>>,
+    pub login_throttle: LoginThrottle,
+}
+
+// Implemented by hand so the repositories themselves need not be `Clone`
+impl<P: PlayerRepository, R: RaceRepository, S: SessionRepository> Clone for AppState<P, R, S> {
+    fn clone(&self) -> Self {
+        Self {
+            player_repository: Arc::clone(&self.player_repository),
+            race_repository: Arc::clone(&self.race_repository),
+            session_repository: Arc::clone(&self.session_repository),
+            jwt_service: Arc::clone(&self.jwt_service),
+            session_manager: Arc::clone(&self.session_manager),
+            login_throttle: self.login_throttle.clone(),
+        }
+    }
 }
 
 impl<P: PlayerRepository, R: RaceRepository, S: SessionRepository> AppState<P, R, S> {
@@ -21,6 +35,7 @@ impl<P: PlayerRepository, R: RaceRepository, S: SessionRepository> AppState<P, R
         session_repository: Arc<S>,
         jwt_service: Arc<JwtService>,
         session_manager: Arc<SessionManager<S>>,
+        login_throttle: LoginThrottle,
     ) -> Self {
         Self {
             player_repository,
@@ -28,6 +43,7 @@ impl<P: PlayerRepository, R: RaceRepository, S: SessionRepository> AppState<P, R
             session_repository,
             jwt_service,
             session_manager,
+            login_throttle,
         }
     }
 }
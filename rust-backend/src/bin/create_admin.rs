@@ -1,5 +1,6 @@
 use mongodb::{Client, Database};
-use rust_backend::domain::{Car, Email, Password, Pilot, Player, TeamName, UserRole};
+use rust_backend::domain::{Email, Password, Player, TeamName, UserRole};
+use rust_backend::services::starter_pack::StarterPack;
 use std::env;
 
 #[tokio::main]
@@ -19,32 +20,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let password = Password::new(password)?;
     let password_hash = password.hash()?;
 
-    // Create basic car and pilot for the admin user
-    let car_name = rust_backend::domain::CarName::parse("Admin Car")?;
-    let car = Car::new(car_name, None)?;
-
-    let pilot_name = rust_backend::domain::PilotName::parse("Admin Pilot")?;
-    let pilot_skills = rust_backend::domain::PilotSkills {
-        reaction_time: 50,
-        precision: 50,
-        focus: 50,
-        stamina: 50,
-    };
-    let pilot_performance = rust_backend::domain::PilotPerformance {
-        straight_value: 50,
-        curve_value: 50,
-    };
-    let pilot = Pilot::new(
-        pilot_name,
-        rust_backend::domain::PilotClass::AllRounder,
-        rust_backend::domain::PilotRarity::Professional,
-        pilot_skills,
-        pilot_performance,
-        None,
-    )?;
-
-    // Create admin player
-    let mut admin_player = Player::new(email, password_hash, team_name, vec![car], vec![pilot])?;
+    // Create admin player with the same starter pack as every new player
+    let mut admin_player = StarterPack::new()?.grant(email, password_hash, team_name)?;
 
     // Set admin role
     admin_player.update_role(UserRole::Admin);
@@ -0,0 +1,320 @@
+use std::collections::HashMap;
+
+use clap::{Parser, ValueEnum};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rust_backend::domain::{
+    builtin_track_templates, BoostHand, BotPersonality, BotProfile, CarLapValues, MovementType,
+    PerformanceModelKind, RaceParticipant, Sector,
+};
+use rust_backend::engine::{self, Grid};
+use uuid::Uuid;
+
+/// Run seeded bot-only races and report balance statistics
+///
+/// Example: `cargo run --bin simulate -- --races 5000 --track grand-prix
+/// --bots aggressive:0.9,adaptive:0.9,conservative:0.5 --deck 0,1,2,4`
+#[derive(Parser, Debug)]
+#[command(name = "simulate")]
+struct Args {
+    /// Number of races to run
+    #[arg(long, default_value_t = 1000)]
+    races: u32,
+
+    /// Seed for the random number generator; the same seed gives the same results
+    #[arg(long, default_value_t = 42)]
+    seed: u64,
+
+    /// Built-in track template id
+    #[arg(long, default_value = "beginner-loop")]
+    track: String,
+
+    /// Laps per race (defaults to the template's recommended laps)
+    #[arg(long)]
+    laps: Option<u32>,
+
+    /// Boost card values in each bot's hand; the hand refills once every card is played
+    #[arg(long, value_delimiter = ',', default_value = "0,1,2,3,4")]
+    deck: Vec<u32>,
+
+    /// Bots taking part, as `personality:difficulty` entries
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_parser = parse_bot,
+        default_value = "aggressive:0.8,conservative:0.8,adaptive:0.8"
+    )]
+    bots: Vec<BotProfile>,
+
+    /// Performance formula used to apply boosts
+    #[arg(long, value_enum, default_value_t = Model::Additive)]
+    model: Model,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Model {
+    Additive,
+    Multiplicative,
+    DiminishingReturns,
+}
+
+impl From<Model> for PerformanceModelKind {
+    fn from(model: Model) -> Self {
+        match model {
+            Model::Additive => PerformanceModelKind::Additive,
+            Model::Multiplicative => PerformanceModelKind::Multiplicative,
+            Model::DiminishingReturns => PerformanceModelKind::DiminishingReturns,
+        }
+    }
+}
+
+fn parse_bot(value: &str) -> Result<BotProfile, String> {
+    let (personality, difficulty) = value.split_once(':').unwrap_or((value, "1.0"));
+    let personality = match personality.to_lowercase().as_str() {
+        "aggressive" => BotPersonality::Aggressive,
+        "conservative" => BotPersonality::Conservative,
+        "adaptive" => BotPersonality::Adaptive,
+        other => return Err(format!("Unknown bot personality: {other}")),
+    };
+    let difficulty = difficulty
+        .parse::<f64>()
+        .map_err(|e| format!("Invalid bot difficulty '{difficulty}': {e}"))?;
+
+    BotProfile::new(personality, difficulty)
+}
+
+/// Boost cards of one bot for the current cycle
+struct Deck {
+    cards: Vec<u32>,
+    remaining: Vec<u32>,
+}
+
+impl Deck {
+    fn new(cards: &[u32]) -> Self {
+        Self {
+            cards: cards.to_vec(),
+            remaining: cards.to_vec(),
+        }
+    }
+
+    fn available(&self) -> Vec<u8> {
+        #[allow(clippy::cast_possible_truncation)]
+        self.remaining.iter().map(|&card| card as u8).collect()
+    }
+
+    fn play(&mut self, card: u32) {
+        if let Some(index) = self.remaining.iter().position(|&c| c == card) {
+            self.remaining.swap_remove(index);
+        }
+        if self.remaining.is_empty() {
+            self.remaining.clone_from(&self.cards);
+        }
+    }
+}
+
+/// Totals gathered over all simulated races
+struct Stats {
+    wins: Vec<u32>,
+    finish_position_sum: Vec<u64>,
+    turns: u64,
+    /// Cars in each sector, summed over every turn
+    occupancy: Vec<u64>,
+    /// Turns a limited sector was full
+    full_turns: Vec<u64>,
+    /// Cars that beat a sector's maximum but could not move up
+    blocked: Vec<u64>,
+}
+
+impl Stats {
+    fn new(bots: usize, sectors: usize) -> Self {
+        Self {
+            wins: vec![0; bots],
+            finish_position_sum: vec![0; bots],
+            turns: 0,
+            occupancy: vec![0; sectors],
+            full_turns: vec![0; sectors],
+            blocked: vec![0; sectors],
+        }
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+
+    let template = builtin_track_templates()
+        .into_iter()
+        .find(|t| t.template_id == args.track)
+        .ok_or_else(|| format!("Unknown track template: {}", args.track))?;
+    let laps = args.laps.unwrap_or(template.recommended_laps);
+    if args.bots.is_empty() {
+        return Err("At least one bot is required".into());
+    }
+    if args.deck.is_empty() {
+        return Err("The deck needs at least one card".into());
+    }
+    for &card in &args.deck {
+        engine::validate_boost_value(card)?;
+    }
+
+    let sectors = template.sectors;
+    let model = PerformanceModelKind::from(args.model);
+    let mut rng = StdRng::seed_from_u64(args.seed);
+    let mut stats = Stats::new(args.bots.len(), sectors.len());
+
+    for _ in 0..args.races {
+        run_race(&args, &sectors, laps, model, &mut rng, &mut stats);
+    }
+
+    print_report(&args, &template.name, &sectors, laps, &stats);
+    Ok(())
+}
+
+fn run_race(
+    args: &Args,
+    sectors: &[Sector],
+    laps: u32,
+    model: PerformanceModelKind,
+    rng: &mut StdRng,
+    stats: &mut Stats,
+) {
+    // Shuffle the grid so ties are not always won by the first bot
+    let mut order: Vec<usize> = (0..args.bots.len()).collect();
+    order.shuffle(rng);
+
+    let mut participants: Vec<RaceParticipant> = order
+        .iter()
+        .map(|&bot_index| RaceParticipant {
+            player_uuid: Uuid::from_u128(rng.gen()),
+            car_uuid: Uuid::from_u128(rng.gen()),
+            pilot_uuid: Uuid::from_u128(rng.gen()),
+            current_sector: 0,
+            current_position_in_sector: 0,
+            current_lap: 1,
+            total_value: 0,
+            is_finished: false,
+            finish_position: None,
+            boost_hand: BoostHand::new(),
+            boost_usage_history: Vec::new(),
+            bot: Some(args.bots[bot_index]),
+            ghost: None,
+            ballast: 0,
+            car_class: None,
+            class_position: None,
+            is_loaner: false,
+        })
+        .collect();
+    let mut decks: Vec<Deck> = order.iter().map(|_| Deck::new(&args.deck)).collect();
+
+    // Like a race, the last lap is played out before the standings are final
+    for turn in 1..=laps + 1 {
+        if participants.iter().all(|p| p.is_finished) {
+            break;
+        }
+        stats.turns += 1;
+
+        let mut participant_values: HashMap<Uuid, u32> = HashMap::new();
+        for (index, participant) in participants.iter().enumerate() {
+            let Some(profile) = participant.bot.filter(|_| !participant.is_finished) else {
+                continue;
+            };
+            let sector = &sectors[participant.current_sector as usize];
+            let values = CarLapValues {
+                engine: profile.engine_value,
+                body: profile.body_value,
+                pilot: profile.pilot_value,
+            };
+            let drs = engine::has_drs(&participants, participant);
+            let final_value = |card: u32| {
+                engine::calculate_performance(model.model(), sector, values, card, 0, drs)
+                    .final_value
+            };
+
+            let card = u32::from(profile.choose_boost(
+                &decks[index].available(),
+                sector,
+                |card| final_value(u32::from(card)),
+                rng,
+            ));
+            decks[index].play(card);
+            participant_values.insert(participant.player_uuid, final_value(card));
+        }
+
+        let mut grid = Grid {
+            sectors,
+            participants: &mut participants,
+            current_lap: turn,
+            total_laps: laps,
+        };
+        let movements = grid.resolve_movements(&participant_values);
+        grid.add_lap_values(&participant_values);
+        grid.sort_positions();
+
+        for movement in &movements {
+            let sector = &sectors[movement.from_sector as usize];
+            if movement.movement_type == MovementType::StayedInSector
+                && movement.final_value > sector.max_value
+            {
+                stats.blocked[movement.from_sector as usize] += 1;
+            }
+        }
+        for sector in sectors {
+            let cars = participants
+                .iter()
+                .filter(|p| !p.is_finished && p.current_sector == sector.id)
+                .count() as u64;
+            stats.occupancy[sector.id as usize] += cars;
+            if sector
+                .capacity_for_lap(turn)
+                .is_some_and(|capacity| cars >= u64::from(capacity))
+            {
+                stats.full_turns[sector.id as usize] += 1;
+            }
+        }
+    }
+
+    engine::assign_finish_positions(&mut participants);
+    for (participant, &bot_index) in participants.iter().zip(&order) {
+        let position = participant.finish_position.unwrap_or_default();
+        if position == 1 {
+            stats.wins[bot_index] += 1;
+        }
+        stats.finish_position_sum[bot_index] += u64::from(position);
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn print_report(args: &Args, track_name: &str, sectors: &[Sector], laps: u32, stats: &Stats) {
+    let races = f64::from(args.races.max(1));
+    let turns = stats.turns.max(1) as f64;
+
+    println!(
+        "{} races on {track_name} ({laps} laps), seed {}, deck {:?}, {:?} model",
+        args.races, args.seed, args.deck, args.model
+    );
+
+    println!("\nBot                      Win rate  Avg finish");
+    for (index, bot) in args.bots.iter().enumerate() {
+        let label = format!("{:?} @ {:.2}", bot.personality, bot.difficulty);
+        println!(
+            "{label:<24} {:>7.1}%  {:>10.2}",
+            f64::from(stats.wins[index]) / races * 100.0,
+            stats.finish_position_sum[index] as f64 / races
+        );
+    }
+
+    println!("\nSector                   Capacity  Avg cars  Full turns  Blocked/race");
+    for sector in sectors {
+        let index = sector.id as usize;
+        let capacity = sector
+            .slot_capacity
+            .map_or_else(|| "-".to_string(), |c| c.to_string());
+        println!(
+            "{:<24} {capacity:>8}  {:>8.2}  {:>9.1}%  {:>12.2}",
+            format!("{} {}", sector.id, sector.name),
+            stats.occupancy[index] as f64 / turns,
+            stats.full_turns[index] as f64 / turns * 100.0,
+            stats.blocked[index] as f64 / races
+        );
+    }
+}
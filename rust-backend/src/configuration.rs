@@ -1,12 +1,70 @@
 use config::{Config, ConfigError, Environment as ConfigEnvironment, File};
 use secrecy::{ExposeSecret, Secret};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::convert::{TryFrom, TryInto};
+use std::net::IpAddr;
+use utoipa::ToSchema;
+
+use crate::domain::ComponentRarity;
 
 #[derive(Deserialize, Clone)]
 pub struct Settings {
     pub database: DatabaseSettings,
     pub application: ApplicationSettings,
+    #[serde(default)]
+    pub cache: CacheSettings,
+    #[serde(default)]
+    pub archive: ArchiveSettings,
+    #[serde(default)]
+    pub janitor: JanitorSettings,
+    #[serde(default)]
+    pub turn_deadlines: TurnDeadlineSettings,
+    #[serde(default)]
+    pub countdowns: CountdownSettings,
+    #[serde(default)]
+    pub webhooks: WebhookSettings,
+    #[serde(default)]
+    pub anchoring: AnchoringSettings,
+    #[serde(default)]
+    pub payouts: PayoutSettings,
+    #[serde(default)]
+    pub anticheat: AntiCheatSettings,
+    #[serde(default)]
+    pub analytics_export: AnalyticsExportSettings,
+    #[serde(default)]
+    pub erasure: ErasureSettings,
+    #[serde(default)]
+    pub events: EventSettings,
+    #[serde(default)]
+    pub outbox: OutboxSettings,
+    #[serde(default)]
+    pub push: PushSettings,
+    #[serde(default)]
+    pub email: EmailSettings,
+    #[serde(default)]
+    pub chat: ChatSettings,
+    #[serde(default)]
+    pub packs: PackSettings,
+    #[serde(default)]
+    pub discord: DiscordSettings,
+    #[serde(default)]
+    pub telemetry: TelemetrySettings,
+    #[serde(default)]
+    pub jwt: JwtSettings,
+    #[serde(default)]
+    pub sessions: SessionSettings,
+    #[serde(default)]
+    pub login_throttle: LoginThrottleSettings,
+    #[serde(default)]
+    pub oauth: OAuthSettings,
+    #[serde(default)]
+    pub secrets: SecretSettings,
+    #[serde(default)]
+    pub cors: CorsSettings,
+    #[serde(default)]
+    pub http: HttpSettings,
+    #[serde(default)]
+    pub grpc: GrpcSettings,
 }
 
 #[derive(Deserialize, Clone)]
@@ -15,6 +73,16 @@ pub struct ApplicationSettings {
     pub port: u16,
     pub host: String,
     pub base_url: String,
+    /// Serve HTTPS instead of HTTP (requires the `tls` feature)
+    #[serde(default)]
+    pub tls: Option<TlsSettings>,
+}
+
+/// Certificate chain and private key of the HTTPS listener, in PEM files
+#[derive(Deserialize, Clone)]
+pub struct TlsSettings {
+    pub cert_file: String,
+    pub key_file: String,
 }
 
 #[derive(Deserialize, Clone)]
@@ -26,6 +94,1238 @@ pub struct DatabaseSettings {
     pub host: String,
     pub database_name: String,
     pub require_ssl: bool,
+    #[serde(default)]
+    pub backend: DatabaseBackend,
+    /// Full `MongoDB` connection string, used instead of the fields above
+    /// when set; usually filled in from the secret store
+    #[serde(default)]
+    pub uri: Option<Secret<String>>,
+}
+
+/// Storage used by the application
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DatabaseBackend {
+    /// Connect to `MongoDB` using the database settings
+    #[default]
+    Mongodb,
+    /// Keep the accounts used by the auth routes in process memory; nothing
+    /// survives a restart and every other route, still on `MongoDB`, fails
+    Memory,
+    /// Store the accounts used by the auth routes in `PostgreSQL` (requires the
+    /// `postgres` feature); every other route, still on `MongoDB`, fails
+    Postgres,
+}
+
+/// Caching of race documents read by polling endpoints
+#[derive(Deserialize, Clone)]
+pub struct CacheSettings {
+    #[serde(default)]
+    pub backend: CacheBackend,
+    /// Seconds a cached race stays valid if no write invalidates it first
+    #[serde(default = "default_cache_ttl_seconds")]
+    pub ttl_seconds: u64,
+    /// Only used by the `redis` backend
+    #[serde(default = "default_redis_url")]
+    pub redis_url: String,
+    /// Seconds validated car data is reused per player and car; 0 disables it
+    /// Kept in process memory whatever the backend.
+    #[serde(default = "default_car_data_ttl_seconds")]
+    pub car_data_ttl_seconds: u64,
+}
+
+impl Default for CacheSettings {
+    fn default() -> Self {
+        Self {
+            backend: CacheBackend::default(),
+            ttl_seconds: default_cache_ttl_seconds(),
+            redis_url: default_redis_url(),
+            car_data_ttl_seconds: default_car_data_ttl_seconds(),
+        }
+    }
+}
+
+fn default_cache_ttl_seconds() -> u64 {
+    5
+}
+
+fn default_car_data_ttl_seconds() -> u64 {
+    30
+}
+
+fn default_redis_url() -> String {
+    "redis://127.0.0.1:6379".to_string()
+}
+
+/// Background job moving old finished races to the archive collections
+#[derive(Deserialize, Clone)]
+pub struct ArchiveSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Races finished longer ago than this are archived
+    #[serde(default = "default_archive_after_days")]
+    pub finished_after_days: u64,
+    #[serde(default = "default_archive_interval_minutes")]
+    pub interval_minutes: u64,
+    /// Most races archived per run
+    #[serde(default = "default_archive_batch_size")]
+    pub batch_size: u32,
+}
+
+impl Default for ArchiveSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            finished_after_days: default_archive_after_days(),
+            interval_minutes: default_archive_interval_minutes(),
+            batch_size: default_archive_batch_size(),
+        }
+    }
+}
+
+fn default_archive_after_days() -> u64 {
+    30
+}
+
+fn default_archive_interval_minutes() -> u64 {
+    60
+}
+
+fn default_archive_batch_size() -> u32 {
+    100
+}
+
+/// Background job cancelling waiting races nobody touched for a while
+#[derive(Deserialize, Clone)]
+pub struct JanitorSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Waiting races without any write for this long are cancelled
+    #[serde(default = "default_janitor_idle_hours")]
+    pub idle_after_hours: u64,
+    #[serde(default = "default_janitor_interval_minutes")]
+    pub interval_minutes: u64,
+    /// Most races cancelled per run
+    #[serde(default = "default_janitor_batch_size")]
+    pub batch_size: u32,
+    /// Leave a notification for the player who opened each cancelled race
+    #[serde(default = "default_notify_creator")]
+    pub notify_creator: bool,
+}
+
+impl Default for JanitorSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            idle_after_hours: default_janitor_idle_hours(),
+            interval_minutes: default_janitor_interval_minutes(),
+            batch_size: default_janitor_batch_size(),
+            notify_creator: default_notify_creator(),
+        }
+    }
+}
+
+fn default_janitor_idle_hours() -> u64 {
+    24
+}
+
+fn default_janitor_interval_minutes() -> u64 {
+    15
+}
+
+fn default_janitor_batch_size() -> u32 {
+    100
+}
+
+fn default_notify_creator() -> bool {
+    true
+}
+
+/// Background job warning participants when a lap's deadline is near
+///
+/// Only races created with a turn time limit have deadlines.
+#[derive(Deserialize, Clone)]
+pub struct TurnDeadlineSettings {
+    #[serde(default = "default_turn_deadlines_enabled")]
+    pub enabled: bool,
+    /// Warn this long before the deadline
+    #[serde(default = "default_turn_deadline_warning_seconds")]
+    pub warning_seconds: u64,
+    #[serde(default = "default_turn_deadline_interval_seconds")]
+    pub interval_seconds: u64,
+    /// Most races warned per run
+    #[serde(default = "default_turn_deadline_batch_size")]
+    pub batch_size: u32,
+}
+
+impl Default for TurnDeadlineSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_turn_deadlines_enabled(),
+            warning_seconds: default_turn_deadline_warning_seconds(),
+            interval_seconds: default_turn_deadline_interval_seconds(),
+            batch_size: default_turn_deadline_batch_size(),
+        }
+    }
+}
+
+fn default_turn_deadlines_enabled() -> bool {
+    true
+}
+
+fn default_turn_deadline_warning_seconds() -> u64 {
+    15
+}
+
+fn default_turn_deadline_interval_seconds() -> u64 {
+    2
+}
+
+fn default_turn_deadline_batch_size() -> u32 {
+    100
+}
+
+/// Background job ticking the start countdown of races and opening their first lap
+///
+/// Only races created with a start countdown go through it; the job runs
+/// every second so ticks stay in step with the clock.
+#[derive(Deserialize, Clone)]
+pub struct CountdownSettings {
+    #[serde(default = "default_countdowns_enabled")]
+    pub enabled: bool,
+    /// Most races counted down per run
+    #[serde(default = "default_countdown_batch_size")]
+    pub batch_size: u32,
+}
+
+impl Default for CountdownSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_countdowns_enabled(),
+            batch_size: default_countdown_batch_size(),
+        }
+    }
+}
+
+fn default_countdowns_enabled() -> bool {
+    true
+}
+
+fn default_countdown_batch_size() -> u32 {
+    100
+}
+
+/// Background job sending race webhooks
+#[derive(Deserialize, Clone)]
+pub struct WebhookSettings {
+    #[serde(default = "default_webhooks_enabled")]
+    pub enabled: bool,
+    /// How often queued deliveries are looked for
+    #[serde(default = "default_webhook_interval_seconds")]
+    pub interval_seconds: u64,
+    #[serde(default = "default_webhook_timeout_seconds")]
+    pub timeout_seconds: u64,
+    /// Attempts before a delivery is marked failed
+    #[serde(default = "default_webhook_max_attempts")]
+    pub max_attempts: u32,
+    /// Wait after the first failure, doubled after each further one
+    #[serde(default = "default_webhook_retry_base_seconds")]
+    pub retry_base_seconds: u64,
+    /// Most deliveries attempted per run
+    #[serde(default = "default_webhook_batch_size")]
+    pub batch_size: u32,
+}
+
+impl Default for WebhookSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_webhooks_enabled(),
+            interval_seconds: default_webhook_interval_seconds(),
+            timeout_seconds: default_webhook_timeout_seconds(),
+            max_attempts: default_webhook_max_attempts(),
+            retry_base_seconds: default_webhook_retry_base_seconds(),
+            batch_size: default_webhook_batch_size(),
+        }
+    }
+}
+
+fn default_webhooks_enabled() -> bool {
+    true
+}
+
+fn default_webhook_interval_seconds() -> u64 {
+    5
+}
+
+fn default_webhook_timeout_seconds() -> u64 {
+    10
+}
+
+fn default_webhook_max_attempts() -> u32 {
+    6
+}
+
+fn default_webhook_retry_base_seconds() -> u64 {
+    30
+}
+
+fn default_webhook_batch_size() -> u32 {
+    50
+}
+
+/// Background job writing finished race results to Solana
+///
+/// The job posts `{"race_uuid", "hash"}` to the signing service, which sends a
+/// memo transaction holding the hash and answers `{"signature"}`.
+#[derive(Deserialize, Clone)]
+pub struct AnchoringSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub signing_service_url: String,
+    /// Sent as `authorization: Bearer <token>` when set
+    #[serde(default)]
+    pub signing_service_token: Option<Secret<String>>,
+    #[serde(default = "default_anchoring_interval_seconds")]
+    pub interval_seconds: u64,
+    #[serde(default = "default_anchoring_timeout_seconds")]
+    pub timeout_seconds: u64,
+    /// Most races anchored per run
+    #[serde(default = "default_anchoring_batch_size")]
+    pub batch_size: u32,
+}
+
+impl Default for AnchoringSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            signing_service_url: String::new(),
+            signing_service_token: None,
+            interval_seconds: default_anchoring_interval_seconds(),
+            timeout_seconds: default_anchoring_timeout_seconds(),
+            batch_size: default_anchoring_batch_size(),
+        }
+    }
+}
+
+fn default_anchoring_interval_seconds() -> u64 {
+    60
+}
+
+fn default_anchoring_timeout_seconds() -> u64 {
+    30
+}
+
+fn default_anchoring_batch_size() -> u32 {
+    20
+}
+
+/// Background job paying race prizes through the signing service
+///
+/// Each transfer posts `{"payout_uuid", "recipient", "mint", "amount"}` and
+/// expects `{"signature"}`; the service must send one transfer per
+/// `payout_uuid`, as a payout whose answer was lost is posted again.
+#[derive(Deserialize, Clone)]
+pub struct PayoutSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub signing_service_url: String,
+    /// Sent as `authorization: Bearer <token>` when set
+    #[serde(default)]
+    pub signing_service_token: Option<Secret<String>>,
+    #[serde(default = "default_payout_interval_seconds")]
+    pub interval_seconds: u64,
+    #[serde(default = "default_payout_timeout_seconds")]
+    pub timeout_seconds: u64,
+    /// Attempts before a payout is marked failed
+    #[serde(default = "default_payout_max_attempts")]
+    pub max_attempts: u32,
+    /// Wait after the first failure, doubled after each further one
+    #[serde(default = "default_payout_retry_base_seconds")]
+    pub retry_base_seconds: u64,
+    /// Most races queued and transfers attempted per run
+    #[serde(default = "default_payout_batch_size")]
+    pub batch_size: u32,
+}
+
+impl Default for PayoutSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            signing_service_url: String::new(),
+            signing_service_token: None,
+            interval_seconds: default_payout_interval_seconds(),
+            timeout_seconds: default_payout_timeout_seconds(),
+            max_attempts: default_payout_max_attempts(),
+            retry_base_seconds: default_payout_retry_base_seconds(),
+            batch_size: default_payout_batch_size(),
+        }
+    }
+}
+
+fn default_payout_interval_seconds() -> u64 {
+    30
+}
+
+fn default_payout_timeout_seconds() -> u64 {
+    60
+}
+
+fn default_payout_max_attempts() -> u32 {
+    8
+}
+
+fn default_payout_retry_base_seconds() -> u64 {
+    60
+}
+
+fn default_payout_batch_size() -> u32 {
+    20
+}
+
+/// Background job flagging suspicious play for administrators to review
+#[derive(Deserialize, Clone)]
+pub struct AntiCheatSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_anticheat_interval_minutes")]
+    pub interval_minutes: u64,
+    /// Races finished and devices registered longer ago are not analysed
+    #[serde(default = "default_anticheat_lookback_hours")]
+    pub lookback_hours: u64,
+    /// Most races analysed per run
+    #[serde(default = "default_anticheat_batch_size")]
+    pub batch_size: u32,
+    /// Submissions sooner than this after their lap opened are too fast for a person
+    #[serde(default = "default_anticheat_min_reaction_millis")]
+    pub min_reaction_millis: i64,
+    /// Too-fast submissions in one race before the player is flagged
+    #[serde(default = "default_anticheat_fast_submissions")]
+    pub fast_submissions: u32,
+    /// Largest lead over the runner-up that counts as winning a sector by a hair
+    #[serde(default = "default_anticheat_tight_margin")]
+    pub tight_margin: u32,
+    /// Contested sectors a player must have raced in before their wins are judged
+    #[serde(default = "default_anticheat_min_contested_laps")]
+    pub min_contested_laps: u32,
+    /// Share of contested sectors won by a hair that gets the player flagged
+    #[serde(default = "default_anticheat_tight_win_ratio")]
+    pub tight_win_ratio: f64,
+}
+
+impl Default for AntiCheatSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_minutes: default_anticheat_interval_minutes(),
+            lookback_hours: default_anticheat_lookback_hours(),
+            batch_size: default_anticheat_batch_size(),
+            min_reaction_millis: default_anticheat_min_reaction_millis(),
+            fast_submissions: default_anticheat_fast_submissions(),
+            tight_margin: default_anticheat_tight_margin(),
+            min_contested_laps: default_anticheat_min_contested_laps(),
+            tight_win_ratio: default_anticheat_tight_win_ratio(),
+        }
+    }
+}
+
+fn default_anticheat_interval_minutes() -> u64 {
+    15
+}
+
+fn default_anticheat_lookback_hours() -> u64 {
+    24
+}
+
+fn default_anticheat_batch_size() -> u32 {
+    50
+}
+
+fn default_anticheat_min_reaction_millis() -> i64 {
+    300
+}
+
+fn default_anticheat_fast_submissions() -> u32 {
+    3
+}
+
+fn default_anticheat_tight_margin() -> u32 {
+    1
+}
+
+fn default_anticheat_min_contested_laps() -> u32 {
+    5
+}
+
+fn default_anticheat_tight_win_ratio() -> f64 {
+    0.8
+}
+
+/// Background job shipping anonymized gameplay records of finished races to a
+/// data warehouse, for balance research
+///
+/// Player and race UUIDs are replaced by a keyed hash, so records of the same
+/// player link up across races without naming them.
+#[derive(Deserialize, Clone)]
+pub struct AnalyticsExportSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub sink: AnalyticsSinkKind,
+    /// Key of the hash replacing UUIDs, required when enabled; changing it
+    /// unlinks later records from earlier ones
+    #[serde(default)]
+    pub pseudonym_key: Option<Secret<String>>,
+    #[serde(default = "default_analytics_export_interval_minutes")]
+    pub interval_minutes: u64,
+    /// Most races shipped per run, as one batch
+    #[serde(default = "default_analytics_export_batch_size")]
+    pub batch_size: u32,
+    #[serde(default = "default_analytics_export_timeout_seconds")]
+    pub timeout_seconds: u64,
+    /// Only used by the `s3` sink
+    #[serde(default)]
+    pub s3: Option<S3ExportSettings>,
+    /// Only used by the `bigquery` sink
+    #[serde(default)]
+    pub bigquery: Option<BigQueryExportSettings>,
+}
+
+impl Default for AnalyticsExportSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sink: AnalyticsSinkKind::default(),
+            pseudonym_key: None,
+            interval_minutes: default_analytics_export_interval_minutes(),
+            batch_size: default_analytics_export_batch_size(),
+            timeout_seconds: default_analytics_export_timeout_seconds(),
+            s3: None,
+            bigquery: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AnalyticsSinkKind {
+    /// Records are written to the application log, for local development
+    #[default]
+    Log,
+    /// One newline-delimited JSON object per batch, signed with the AWS_*
+    /// environment variables
+    S3,
+    /// Rows streamed into a `BigQuery` table
+    Bigquery,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct S3ExportSettings {
+    pub bucket: String,
+    pub region: String,
+    /// Key prefix of the batches; letters, digits, `-`, `_`, `.` and `/` only
+    #[serde(default = "default_analytics_export_s3_prefix")]
+    pub prefix: String,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct BigQueryExportSettings {
+    pub project_id: String,
+    pub dataset_id: String,
+    pub table_id: String,
+    /// OAuth access token; without one, a token of the instance's service
+    /// account is asked from the GCP metadata server
+    #[serde(default)]
+    pub access_token: Option<Secret<String>>,
+}
+
+fn default_analytics_export_interval_minutes() -> u64 {
+    60
+}
+
+fn default_analytics_export_batch_size() -> u32 {
+    100
+}
+
+fn default_analytics_export_timeout_seconds() -> u64 {
+    30
+}
+
+fn default_analytics_export_s3_prefix() -> String {
+    "gameplay".to_string()
+}
+
+/// Background job erasing the accounts whose players asked for it
+#[derive(Deserialize, Clone)]
+pub struct ErasureSettings {
+    #[serde(default = "default_erasure_enabled")]
+    pub enabled: bool,
+    /// Days an account is kept after its erasure was requested, during which
+    /// the player can still cancel it
+    #[serde(default = "default_erasure_grace_days")]
+    pub grace_days: u64,
+    #[serde(default = "default_erasure_interval_minutes")]
+    pub interval_minutes: u64,
+    /// Most accounts erased per run
+    #[serde(default = "default_erasure_batch_size")]
+    pub batch_size: u32,
+}
+
+impl Default for ErasureSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_erasure_enabled(),
+            grace_days: default_erasure_grace_days(),
+            interval_minutes: default_erasure_interval_minutes(),
+            batch_size: default_erasure_batch_size(),
+        }
+    }
+}
+
+fn default_erasure_enabled() -> bool {
+    true
+}
+
+fn default_erasure_grace_days() -> u64 {
+    30
+}
+
+fn default_erasure_interval_minutes() -> u64 {
+    60
+}
+
+fn default_erasure_batch_size() -> u32 {
+    50
+}
+
+/// Background job relaying race outboxes to the event bus and webhook queue
+#[derive(Deserialize, Clone)]
+pub struct OutboxSettings {
+    /// How often races with pending messages are looked for
+    #[serde(default = "default_outbox_interval_milliseconds")]
+    pub interval_milliseconds: u64,
+    /// Most races relayed per run
+    #[serde(default = "default_outbox_batch_size")]
+    pub batch_size: u32,
+}
+
+impl Default for OutboxSettings {
+    fn default() -> Self {
+        Self {
+            interval_milliseconds: default_outbox_interval_milliseconds(),
+            batch_size: default_outbox_batch_size(),
+        }
+    }
+}
+
+fn default_outbox_interval_milliseconds() -> u64 {
+    500
+}
+
+fn default_outbox_batch_size() -> u32 {
+    100
+}
+
+/// Background job pushing player notifications to their devices
+#[derive(Deserialize, Clone)]
+pub struct PushSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_push_interval_seconds")]
+    pub interval_seconds: u64,
+    /// Most notifications pushed per run
+    #[serde(default = "default_push_batch_size")]
+    pub batch_size: u32,
+    /// Remind players of a lap still waiting on them after this long; 0 disables reminders
+    #[serde(default = "default_turn_reminder_after_minutes")]
+    pub turn_reminder_after_minutes: u64,
+    /// Firebase service account key file (JSON); Android devices are skipped without it
+    #[serde(default)]
+    pub fcm_service_account_file: Option<String>,
+    /// iOS devices are skipped without it
+    #[serde(default)]
+    pub apns: Option<ApnsSettings>,
+}
+
+impl Default for PushSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_seconds: default_push_interval_seconds(),
+            batch_size: default_push_batch_size(),
+            turn_reminder_after_minutes: default_turn_reminder_after_minutes(),
+            fcm_service_account_file: None,
+            apns: None,
+        }
+    }
+}
+
+/// Token-based authentication with the Apple Push Notification service
+#[derive(Deserialize, Clone)]
+pub struct ApnsSettings {
+    /// `.p8` signing key downloaded from the Apple developer account
+    pub key_file: String,
+    pub key_id: String,
+    pub team_id: String,
+    /// Bundle id of the app
+    pub topic: String,
+    /// Use the development environment, for debug builds of the app
+    #[serde(default)]
+    pub sandbox: bool,
+}
+
+fn default_push_interval_seconds() -> u64 {
+    5
+}
+
+fn default_push_batch_size() -> u32 {
+    100
+}
+
+fn default_turn_reminder_after_minutes() -> u64 {
+    30
+}
+
+/// Email digests of player notifications
+#[derive(Deserialize, Clone)]
+pub struct EmailSettings {
+    #[serde(default)]
+    pub backend: EmailBackend,
+    /// `From` address of every email
+    #[serde(default = "default_email_sender")]
+    pub sender: String,
+    /// How often each player's pending notifications are mailed as one digest
+    #[serde(default = "default_email_interval_minutes")]
+    pub interval_minutes: u64,
+    /// Most players mailed per run
+    #[serde(default = "default_email_batch_size")]
+    pub batch_size: u32,
+    /// Only used by the `smtp` backend
+    #[serde(default)]
+    pub smtp: Option<SmtpSettings>,
+    /// Only used by the `sendgrid` backend
+    #[serde(default)]
+    pub sendgrid_api_key: Option<Secret<String>>,
+}
+
+impl Default for EmailSettings {
+    fn default() -> Self {
+        Self {
+            backend: EmailBackend::default(),
+            sender: default_email_sender(),
+            interval_minutes: default_email_interval_minutes(),
+            batch_size: default_email_batch_size(),
+            smtp: None,
+            sendgrid_api_key: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+pub struct SmtpSettings {
+    pub host: String,
+    /// Submission port; the connection is upgraded with STARTTLS
+    #[serde(default = "default_smtp_port")]
+    pub port: u16,
+    pub username: String,
+    pub password: Secret<String>,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EmailBackend {
+    /// No emails are sent
+    #[default]
+    None,
+    /// Emails are written to the application log, for local development
+    Log,
+    /// Sent through an SMTP relay (requires the `smtp` feature)
+    Smtp,
+    /// Sent through the `SendGrid` v3 API
+    Sendgrid,
+}
+
+fn default_email_sender() -> String {
+    "Boardurance <noreply@boardurance.local>".to_string()
+}
+
+fn default_email_interval_minutes() -> u64 {
+    10
+}
+
+fn default_email_batch_size() -> u32 {
+    100
+}
+
+fn default_smtp_port() -> u16 {
+    587
+}
+
+/// Lobby and global chat rooms served over WebSocket
+#[derive(Deserialize, Clone)]
+pub struct ChatSettings {
+    /// Open a room every player can join, besides race lobbies
+    #[serde(default)]
+    pub global_room: bool,
+    #[serde(default = "default_chat_max_message_length")]
+    pub max_message_length: usize,
+}
+
+impl Default for ChatSettings {
+    fn default() -> Self {
+        Self {
+            global_room: false,
+            max_message_length: default_chat_max_message_length(),
+        }
+    }
+}
+
+fn default_chat_max_message_length() -> usize {
+    500
+}
+
+/// Integration API used by Discord bots, with keys scoped to one guild
+#[derive(Deserialize, Clone)]
+pub struct DiscordSettings {
+    /// Link posted for players to join a race; `{race_uuid}` is replaced
+    #[serde(default = "default_discord_join_url")]
+    pub join_url: String,
+}
+
+impl Default for DiscordSettings {
+    fn default() -> Self {
+        Self {
+            join_url: default_discord_join_url(),
+        }
+    }
+}
+
+fn default_discord_join_url() -> String {
+    "http://localhost:5173/races/{race_uuid}".to_string()
+}
+
+/// Card packs players buy with credits
+#[derive(Deserialize, Clone)]
+pub struct PackSettings {
+    #[serde(default = "default_pack_catalog")]
+    pub catalog: Vec<PackDefinition>,
+}
+
+impl Default for PackSettings {
+    fn default() -> Self {
+        Self {
+            catalog: default_pack_catalog(),
+        }
+    }
+}
+
+/// One pack on sale and its drop table
+#[derive(Deserialize, Serialize, Clone, Debug, ToSchema)]
+pub struct PackDefinition {
+    /// Stable id used in URLs and pity counters; must not contain dots
+    pub id: String,
+    pub name: String,
+    /// Credits the pack costs
+    pub price: u32,
+    /// Engines and bodies in one pack
+    pub components: u32,
+    /// Relative weight of each rarity; rarities left out never drop
+    pub drop_rates: Vec<DropRate>,
+    /// Rarity guaranteed after a run of worse drops
+    #[serde(default)]
+    pub pity: Option<PitySettings>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, ToSchema)]
+pub struct DropRate {
+    pub rarity: ComponentRarity,
+    pub weight: u32,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, ToSchema)]
+pub struct PitySettings {
+    /// Drops in a row below `min_rarity` that make the next one `min_rarity`
+    pub after: u32,
+    pub min_rarity: ComponentRarity,
+}
+
+fn default_pack_catalog() -> Vec<PackDefinition> {
+    let rate = |rarity, weight| DropRate { rarity, weight };
+    vec![PackDefinition {
+        id: "standard".to_string(),
+        name: "Standard Pack".to_string(),
+        price: 200,
+        components: 3,
+        drop_rates: vec![
+            rate(ComponentRarity::Common, 60),
+            rate(ComponentRarity::Uncommon, 25),
+            rate(ComponentRarity::Rare, 10),
+            rate(ComponentRarity::Epic, 4),
+            rate(ComponentRarity::Legendary, 1),
+        ],
+        pity: Some(PitySettings {
+            after: 10,
+            min_rarity: ComponentRarity::Rare,
+        }),
+    }]
+}
+
+/// Where traces go besides the JSON log
+#[derive(Deserialize, Clone, Default)]
+pub struct TelemetrySettings {
+    /// OTLP gRPC collector, e.g. `http://localhost:4317` for Jaeger or Tempo;
+    /// traces are only exported when set (requires the `otlp` feature)
+    #[serde(default)]
+    pub otlp_endpoint: Option<String>,
+    /// Sentry project DSN; panics and logged errors are reported when set
+    /// (requires the `sentry` feature)
+    #[serde(default)]
+    pub sentry_dsn: Option<String>,
+}
+
+/// Limits and encoding of HTTP requests and responses
+#[derive(Deserialize, Clone)]
+pub struct HttpSettings {
+    /// Largest request body accepted; bigger ones get `413`
+    #[serde(default = "default_max_body_bytes")]
+    pub max_body_bytes: usize,
+    /// Time a handler has to answer before the request fails with `408`
+    #[serde(default = "default_request_timeout_seconds")]
+    pub request_timeout_seconds: u64,
+    /// Routes given a different timeout, by route template
+    #[serde(default)]
+    pub route_timeouts: Vec<RouteTimeoutSettings>,
+    /// Gzip JSON and text responses for clients accepting it
+    #[serde(default = "default_compression")]
+    pub compression: bool,
+}
+
+impl Default for HttpSettings {
+    fn default() -> Self {
+        Self {
+            max_body_bytes: default_max_body_bytes(),
+            request_timeout_seconds: default_request_timeout_seconds(),
+            route_timeouts: Vec::new(),
+            compression: default_compression(),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+pub struct RouteTimeoutSettings {
+    /// e.g. `/api/v1/admin/stats` or `/api/v1/races/:race_uuid/status`
+    pub route: String,
+    pub timeout_seconds: u64,
+}
+
+fn default_max_body_bytes() -> usize {
+    2 * 1024 * 1024
+}
+
+fn default_request_timeout_seconds() -> u64 {
+    30
+}
+
+fn default_compression() -> bool {
+    true
+}
+
+/// gRPC race service for game servers and matchmaking services
+#[derive(Deserialize, Clone, Default)]
+pub struct GrpcSettings {
+    /// Port the service listens on, next to the HTTP port; off when unset
+    /// (requires the `grpc` feature)
+    #[serde(default)]
+    pub port: Option<u16>,
+    /// Key callers send as `authorization: Bearer <key>`; unchecked when unset
+    #[serde(default)]
+    pub api_key: Option<Secret<String>>,
+}
+
+/// Browser origins allowed to call the API
+#[derive(Deserialize, Clone)]
+pub struct CorsSettings {
+    /// Full origins such as `https://play.boardurance.com`, or `*` for any
+    /// origin, which requires `allow_credentials: false`
+    #[serde(default = "default_cors_allowed_origins")]
+    pub allowed_origins: Vec<String>,
+    /// Request headers clients may send; `X-Request-Id` is always allowed
+    #[serde(default = "default_cors_allowed_headers")]
+    pub allowed_headers: Vec<String>,
+    /// Let browsers send cookies and `Authorization` headers cross-origin
+    #[serde(default = "default_cors_allow_credentials")]
+    pub allow_credentials: bool,
+    /// How long browsers may cache a preflight response
+    #[serde(default)]
+    pub max_age_seconds: Option<u64>,
+}
+
+impl Default for CorsSettings {
+    fn default() -> Self {
+        Self {
+            allowed_origins: default_cors_allowed_origins(),
+            allowed_headers: default_cors_allowed_headers(),
+            allow_credentials: default_cors_allow_credentials(),
+            max_age_seconds: None,
+        }
+    }
+}
+
+/// The frontend dev servers
+fn default_cors_allowed_origins() -> Vec<String> {
+    [
+        "http://localhost:5173",
+        "http://localhost:5174",
+        "http://localhost:5175",
+    ]
+    .map(String::from)
+    .to_vec()
+}
+
+fn default_cors_allowed_headers() -> Vec<String> {
+    ["content-type", "authorization", "accept"]
+        .map(String::from)
+        .to_vec()
+}
+
+fn default_cors_allow_credentials() -> bool {
+    true
+}
+
+/// Keys signing and verifying access and refresh tokens
+///
+/// The first key signs new tokens and its `kid` goes in their header; the
+/// others only verify tokens signed before a rotation. Without any key the
+/// `JWT_SECRET` environment variable is used, with no `kid`.
+#[derive(Deserialize, Clone, Default)]
+pub struct JwtSettings {
+    #[serde(default)]
+    pub keys: Vec<JwtKeySettings>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct JwtKeySettings {
+    pub kid: String,
+    pub secret: Secret<String>,
+}
+
+/// Limits on the sessions opened by logging in
+#[derive(Deserialize, Clone)]
+pub struct SessionSettings {
+    /// Sessions a player can have at once; logging in once more ends the oldest
+    #[serde(default = "default_max_sessions_per_player")]
+    pub max_per_player: usize,
+    /// Tie each session to the `x-device-fingerprint` header sent when logging
+    /// in, which sensitive operations such as trades must then repeat
+    #[serde(default)]
+    pub bind_to_device: bool,
+}
+
+impl Default for SessionSettings {
+    fn default() -> Self {
+        Self {
+            max_per_player: default_max_sessions_per_player(),
+            bind_to_device: false,
+        }
+    }
+}
+
+fn default_max_sessions_per_player() -> usize {
+    5
+}
+
+/// Lockouts after repeated failed logins
+///
+/// Failures are counted per account and per client IP. Once either count
+/// reaches its limit, logins are refused for `base_lockout_seconds`, doubling
+/// with each further failure up to `max_lockout_seconds`. Counts are forgotten
+/// after `window_seconds` without a failure; a successful login clears the
+/// account's.
+///
+/// The client IP is the connection's peer address. `X-Forwarded-For` is only
+/// read when the peer is one of `trusted_proxies`, such as the load balancer.
+#[derive(Deserialize, Clone)]
+pub struct LoginThrottleSettings {
+    #[serde(default = "default_login_throttle_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_max_failures_per_account")]
+    pub max_failures_per_account: u32,
+    #[serde(default = "default_max_failures_per_ip")]
+    pub max_failures_per_ip: u32,
+    #[serde(default = "default_base_lockout_seconds")]
+    pub base_lockout_seconds: u64,
+    #[serde(default = "default_max_lockout_seconds")]
+    pub max_lockout_seconds: u64,
+    #[serde(default = "default_login_failure_window_seconds")]
+    pub window_seconds: u64,
+    #[serde(default)]
+    pub trusted_proxies: Vec<IpAddr>,
+}
+
+impl Default for LoginThrottleSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_failures_per_account: default_max_failures_per_account(),
+            max_failures_per_ip: default_max_failures_per_ip(),
+            base_lockout_seconds: default_base_lockout_seconds(),
+            max_lockout_seconds: default_max_lockout_seconds(),
+            window_seconds: default_login_failure_window_seconds(),
+            trusted_proxies: Vec::new(),
+        }
+    }
+}
+
+fn default_login_throttle_enabled() -> bool {
+    true
+}
+
+fn default_max_failures_per_account() -> u32 {
+    5
+}
+
+fn default_max_failures_per_ip() -> u32 {
+    20
+}
+
+fn default_base_lockout_seconds() -> u64 {
+    30
+}
+
+fn default_max_lockout_seconds() -> u64 {
+    3600
+}
+
+fn default_login_failure_window_seconds() -> u64 {
+    900
+}
+
+/// `OAuth2` clients for signing in with a provider account; a provider without
+/// settings is not offered
+#[derive(Deserialize, Clone, Default)]
+pub struct OAuthSettings {
+    #[serde(default)]
+    pub google: Option<OAuthClientSettings>,
+    #[serde(default)]
+    pub discord: Option<OAuthClientSettings>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct OAuthClientSettings {
+    pub client_id: String,
+    pub client_secret: Secret<String>,
+    /// The callback URL registered with the provider, e.g.
+    /// `https://api.example.com/api/v1/auth/oauth/google/callback`
+    pub redirect_uri: String,
+}
+
+/// Secret store the `MongoDB` URI and JWT keys are read from at startup
+#[derive(Deserialize, Clone, Default)]
+pub struct SecretSettings {
+    #[serde(default)]
+    pub provider: SecretProvider,
+    /// Only used by the `vault` provider
+    #[serde(default)]
+    pub vault: Option<VaultSettings>,
+    /// Only used by the `aws` provider
+    #[serde(default)]
+    pub aws: Option<AwsSecretsSettings>,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SecretProvider {
+    /// Secrets come from the configuration files and environment
+    #[default]
+    None,
+    /// `HashiCorp` Vault KV version 2 secrets engine
+    Vault,
+    /// AWS Secrets Manager
+    Aws,
+}
+
+/// Vault secret read with a token
+#[derive(Deserialize, Clone)]
+pub struct VaultSettings {
+    /// e.g. `https://vault.example.com:8200`
+    pub address: String,
+    /// Falls back to the `VAULT_TOKEN` environment variable
+    #[serde(default)]
+    pub token: Option<Secret<String>>,
+    /// Mount point of the KV engine
+    #[serde(default = "default_vault_mount")]
+    pub mount: String,
+    /// Path of the secret within the mount
+    pub path: String,
+}
+
+fn default_vault_mount() -> String {
+    "secret".to_string()
+}
+
+/// Secrets Manager secret read with the credentials in the `AWS_ACCESS_KEY_ID`,
+/// `AWS_SECRET_ACCESS_KEY` and `AWS_SESSION_TOKEN` environment variables
+#[derive(Deserialize, Clone)]
+pub struct AwsSecretsSettings {
+    pub region: String,
+    /// Name or ARN of the secret
+    pub secret_id: String,
+}
+
+/// Message broker race domain events are published to
+#[derive(Deserialize, Clone)]
+pub struct EventSettings {
+    #[serde(default)]
+    pub backend: EventBackend,
+    /// Only used by the `nats` backend
+    #[serde(default = "default_nats_url")]
+    pub nats_url: String,
+    /// Events go to `{subject_prefix}.races.{race_uuid}.{event}`
+    #[serde(default = "default_event_subject_prefix")]
+    pub subject_prefix: String,
+}
+
+impl Default for EventSettings {
+    fn default() -> Self {
+        Self {
+            backend: EventBackend::default(),
+            nats_url: default_nats_url(),
+            subject_prefix: default_event_subject_prefix(),
+        }
+    }
+}
+
+fn default_nats_url() -> String {
+    "nats://127.0.0.1:4222".to_string()
+}
+
+fn default_event_subject_prefix() -> String {
+    "boardurance".to_string()
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheBackend {
+    /// Always read from the database
+    #[default]
+    None,
+    /// Per-process cache; each server instance keeps its own copy
+    Memory,
+    /// Shared Redis cache (requires the `redis` feature)
+    Redis,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EventBackend {
+    /// Events are dropped
+    #[default]
+    None,
+    /// Events are written to the application log, for local development
+    Log,
+    /// NATS server (requires the `nats` feature)
+    Nats,
 }
 
 impl DatabaseSettings {
@@ -73,6 +1373,23 @@ impl DatabaseSettings {
         }
     }
 
+    #[must_use]
+    pub fn postgres_connection_string(&self) -> String {
+        let ssl_mode = if self.require_ssl {
+            "require"
+        } else {
+            "prefer"
+        };
+        format!(
+            "postgres://{}:{}@{}:{}/{}?sslmode={ssl_mode}",
+            self.username,
+            self.password.expose_secret(),
+            self.host,
+            self.port,
+            self.database_name
+        )
+    }
+
     // For local development without authentication
     #[must_use]
     pub fn connection_string_without_auth(&self) -> String {
@@ -109,7 +1426,10 @@ pub fn get_configuration() -> Result<Settings, ConfigError> {
         )
         .build()?;
 
-    settings.try_deserialize::<Settings>()
+    let mut settings = settings.try_deserialize::<Settings>()?;
+    crate::secrets::load_secrets(&mut settings)
+        .map_err(|e| ConfigError::Message(format!("Failed to load secrets: {e:#}")))?;
+    Ok(settings)
 }
 
 /// The possible runtime environment for our application.
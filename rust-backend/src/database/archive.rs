@@ -0,0 +1,217 @@
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use mongodb::{
+    bson::{doc, spec::BinarySubtype, Binary, DateTime as BsonDateTime, Document},
+    options::{FindOptions, ReplaceOptions},
+    Database,
+};
+use std::io::Write;
+use uuid::Uuid;
+
+use crate::domain::{Race, RaceEvent, RaceStatus};
+
+/// Collection holding finished races moved out of `races`
+pub const RACES_ARCHIVE: &str = "races_archive";
+/// Collection holding the compressed event log of each archived race
+pub const RACE_EVENT_ARCHIVES: &str = "race_event_archives";
+
+/// `$unionWith` stage adding the archived races matching `filter`
+///
+/// Goes after a `$match` on `races` with the same filter, so a query sees
+/// live and archived races alike.
+#[must_use]
+pub fn union_with_archived_races(filter: Document) -> Document {
+    doc! { "$unionWith": {
+        "coll": RACES_ARCHIVE,
+        "pipeline": [{ "$match": filter }],
+    } }
+}
+
+/// Move races that finished before `cutoff` to the archive, at most `batch_size`
+///
+/// Each race is copied to [`RACES_ARCHIVE`] and its events compressed into
+/// one [`RACE_EVENT_ARCHIVES`] document before the originals are deleted, so
+/// an interrupted run is simply picked up by the next one.
+/// With `wait_for_anchor`, races are kept until their results are anchored.
+/// Returns the number of races archived.
+pub async fn archive_finished_races(
+    database: &Database,
+    cutoff: BsonDateTime,
+    batch_size: u32,
+    wait_for_anchor: bool,
+) -> Result<u64, mongodb::error::Error> {
+    let races = database.collection::<Race>("races");
+    let mut filter = doc! {
+        "status": mongodb::bson::to_bson(&RaceStatus::Finished)?,
+        // Finished races are never written again
+        "updated_at": { "$lt": cutoff },
+        // Wait for the outbox relay, as the archive copy drops the outbox
+        "outbox.uuid": { "$exists": false },
+        // Prize races stay until their payouts are queued
+        "$or": [
+            { "prize_pool": { "$exists": false } },
+            { "payouts_queued": true },
+        ],
+    };
+    if wait_for_anchor {
+        filter.insert("anchor", doc! { "$ne": null });
+    }
+    let options = FindOptions::builder()
+        .sort(doc! { "updated_at": 1 })
+        .limit(i64::from(batch_size))
+        .build();
+    let mut cursor = races.find(filter, options).await?;
+
+    let mut archived = 0;
+    while cursor.advance().await? {
+        let race: Race = cursor.deserialize_current()?;
+        archive_race(database, &race).await?;
+        archived += 1;
+    }
+    Ok(archived)
+}
+
+async fn archive_race(database: &Database, race: &Race) -> Result<(), mongodb::error::Error> {
+    let race_filter = doc! { "uuid": race.uuid.to_string() };
+    let event_filter = doc! { "race_uuid": race.uuid.to_string() };
+    let upsert = ReplaceOptions::builder().upsert(true).build();
+
+    database
+        .collection::<Race>(RACES_ARCHIVE)
+        .replace_one(race_filter.clone(), race, upsert.clone())
+        .await?;
+
+    let events = database.collection::<RaceEvent>("race_events");
+    let options = FindOptions::builder()
+        .sort(doc! { "occurred_at": 1, "_id": 1 })
+        .build();
+    let mut cursor = events.find(event_filter.clone(), options).await?;
+    let mut logged = Vec::new();
+    while cursor.advance().await? {
+        logged.push(cursor.deserialize_current()?);
+    }
+
+    if !logged.is_empty() {
+        let blob = doc! {
+            "race_uuid": race.uuid.to_string(),
+            "event_count": i64::try_from(logged.len()).unwrap_or(i64::MAX),
+            "archived_at": BsonDateTime::now(),
+            "events": Binary {
+                subtype: BinarySubtype::Generic,
+                bytes: compress_events(&logged)?,
+            },
+        };
+        database
+            .collection::<Document>(RACE_EVENT_ARCHIVES)
+            .replace_one(event_filter.clone(), blob, upsert)
+            .await?;
+        events.delete_many(event_filter, None).await?;
+    }
+
+    database
+        .collection::<Race>("races")
+        .delete_one(race_filter, None)
+        .await?;
+    tracing::debug!("Archived race {}", race.uuid);
+    Ok(())
+}
+
+/// Archived race by UUID
+pub async fn find_archived_race(
+    database: &Database,
+    race_uuid: Uuid,
+) -> Result<Option<Race>, mongodb::error::Error> {
+    database
+        .collection::<Race>(RACES_ARCHIVE)
+        .find_one(doc! { "uuid": race_uuid.to_string() }, None)
+        .await
+}
+
+/// Event log of an archived race, oldest first; empty if it has none
+pub async fn find_archived_race_events(
+    database: &Database,
+    race_uuid: Uuid,
+) -> Result<Vec<RaceEvent>, mongodb::error::Error> {
+    let blob = database
+        .collection::<Document>(RACE_EVENT_ARCHIVES)
+        .find_one(doc! { "race_uuid": race_uuid.to_string() }, None)
+        .await?;
+
+    match blob {
+        Some(blob) => {
+            let bytes = blob
+                .get_binary_generic("events")
+                .map_err(mongodb::error::Error::custom)?;
+            decompress_events(bytes)
+        }
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Gzipped BSON document holding `events`
+fn compress_events(events: &[RaceEvent]) -> Result<Vec<u8>, mongodb::error::Error> {
+    let document = doc! { "events": mongodb::bson::to_bson(events)? };
+    let mut bytes = Vec::new();
+    document.to_writer(&mut bytes)?;
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&bytes)?;
+    Ok(encoder.finish()?)
+}
+
+fn decompress_events(bytes: &[u8]) -> Result<Vec<RaceEvent>, mongodb::error::Error> {
+    let document = Document::from_reader(GzDecoder::new(bytes))?;
+    let events = document
+        .get_array("events")
+        .map_err(mongodb::error::Error::custom)?;
+    Ok(mongodb::bson::from_bson(events.clone().into())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{LapCharacteristic, RaceChange};
+
+    #[test]
+    fn events_survive_compression() {
+        let race_uuid = Uuid::new_v4();
+        let events: Vec<RaceEvent> = (1..=3)
+            .map(|lap| {
+                RaceEvent::new(
+                    race_uuid,
+                    BsonDateTime::from_millis(1_700_000_000_000 + i64::from(lap)),
+                    RaceChange::PhaseChanged {
+                        status: RaceStatus::InProgress,
+                        current_lap: lap,
+                        lap_characteristic: LapCharacteristic::Curve,
+                    },
+                )
+            })
+            .collect();
+
+        let restored = decompress_events(&compress_events(&events).unwrap()).unwrap();
+        assert_eq!(restored.len(), 3);
+        assert_eq!(restored[2].occurred_at, events[2].occurred_at);
+        assert!(matches!(
+            restored[2].change,
+            RaceChange::PhaseChanged { current_lap: 3, .. }
+        ));
+    }
+
+    #[test]
+    fn union_stage_reads_the_archive_with_the_same_filter() {
+        let filter = doc! { "status": "Finished" };
+        let stage = union_with_archived_races(filter.clone());
+        let union = stage.get_document("$unionWith").unwrap();
+
+        assert_eq!(union.get_str("coll").unwrap(), RACES_ARCHIVE);
+        let pipeline = union.get_array("pipeline").unwrap();
+        assert_eq!(
+            pipeline[0]
+                .as_document()
+                .unwrap()
+                .get_document("$match")
+                .unwrap(),
+            &filter
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Consecutive failed operations that open the database circuit
+const FAILURE_THRESHOLD: u32 = 5;
+/// How long the database circuit stays open before an operation may try again
+const OPEN_DURATION: Duration = Duration::from_secs(10);
+
+static DATABASE_BREAKER: OnceLock<CircuitBreaker> = OnceLock::new();
+
+/// The circuit breaker guarding every retried `MongoDB` operation
+pub fn database_breaker() -> &'static CircuitBreaker {
+    DATABASE_BREAKER.get_or_init(|| CircuitBreaker::new(FAILURE_THRESHOLD, OPEN_DURATION))
+}
+
+/// Error of an operation the open circuit did not let through
+#[derive(Debug, Clone, Copy, thiserror::Error)]
+#[error("Database unavailable, retry in {}s", .retry_after.as_secs().max(1))]
+pub struct CircuitOpen {
+    pub retry_after: Duration,
+}
+
+/// Whether a `MongoDB` error comes from the open circuit rather than the server
+#[must_use]
+pub fn is_circuit_open(error: &mongodb::error::Error) -> bool {
+    error.get_custom::<CircuitOpen>().is_some()
+}
+
+#[derive(Debug, Clone, Copy)]
+enum State {
+    Closed {
+        failures: u32,
+    },
+    Open {
+        until: Instant,
+    },
+    /// One trial operation is running; it closes or reopens the circuit,
+    /// or is given up on at `until`
+    HalfOpen {
+        until: Instant,
+    },
+}
+
+/// Stops calling a failing dependency until it had time to recover
+///
+/// After `failure_threshold` consecutive failures the circuit opens and
+/// operations fail at once for `open_duration`. The first operation after
+/// that is let through as a trial: its success closes the circuit, its
+/// failure opens it again. A trial that never reports back, e.g. because
+/// its request was dropped, is given up on after another `open_duration`.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    open_duration: Duration,
+    state: Mutex<State>,
+}
+
+impl CircuitBreaker {
+    #[must_use]
+    pub fn new(failure_threshold: u32, open_duration: Duration) -> Self {
+        Self {
+            failure_threshold,
+            open_duration,
+            state: Mutex::new(State::Closed { failures: 0 }),
+        }
+    }
+
+    /// Let an operation through, or refuse it while the circuit is open
+    pub fn acquire(&self) -> Result<(), CircuitOpen> {
+        let now = Instant::now();
+        let mut state = self.lock();
+        match *state {
+            State::Closed { .. } => Ok(()),
+            State::Open { until } | State::HalfOpen { until } if now < until => Err(CircuitOpen {
+                retry_after: until - now,
+            }),
+            State::Open { .. } | State::HalfOpen { .. } => {
+                *state = State::HalfOpen {
+                    until: now + self.open_duration,
+                };
+                Ok(())
+            }
+        }
+    }
+
+    /// How long operations are still refused for, without taking the trial
+    #[must_use]
+    pub fn open_for(&self) -> Option<Duration> {
+        let now = Instant::now();
+        match *self.lock() {
+            State::Closed { .. } => None,
+            State::Open { until } | State::HalfOpen { until } => (now < until).then(|| until - now),
+        }
+    }
+
+    pub fn record_success(&self) {
+        *self.lock() = State::Closed { failures: 0 };
+    }
+
+    pub fn record_failure(&self) {
+        let mut state = self.lock();
+        let failures = match *state {
+            State::Closed { failures } => failures + 1,
+            State::Open { .. } | State::HalfOpen { .. } => self.failure_threshold,
+        };
+        *state = if failures >= self.failure_threshold {
+            if !matches!(*state, State::Open { .. }) {
+                tracing::error!(
+                    "Database circuit opened after {} consecutive failures",
+                    failures
+                );
+            }
+            State::Open {
+                until: Instant::now() + self.open_duration,
+            }
+        } else {
+            State::Closed { failures }
+        };
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        // The state is a plain value, valid even if a holder panicked
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn circuit_opens_after_consecutive_failures_only() {
+        let breaker = CircuitBreaker::new(3, Duration::from_secs(30));
+        breaker.record_failure();
+        breaker.record_failure();
+        breaker.record_success();
+        breaker.record_failure();
+        breaker.record_failure();
+        assert!(breaker.acquire().is_ok());
+
+        breaker.record_failure();
+        let refused = breaker.acquire().unwrap_err();
+        assert!(refused.retry_after <= Duration::from_secs(30));
+        assert!(breaker.open_for().is_some());
+    }
+
+    #[test]
+    fn one_trial_is_let_through_once_the_circuit_timed_out() {
+        let breaker = CircuitBreaker::new(1, Duration::ZERO);
+        breaker.record_failure();
+
+        assert!(breaker.acquire().is_ok());
+        breaker.record_failure();
+        assert!(breaker.acquire().is_ok());
+        breaker.record_success();
+        assert!(breaker.open_for().is_none());
+
+        let breaker = CircuitBreaker::new(1, Duration::from_millis(20));
+        breaker.record_failure();
+        std::thread::sleep(Duration::from_millis(25));
+        assert!(breaker.acquire().is_ok());
+        assert!(breaker.acquire().is_err(), "only one trial at a time");
+    }
+}
@@ -0,0 +1,566 @@
+use mongodb::Database;
+use mongodb::{
+    bson::{doc, Document},
+    error::{ErrorKind, WriteFailure},
+    options::{IndexOptions, UpdateOptions},
+    IndexModel,
+};
+use std::time::Duration;
+
+/// Version of the index set below
+///
+/// Bump it whenever an index is added, changed or retired. Servers only touch
+/// indexes when the version stored in the database is older than theirs, so an
+/// older build still running during a rollout never undoes a newer one.
+pub const INDEX_VERSION: i32 = 23;
+
+/// Collection holding the index version marker
+const SCHEMA_COLLECTION: &str = "schema_versions";
+const INDEX_MARKER_ID: &str = "indexes";
+
+/// Server error codes for an existing index with the same name but other options or keys
+const INDEX_OPTIONS_CONFLICT: i32 = 85;
+const INDEX_KEY_SPECS_CONFLICT: i32 = 86;
+/// Server error code when dropping an index that does not exist
+const INDEX_NOT_FOUND: i32 = 27;
+/// Server error code of a write rejected by a unique index
+const DUPLICATE_KEY: i32 = 11000;
+
+/// Index created at startup
+pub struct IndexSpec {
+    pub collection: &'static str,
+    pub name: &'static str,
+    pub keys: Document,
+    pub unique: bool,
+    /// Only index documents matching this filter, e.g. those with the field set
+    pub partial_filter: Option<Document>,
+    /// Delete documents this long after the indexed date
+    pub expire_after: Option<Duration>,
+}
+
+impl IndexSpec {
+    fn new(collection: &'static str, name: &'static str, keys: Document) -> Self {
+        Self {
+            collection,
+            name,
+            keys,
+            unique: false,
+            partial_filter: None,
+            expire_after: None,
+        }
+    }
+
+    fn unique(mut self) -> Self {
+        self.unique = true;
+        self
+    }
+
+    fn partial(mut self, filter: Document) -> Self {
+        self.partial_filter = Some(filter);
+        self
+    }
+
+    fn expiring(mut self, after: Duration) -> Self {
+        self.expire_after = Some(after);
+        self
+    }
+
+    fn model(&self) -> IndexModel {
+        let options = IndexOptions::builder()
+            .name(self.name.to_string())
+            .unique(self.unique.then_some(true))
+            .partial_filter_expression(self.partial_filter.clone())
+            .expire_after(self.expire_after)
+            .build();
+        IndexModel::builder()
+            .keys(self.keys.clone())
+            .options(options)
+            .build()
+    }
+}
+
+/// Every index the application relies on
+#[must_use]
+pub fn index_specs() -> Vec<IndexSpec> {
+    race_index_specs()
+        .into_iter()
+        .chain(player_index_specs())
+        .chain(economy_index_specs())
+        .chain(notification_index_specs())
+        .chain(spectator_index_specs())
+        .chain(social_index_specs())
+        .chain(history_index_specs())
+        .chain(delivery_index_specs())
+        .collect()
+}
+
+/// Indexes of races and ghosts
+fn race_index_specs() -> Vec<IndexSpec> {
+    vec![
+        IndexSpec::new("races", "races_uuid", doc! { "uuid": 1 }).unique(),
+        IndexSpec::new("races", "races_status", doc! { "status": 1 }),
+        // Waiting races gone quiet, found by the janitor
+        IndexSpec::new(
+            "races",
+            "races_status_updated",
+            doc! { "status": 1, "updated_at": 1 },
+        ),
+        // Running laps closing soon, found by the deadline warnings
+        IndexSpec::new(
+            "races",
+            "races_status_turn_deadline",
+            doc! { "status": 1, "turn_deadline_at": 1 },
+        ),
+        IndexSpec::new(
+            "races",
+            "races_participant_player",
+            doc! { "participants.player_uuid": 1 },
+        ),
+        IndexSpec::new(
+            "races",
+            "races_participant_pilot",
+            doc! { "participants.pilot_uuid": 1 },
+        ),
+        // Finished races of a player, most recently finished first
+        IndexSpec::new(
+            "races",
+            "races_participant_history",
+            doc! { "participants.player_uuid": 1, "status": 1, "updated_at": -1, "uuid": -1 },
+        ),
+        IndexSpec::new("ghosts", "ghosts_uuid", doc! { "uuid": 1 }).unique(),
+    ]
+}
+
+/// Indexes of players, their tracks, pack openings and trades
+fn player_index_specs() -> Vec<IndexSpec> {
+    vec![
+        IndexSpec::new("players", "players_uuid", doc! { "uuid": 1 }).unique(),
+        IndexSpec::new("players", "players_email", doc! { "email": 1 }).unique(),
+        // Wallets are optional, so only players that have one are indexed
+        IndexSpec::new(
+            "players",
+            "players_wallet_address",
+            doc! { "wallet_address": 1 },
+        )
+        .unique()
+        .partial(doc! { "wallet_address": { "$type": "string" } }),
+        IndexSpec::new("players", "players_car_uuid", doc! { "cars.uuid": 1 }),
+        // Track templates and stored track designs share the collection
+        IndexSpec::new("tracks", "tracks_template_id", doc! { "template_id": 1 })
+            .unique()
+            .partial(doc! { "template_id": { "$type": "string" } }),
+        IndexSpec::new("tracks", "tracks_uuid", doc! { "uuid": 1 })
+            .unique()
+            .partial(doc! { "uuid": { "$type": "string" } }),
+        IndexSpec::new(
+            "pack_openings",
+            "pack_openings_player_time",
+            doc! { "player_uuid": 1, "opened_at": -1 },
+        ),
+        IndexSpec::new("trades", "trades_uuid", doc! { "uuid": 1 }).unique(),
+        IndexSpec::new(
+            "trades",
+            "trades_offerer_time",
+            doc! { "offerer_uuid": 1, "created_at": -1 },
+        ),
+        IndexSpec::new(
+            "trades",
+            "trades_recipient_time",
+            doc! { "recipient_uuid": 1, "created_at": -1 },
+        ),
+    ]
+}
+
+/// Indexes of payouts, cheat flags and fantasy leagues
+fn economy_index_specs() -> Vec<IndexSpec> {
+    vec![
+        IndexSpec::new("race_payouts", "race_payouts_uuid", doc! { "uuid": 1 }).unique(),
+        // One payout per prized player, so queueing a race again adds nothing
+        IndexSpec::new(
+            "race_payouts",
+            "race_payouts_race_player",
+            doc! { "race_uuid": 1, "player_uuid": 1 },
+        )
+        .unique(),
+        IndexSpec::new("cheat_flags", "cheat_flags_uuid", doc! { "uuid": 1 }).unique(),
+        // One flag per finding, so analysing a race again raises nothing new
+        IndexSpec::new("cheat_flags", "cheat_flags_key", doc! { "key": 1 }).unique(),
+        // Review queue, oldest first
+        IndexSpec::new(
+            "cheat_flags",
+            "cheat_flags_status_time",
+            doc! { "status": 1, "flagged_at": 1 },
+        ),
+        // Pending payouts that are due, found by the payout worker
+        IndexSpec::new(
+            "race_payouts",
+            "race_payouts_status_next_attempt",
+            doc! { "status": 1, "next_attempt_at": 1 },
+        ),
+        IndexSpec::new(
+            "fantasy_leagues",
+            "fantasy_leagues_uuid",
+            doc! { "uuid": 1 },
+        )
+        .unique(),
+    ]
+}
+
+/// Indexes of notifications and the devices they are pushed to
+fn notification_index_specs() -> Vec<IndexSpec> {
+    vec![
+        IndexSpec::new(
+            "notifications",
+            "notifications_player_time",
+            doc! { "player_uuid": 1, "created_at": -1 },
+        ),
+        IndexSpec::new("notifications", "notifications_uuid", doc! { "uuid": 1 })
+            .unique()
+            .partial(doc! { "uuid": { "$type": "string" } }),
+        IndexSpec::new(
+            "notification_preferences",
+            "notification_preferences_player",
+            doc! { "player_uuid": 1 },
+        )
+        .unique(),
+        IndexSpec::new("device_tokens", "device_tokens_token", doc! { "token": 1 }).unique(),
+        IndexSpec::new(
+            "device_tokens",
+            "device_tokens_player",
+            doc! { "player_uuid": 1 },
+        ),
+    ]
+}
+
+/// Indexes of race spectators and their predictions
+fn spectator_index_specs() -> Vec<IndexSpec> {
+    vec![
+        // One heartbeat per viewer and race, counted by the race metadata
+        IndexSpec::new(
+            "race_spectators",
+            "race_spectators_viewer",
+            doc! { "race_uuid": 1, "viewer_id": 1 },
+        )
+        .unique(),
+        // Viewers gone for good are dropped once the count no longer sees them
+        IndexSpec::new(
+            "race_spectators",
+            "race_spectators_expiry",
+            doc! { "last_seen_at": 1 },
+        )
+        .expiring(Duration::from_hours(1)),
+        // One prediction per spectator and lap, the winner's under a null lap
+        IndexSpec::new(
+            "spectator_predictions",
+            "spectator_predictions_viewer",
+            doc! { "race_uuid": 1, "viewer_id": 1, "lap": 1 },
+        )
+        .unique(),
+    ]
+}
+
+/// Indexes of friendships, blocks and moderation
+fn social_index_specs() -> Vec<IndexSpec> {
+    vec![
+        // One friendship or request per pair of players, whichever way
+        IndexSpec::new("friendships", "friendships_pair", doc! { "pair": 1 }).unique(),
+        IndexSpec::new(
+            "friendships",
+            "friendships_requester",
+            doc! { "requester_uuid": 1 },
+        ),
+        IndexSpec::new(
+            "friendships",
+            "friendships_addressee",
+            doc! { "addressee_uuid": 1 },
+        ),
+        IndexSpec::new(
+            "player_blocks",
+            "player_blocks_pair",
+            doc! { "blocker_uuid": 1, "blocked_uuid": 1 },
+        )
+        .unique(),
+        IndexSpec::new(
+            "player_blocks",
+            "player_blocks_blocked",
+            doc! { "blocked_uuid": 1 },
+        ),
+        IndexSpec::new("reports", "reports_uuid", doc! { "uuid": 1 }).unique(),
+        // The moderation queue lists reports by status, oldest first
+        IndexSpec::new(
+            "reports",
+            "reports_status_time",
+            doc! { "status": 1, "created_at": 1 },
+        ),
+        // One open report per reporter and reported player
+        IndexSpec::new(
+            "reports",
+            "reports_open_pair",
+            doc! { "reporter_uuid": 1, "reported_uuid": 1 },
+        )
+        .unique()
+        .partial(doc! { "status": "Open" }),
+        IndexSpec::new(
+            "player_bans",
+            "player_bans_player",
+            doc! { "player_uuid": 1 },
+        )
+        .unique(),
+    ]
+}
+
+/// Indexes of the race event log, race archives and audit log
+fn history_index_specs() -> Vec<IndexSpec> {
+    vec![
+        IndexSpec::new(
+            "race_events",
+            "race_events_race_time",
+            doc! { "race_uuid": 1, "occurred_at": 1 },
+        ),
+        // One reaction per participant and lap
+        IndexSpec::new(
+            "race_events",
+            "race_events_reactions",
+            doc! { "race_uuid": 1, "change.lap": 1, "change.player_uuid": 1 },
+        )
+        .unique()
+        .partial(doc! { "change.type": "Reaction" }),
+        // Lap resolutions read by the admin stats
+        IndexSpec::new(
+            "race_events",
+            "race_events_type_time",
+            doc! { "change.type": 1, "occurred_at": 1 },
+        ),
+        // Archived races are read through `$unionWith` with the live queries' filters
+        IndexSpec::new("races_archive", "races_archive_uuid", doc! { "uuid": 1 }).unique(),
+        IndexSpec::new(
+            "races_archive",
+            "races_archive_participant_history",
+            doc! { "participants.player_uuid": 1, "status": 1, "updated_at": -1, "uuid": -1 },
+        ),
+        IndexSpec::new(
+            "race_event_archives",
+            "race_event_archives_race",
+            doc! { "race_uuid": 1 },
+        )
+        .unique(),
+        // Figures of finished races, computed once
+        IndexSpec::new("race_stats", "race_stats_race", doc! { "race_uuid": 1 }).unique(),
+        // Board after each lap, one per race and lap
+        IndexSpec::new(
+            "race_snapshots",
+            "race_snapshots_race_lap",
+            doc! { "race_uuid": 1, "lap": 1 },
+        )
+        .unique(),
+        IndexSpec::new(
+            "audit_log",
+            "audit_log_race_time",
+            doc! { "race_uuid": 1, "occurred_at": -1 },
+        ),
+        IndexSpec::new(
+            "audit_log",
+            "audit_log_player_time",
+            doc! { "player_uuid": 1, "occurred_at": -1 },
+        ),
+        IndexSpec::new(
+            "audit_log",
+            "audit_log_actor_time",
+            doc! { "actor": 1, "occurred_at": -1 },
+        ),
+        IndexSpec::new(
+            "audit_log",
+            "audit_log_action_time",
+            doc! { "action": 1, "occurred_at": 1 },
+        ),
+    ]
+}
+
+/// Indexes of the webhook, outbox, push and email queues
+fn delivery_index_specs() -> Vec<IndexSpec> {
+    vec![
+        IndexSpec::new("webhooks", "webhooks_uuid", doc! { "uuid": 1 }).unique(),
+        IndexSpec::new("webhooks", "webhooks_race", doc! { "race_uuid": 1 }),
+        IndexSpec::new(
+            "webhook_deliveries",
+            "webhook_deliveries_uuid",
+            doc! { "uuid": 1 },
+        )
+        .unique(),
+        // The sender claims the oldest due pending delivery
+        IndexSpec::new(
+            "webhook_deliveries",
+            "webhook_deliveries_due",
+            doc! { "status": 1, "next_attempt_at": 1 },
+        ),
+        IndexSpec::new(
+            "webhook_deliveries",
+            "webhook_deliveries_webhook_time",
+            doc! { "webhook_uuid": 1, "created_at": -1 },
+        ),
+        // The outbox relay claims races with pending messages whose lease expired
+        IndexSpec::new(
+            "races",
+            "races_pending_outbox",
+            doc! { "outbox_lease_until": 1 },
+        )
+        .partial(doc! { "outbox.uuid": { "$exists": true } }),
+        // The push notifier claims the oldest notification not pushed yet
+        IndexSpec::new(
+            "notifications",
+            "notifications_push_pending",
+            doc! { "push_pending": 1, "created_at": 1 },
+        )
+        .partial(doc! { "push_pending": true }),
+        // Turn notifications not followed by a reminder yet
+        IndexSpec::new(
+            "notifications",
+            "notifications_turn_reminders",
+            doc! { "created_at": 1 },
+        )
+        .partial(doc! { "kind": "YourTurn", "reminder_sent": false }),
+        // Players with notifications waiting for their email digest
+        IndexSpec::new(
+            "notifications",
+            "notifications_email_pending",
+            doc! { "player_uuid": 1, "created_at": 1 },
+        )
+        .partial(doc! { "email_pending": true }),
+    ]
+}
+
+/// Indexes from earlier versions that are no longer wanted, as (collection, name)
+const RETIRED_INDEXES: &[(&str, &str)] = &[];
+
+/// Create or update indexes if the database has an older index version
+/// Returns whether anything was changed.
+pub async fn ensure_indexes(database: &Database) -> Result<bool, mongodb::error::Error> {
+    let markers = database.collection::<Document>(SCHEMA_COLLECTION);
+    let stored_version = markers
+        .find_one(doc! { "_id": INDEX_MARKER_ID }, None)
+        .await?
+        .and_then(|marker| marker.get_i32("version").ok())
+        .unwrap_or(0);
+
+    if stored_version >= INDEX_VERSION {
+        tracing::debug!("Indexes are at version {}", stored_version);
+        return Ok(false);
+    }
+
+    for (collection, name) in RETIRED_INDEXES {
+        let collection = database.collection::<Document>(collection);
+        if let Err(e) = collection.drop_index(*name, None).await {
+            if error_code(&e) != Some(INDEX_NOT_FOUND) {
+                return Err(e);
+            }
+        }
+    }
+
+    for spec in index_specs() {
+        let collection = database.collection::<Document>(spec.collection);
+        match collection.create_index(spec.model(), None).await {
+            Ok(_) => {}
+            // The definition changed since the index was created: replace it
+            Err(e)
+                if matches!(
+                    error_code(&e),
+                    Some(INDEX_OPTIONS_CONFLICT | INDEX_KEY_SPECS_CONFLICT)
+                ) =>
+            {
+                tracing::info!("Recreating index {} on {}", spec.name, spec.collection);
+                collection.drop_index(spec.name, None).await?;
+                collection.create_index(spec.model(), None).await?;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    markers
+        .update_one(
+            doc! { "_id": INDEX_MARKER_ID },
+            doc! { "$set": { "version": INDEX_VERSION } },
+            UpdateOptions::builder().upsert(true).build(),
+        )
+        .await?;
+
+    tracing::info!(
+        "Updated indexes from version {} to {}",
+        stored_version,
+        INDEX_VERSION
+    );
+    Ok(true)
+}
+
+/// Whether a write was rejected by a unique index, e.g. because the same
+/// document was already inserted
+#[must_use]
+pub fn is_duplicate_key(error: &mongodb::error::Error) -> bool {
+    matches!(
+        error.kind.as_ref(),
+        ErrorKind::Write(WriteFailure::WriteError(write_error)) if write_error.code == DUPLICATE_KEY
+    )
+}
+
+fn error_code(error: &mongodb::error::Error) -> Option<i32> {
+    match error.kind.as_ref() {
+        ErrorKind::Command(command_error) => Some(command_error.code),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn index_names_are_unique() {
+        let specs = index_specs();
+        let names: HashSet<_> = specs.iter().map(|spec| spec.name).collect();
+        assert_eq!(names.len(), specs.len());
+    }
+
+    #[test]
+    fn retired_indexes_are_not_recreated() {
+        let specs = index_specs();
+        for (collection, name) in RETIRED_INDEXES {
+            assert!(!specs
+                .iter()
+                .any(|spec| spec.collection == *collection && spec.name == *name));
+        }
+    }
+
+    #[test]
+    fn lookup_fields_are_indexed() {
+        let specs = index_specs();
+        let indexed = |collection: &str, field: &str| {
+            specs
+                .iter()
+                .any(|spec| spec.collection == collection && spec.keys.contains_key(field))
+        };
+
+        assert!(indexed("races", "uuid"));
+        assert!(indexed("races", "status"));
+        assert!(indexed("races", "participants.player_uuid"));
+        assert!(indexed("players", "uuid"));
+        assert!(indexed("players", "email"));
+        assert!(indexed("players", "wallet_address"));
+        assert!(indexed("players", "cars.uuid"));
+        assert!(indexed("race_events", "race_uuid"));
+        assert!(indexed("races_archive", "uuid"));
+        assert!(indexed("race_event_archives", "race_uuid"));
+        assert!(indexed("race_snapshots", "race_uuid"));
+        assert!(indexed("race_stats", "race_uuid"));
+        assert!(indexed("notifications", "player_uuid"));
+        assert!(indexed("pack_openings", "player_uuid"));
+        assert!(indexed("trades", "offerer_uuid"));
+        assert!(indexed("trades", "recipient_uuid"));
+        assert!(indexed("race_payouts", "race_uuid"));
+        assert!(indexed("race_payouts", "status"));
+        assert!(indexed("cheat_flags", "key"));
+        assert!(indexed("audit_log", "action"));
+        assert!(indexed("device_tokens", "token"));
+        assert!(indexed("audit_log", "race_uuid"));
+        assert!(indexed("audit_log", "actor"));
+    }
+}
@@ -0,0 +1,11 @@
+mod archive;
+mod circuit_breaker;
+mod indexes;
+mod retry;
+mod snapshots;
+
+pub use archive::*;
+pub use circuit_breaker::*;
+pub use indexes::*;
+pub use retry::*;
+pub use snapshots::*;
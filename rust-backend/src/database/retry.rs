@@ -0,0 +1,206 @@
+use mongodb::error::{ErrorKind, RETRYABLE_WRITE_ERROR, TRANSIENT_TRANSACTION_ERROR};
+use rand::Rng;
+use std::future::Future;
+use std::time::Duration;
+
+use super::database_breaker;
+
+/// Server error codes of a replica set changing primary or a node going away,
+/// the ones the driver itself retries reads on
+const TRANSIENT_CODES: [i32; 13] = [
+    6,     // HostUnreachable
+    7,     // HostNotFound
+    89,    // NetworkTimeout
+    91,    // ShutdownInProgress
+    134,   // ReadConcernMajorityNotAvailableYet
+    189,   // PrimarySteppedDown
+    262,   // ExceededTimeLimit
+    9001,  // SocketException
+    10107, // NotWritablePrimary
+    11600, // InterruptedAtShutdown
+    11602, // InterruptedDueToReplStateChange
+    13435, // NotPrimaryNoSecondaryOk
+    13436, // NotPrimaryOrSecondary
+];
+
+/// How often and how patiently a `MongoDB` operation is retried
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Attempts in total, the first one included
+    pub max_attempts: u32,
+    /// Upper bound of the delay before the first retry, doubled for each next one
+    pub base_delay: Duration,
+    /// Upper bound of any delay
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    /// Rides out a primary election or a dropped connection, which take
+    /// well under a second, without holding a player's request much longer
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            base_delay: Duration::from_millis(50),
+            max_delay: Duration::from_secs(1),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Run `operation` until it succeeds, fails with an error that is not
+    /// [transient](is_transient) or runs out of attempts
+    ///
+    /// Only for operations that are safe to repeat: the first attempt may
+    /// have been applied even though its reply was lost.
+    pub async fn run<T, F, Fut>(
+        &self,
+        name: &str,
+        mut operation: F,
+    ) -> Result<T, mongodb::error::Error>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, mongodb::error::Error>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match operation().await {
+                Err(e) if attempt < self.max_attempts && is_transient(&e) => {
+                    let delay = self.delay(attempt);
+                    tracing::warn!(
+                        "{} failed on attempt {}, retrying in {:?}: {}",
+                        name,
+                        attempt,
+                        delay,
+                        e
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Delay before retry number `attempt`, drawn uniformly up to an
+    /// exponentially growing cap so that concurrent retries spread out
+    fn delay(&self, attempt: u32) -> Duration {
+        let cap = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_delay);
+        cap.mul_f64(rand::thread_rng().gen_range(0.0..=1.0))
+    }
+}
+
+/// Run `operation` with the default [`RetryPolicy`], behind the
+/// [database circuit breaker](database_breaker)
+///
+/// While the circuit is open the operation is not attempted and the error
+/// is a [`CircuitOpen`](super::CircuitOpen). An operation still failing
+/// transiently after its retries counts as one failure towards opening it.
+pub async fn with_retry<T, F, Fut>(name: &str, operation: F) -> Result<T, mongodb::error::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, mongodb::error::Error>>,
+{
+    let breaker = database_breaker();
+    breaker.acquire().map_err(mongodb::error::Error::custom)?;
+    let result = RetryPolicy::default().run(name, operation).await;
+    match &result {
+        Err(e) if is_transient(e) => breaker.record_failure(),
+        // Any answer from the server shows it is reachable
+        _ => breaker.record_success(),
+    }
+    result
+}
+
+/// Whether an error is a network or replica set hiccup that may clear up on
+/// its own, as opposed to a failure a retry would only repeat
+#[must_use]
+pub fn is_transient(error: &mongodb::error::Error) -> bool {
+    if error.contains_label(RETRYABLE_WRITE_ERROR)
+        || error.contains_label(TRANSIENT_TRANSACTION_ERROR)
+    {
+        return true;
+    }
+    match error.kind.as_ref() {
+        ErrorKind::Io(_)
+        | ErrorKind::ConnectionPoolCleared { .. }
+        | ErrorKind::ServerSelection { .. } => true,
+        ErrorKind::Command(command_error) => TRANSIENT_CODES.contains(&command_error.code),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    const QUICK: RetryPolicy = RetryPolicy {
+        max_attempts: 3,
+        base_delay: Duration::from_millis(1),
+        max_delay: Duration::from_millis(2),
+    };
+
+    fn network_error() -> mongodb::error::Error {
+        std::io::Error::from(std::io::ErrorKind::ConnectionReset).into()
+    }
+
+    #[test]
+    fn network_errors_are_transient_but_application_errors_are_not() {
+        assert!(is_transient(&network_error()));
+        assert!(!is_transient(&mongodb::error::Error::custom(
+            "Player already submitted an action"
+        )));
+    }
+
+    #[test]
+    fn delays_stay_below_the_cap() {
+        let policy = RetryPolicy::default();
+        for attempt in 1..10 {
+            assert!(policy.delay(attempt) <= policy.max_delay);
+        }
+        assert!(policy.delay(1) <= policy.base_delay);
+    }
+
+    #[tokio::test]
+    async fn transient_errors_are_retried_until_success() {
+        let calls = AtomicU32::new(0);
+        let result = QUICK
+            .run("test", || async {
+                if calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                    Err(network_error())
+                } else {
+                    Ok("saved")
+                }
+            })
+            .await;
+
+        assert_eq!(result.unwrap(), "saved");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn other_errors_and_exhausted_attempts_are_returned() {
+        let calls = AtomicU32::new(0);
+        let result: Result<(), _> = QUICK
+            .run("test", || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(mongodb::error::Error::custom("invalid action"))
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        calls.store(0, Ordering::SeqCst);
+        let result: Result<(), _> = QUICK
+            .run("test", || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(network_error())
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), QUICK.max_attempts);
+    }
+}
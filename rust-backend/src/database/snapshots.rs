@@ -0,0 +1,61 @@
+use mongodb::{bson::doc, options::FindOptions, Database};
+use uuid::Uuid;
+
+use super::is_duplicate_key;
+use crate::domain::{LapResult, RaceSnapshot};
+
+/// Collection holding the board of each race after every processed lap
+pub const RACE_SNAPSHOTS: &str = "race_snapshots";
+
+/// Store the board each of `lap_results` left behind
+///
+/// Snapshots are unique per race and lap, so recording a lap again keeps
+/// the first one.
+pub async fn record_race_snapshots(
+    database: &Database,
+    race_uuid: Uuid,
+    lap_results: &[LapResult],
+) -> Result<(), mongodb::error::Error> {
+    let snapshots = database.collection::<RaceSnapshot>(RACE_SNAPSHOTS);
+    for lap_result in lap_results {
+        let snapshot = RaceSnapshot::after_lap(race_uuid, lap_result);
+        if let Err(e) = snapshots.insert_one(&snapshot, None).await {
+            if !is_duplicate_key(&e) {
+                return Err(e);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Snapshots of a race, first lap first
+pub async fn find_race_snapshots(
+    database: &Database,
+    race_uuid: Uuid,
+) -> Result<Vec<RaceSnapshot>, mongodb::error::Error> {
+    let options = FindOptions::builder().sort(doc! { "lap": 1 }).build();
+    let mut cursor = database
+        .collection::<RaceSnapshot>(RACE_SNAPSHOTS)
+        .find(doc! { "race_uuid": race_uuid.to_string() }, options)
+        .await?;
+    let mut snapshots = Vec::new();
+    while cursor.advance().await? {
+        snapshots.push(cursor.deserialize_current()?);
+    }
+    Ok(snapshots)
+}
+
+/// Snapshot of a race after `lap`, if that lap was processed
+pub async fn find_race_snapshot(
+    database: &Database,
+    race_uuid: Uuid,
+    lap: u32,
+) -> Result<Option<RaceSnapshot>, mongodb::error::Error> {
+    database
+        .collection::<RaceSnapshot>(RACE_SNAPSHOTS)
+        .find_one(
+            doc! { "race_uuid": race_uuid.to_string(), "lap": lap },
+            None,
+        )
+        .await
+}
@@ -0,0 +1,102 @@
+use mongodb::bson::DateTime as BsonDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+
+use super::{Race, RaceEvent};
+
+/// Proof that a finished race's results were written on-chain
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct RaceAnchor {
+    /// Hex SHA-256 of the final classification and event log, see [`anchor_hash`]
+    pub hash: String,
+    /// Solana transaction carrying `hash` as its memo
+    pub transaction_signature: String,
+    #[schema(value_type = String, format = "date-time")]
+    pub anchored_at: BsonDateTime,
+}
+
+/// Hex SHA-256 anchored for a finished race
+///
+/// Hashes compact JSON, with object keys sorted, of
+/// `{"race_uuid", "classification", "events"}`: the classification lists
+/// `{"player_uuid", "car_uuid", "finish_position"}` by finishing order, and
+/// the events are `{"occurred_at" (Unix milliseconds), "change"}` in log
+/// order. Anyone holding the race and its event log can recompute it.
+#[must_use]
+pub fn anchor_hash(race: &Race, events: &[RaceEvent]) -> String {
+    let mut classification: Vec<_> = race.participants.iter().collect();
+    classification.sort_by_key(|p| (p.finish_position.unwrap_or(u32::MAX), p.player_uuid));
+    let classification: Vec<_> = classification
+        .into_iter()
+        .map(|p| {
+            json!({
+                "player_uuid": p.player_uuid.to_string(),
+                "car_uuid": p.car_uuid.to_string(),
+                "finish_position": p.finish_position,
+            })
+        })
+        .collect();
+    let events: Vec<_> = events
+        .iter()
+        .map(|event| {
+            json!({
+                "occurred_at": event.occurred_at.timestamp_millis(),
+                "change": event.change,
+            })
+        })
+        .collect();
+
+    // `Value` keeps its keys sorted, so the same race always gives the same bytes
+    let payload = json!({
+        "race_uuid": race.uuid.to_string(),
+        "classification": classification,
+        "events": events,
+    });
+    hex::encode(Sha256::digest(payload.to_string().as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{RaceChange, RaceStatus, Sector, SectorType, Track};
+
+    fn finished_race() -> Race {
+        let sector = |id, sector_type| Sector {
+            id,
+            name: format!("Sector {id}"),
+            min_value: id * 10,
+            max_value: id * 10 + 10,
+            slot_capacity: None,
+            sector_type,
+            capacity_schedule: Vec::new(),
+            modifiers: None,
+        };
+        let sectors = vec![sector(0, SectorType::Start), sector(1, SectorType::Finish)];
+        let track = Track::new("Track".to_string(), sectors).unwrap();
+        let mut race = Race::new("Anchored".to_string(), track, 1);
+        race.status = RaceStatus::Finished;
+        race
+    }
+
+    #[test]
+    fn hash_covers_the_event_log() {
+        let race = finished_race();
+        let finished = RaceEvent::new(
+            race.uuid,
+            BsonDateTime::from_millis(1_700_000_000_000),
+            RaceChange::PhaseChanged {
+                status: RaceStatus::Finished,
+                current_lap: 1,
+                lap_characteristic: race.lap_characteristic.clone(),
+            },
+        );
+
+        let hash = anchor_hash(&race, std::slice::from_ref(&finished));
+
+        assert_eq!(hash.len(), 64);
+        assert_eq!(anchor_hash(&race, &[finished]), hash);
+        assert_ne!(anchor_hash(&race, &[]), hash);
+    }
+}
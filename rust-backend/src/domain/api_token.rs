@@ -0,0 +1,139 @@
+use mongodb::bson::DateTime as BsonDateTime;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Prefix of API tokens, telling them apart from session JWTs
+pub const API_TOKEN_PREFIX: &str = "bdt_";
+
+/// What an API token may read
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub enum ApiScope {
+    #[serde(rename = "read:races")]
+    ReadRaces,
+    #[serde(rename = "read:leaderboard")]
+    ReadLeaderboard,
+}
+
+impl ApiScope {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ReadRaces => "read:races",
+            Self::ReadLeaderboard => "read:leaderboard",
+        }
+    }
+}
+
+/// Read-only token a player hands to a third-party site, such as a stream
+/// overlay or a stats site
+///
+/// Only a hash of the token is stored; the token itself is shown once, when
+/// it is issued.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ApiToken {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<mongodb::bson::oid::ObjectId>,
+    #[serde(with = "uuid_as_string")]
+    pub uuid: Uuid,
+    /// Player who issued the token
+    #[serde(with = "uuid_as_string")]
+    pub owner_uuid: Uuid,
+    /// What the token is for, as named by its owner
+    pub name: String,
+    pub scopes: Vec<ApiScope>,
+    /// SHA-256 of the token, in hex
+    pub token_hash: String,
+    pub created_at: BsonDateTime,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revoked_at: Option<BsonDateTime>,
+}
+
+impl ApiToken {
+    /// A new token for `owner_uuid`, with the token to hand to the site
+    #[must_use]
+    pub fn issue(owner_uuid: Uuid, name: String, scopes: Vec<ApiScope>) -> (Self, String) {
+        use rand::RngCore;
+
+        let mut secret = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut secret);
+        let token = format!("{API_TOKEN_PREFIX}{}", hex::encode(secret));
+        let api_token = Self {
+            id: None,
+            uuid: Uuid::new_v4(),
+            owner_uuid,
+            name,
+            scopes,
+            token_hash: Self::hash(&token),
+            created_at: BsonDateTime::now(),
+            revoked_at: None,
+        };
+        (api_token, token)
+    }
+
+    /// Hash a token is stored and looked up by
+    #[must_use]
+    pub fn hash(token: &str) -> String {
+        hex::encode(Sha256::digest(token.as_bytes()))
+    }
+
+    /// Whether the token may be used where `scope` is needed
+    #[must_use]
+    pub fn allows(&self, scope: ApiScope) -> bool {
+        self.revoked_at.is_none() && self.scopes.contains(&scope)
+    }
+}
+
+mod uuid_as_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use uuid::Uuid;
+
+    pub fn serialize<S>(uuid: &Uuid, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&uuid.to_string())
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Uuid, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Uuid::parse_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_only_allow_their_scopes_until_revoked() {
+        let (mut api_token, token) = ApiToken::issue(
+            Uuid::new_v4(),
+            "Overlay".to_string(),
+            vec![ApiScope::ReadLeaderboard],
+        );
+
+        assert!(token.starts_with(API_TOKEN_PREFIX));
+        assert_eq!(api_token.token_hash, ApiToken::hash(&token));
+        assert!(api_token.allows(ApiScope::ReadLeaderboard));
+        assert!(!api_token.allows(ApiScope::ReadRaces));
+
+        api_token.revoked_at = Some(BsonDateTime::now());
+        assert!(!api_token.allows(ApiScope::ReadLeaderboard));
+    }
+
+    #[test]
+    fn scopes_are_stored_by_name() {
+        let scopes =
+            serde_json::to_value([ApiScope::ReadRaces, ApiScope::ReadLeaderboard]).unwrap();
+
+        assert_eq!(
+            scopes,
+            serde_json::json!(["read:races", "read:leaderboard"])
+        );
+    }
+}
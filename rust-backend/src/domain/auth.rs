@@ -2,6 +2,7 @@ use argon2::{
     password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
     Argon2,
 };
+use chrono::{DateTime, Utc};
 use secrecy::{ExposeSecret, Secret};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
@@ -27,6 +28,80 @@ impl UserRole {
     pub fn can_access_any_resource(&self) -> bool {
         self.is_admin()
     }
+
+    /// Permissions granted to this role, put in its access tokens
+    #[must_use]
+    pub fn permissions(&self) -> Vec<Permission> {
+        match self {
+            UserRole::Player => Vec::new(),
+            UserRole::Admin | UserRole::SuperAdmin => vec![
+                Permission::AuditRead,
+                Permission::ModerationManage,
+                Permission::PayoutsManage,
+                Permission::StatsRead,
+                Permission::IntegrationsManage,
+            ],
+        }
+    }
+}
+
+/// Capability a route may require of the caller's access token
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub enum Permission {
+    #[serde(rename = "audit:read")]
+    AuditRead,
+    #[serde(rename = "moderation:manage")]
+    ModerationManage,
+    #[serde(rename = "payouts:manage")]
+    PayoutsManage,
+    #[serde(rename = "stats:read")]
+    StatsRead,
+    #[serde(rename = "integrations:manage")]
+    IntegrationsManage,
+}
+
+impl Permission {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Permission::AuditRead => "audit:read",
+            Permission::ModerationManage => "moderation:manage",
+            Permission::PayoutsManage => "payouts:manage",
+            Permission::StatsRead => "stats:read",
+            Permission::IntegrationsManage => "integrations:manage",
+        }
+    }
+}
+
+/// Identity providers players can sign in with
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OAuthProvider {
+    Google,
+    Discord,
+}
+
+impl OAuthProvider {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OAuthProvider::Google => "google",
+            OAuthProvider::Discord => "discord",
+        }
+    }
+}
+
+/// A provider account linked to a player
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct OAuthIdentity {
+    pub provider: OAuthProvider,
+    /// The account's ID at the provider
+    pub subject: String,
+    /// Email the provider gave when the account was linked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[schema(value_type = String, format = "date-time")]
+    pub linked_at: DateTime<Utc>,
 }
 
 /// A secure password wrapper that prevents accidental exposure
@@ -0,0 +1,272 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::race::{MovementType, ParticipantMovement, Race, RaceParticipant};
+use super::race_event::RaceChange;
+
+/// Highlight of a finished race
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, ToSchema)]
+pub enum AwardKind {
+    /// Most places gained from the worst standing held after any lap to the finish
+    BiggestComeback,
+    /// Most sectors gained per boost point spent
+    MostEfficientBoostUsage,
+    /// Most moves up into the next sector
+    MostOvertakes,
+}
+
+/// Award handed out when a race finishes
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct RaceAward {
+    pub kind: AwardKind,
+    #[serde(with = "uuid_as_string")]
+    #[schema(value_type = String, format = "uuid")]
+    pub player_uuid: Uuid,
+    /// Places gained, sectors gained per boost point, or overtakes, by kind
+    pub value: f64,
+}
+
+/// Awards of a finished race, from the changes in its event log
+///
+/// Each award goes to the participant with the best value, ties going to the
+/// better finisher; an award nobody earned (e.g. no overtakes) is left out.
+#[must_use]
+pub fn race_awards(race: &Race, changes: &[RaceChange]) -> Vec<RaceAward> {
+    let mut laps: BTreeMap<u32, Vec<&ParticipantMovement>> = BTreeMap::new();
+    for change in changes {
+        if let RaceChange::Movement { lap, movement } = change {
+            laps.entry(*lap).or_default().push(movement);
+        }
+    }
+
+    let mut overtakes: HashMap<Uuid, u32> = HashMap::new();
+    let mut sectors_gained: HashMap<Uuid, u32> = HashMap::new();
+    for movement in laps.values().flatten() {
+        match movement.movement_type {
+            MovementType::MovedUp => {
+                *overtakes.entry(movement.player_uuid).or_default() += 1;
+                *sectors_gained.entry(movement.player_uuid).or_default() += 1;
+            }
+            MovementType::FinishedLap | MovementType::FinishedRace => {
+                *sectors_gained.entry(movement.player_uuid).or_default() += 1;
+            }
+            MovementType::StayedInSector | MovementType::MovedDown => {}
+        }
+    }
+
+    let comebacks = comebacks(race, &laps);
+    let efficiency: HashMap<Uuid, f64> = race
+        .participants
+        .iter()
+        .filter(|p| !p.boost_usage_history.is_empty())
+        .map(|p| {
+            // A zero card still costs a play, so it counts as one point
+            let spent: u32 = p
+                .boost_usage_history
+                .iter()
+                .map(|record| u32::from(record.boost_value).max(1))
+                .sum();
+            let gained = sectors_gained.get(&p.player_uuid).copied().unwrap_or(0);
+            (p.player_uuid, f64::from(gained) / f64::from(spent))
+        })
+        .collect();
+
+    let overtakes = overtakes
+        .into_iter()
+        .map(|(player_uuid, count)| (player_uuid, f64::from(count)))
+        .collect();
+    [
+        (AwardKind::BiggestComeback, comebacks),
+        (AwardKind::MostEfficientBoostUsage, efficiency),
+        (AwardKind::MostOvertakes, overtakes),
+    ]
+    .into_iter()
+    .filter_map(|(kind, values)| best(race, kind, &values))
+    .collect()
+}
+
+/// Places each participant gained from their worst standing after a lap to their final one
+fn comebacks(race: &Race, laps: &BTreeMap<u32, Vec<&ParticipantMovement>>) -> HashMap<Uuid, f64> {
+    // (finished, laps completed, sector) of each car, best first when compared
+    let mut standing: HashMap<Uuid, (bool, u32, u32)> = HashMap::new();
+    let mut worst_rank: HashMap<Uuid, usize> = HashMap::new();
+
+    for movements in laps.values() {
+        for movement in movements {
+            let entry =
+                standing
+                    .entry(movement.player_uuid)
+                    .or_insert((false, 0, movement.from_sector));
+            match movement.movement_type {
+                MovementType::FinishedRace => entry.0 = true,
+                MovementType::FinishedLap => entry.1 += 1,
+                _ => {}
+            }
+            entry.2 = movement.to_sector;
+        }
+        for (player_uuid, own) in &standing {
+            let rank = 1 + standing.values().filter(|other| *other > own).count();
+            let worst = worst_rank.entry(*player_uuid).or_insert(rank);
+            *worst = (*worst).max(rank);
+        }
+    }
+
+    race.participants
+        .iter()
+        .filter_map(|p| {
+            let worst = *worst_rank.get(&p.player_uuid)?;
+            let final_rank = usize::try_from(p.finish_position?).ok()?;
+            #[allow(clippy::cast_precision_loss)]
+            let gained = worst.saturating_sub(final_rank) as f64;
+            Some((p.player_uuid, gained))
+        })
+        .collect()
+}
+
+fn best(race: &Race, kind: AwardKind, values: &HashMap<Uuid, f64>) -> Option<RaceAward> {
+    race.participants
+        .iter()
+        .filter_map(|p| {
+            let value = *values.get(&p.player_uuid)?;
+            (value > 0.0).then_some((p, value))
+        })
+        .max_by(|(a, a_value), (b, b_value)| {
+            a_value.total_cmp(b_value).then_with(|| {
+                // Lower finish positions are better; unfinished cars come last
+                let position = |p: &RaceParticipant| p.finish_position.unwrap_or(u32::MAX);
+                position(b).cmp(&position(a))
+            })
+        })
+        .map(|(participant, value)| RaceAward {
+            kind,
+            player_uuid: participant.player_uuid,
+            value,
+        })
+}
+
+mod uuid_as_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use uuid::Uuid;
+
+    pub fn serialize<S>(uuid: &Uuid, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&uuid.to_string())
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Uuid, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Uuid::parse_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{BoostUsageRecord, RaceStatus, Sector, SectorType, Track};
+
+    fn race() -> Race {
+        let sector = |id: u32, sector_type| Sector {
+            id,
+            name: format!("Sector {id}"),
+            min_value: id * 10,
+            max_value: id * 10 + 10,
+            slot_capacity: None,
+            sector_type,
+            capacity_schedule: Vec::new(),
+            modifiers: None,
+        };
+        let sectors = vec![
+            sector(0, SectorType::Start),
+            sector(1, SectorType::Straight),
+            sector(2, SectorType::Finish),
+        ];
+        let track = Track::new("Track".to_string(), sectors).unwrap();
+        let mut race = Race::new("Awarded".to_string(), track, 2);
+        for _ in 0..2 {
+            race.add_participant(Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4())
+                .unwrap();
+        }
+        race.status = RaceStatus::Finished;
+        race
+    }
+
+    fn moved(lap: u32, player_uuid: Uuid, from: u32, to: u32, kind: MovementType) -> RaceChange {
+        RaceChange::Movement {
+            lap,
+            movement: ParticipantMovement {
+                player_uuid,
+                from_sector: from,
+                to_sector: to,
+                final_value: 10,
+                movement_type: kind,
+                steps: Vec::new(),
+            },
+        }
+    }
+
+    fn played(boost_values: &[u8]) -> Vec<BoostUsageRecord> {
+        boost_values
+            .iter()
+            .enumerate()
+            .map(|(index, &boost_value)| BoostUsageRecord {
+                lap_number: u32::try_from(index).unwrap() + 1,
+                boost_value,
+                cycle_number: 1,
+                cards_remaining_after: 4,
+                replenishment_occurred: false,
+                lap_characteristic: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn trailing_car_that_wins_gets_the_comeback_and_overtakes() {
+        let mut race = race();
+        let (early, late) = (
+            race.participants[0].player_uuid,
+            race.participants[1].player_uuid,
+        );
+        race.participants[0].finish_position = Some(2);
+        race.participants[1].finish_position = Some(1);
+        race.participants[0].boost_usage_history = played(&[4, 4]);
+        race.participants[1].boost_usage_history = played(&[1, 1]);
+        let changes = vec![
+            moved(1, early, 0, 1, MovementType::MovedUp),
+            moved(1, late, 0, 0, MovementType::StayedInSector),
+            moved(2, early, 1, 1, MovementType::StayedInSector),
+            moved(2, late, 0, 1, MovementType::MovedUp),
+            moved(3, early, 1, 2, MovementType::MovedUp),
+            moved(3, late, 1, 2, MovementType::MovedUp),
+        ];
+
+        let awards = race_awards(&race, &changes);
+
+        let winner = |kind| {
+            awards
+                .iter()
+                .find(|a| a.kind == kind)
+                .map(|a| (a.player_uuid, a.value))
+        };
+        assert_eq!(winner(AwardKind::BiggestComeback), Some((late, 1.0)));
+        assert_eq!(
+            winner(AwardKind::MostEfficientBoostUsage),
+            Some((late, 1.0))
+        );
+        // Both overtook twice; the winner of the race takes the tie
+        assert_eq!(winner(AwardKind::MostOvertakes), Some((late, 2.0)));
+    }
+
+    #[test]
+    fn awards_nobody_earned_are_left_out() {
+        let race = race();
+        assert!(race_awards(&race, &[]).is_empty());
+    }
+}
@@ -0,0 +1,207 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{BoostUsageRecord, LapCharacteristic};
+
+/// Cards a player played at one place in their boost cycles
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct CyclePositionUsage {
+    /// Place of the card in its cycle, from 1
+    pub position: u32,
+    pub plays: u32,
+    pub average_boost: f64,
+}
+
+/// Cards a player played on laps of one characteristic
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct CharacteristicUsage {
+    pub lap_characteristic: LapCharacteristic,
+    pub plays: u32,
+    pub average_boost: f64,
+}
+
+/// How a player spends boost cards across races
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct BoostAnalytics {
+    #[schema(value_type = String, format = "uuid")]
+    pub player_uuid: Uuid,
+    pub races: u32,
+    pub cards_played: u32,
+    /// Times each card was played, by card value from 0 to 4
+    pub card_counts: Vec<u32>,
+    pub average_boost: Option<f64>,
+    /// Mean card played in the first half of a cycle
+    pub early_cycle_average: Option<f64>,
+    /// Mean card played in the second half of a cycle
+    pub late_cycle_average: Option<f64>,
+    pub by_cycle_position: Vec<CyclePositionUsage>,
+    /// Only laps whose characteristic was recorded with the card
+    pub by_characteristic: Vec<CharacteristicUsage>,
+}
+
+/// Plays and total card value of a group of plays
+#[derive(Default)]
+struct Tally {
+    plays: u32,
+    total: u32,
+}
+
+impl Tally {
+    fn add(&mut self, boost_value: u8) {
+        self.plays += 1;
+        self.total += u32::from(boost_value);
+    }
+
+    fn average(&self) -> Option<f64> {
+        (self.plays > 0).then(|| f64::from(self.total) / f64::from(self.plays))
+    }
+}
+
+impl BoostAnalytics {
+    /// Analytics of a player's boost usage history in each of their races
+    ///
+    /// The middle card of a cycle with an odd number of plays counts as
+    /// neither early nor late.
+    #[must_use]
+    pub fn from_histories(player_uuid: Uuid, histories: &[&[BoostUsageRecord]]) -> Self {
+        let mut card_counts = vec![0; 5];
+        let mut overall = Tally::default();
+        let mut early = Tally::default();
+        let mut late = Tally::default();
+        let mut by_position: BTreeMap<u32, Tally> = BTreeMap::new();
+        let mut straight = Tally::default();
+        let mut curve = Tally::default();
+
+        for history in histories {
+            let mut cycles: BTreeMap<u32, Vec<&BoostUsageRecord>> = BTreeMap::new();
+            for usage in *history {
+                cycles.entry(usage.cycle_number).or_default().push(usage);
+            }
+
+            for mut cycle in cycles.into_values() {
+                cycle.sort_by_key(|usage| usage.lap_number);
+                let length = cycle.len();
+                for (index, usage) in cycle.into_iter().enumerate() {
+                    let value = usage.boost_value;
+                    overall.add(value);
+                    if let Some(count) = card_counts.get_mut(usize::from(value)) {
+                        *count += 1;
+                    }
+                    if 2 * (index + 1) <= length {
+                        early.add(value);
+                    } else if 2 * index >= length {
+                        late.add(value);
+                    }
+                    let position = u32::try_from(index + 1).unwrap_or(u32::MAX);
+                    by_position.entry(position).or_default().add(value);
+                    match usage.lap_characteristic {
+                        Some(LapCharacteristic::Straight) => straight.add(value),
+                        Some(LapCharacteristic::Curve) => curve.add(value),
+                        None => {}
+                    }
+                }
+            }
+        }
+
+        let by_cycle_position = by_position
+            .into_iter()
+            .filter_map(|(position, tally)| {
+                Some(CyclePositionUsage {
+                    position,
+                    plays: tally.plays,
+                    average_boost: tally.average()?,
+                })
+            })
+            .collect();
+        let by_characteristic = [
+            (LapCharacteristic::Straight, straight),
+            (LapCharacteristic::Curve, curve),
+        ]
+        .into_iter()
+        .filter_map(|(lap_characteristic, tally)| {
+            Some(CharacteristicUsage {
+                lap_characteristic,
+                plays: tally.plays,
+                average_boost: tally.average()?,
+            })
+        })
+        .collect();
+
+        Self {
+            player_uuid,
+            races: u32::try_from(histories.len()).unwrap_or(u32::MAX),
+            cards_played: overall.plays,
+            card_counts,
+            average_boost: overall.average(),
+            early_cycle_average: early.average(),
+            late_cycle_average: late.average(),
+            by_cycle_position,
+            by_characteristic,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(
+        lap_number: u32,
+        cycle_number: u32,
+        boost_value: u8,
+        lap_characteristic: Option<LapCharacteristic>,
+    ) -> BoostUsageRecord {
+        BoostUsageRecord {
+            lap_number,
+            boost_value,
+            cycle_number,
+            cards_remaining_after: 0,
+            replenishment_occurred: false,
+            lap_characteristic,
+        }
+    }
+
+    #[test]
+    fn early_and_late_cards_are_split_within_each_cycle() {
+        let straight = Some(LapCharacteristic::Straight);
+        let curve = Some(LapCharacteristic::Curve);
+        let first_race = [
+            usage(1, 1, 4, straight.clone()),
+            usage(2, 1, 3, curve.clone()),
+            usage(3, 1, 2, curve.clone()),
+            usage(4, 1, 1, None),
+            usage(5, 1, 0, curve),
+        ];
+        let second_race = [usage(1, 1, 4, straight.clone()), usage(2, 1, 0, straight)];
+        let histories: Vec<&[BoostUsageRecord]> = vec![&first_race, &second_race];
+
+        let analytics = BoostAnalytics::from_histories(Uuid::new_v4(), &histories);
+
+        assert_eq!(analytics.races, 2);
+        assert_eq!(analytics.cards_played, 7);
+        assert_eq!(analytics.card_counts, vec![2, 1, 1, 1, 2]);
+        // 4 and 3, then 4 from the short cycle
+        assert_eq!(analytics.early_cycle_average, Some(11.0 / 3.0));
+        // 1 and 0, then 0 from the short cycle; the middle 2 is left out
+        assert_eq!(analytics.late_cycle_average, Some(1.0 / 3.0));
+        assert_eq!(analytics.by_cycle_position.len(), 5);
+        assert!((analytics.by_cycle_position[0].average_boost - 4.0).abs() < f64::EPSILON);
+        assert_eq!(
+            analytics.by_characteristic,
+            vec![
+                CharacteristicUsage {
+                    lap_characteristic: LapCharacteristic::Straight,
+                    plays: 3,
+                    average_boost: 8.0 / 3.0,
+                },
+                CharacteristicUsage {
+                    lap_characteristic: LapCharacteristic::Curve,
+                    plays: 3,
+                    average_boost: 5.0 / 3.0,
+                },
+            ]
+        );
+    }
+}
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use utoipa::ToSchema;
 
+use super::performance_model::PerformanceModel;
 use super::race::{BoostHand, MovementProbability, Sector};
 
 /// Error types for boost card operations
@@ -16,6 +17,9 @@ pub enum BoostCardError {
         boost_value: u8,
         available_cards: Vec<u8>,
     },
+
+    #[error("Boost card {0} is already banked for the next cycle")]
+    CardAlreadyBanked(u8),
 }
 
 /// Result of using a boost card
@@ -83,6 +87,10 @@ impl BoostCardErrorResponse {
                     "Boost card {boost_value} is not available. Available cards: {available_cards:?}"
                 ),
             ),
+            BoostCardError::CardAlreadyBanked(banked) => (
+                "BOOST_CARD_ALREADY_BANKED".to_string(),
+                format!("Boost card {banked} is already banked for the next cycle"),
+            ),
         };
 
         Self {
@@ -150,7 +158,7 @@ impl BoostHandManager {
         Self::validate_boost_selection(boost_hand, boost_value)?;
 
         // Track state before using card
-        let cards_before = boost_hand.cards_remaining;
+        let cycle_before = boost_hand.current_cycle;
 
         // Use the card
         boost_hand
@@ -161,9 +169,9 @@ impl BoostHandManager {
             })?;
 
         // Check if replenishment occurred
-        // Replenishment happens when all cards were used (cards_before was 1)
-        // and now cards_remaining is 5 again
-        let replenishment_occurred = cards_before == 1 && boost_hand.cards_remaining == 5;
+        // Replenishment starts a new cycle, so compare against the cycle before use
+        // (playing a carried-over card never replenishes the hand)
+        let replenishment_occurred = boost_hand.current_cycle != cycle_before;
 
         Ok(BoostUsageResult {
             boost_value,
@@ -173,6 +181,48 @@ impl BoostHandManager {
         })
     }
 
+    /// Bank an unused boost card into the next cycle
+    ///
+    /// The card is removed from the current cycle and handed back as an extra
+    /// card once the hand replenishes. Only one card can be banked per cycle,
+    /// and a carried-over card cannot be banked again.
+    ///
+    /// # Arguments
+    /// * `boost_hand` - Mutable reference to the player's boost hand
+    /// * `boost_value` - The boost card value to bank (0-4)
+    ///
+    /// # Returns
+    /// * `Ok(BoostUsageResult)` describing the hand after banking
+    /// * `Err(BoostCardError)` if the card cannot be banked
+    pub fn bank_boost_card(
+        boost_hand: &mut BoostHand,
+        boost_value: u8,
+    ) -> Result<BoostUsageResult, BoostCardError> {
+        if boost_value > 4 {
+            return Err(BoostCardError::InvalidBoostValue(boost_value));
+        }
+
+        if let Some(banked) = boost_hand.banked_card {
+            return Err(BoostCardError::CardAlreadyBanked(banked));
+        }
+
+        let cycle_before = boost_hand.current_cycle;
+
+        boost_hand
+            .bank_card(boost_value)
+            .map_err(|_| BoostCardError::CardNotAvailable {
+                boost_value,
+                available_cards: boost_hand.get_available_cards(),
+            })?;
+
+        Ok(BoostUsageResult {
+            boost_value,
+            cards_remaining: boost_hand.cards_remaining,
+            current_cycle: boost_hand.current_cycle,
+            replenishment_occurred: boost_hand.current_cycle != cycle_before,
+        })
+    }
+
     /// Get boost availability for API response
     ///
     /// Generates a comprehensive boost availability response including
@@ -183,6 +233,7 @@ impl BoostHandManager {
     /// * `boost_hand` - The player's boost hand
     /// * `current_sector` - The sector the player is currently in
     /// * `base_performance` - The player's base performance value (before boost)
+    /// * `model` - The race's performance model used for the impact preview
     ///
     /// # Returns
     /// * `BoostAvailability` struct with complete boost hand information
@@ -191,6 +242,7 @@ impl BoostHandManager {
         boost_hand: &BoostHand,
         current_sector: &Sector,
         base_performance: u32,
+        model: &dyn PerformanceModel,
     ) -> BoostAvailability {
         let available_cards = boost_hand.get_available_cards();
 
@@ -200,10 +252,8 @@ impl BoostHandManager {
                 let is_available = boost_hand.is_card_available(boost);
 
                 // Calculate predicted final value with boost
-                let capped_base = std::cmp::min(base_performance, current_sector.max_value);
-                let boost_multiplier = 1.0 + (f64::from(boost) * 0.08);
-                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-                let predicted_final = (f64::from(capped_base) * boost_multiplier).round() as u32;
+                let predicted_final =
+                    model.final_value(base_performance, current_sector, u32::from(boost));
 
                 // Calculate movement probability
                 let movement_probability =
@@ -255,6 +305,7 @@ impl BoostHandManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::performance_model::MultiplicativeModel;
     use crate::domain::race::SectorType;
 
     fn create_test_boost_hand() -> BoostHand {
@@ -269,6 +320,8 @@ mod tests {
             max_value: 20,
             slot_capacity: Some(3),
             sector_type: SectorType::Straight,
+            capacity_schedule: Vec::new(),
+            modifiers: None,
         }
     }
 
@@ -407,8 +460,12 @@ mod tests {
         let sector = create_test_sector();
         let base_performance = 15;
 
-        let availability =
-            BoostHandManager::get_boost_availability(&hand, &sector, base_performance);
+        let availability = BoostHandManager::get_boost_availability(
+            &hand,
+            &sector,
+            base_performance,
+            &MultiplicativeModel::default(),
+        );
 
         // Verify basic fields
         assert_eq!(availability.available_cards.len(), 5);
@@ -450,8 +507,12 @@ mod tests {
         hand.use_card(1).unwrap();
         hand.use_card(3).unwrap();
 
-        let availability =
-            BoostHandManager::get_boost_availability(&hand, &sector, base_performance);
+        let availability = BoostHandManager::get_boost_availability(
+            &hand,
+            &sector,
+            base_performance,
+            &MultiplicativeModel::default(),
+        );
 
         // Verify available cards
         assert_eq!(availability.available_cards.len(), 3);
@@ -520,14 +581,68 @@ mod tests {
         assert!(!response.available_cards.contains(&2));
     }
 
+    #[test]
+    fn test_bank_boost_card_carries_into_next_cycle() {
+        let mut hand = create_test_boost_hand();
+
+        let result = BoostHandManager::bank_boost_card(&mut hand, 4).unwrap();
+        assert_eq!(result.cards_remaining, 4);
+        assert!(!result.replenishment_occurred);
+        assert_eq!(hand.banked_card, Some(4));
+        assert!(!hand.is_card_available(4));
+
+        // Second bank in the same cycle is rejected
+        assert!(matches!(
+            BoostHandManager::bank_boost_card(&mut hand, 3),
+            Err(BoostCardError::CardAlreadyBanked(4))
+        ));
+
+        // Finish the cycle with the remaining cards
+        for i in 0..=3 {
+            BoostHandManager::use_boost_card(&mut hand, i).unwrap();
+        }
+
+        assert_eq!(hand.current_cycle, 2);
+        assert_eq!(hand.banked_card, None);
+        assert_eq!(hand.carried_over_card, Some(4));
+
+        // The regular 4 is played first, then the carried-over copy
+        BoostHandManager::use_boost_card(&mut hand, 4).unwrap();
+        assert!(hand.is_card_available(4));
+        let result = BoostHandManager::use_boost_card(&mut hand, 4).unwrap();
+        assert_eq!(result.cards_remaining, 4);
+        assert!(!result.replenishment_occurred);
+        assert!(!hand.is_card_available(4));
+    }
+
+    #[test]
+    fn test_bank_boost_card_rejects_used_card() {
+        let mut hand = create_test_boost_hand();
+        hand.use_card(1).unwrap();
+
+        assert!(matches!(
+            BoostHandManager::bank_boost_card(&mut hand, 1),
+            Err(BoostCardError::CardNotAvailable { boost_value: 1, .. })
+        ));
+        assert!(matches!(
+            BoostHandManager::bank_boost_card(&mut hand, 7),
+            Err(BoostCardError::InvalidBoostValue(7))
+        ));
+        assert_eq!(hand.banked_card, None);
+    }
+
     #[test]
     fn test_boost_impact_calculation() {
         let hand = create_test_boost_hand();
         let sector = create_test_sector(); // min: 10, max: 20
         let base_performance = 15;
 
-        let availability =
-            BoostHandManager::get_boost_availability(&hand, &sector, base_performance);
+        let availability = BoostHandManager::get_boost_availability(
+            &hand,
+            &sector,
+            base_performance,
+            &MultiplicativeModel::default(),
+        );
 
         // Verify boost calculations
         // Base is 15, capped to sector max (20)
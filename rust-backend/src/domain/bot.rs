@@ -0,0 +1,159 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::car::performance_score;
+use super::race::Sector;
+
+/// Component values given to bot cars, roughly a starter car
+pub const BOT_ENGINE_VALUE: u32 = 7;
+pub const BOT_BODY_VALUE: u32 = 6;
+pub const BOT_PILOT_VALUE: u32 = 5;
+
+/// How a bot spends its boost cards
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, ToSchema)]
+pub enum BotPersonality {
+    /// Always plays its highest available card, burning through the hand early
+    Aggressive,
+    /// Plays the lowest card that keeps it from dropping a sector
+    Conservative,
+    /// Plays the cheapest card that clears the sector threshold, otherwise saves
+    #[default]
+    Adaptive,
+}
+
+/// Strategy and car of a computer-controlled participant
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, ToSchema)]
+pub struct BotProfile {
+    pub personality: BotPersonality,
+    /// Decision quality from 0.0 (random cards) to 1.0 (always follows its personality)
+    pub difficulty: f64,
+    pub engine_value: u32,
+    pub body_value: u32,
+    pub pilot_value: u32,
+}
+
+impl BotProfile {
+    /// Create a bot with the default bot car
+    pub fn new(personality: BotPersonality, difficulty: f64) -> Result<Self, String> {
+        if !(0.0..=1.0).contains(&difficulty) {
+            return Err(format!(
+                "Invalid bot difficulty: {difficulty}. Must be between 0.0 and 1.0"
+            ));
+        }
+
+        Ok(Self {
+            personality,
+            difficulty,
+            engine_value: BOT_ENGINE_VALUE,
+            body_value: BOT_BODY_VALUE,
+            pilot_value: BOT_PILOT_VALUE,
+        })
+    }
+
+    /// Performance rating of the bot's car, as rated for the starting grid
+    #[must_use]
+    pub fn performance_rating(&self) -> u32 {
+        performance_score(2 * (self.engine_value + self.body_value + self.pilot_value))
+    }
+
+    /// Pick a boost card among the available ones
+    ///
+    /// `final_value` gives the lap value a card would produce in the bot's current
+    /// sector. With probability `1 - difficulty` the personality is ignored and a
+    /// random available card is played instead.
+    pub fn choose_boost<R: Rng + ?Sized>(
+        &self,
+        available_cards: &[u8],
+        sector: &Sector,
+        final_value: impl Fn(u8) -> u32,
+        rng: &mut R,
+    ) -> u8 {
+        let mut cards = available_cards.to_vec();
+        cards.sort_unstable();
+        let Some(&lowest) = cards.first() else {
+            return 0;
+        };
+
+        if rng.gen::<f64>() >= self.difficulty {
+            return *cards.choose(rng).unwrap_or(&lowest);
+        }
+
+        let keeps_sector = |card: &&u8| final_value(**card) >= sector.min_value;
+        let clears_sector = |card: &&u8| final_value(**card) > sector.max_value;
+
+        match self.personality {
+            BotPersonality::Aggressive => *cards.last().unwrap_or(&lowest),
+            BotPersonality::Conservative => *cards.iter().find(keeps_sector).unwrap_or(&lowest),
+            BotPersonality::Adaptive => *cards
+                .iter()
+                .find(clears_sector)
+                .or_else(|| cards.iter().find(keeps_sector))
+                .unwrap_or(&lowest),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::race::SectorType;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn create_test_sector() -> Sector {
+        Sector {
+            id: 1,
+            name: "Straight".to_string(),
+            min_value: 12,
+            max_value: 13,
+            slot_capacity: Some(3),
+            sector_type: SectorType::Straight,
+            capacity_schedule: Vec::new(),
+            modifiers: None,
+        }
+    }
+
+    fn choose(personality: BotPersonality, cards: &[u8]) -> u8 {
+        let bot = BotProfile::new(personality, 1.0).unwrap();
+        let mut rng = StdRng::seed_from_u64(7);
+        // Base value 10: card 2 keeps the sector, card 4 moves up
+        bot.choose_boost(
+            cards,
+            &create_test_sector(),
+            |card| 10 + u32::from(card),
+            &mut rng,
+        )
+    }
+
+    #[test]
+    fn test_personalities_pick_different_cards() {
+        let cards = [0, 1, 2, 3, 4];
+        assert_eq!(choose(BotPersonality::Aggressive, &cards), 4);
+        assert_eq!(choose(BotPersonality::Conservative, &cards), 2);
+        assert_eq!(choose(BotPersonality::Adaptive, &cards), 4);
+    }
+
+    #[test]
+    fn test_adaptive_saves_when_it_cannot_move_up() {
+        assert_eq!(choose(BotPersonality::Adaptive, &[0, 2, 3]), 2);
+        assert_eq!(choose(BotPersonality::Conservative, &[0]), 0);
+    }
+
+    #[test]
+    fn test_low_difficulty_plays_available_cards_only() {
+        let bot = BotProfile::new(BotPersonality::Aggressive, 0.0).unwrap();
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..20 {
+            let card = bot.choose_boost(&[1, 3], &create_test_sector(), u32::from, &mut rng);
+            assert!(card == 1 || card == 3);
+        }
+    }
+
+    #[test]
+    fn test_difficulty_must_be_a_ratio() {
+        assert!(BotProfile::new(BotPersonality::Adaptive, 1.5).is_err());
+        assert!(BotProfile::new(BotPersonality::Adaptive, -0.1).is_err());
+    }
+}
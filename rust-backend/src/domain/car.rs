@@ -4,6 +4,18 @@ use unicode_segmentation::UnicodeSegmentation;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
+/// Highest sum of a car's engine, body and pilot values over both kinds of lap
+pub const MAX_CAR_VALUE_TOTAL: u32 = 60;
+
+/// Performance rating from 0 to 100 of a car whose values sum to `value_total`
+///
+/// Player cars, bots and ghosts are all rated this way, so ratings compare
+/// across them in matchmaking, on the starting grid and in the UI.
+#[must_use]
+pub fn performance_score(value_total: u32) -> u32 {
+    (value_total.min(MAX_CAR_VALUE_TOTAL) * 100 + MAX_CAR_VALUE_TOTAL / 2) / MAX_CAR_VALUE_TOTAL
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct Car {
     #[serde(with = "uuid_as_string")]
@@ -0,0 +1,118 @@
+use mongodb::bson::DateTime as BsonDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Suspicious pattern the anti-cheat analysis looks for
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, ToSchema)]
+pub enum CheatFlagKind {
+    /// Actions submitted faster after the lap opened than a person can react
+    ImpossibleTiming,
+    /// Contested sectors won by a hair too often, as if the other cars'
+    /// boosts were known
+    SuspiciousBoosts,
+    /// The same device registered to several accounts
+    SharedDevice,
+}
+
+/// Where a flag stands in the review queue
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+pub enum CheatFlagStatus {
+    /// Waiting for an administrator
+    Open,
+    /// Reviewed and found harmless
+    Dismissed,
+    /// Reviewed and found to be cheating
+    Confirmed,
+}
+
+/// What an administrator decides about a flag
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+pub enum CheatFlagVerdict {
+    Dismiss,
+    Confirm,
+}
+
+/// Suspicious pattern found for a player, waiting for review
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct CheatFlag {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub id: Option<mongodb::bson::oid::ObjectId>,
+    #[serde(with = "uuid_as_string")]
+    #[schema(value_type = String, format = "uuid")]
+    pub uuid: Uuid,
+    /// Identifies the finding, so analysing the same data again adds nothing
+    pub key: String,
+    pub kind: CheatFlagKind,
+    #[serde(with = "uuid_as_string")]
+    #[schema(value_type = String, format = "uuid")]
+    pub player_uuid: Uuid,
+    /// Race the pattern was found in, if any
+    pub race_uuid: Option<String>,
+    /// Other account involved, for shared devices
+    pub related_player_uuid: Option<String>,
+    /// What was measured, for the reviewer
+    pub evidence: String,
+    pub status: CheatFlagStatus,
+    #[schema(value_type = String, format = "date-time")]
+    pub flagged_at: BsonDateTime,
+    #[schema(value_type = Option<String>, format = "date-time")]
+    pub reviewed_at: Option<BsonDateTime>,
+    /// Administrator who reviewed the flag
+    pub reviewed_by: Option<String>,
+    pub review_note: Option<String>,
+}
+
+impl CheatFlag {
+    /// Open flag of `kind` for `player_uuid`, keyed by `scope`, e.g. the race
+    #[must_use]
+    pub fn new(kind: CheatFlagKind, player_uuid: Uuid, scope: &str, evidence: String) -> Self {
+        Self {
+            id: None,
+            uuid: Uuid::new_v4(),
+            key: format!("{kind:?}:{player_uuid}:{scope}"),
+            kind,
+            player_uuid,
+            race_uuid: None,
+            related_player_uuid: None,
+            evidence,
+            status: CheatFlagStatus::Open,
+            flagged_at: BsonDateTime::now(),
+            reviewed_at: None,
+            reviewed_by: None,
+            review_note: None,
+        }
+    }
+
+    /// Close the flag with the administrator's verdict
+    pub fn review(&mut self, verdict: CheatFlagVerdict, admin_uuid: Uuid, note: Option<String>) {
+        self.status = match verdict {
+            CheatFlagVerdict::Dismiss => CheatFlagStatus::Dismissed,
+            CheatFlagVerdict::Confirm => CheatFlagStatus::Confirmed,
+        };
+        self.reviewed_at = Some(BsonDateTime::now());
+        self.reviewed_by = Some(admin_uuid.to_string());
+        self.review_note = note;
+    }
+}
+
+mod uuid_as_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use uuid::Uuid;
+
+    pub fn serialize<S>(uuid: &Uuid, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&uuid.to_string())
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Uuid, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Uuid::parse_str(&s).map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+use uuid::Uuid;
+
+use super::race::{LapResult, MovementType, Race, RaceParticipant};
+use super::race_event::RaceChange;
+
+/// Human-readable commentary on the laps resolved between two states of a race
+///
+/// `names` holds the team names of the players; bots and ghosts are named
+/// after what they are, and players without a known name after their car.
+#[must_use]
+pub fn lap_commentary<S: BuildHasher>(
+    before: &Race,
+    after: &Race,
+    lap_results: &[LapResult],
+    names: &HashMap<Uuid, String, S>,
+) -> Vec<RaceChange> {
+    let name_of = |player_uuid: Uuid| {
+        after
+            .participants
+            .iter()
+            .find(|p| p.player_uuid == player_uuid)
+            .map_or_else(|| short_uuid(player_uuid), |p| display_name(p, names))
+    };
+    let sector_name = |sector_id: u32| {
+        after
+            .track
+            .sectors
+            .get(sector_id as usize)
+            .map_or_else(|| format!("Sector {sector_id}"), |s| s.name.clone())
+    };
+
+    let mut lines = Vec::new();
+    for lap_result in lap_results {
+        for movement in &lap_result.movements {
+            let name = name_of(movement.player_uuid);
+            let text = match movement.movement_type {
+                MovementType::StayedInSector => continue,
+                MovementType::MovedUp => {
+                    format!("{name} overtakes into {}", sector_name(movement.to_sector))
+                }
+                MovementType::MovedDown => {
+                    format!("{name} drops back to {}", sector_name(movement.to_sector))
+                }
+                MovementType::FinishedLap => format!("{name} completes lap {}", lap_result.lap),
+                MovementType::FinishedRace => finish_line(after, movement.player_uuid, &name),
+            };
+            lines.push(RaceChange::Commentary {
+                lap: lap_result.lap,
+                player_uuid: movement.player_uuid,
+                text,
+            });
+        }
+    }
+
+    // Playing the last card of a hand refills it, completing a cycle
+    if let Some(last_lap) = lap_results.last().map(|lap_result| lap_result.lap) {
+        for participant in &after.participants {
+            let refilled = before
+                .participants
+                .iter()
+                .find(|p| p.player_uuid == participant.player_uuid)
+                .is_some_and(|previous| {
+                    participant.boost_hand.cycles_completed > previous.boost_hand.cycles_completed
+                });
+            if refilled {
+                lines.push(RaceChange::Commentary {
+                    lap: last_lap,
+                    player_uuid: participant.player_uuid,
+                    text: format!(
+                        "{} burns their last boost card",
+                        display_name(participant, names)
+                    ),
+                });
+            }
+        }
+    }
+
+    lines
+}
+
+fn finish_line(race: &Race, player_uuid: Uuid, name: &str) -> String {
+    let position = race
+        .participants
+        .iter()
+        .find(|p| p.player_uuid == player_uuid)
+        .and_then(|p| p.finish_position);
+    match position {
+        Some(position) => format!("{name} crosses the finish line in P{position}"),
+        None => format!("{name} crosses the finish line"),
+    }
+}
+
+fn display_name<S: BuildHasher>(
+    participant: &RaceParticipant,
+    names: &HashMap<Uuid, String, S>,
+) -> String {
+    if let Some(bot) = participant.bot {
+        format!("{:?} bot", bot.personality)
+    } else if participant.ghost.is_some() {
+        "Ghost".to_string()
+    } else {
+        names
+            .get(&participant.player_uuid)
+            .cloned()
+            .unwrap_or_else(|| format!("Car {}", short_uuid(participant.car_uuid)))
+    }
+}
+
+fn short_uuid(uuid: Uuid) -> String {
+    uuid.to_string().chars().take(8).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{LapCharacteristic, ParticipantMovement, Sector, SectorType, Track};
+
+    fn race() -> Race {
+        let sector = |id: u32, name: &str, sector_type| Sector {
+            id,
+            name: name.to_string(),
+            min_value: id * 10,
+            max_value: id * 10 + 10,
+            slot_capacity: None,
+            sector_type,
+            capacity_schedule: Vec::new(),
+            modifiers: None,
+        };
+        let sectors = vec![
+            sector(0, "Start", SectorType::Start),
+            sector(1, "Hairpin", SectorType::Curve),
+            sector(2, "Finish", SectorType::Finish),
+        ];
+        let track = Track::new("Track".to_string(), sectors).unwrap();
+        let mut race = Race::new("Commented".to_string(), track, 3);
+        for _ in 0..2 {
+            race.add_participant(Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4())
+                .unwrap();
+        }
+        race
+    }
+
+    fn lap_result(movements: Vec<ParticipantMovement>) -> LapResult {
+        LapResult {
+            lap: 1,
+            lap_characteristic: LapCharacteristic::Straight,
+            sector_positions: HashMap::new(),
+            movements,
+        }
+    }
+
+    fn movement(
+        player_uuid: Uuid,
+        to_sector: u32,
+        movement_type: MovementType,
+    ) -> ParticipantMovement {
+        ParticipantMovement {
+            player_uuid,
+            from_sector: 0,
+            to_sector,
+            final_value: 12,
+            movement_type,
+            steps: Vec::new(),
+        }
+    }
+
+    fn texts(changes: &[RaceChange]) -> Vec<&str> {
+        changes
+            .iter()
+            .filter_map(|change| match change {
+                RaceChange::Commentary { text, .. } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn overtakes_name_the_team_and_sector() {
+        let race = race();
+        let leader = race.participants[0].player_uuid;
+        let other = race.participants[1].player_uuid;
+        let names = HashMap::from([(leader, "Red Bolts".to_string())]);
+        let lap = lap_result(vec![
+            movement(leader, 1, MovementType::MovedUp),
+            movement(other, 0, MovementType::StayedInSector),
+        ]);
+
+        let changes = lap_commentary(&race, &race, &[lap], &names);
+
+        assert_eq!(texts(&changes), vec!["Red Bolts overtakes into Hairpin"]);
+    }
+
+    #[test]
+    fn refilled_hand_means_the_last_card_was_played() {
+        let before = race();
+        let mut after = before.clone();
+        after.participants[1].boost_hand.cycles_completed = 1;
+        let car = after.participants[1].car_uuid.to_string();
+
+        let changes = lap_commentary(&before, &after, &[lap_result(Vec::new())], &HashMap::new());
+
+        assert_eq!(
+            texts(&changes),
+            vec![format!("Car {} burns their last boost card", &car[..8])]
+        );
+    }
+}
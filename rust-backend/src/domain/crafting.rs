@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::domain::ComponentRarity;
+
+/// Component kinds that can be fused
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+pub enum CraftableComponent {
+    Engine,
+    Body,
+}
+
+/// Component fused from two components of the same rarity
+///
+/// Recipes are deterministic: the result is one rarity up and keeps the better
+/// of each input stat plus one, capped at what the new rarity allows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FusionRecipe {
+    pub rarity: ComponentRarity,
+    pub straight_value: u8,
+    pub curve_value: u8,
+    /// Credits the fusion costs
+    pub cost: u32,
+}
+
+impl FusionRecipe {
+    /// Recipe fusing two components of `rarity`, given as straight and curve
+    /// values; `None` when no rarity is above `rarity`
+    #[must_use]
+    pub fn fuse(rarity: ComponentRarity, first: (u8, u8), second: (u8, u8)) -> Option<Self> {
+        let rarity = rarity.next()?;
+        let derive = |a: u8, b: u8| a.max(b).saturating_add(1).min(rarity.get_max_values());
+
+        Some(Self {
+            rarity,
+            straight_value: derive(first.0, second.0),
+            curve_value: derive(first.1, second.1),
+            cost: fusion_cost(rarity),
+        })
+    }
+}
+
+/// Credits fusing into a component of `rarity` costs
+#[must_use]
+pub fn fusion_cost(rarity: ComponentRarity) -> u32 {
+    match rarity {
+        ComponentRarity::Common => 0,
+        ComponentRarity::Uncommon => 100,
+        ComponentRarity::Rare => 250,
+        ComponentRarity::Epic => 500,
+        ComponentRarity::Legendary => 1000,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fusion_raises_rarity_and_caps_stats() {
+        let recipe = FusionRecipe::fuse(ComponentRarity::Common, (6, 2), (3, 4)).unwrap();
+
+        assert_eq!(recipe.rarity, ComponentRarity::Uncommon);
+        assert_eq!((recipe.straight_value, recipe.curve_value), (7, 5));
+        assert_eq!(recipe.cost, 100);
+        assert_eq!(
+            FusionRecipe::fuse(ComponentRarity::Common, (3, 4), (6, 2)),
+            Some(recipe)
+        );
+
+        let capped = FusionRecipe::fuse(ComponentRarity::Epic, (10, 9), (9, 10)).unwrap();
+        assert_eq!((capped.straight_value, capped.curve_value), (10, 10));
+
+        assert_eq!(
+            FusionRecipe::fuse(ComponentRarity::Legendary, (10, 10), (10, 10)),
+            None
+        );
+    }
+}
@@ -23,7 +23,10 @@ pub struct Engine {
 #[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct EngineName(String);
 
-#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+/// Rarity of an engine or body, from most to least common
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, ToSchema,
+)]
 pub enum ComponentRarity {
     Common,
     Uncommon,
@@ -113,6 +116,18 @@ impl AsRef<str> for EngineName {
 }
 
 impl ComponentRarity {
+    /// Rarity one step up, `None` for `Legendary`
+    #[must_use]
+    pub fn next(self) -> Option<Self> {
+        match self {
+            ComponentRarity::Common => Some(ComponentRarity::Uncommon),
+            ComponentRarity::Uncommon => Some(ComponentRarity::Rare),
+            ComponentRarity::Rare => Some(ComponentRarity::Epic),
+            ComponentRarity::Epic => Some(ComponentRarity::Legendary),
+            ComponentRarity::Legendary => None,
+        }
+    }
+
     #[must_use]
     pub fn get_value_multiplier(&self) -> f32 {
         match self {
@@ -0,0 +1,311 @@
+use std::collections::HashMap;
+
+use mongodb::bson::DateTime as BsonDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::race::{Race, RaceStatus};
+
+/// Fantasy points by finish position, P1 first; lower places score nothing
+pub const FANTASY_POSITION_POINTS: [u32; 8] = [10, 8, 6, 5, 4, 3, 2, 1];
+
+/// Most picks a roster may hold
+pub const MAX_ROSTER_SIZE: usize = 10;
+
+/// Real player or car drafted onto a fantasy roster
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, ToSchema)]
+#[serde(tag = "kind", content = "uuid")]
+pub enum FantasyPick {
+    /// Scores for every finish of the player, whatever car they drive
+    #[schema(value_type = String, format = "uuid")]
+    Player(#[serde(with = "uuid_as_string")] Uuid),
+    /// Scores for every finish of the car
+    #[schema(value_type = String, format = "uuid")]
+    Car(#[serde(with = "uuid_as_string")] Uuid),
+}
+
+/// Picks of one league member
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct FantasyRoster {
+    #[serde(with = "uuid_as_string")]
+    #[schema(value_type = String, format = "uuid")]
+    pub owner_uuid: Uuid,
+    pub picks: Vec<FantasyPick>,
+}
+
+/// Fantasy league over a championship: a fixed series of races
+///
+/// Members draft real players and cars until the first championship race
+/// starts; each pick belongs to at most one roster of the league.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct FantasyLeague {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub id: Option<mongodb::bson::oid::ObjectId>,
+    #[serde(with = "uuid_as_string")]
+    #[schema(value_type = String, format = "uuid")]
+    pub uuid: Uuid,
+    pub name: String,
+    /// Player who created the league
+    #[serde(with = "uuid_as_string")]
+    #[schema(value_type = String, format = "uuid")]
+    pub owner_uuid: Uuid,
+    /// Races making up the championship
+    #[schema(value_type = Vec<String>)]
+    pub championship: Vec<String>,
+    pub roster_size: usize,
+    pub rosters: Vec<FantasyRoster>,
+    #[schema(value_type = String, format = "date-time")]
+    pub created_at: BsonDateTime,
+}
+
+/// Why a draft was refused
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FantasyDraftError {
+    TooManyPicks,
+    DuplicatePick(FantasyPick),
+    /// Another member already drafted the pick
+    PickTaken(FantasyPick),
+}
+
+impl std::fmt::Display for FantasyDraftError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TooManyPicks => write!(f, "Roster holds more picks than the league allows"),
+            Self::DuplicatePick(pick) => write!(f, "{pick:?} is picked twice"),
+            Self::PickTaken(pick) => write!(f, "{pick:?} is already on another roster"),
+        }
+    }
+}
+
+impl FantasyLeague {
+    #[must_use]
+    pub fn new(name: String, owner_uuid: Uuid, championship: &[Uuid], roster_size: usize) -> Self {
+        Self {
+            id: None,
+            uuid: Uuid::new_v4(),
+            name,
+            owner_uuid,
+            championship: championship.iter().map(Uuid::to_string).collect(),
+            roster_size,
+            rosters: Vec::new(),
+            created_at: BsonDateTime::now(),
+        }
+    }
+
+    /// Set the picks of `owner_uuid`, joining the league if needed
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the roster is too big, repeats a pick, or takes
+    /// one already on another member's roster.
+    pub fn draft(
+        &mut self,
+        owner_uuid: Uuid,
+        picks: Vec<FantasyPick>,
+    ) -> Result<(), FantasyDraftError> {
+        if picks.len() > self.roster_size {
+            return Err(FantasyDraftError::TooManyPicks);
+        }
+        for (index, pick) in picks.iter().enumerate() {
+            if picks[..index].contains(pick) {
+                return Err(FantasyDraftError::DuplicatePick(*pick));
+            }
+            let taken = self
+                .rosters
+                .iter()
+                .any(|roster| roster.owner_uuid != owner_uuid && roster.picks.contains(pick));
+            if taken {
+                return Err(FantasyDraftError::PickTaken(*pick));
+            }
+        }
+
+        match self.rosters.iter_mut().find(|r| r.owner_uuid == owner_uuid) {
+            Some(roster) => roster.picks = picks,
+            None => self.rosters.push(FantasyRoster { owner_uuid, picks }),
+        }
+        Ok(())
+    }
+
+    /// Members ranked by points from the finished championship races, best first
+    #[must_use]
+    pub fn standings(&self, races: &[Race]) -> Vec<FantasyStanding> {
+        let mut pick_points: HashMap<FantasyPick, u32> = HashMap::new();
+        let mut races_scored = 0;
+        for race in races {
+            if race.status != RaceStatus::Finished
+                || !self.championship.contains(&race.uuid.to_string())
+            {
+                continue;
+            }
+            races_scored += 1;
+            for participant in &race.participants {
+                let points = participant
+                    .finish_position
+                    .and_then(|position| usize::try_from(position).ok()?.checked_sub(1))
+                    .and_then(|index| FANTASY_POSITION_POINTS.get(index))
+                    .copied()
+                    .unwrap_or(0);
+                *pick_points
+                    .entry(FantasyPick::Player(participant.player_uuid))
+                    .or_default() += points;
+                *pick_points
+                    .entry(FantasyPick::Car(participant.car_uuid))
+                    .or_default() += points;
+            }
+        }
+
+        let mut standings: Vec<FantasyStanding> = self
+            .rosters
+            .iter()
+            .map(|roster| FantasyStanding {
+                owner_uuid: roster.owner_uuid,
+                points: roster
+                    .picks
+                    .iter()
+                    .map(|pick| pick_points.get(pick).copied().unwrap_or(0))
+                    .sum(),
+                races_scored,
+            })
+            .collect();
+        standings.sort_by(|a, b| {
+            b.points
+                .cmp(&a.points)
+                .then(a.owner_uuid.cmp(&b.owner_uuid))
+        });
+        standings
+    }
+}
+
+/// Member's standing in a fantasy league
+#[derive(Debug, Serialize, Clone, PartialEq, Eq, ToSchema)]
+pub struct FantasyStanding {
+    #[schema(value_type = String, format = "uuid")]
+    pub owner_uuid: Uuid,
+    pub points: u32,
+    /// Championship races finished so far
+    pub races_scored: u32,
+}
+
+mod uuid_as_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use uuid::Uuid;
+
+    pub fn serialize<S>(uuid: &Uuid, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&uuid.to_string())
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Uuid, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Uuid::parse_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Sector, SectorType, Track};
+
+    fn finished_race() -> Race {
+        let sector = |id: u32, sector_type| Sector {
+            id,
+            name: format!("Sector {id}"),
+            min_value: id * 10,
+            max_value: id * 10 + 10,
+            slot_capacity: None,
+            sector_type,
+            capacity_schedule: Vec::new(),
+            modifiers: None,
+        };
+        let sectors = vec![sector(0, SectorType::Start), sector(1, SectorType::Finish)];
+        let track = Track::new("Track".to_string(), sectors).unwrap();
+        let mut race = Race::new("Round".to_string(), track, 2);
+        for position in 1..=2 {
+            race.add_participant(Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4())
+                .unwrap();
+            race.participants.last_mut().unwrap().finish_position = Some(position);
+        }
+        race.status = RaceStatus::Finished;
+        race
+    }
+
+    #[test]
+    fn picks_belong_to_a_single_roster() {
+        let mut league = FantasyLeague::new("League".to_string(), Uuid::new_v4(), &[], 2);
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+        let pick = FantasyPick::Player(Uuid::new_v4());
+
+        league.draft(first, vec![pick]).unwrap();
+
+        assert_eq!(
+            league.draft(second, vec![pick]),
+            Err(FantasyDraftError::PickTaken(pick))
+        );
+        let car = FantasyPick::Car(Uuid::new_v4());
+        assert_eq!(
+            league.draft(second, vec![car, car]),
+            Err(FantasyDraftError::DuplicatePick(car))
+        );
+        assert_eq!(
+            league.draft(second, vec![car, car, car]),
+            Err(FantasyDraftError::TooManyPicks)
+        );
+
+        // Redrafting replaces the member's own picks
+        league.draft(first, vec![car]).unwrap();
+        league.draft(second, vec![pick]).unwrap();
+        assert_eq!(league.rosters.len(), 2);
+    }
+
+    #[test]
+    fn standings_add_up_points_of_finished_championship_races() {
+        let race = finished_race();
+        let mut other_race = finished_race();
+        other_race.status = RaceStatus::InProgress;
+        let mut league = FantasyLeague::new(
+            "League".to_string(),
+            Uuid::new_v4(),
+            &[race.uuid, other_race.uuid],
+            2,
+        );
+        let (winner, runner_up) = (&race.participants[0], &race.participants[1]);
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+        league
+            .draft(first, vec![FantasyPick::Player(winner.player_uuid)])
+            .unwrap();
+        league
+            .draft(
+                second,
+                vec![
+                    FantasyPick::Car(runner_up.car_uuid),
+                    FantasyPick::Player(other_race.participants[0].player_uuid),
+                ],
+            )
+            .unwrap();
+
+        let standings = league.standings(&[race.clone(), other_race]);
+
+        assert_eq!(
+            standings,
+            vec![
+                FantasyStanding {
+                    owner_uuid: first,
+                    points: FANTASY_POSITION_POINTS[0],
+                    races_scored: 1,
+                },
+                FantasyStanding {
+                    owner_uuid: second,
+                    points: FANTASY_POSITION_POINTS[1],
+                    races_scored: 1,
+                },
+            ]
+        );
+    }
+}
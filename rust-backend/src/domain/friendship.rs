@@ -0,0 +1,185 @@
+use mongodb::bson::DateTime as BsonDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Where a friendship stands
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+pub enum FriendshipStatus {
+    /// Requested, waiting for the other player to accept
+    Pending,
+    Accepted,
+}
+
+/// Friendship between two players, or a request for one
+///
+/// There is at most one per pair of players, whoever asked first.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct Friendship {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub id: Option<mongodb::bson::oid::ObjectId>,
+    /// Both player UUIDs in ascending order, so each pair has a single key
+    pub pair: String,
+    #[serde(with = "uuid_as_string")]
+    #[schema(value_type = String, format = "uuid")]
+    pub requester_uuid: Uuid,
+    #[serde(with = "uuid_as_string")]
+    #[schema(value_type = String, format = "uuid")]
+    pub addressee_uuid: Uuid,
+    pub status: FriendshipStatus,
+    #[schema(value_type = String, format = "date-time")]
+    pub requested_at: BsonDateTime,
+    #[schema(value_type = Option<String>, format = "date-time")]
+    pub accepted_at: Option<BsonDateTime>,
+}
+
+impl Friendship {
+    /// Request from `requester_uuid` to befriend `addressee_uuid`
+    #[must_use]
+    pub fn request(requester_uuid: Uuid, addressee_uuid: Uuid) -> Self {
+        Self {
+            id: None,
+            pair: Self::pair_key(requester_uuid, addressee_uuid),
+            requester_uuid,
+            addressee_uuid,
+            status: FriendshipStatus::Pending,
+            requested_at: BsonDateTime::now(),
+            accepted_at: None,
+        }
+    }
+
+    /// Key shared by both directions of a pair
+    #[must_use]
+    pub fn pair_key(a: Uuid, b: Uuid) -> String {
+        let (low, high) = if a <= b { (a, b) } else { (b, a) };
+        format!("{low}:{high}")
+    }
+
+    /// The player on the other side from `player_uuid`
+    #[must_use]
+    pub fn other(&self, player_uuid: Uuid) -> Uuid {
+        if self.requester_uuid == player_uuid {
+            self.addressee_uuid
+        } else {
+            self.requester_uuid
+        }
+    }
+
+    #[must_use]
+    pub fn is_accepted(&self) -> bool {
+        self.status == FriendshipStatus::Accepted
+    }
+}
+
+/// How much of another player a player keeps away
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+pub enum BlockKind {
+    /// Their chat messages are hidden from the player
+    Mute,
+    /// Neither sees the other's chat messages and neither can invite the other
+    Block,
+}
+
+/// One player muting or blocking another
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct PlayerBlock {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub id: Option<mongodb::bson::oid::ObjectId>,
+    #[serde(with = "uuid_as_string")]
+    #[schema(value_type = String, format = "uuid")]
+    pub blocker_uuid: Uuid,
+    #[serde(with = "uuid_as_string")]
+    #[schema(value_type = String, format = "uuid")]
+    pub blocked_uuid: Uuid,
+    pub kind: BlockKind,
+    #[schema(value_type = String, format = "date-time")]
+    pub created_at: BsonDateTime,
+}
+
+impl PlayerBlock {
+    /// `player_uuid` muting or blocking `blocked_uuid`
+    #[must_use]
+    pub fn new(player_uuid: Uuid, blocked_uuid: Uuid, kind: BlockKind) -> Self {
+        Self {
+            id: None,
+            blocker_uuid: player_uuid,
+            blocked_uuid,
+            kind,
+            created_at: BsonDateTime::now(),
+        }
+    }
+
+    /// Players whose chat messages `player_uuid` must not see, given the
+    /// blocks involving them
+    ///
+    /// Mutes only hide the muted player's messages from whoever muted them;
+    /// blocks work both ways.
+    pub fn hidden_from(player_uuid: Uuid, blocks: &[Self]) -> impl Iterator<Item = Uuid> + '_ {
+        blocks.iter().filter_map(move |block| {
+            if block.blocker_uuid == player_uuid {
+                Some(block.blocked_uuid)
+            } else if block.blocked_uuid == player_uuid && block.kind == BlockKind::Block {
+                Some(block.blocker_uuid)
+            } else {
+                None
+            }
+        })
+    }
+}
+
+mod uuid_as_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use uuid::Uuid;
+
+    pub fn serialize<S>(uuid: &Uuid, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&uuid.to_string())
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Uuid, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Uuid::parse_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn both_directions_share_the_pair_key() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        assert_eq!(Friendship::pair_key(a, b), Friendship::pair_key(b, a));
+
+        let request = Friendship::request(a, b);
+        assert_eq!(request.other(a), b);
+        assert_eq!(request.other(b), a);
+        assert!(!request.is_accepted());
+    }
+
+    #[test]
+    fn blocks_hide_both_ways_but_mutes_one_way() {
+        let (player, muted, blocked, rival) = (
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        );
+        let blocks = vec![
+            PlayerBlock::new(player, muted, BlockKind::Mute),
+            PlayerBlock::new(player, blocked, BlockKind::Block),
+            PlayerBlock::new(rival, player, BlockKind::Block),
+            PlayerBlock::new(Uuid::new_v4(), player, BlockKind::Mute),
+        ];
+
+        let hidden: Vec<Uuid> = PlayerBlock::hidden_from(player, &blocks).collect();
+        assert_eq!(hidden, vec![muted, blocked, rival]);
+    }
+}
@@ -0,0 +1,109 @@
+use mongodb::bson::DateTime as BsonDateTime;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+/// Prefix of guild keys, so leaked ones are easy to recognise
+const KEY_PREFIX: &str = "bdg_";
+
+/// API key a Discord guild's bot calls the integration endpoints with
+///
+/// Only a hash of the key is stored; the key itself is shown once, when it
+/// is issued.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GuildApiKey {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<mongodb::bson::oid::ObjectId>,
+    #[serde(with = "uuid_as_string")]
+    pub uuid: Uuid,
+    /// Discord guild (server) ID the key acts for
+    pub guild_id: String,
+    /// SHA-256 of the key, in hex
+    pub key_hash: String,
+    /// Admin who issued the key
+    #[serde(with = "uuid_as_string")]
+    pub created_by: Uuid,
+    pub created_at: BsonDateTime,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revoked_at: Option<BsonDateTime>,
+}
+
+impl GuildApiKey {
+    /// A new key for `guild_id`, with the key to hand to the guild's bot
+    #[must_use]
+    pub fn issue(guild_id: String, created_by: Uuid) -> (Self, String) {
+        use rand::RngCore;
+
+        let mut secret = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut secret);
+        let key = format!("{KEY_PREFIX}{}", hex::encode(secret));
+        let api_key = Self {
+            id: None,
+            uuid: Uuid::new_v4(),
+            guild_id,
+            key_hash: Self::hash(&key),
+            created_by,
+            created_at: BsonDateTime::now(),
+            revoked_at: None,
+        };
+        (api_key, key)
+    }
+
+    /// Hash a key is stored and looked up by
+    #[must_use]
+    pub fn hash(key: &str) -> String {
+        hex::encode(Sha256::digest(key.as_bytes()))
+    }
+}
+
+/// Race created through a guild's bot, which only that guild can follow
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GuildRace {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<mongodb::bson::oid::ObjectId>,
+    pub guild_id: String,
+    #[serde(with = "uuid_as_string")]
+    pub race_uuid: Uuid,
+    /// Channel the race was announced in, if the bot gave it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel_id: Option<String>,
+    pub created_at: BsonDateTime,
+}
+
+mod uuid_as_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use uuid::Uuid;
+
+    pub fn serialize<S>(uuid: &Uuid, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&uuid.to_string())
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Uuid, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Uuid::parse_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn issued_keys_are_stored_by_hash_only() {
+        let admin = Uuid::new_v4();
+        let (api_key, key) = GuildApiKey::issue("81384788765712384".to_string(), admin);
+
+        assert!(key.starts_with(KEY_PREFIX));
+        assert_eq!(api_key.key_hash, GuildApiKey::hash(&key));
+        assert_ne!(api_key.key_hash, key);
+        let (other, other_key) = GuildApiKey::issue("81384788765712384".to_string(), admin);
+        assert_ne!(other_key, key);
+        assert_ne!(other.key_hash, api_key.key_hash);
+    }
+}
@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::{Body, Car, Engine, Pilot, Player, Race};
+
+/// Owned car and the active race it is entered in, if any
+#[derive(Debug, Serialize, ToSchema)]
+pub struct InventoryCar {
+    pub car: Car,
+    /// Active race the car is locked in
+    pub race_uuid: Option<String>,
+}
+
+/// Owned pilot, the car it is assigned to and the active race it drives in
+#[derive(Debug, Serialize, ToSchema)]
+pub struct InventoryPilot {
+    pub pilot: Pilot,
+    pub car_uuid: Option<String>,
+    /// Active race the pilot is locked in
+    pub race_uuid: Option<String>,
+}
+
+/// Owned engine, the car it is fitted to and that car's active race
+#[derive(Debug, Serialize, ToSchema)]
+pub struct InventoryEngine {
+    pub engine: Engine,
+    pub car_uuid: Option<String>,
+    /// Active race the engine is locked in through its car
+    pub race_uuid: Option<String>,
+}
+
+/// Owned body, the car it is fitted to and that car's active race
+#[derive(Debug, Serialize, ToSchema)]
+pub struct InventoryBody {
+    pub body: Body,
+    pub car_uuid: Option<String>,
+    /// Active race the body is locked in through its car
+    pub race_uuid: Option<String>,
+}
+
+/// Everything a player owns with where each item is in use
+///
+/// Items are locked while the race they are entered in, directly or through
+/// their car, has not finished.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PlayerInventory {
+    pub player_uuid: String,
+    pub credits: u64,
+    pub cars: Vec<InventoryCar>,
+    pub pilots: Vec<InventoryPilot>,
+    pub engines: Vec<InventoryEngine>,
+    pub bodies: Vec<InventoryBody>,
+}
+
+impl PlayerInventory {
+    /// Inventory of `player`, locking items entered in `active_races`
+    #[must_use]
+    pub fn new(player: &Player, active_races: &[Race]) -> Self {
+        let mut car_races = HashMap::new();
+        let mut pilot_races = HashMap::new();
+        for race in active_races {
+            for participant in race
+                .participants
+                .iter()
+                .filter(|p| p.player_uuid == player.uuid)
+            {
+                car_races.insert(participant.car_uuid, race.uuid.to_string());
+                pilot_races.insert(participant.pilot_uuid, race.uuid.to_string());
+            }
+        }
+
+        let fitted_to = |uuid: Uuid, slot: fn(&Car) -> Option<Uuid>| {
+            player.cars.iter().find(|car| slot(car) == Some(uuid))
+        };
+        let car_of_pilot = |uuid: Uuid| {
+            player
+                .cars
+                .iter()
+                .find(|car| car.pilot_uuids.contains(&uuid))
+        };
+        let race_of = |car: Option<&Car>| car.and_then(|car| car_races.get(&car.uuid).cloned());
+
+        let engines = player
+            .engines
+            .iter()
+            .map(|engine| {
+                let car = fitted_to(engine.uuid, |car| car.engine_uuid);
+                InventoryEngine {
+                    engine: engine.clone(),
+                    car_uuid: car.map(|car| car.uuid.to_string()),
+                    race_uuid: race_of(car),
+                }
+            })
+            .collect();
+        let bodies = player
+            .bodies
+            .iter()
+            .map(|body| {
+                let car = fitted_to(body.uuid, |car| car.body_uuid);
+                InventoryBody {
+                    body: body.clone(),
+                    car_uuid: car.map(|car| car.uuid.to_string()),
+                    race_uuid: race_of(car),
+                }
+            })
+            .collect();
+        let pilots = player
+            .pilots
+            .iter()
+            .map(|pilot| InventoryPilot {
+                pilot: pilot.clone(),
+                car_uuid: car_of_pilot(pilot.uuid).map(|car| car.uuid.to_string()),
+                race_uuid: pilot_races.get(&pilot.uuid).cloned(),
+            })
+            .collect();
+        let cars = player
+            .cars
+            .iter()
+            .map(|car| InventoryCar {
+                car: car.clone(),
+                race_uuid: car_races.get(&car.uuid).cloned(),
+            })
+            .collect();
+
+        Self {
+            player_uuid: player.uuid.to_string(),
+            credits: player.credits,
+            cars,
+            pilots,
+            engines,
+            bodies,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Email, Password, Sector, SectorType, TeamName, Track};
+    use crate::services::starter_pack::StarterPack;
+
+    fn race() -> Race {
+        let sector = |id, sector_type| Sector {
+            id,
+            name: format!("Sector {id}"),
+            min_value: id * 10,
+            max_value: id * 10 + 10,
+            slot_capacity: None,
+            sector_type,
+            capacity_schedule: Vec::new(),
+            modifiers: None,
+        };
+        let sectors = vec![sector(0, SectorType::Start), sector(1, SectorType::Finish)];
+        let track = Track::new("Track".to_string(), sectors).unwrap();
+        Race::new("Locked".to_string(), track, 3)
+    }
+
+    #[test]
+    fn items_entered_in_an_active_race_are_locked() {
+        let player = StarterPack::new()
+            .unwrap()
+            .grant(
+                Email::parse("inventory@example.com").unwrap(),
+                Password::new("InventoryPass123".to_string())
+                    .unwrap()
+                    .hash()
+                    .unwrap(),
+                TeamName::parse("Inventory").unwrap(),
+            )
+            .unwrap();
+        let racing_car = player.cars[0].clone();
+        let racing_pilot = racing_car.pilot_uuids[0];
+        let mut race = race();
+        race.add_participant(player.uuid, racing_car.uuid, racing_pilot)
+            .unwrap();
+        let race_uuid = race.uuid.to_string();
+
+        let inventory = PlayerInventory::new(&player, &[race]);
+
+        for car in &inventory.cars {
+            let expected = (car.car.uuid == racing_car.uuid).then(|| race_uuid.clone());
+            assert_eq!(car.race_uuid, expected);
+        }
+        for pilot in &inventory.pilots {
+            assert!(pilot.car_uuid.is_some());
+            let expected = (pilot.pilot.uuid == racing_pilot).then(|| race_uuid.clone());
+            assert_eq!(pilot.race_uuid, expected);
+        }
+        let racing_car_uuid = Some(racing_car.uuid.to_string());
+        for engine in &inventory.engines {
+            let locked = engine.car_uuid == racing_car_uuid;
+            assert_eq!(engine.race_uuid.is_some(), locked);
+        }
+        for body in &inventory.bodies {
+            let locked = body.car_uuid == racing_car_uuid;
+            assert_eq!(body.race_uuid.is_some(), locked);
+        }
+    }
+}
@@ -1,17 +1,66 @@
+mod anchor;
+mod api_token;
 mod auth;
+pub mod award;
 mod body;
+mod boost_analytics;
 pub mod boost_hand_manager;
+mod bot;
 mod car;
+mod cheat_flag;
+pub mod commentary;
+mod crafting;
 mod engine;
+pub mod fantasy;
+mod friendship;
+mod guild_key;
+mod inventory;
+mod notification;
+mod payout;
+pub mod performance_model;
 mod pilot;
 mod player;
+pub mod prediction;
 mod race;
+mod race_event;
+mod race_snapshot;
+mod race_stats;
+mod report;
+pub mod rules;
+mod time_trial;
+mod track_design;
+mod track_template;
+mod trade;
+mod webhook;
 
+pub use anchor::*;
+pub use api_token::*;
 pub use auth::*;
+pub use award::{AwardKind, RaceAward};
 pub use body::*;
+pub use boost_analytics::*;
 pub use boost_hand_manager::*;
+pub use bot::*;
 pub use car::*;
+pub use cheat_flag::*;
+pub use crafting::*;
 pub use engine::*;
+pub use friendship::*;
+pub use guild_key::*;
+pub use inventory::*;
+pub use notification::*;
+pub use payout::*;
+pub use performance_model::*;
 pub use pilot::*;
 pub use player::*;
 pub use race::*;
+pub use race_event::*;
+pub use race_snapshot::*;
+pub use race_stats::*;
+pub use report::*;
+pub use rules::{GameRules, CURRENT_RULES_VERSION, LEGACY_RULES_VERSION};
+pub use time_trial::*;
+pub use track_design::*;
+pub use track_template::*;
+pub use trade::*;
+pub use webhook::*;
@@ -0,0 +1,341 @@
+use mongodb::bson::DateTime as BsonDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::race::{Race, RaceStatus};
+
+/// What a notification is about
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+pub enum NotificationKind {
+    /// A race the player opened was cancelled
+    RaceCancelled,
+    /// A new lap of one of the player's races waits for their action
+    YourTurn,
+    /// The player still has not acted on a lap some time after it opened
+    TurnReminder,
+    /// A race the player took part in finished
+    RaceFinished,
+    /// The creator of a waiting race asked the player to join it
+    RaceInvitation,
+}
+
+impl NotificationKind {
+    /// Headline shown on devices and in email subjects
+    #[must_use]
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::RaceCancelled => "Race cancelled",
+            Self::YourTurn => "Your turn",
+            Self::TurnReminder => "Still your turn",
+            Self::RaceFinished => "Race finished",
+            Self::RaceInvitation => "Race invitation",
+        }
+    }
+
+    /// Whether players can also get this kind by email; turns open too often
+    /// for that, so only their reminders are mailed
+    #[must_use]
+    pub fn is_emailed(self) -> bool {
+        matches!(
+            self,
+            Self::TurnReminder | Self::RaceFinished | Self::RaceInvitation
+        )
+    }
+}
+
+/// Message left for a player about one of their races
+///
+/// Stored in the player's inbox and, while `push_pending`, sent to their
+/// registered devices by the push notifier.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct Notification {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub id: Option<mongodb::bson::oid::ObjectId>,
+    /// Notifications stored before it existed get a random one when read
+    #[serde(with = "uuid_as_string", default = "Uuid::new_v4")]
+    pub uuid: Uuid,
+    #[serde(with = "uuid_as_string")]
+    pub player_uuid: Uuid,
+    pub kind: NotificationKind,
+    #[serde(with = "uuid_as_string")]
+    pub race_uuid: Uuid,
+    /// Lap a turn notification is about
+    #[serde(default)]
+    pub lap: Option<u32>,
+    pub message: String,
+    #[schema(value_type = String, format = "date-time")]
+    pub created_at: BsonDateTime,
+    #[serde(default)]
+    pub push_pending: bool,
+    /// Whether a `YourTurn` notification was followed by a reminder
+    #[serde(default)]
+    pub reminder_sent: bool,
+    /// Waiting for the next email digest of the player
+    #[serde(default)]
+    pub email_pending: bool,
+}
+
+impl Notification {
+    fn new(player_uuid: Uuid, kind: NotificationKind, race: &Race, message: String) -> Self {
+        Self {
+            id: None,
+            uuid: Uuid::new_v4(),
+            player_uuid,
+            kind,
+            race_uuid: race.uuid,
+            lap: None,
+            message,
+            created_at: BsonDateTime::now(),
+            push_pending: true,
+            reminder_sent: false,
+            email_pending: kind.is_emailed(),
+        }
+    }
+
+    #[must_use]
+    pub fn race_cancelled(player_uuid: Uuid, race: &Race, reason: &str) -> Self {
+        let message = format!("Race \"{}\" was cancelled: {reason}", race.name);
+        Self::new(player_uuid, NotificationKind::RaceCancelled, race, message)
+    }
+
+    #[must_use]
+    pub fn your_turn(player_uuid: Uuid, race: &Race) -> Self {
+        let message = format!(
+            "Lap {} of {} in \"{}\" is waiting for your move",
+            race.current_lap, race.total_laps, race.name
+        );
+        Self {
+            lap: Some(race.current_lap),
+            ..Self::new(player_uuid, NotificationKind::YourTurn, race, message)
+        }
+    }
+
+    #[must_use]
+    pub fn turn_reminder(player_uuid: Uuid, race: &Race, waited_minutes: u64) -> Self {
+        let message = format!(
+            "\"{}\" has been waiting {waited_minutes} minutes for your move on lap {}",
+            race.name, race.current_lap
+        );
+        Self {
+            lap: Some(race.current_lap),
+            ..Self::new(player_uuid, NotificationKind::TurnReminder, race, message)
+        }
+    }
+
+    #[must_use]
+    pub fn race_finished(player_uuid: Uuid, race: &Race, position: Option<u32>) -> Self {
+        let message = match position {
+            Some(position) => format!("\"{}\" finished: you placed #{position}", race.name),
+            None => format!("\"{}\" finished", race.name),
+        };
+        Self::new(player_uuid, NotificationKind::RaceFinished, race, message)
+    }
+
+    #[must_use]
+    pub fn race_invitation(player_uuid: Uuid, race: &Race, inviter: &str) -> Self {
+        let message = format!("{inviter} invited you to race \"{}\"", race.name);
+        Self::new(player_uuid, NotificationKind::RaceInvitation, race, message)
+    }
+
+    /// Notifications for the human participants when `before` becomes
+    /// `after`: their turn when a lap opens, their result when the race ends
+    #[must_use]
+    pub fn between(before: &Race, after: &Race) -> Vec<Self> {
+        let humans = after.participants.iter().filter(|p| p.bot.is_none());
+        if after.status == RaceStatus::InProgress
+            && (before.status != RaceStatus::InProgress || before.current_lap != after.current_lap)
+        {
+            humans
+                .filter(|p| !p.is_finished)
+                .map(|p| Self::your_turn(p.player_uuid, after))
+                .collect()
+        } else if before.status != RaceStatus::Finished && after.status == RaceStatus::Finished {
+            humans
+                .map(|p| Self::race_finished(p.player_uuid, after, p.finish_position))
+                .collect()
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Which notifications a player also receives by email
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+pub struct NotificationPreferences {
+    #[serde(default = "enabled")]
+    pub race_invitations: bool,
+    #[serde(default = "enabled")]
+    pub turn_reminders: bool,
+    /// Results of finished races
+    #[serde(default = "enabled")]
+    pub race_results: bool,
+}
+
+impl Default for NotificationPreferences {
+    fn default() -> Self {
+        Self {
+            race_invitations: true,
+            turn_reminders: true,
+            race_results: true,
+        }
+    }
+}
+
+impl NotificationPreferences {
+    #[must_use]
+    pub fn emails(&self, kind: NotificationKind) -> bool {
+        match kind {
+            NotificationKind::RaceInvitation => self.race_invitations,
+            NotificationKind::TurnReminder => self.turn_reminders,
+            NotificationKind::RaceFinished => self.race_results,
+            NotificationKind::RaceCancelled | NotificationKind::YourTurn => false,
+        }
+    }
+}
+
+fn enabled() -> bool {
+    true
+}
+
+/// Service delivering push notifications to a device
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+pub enum DevicePlatform {
+    /// Firebase Cloud Messaging, for Android and web clients
+    Fcm,
+    /// Apple Push Notification service
+    Apns,
+}
+
+/// Device a player receives push notifications on
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct DeviceToken {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub id: Option<mongodb::bson::oid::ObjectId>,
+    #[serde(with = "uuid_as_string")]
+    pub player_uuid: Uuid,
+    pub platform: DevicePlatform,
+    /// Registration token issued to the app by the platform
+    pub token: String,
+    #[schema(value_type = String, format = "date-time")]
+    pub registered_at: BsonDateTime,
+}
+
+impl DeviceToken {
+    #[must_use]
+    pub fn new(player_uuid: Uuid, platform: DevicePlatform, token: String) -> Self {
+        Self {
+            id: None,
+            player_uuid,
+            platform,
+            token,
+            registered_at: BsonDateTime::now(),
+        }
+    }
+}
+
+mod uuid_as_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use uuid::Uuid;
+
+    pub fn serialize<S>(uuid: &Uuid, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&uuid.to_string())
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Uuid, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Uuid::parse_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Sector, SectorType, Track};
+
+    fn race_in_progress(players: &[Uuid]) -> Race {
+        let sector = |id, sector_type| Sector {
+            id,
+            name: format!("Sector {id}"),
+            min_value: id * 10,
+            max_value: id * 10 + 10,
+            slot_capacity: None,
+            sector_type,
+            capacity_schedule: Vec::new(),
+            modifiers: None,
+        };
+        let sectors = vec![sector(0, SectorType::Start), sector(1, SectorType::Finish)];
+        let track = Track::new("Track".to_string(), sectors).unwrap();
+        let mut race = Race::new("Notified".to_string(), track, 3);
+        for player_uuid in players {
+            race.add_participant(*player_uuid, Uuid::new_v4(), Uuid::new_v4())
+                .unwrap();
+        }
+        race.status = RaceStatus::InProgress;
+        race.current_lap = 1;
+        race
+    }
+
+    #[test]
+    fn opening_a_lap_notifies_players_still_racing() {
+        let (racing, finished) = (Uuid::new_v4(), Uuid::new_v4());
+        let before = race_in_progress(&[racing, finished]);
+        let mut after = before.clone();
+        after.current_lap = 2;
+        after.participants[1].is_finished = true;
+
+        let notifications = Notification::between(&before, &after);
+
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].player_uuid, racing);
+        assert_eq!(notifications[0].kind, NotificationKind::YourTurn);
+        assert_eq!(notifications[0].lap, Some(2));
+        assert!(notifications[0].push_pending);
+    }
+
+    #[test]
+    fn finishing_notifies_every_player_of_their_position() {
+        let players = [Uuid::new_v4(), Uuid::new_v4()];
+        let before = race_in_progress(&players);
+        let mut after = before.clone();
+        after.status = RaceStatus::Finished;
+        after.participants[0].finish_position = Some(2);
+        after.participants[1].finish_position = Some(1);
+
+        let notifications = Notification::between(&before, &after);
+
+        assert_eq!(notifications.len(), 2);
+        assert!(notifications
+            .iter()
+            .all(|n| n.kind == NotificationKind::RaceFinished));
+        assert!(notifications[0].message.contains("#2"));
+    }
+
+    #[test]
+    fn preferences_choose_the_emailed_kinds() {
+        let preferences = NotificationPreferences {
+            turn_reminders: false,
+            ..NotificationPreferences::default()
+        };
+
+        assert!(preferences.emails(NotificationKind::RaceFinished));
+        assert!(preferences.emails(NotificationKind::RaceInvitation));
+        assert!(!preferences.emails(NotificationKind::TurnReminder));
+        assert!(!preferences.emails(NotificationKind::YourTurn));
+    }
+
+    #[test]
+    fn saving_the_same_lap_notifies_nobody() {
+        let before = race_in_progress(&[Uuid::new_v4()]);
+
+        assert!(Notification::between(&before, &before.clone()).is_empty());
+    }
+}
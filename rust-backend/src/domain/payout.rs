@@ -0,0 +1,214 @@
+use mongodb::bson::DateTime as BsonDateTime;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::Race;
+
+/// On-chain prizes of a race, paid once it finishes
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+pub struct PrizePool {
+    /// SPL token mint the prizes are paid in; SOL when omitted
+    #[serde(default)]
+    pub mint: Option<String>,
+    /// Prize of each finishing position, first place first, in the token's
+    /// smallest unit (lamports for SOL)
+    pub amounts: Vec<u64>,
+}
+
+impl PrizePool {
+    /// Amounts must be positive and fit a BSON integer
+    pub fn validate(&self) -> Result<(), String> {
+        if self.amounts.is_empty() {
+            return Err("A prize pool needs at least one prize".to_string());
+        }
+        if self
+            .amounts
+            .iter()
+            .any(|&amount| amount == 0 || i64::try_from(amount).is_err())
+        {
+            return Err(format!(
+                "Prizes must be between 1 and {} base units",
+                i64::MAX
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Where a payout stands
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+pub enum PayoutStatus {
+    /// Waiting for its first or next transfer attempt
+    Pending,
+    Paid,
+    /// Gave up after the last allowed attempt, or the player has no wallet
+    Failed,
+}
+
+/// Prize owed to one participant of a finished race
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct RacePayout {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub id: Option<mongodb::bson::oid::ObjectId>,
+    #[serde(with = "uuid_as_string")]
+    #[schema(value_type = String, format = "uuid")]
+    pub uuid: Uuid,
+    #[serde(with = "uuid_as_string")]
+    #[schema(value_type = String, format = "uuid")]
+    pub race_uuid: Uuid,
+    #[serde(with = "uuid_as_string")]
+    #[schema(value_type = String, format = "uuid")]
+    pub player_uuid: Uuid,
+    pub finish_position: u32,
+    /// Wallet the prize goes to
+    pub recipient: Option<String>,
+    pub mint: Option<String>,
+    pub amount: u64,
+    pub status: PayoutStatus,
+    pub attempts: u32,
+    #[schema(value_type = String, format = "date-time")]
+    pub next_attempt_at: BsonDateTime,
+    pub last_error: Option<String>,
+    /// Solana transfer, once paid
+    pub transaction_signature: Option<String>,
+    #[schema(value_type = String, format = "date-time")]
+    pub created_at: BsonDateTime,
+    #[schema(value_type = Option<String>, format = "date-time")]
+    pub paid_at: Option<BsonDateTime>,
+}
+
+/// Payouts owed for a finished race with a prize pool
+///
+/// Each finisher placed within the pool gets the prize of their position;
+/// bots and ghosts are skipped. A player without a wallet in `wallets` gets
+/// a failed payout saying so, so the prize shows as unpaid.
+#[must_use]
+pub fn race_payouts<S: BuildHasher>(
+    race: &Race,
+    prize_pool: &PrizePool,
+    wallets: &HashMap<Uuid, String, S>,
+) -> Vec<RacePayout> {
+    let now = BsonDateTime::now();
+    race.participants
+        .iter()
+        .filter(|p| p.bot.is_none() && p.ghost.is_none())
+        .filter_map(|p| {
+            let position = p.finish_position?;
+            let amount = *prize_pool
+                .amounts
+                .get(usize::try_from(position).ok()?.checked_sub(1)?)?;
+            let recipient = wallets.get(&p.player_uuid).cloned();
+            let (status, last_error) = match recipient {
+                Some(_) => (PayoutStatus::Pending, None),
+                None => (
+                    PayoutStatus::Failed,
+                    Some("Player has no wallet address".to_string()),
+                ),
+            };
+            Some(RacePayout {
+                id: None,
+                uuid: Uuid::new_v4(),
+                race_uuid: race.uuid,
+                player_uuid: p.player_uuid,
+                finish_position: position,
+                recipient,
+                mint: prize_pool.mint.clone(),
+                amount,
+                status,
+                attempts: 0,
+                next_attempt_at: now,
+                last_error,
+                transaction_signature: None,
+                created_at: now,
+                paid_at: None,
+            })
+        })
+        .collect()
+}
+
+mod uuid_as_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use uuid::Uuid;
+
+    pub fn serialize<S>(uuid: &Uuid, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&uuid.to_string())
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Uuid, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Uuid::parse_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Sector, SectorType, Track};
+
+    fn finished_race(players: &[Uuid]) -> Race {
+        let sector = |id, sector_type| Sector {
+            id,
+            name: format!("Sector {id}"),
+            min_value: id * 10,
+            max_value: id * 10 + 10,
+            slot_capacity: None,
+            sector_type,
+            capacity_schedule: Vec::new(),
+            modifiers: None,
+        };
+        let sectors = vec![sector(0, SectorType::Start), sector(1, SectorType::Finish)];
+        let track = Track::new("Track".to_string(), sectors).unwrap();
+        let mut race = Race::new("Prize race".to_string(), track, 1);
+        for (position, &player) in (1..).zip(players) {
+            race.add_participant(player, Uuid::new_v4(), Uuid::new_v4())
+                .unwrap();
+            let participant = race.participants.last_mut().unwrap();
+            participant.is_finished = true;
+            participant.finish_position = Some(position);
+        }
+        race
+    }
+
+    #[test]
+    fn prizes_go_to_placed_finishers_with_a_wallet() {
+        let players = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+        let race = finished_race(&players);
+        let pool = PrizePool {
+            mint: None,
+            amounts: vec![1_000, 500],
+        };
+        let wallets = HashMap::from([(players[0], "Wallet1".to_string())]);
+
+        let payouts = race_payouts(&race, &pool, &wallets);
+
+        assert_eq!(payouts.len(), 2);
+        assert_eq!(payouts[0].player_uuid, players[0]);
+        assert_eq!(payouts[0].amount, 1_000);
+        assert_eq!(payouts[0].status, PayoutStatus::Pending);
+        assert_eq!(payouts[1].player_uuid, players[1]);
+        assert_eq!(payouts[1].status, PayoutStatus::Failed);
+    }
+
+    #[test]
+    fn prize_pools_reject_empty_or_oversized_prizes() {
+        let pool = |amounts: Vec<u64>| PrizePool {
+            mint: None,
+            amounts,
+        };
+
+        assert!(pool(vec![100]).validate().is_ok());
+        assert!(pool(Vec::new()).validate().is_err());
+        assert!(pool(vec![100, 0]).validate().is_err());
+        assert!(pool(vec![u64::MAX]).validate().is_err());
+    }
+}
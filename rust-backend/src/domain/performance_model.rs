@@ -0,0 +1,201 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::race::Sector;
+
+/// Formula turning a car's base value and a boost card into a final lap value
+///
+/// The base value is first capped by the sector ceiling, then the boost is applied
+/// on top of the capped value. Implementations only need to describe how the boost
+/// is applied; capping can be overridden when a model needs a different ceiling rule.
+pub trait PerformanceModel: Send + Sync {
+    /// Identifier reported to clients alongside previews
+    fn name(&self) -> &'static str;
+
+    /// Apply the sector performance ceiling to the base value
+    fn cap_base_value(&self, base_value: u32, sector: &Sector) -> u32 {
+        std::cmp::min(base_value, sector.max_value)
+    }
+
+    /// Apply a boost card to an already capped base value
+    fn apply_boost(&self, capped_base_value: u32, boost_value: u32) -> u32;
+
+    /// Full calculation: cap the base value, then apply the boost
+    fn final_value(&self, base_value: u32, sector: &Sector, boost_value: u32) -> u32 {
+        self.apply_boost(self.cap_base_value(base_value, sector), boost_value)
+    }
+}
+
+/// Boost value is added directly to the capped base value
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AdditiveModel;
+
+impl PerformanceModel for AdditiveModel {
+    fn name(&self) -> &'static str {
+        "Additive"
+    }
+
+    fn apply_boost(&self, capped_base_value: u32, boost_value: u32) -> u32 {
+        capped_base_value + boost_value
+    }
+}
+
+/// Each boost step scales the capped base value by a fixed percentage
+/// `final = capped * (1 + boost * boost_step)`
+#[derive(Debug, Clone, Copy)]
+pub struct MultiplicativeModel {
+    pub boost_step: f64,
+}
+
+impl Default for MultiplicativeModel {
+    fn default() -> Self {
+        Self { boost_step: 0.08 }
+    }
+}
+
+impl PerformanceModel for MultiplicativeModel {
+    fn name(&self) -> &'static str {
+        "Multiplicative"
+    }
+
+    fn apply_boost(&self, capped_base_value: u32, boost_value: u32) -> u32 {
+        let multiplier = 1.0 + f64::from(boost_value) * self.boost_step;
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let final_value = (f64::from(capped_base_value) * multiplier).round() as u32;
+        final_value
+    }
+}
+
+/// Multiplicative boost where every extra step is worth a fraction of the previous one
+/// With the defaults the bonus is 8%, 12%, 14% and 15% for boost cards 1 to 4
+#[derive(Debug, Clone, Copy)]
+pub struct DiminishingReturnsModel {
+    pub first_step: f64,
+    pub decay: f64,
+}
+
+impl Default for DiminishingReturnsModel {
+    fn default() -> Self {
+        Self {
+            first_step: 0.08,
+            decay: 0.5,
+        }
+    }
+}
+
+impl PerformanceModel for DiminishingReturnsModel {
+    fn name(&self) -> &'static str {
+        "DiminishingReturns"
+    }
+
+    fn apply_boost(&self, capped_base_value: u32, boost_value: u32) -> u32 {
+        let mut bonus = 0.0;
+        let mut step = self.first_step;
+        for _ in 0..boost_value {
+            bonus += step;
+            step *= self.decay;
+        }
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let final_value = (f64::from(capped_base_value) * (1.0 + bonus)).round() as u32;
+        final_value
+    }
+}
+
+static ADDITIVE: AdditiveModel = AdditiveModel;
+static MULTIPLICATIVE: MultiplicativeModel = MultiplicativeModel { boost_step: 0.08 };
+static DIMINISHING_RETURNS: DiminishingReturnsModel = DiminishingReturnsModel {
+    first_step: 0.08,
+    decay: 0.5,
+};
+
+/// Performance formula selected in a race's configuration
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, ToSchema)]
+pub enum PerformanceModelKind {
+    /// `final = capped + boost` (formula used by races created before models were configurable)
+    #[default]
+    Additive,
+    /// `final = capped * (1 + boost * 0.08)`
+    Multiplicative,
+    /// Multiplicative with each extra boost step worth half the previous one
+    DiminishingReturns,
+}
+
+impl PerformanceModelKind {
+    /// Get the model implementation for this kind
+    #[must_use]
+    pub fn model(self) -> &'static dyn PerformanceModel {
+        match self {
+            PerformanceModelKind::Additive => &ADDITIVE,
+            PerformanceModelKind::Multiplicative => &MULTIPLICATIVE,
+            PerformanceModelKind::DiminishingReturns => &DIMINISHING_RETURNS,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::race::SectorType;
+
+    fn create_test_sector() -> Sector {
+        Sector {
+            id: 1,
+            name: "Test Sector".to_string(),
+            min_value: 10,
+            max_value: 20,
+            slot_capacity: Some(3),
+            sector_type: SectorType::Straight,
+            capacity_schedule: Vec::new(),
+            modifiers: None,
+        }
+    }
+
+    #[test]
+    fn test_all_models_cap_base_value_at_sector_ceiling() {
+        let sector = create_test_sector();
+        for kind in [
+            PerformanceModelKind::Additive,
+            PerformanceModelKind::Multiplicative,
+            PerformanceModelKind::DiminishingReturns,
+        ] {
+            let model = kind.model();
+            assert_eq!(model.cap_base_value(25, &sector), 20);
+            assert_eq!(model.final_value(25, &sector, 0), 20, "{}", model.name());
+        }
+    }
+
+    #[test]
+    fn test_additive_model() {
+        let sector = create_test_sector();
+        let model = PerformanceModelKind::Additive.model();
+        assert_eq!(model.final_value(15, &sector, 4), 19);
+        assert_eq!(model.final_value(30, &sector, 3), 23);
+    }
+
+    #[test]
+    fn test_multiplicative_model() {
+        let sector = create_test_sector();
+        let model = PerformanceModelKind::Multiplicative.model();
+        // 15 * 1.16 = 17.4
+        assert_eq!(model.final_value(15, &sector, 2), 17);
+        // 20 * 1.32 = 26.4
+        assert_eq!(model.final_value(25, &sector, 4), 26);
+    }
+
+    #[test]
+    fn test_diminishing_returns_model() {
+        let model = PerformanceModelKind::DiminishingReturns.model();
+        // Bonus of 8%, 12%, 14%, 15% on a base of 100
+        let values: Vec<u32> = (0..=4).map(|boost| model.apply_boost(100, boost)).collect();
+        assert_eq!(values, vec![100, 108, 112, 114, 115]);
+    }
+
+    #[test]
+    fn test_default_kind_is_additive() {
+        assert_eq!(
+            PerformanceModelKind::default(),
+            PerformanceModelKind::Additive
+        );
+        assert_eq!(PerformanceModelKind::default().model().name(), "Additive");
+    }
+}
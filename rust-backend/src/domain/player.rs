@@ -1,10 +1,12 @@
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use unicode_segmentation::UnicodeSegmentation;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
-use super::{Body, Car, Engine, HashedPassword, Password, Pilot, UserRole};
+use super::{Body, Car, Engine, HashedPassword, OAuthIdentity, Password, Pilot, UserRole};
 
 #[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct Player {
@@ -24,6 +26,24 @@ pub struct Player {
     pub pilots: Vec<Pilot>,
     pub engines: Vec<Engine>,
     pub bodies: Vec<Body>,
+    /// In-game currency spent on crafting
+    #[serde(default)]
+    pub credits: u64,
+    /// Drops in a row below each card pack's pity rarity, by pack id
+    #[serde(default)]
+    pub pack_pity: HashMap<String, u32>,
+    /// Trade escrow moves already applied to the player, so a settlement cut
+    /// short can be resumed without moving items twice
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trade_steps: Vec<String>,
+    /// When the player asked for their account to be erased; it is erased
+    /// once the grace period is over, unless they change their mind
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>, format = "date-time")]
+    pub erasure_requested_at: Option<mongodb::bson::DateTime>,
+    /// Provider accounts the player can sign in with
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub oauth_identities: Vec<OAuthIdentity>,
     #[schema(value_type = String, format = "date-time")]
     pub created_at: DateTime<Utc>,
     #[schema(value_type = String, format = "date-time")]
@@ -80,6 +100,11 @@ impl Player {
             pilots,
             engines: vec![],
             bodies: vec![],
+            credits: 0,
+            pack_pity: HashMap::new(),
+            trade_steps: Vec::new(),
+            erasure_requested_at: None,
+            oauth_identities: Vec::new(),
             created_at: now,
             updated_at: now,
         })
@@ -125,6 +150,11 @@ impl Player {
             pilots,
             engines,
             bodies,
+            credits: 0,
+            pack_pity: HashMap::new(),
+            trade_steps: Vec::new(),
+            erasure_requested_at: None,
+            oauth_identities: Vec::new(),
             created_at: now,
             updated_at: now,
         })
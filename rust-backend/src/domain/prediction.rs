@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+use mongodb::bson::DateTime as BsonDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::race::{Race, RaceStatus};
+use super::race_event::RaceChange;
+
+/// Points for naming the top mover of a lap
+pub const LAP_PREDICTION_POINTS: u32 = 1;
+/// Points for naming the winner of the race
+pub const WINNER_PREDICTION_POINTS: u32 = 5;
+
+/// Spectator's guess at the top mover of a lap or the winner of a race
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct SpectatorPrediction {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub id: Option<mongodb::bson::oid::ObjectId>,
+    #[serde(with = "uuid_as_string")]
+    #[schema(value_type = String, format = "uuid")]
+    pub race_uuid: Uuid,
+    /// Identifier the spectator's client generated, as sent with heartbeats
+    #[serde(with = "uuid_as_string")]
+    #[schema(value_type = String, format = "uuid")]
+    pub viewer_id: Uuid,
+    /// Lap whose top mover is predicted; the race winner when omitted
+    pub lap: Option<u32>,
+    /// Participant predicted
+    #[serde(with = "uuid_as_string")]
+    #[schema(value_type = String, format = "uuid")]
+    pub player_uuid: Uuid,
+    #[schema(value_type = String, format = "date-time")]
+    pub created_at: BsonDateTime,
+}
+
+impl SpectatorPrediction {
+    #[must_use]
+    pub fn new(race_uuid: Uuid, viewer_id: Uuid, lap: Option<u32>, player_uuid: Uuid) -> Self {
+        Self {
+            id: None,
+            race_uuid,
+            viewer_id,
+            lap,
+            player_uuid,
+            created_at: BsonDateTime::now(),
+        }
+    }
+
+    /// Points earned, or `None` while the lap or race is still to be decided
+    #[must_use]
+    pub fn points<S: BuildHasher>(
+        &self,
+        race: &Race,
+        top_movers: &HashMap<u32, Uuid, S>,
+    ) -> Option<u32> {
+        let (correct, points) = match self.lap {
+            Some(lap) => (
+                *top_movers.get(&lap)? == self.player_uuid,
+                LAP_PREDICTION_POINTS,
+            ),
+            None if race.status == RaceStatus::Finished => (
+                race.participants
+                    .iter()
+                    .any(|p| p.player_uuid == self.player_uuid && p.finish_position == Some(1)),
+                WINNER_PREDICTION_POINTS,
+            ),
+            None => return None,
+        };
+        Some(if correct { points } else { 0 })
+    }
+}
+
+/// Top mover of each resolved lap: the car with the best final value
+///
+/// Ties go to the car whose movement was logged first.
+#[must_use]
+pub fn top_movers(changes: &[RaceChange]) -> HashMap<u32, Uuid> {
+    let mut best: HashMap<u32, (Uuid, u32)> = HashMap::new();
+    for change in changes {
+        if let RaceChange::Movement { lap, movement } = change {
+            let entry = best
+                .entry(*lap)
+                .or_insert((movement.player_uuid, movement.final_value));
+            if movement.final_value > entry.1 {
+                *entry = (movement.player_uuid, movement.final_value);
+            }
+        }
+    }
+    best.into_iter()
+        .map(|(lap, (player_uuid, _))| (lap, player_uuid))
+        .collect()
+}
+
+/// Spectator's standing in a race's prediction game
+#[derive(Debug, Serialize, Clone, PartialEq, Eq, ToSchema)]
+pub struct PredictionStanding {
+    #[schema(value_type = String, format = "uuid")]
+    pub viewer_id: Uuid,
+    pub points: u32,
+    /// Predictions already decided that were right
+    pub correct: u32,
+    /// Predictions already decided
+    pub decided: u32,
+}
+
+/// Spectators of a race by points, best first; ties by more correct predictions
+#[must_use]
+pub fn prediction_leaderboard<S: BuildHasher>(
+    predictions: &[SpectatorPrediction],
+    race: &Race,
+    top_movers: &HashMap<u32, Uuid, S>,
+) -> Vec<PredictionStanding> {
+    let mut standings: HashMap<Uuid, PredictionStanding> = HashMap::new();
+    for prediction in predictions {
+        let standing = standings
+            .entry(prediction.viewer_id)
+            .or_insert(PredictionStanding {
+                viewer_id: prediction.viewer_id,
+                points: 0,
+                correct: 0,
+                decided: 0,
+            });
+        if let Some(points) = prediction.points(race, top_movers) {
+            standing.decided += 1;
+            if points > 0 {
+                standing.correct += 1;
+                standing.points += points;
+            }
+        }
+    }
+
+    let mut standings: Vec<PredictionStanding> = standings.into_values().collect();
+    standings.sort_by(|a, b| {
+        b.points
+            .cmp(&a.points)
+            .then(b.correct.cmp(&a.correct))
+            .then(a.viewer_id.cmp(&b.viewer_id))
+    });
+    standings
+}
+
+mod uuid_as_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use uuid::Uuid;
+
+    pub fn serialize<S>(uuid: &Uuid, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&uuid.to_string())
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Uuid, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Uuid::parse_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{MovementType, ParticipantMovement, Sector, SectorType, Track};
+
+    fn race() -> Race {
+        let sector = |id: u32, sector_type| Sector {
+            id,
+            name: format!("Sector {id}"),
+            min_value: id * 10,
+            max_value: id * 10 + 10,
+            slot_capacity: None,
+            sector_type,
+            capacity_schedule: Vec::new(),
+            modifiers: None,
+        };
+        let sectors = vec![sector(0, SectorType::Start), sector(1, SectorType::Finish)];
+        let track = Track::new("Track".to_string(), sectors).unwrap();
+        let mut race = Race::new("Predicted".to_string(), track, 2);
+        for _ in 0..2 {
+            race.add_participant(Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4())
+                .unwrap();
+        }
+        race
+    }
+
+    fn moved(lap: u32, player_uuid: Uuid, final_value: u32) -> RaceChange {
+        RaceChange::Movement {
+            lap,
+            movement: ParticipantMovement {
+                player_uuid,
+                from_sector: 0,
+                to_sector: 0,
+                final_value,
+                movement_type: MovementType::StayedInSector,
+                steps: Vec::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn top_mover_has_the_best_final_value_of_the_lap() {
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+        let changes = vec![moved(1, first, 8), moved(1, second, 12), moved(2, first, 9)];
+
+        let movers = top_movers(&changes);
+
+        assert_eq!(movers.get(&1), Some(&second));
+        assert_eq!(movers.get(&2), Some(&first));
+    }
+
+    #[test]
+    fn winner_predictions_score_once_the_race_finishes() {
+        let mut race = race();
+        let (winner, other) = (
+            race.participants[0].player_uuid,
+            race.participants[1].player_uuid,
+        );
+        let (right, wrong) = (Uuid::new_v4(), Uuid::new_v4());
+        let predictions = vec![
+            SpectatorPrediction::new(race.uuid, right, None, winner),
+            SpectatorPrediction::new(race.uuid, right, Some(1), other),
+            SpectatorPrediction::new(race.uuid, wrong, None, other),
+        ];
+        let movers = HashMap::from([(1, other)]);
+
+        let standings = prediction_leaderboard(&predictions, &race, &movers);
+        assert_eq!(standings[0].viewer_id, right);
+        assert_eq!((standings[0].points, standings[0].decided), (1, 1));
+        assert_eq!((standings[1].points, standings[1].decided), (0, 0));
+
+        race.status = RaceStatus::Finished;
+        race.participants[0].finish_position = Some(1);
+        race.participants[1].finish_position = Some(2);
+        let standings = prediction_leaderboard(&predictions, &race, &movers);
+        assert_eq!(
+            standings[0],
+            PredictionStanding {
+                viewer_id: right,
+                points: LAP_PREDICTION_POINTS + WINNER_PREDICTION_POINTS,
+                correct: 2,
+                decided: 2,
+            }
+        );
+        assert_eq!((standings[1].points, standings[1].decided), (0, 1));
+    }
+}
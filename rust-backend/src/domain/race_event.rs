@@ -0,0 +1,366 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::DateTime as BsonDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::race::{
+    LapCharacteristic, LapResult, ParticipantMovement, Race, RaceParticipant, RaceStatus,
+};
+
+/// One change to a race, as recorded in the race event log
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(tag = "type")]
+pub enum RaceChange {
+    /// A participant moved while a lap was resolved
+    Movement {
+        lap: u32,
+        movement: ParticipantMovement,
+    },
+    /// The race status, lap or lap characteristic changed
+    PhaseChanged {
+        status: RaceStatus,
+        current_lap: u32,
+        lap_characteristic: LapCharacteristic,
+    },
+    /// A participant joined or its state changed; carries the new state
+    ParticipantUpdated { participant: Box<RaceParticipant> },
+    /// A participant reacted to a resolved lap
+    Reaction {
+        lap: u32,
+        #[serde(with = "uuid_as_string")]
+        #[schema(value_type = String, format = "uuid")]
+        player_uuid: Uuid,
+        emote: Emote,
+    },
+    /// The race is counting down to its first lap
+    CountdownTick {
+        #[schema(value_type = String, format = "date-time")]
+        starts_at: DateTime<Utc>,
+        seconds_remaining: u64,
+    },
+    /// The current lap's deadline is near and some participants have not acted yet
+    TurnDeadlineNear {
+        lap: u32,
+        #[schema(value_type = String, format = "date-time")]
+        deadline_at: DateTime<Utc>,
+        seconds_remaining: u64,
+    },
+    /// Human-readable line about a resolved lap, for spectators and replays
+    Commentary {
+        lap: u32,
+        #[serde(with = "uuid_as_string")]
+        #[schema(value_type = String, format = "uuid")]
+        player_uuid: Uuid,
+        text: String,
+    },
+}
+
+/// Reaction participants can send once a lap is resolved
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Emote {
+    ThumbsUp,
+    Fire,
+    Wow,
+    Laugh,
+    Oops,
+    GoodGame,
+}
+
+impl RaceChange {
+    /// Changes that turn `before` into `after`
+    ///
+    /// `lap_results` are the laps resolved in between, in order; their
+    /// movements come first, followed by the new phase and participant states.
+    #[must_use]
+    pub fn between(before: &Race, after: &Race, lap_results: &[LapResult]) -> Vec<Self> {
+        let mut changes: Vec<Self> = lap_results
+            .iter()
+            .flat_map(|lap_result| {
+                lap_result.movements.iter().map(|movement| Self::Movement {
+                    lap: lap_result.lap,
+                    movement: movement.clone(),
+                })
+            })
+            .collect();
+
+        if before.status != after.status
+            || before.current_lap != after.current_lap
+            || before.lap_characteristic != after.lap_characteristic
+        {
+            changes.push(Self::PhaseChanged {
+                status: after.status.clone(),
+                current_lap: after.current_lap,
+                lap_characteristic: after.lap_characteristic.clone(),
+            });
+        }
+
+        for participant in &after.participants {
+            let updated = before
+                .participants
+                .iter()
+                .find(|p| p.player_uuid == participant.player_uuid)
+                .is_none_or(|previous| {
+                    // Participants hold nested hands and replays without `PartialEq`
+                    serde_json::to_value(previous).ok() != serde_json::to_value(participant).ok()
+                });
+            if updated {
+                changes.push(Self::ParticipantUpdated {
+                    participant: Box::new(participant.clone()),
+                });
+            }
+        }
+
+        changes
+    }
+
+    /// This change as `viewer`, racing a pilot with `focus`, may see it in
+    /// `race`, see [`Race::as_seen_by`]
+    ///
+    /// Participant updates for anyone but the viewer carry the concealed
+    /// participant, so rivals' hands never leave the server. Under fog of war,
+    /// movements, updates and commentary about cars out of view are `None`.
+    #[must_use]
+    pub fn as_seen_by(&self, race: &Race, viewer: Option<Uuid>, focus: Option<u8>) -> Option<Self> {
+        match self {
+            Self::Movement { movement, .. }
+                if !race.shows_car(viewer, focus, movement.player_uuid) =>
+            {
+                None
+            }
+            Self::Commentary { player_uuid, .. }
+                if !race.shows_car(viewer, focus, *player_uuid) =>
+            {
+                None
+            }
+            Self::ParticipantUpdated { participant }
+                if !race.shows_car(viewer, focus, participant.player_uuid) =>
+            {
+                None
+            }
+            Self::ParticipantUpdated { participant } if Some(participant.player_uuid) != viewer => {
+                Some(Self::ParticipantUpdated {
+                    participant: Box::new(participant.concealed(race.current_lap)),
+                })
+            }
+            change => Some(change.clone()),
+        }
+    }
+
+    /// Bring `race` up to date with the phase or participant state this
+    /// change carries, so a live stream can keep filtering with
+    /// [`RaceChange::as_seen_by`]
+    pub fn follow(&self, race: &mut Race) {
+        match self {
+            Self::PhaseChanged {
+                status,
+                current_lap,
+                lap_characteristic,
+            } => {
+                race.status = status.clone();
+                race.current_lap = *current_lap;
+                race.lap_characteristic = lap_characteristic.clone();
+            }
+            Self::ParticipantUpdated { participant } => {
+                match race
+                    .participants
+                    .iter_mut()
+                    .find(|p| p.player_uuid == participant.player_uuid)
+                {
+                    Some(existing) => *existing = (**participant).clone(),
+                    None => race.participants.push((**participant).clone()),
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Entry of the race event log
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RaceEvent {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<mongodb::bson::oid::ObjectId>,
+    #[serde(with = "uuid_as_string")]
+    pub race_uuid: Uuid,
+    pub occurred_at: BsonDateTime,
+    pub change: RaceChange,
+}
+
+impl RaceEvent {
+    #[must_use]
+    pub fn new(race_uuid: Uuid, occurred_at: BsonDateTime, change: RaceChange) -> Self {
+        Self {
+            id: None,
+            race_uuid,
+            occurred_at,
+            change,
+        }
+    }
+}
+
+mod uuid_as_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use uuid::Uuid;
+
+    pub fn serialize<S>(uuid: &Uuid, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&uuid.to_string())
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Uuid, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Uuid::parse_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{MovementType, Sector, SectorType, Track};
+    use std::collections::HashMap;
+
+    fn sector(id: u32, sector_type: SectorType) -> Sector {
+        Sector {
+            id,
+            name: format!("Sector {id}"),
+            min_value: id * 10,
+            max_value: id * 10 + 10,
+            slot_capacity: None,
+            sector_type,
+            capacity_schedule: Vec::new(),
+            modifiers: None,
+        }
+    }
+
+    fn race() -> Race {
+        let sectors = vec![sector(0, SectorType::Start), sector(1, SectorType::Finish)];
+        let track = Track::new("Track".to_string(), sectors).unwrap();
+        let mut race = Race::new("Logged".to_string(), track, 3);
+        race.add_participant(Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4())
+            .unwrap();
+        race
+    }
+
+    #[test]
+    fn unchanged_race_has_no_changes() {
+        let race = race();
+        assert!(RaceChange::between(&race, &race.clone(), &[]).is_empty());
+    }
+
+    #[test]
+    fn joining_player_is_a_participant_change() {
+        let before = race();
+        let mut after = before.clone();
+        let player_uuid = Uuid::new_v4();
+        after
+            .add_participant(player_uuid, Uuid::new_v4(), Uuid::new_v4())
+            .unwrap();
+
+        let changes = RaceChange::between(&before, &after, &[]);
+        assert_eq!(changes.len(), 1);
+        assert!(matches!(
+            &changes[0],
+            RaceChange::ParticipantUpdated { participant } if participant.player_uuid == player_uuid
+        ));
+    }
+
+    #[test]
+    fn resolved_lap_yields_movements_then_phase_then_participants() {
+        let before = race();
+        let mut after = before.clone();
+        after.status = RaceStatus::InProgress;
+        after.current_lap = 2;
+        after.participants[0].total_value = 12;
+        let movement = ParticipantMovement {
+            player_uuid: after.participants[0].player_uuid,
+            from_sector: 0,
+            to_sector: 1,
+            final_value: 12,
+            movement_type: MovementType::MovedUp,
+            steps: Vec::new(),
+        };
+        let lap_result = LapResult {
+            lap: 1,
+            lap_characteristic: LapCharacteristic::Straight,
+            sector_positions: HashMap::new(),
+            movements: vec![movement],
+        };
+
+        let changes = RaceChange::between(&before, &after, &[lap_result]);
+        assert_eq!(changes.len(), 3);
+        assert!(matches!(changes[0], RaceChange::Movement { lap: 1, .. }));
+        assert!(matches!(
+            changes[1],
+            RaceChange::PhaseChanged { current_lap: 2, .. }
+        ));
+        assert!(matches!(changes[2], RaceChange::ParticipantUpdated { .. }));
+    }
+
+    #[test]
+    fn participant_updates_conceal_rivals_hands() {
+        let race = race();
+        let player_uuid = race.participants[0].player_uuid;
+        let change = RaceChange::ParticipantUpdated {
+            participant: Box::new(race.participants[0].clone()),
+        };
+
+        let Some(RaceChange::ParticipantUpdated { participant }) =
+            change.as_seen_by(&race, Some(player_uuid), None)
+        else {
+            panic!("participant update expected");
+        };
+        assert!(!participant.boost_hand.cards.is_empty());
+
+        let Some(RaceChange::ParticipantUpdated { participant }) =
+            change.as_seen_by(&race, Some(Uuid::new_v4()), None)
+        else {
+            panic!("participant update expected");
+        };
+        assert!(participant.boost_hand.cards.is_empty());
+    }
+
+    #[test]
+    fn fog_of_war_hides_changes_about_cars_out_of_view() {
+        let mut race = race();
+        race.rules.fog_of_war = true;
+        let player_uuid = race.participants[0].player_uuid;
+        let commentary = RaceChange::Commentary {
+            lap: 1,
+            player_uuid,
+            text: "Overtakes into Sector 1".to_string(),
+        };
+        assert!(commentary.as_seen_by(&race, None, None).is_some());
+
+        RaceChange::PhaseChanged {
+            status: RaceStatus::InProgress,
+            current_lap: 1,
+            lap_characteristic: LapCharacteristic::Straight,
+        }
+        .follow(&mut race);
+        assert!(commentary.as_seen_by(&race, None, None).is_none());
+        assert!(commentary
+            .as_seen_by(&race, Some(player_uuid), None)
+            .is_some());
+    }
+
+    #[test]
+    fn reactions_open_once_a_lap_is_resolved() {
+        let mut race = race();
+        assert_eq!(race.last_resolved_lap(), None);
+
+        race.status = RaceStatus::InProgress;
+        assert_eq!(race.last_resolved_lap(), None);
+        race.current_lap = 2;
+        assert_eq!(race.last_resolved_lap(), Some(1));
+
+        race.status = RaceStatus::Finished;
+        assert_eq!(race.last_resolved_lap(), Some(2));
+    }
+}
@@ -0,0 +1,168 @@
+use mongodb::bson::DateTime as BsonDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{LapCharacteristic, LapResult};
+
+/// A car on the board, as stored in a snapshot
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+pub struct SnapshotCar {
+    #[serde(with = "uuid_as_string")]
+    #[schema(value_type = String, format = "uuid")]
+    pub player_uuid: Uuid,
+    pub position_in_sector: u32,
+    pub lap: u32,
+    pub total_value: u32,
+}
+
+/// Cars in one sector, best position first
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+pub struct SectorOccupancy {
+    pub sector_id: u32,
+    pub cars: Vec<SnapshotCar>,
+}
+
+/// Sector occupancy of a race right after one of its laps was processed
+///
+/// Only occupied sectors are listed, in track order; cars that finished the
+/// race are off the board.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct RaceSnapshot {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub id: Option<mongodb::bson::oid::ObjectId>,
+    #[serde(with = "uuid_as_string")]
+    #[schema(value_type = String, format = "uuid")]
+    pub race_uuid: Uuid,
+    pub lap: u32,
+    pub lap_characteristic: LapCharacteristic,
+    pub sectors: Vec<SectorOccupancy>,
+    #[schema(value_type = String, format = "date-time")]
+    pub taken_at: BsonDateTime,
+}
+
+impl RaceSnapshot {
+    /// Snapshot of the board a processed lap left behind
+    #[must_use]
+    pub fn after_lap(race_uuid: Uuid, lap_result: &LapResult) -> Self {
+        let mut sectors: Vec<SectorOccupancy> = lap_result
+            .sector_positions
+            .iter()
+            .filter_map(|(sector_id, participants)| {
+                let mut cars: Vec<SnapshotCar> = participants
+                    .iter()
+                    .map(|p| SnapshotCar {
+                        player_uuid: p.player_uuid,
+                        position_in_sector: p.current_position_in_sector,
+                        lap: p.current_lap,
+                        total_value: p.total_value,
+                    })
+                    .collect();
+                cars.sort_by_key(|car| car.position_in_sector);
+                Some(SectorOccupancy {
+                    sector_id: sector_id.parse().ok()?,
+                    cars,
+                })
+            })
+            .filter(|sector| !sector.cars.is_empty())
+            .collect();
+        sectors.sort_by_key(|sector| sector.sector_id);
+
+        Self {
+            id: None,
+            race_uuid,
+            lap: lap_result.lap,
+            lap_characteristic: lap_result.lap_characteristic.clone(),
+            sectors,
+            taken_at: BsonDateTime::now(),
+        }
+    }
+}
+
+mod uuid_as_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use uuid::Uuid;
+
+    pub fn serialize<S>(uuid: &Uuid, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&uuid.to_string())
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Uuid, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Uuid::parse_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Race, Sector, SectorType, Track};
+    use std::collections::HashMap;
+
+    #[test]
+    fn snapshots_list_occupied_sectors_in_track_order() {
+        let sector = |id, sector_type| Sector {
+            id,
+            name: format!("Sector {id}"),
+            min_value: id * 10,
+            max_value: id * 10 + 10,
+            slot_capacity: None,
+            sector_type,
+            capacity_schedule: Vec::new(),
+            modifiers: None,
+        };
+        let sectors = vec![
+            sector(0, SectorType::Start),
+            sector(1, SectorType::Straight),
+            sector(2, SectorType::Finish),
+        ];
+        let track = Track::new("Track".to_string(), sectors).unwrap();
+        let mut race = Race::new("Snapshot race".to_string(), track, 2);
+        for _ in 0..3 {
+            race.add_participant(Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4())
+                .unwrap();
+        }
+        let mut participants = race.participants.clone();
+        participants[0].current_sector = 2;
+        participants[1].current_position_in_sector = 1;
+        participants[2].current_position_in_sector = 0;
+        let lap_result = LapResult {
+            lap: 1,
+            lap_characteristic: LapCharacteristic::Straight,
+            sector_positions: HashMap::from([
+                (
+                    "0".to_string(),
+                    vec![participants[1].clone(), participants[2].clone()],
+                ),
+                ("1".to_string(), Vec::new()),
+                ("2".to_string(), vec![participants[0].clone()]),
+            ]),
+            movements: Vec::new(),
+        };
+
+        let snapshot = RaceSnapshot::after_lap(race.uuid, &lap_result);
+
+        let layout: Vec<(u32, Vec<Uuid>)> = snapshot
+            .sectors
+            .iter()
+            .map(|s| (s.sector_id, s.cars.iter().map(|c| c.player_uuid).collect()))
+            .collect();
+        assert_eq!(
+            layout,
+            vec![
+                (
+                    0,
+                    vec![participants[2].player_uuid, participants[1].player_uuid]
+                ),
+                (2, vec![participants[0].player_uuid]),
+            ]
+        );
+    }
+}
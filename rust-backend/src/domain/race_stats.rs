@@ -0,0 +1,236 @@
+use mongodb::bson::DateTime as BsonDateTime;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{MovementType, ParticipantMovement, Race};
+
+/// Figures of one resolved lap
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct LapStats {
+    pub lap: u32,
+    /// Cars that got ahead of another car on this lap, counted once per car passed
+    pub overtakes: u32,
+    /// Mean boost card played on this lap; absent when none was
+    pub average_boost: Option<f64>,
+    /// Cars in each sector once the lap was resolved, by sector id
+    pub sector_occupancy: Vec<u32>,
+}
+
+/// Aggregate figures of a race, lap by lap
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct RaceStats {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub id: Option<mongodb::bson::oid::ObjectId>,
+    #[serde(with = "uuid_as_string")]
+    #[schema(value_type = String, format = "uuid")]
+    pub race_uuid: Uuid,
+    /// Resolved laps, first lap first
+    pub laps: Vec<LapStats>,
+    /// Times the car furthest along the track changed
+    pub lead_changes: u32,
+    #[schema(value_type = String, format = "date-time")]
+    pub computed_at: BsonDateTime,
+}
+
+impl RaceStats {
+    /// Figures of `race` from the movements of its event log, with their lap
+    ///
+    /// Cars are compared by how far along the track they are: completed laps,
+    /// then sector. A car enters the comparison with its first movement, and
+    /// a tie for the lead leaves the leader unchanged.
+    #[must_use]
+    pub fn compute(race: &Race, movements: &[(u32, ParticipantMovement)]) -> Self {
+        let sector_count = race.track.sectors.len();
+
+        let mut by_lap: BTreeMap<u32, Vec<&ParticipantMovement>> = BTreeMap::new();
+        for (lap, movement) in movements {
+            by_lap.entry(*lap).or_default().push(movement);
+        }
+
+        let mut progress: HashMap<Uuid, i64> = HashMap::new();
+        let mut sectors: HashMap<Uuid, Option<u32>> = HashMap::new();
+        let mut leader: Option<Uuid> = None;
+        let mut lead_changes = 0;
+        let mut laps = Vec::new();
+        for (lap, lap_movements) in by_lap {
+            for movement in &lap_movements {
+                progress
+                    .entry(movement.player_uuid)
+                    .or_insert_with(|| i64::from(movement.from_sector));
+            }
+            let before = progress.clone();
+
+            for movement in &lap_movements {
+                let step = match movement.movement_type {
+                    MovementType::FinishedLap | MovementType::FinishedRace => 1,
+                    _ => i64::from(movement.to_sector) - i64::from(movement.from_sector),
+                };
+                *progress.entry(movement.player_uuid).or_default() += step;
+                let sector = (movement.movement_type != MovementType::FinishedRace)
+                    .then_some(movement.to_sector);
+                sectors.insert(movement.player_uuid, sector);
+            }
+
+            let overtakes: usize = before
+                .iter()
+                .map(|(car, was)| {
+                    before
+                        .iter()
+                        .filter(|&(other, other_was)| {
+                            was < other_was && progress[car] > progress[other]
+                        })
+                        .count()
+                })
+                .sum();
+
+            let mut sector_occupancy = vec![0; sector_count];
+            for sector in sectors.values().flatten() {
+                if let Some(cars) = usize::try_from(*sector)
+                    .ok()
+                    .and_then(|sector| sector_occupancy.get_mut(sector))
+                {
+                    *cars += 1;
+                }
+            }
+
+            if let Some(&best) = progress.values().max() {
+                let mut leaders = progress.iter().filter(|&(_, &p)| p == best);
+                if let (Some((&front, _)), None) = (leaders.next(), leaders.next()) {
+                    if leader.is_some_and(|leader| leader != front) {
+                        lead_changes += 1;
+                    }
+                    leader = Some(front);
+                }
+            }
+
+            laps.push(LapStats {
+                lap,
+                overtakes: u32::try_from(overtakes).unwrap_or(u32::MAX),
+                average_boost: average_boost(race, lap),
+                sector_occupancy,
+            });
+        }
+
+        Self {
+            id: None,
+            race_uuid: race.uuid,
+            laps,
+            lead_changes,
+            computed_at: BsonDateTime::now(),
+        }
+    }
+}
+
+/// Mean boost card the participants played on `lap`
+fn average_boost(race: &Race, lap: u32) -> Option<f64> {
+    let boosts: Vec<f64> = race
+        .participants
+        .iter()
+        .flat_map(|p| &p.boost_usage_history)
+        .filter(|usage| usage.lap_number == lap)
+        .map(|usage| f64::from(usage.boost_value))
+        .collect();
+    if boosts.is_empty() {
+        return None;
+    }
+    #[allow(clippy::cast_precision_loss)]
+    Some(boosts.iter().sum::<f64>() / boosts.len() as f64)
+}
+
+mod uuid_as_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use uuid::Uuid;
+
+    pub fn serialize<S>(uuid: &Uuid, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&uuid.to_string())
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Uuid, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Uuid::parse_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{BoostUsageRecord, Sector, SectorType, Track};
+
+    fn movement(
+        player_uuid: Uuid,
+        from_sector: u32,
+        to_sector: u32,
+        movement_type: MovementType,
+    ) -> ParticipantMovement {
+        ParticipantMovement {
+            player_uuid,
+            from_sector,
+            to_sector,
+            final_value: 10,
+            movement_type,
+            steps: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn overtakes_and_lead_changes_follow_the_cars_progress() {
+        let sector = |id, sector_type| Sector {
+            id,
+            name: format!("Sector {id}"),
+            min_value: id * 10,
+            max_value: id * 10 + 10,
+            slot_capacity: None,
+            sector_type,
+            capacity_schedule: Vec::new(),
+            modifiers: None,
+        };
+        let sectors = vec![
+            sector(0, SectorType::Start),
+            sector(1, SectorType::Straight),
+            sector(2, SectorType::Finish),
+        ];
+        let track = Track::new("Track".to_string(), sectors).unwrap();
+        let mut race = Race::new("Stats race".to_string(), track, 2);
+        let (chaser, leader) = (Uuid::new_v4(), Uuid::new_v4());
+        for player in [chaser, leader] {
+            race.add_participant(player, Uuid::new_v4(), Uuid::new_v4())
+                .unwrap();
+        }
+        for (participant, boost_value) in race.participants.iter_mut().zip([1, 4]) {
+            participant.boost_usage_history.push(BoostUsageRecord {
+                lap_number: 2,
+                boost_value,
+                cycle_number: 1,
+                cards_remaining_after: 4,
+                replenishment_occurred: false,
+                lap_characteristic: None,
+            });
+        }
+        let movements = vec![
+            (1, movement(chaser, 0, 1, MovementType::MovedUp)),
+            (1, movement(leader, 1, 2, MovementType::MovedUp)),
+            (2, movement(chaser, 1, 2, MovementType::MovedUp)),
+            (2, movement(leader, 2, 1, MovementType::MovedDown)),
+        ];
+
+        let stats = RaceStats::compute(&race, &movements);
+
+        assert_eq!(stats.laps.len(), 2);
+        assert_eq!(stats.laps[0].overtakes, 0);
+        assert_eq!(stats.laps[0].average_boost, None);
+        assert_eq!(stats.laps[0].sector_occupancy, vec![0, 1, 1]);
+        assert_eq!(stats.laps[1].overtakes, 1);
+        assert_eq!(stats.laps[1].average_boost, Some(2.5));
+        assert_eq!(stats.laps[1].sector_occupancy, vec![0, 1, 1]);
+        assert_eq!(stats.lead_changes, 1);
+    }
+}
@@ -0,0 +1,168 @@
+use mongodb::bson::DateTime as BsonDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Longest free-text details a report can carry
+pub const MAX_REPORT_DETAILS_LENGTH: usize = 1000;
+
+/// What a player is reported for
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+pub enum ReportReason {
+    /// Abusive or offensive team name
+    OffensiveName,
+    /// Abusive chat messages
+    AbusiveChat,
+    Other,
+}
+
+/// Where a report stands in the moderation queue
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+pub enum ReportStatus {
+    /// Waiting for a moderator
+    Open,
+    /// Reviewed, no action taken
+    Dismissed,
+    /// Reviewed and the reported player was banned
+    Actioned,
+}
+
+/// What a moderator decides about a report
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+pub enum ModerationAction {
+    Dismiss,
+    /// Ban the reported player, closing their other open reports too
+    Ban,
+}
+
+/// Player report waiting in, or resolved from, the moderation queue
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct Report {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub id: Option<mongodb::bson::oid::ObjectId>,
+    #[serde(with = "uuid_as_string")]
+    #[schema(value_type = String, format = "uuid")]
+    pub uuid: Uuid,
+    #[serde(with = "uuid_as_string")]
+    #[schema(value_type = String, format = "uuid")]
+    pub reporter_uuid: Uuid,
+    #[serde(with = "uuid_as_string")]
+    #[schema(value_type = String, format = "uuid")]
+    pub reported_uuid: Uuid,
+    pub reason: ReportReason,
+    /// What happened, e.g. the offending chat message
+    pub details: Option<String>,
+    /// Race the abuse happened in, if any
+    pub race_uuid: Option<String>,
+    pub status: ReportStatus,
+    #[schema(value_type = String, format = "date-time")]
+    pub created_at: BsonDateTime,
+    #[schema(value_type = Option<String>, format = "date-time")]
+    pub resolved_at: Option<BsonDateTime>,
+    /// Administrator who resolved the report
+    pub resolved_by: Option<String>,
+    pub resolution_note: Option<String>,
+}
+
+impl Report {
+    /// Report by `player_uuid` against `target_uuid`
+    #[must_use]
+    pub fn new(
+        player_uuid: Uuid,
+        target_uuid: Uuid,
+        reason: ReportReason,
+        details: Option<String>,
+        race_uuid: Option<Uuid>,
+    ) -> Self {
+        Self {
+            id: None,
+            uuid: Uuid::new_v4(),
+            reporter_uuid: player_uuid,
+            reported_uuid: target_uuid,
+            reason,
+            details,
+            race_uuid: race_uuid.map(|uuid| uuid.to_string()),
+            status: ReportStatus::Open,
+            created_at: BsonDateTime::now(),
+            resolved_at: None,
+            resolved_by: None,
+            resolution_note: None,
+        }
+    }
+
+    /// Close the report with the moderator's decision
+    pub fn resolve(&mut self, action: ModerationAction, admin_uuid: Uuid, note: Option<String>) {
+        self.status = match action {
+            ModerationAction::Dismiss => ReportStatus::Dismissed,
+            ModerationAction::Ban => ReportStatus::Actioned,
+        };
+        self.resolved_at = Some(BsonDateTime::now());
+        self.resolved_by = Some(admin_uuid.to_string());
+        self.resolution_note = note;
+    }
+}
+
+/// Player banned by a moderator; banned players cannot join races
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct PlayerBan {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub id: Option<mongodb::bson::oid::ObjectId>,
+    #[serde(with = "uuid_as_string")]
+    #[schema(value_type = String, format = "uuid")]
+    pub player_uuid: Uuid,
+    /// Report that led to the ban
+    #[serde(with = "uuid_as_string")]
+    #[schema(value_type = String, format = "uuid")]
+    pub report_uuid: Uuid,
+    #[serde(with = "uuid_as_string")]
+    #[schema(value_type = String, format = "uuid")]
+    pub banned_by: Uuid,
+    pub note: Option<String>,
+    #[schema(value_type = String, format = "date-time")]
+    pub banned_at: BsonDateTime,
+}
+
+mod uuid_as_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use uuid::Uuid;
+
+    pub fn serialize<S>(uuid: &Uuid, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&uuid.to_string())
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Uuid, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Uuid::parse_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolving_records_the_decision() {
+        let admin = Uuid::new_v4();
+        let mut report = Report::new(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            ReportReason::AbusiveChat,
+            Some("insults".to_string()),
+            None,
+        );
+        assert_eq!(report.status, ReportStatus::Open);
+
+        report.resolve(ModerationAction::Ban, admin, None);
+        assert_eq!(report.status, ReportStatus::Actioned);
+        assert_eq!(report.resolved_by, Some(admin.to_string()));
+        assert!(report.resolved_at.is_some());
+    }
+}
@@ -0,0 +1,125 @@
+use super::performance_model::{PerformanceModel, PerformanceModelKind};
+use super::race::RaceRules;
+
+/// Rules version assumed for races stored before versioning existed
+pub const LEGACY_RULES_VERSION: u32 = 1;
+
+/// Rules version stamped on newly created races
+pub const CURRENT_RULES_VERSION: u32 = 2;
+
+/// Game rules as they stood at a given version
+///
+/// Every released version keeps its implementation so that a race resolves
+/// under the rules it was created with, even after a balance change.
+pub trait GameRules: Send + Sync {
+    /// Version number stored on race documents
+    fn version(&self) -> u32;
+
+    /// Performance formula for a race configured with `rules`
+    fn performance_model(&self, rules: &RaceRules) -> &'static dyn PerformanceModel;
+
+    /// Whether players may bank a boost card into the next cycle
+    fn allows_card_banking(&self, rules: &RaceRules) -> bool;
+}
+
+/// Original rules: additive boost on the capped base value, full replenishment only
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RulesV1;
+
+impl GameRules for RulesV1 {
+    fn version(&self) -> u32 {
+        1
+    }
+
+    fn performance_model(&self, _rules: &RaceRules) -> &'static dyn PerformanceModel {
+        PerformanceModelKind::Additive.model()
+    }
+
+    fn allows_card_banking(&self, _rules: &RaceRules) -> bool {
+        false
+    }
+}
+
+/// Configurable rules: per-race performance model and optional card banking
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RulesV2;
+
+impl GameRules for RulesV2 {
+    fn version(&self) -> u32 {
+        2
+    }
+
+    fn performance_model(&self, rules: &RaceRules) -> &'static dyn PerformanceModel {
+        rules.performance_model.model()
+    }
+
+    fn allows_card_banking(&self, rules: &RaceRules) -> bool {
+        rules.boost_card_banking
+    }
+}
+
+static RULES_V1: RulesV1 = RulesV1;
+static RULES_V2: RulesV2 = RulesV2;
+
+/// Look up the rules implementation for a stored version
+/// Returns `None` for versions this build does not know about
+#[must_use]
+pub fn rules_for_version(version: u32) -> Option<&'static dyn GameRules> {
+    match version {
+        1 => Some(&RULES_V1),
+        2 => Some(&RULES_V2),
+        _ => None,
+    }
+}
+
+/// Rules applied to newly created races
+#[must_use]
+pub fn current_rules() -> &'static dyn GameRules {
+    &RULES_V2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::race::GridOrder;
+
+    #[test]
+    fn test_every_known_version_resolves() {
+        for version in LEGACY_RULES_VERSION..=CURRENT_RULES_VERSION {
+            let rules = rules_for_version(version).expect("known version");
+            assert_eq!(rules.version(), version);
+        }
+        assert!(rules_for_version(0).is_none());
+        assert!(rules_for_version(CURRENT_RULES_VERSION + 1).is_none());
+        assert_eq!(current_rules().version(), CURRENT_RULES_VERSION);
+    }
+
+    #[test]
+    fn test_v1_ignores_configurable_rules() {
+        let race_rules = RaceRules {
+            boost_card_banking: true,
+            performance_model: PerformanceModelKind::Multiplicative,
+            turn_time_limit_seconds: None,
+            start_countdown_seconds: None,
+            grid_order: GridOrder::BestAtFront,
+            success_ballast: None,
+            catch_up_assist: None,
+            skill_bracket: None,
+            car_tier: None,
+            car_classes: Vec::new(),
+            allowed_rarities: Vec::new(),
+            commit_reveal: false,
+            fog_of_war: false,
+            max_view_radius: None,
+            focus_limited_view: false,
+        };
+
+        let v1 = rules_for_version(1).unwrap();
+        assert_eq!(v1.performance_model(&race_rules).name(), "Additive");
+        assert!(!v1.allows_card_banking(&race_rules));
+
+        let v2 = rules_for_version(2).unwrap();
+        assert_eq!(v2.performance_model(&race_rules).name(), "Multiplicative");
+        assert!(v2.allows_card_banking(&race_rules));
+    }
+}
@@ -0,0 +1,291 @@
+use mongodb::bson::DateTime as BsonDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::car::performance_score;
+use super::race::{LapCharacteristic, Race, RaceMode, RaceStatus, Track};
+use crate::services::car_validation::ValidatedCarData;
+
+/// Component values a car contributes on one kind of lap
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+pub struct CarLapValues {
+    pub engine: u32,
+    pub body: u32,
+    pub pilot: u32,
+}
+
+/// Snapshot of the car a ghost was recorded with
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+pub struct GhostCar {
+    pub straight: CarLapValues,
+    pub curve: CarLapValues,
+}
+
+impl GhostCar {
+    #[must_use]
+    pub fn from_car_data(car_data: &ValidatedCarData) -> Self {
+        Self {
+            straight: CarLapValues {
+                engine: u32::from(car_data.engine.straight_value),
+                body: u32::from(car_data.body.straight_value),
+                pilot: u32::from(car_data.pilot.performance.straight_value),
+            },
+            curve: CarLapValues {
+                engine: u32::from(car_data.engine.curve_value),
+                body: u32::from(car_data.body.curve_value),
+                pilot: u32::from(car_data.pilot.performance.curve_value),
+            },
+        }
+    }
+
+    /// Performance rating of the recorded values, as rated for the starting grid
+    #[must_use]
+    pub fn performance_rating(&self) -> u32 {
+        performance_score(
+            [self.straight, self.curve]
+                .iter()
+                .map(|values| values.engine + values.body + values.pilot)
+                .sum(),
+        )
+    }
+
+    #[must_use]
+    pub fn values_for(&self, lap_characteristic: &LapCharacteristic) -> CarLapValues {
+        match lap_characteristic {
+            LapCharacteristic::Straight => self.straight,
+            LapCharacteristic::Curve => self.curve,
+        }
+    }
+}
+
+/// Recorded time trial run that other players can race against
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct Ghost {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub id: Option<mongodb::bson::oid::ObjectId>,
+    #[serde(with = "uuid_as_string")]
+    pub uuid: Uuid,
+    #[serde(with = "uuid_as_string")]
+    pub player_uuid: Uuid,
+    #[serde(with = "uuid_as_string")]
+    pub source_race_uuid: Uuid,
+    /// Layout the run was recorded on; races against the ghost reuse it
+    pub track: Track,
+    pub turns: u32,
+    /// Score of the run
+    pub laps_completed: u32,
+    pub car: GhostCar,
+    /// Boost card played on each turn, in order
+    pub boosts: Vec<u32>,
+    #[schema(value_type = String, format = "date-time")]
+    pub created_at: BsonDateTime,
+}
+
+impl Ghost {
+    /// Record a player's finished time trial as a ghost
+    pub fn record(
+        race: &Race,
+        player_uuid: Uuid,
+        car_data: &ValidatedCarData,
+    ) -> Result<Self, String> {
+        if race.mode != RaceMode::TimeTrial {
+            return Err("Only time trial races can be recorded as ghosts".to_string());
+        }
+        if race.status != RaceStatus::Finished {
+            return Err("Time trial is not finished yet".to_string());
+        }
+
+        let participant = race
+            .participants
+            .iter()
+            .find(|p| p.player_uuid == player_uuid && p.ghost.is_none())
+            .ok_or("Player not found in race")?;
+
+        // Turns without a boost card played (pit stops) are replayed as a 0 card
+        let mut boosts = vec![0; race.total_laps as usize];
+        for usage in &participant.boost_usage_history {
+            if let Some(turn) = boosts.get_mut(usage.lap_number.saturating_sub(1) as usize) {
+                *turn = u32::from(usage.boost_value);
+            }
+        }
+
+        Ok(Self {
+            id: None,
+            uuid: Uuid::new_v4(),
+            player_uuid,
+            source_race_uuid: race.uuid,
+            track: race.track.clone(),
+            turns: race.total_laps,
+            laps_completed: Race::laps_completed(participant),
+            car: GhostCar::from_car_data(car_data),
+            boosts,
+            created_at: BsonDateTime::now(),
+        })
+    }
+
+    /// Replay data carried by the ghost's participant in a new race
+    #[must_use]
+    pub fn replay(&self) -> GhostReplay {
+        GhostReplay {
+            ghost_uuid: self.uuid,
+            car: self.car,
+            boosts: self.boosts.clone(),
+        }
+    }
+}
+
+/// Recorded actions a ghost participant plays back, one per turn
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+pub struct GhostReplay {
+    #[serde(with = "uuid_as_string")]
+    pub ghost_uuid: Uuid,
+    pub car: GhostCar,
+    pub boosts: Vec<u32>,
+}
+
+impl GhostReplay {
+    /// Boost card recorded for a turn (1-based); 0 once the recording runs out
+    #[must_use]
+    pub fn boost_for_turn(&self, turn: u32) -> u32 {
+        self.boosts
+            .get(turn.saturating_sub(1) as usize)
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+mod uuid_as_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use uuid::Uuid;
+
+    pub fn serialize<S>(uuid: &Uuid, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&uuid.to_string())
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Uuid, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Uuid::parse_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        builtin_track_templates, Body, BodyName, Car, CarName, ComponentRarity, Engine, EngineName,
+        Pilot, PilotClass, PilotName, PilotPerformance, PilotRarity, PilotSkills,
+    };
+
+    fn create_test_car_data() -> ValidatedCarData {
+        ValidatedCarData {
+            car: Car::new(CarName::parse("Test Car").unwrap(), None).unwrap(),
+            engine: Engine::new(
+                EngineName::parse("Test Engine").unwrap(),
+                ComponentRarity::Common,
+                6,
+                4,
+                None,
+            )
+            .unwrap(),
+            body: Body::new(
+                BodyName::parse("Test Body").unwrap(),
+                ComponentRarity::Common,
+                4,
+                6,
+                None,
+            )
+            .unwrap(),
+            pilot: Pilot::new(
+                PilotName::parse("Test Pilot").unwrap(),
+                PilotClass::AllRounder,
+                PilotRarity::Professional,
+                PilotSkills::new(6, 6, 7, 5).unwrap(),
+                PilotPerformance::new(3, 3).unwrap(),
+                None,
+            )
+            .unwrap(),
+        }
+    }
+
+    fn create_test_ghost(boosts: Vec<u32>) -> Ghost {
+        Ghost {
+            id: None,
+            uuid: Uuid::new_v4(),
+            player_uuid: Uuid::new_v4(),
+            source_race_uuid: Uuid::new_v4(),
+            track: builtin_track_templates()[0].to_track(None).unwrap(),
+            turns: 3,
+            laps_completed: 0,
+            car: GhostCar::from_car_data(&create_test_car_data()),
+            boosts,
+            created_at: BsonDateTime::now(),
+        }
+    }
+
+    #[test]
+    fn test_race_against_ghost_replays_recording() {
+        let ghost = create_test_ghost(vec![4, 3, 2]);
+        let player = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let mut race =
+            Race::new_time_trial_against("Time Trial".to_string(), player, &ghost).unwrap();
+
+        assert_eq!(race.mode, RaceMode::TimeTrial);
+        assert_eq!(race.total_laps, ghost.turns);
+        assert_eq!(race.participants.len(), 2);
+        assert!(race.participants.iter().all(|p| p.current_sector == 0));
+
+        let car_data = create_test_car_data();
+        for card in 0..3 {
+            race.process_individual_lap_action(player.0, card, &car_data)
+                .unwrap();
+        }
+
+        assert_eq!(race.status, RaceStatus::Finished);
+        let ghost_participant = race.participants.iter().find(|p| p.ghost.is_some());
+        assert!(ghost_participant.unwrap().total_value > 0);
+    }
+
+    #[test]
+    fn test_record_ghost_from_finished_time_trial() {
+        let track = builtin_track_templates()[0].to_track(None).unwrap();
+        let player = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let mut race = Race::new_time_trial("Time Trial".to_string(), track, 2, player).unwrap();
+        let car_data = create_test_car_data();
+
+        race.process_individual_lap_action(player.0, 3, &car_data)
+            .unwrap();
+        assert!(Ghost::record(&race, player.0, &car_data).is_err());
+        race.process_individual_lap_action(player.0, 1, &car_data)
+            .unwrap();
+
+        let ghost = Ghost::record(&race, player.0, &car_data).unwrap();
+        assert_eq!(ghost.boosts, vec![3, 1]);
+        assert_eq!(ghost.turns, 2);
+        assert_eq!(
+            ghost.laps_completed,
+            Race::laps_completed(&race.participants[0])
+        );
+        assert_eq!(ghost.replay().boost_for_turn(2), 1);
+        assert_eq!(ghost.replay().boost_for_turn(3), 0);
+    }
+
+    #[test]
+    fn test_only_time_trials_are_recorded() {
+        let track = builtin_track_templates()[0].to_track(None).unwrap();
+        let mut race = Race::new("Standard".to_string(), track, 1);
+        race.status = RaceStatus::Finished;
+        assert!(
+            Ghost::record(&race, Uuid::new_v4(), &create_test_car_data())
+                .unwrap_err()
+                .contains("time trial")
+        );
+    }
+}
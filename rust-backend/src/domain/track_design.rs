@@ -0,0 +1,461 @@
+use mongodb::bson::DateTime as BsonDateTime;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::race::{Sector, Track, TrackValidationError};
+
+/// Format identifier written into every exported track
+pub const TRACK_EXPORT_FORMAT: &str = "boardurance-track";
+
+/// Current version of the portable track schema
+pub const TRACK_EXPORT_SCHEMA_VERSION: u32 = 1;
+
+/// Standalone track stored in the `tracks` collection
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct TrackDesign {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub id: Option<mongodb::bson::oid::ObjectId>,
+    #[serde(with = "uuid_as_string")]
+    pub uuid: Uuid,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub sectors: Vec<Sector>,
+    /// Player who created the track; imported tracks have no author
+    #[serde(default)]
+    pub author_uuid: Option<Uuid>,
+    /// Public tracks are listed for every player
+    #[serde(default)]
+    pub is_public: bool,
+    #[serde(default)]
+    pub ratings: Vec<TrackRating>,
+    /// Players who marked the track as a favorite
+    #[serde(default)]
+    pub favorited_by: Vec<Uuid>,
+    #[schema(value_type = String, format = "date-time")]
+    pub created_at: BsonDateTime,
+    #[schema(value_type = String, format = "date-time")]
+    pub updated_at: BsonDateTime,
+}
+
+/// A single player's rating of a track
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct TrackRating {
+    pub player_uuid: Uuid,
+    /// 1 to 5 stars
+    pub stars: u8,
+}
+
+/// Ordering used when listing community tracks
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TrackSort {
+    /// Most favorited first, ties broken by rating
+    #[default]
+    Popular,
+    /// Highest average rating first
+    TopRated,
+    /// Most recently created first
+    Newest,
+}
+
+impl TrackDesign {
+    /// Create a new track design, validating the sector layout
+    pub fn new(
+        name: String,
+        description: String,
+        sectors: Vec<Sector>,
+        author_uuid: Option<Uuid>,
+    ) -> Result<Self, Vec<TrackValidationError>> {
+        Track::validate_sectors(&sectors)?;
+
+        let now = BsonDateTime::now();
+        Ok(Self {
+            id: None,
+            uuid: Uuid::new_v4(),
+            name,
+            description,
+            sectors,
+            author_uuid,
+            is_public: false,
+            ratings: Vec::new(),
+            favorited_by: Vec::new(),
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    /// Snapshot this design as a race track
+    /// The design name is used unless a custom name is given
+    pub fn to_track(&self, name: Option<String>) -> Result<Track, Vec<TrackValidationError>> {
+        Track::new_validated(
+            name.unwrap_or_else(|| self.name.clone()),
+            self.sectors.clone(),
+        )
+    }
+
+    /// Check whether the given player created this track
+    #[must_use]
+    pub fn is_authored_by(&self, player_uuid: Uuid) -> bool {
+        self.author_uuid == Some(player_uuid)
+    }
+
+    /// Replace the sector layout, keeping the old one if the new layout is invalid
+    pub fn update_sectors(
+        &mut self,
+        sectors: Vec<Sector>,
+    ) -> Result<(), Vec<TrackValidationError>> {
+        Track::validate_sectors(&sectors)?;
+        self.sectors = sectors;
+        self.updated_at = BsonDateTime::now();
+        Ok(())
+    }
+
+    /// Rate the track, replacing any earlier rating by the same player
+    pub fn rate(&mut self, player_uuid: Uuid, stars: u8) -> Result<(), String> {
+        if !(1..=5).contains(&stars) {
+            return Err(format!("Invalid rating: {stars}. Must be between 1 and 5"));
+        }
+        if self.is_authored_by(player_uuid) {
+            return Err("Authors cannot rate their own track".to_string());
+        }
+
+        match self
+            .ratings
+            .iter_mut()
+            .find(|rating| rating.player_uuid == player_uuid)
+        {
+            Some(rating) => rating.stars = stars,
+            None => self.ratings.push(TrackRating { player_uuid, stars }),
+        }
+        Ok(())
+    }
+
+    /// Average star rating, `None` until the track has been rated
+    #[must_use]
+    pub fn average_rating(&self) -> Option<f64> {
+        if self.ratings.is_empty() {
+            return None;
+        }
+        let total: u32 = self.ratings.iter().map(|r| u32::from(r.stars)).sum();
+        #[allow(clippy::cast_precision_loss)]
+        let count = self.ratings.len() as f64;
+        Some(f64::from(total) / count)
+    }
+
+    /// Mark the track as a favorite; returns false if it already was
+    pub fn add_favorite(&mut self, player_uuid: Uuid) -> bool {
+        if self.favorited_by.contains(&player_uuid) {
+            return false;
+        }
+        self.favorited_by.push(player_uuid);
+        true
+    }
+
+    /// Remove a favorite; returns false if the player had not favorited the track
+    pub fn remove_favorite(&mut self, player_uuid: Uuid) -> bool {
+        let before = self.favorited_by.len();
+        self.favorited_by.retain(|uuid| *uuid != player_uuid);
+        self.favorited_by.len() != before
+    }
+}
+
+/// Order community tracks for listing
+pub fn sort_track_designs(tracks: &mut [TrackDesign], sort: TrackSort) {
+    let by_rating = |a: &TrackDesign, b: &TrackDesign| {
+        b.average_rating()
+            .unwrap_or(0.0)
+            .total_cmp(&a.average_rating().unwrap_or(0.0))
+            .then_with(|| b.ratings.len().cmp(&a.ratings.len()))
+    };
+
+    match sort {
+        TrackSort::Popular => tracks.sort_by(|a, b| {
+            b.favorited_by
+                .len()
+                .cmp(&a.favorited_by.len())
+                .then_with(|| by_rating(a, b))
+        }),
+        TrackSort::TopRated => tracks.sort_by(by_rating),
+        TrackSort::Newest => tracks.sort_by_key(|track| std::cmp::Reverse(track.created_at)),
+    }
+}
+
+/// Reasons an exported track can be refused on import
+#[derive(Debug, thiserror::Error)]
+pub enum TrackImportError {
+    #[error("Unsupported track format '{0}'")]
+    UnsupportedFormat(String),
+
+    #[error("Unsupported track schema version {0}")]
+    UnsupportedSchemaVersion(u32),
+
+    #[error("Checksum does not match the track content")]
+    ChecksumMismatch,
+
+    #[error("Track definition is invalid")]
+    InvalidTrack(Vec<TrackValidationError>),
+}
+
+impl TrackImportError {
+    /// Error code reported to API clients
+    #[must_use]
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            TrackImportError::UnsupportedFormat(_) => "UNSUPPORTED_FORMAT",
+            TrackImportError::UnsupportedSchemaVersion(_) => "UNSUPPORTED_SCHEMA_VERSION",
+            TrackImportError::ChecksumMismatch => "CHECKSUM_MISMATCH",
+            TrackImportError::InvalidTrack(_) => "INVALID_TRACK",
+        }
+    }
+}
+
+/// Portable JSON representation of a track, used to share tracks between servers
+///
+/// `checksum` is the hex SHA-256 of the schema version, name, description and
+/// sectors serialized as JSON, so accidental edits are caught on import.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct TrackExport {
+    pub format: String,
+    pub schema_version: u32,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub sectors: Vec<Sector>,
+    pub checksum: String,
+}
+
+/// Fields covered by the checksum, in a fixed order
+#[derive(Serialize)]
+struct ChecksumPayload<'a> {
+    schema_version: u32,
+    name: &'a str,
+    description: &'a str,
+    sectors: &'a [Sector],
+}
+
+impl TrackExport {
+    /// Export a stored track design in the current schema version
+    #[must_use]
+    pub fn from_design(design: &TrackDesign) -> Self {
+        let checksum = Self::compute_checksum(
+            TRACK_EXPORT_SCHEMA_VERSION,
+            &design.name,
+            &design.description,
+            &design.sectors,
+        );
+
+        Self {
+            format: TRACK_EXPORT_FORMAT.to_string(),
+            schema_version: TRACK_EXPORT_SCHEMA_VERSION,
+            name: design.name.clone(),
+            description: design.description.clone(),
+            sectors: design.sectors.clone(),
+            checksum,
+        }
+    }
+
+    /// Hex SHA-256 checksum of the exported content
+    #[must_use]
+    pub fn compute_checksum(
+        schema_version: u32,
+        name: &str,
+        description: &str,
+        sectors: &[Sector],
+    ) -> String {
+        let payload = ChecksumPayload {
+            schema_version,
+            name,
+            description,
+            sectors,
+        };
+        let bytes = serde_json::to_vec(&payload).expect("track payload is always serializable");
+        hex::encode(Sha256::digest(bytes))
+    }
+
+    /// Check format, schema version, checksum and sector layout
+    pub fn verify(&self) -> Result<(), TrackImportError> {
+        if self.format != TRACK_EXPORT_FORMAT {
+            return Err(TrackImportError::UnsupportedFormat(self.format.clone()));
+        }
+        if self.schema_version != TRACK_EXPORT_SCHEMA_VERSION {
+            return Err(TrackImportError::UnsupportedSchemaVersion(
+                self.schema_version,
+            ));
+        }
+
+        let expected = Self::compute_checksum(
+            self.schema_version,
+            &self.name,
+            &self.description,
+            &self.sectors,
+        );
+        if !self.checksum.eq_ignore_ascii_case(&expected) {
+            return Err(TrackImportError::ChecksumMismatch);
+        }
+
+        Track::validate_sectors(&self.sectors).map_err(TrackImportError::InvalidTrack)
+    }
+
+    /// Verify the export and turn it into a new track design with a fresh UUID
+    pub fn into_design(self) -> Result<TrackDesign, TrackImportError> {
+        self.verify()?;
+        TrackDesign::new(self.name, self.description, self.sectors, None)
+            .map_err(TrackImportError::InvalidTrack)
+    }
+}
+
+mod uuid_as_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use uuid::Uuid;
+
+    pub fn serialize<S>(uuid: &Uuid, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&uuid.to_string())
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Uuid, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Uuid::parse_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::builtin_track_templates;
+
+    fn create_test_design() -> TrackDesign {
+        let template = &builtin_track_templates()[0];
+        TrackDesign::new(
+            "Shared Track".to_string(),
+            "Exported for testing".to_string(),
+            template.sectors.clone(),
+            Some(Uuid::new_v4()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let design = create_test_design();
+        let export = TrackExport::from_design(&design);
+
+        let json = serde_json::to_string(&export).unwrap();
+        let parsed: TrackExport = serde_json::from_str(&json).unwrap();
+        let imported = parsed.into_design().unwrap();
+
+        assert_ne!(imported.uuid, design.uuid);
+        assert_eq!(imported.name, design.name);
+        assert_eq!(imported.description, design.description);
+        assert_eq!(imported.sectors.len(), design.sectors.len());
+    }
+
+    #[test]
+    fn test_import_rejects_tampered_content() {
+        let mut export = TrackExport::from_design(&create_test_design());
+        export.sectors[1].max_value += 1;
+
+        assert!(matches!(
+            export.verify(),
+            Err(TrackImportError::ChecksumMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_import_rejects_unknown_format_and_version() {
+        let mut export = TrackExport::from_design(&create_test_design());
+        export.schema_version = TRACK_EXPORT_SCHEMA_VERSION + 1;
+        assert!(matches!(
+            export.verify(),
+            Err(TrackImportError::UnsupportedSchemaVersion(_))
+        ));
+
+        export.format = "other-game-track".to_string();
+        assert!(matches!(
+            export.verify(),
+            Err(TrackImportError::UnsupportedFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_import_validates_sectors() {
+        let mut export = TrackExport::from_design(&create_test_design());
+        export.sectors.truncate(1);
+        export.checksum = TrackExport::compute_checksum(
+            export.schema_version,
+            &export.name,
+            &export.description,
+            &export.sectors,
+        );
+
+        let err = export.into_design().unwrap_err();
+        assert_eq!(err.error_code(), "INVALID_TRACK");
+    }
+
+    #[test]
+    fn test_rating_replaces_previous_vote() {
+        let mut design = create_test_design();
+        let player = Uuid::new_v4();
+
+        design.rate(player, 2).unwrap();
+        design.rate(player, 4).unwrap();
+        design.rate(Uuid::new_v4(), 5).unwrap();
+
+        assert_eq!(design.ratings.len(), 2);
+        assert_eq!(design.average_rating(), Some(4.5));
+    }
+
+    #[test]
+    fn test_rating_rules() {
+        let mut design = create_test_design();
+        let author = design.author_uuid.unwrap();
+
+        assert!(design.rate(Uuid::new_v4(), 0).is_err());
+        assert!(design.rate(Uuid::new_v4(), 6).is_err());
+        assert!(design.rate(author, 5).is_err());
+        assert_eq!(design.average_rating(), None);
+    }
+
+    #[test]
+    fn test_favorites_are_unique_per_player() {
+        let mut design = create_test_design();
+        let player = Uuid::new_v4();
+
+        assert!(design.add_favorite(player));
+        assert!(!design.add_favorite(player));
+        assert_eq!(design.favorited_by.len(), 1);
+        assert!(design.remove_favorite(player));
+        assert!(!design.remove_favorite(player));
+    }
+
+    #[test]
+    fn test_popular_sort_prefers_favorites_then_rating() {
+        let mut quiet = create_test_design();
+        quiet.rate(Uuid::new_v4(), 5).unwrap();
+        let mut loved = create_test_design();
+        loved.add_favorite(Uuid::new_v4());
+        loved.add_favorite(Uuid::new_v4());
+        let mut liked = create_test_design();
+        liked.add_favorite(Uuid::new_v4());
+        liked.add_favorite(Uuid::new_v4());
+        liked.rate(Uuid::new_v4(), 1).unwrap();
+
+        let mut tracks = vec![liked.clone(), quiet.clone(), loved.clone()];
+        sort_track_designs(&mut tracks, TrackSort::Popular);
+        assert_eq!(tracks[0].uuid, liked.uuid);
+        assert_eq!(tracks[1].uuid, loved.uuid);
+        assert_eq!(tracks[2].uuid, quiet.uuid);
+
+        sort_track_designs(&mut tracks, TrackSort::TopRated);
+        assert_eq!(tracks[0].uuid, quiet.uuid);
+    }
+}
@@ -0,0 +1,159 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::race::{Sector, SectorType, Track, TrackValidationError};
+
+/// Preset track layout shipped with the server
+/// Stored in the `tracks` collection and looked up by its stable `template_id`
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct TrackTemplate {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub id: Option<mongodb::bson::oid::ObjectId>,
+    /// Stable identifier used by `CreateRaceRequest.template_id`
+    pub template_id: String,
+    pub name: String,
+    pub description: String,
+    /// Lap count the layout was balanced for
+    pub recommended_laps: u32,
+    pub sectors: Vec<Sector>,
+}
+
+impl TrackTemplate {
+    /// Build a race track from this template
+    /// The template name is used unless a custom name is given
+    pub fn to_track(&self, name: Option<String>) -> Result<Track, Vec<TrackValidationError>> {
+        Track::new_validated(
+            name.unwrap_or_else(|| self.name.clone()),
+            self.sectors.clone(),
+        )
+    }
+}
+
+/// Shorthand for declaring template sectors
+fn sector(
+    id: u32,
+    name: &str,
+    min_value: u32,
+    max_value: u32,
+    slot_capacity: Option<u32>,
+    sector_type: SectorType,
+) -> Sector {
+    Sector {
+        id,
+        name: name.to_string(),
+        min_value,
+        max_value,
+        slot_capacity,
+        sector_type,
+        capacity_schedule: Vec::new(),
+        modifiers: None,
+    }
+}
+
+/// Built-in track templates seeded into the database at startup
+///
+/// Thresholds are tuned around starter cars, whose base value usually lands
+/// between 15 and 22 before boosts.
+#[must_use]
+pub fn builtin_track_templates() -> Vec<TrackTemplate> {
+    use SectorType::{Curve, Finish, Start, Straight};
+
+    vec![
+        TrackTemplate {
+            id: None,
+            template_id: "beginner-loop".to_string(),
+            name: "Beginner Loop".to_string(),
+            description: "Short, forgiving loop with roomy sectors for first races".to_string(),
+            recommended_laps: 3,
+            sectors: vec![
+                sector(0, "Grid", 0, 12, None, Start),
+                sector(1, "Long Straight", 10, 18, Some(5), Straight),
+                sector(2, "Wide Hairpin", 14, 21, Some(4), Curve),
+                sector(3, "Finish Line", 18, 26, None, Finish),
+            ],
+        },
+        TrackTemplate {
+            id: None,
+            template_id: "sprint-oval".to_string(),
+            name: "Sprint Oval".to_string(),
+            description: "Fast oval favouring straight-line speed".to_string(),
+            recommended_laps: 5,
+            sectors: vec![
+                sector(0, "Grid", 0, 10, None, Start),
+                sector(1, "Front Straight", 8, 16, Some(4), Straight),
+                sector(2, "Banked Turn 1", 12, 19, Some(3), Curve),
+                sector(3, "Back Straight", 15, 22, Some(4), Straight),
+                sector(4, "Finish Line", 18, 26, None, Finish),
+            ],
+        },
+        TrackTemplate {
+            id: None,
+            template_id: "technical-circuit".to_string(),
+            name: "Technical Circuit".to_string(),
+            description: "Twisty layout with tight sectors that reward cornering".to_string(),
+            recommended_laps: 4,
+            sectors: vec![
+                sector(0, "Grid", 0, 10, None, Start),
+                sector(1, "Chicane", 9, 15, Some(2), Curve),
+                sector(2, "Short Straight", 11, 17, Some(3), Straight),
+                sector(3, "Esses", 13, 19, Some(2), Curve),
+                sector(4, "Hairpin", 15, 21, Some(2), Curve),
+                sector(5, "Pit Straight", 17, 24, Some(3), Straight),
+                sector(6, "Finish Line", 20, 28, None, Finish),
+            ],
+        },
+        TrackTemplate {
+            id: None,
+            template_id: "grand-prix".to_string(),
+            name: "Grand Prix".to_string(),
+            description: "Long, balanced circuit mixing straights and corners".to_string(),
+            recommended_laps: 6,
+            sectors: vec![
+                sector(0, "Grid", 0, 10, None, Start),
+                sector(1, "Main Straight", 8, 15, Some(4), Straight),
+                sector(2, "Turn 1", 10, 17, Some(3), Curve),
+                sector(3, "Sweeper", 12, 18, Some(3), Curve),
+                sector(4, "Back Straight", 14, 20, Some(4), Straight),
+                sector(5, "Turn 5", 15, 22, Some(3), Curve),
+                sector(6, "Kink", 17, 23, Some(3), Straight),
+                sector(7, "Final Corner", 19, 25, Some(2), Curve),
+                sector(8, "Finish Line", 21, 29, None, Finish),
+            ],
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_builtin_templates_are_valid_tracks() {
+        for template in builtin_track_templates() {
+            let track = template.to_track(None);
+            assert!(
+                track.is_ok(),
+                "Template {} is invalid: {:?}",
+                template.template_id,
+                track.err()
+            );
+        }
+    }
+
+    #[test]
+    fn test_builtin_template_ids_are_unique() {
+        let templates = builtin_track_templates();
+        let ids: HashSet<&str> = templates.iter().map(|t| t.template_id.as_str()).collect();
+        assert_eq!(ids.len(), templates.len());
+    }
+
+    #[test]
+    fn test_to_track_uses_custom_name() {
+        let template = &builtin_track_templates()[0];
+        let track = template.to_track(Some("My Track".to_string())).unwrap();
+        assert_eq!(track.name, "My Track");
+        assert_eq!(track.sectors.len(), template.sectors.len());
+    }
+}
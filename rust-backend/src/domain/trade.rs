@@ -0,0 +1,252 @@
+use mongodb::bson::DateTime as BsonDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{Body, Engine, Player};
+
+/// Where a trade offer stands
+///
+/// `Escrowing`, `Settling` and `Cancelling` are only seen while items move, or
+/// when a move was cut short; accepting or cancelling the trade again resumes
+/// it.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+pub enum TradeStatus {
+    /// The offerer's items are moving into escrow
+    Escrowing,
+    /// Waiting for the recipient, with the offerer's items in escrow
+    Open,
+    /// Accepted; both sides' items are changing hands
+    Settling,
+    Completed,
+    /// The offerer's items are going back to them
+    Cancelling,
+    Cancelled,
+}
+
+/// Engines, bodies and credits one side of a trade gives
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, ToSchema)]
+pub struct TradeItems {
+    #[serde(default)]
+    #[schema(value_type = Vec<String>, format = "uuid")]
+    pub engine_uuids: Vec<Uuid>,
+    #[serde(default)]
+    #[schema(value_type = Vec<String>, format = "uuid")]
+    pub body_uuids: Vec<Uuid>,
+    #[serde(default)]
+    pub credits: u64,
+}
+
+impl TradeItems {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.engine_uuids.is_empty() && self.body_uuids.is_empty() && self.credits == 0
+    }
+}
+
+/// Items held by a trade instead of a player
+#[derive(Debug, Serialize, Deserialize, Clone, Default, ToSchema)]
+pub struct Escrow {
+    pub engines: Vec<Engine>,
+    pub bodies: Vec<Body>,
+    pub credits: u64,
+}
+
+impl Escrow {
+    /// Take `items` from `player`; the player is left untouched when any item
+    /// is missing, fitted to a car, or the credits fall short
+    pub fn take(player: &mut Player, items: &TradeItems) -> Result<Self, String> {
+        let fitted = |uuid: Uuid| {
+            player
+                .cars
+                .iter()
+                .any(|car| car.engine_uuid == Some(uuid) || car.body_uuid == Some(uuid))
+        };
+        for &uuid in items.engine_uuids.iter().chain(&items.body_uuids) {
+            if fitted(uuid) {
+                return Err(format!(
+                    "Component {uuid} is fitted to a car and cannot be traded"
+                ));
+            }
+        }
+        for &uuid in &items.engine_uuids {
+            if !player.engines.iter().any(|engine| engine.uuid == uuid) {
+                return Err(format!("Engine {uuid} is not owned by the player"));
+            }
+        }
+        for &uuid in &items.body_uuids {
+            if !player.bodies.iter().any(|body| body.uuid == uuid) {
+                return Err(format!("Body {uuid} is not owned by the player"));
+            }
+        }
+        if player.credits < items.credits {
+            return Err("Not enough credits for the trade".to_string());
+        }
+
+        let (engines, kept_engines) = std::mem::take(&mut player.engines)
+            .into_iter()
+            .partition(|engine| items.engine_uuids.contains(&engine.uuid));
+        player.engines = kept_engines;
+        let (bodies, kept_bodies) = std::mem::take(&mut player.bodies)
+            .into_iter()
+            .partition(|body| items.body_uuids.contains(&body.uuid));
+        player.bodies = kept_bodies;
+        player.credits -= items.credits;
+
+        Ok(Self {
+            engines,
+            bodies,
+            credits: items.credits,
+        })
+    }
+
+    /// Hand the escrowed items to `player`
+    pub fn deliver(self, player: &mut Player) {
+        player.engines.extend(self.engines);
+        player.bodies.extend(self.bodies);
+        player.credits += self.credits;
+    }
+}
+
+/// Offer from one player to swap items with another
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct TradeOffer {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub id: Option<mongodb::bson::oid::ObjectId>,
+    #[serde(with = "uuid_as_string")]
+    #[schema(value_type = String, format = "uuid")]
+    pub uuid: Uuid,
+    #[serde(with = "uuid_as_string")]
+    #[schema(value_type = String, format = "uuid")]
+    pub offerer_uuid: Uuid,
+    #[serde(with = "uuid_as_string")]
+    #[schema(value_type = String, format = "uuid")]
+    pub recipient_uuid: Uuid,
+    /// What the offerer gives
+    pub offered: TradeItems,
+    /// What the offerer wants from the recipient
+    pub requested: TradeItems,
+    /// The offerer's items while the offer is open
+    pub offerer_escrow: Option<Escrow>,
+    /// The recipient's items while the trade settles
+    pub recipient_escrow: Option<Escrow>,
+    pub status: TradeStatus,
+    #[schema(value_type = String, format = "date-time")]
+    pub created_at: BsonDateTime,
+    /// When the trade completed or was cancelled
+    #[schema(value_type = Option<String>, format = "date-time")]
+    pub settled_at: Option<BsonDateTime>,
+}
+
+impl TradeOffer {
+    /// Offer from `offerer_uuid` giving `offered` for the recipient's `requested`
+    #[must_use]
+    pub fn new(
+        offerer_uuid: Uuid,
+        recipient_uuid: Uuid,
+        offered: TradeItems,
+        requested: TradeItems,
+    ) -> Self {
+        Self {
+            id: None,
+            uuid: Uuid::new_v4(),
+            offerer_uuid,
+            recipient_uuid,
+            offered,
+            requested,
+            offerer_escrow: None,
+            recipient_escrow: None,
+            status: TradeStatus::Escrowing,
+            created_at: BsonDateTime::now(),
+            settled_at: None,
+        }
+    }
+
+    #[must_use]
+    pub fn involves(&self, player_uuid: Uuid) -> bool {
+        self.offerer_uuid == player_uuid || self.recipient_uuid == player_uuid
+    }
+}
+
+mod uuid_as_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use uuid::Uuid;
+
+    pub fn serialize<S>(uuid: &Uuid, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&uuid.to_string())
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Uuid, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Uuid::parse_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Email, Password, TeamName};
+    use crate::services::starter_pack::StarterPack;
+
+    fn player(email: &str) -> Player {
+        StarterPack::new()
+            .unwrap()
+            .grant(
+                Email::parse(email).unwrap(),
+                Password::new("TraderPass123".to_string())
+                    .unwrap()
+                    .hash()
+                    .unwrap(),
+                TeamName::parse("Traders").unwrap(),
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn escrow_moves_items_between_players() {
+        let mut offerer = player("offerer@example.com");
+        let mut recipient = player("recipient@example.com");
+        offerer.credits = 150;
+        let engine = offerer.engines[0].clone();
+        offerer.cars[0].unassign_engine();
+        let items = TradeItems {
+            engine_uuids: vec![engine.uuid],
+            body_uuids: Vec::new(),
+            credits: 100,
+        };
+
+        let escrow = Escrow::take(&mut offerer, &items).unwrap();
+        assert!(offerer.engines.iter().all(|e| e.uuid != engine.uuid));
+        assert_eq!(offerer.credits, 50);
+
+        escrow.deliver(&mut recipient);
+        assert!(recipient.engines.iter().any(|e| e.uuid == engine.uuid));
+        assert_eq!(recipient.credits, 100);
+    }
+
+    #[test]
+    fn failed_escrow_leaves_the_player_untouched() {
+        let mut offerer = player("offerer@example.com");
+        let fitted = TradeItems {
+            engine_uuids: vec![offerer.cars[0].engine_uuid.unwrap()],
+            ..TradeItems::default()
+        };
+        let too_expensive = TradeItems {
+            credits: 1,
+            ..TradeItems::default()
+        };
+        let engines_before = offerer.engines.len();
+
+        assert!(Escrow::take(&mut offerer, &fitted).is_err());
+        assert!(Escrow::take(&mut offerer, &too_expensive).is_err());
+        assert_eq!(offerer.engines.len(), engines_before);
+        assert_eq!(offerer.credits, 0);
+    }
+}
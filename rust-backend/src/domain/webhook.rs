@@ -0,0 +1,284 @@
+use mongodb::bson::DateTime as BsonDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::award::RaceAward;
+use super::race::{LapResult, ParticipantMovement, Race, RaceStatus};
+
+/// Race lifecycle event a webhook can subscribe to
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+pub enum WebhookEvent {
+    RaceStarted,
+    LapProcessed,
+    RaceFinished,
+}
+
+impl WebhookEvent {
+    pub const ALL: [Self; 3] = [Self::RaceStarted, Self::LapProcessed, Self::RaceFinished];
+
+    /// Events that turning `before` into `after` raises, in order
+    #[must_use]
+    pub fn between(before: &Race, after: &Race, lap_results: &[LapResult]) -> Vec<Self> {
+        let mut events = Vec::new();
+        if before.status != RaceStatus::InProgress && after.status == RaceStatus::InProgress {
+            events.push(Self::RaceStarted);
+        }
+        if !lap_results.is_empty() {
+            events.push(Self::LapProcessed);
+        }
+        if before.status != RaceStatus::Finished && after.status == RaceStatus::Finished {
+            events.push(Self::RaceFinished);
+        }
+        events
+    }
+}
+
+/// URL registered by a race creator to be called on race events
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Webhook {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<mongodb::bson::oid::ObjectId>,
+    #[serde(with = "uuid_as_string")]
+    pub uuid: Uuid,
+    #[serde(with = "uuid_as_string")]
+    pub race_uuid: Uuid,
+    /// Race creator who registered it
+    #[serde(with = "uuid_as_string")]
+    pub player_uuid: Uuid,
+    pub url: String,
+    /// Key of the HMAC-SHA256 signature sent with each delivery
+    pub secret: String,
+    pub events: Vec<WebhookEvent>,
+    pub created_at: BsonDateTime,
+}
+
+impl Webhook {
+    #[must_use]
+    pub fn new(race_uuid: Uuid, player_uuid: Uuid, url: String, events: Vec<WebhookEvent>) -> Self {
+        use rand::RngCore;
+
+        let mut secret = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut secret);
+        Self {
+            id: None,
+            uuid: Uuid::new_v4(),
+            race_uuid,
+            player_uuid,
+            url,
+            secret: hex::encode(secret),
+            events,
+            created_at: BsonDateTime::now(),
+        }
+    }
+}
+
+/// Where a delivery stands
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+pub enum DeliveryStatus {
+    /// Waiting for its first or next attempt
+    Pending,
+    Delivered,
+    /// Gave up after the last allowed attempt
+    Failed,
+}
+
+/// One event sent, or to be sent, to one webhook
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WebhookDelivery {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<mongodb::bson::oid::ObjectId>,
+    #[serde(with = "uuid_as_string")]
+    pub uuid: Uuid,
+    #[serde(with = "uuid_as_string")]
+    pub webhook_uuid: Uuid,
+    #[serde(with = "uuid_as_string")]
+    pub race_uuid: Uuid,
+    pub event: WebhookEvent,
+    /// JSON body, kept as sent so retries carry the same signature
+    pub payload: String,
+    pub status: DeliveryStatus,
+    pub attempts: u32,
+    pub next_attempt_at: BsonDateTime,
+    /// HTTP status of the last attempt, if the endpoint answered
+    pub last_status_code: Option<u16>,
+    pub last_error: Option<String>,
+    pub created_at: BsonDateTime,
+    pub delivered_at: Option<BsonDateTime>,
+}
+
+impl WebhookDelivery {
+    #[must_use]
+    pub fn new(webhook: &Webhook, event: WebhookEvent, payload: String) -> Self {
+        let now = BsonDateTime::now();
+        Self {
+            id: None,
+            uuid: Uuid::new_v4(),
+            webhook_uuid: webhook.uuid,
+            race_uuid: webhook.race_uuid,
+            event,
+            payload,
+            status: DeliveryStatus::Pending,
+            attempts: 0,
+            next_attempt_at: now,
+            last_status_code: None,
+            last_error: None,
+            created_at: now,
+            delivered_at: None,
+        }
+    }
+}
+
+/// Standing of one participant, as sent to webhooks
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct WebhookStanding {
+    pub player_uuid: String,
+    pub current_sector: u32,
+    pub total_value: u32,
+    pub finish_position: Option<u32>,
+}
+
+/// JSON body posted to webhooks
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct WebhookPayload {
+    pub event: WebhookEvent,
+    pub race_uuid: String,
+    pub race_name: String,
+    pub status: RaceStatus,
+    pub current_lap: u32,
+    pub total_laps: u32,
+    /// Movements of the processed laps; empty for other events
+    pub movements: Vec<ParticipantMovement>,
+    pub standings: Vec<WebhookStanding>,
+    /// Highlights of the race; only set once it finished
+    pub awards: Vec<RaceAward>,
+    pub occurred_at: String,
+}
+
+impl WebhookPayload {
+    #[must_use]
+    pub fn new(event: WebhookEvent, race: &Race, lap_results: &[LapResult]) -> Self {
+        let movements = if event == WebhookEvent::LapProcessed {
+            lap_results
+                .iter()
+                .flat_map(|lap_result| lap_result.movements.iter().cloned())
+                .collect()
+        } else {
+            Vec::new()
+        };
+        Self {
+            event,
+            race_uuid: race.uuid.to_string(),
+            race_name: race.name.clone(),
+            status: race.status.clone(),
+            current_lap: race.current_lap,
+            total_laps: race.total_laps,
+            movements,
+            standings: race
+                .participants
+                .iter()
+                .map(|participant| WebhookStanding {
+                    player_uuid: participant.player_uuid.to_string(),
+                    current_sector: participant.current_sector,
+                    total_value: participant.total_value,
+                    finish_position: participant.finish_position,
+                })
+                .collect(),
+            awards: race.awards.clone(),
+            occurred_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+mod uuid_as_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use uuid::Uuid;
+
+    pub fn serialize<S>(uuid: &Uuid, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&uuid.to_string())
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Uuid, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Uuid::parse_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Sector, SectorType, Track};
+
+    fn race() -> Race {
+        let sectors = vec![
+            Sector {
+                id: 0,
+                name: "Start".to_string(),
+                min_value: 0,
+                max_value: 10,
+                slot_capacity: None,
+                sector_type: SectorType::Start,
+                capacity_schedule: Vec::new(),
+                modifiers: None,
+            },
+            Sector {
+                id: 1,
+                name: "Finish".to_string(),
+                min_value: 10,
+                max_value: 20,
+                slot_capacity: None,
+                sector_type: SectorType::Finish,
+                capacity_schedule: Vec::new(),
+                modifiers: None,
+            },
+        ];
+        let track = Track::new("Track".to_string(), sectors).unwrap();
+        Race::new("Hooked".to_string(), track, 2)
+    }
+
+    fn lap_result() -> LapResult {
+        LapResult {
+            lap: 1,
+            lap_characteristic: crate::domain::LapCharacteristic::Straight,
+            sector_positions: std::collections::HashMap::new(),
+            movements: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn starting_a_race_raises_race_started() {
+        let before = race();
+        let mut after = before.clone();
+        after.status = RaceStatus::InProgress;
+
+        assert_eq!(
+            WebhookEvent::between(&before, &after, &[]),
+            vec![WebhookEvent::RaceStarted]
+        );
+    }
+
+    #[test]
+    fn final_lap_raises_lap_processed_then_race_finished() {
+        let mut before = race();
+        before.status = RaceStatus::InProgress;
+        let mut after = before.clone();
+        after.status = RaceStatus::Finished;
+
+        assert_eq!(
+            WebhookEvent::between(&before, &after, &[lap_result()]),
+            vec![WebhookEvent::LapProcessed, WebhookEvent::RaceFinished]
+        );
+    }
+
+    #[test]
+    fn joining_raises_nothing() {
+        let before = race();
+        assert!(WebhookEvent::between(&before, &before.clone(), &[]).is_empty());
+    }
+}
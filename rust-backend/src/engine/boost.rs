@@ -0,0 +1,46 @@
+use crate::domain::boost_hand_manager::BoostHandManager;
+use crate::domain::{BoostUsageRecord, LapCharacteristic, RaceParticipant};
+
+/// Highest boost card in a hand
+pub const MAX_BOOST_VALUE: u32 = 4;
+
+/// Check that a boost value matches a card of the hand
+pub fn validate_boost_value(boost_value: u32) -> Result<(), String> {
+    if boost_value > MAX_BOOST_VALUE {
+        return Err(format!(
+            "Invalid boost value: {boost_value}. Must be between 0 and {MAX_BOOST_VALUE}"
+        ));
+    }
+    Ok(())
+}
+
+/// Use a boost card from a participant's hand and record it in their history
+pub fn spend_card(
+    participant: &mut RaceParticipant,
+    lap_number: u32,
+    lap_characteristic: &LapCharacteristic,
+    boost_value: u32,
+) -> Result<(), String> {
+    validate_boost_value(boost_value)?;
+
+    #[allow(clippy::cast_possible_truncation)]
+    let boost_value_u8 = boost_value as u8;
+
+    // Record the cycle number BEFORE using the card (since replenishment increments it)
+    let cycle_before_use = participant.boost_hand.current_cycle;
+
+    let boost_usage_result =
+        BoostHandManager::use_boost_card(&mut participant.boost_hand, boost_value_u8)
+            .map_err(|e| e.to_string())?;
+
+    participant.boost_usage_history.push(BoostUsageRecord {
+        lap_number,
+        boost_value: boost_value_u8,
+        cycle_number: cycle_before_use,
+        cards_remaining_after: boost_usage_result.cards_remaining,
+        replenishment_occurred: boost_usage_result.replenishment_occurred,
+        lap_characteristic: Some(lap_characteristic.clone()),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,12 @@
+//! Game rules for resolving a lap, free of persistence
+//!
+//! Everything here works on plain race state (sectors, participants, car
+//! values) so it can be shared by `Race`, the simulator and benchmarks.
+
+mod boost;
+mod movement;
+mod performance;
+
+pub use boost::*;
+pub use movement::*;
+pub use performance::*;
@@ -0,0 +1,647 @@
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use crate::domain::{
+    MovementStep, MovementType, ParticipantMovement, RaceParticipant, Sector, SectorType,
+};
+
+/// Cars on a track while a lap is being resolved
+pub struct Grid<'a> {
+    pub sectors: &'a [Sector],
+    pub participants: &'a mut [RaceParticipant],
+    /// Race lap being resolved, used for scheduled sector capacities
+    pub current_lap: u32,
+    /// Laps a car must complete to finish
+    pub total_laps: u32,
+}
+
+impl Grid<'_> {
+    /// Move every car that has a lap value, best sector first
+    ///
+    /// Cars below the sector minimum drop to the nearest sector with room. Only
+    /// the best car of a sector may move up, and only if it beats the sector
+    /// maximum and the next sector has room. Each movement lists the steps
+    /// that led to it: the car's rank, the capacity checks and the move.
+    pub fn resolve_movements(
+        &mut self,
+        participant_values: &HashMap<Uuid, u32>,
+    ) -> Vec<ParticipantMovement> {
+        let mut movements = Vec::new();
+
+        #[allow(clippy::cast_possible_truncation)]
+        let max_sector = (self.sectors.len() - 1) as u32;
+
+        for sector_id in (0..=max_sector).rev() {
+            movements.extend(self.sector_movements(sector_id, participant_values));
+        }
+
+        movements
+    }
+
+    /// Add each car's lap value to its running total
+    pub fn add_lap_values(&mut self, participant_values: &HashMap<Uuid, u32>) {
+        for participant in self.participants.iter_mut().filter(|p| !p.is_finished) {
+            if let Some(&final_value) = participant_values.get(&participant.player_uuid) {
+                participant.total_value += final_value;
+            }
+        }
+    }
+
+    /// Rank the cars in each sector by total value (highest first)
+    pub fn sort_positions(&mut self) {
+        let mut sector_groups: HashMap<u32, Vec<&mut RaceParticipant>> = HashMap::new();
+
+        for participant in self.participants.iter_mut() {
+            if !participant.is_finished {
+                sector_groups
+                    .entry(participant.current_sector)
+                    .or_default()
+                    .push(participant);
+            }
+        }
+
+        for participants in sector_groups.values_mut() {
+            participants.sort_by_key(|p| std::cmp::Reverse(p.total_value));
+
+            for (index, participant) in participants.iter_mut().enumerate() {
+                #[allow(clippy::cast_possible_truncation)]
+                {
+                    participant.current_position_in_sector = index as u32;
+                }
+            }
+        }
+    }
+
+    /// Spread the cars over the track following `order`, front first
+    ///
+    /// The front of the order starts in the furthest sectors and the back in
+    /// the first one, leaving the finish sector empty. A car whose sector is
+    /// full drops back to the nearest sector with room. Cars missing from
+    /// `order` line up behind the others.
+    pub fn line_up(&mut self, order: &[Uuid]) {
+        let mut ranked: Vec<usize> = (0..self.participants.len()).collect();
+        ranked.sort_by_key(|&index| {
+            let player_uuid = self.participants[index].player_uuid;
+            order
+                .iter()
+                .position(|uuid| *uuid == player_uuid)
+                .unwrap_or(order.len())
+        });
+
+        #[allow(clippy::cast_possible_truncation)]
+        let starting_sectors: Vec<u32> = self
+            .sectors
+            .iter()
+            .enumerate()
+            .filter(|(_, sector)| !matches!(sector.sector_type, SectorType::Finish))
+            .map(|(sector_id, _)| sector_id as u32)
+            .collect();
+        if starting_sectors.is_empty() {
+            return;
+        }
+
+        let car_count = ranked.len();
+        let mut placed: HashMap<u32, u32> = HashMap::new();
+        for (rank, &participant_index) in ranked.iter().enumerate() {
+            let spread = (car_count - 1 - rank) * starting_sectors.len() / car_count;
+            let sector_id = starting_sectors[..=spread]
+                .iter()
+                .rev()
+                .copied()
+                .find(|&sector_id| {
+                    match self.sectors[sector_id as usize].capacity_for_lap(self.current_lap) {
+                        None => true,
+                        Some(capacity) => placed.get(&sector_id).copied().unwrap_or(0) < capacity,
+                    }
+                })
+                .unwrap_or(starting_sectors[0]);
+
+            let position = placed.entry(sector_id).or_insert(0);
+            let participant = &mut self.participants[participant_index];
+            participant.current_sector = sector_id;
+            participant.current_position_in_sector = *position;
+            *position += 1;
+        }
+    }
+
+    fn sector_movements(
+        &mut self,
+        sector_id: u32,
+        participant_values: &HashMap<Uuid, u32>,
+    ) -> Vec<ParticipantMovement> {
+        let mut participants_in_sector: Vec<(usize, u32)> = self
+            .participants
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.current_sector == sector_id && !p.is_finished)
+            .filter_map(|(i, p)| {
+                participant_values
+                    .get(&p.player_uuid)
+                    .map(|&value| (i, value))
+            })
+            .collect();
+
+        // Sort by performance value (highest first) - this determines ranking
+        participants_in_sector.sort_by_key(|&(_, value)| std::cmp::Reverse(value));
+
+        #[allow(clippy::cast_possible_truncation)]
+        let cars_in_sector = participants_in_sector.len() as u32;
+        (1..)
+            .zip(participants_in_sector)
+            .map(|(rank, (participant_index, final_value))| {
+                let mut movement =
+                    self.movement_for(participant_index, final_value, sector_id, rank == 1);
+                movement.steps.insert(
+                    0,
+                    MovementStep::Ranked {
+                        sector: sector_id,
+                        rank,
+                        cars_in_sector,
+                    },
+                );
+                movement
+            })
+            .collect()
+    }
+
+    fn movement_for(
+        &mut self,
+        participant_index: usize,
+        final_value: u32,
+        sector_id: u32,
+        is_first_ranked: bool,
+    ) -> ParticipantMovement {
+        let Some(sector) = self.sectors.get(sector_id as usize) else {
+            // Invalid sector - shouldn't happen
+            return self.stay(participant_index, sector_id, final_value);
+        };
+
+        if final_value < sector.min_value {
+            // Any car can move down if performance is too low
+            self.move_down(participant_index, sector_id, final_value)
+        } else if final_value > sector.max_value && is_first_ranked {
+            // Only the first-ranked car can move up
+            self.move_up(participant_index, sector_id, final_value)
+        } else {
+            self.stay(participant_index, sector_id, final_value)
+        }
+    }
+
+    fn move_down(
+        &mut self,
+        participant_index: usize,
+        from_sector: u32,
+        final_value: u32,
+    ) -> ParticipantMovement {
+        if from_sector == 0 {
+            // Already in lowest sector, can't move down
+            return self.stay(participant_index, from_sector, final_value);
+        }
+
+        // The first sector has infinite capacity, so the search always ends there
+        let mut steps = Vec::new();
+        let target_sector = (1..from_sector)
+            .rev()
+            .find(|&sector_id| {
+                let has_room = self.has_room(sector_id, participant_index);
+                steps.push(MovementStep::CapacityChecked {
+                    sector: sector_id,
+                    has_room,
+                });
+                has_room
+            })
+            .unwrap_or(0);
+        steps.push(MovementStep::Displaced {
+            from_sector,
+            to_sector: target_sector,
+        });
+
+        let participant = &mut self.participants[participant_index];
+        participant.current_sector = target_sector;
+        // Place at last position until the sector is re-ranked
+        participant.current_position_in_sector = u32::MAX;
+
+        ParticipantMovement {
+            player_uuid: participant.player_uuid,
+            from_sector,
+            to_sector: target_sector,
+            final_value,
+            movement_type: MovementType::MovedDown,
+            steps,
+        }
+    }
+
+    fn move_up(
+        &mut self,
+        participant_index: usize,
+        from_sector: u32,
+        final_value: u32,
+    ) -> ParticipantMovement {
+        let next_sector = from_sector + 1;
+
+        #[allow(clippy::cast_possible_truncation)]
+        if next_sector >= self.sectors.len() as u32 {
+            // Completed a lap
+            let participant = &mut self.participants[participant_index];
+            participant.current_lap += 1;
+
+            if participant.current_lap > self.total_laps {
+                participant.is_finished = true;
+                return ParticipantMovement {
+                    player_uuid: participant.player_uuid,
+                    from_sector,
+                    to_sector: from_sector,
+                    final_value,
+                    movement_type: MovementType::FinishedRace,
+                    steps: Vec::new(),
+                };
+            }
+
+            // Start new lap - go back to sector 0
+            participant.current_sector = 0;
+            return ParticipantMovement {
+                player_uuid: participant.player_uuid,
+                from_sector,
+                to_sector: 0,
+                final_value,
+                movement_type: MovementType::FinishedLap,
+                steps: vec![MovementStep::Displaced {
+                    from_sector,
+                    to_sector: 0,
+                }],
+            };
+        }
+
+        let has_room = self.has_room(next_sector, participant_index);
+        let checked = MovementStep::CapacityChecked {
+            sector: next_sector,
+            has_room,
+        };
+        if !has_room {
+            // Sector is full, stay in current sector
+            let mut movement = self.stay(participant_index, from_sector, final_value);
+            movement.steps.push(checked);
+            return movement;
+        }
+
+        let participant = &mut self.participants[participant_index];
+        participant.current_sector = next_sector;
+        ParticipantMovement {
+            player_uuid: participant.player_uuid,
+            from_sector,
+            to_sector: next_sector,
+            final_value,
+            movement_type: MovementType::MovedUp,
+            steps: vec![
+                checked,
+                MovementStep::Displaced {
+                    from_sector,
+                    to_sector: next_sector,
+                },
+            ],
+        }
+    }
+
+    /// Whether a sector can take one more car this lap, not counting the moving car
+    fn has_room(&self, sector_id: u32, participant_index: usize) -> bool {
+        match self.sectors[sector_id as usize].capacity_for_lap(self.current_lap) {
+            None => true,
+            Some(capacity) => {
+                let current_count = self
+                    .participants
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, p)| {
+                        *i != participant_index && p.current_sector == sector_id && !p.is_finished
+                    })
+                    .count();
+                current_count < capacity as usize
+            }
+        }
+    }
+
+    fn stay(
+        &self,
+        participant_index: usize,
+        sector_id: u32,
+        final_value: u32,
+    ) -> ParticipantMovement {
+        ParticipantMovement {
+            player_uuid: self.participants[participant_index].player_uuid,
+            from_sector: sector_id,
+            to_sector: sector_id,
+            final_value,
+            movement_type: MovementType::StayedInSector,
+            steps: Vec::new(),
+        }
+    }
+}
+
+/// Active cars grouped by sector (as string keys), ordered by position
+#[must_use]
+pub fn sector_positions(participants: &[RaceParticipant]) -> HashMap<String, Vec<RaceParticipant>> {
+    let mut positions: HashMap<String, Vec<RaceParticipant>> = HashMap::new();
+
+    for participant in participants.iter().filter(|p| !p.is_finished) {
+        positions
+            .entry(participant.current_sector.to_string())
+            .or_default()
+            .push(participant.clone());
+    }
+
+    for participants in positions.values_mut() {
+        participants.sort_by_key(|p| p.current_position_in_sector);
+    }
+
+    positions
+}
+
+/// Number every car from 1 by final standing
+///
+/// Finished cars come first, then the furthest sector, the best position in
+/// that sector and finally the highest total value.
+pub fn assign_finish_positions(participants: &mut [RaceParticipant]) {
+    let mut standings: Vec<&mut RaceParticipant> = participants.iter_mut().collect();
+
+    standings.sort_by(|a, b| {
+        b.is_finished
+            .cmp(&a.is_finished)
+            .then_with(|| b.current_sector.cmp(&a.current_sector))
+            .then_with(|| {
+                a.current_position_in_sector
+                    .cmp(&b.current_position_in_sector)
+            })
+            .then_with(|| b.total_value.cmp(&a.total_value))
+    });
+
+    for (index, participant) in standings.iter_mut().enumerate() {
+        #[allow(clippy::cast_possible_truncation)]
+        {
+            participant.finish_position = Some(index as u32 + 1);
+        }
+    }
+}
+
+/// Number the cars of each car class from 1 by their overall finish position
+///
+/// Participants without a class keep no class position.
+pub fn assign_class_positions(participants: &mut [RaceParticipant]) {
+    let mut classed: Vec<&mut RaceParticipant> = participants
+        .iter_mut()
+        .filter(|p| p.car_class.is_some())
+        .collect();
+    classed.sort_by_key(|p| p.finish_position.unwrap_or(u32::MAX));
+
+    let mut next_position: HashMap<String, u32> = HashMap::new();
+    for participant in classed {
+        let Some(class) = participant.car_class.clone() else {
+            continue;
+        };
+        let position = next_position.entry(class).or_insert(1);
+        participant.class_position = Some(*position);
+        *position += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::BoostHand;
+    use proptest::prelude::*;
+
+    fn create_test_sectors() -> Vec<Sector> {
+        let sector = |id: u32, min_value, max_value, slot_capacity, sector_type| Sector {
+            id,
+            name: format!("Sector {id}"),
+            min_value,
+            max_value,
+            slot_capacity,
+            sector_type,
+            capacity_schedule: Vec::new(),
+            modifiers: None,
+        };
+        vec![
+            sector(0, 0, 10, None, SectorType::Start),
+            sector(1, 8, 15, Some(2), SectorType::Straight),
+            sector(2, 12, 20, Some(1), SectorType::Curve),
+            sector(3, 18, 25, None, SectorType::Finish),
+        ]
+    }
+
+    fn create_participant(current_sector: u32) -> RaceParticipant {
+        RaceParticipant {
+            player_uuid: Uuid::new_v4(),
+            car_uuid: Uuid::new_v4(),
+            pilot_uuid: Uuid::new_v4(),
+            current_sector,
+            current_position_in_sector: 0,
+            current_lap: 1,
+            total_value: 0,
+            is_finished: false,
+            finish_position: None,
+            boost_hand: BoostHand::new(),
+            boost_usage_history: Vec::new(),
+            bot: None,
+            ghost: None,
+            ballast: 0,
+            car_class: None,
+            class_position: None,
+            is_loaner: false,
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn limited_sectors_never_overflow(
+            starts in prop::collection::vec(0u32..4, 1..12),
+            values in prop::collection::vec(0u32..30, 12),
+        ) {
+            let sectors = create_test_sectors();
+            // Start from a layout that respects capacities
+            let mut participants: Vec<RaceParticipant> = starts
+                .iter()
+                .map(|&sector| create_participant(if sector == 1 || sector == 2 { 0 } else { sector }))
+                .collect();
+            let participant_values: HashMap<Uuid, u32> = participants
+                .iter()
+                .zip(&values)
+                .map(|(p, &value)| (p.player_uuid, value))
+                .collect();
+
+            let mut grid = Grid {
+                sectors: &sectors,
+                participants: &mut participants,
+                current_lap: 1,
+                total_laps: 3,
+            };
+            grid.resolve_movements(&participant_values);
+
+            for sector in &sectors {
+                if let Some(capacity) = sector.slot_capacity {
+                    let count = participants
+                        .iter()
+                        .filter(|p| p.current_sector == sector.id && !p.is_finished)
+                        .count();
+                    prop_assert!(count <= capacity as usize);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn only_the_best_car_moves_up() {
+        let sectors = create_test_sectors();
+        let mut participants = vec![create_participant(0), create_participant(0)];
+        let participant_values = HashMap::from([
+            (participants[0].player_uuid, 14),
+            (participants[1].player_uuid, 12),
+        ]);
+
+        let mut grid = Grid {
+            sectors: &sectors,
+            participants: &mut participants,
+            current_lap: 1,
+            total_laps: 3,
+        };
+        grid.resolve_movements(&participant_values);
+
+        assert_eq!(participants[0].current_sector, 1);
+        assert_eq!(participants[1].current_sector, 0);
+    }
+
+    #[test]
+    fn movements_list_the_steps_that_led_to_them() {
+        let sectors = create_test_sectors();
+        let mut participants = vec![
+            create_participant(1),
+            create_participant(1),
+            create_participant(2),
+        ];
+        let participant_values = HashMap::from([
+            (participants[0].player_uuid, 17),
+            (participants[1].player_uuid, 16),
+            (participants[2].player_uuid, 5),
+        ]);
+
+        let mut grid = Grid {
+            sectors: &sectors,
+            participants: &mut participants,
+            current_lap: 1,
+            total_laps: 3,
+        };
+        let movements = grid.resolve_movements(&participant_values);
+
+        // The car in sector 2 drops first, past full sector 1, to sector 0
+        assert_eq!(
+            movements[0].steps,
+            vec![
+                MovementStep::Ranked {
+                    sector: 2,
+                    rank: 1,
+                    cars_in_sector: 1
+                },
+                MovementStep::CapacityChecked {
+                    sector: 1,
+                    has_room: false
+                },
+                MovementStep::Displaced {
+                    from_sector: 2,
+                    to_sector: 0
+                },
+            ]
+        );
+        // Its place frees sector 2 for the best car of sector 1
+        assert_eq!(
+            movements[1].steps,
+            vec![
+                MovementStep::Ranked {
+                    sector: 1,
+                    rank: 1,
+                    cars_in_sector: 2
+                },
+                MovementStep::CapacityChecked {
+                    sector: 2,
+                    has_room: true
+                },
+                MovementStep::Displaced {
+                    from_sector: 1,
+                    to_sector: 2
+                },
+            ]
+        );
+        assert_eq!(
+            movements[2].steps,
+            vec![MovementStep::Ranked {
+                sector: 1,
+                rank: 2,
+                cars_in_sector: 2
+            }]
+        );
+    }
+
+    #[test]
+    fn line_up_drops_back_when_a_sector_is_full() {
+        let sectors = create_test_sectors();
+        let mut participants = vec![create_participant(0), create_participant(0)];
+        participants[0].current_sector = 3;
+        let order = [participants[0].player_uuid, participants[1].player_uuid];
+
+        let mut grid = Grid {
+            sectors: &sectors,
+            participants: &mut participants,
+            current_lap: 1,
+            total_laps: 3,
+        };
+        grid.line_up(&order);
+
+        // Two cars over three starting sectors put the front car in sector 1
+        assert_eq!(participants[0].current_sector, 1);
+        assert_eq!(participants[1].current_sector, 0);
+
+        let mut participants: Vec<RaceParticipant> =
+            (0..6).map(|_| create_participant(0)).collect();
+        let order: Vec<Uuid> = participants.iter().map(|p| p.player_uuid).collect();
+        let mut grid = Grid {
+            sectors: &sectors,
+            participants: &mut participants,
+            current_lap: 1,
+            total_laps: 3,
+        };
+        grid.line_up(&order);
+
+        let sectors_taken: Vec<u32> = participants.iter().map(|p| p.current_sector).collect();
+        assert_eq!(sectors_taken, vec![2, 1, 1, 0, 0, 0]);
+        assert_eq!(participants[2].current_position_in_sector, 1);
+    }
+
+    #[test]
+    fn finish_positions_rank_furthest_car_first() {
+        let mut participants = vec![create_participant(1), create_participant(3)];
+        participants[0].total_value = 50;
+
+        assign_finish_positions(&mut participants);
+
+        assert_eq!(participants[1].finish_position, Some(1));
+        assert_eq!(participants[0].finish_position, Some(2));
+    }
+
+    #[test]
+    fn class_positions_rank_cars_within_their_class() {
+        let mut participants = vec![
+            create_participant(3),
+            create_participant(2),
+            create_participant(1),
+            create_participant(0),
+        ];
+        for (participant, class) in participants.iter_mut().zip(["Pro", "Am", "Pro"]) {
+            participant.car_class = Some(class.to_string());
+        }
+
+        assign_finish_positions(&mut participants);
+        assign_class_positions(&mut participants);
+
+        let positions: Vec<Option<u32>> = participants.iter().map(|p| p.class_position).collect();
+        assert_eq!(positions, vec![Some(1), Some(1), Some(2), None]);
+    }
+}
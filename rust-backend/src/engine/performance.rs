@@ -0,0 +1,179 @@
+use crate::domain::performance_model::PerformanceModel;
+use crate::domain::{
+    CarLapValues, PerformanceCalculation, RaceParticipant, Sector, SectorType, DRS_BONUS,
+};
+
+/// Whether another active car is exactly one position ahead in the same sector
+#[must_use]
+pub fn has_drs(participants: &[RaceParticipant], participant: &RaceParticipant) -> bool {
+    participants.iter().any(|other| {
+        other.player_uuid != participant.player_uuid
+            && !other.is_finished
+            && other.current_sector == participant.current_sector
+            && other.current_position_in_sector + 1 == participant.current_position_in_sector
+    })
+}
+
+/// Sectors between a car and the furthest active car, counting completed laps
+#[must_use]
+pub fn distance_to_leader(
+    participants: &[RaceParticipant],
+    sector_count: usize,
+    participant: &RaceParticipant,
+) -> u32 {
+    #[allow(clippy::cast_possible_truncation)]
+    let progress = |p: &RaceParticipant| {
+        p.current_lap.saturating_sub(1) * sector_count as u32 + p.current_sector
+    };
+    participants
+        .iter()
+        .filter(|other| !other.is_finished)
+        .map(progress)
+        .max()
+        .map_or(0, |leader| leader.saturating_sub(progress(participant)))
+}
+
+/// Apply a boost to a capped base value, including the effects of the sector
+///
+/// Chicanes halve the gain from the boost card. In a DRS zone a car running
+/// directly behind another one gets `DRS_BONUS` on top of its boosted value.
+#[must_use]
+pub fn boosted_value(
+    model: &dyn PerformanceModel,
+    sector: &Sector,
+    capped_base_value: u32,
+    boost_value: u32,
+    drs: bool,
+) -> u32 {
+    let boosted = model.apply_boost(capped_base_value, boost_value);
+
+    match sector.sector_type {
+        SectorType::Chicane => capped_base_value + boosted.saturating_sub(capped_base_value) / 2,
+        SectorType::DrsZone if drs => boosted + DRS_BONUS,
+        _ => boosted,
+    }
+}
+
+/// Full lap value of a car in a sector
+///
+/// Sector modifiers scale the engine and body values, the success ballast is
+/// taken off their sum with the pilot value, the result is capped by the
+/// sector ceiling, then the boost and sector effects are applied.
+#[must_use]
+pub fn calculate_performance(
+    model: &dyn PerformanceModel,
+    sector: &Sector,
+    values: CarLapValues,
+    boost_value: u32,
+    ballast: u32,
+    drs: bool,
+) -> PerformanceCalculation {
+    let (engine_value, body_value) = sector.apply_modifiers(values.engine, values.body);
+    let base_value = (engine_value + body_value + values.pilot).saturating_sub(ballast);
+    let capped_base_value = model.cap_base_value(base_value, sector);
+
+    PerformanceCalculation {
+        engine_contribution: engine_value,
+        body_contribution: body_value,
+        pilot_contribution: values.pilot,
+        ballast,
+        base_value,
+        sector_ceiling: sector.max_value,
+        capped_base_value,
+        boost_value,
+        catch_up_bonus: 0,
+        final_value: boosted_value(model, sector, capped_base_value, boost_value, drs),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::PerformanceModelKind;
+    use proptest::prelude::*;
+
+    fn sector(min_value: u32, max_value: u32, sector_type: SectorType) -> Sector {
+        Sector {
+            id: 1,
+            name: "Sector".to_string(),
+            min_value,
+            max_value,
+            slot_capacity: Some(3),
+            sector_type,
+            capacity_schedule: Vec::new(),
+            modifiers: None,
+        }
+    }
+
+    fn model_kind() -> impl Strategy<Value = PerformanceModelKind> {
+        prop_oneof![
+            Just(PerformanceModelKind::Additive),
+            Just(PerformanceModelKind::Multiplicative),
+            Just(PerformanceModelKind::DiminishingReturns),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn capped_base_never_exceeds_ceiling(
+            kind in model_kind(),
+            engine in 0u32..20,
+            body in 0u32..20,
+            pilot in 0u32..20,
+            max_value in 1u32..40,
+            boost in 0u32..=4,
+        ) {
+            let sector = sector(0, max_value, SectorType::Straight);
+            let values = CarLapValues { engine, body, pilot };
+            let performance = calculate_performance(kind.model(), &sector, values, boost, 0, false);
+
+            prop_assert!(performance.capped_base_value <= max_value);
+            prop_assert!(performance.final_value >= performance.capped_base_value);
+        }
+
+        #[test]
+        fn higher_boost_never_lowers_final_value(
+            kind in model_kind(),
+            capped in 0u32..40,
+            boost in 0u32..4,
+            chicane in any::<bool>(),
+        ) {
+            let sector_type = if chicane { SectorType::Chicane } else { SectorType::Straight };
+            let sector = sector(0, 40, sector_type);
+            let model = kind.model();
+
+            prop_assert!(
+                boosted_value(model, &sector, capped, boost + 1, false)
+                    >= boosted_value(model, &sector, capped, boost, false)
+            );
+        }
+    }
+
+    #[test]
+    fn drs_bonus_only_applies_in_drs_zones() {
+        let model = PerformanceModelKind::Additive.model();
+        let drs_zone = sector(0, 40, SectorType::DrsZone);
+        let straight = sector(0, 40, SectorType::Straight);
+
+        assert_eq!(boosted_value(model, &drs_zone, 10, 1, true), 11 + DRS_BONUS);
+        assert_eq!(boosted_value(model, &straight, 10, 1, true), 11);
+    }
+
+    #[test]
+    fn ballast_comes_off_the_base_value_before_the_ceiling() {
+        let model = PerformanceModelKind::Additive.model();
+        let straight = sector(0, 12, SectorType::Straight);
+        let values = CarLapValues {
+            engine: 6,
+            body: 5,
+            pilot: 4,
+        };
+
+        let performance = calculate_performance(model, &straight, values, 2, 4, false);
+
+        assert_eq!(performance.ballast, 4);
+        assert_eq!(performance.base_value, 11);
+        assert_eq!(performance.capped_base_value, 11);
+        assert_eq!(performance.final_value, 13);
+    }
+}
@@ -0,0 +1,89 @@
+//! GraphQL mirrors of the domain enums, which are not `Copy` as async-graphql
+//! requires; `remote` keeps the variants in step with the originals.
+
+use async_graphql::Enum;
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+#[graphql(remote = "crate::domain::RaceStatus")]
+pub enum RaceStatus {
+    Waiting,
+    Starting,
+    InProgress,
+    Finished,
+    Cancelled,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+#[graphql(remote = "crate::domain::RaceMode")]
+pub enum RaceMode {
+    Standard,
+    Practice,
+    TimeTrial,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+#[graphql(remote = "crate::domain::LapCharacteristic")]
+pub enum LapCharacteristic {
+    Straight,
+    Curve,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+#[graphql(remote = "crate::domain::SectorType")]
+pub enum SectorType {
+    Start,
+    Straight,
+    Curve,
+    Finish,
+    Chicane,
+    PitLane,
+    DrsZone,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+#[graphql(remote = "crate::domain::MovementType")]
+pub enum MovementType {
+    StayedInSector,
+    MovedUp,
+    MovedDown,
+    FinishedLap,
+    FinishedRace,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+#[graphql(remote = "crate::domain::MovementProbability")]
+pub enum MovementProbability {
+    MoveUp,
+    Stay,
+    MoveDown,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+#[graphql(remote = "crate::domain::Emote")]
+pub enum Emote {
+    ThumbsUp,
+    Fire,
+    Wow,
+    Laugh,
+    Oops,
+    GoodGame,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum TurnPhase {
+    WaitingForPlayers,
+    AllSubmitted,
+    Processing,
+    TurnProcessed,
+    Complete,
+}
+
+/// `RaceStatusType` without the message of its `Error` variant
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum RaceProgressState {
+    Waiting,
+    Starting,
+    Ongoing,
+    Finished,
+    Error,
+}
@@ -0,0 +1,198 @@
+//! GraphQL API next to the REST routes
+//!
+//! Queries resolve races, participants, tracks and player stats field by
+//! field; subscriptions follow the race event log. Nothing is written through
+//! GraphQL, so the schema has no mutations. The routes sit behind the same
+//! authentication as the REST race reads, and races are shown as the
+//! authenticated player sees them.
+
+mod enums;
+mod query;
+mod subscription;
+mod types;
+
+pub use query::QueryRoot;
+pub use subscription::{RaceChangeEvent, SubscriptionRoot};
+pub use types::{PlayerNode, PlayerStats, RaceNode};
+
+use async_graphql::{
+    http::{
+        playground_source, GraphQLPlaygroundConfig, WebSocketProtocols, WsMessage,
+        ALL_WEBSOCKET_PROTOCOLS,
+    },
+    EmptyMutation, ErrorExtensions, Schema,
+};
+use axum::{
+    extract::ws::{CloseFrame, Message, WebSocket, WebSocketUpgrade},
+    http::{header, HeaderMap, StatusCode},
+    response::{Html, IntoResponse, Json, Response},
+    routing::get,
+    Extension, Router,
+};
+use futures_util::{future::ready, SinkExt, StreamExt};
+use mongodb::Database;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::middleware::UserContext;
+
+pub type BoarduranceSchema = Schema<QueryRoot, EmptyMutation, SubscriptionRoot>;
+
+/// Deepest selection a query may nest
+const MAX_QUERY_DEPTH: usize = 8;
+/// Most fields a query may select, counted over the whole document
+const MAX_QUERY_COMPLEXITY: usize = 500;
+
+#[must_use]
+pub fn build_schema(database: Database) -> BoarduranceSchema {
+    Schema::build(QueryRoot, EmptyMutation, SubscriptionRoot)
+        .data(database)
+        .limit_depth(MAX_QUERY_DEPTH)
+        .limit_complexity(MAX_QUERY_COMPLEXITY)
+        .finish()
+}
+
+pub fn routes(schema: BoarduranceSchema) -> Router<Database> {
+    Router::new()
+        .route("/graphql", get(graphql_playground).post(graphql_query))
+        .route("/graphql/ws", get(graphql_subscriptions))
+        .layer(Extension(schema))
+}
+
+/// Player a request is resolved for, passed to resolvers as request data
+#[derive(Clone, Copy)]
+pub(crate) struct Viewer(pub Uuid);
+
+impl Viewer {
+    /// The requesting player, if the schema is run with one
+    pub(crate) fn of(ctx: &async_graphql::Context<'_>) -> Option<Uuid> {
+        ctx.data_opt::<Self>().map(|viewer| viewer.0)
+    }
+}
+
+/// Log `error` and hide it from the client behind `message`
+pub(crate) fn internal_error(message: &str, error: &impl std::fmt::Debug) -> async_graphql::Error {
+    tracing::error!("{}: {:?}", message, error);
+    async_graphql::Error::new(message).extend_with(|_, e| e.set("code", "INTERNAL_ERROR"))
+}
+
+async fn graphql_query(
+    Extension(schema): Extension<BoarduranceSchema>,
+    Extension(user): Extension<UserContext>,
+    Json(request): Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    Json(schema.execute(request.data(Viewer(user.user_uuid))).await)
+}
+
+async fn graphql_playground() -> Html<String> {
+    Html(playground_source(
+        GraphQLPlaygroundConfig::new("/api/v1/graphql").subscription_endpoint("/api/v1/graphql/ws"),
+    ))
+}
+
+/// Subscriptions over `graphql-transport-ws` or the older `graphql-ws`
+async fn graphql_subscriptions(
+    Extension(schema): Extension<BoarduranceSchema>,
+    Extension(user): Extension<UserContext>,
+    headers: HeaderMap,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    let protocol = headers
+        .get(header::SEC_WEBSOCKET_PROTOCOL)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| {
+            value
+                .split(',')
+                .find_map(|protocol| WebSocketProtocols::from_str(protocol.trim()).ok())
+        });
+    let Some(protocol) = protocol else {
+        return (
+            StatusCode::BAD_REQUEST,
+            "Expected the graphql-transport-ws or graphql-ws subprotocol",
+        )
+            .into_response();
+    };
+
+    upgrade
+        .protocols(ALL_WEBSOCKET_PROTOCOLS)
+        .on_upgrade(move |socket| {
+            serve_subscriptions(socket, schema, protocol, Viewer(user.user_uuid))
+        })
+}
+
+async fn serve_subscriptions(
+    socket: WebSocket,
+    schema: BoarduranceSchema,
+    protocol: WebSocketProtocols,
+    viewer: Viewer,
+) {
+    let (mut sink, stream) = socket.split();
+    let incoming = stream
+        .take_while(|message| ready(matches!(message, Ok(m) if !matches!(m, Message::Close(_)))))
+        .filter_map(|message| {
+            ready(match message {
+                Ok(Message::Text(text)) => Some(text.into_bytes()),
+                Ok(Message::Binary(bytes)) => Some(bytes),
+                _ => None,
+            })
+        });
+
+    let mut data = async_graphql::Data::default();
+    data.insert(viewer);
+    let mut outgoing =
+        async_graphql::http::WebSocket::new(schema, incoming, protocol).connection_data(data);
+    while let Some(message) = outgoing.next().await {
+        let message = match message {
+            WsMessage::Text(text) => Message::Text(text),
+            WsMessage::Close(code, reason) => Message::Close(Some(CloseFrame {
+                code,
+                reason: reason.into(),
+            })),
+        };
+        if sink.send(message).await.is_err() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fmt::Write;
+
+    #[test]
+    fn race_status_slices_can_be_selected_separately() {
+        let sdl = Schema::new(QueryRoot, EmptyMutation, SubscriptionRoot).sdl();
+        for field in [
+            "progress: RaceProgressStatus!",
+            "trackSituation: TrackSituationData!",
+            "metadata: RaceMetadata!",
+            "playerData(playerUuid: UUID!): PlayerSpecificData!",
+            "raceChanges(raceUuid: UUID!, since: DateTime): RaceChangeEvent!",
+        ] {
+            assert!(sdl.contains(field), "missing `{field}`");
+        }
+    }
+
+    #[tokio::test]
+    async fn overly_complex_queries_are_rejected_before_reading_the_database() {
+        // The client connects lazily, and validation fails before any resolver runs
+        let database = mongodb::Client::with_uri_str("mongodb://localhost:27017")
+            .await
+            .unwrap()
+            .database("graphql_test");
+        let schema = build_schema(database);
+        let aliases = (0..MAX_QUERY_COMPLEXITY).fold(String::new(), |mut aliases, i| {
+            let _ = write!(aliases, " name{i}: name");
+            aliases
+        });
+        let response = schema
+            .execute(format!(
+                "{{ race(uuid: \"{}\") {{{aliases} }} }}",
+                uuid::Uuid::new_v4()
+            ))
+            .await;
+
+        assert!(response.errors[0].message.contains("too complex"));
+    }
+}
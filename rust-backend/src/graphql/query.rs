@@ -0,0 +1,83 @@
+use async_graphql::{Context, Object, Result};
+use mongodb::Database;
+use uuid::Uuid;
+
+use super::enums;
+use super::internal_error;
+use super::types::{PlayerNode, RaceNode};
+use super::Viewer;
+use crate::domain::TrackDesign;
+use crate::repositories::{MongoTrackRepository, TrackRepository};
+use crate::routes::players::get_player_by_uuid_from_db;
+use crate::routes::races::{
+    get_race_by_uuid, get_races_page_from_db, viewer_focus, DEFAULT_RACE_PAGE_LIMIT,
+};
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn race(&self, ctx: &Context<'_>, uuid: Uuid) -> Result<Option<RaceNode>> {
+        let race = get_race_by_uuid(ctx.data::<Database>()?, uuid)
+            .await
+            .map_err(|e| internal_error("Failed to fetch race", &e))?;
+        let Some(race) = race else {
+            return Ok(None);
+        };
+        let viewer = Viewer::of(ctx);
+        let focus = viewer_focus(ctx.data::<Database>()?, &race, viewer)
+            .await
+            .map_err(|e| internal_error("Failed to fetch pilot", &e))?;
+        Ok(Some(RaceNode(race.as_seen_by(viewer, focus))))
+    }
+
+    /// Newest races first
+    async fn races(
+        &self,
+        ctx: &Context<'_>,
+        status: Option<enums::RaceStatus>,
+        #[graphql(
+            default_with = "DEFAULT_RACE_PAGE_LIMIT",
+            validator(minimum = 1, maximum = 100)
+        )]
+        limit: u32,
+    ) -> Result<Vec<RaceNode>> {
+        let limit = limit as usize;
+        let mut races =
+            get_races_page_from_db(ctx.data::<Database>()?, status.map(Into::into), None, limit)
+                .await
+                .map_err(|e| internal_error("Failed to fetch races", &e))?;
+        races.truncate(limit);
+        let viewer = Viewer::of(ctx);
+        let mut nodes = Vec::with_capacity(races.len());
+        for race in &races {
+            let focus = viewer_focus(ctx.data::<Database>()?, race, viewer)
+                .await
+                .map_err(|e| internal_error("Failed to fetch pilot", &e))?;
+            nodes.push(RaceNode(race.as_seen_by(viewer, focus)));
+        }
+        Ok(nodes)
+    }
+
+    async fn track(&self, ctx: &Context<'_>, uuid: Uuid) -> Result<Option<TrackDesign>> {
+        MongoTrackRepository::new(ctx.data::<Database>()?)
+            .find_by_uuid(uuid)
+            .await
+            .map_err(|e| internal_error("Failed to fetch track", &e))
+    }
+
+    /// Tracks listed for every player
+    async fn tracks(&self, ctx: &Context<'_>) -> Result<Vec<TrackDesign>> {
+        MongoTrackRepository::new(ctx.data::<Database>()?)
+            .find_public()
+            .await
+            .map_err(|e| internal_error("Failed to fetch tracks", &e))
+    }
+
+    async fn player(&self, ctx: &Context<'_>, uuid: Uuid) -> Result<Option<PlayerNode>> {
+        let player = get_player_by_uuid_from_db(ctx.data::<Database>()?, uuid)
+            .await
+            .map_err(|e| internal_error("Failed to fetch player", &e))?;
+        Ok(player.map(PlayerNode))
+    }
+}
@@ -0,0 +1,182 @@
+use async_graphql::{Context, Result, SimpleObject, Subscription, Union};
+use chrono::{DateTime, Utc};
+use futures_util::{future, Stream, StreamExt};
+use mongodb::Database;
+use uuid::Uuid;
+
+use super::enums;
+use super::internal_error;
+use super::Viewer;
+use crate::domain::{ParticipantMovement, RaceChange, RaceEvent, RaceParticipant};
+use crate::routes::races::{get_race_by_uuid, viewer_focus};
+use crate::services::RaceEventLog;
+
+pub struct SubscriptionRoot;
+
+#[Subscription]
+impl SubscriptionRoot {
+    /// Changes to a race as they are recorded, after `since` (or from now on)
+    ///
+    /// Changes are shown as the subscribing player sees them: other players'
+    /// boost hands are hidden and, under fog of war, only cars near their own
+    /// are followed. The stream ends once the race has finished or been
+    /// cancelled.
+    async fn race_changes(
+        &self,
+        ctx: &Context<'_>,
+        race_uuid: Uuid,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<impl Stream<Item = RaceChangeEvent>> {
+        let database = ctx.data::<Database>()?.clone();
+        let viewer = Viewer::of(ctx);
+        let Some(mut race) = get_race_by_uuid(&database, race_uuid)
+            .await
+            .map_err(|e| internal_error("Failed to fetch race", &e))?
+        else {
+            return Err("Race not found".into());
+        };
+
+        // The race is followed from its stored state, so the focus is the one
+        // the pilot had when the subscription opened
+        let focus = viewer_focus(&database, &race, viewer)
+            .await
+            .map_err(|e| internal_error("Failed to fetch pilot", &e))?;
+        let log = RaceEventLog::new(database, race_uuid, since.unwrap_or_else(Utc::now));
+        Ok(log.into_stream().filter_map(move |event| {
+            event.change.follow(&mut race);
+            let change = event.change.as_seen_by(&race, viewer, focus);
+            future::ready(change.map(|change| RaceChangeEvent::from(RaceEvent { change, ..event })))
+        }))
+    }
+}
+
+/// Change to a race with the time it was recorded
+#[derive(SimpleObject)]
+pub struct RaceChangeEvent {
+    pub occurred_at: DateTime<Utc>,
+    pub change: RaceChangeNode,
+}
+
+impl From<RaceEvent> for RaceChangeEvent {
+    fn from(event: RaceEvent) -> Self {
+        let change = match event.change {
+            RaceChange::Movement { lap, movement } => {
+                RaceChangeNode::Movement(MovementChange { lap, movement })
+            }
+            RaceChange::PhaseChanged {
+                status,
+                current_lap,
+                lap_characteristic,
+            } => RaceChangeNode::PhaseChanged(PhaseChange {
+                status: status.into(),
+                current_lap,
+                lap_characteristic: lap_characteristic.into(),
+            }),
+            RaceChange::ParticipantUpdated { participant } => {
+                RaceChangeNode::ParticipantUpdated(ParticipantChange { participant })
+            }
+            RaceChange::Reaction {
+                lap,
+                player_uuid,
+                emote,
+            } => RaceChangeNode::Reaction(ReactionChange {
+                lap,
+                player_uuid,
+                emote: emote.into(),
+            }),
+            RaceChange::CountdownTick {
+                starts_at,
+                seconds_remaining,
+            } => RaceChangeNode::CountdownTick(CountdownTickChange {
+                starts_at,
+                seconds_remaining,
+            }),
+            RaceChange::TurnDeadlineNear {
+                lap,
+                deadline_at,
+                seconds_remaining,
+            } => RaceChangeNode::TurnDeadlineNear(TurnDeadlineChange {
+                lap,
+                deadline_at,
+                seconds_remaining,
+            }),
+            RaceChange::Commentary {
+                lap,
+                player_uuid,
+                text,
+            } => RaceChangeNode::Commentary(CommentaryChange {
+                lap,
+                player_uuid,
+                text,
+            }),
+        };
+        Self {
+            occurred_at: event.occurred_at.to_system_time().into(),
+            change,
+        }
+    }
+}
+
+/// Same cases as [`RaceChange`]
+#[derive(Union)]
+pub enum RaceChangeNode {
+    Movement(MovementChange),
+    PhaseChanged(PhaseChange),
+    ParticipantUpdated(ParticipantChange),
+    Reaction(ReactionChange),
+    CountdownTick(CountdownTickChange),
+    TurnDeadlineNear(TurnDeadlineChange),
+    Commentary(CommentaryChange),
+}
+
+/// A participant moved while a lap was resolved
+#[derive(SimpleObject)]
+pub struct MovementChange {
+    pub lap: u32,
+    pub movement: ParticipantMovement,
+}
+
+/// The race status, lap or lap characteristic changed
+#[derive(SimpleObject)]
+pub struct PhaseChange {
+    pub status: enums::RaceStatus,
+    pub current_lap: u32,
+    pub lap_characteristic: enums::LapCharacteristic,
+}
+
+/// A participant joined or its state changed
+#[derive(SimpleObject)]
+pub struct ParticipantChange {
+    pub participant: Box<RaceParticipant>,
+}
+
+/// A participant reacted to a resolved lap
+#[derive(SimpleObject)]
+pub struct ReactionChange {
+    pub lap: u32,
+    pub player_uuid: Uuid,
+    pub emote: enums::Emote,
+}
+
+/// The race is counting down to its first lap
+#[derive(SimpleObject)]
+pub struct CountdownTickChange {
+    pub starts_at: DateTime<Utc>,
+    pub seconds_remaining: u64,
+}
+
+/// The current lap's deadline is near and some participants have not acted yet
+#[derive(SimpleObject)]
+pub struct TurnDeadlineChange {
+    pub lap: u32,
+    pub deadline_at: DateTime<Utc>,
+    pub seconds_remaining: u64,
+}
+
+/// Human-readable line about a resolved lap
+#[derive(SimpleObject)]
+pub struct CommentaryChange {
+    pub lap: u32,
+    pub player_uuid: Uuid,
+    pub text: String,
+}
@@ -0,0 +1,944 @@
+//! Output types of the GraphQL schema
+//!
+//! Most are the REST response models resolved field by field, so a query only
+//! pays for the parts of the race status it selects.
+
+use async_graphql::{ComplexObject, Context, Object, Result, SimpleObject};
+use chrono::{DateTime, Utc};
+use mongodb::{bson::DateTime as BsonDateTime, Database};
+use uuid::Uuid;
+
+use super::enums;
+use super::internal_error;
+use crate::domain::{
+    BoostAvailability, BoostCycleSummary, BoostImpactOption, BoostUsageRecord, Player, Race,
+    RaceParticipant, RaceStatus, Sector, SectorModifiers, Track, TrackDesign,
+};
+use crate::repositories::{MongoTrackRepository, TrackRepository};
+use crate::routes::players::{
+    get_player_race_history_from_db, get_player_races_from_db, BoostStats, RaceHistoryEntry,
+};
+use crate::routes::races::{
+    build_player_specific_data, build_race_metadata, build_race_progress_status,
+    build_track_situation_data, CurrentPlayerPosition, LapPerformanceRecord, LeaderboardEntry,
+    ParticipantMovement, PerformancePreview, PerformanceThresholds, PlayerSpecificData,
+    RaceMetadata, RaceProgressStatus, RaceStatusType, SectorCapacityInfo, SectorParticipant,
+    SectorSituation, TrackSituationData, TurnPhase,
+};
+
+fn to_chrono(time: BsonDateTime) -> DateTime<Utc> {
+    time.to_system_time().into()
+}
+
+pub struct RaceNode(pub Race);
+
+#[Object(name = "Race")]
+impl RaceNode {
+    async fn uuid(&self) -> Uuid {
+        self.0.uuid
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    async fn status(&self) -> enums::RaceStatus {
+        self.0.status.clone().into()
+    }
+
+    async fn mode(&self) -> enums::RaceMode {
+        self.0.mode.into()
+    }
+
+    async fn current_lap(&self) -> u32 {
+        self.0.current_lap
+    }
+
+    async fn total_laps(&self) -> u32 {
+        self.0.total_laps
+    }
+
+    async fn lap_characteristic(&self) -> enums::LapCharacteristic {
+        self.0.lap_characteristic.clone().into()
+    }
+
+    async fn created_at(&self) -> DateTime<Utc> {
+        to_chrono(self.0.created_at)
+    }
+
+    async fn updated_at(&self) -> DateTime<Utc> {
+        to_chrono(self.0.updated_at)
+    }
+
+    /// Layout the race is run on, as copied when it was created
+    async fn track(&self) -> &Track {
+        &self.0.track
+    }
+
+    /// Stored track the layout was copied from, if any
+    async fn source_track(&self, ctx: &Context<'_>) -> Result<Option<TrackDesign>> {
+        let Some(track_uuid) = self.0.source_track_uuid else {
+            return Ok(None);
+        };
+        MongoTrackRepository::new(ctx.data::<Database>()?)
+            .find_by_uuid(track_uuid)
+            .await
+            .map_err(|e| internal_error("Failed to fetch track", &e))
+    }
+
+    async fn participants(&self) -> &[RaceParticipant] {
+        &self.0.participants
+    }
+
+    /// Same as `race_progress` in the detailed REST status
+    async fn progress(&self) -> RaceProgressStatus {
+        build_race_progress_status(&self.0)
+    }
+
+    /// Same as `track_situation` in the detailed REST status
+    async fn track_situation(&self, ctx: &Context<'_>) -> Result<TrackSituationData> {
+        build_track_situation_data(ctx.data::<Database>()?, &self.0, None)
+            .await
+            .map_err(|e| internal_error("Failed to build track situation", &e))
+    }
+
+    /// Same as `race_metadata` in the detailed REST status
+    async fn metadata(&self, ctx: &Context<'_>) -> Result<RaceMetadata> {
+        Ok(build_race_metadata(ctx.data::<Database>()?, &self.0).await)
+    }
+
+    /// Same as `player_data` in the detailed REST status
+    async fn player_data(
+        &self,
+        ctx: &Context<'_>,
+        player_uuid: Uuid,
+    ) -> Result<PlayerSpecificData> {
+        build_player_specific_data(ctx.data::<Database>()?, &self.0, player_uuid)
+            .await
+            .map_err(|e| internal_error("Failed to build player specific data", &e))
+    }
+}
+
+#[Object]
+impl Track {
+    async fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    async fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn sectors(&self) -> &[Sector] {
+        &self.sectors
+    }
+}
+
+#[Object]
+impl Sector {
+    async fn id(&self) -> u32 {
+        self.id
+    }
+
+    async fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn sector_type(&self) -> enums::SectorType {
+        self.sector_type.clone().into()
+    }
+
+    async fn min_value(&self) -> u32 {
+        self.min_value
+    }
+
+    async fn max_value(&self) -> u32 {
+        self.max_value
+    }
+
+    /// `null` for unlimited sectors
+    async fn slot_capacity(&self) -> Option<u32> {
+        self.slot_capacity
+    }
+
+    async fn modifiers(&self) -> Option<SectorModifiers> {
+        self.modifiers
+    }
+}
+
+#[Object]
+impl SectorModifiers {
+    async fn grip(&self) -> f64 {
+        self.grip
+    }
+
+    async fn elevation(&self) -> i32 {
+        self.elevation
+    }
+}
+
+#[Object]
+impl RaceParticipant {
+    async fn player_uuid(&self) -> Uuid {
+        self.player_uuid
+    }
+
+    async fn car_uuid(&self) -> Uuid {
+        self.car_uuid
+    }
+
+    async fn pilot_uuid(&self) -> Uuid {
+        self.pilot_uuid
+    }
+
+    async fn current_sector(&self) -> u32 {
+        self.current_sector
+    }
+
+    async fn position_in_sector(&self) -> u32 {
+        self.current_position_in_sector
+    }
+
+    async fn current_lap(&self) -> u32 {
+        self.current_lap
+    }
+
+    async fn total_value(&self) -> u32 {
+        self.total_value
+    }
+
+    async fn is_finished(&self) -> bool {
+        self.is_finished
+    }
+
+    async fn finish_position(&self) -> Option<u32> {
+        self.finish_position
+    }
+
+    async fn is_bot(&self) -> bool {
+        self.bot.is_some()
+    }
+
+    async fn is_ghost(&self) -> bool {
+        self.ghost.is_some()
+    }
+
+    /// Car class the participant competes in, for races with car classes
+    async fn car_class(&self) -> Option<&str> {
+        self.car_class.as_deref()
+    }
+
+    /// Finish position within the car class
+    async fn class_position(&self) -> Option<u32> {
+        self.class_position
+    }
+
+    /// Racing a server-owned loaner car
+    async fn is_loaner(&self) -> bool {
+        self.is_loaner
+    }
+}
+
+/// Move of a participant while a lap was resolved
+#[Object(name = "LapMovement")]
+impl crate::domain::ParticipantMovement {
+    async fn player_uuid(&self) -> Uuid {
+        self.player_uuid
+    }
+
+    async fn from_sector(&self) -> u32 {
+        self.from_sector
+    }
+
+    async fn to_sector(&self) -> u32 {
+        self.to_sector
+    }
+
+    async fn final_value(&self) -> u32 {
+        self.final_value
+    }
+
+    async fn movement_type(&self) -> enums::MovementType {
+        self.movement_type.clone().into()
+    }
+}
+
+#[Object]
+impl RaceProgressStatus {
+    async fn status(&self) -> enums::RaceProgressState {
+        match self.status {
+            RaceStatusType::Waiting => enums::RaceProgressState::Waiting,
+            RaceStatusType::Starting => enums::RaceProgressState::Starting,
+            RaceStatusType::Ongoing => enums::RaceProgressState::Ongoing,
+            RaceStatusType::Finished => enums::RaceProgressState::Finished,
+            RaceStatusType::Error { .. } => enums::RaceProgressState::Error,
+        }
+    }
+
+    /// Reason of an `ERROR` status
+    async fn status_message(&self) -> Option<&str> {
+        match &self.status {
+            RaceStatusType::Error { message } => Some(message),
+            _ => None,
+        }
+    }
+
+    async fn current_lap(&self) -> u32 {
+        self.current_lap
+    }
+
+    async fn total_laps(&self) -> u32 {
+        self.total_laps
+    }
+
+    async fn lap_characteristic(&self) -> enums::LapCharacteristic {
+        self.lap_characteristic.clone().into()
+    }
+
+    async fn turn_phase(&self) -> enums::TurnPhase {
+        match self.turn_phase {
+            TurnPhase::WaitingForPlayers => enums::TurnPhase::WaitingForPlayers,
+            TurnPhase::AllSubmitted => enums::TurnPhase::AllSubmitted,
+            TurnPhase::Processing => enums::TurnPhase::Processing,
+            TurnPhase::TurnProcessed => enums::TurnPhase::TurnProcessed,
+            TurnPhase::Complete => enums::TurnPhase::Complete,
+        }
+    }
+
+    /// When the first lap opens, while the race is `STARTING`
+    async fn starts_at(&self) -> Option<DateTime<Utc>> {
+        self.starts_at
+    }
+
+    /// When the current lap stops waiting for actions, if the race has a turn time limit
+    async fn deadline_at(&self) -> Option<DateTime<Utc>> {
+        self.deadline_at
+    }
+
+    async fn seconds_remaining(&self) -> Option<u64> {
+        self.seconds_remaining
+    }
+
+    async fn participants_count(&self) -> u32 {
+        self.participants_count
+    }
+
+    async fn finished_participants(&self) -> u32 {
+        self.finished_participants
+    }
+}
+
+#[Object]
+impl TrackSituationData {
+    async fn sectors(&self) -> &[SectorSituation] {
+        &self.sectors
+    }
+
+    async fn recent_movements(&self) -> &[ParticipantMovement] {
+        &self.recent_movements
+    }
+
+    async fn lap_leaderboard(&self) -> &[LeaderboardEntry] {
+        &self.lap_leaderboard
+    }
+}
+
+#[Object]
+impl SectorSituation {
+    async fn sector_id(&self) -> u32 {
+        self.sector_id
+    }
+
+    async fn sector_name(&self) -> &str {
+        &self.sector_name
+    }
+
+    async fn sector_type(&self) -> enums::SectorType {
+        self.sector_type.clone().into()
+    }
+
+    async fn capacity_info(&self) -> &SectorCapacityInfo {
+        &self.capacity_info
+    }
+
+    async fn participants(&self) -> &[SectorParticipant] {
+        &self.participants
+    }
+
+    async fn performance_thresholds(&self) -> &PerformanceThresholds {
+        &self.performance_thresholds
+    }
+}
+
+#[Object]
+impl SectorCapacityInfo {
+    /// `null` for unlimited sectors
+    async fn max_capacity(&self) -> Option<u32> {
+        self.max_capacity
+    }
+
+    async fn current_occupancy(&self) -> u32 {
+        self.current_occupancy
+    }
+
+    /// `null` for unlimited sectors
+    async fn available_slots(&self) -> Option<u32> {
+        self.available_slots
+    }
+}
+
+#[Object]
+impl SectorParticipant {
+    async fn player_uuid(&self) -> &str {
+        &self.player_uuid
+    }
+
+    async fn player_name(&self) -> Option<&str> {
+        self.player_name.as_deref()
+    }
+
+    async fn car_name(&self) -> &str {
+        &self.car_name
+    }
+
+    async fn position_in_sector(&self) -> u32 {
+        self.position_in_sector
+    }
+
+    async fn total_value(&self) -> u32 {
+        self.total_value
+    }
+
+    async fn current_lap(&self) -> u32 {
+        self.current_lap
+    }
+
+    async fn is_finished(&self) -> bool {
+        self.is_finished
+    }
+}
+
+#[Object]
+impl PerformanceThresholds {
+    async fn min_value(&self) -> u32 {
+        self.min_value
+    }
+
+    async fn max_value(&self) -> u32 {
+        self.max_value
+    }
+
+    async fn move_up_threshold(&self) -> u32 {
+        self.move_up_threshold
+    }
+
+    async fn move_down_threshold(&self) -> u32 {
+        self.move_down_threshold
+    }
+
+    async fn modifiers(&self) -> Option<SectorModifiers> {
+        self.modifiers
+    }
+}
+
+#[Object]
+impl ParticipantMovement {
+    async fn player_uuid(&self) -> &str {
+        &self.player_uuid
+    }
+
+    /// Lap the movement was resolved on
+    async fn lap(&self) -> u32 {
+        self.lap
+    }
+
+    async fn from_sector(&self) -> u32 {
+        self.from_sector
+    }
+
+    async fn to_sector(&self) -> u32 {
+        self.to_sector
+    }
+
+    async fn movement_type(&self) -> enums::MovementType {
+        self.movement_type.clone().into()
+    }
+}
+
+#[Object]
+impl LeaderboardEntry {
+    async fn player_uuid(&self) -> &str {
+        &self.player_uuid
+    }
+
+    async fn player_name(&self) -> Option<&str> {
+        self.player_name.as_deref()
+    }
+
+    async fn car_name(&self) -> &str {
+        &self.car_name
+    }
+
+    async fn current_sector(&self) -> u32 {
+        self.current_sector
+    }
+
+    async fn position_in_sector(&self) -> u32 {
+        self.position_in_sector
+    }
+
+    async fn total_value(&self) -> u32 {
+        self.total_value
+    }
+
+    async fn current_lap(&self) -> u32 {
+        self.current_lap
+    }
+
+    async fn overall_rank(&self) -> u32 {
+        self.overall_rank
+    }
+}
+
+#[Object]
+impl PlayerSpecificData {
+    async fn boost_availability(&self) -> &BoostAvailability {
+        &self.boost_availability
+    }
+
+    async fn performance_preview(&self) -> &PerformancePreview {
+        &self.performance_preview
+    }
+
+    async fn current_position(&self) -> &CurrentPlayerPosition {
+        &self.current_position
+    }
+
+    async fn lap_history(&self) -> Option<&[LapPerformanceRecord]> {
+        self.lap_history.as_deref()
+    }
+
+    async fn boost_usage_history(&self) -> &[BoostUsageRecord] {
+        &self.boost_usage_history
+    }
+
+    async fn boost_cycle_summaries(&self) -> &[BoostCycleSummary] {
+        &self.boost_cycle_summaries
+    }
+}
+
+#[Object]
+impl BoostAvailability {
+    async fn available_cards(&self) -> &[u8] {
+        &self.available_cards
+    }
+
+    async fn current_cycle(&self) -> u32 {
+        self.current_cycle
+    }
+
+    async fn cycles_completed(&self) -> u32 {
+        self.cycles_completed
+    }
+
+    async fn cards_remaining(&self) -> u32 {
+        self.cards_remaining
+    }
+
+    async fn next_replenishment_at(&self) -> Option<u32> {
+        self.next_replenishment_at
+    }
+
+    async fn boost_impact_preview(&self) -> &[BoostImpactOption] {
+        &self.boost_impact_preview
+    }
+}
+
+#[Object]
+impl BoostImpactOption {
+    async fn boost_value(&self) -> u8 {
+        self.boost_value
+    }
+
+    async fn is_available(&self) -> bool {
+        self.is_available
+    }
+
+    async fn predicted_final_value(&self) -> u32 {
+        self.predicted_final_value
+    }
+
+    async fn movement_probability(&self) -> enums::MovementProbability {
+        self.movement_probability.clone().into()
+    }
+}
+
+#[Object]
+impl PerformancePreview {
+    async fn engine_contribution(&self) -> u32 {
+        self.engine_contribution
+    }
+
+    async fn body_contribution(&self) -> u32 {
+        self.body_contribution
+    }
+
+    async fn pilot_contribution(&self) -> u32 {
+        self.pilot_contribution
+    }
+
+    async fn base_value(&self) -> u32 {
+        self.base_value
+    }
+
+    async fn sector_ceiling(&self) -> u32 {
+        self.sector_ceiling
+    }
+
+    async fn capped_base_value(&self) -> u32 {
+        self.capped_base_value
+    }
+}
+
+#[Object]
+impl CurrentPlayerPosition {
+    async fn current_sector(&self) -> u32 {
+        self.current_sector
+    }
+
+    async fn position_in_sector(&self) -> u32 {
+        self.position_in_sector
+    }
+
+    async fn sector_rank(&self) -> u32 {
+        self.sector_rank
+    }
+
+    async fn overall_rank(&self) -> u32 {
+        self.overall_rank
+    }
+
+    async fn distance_to_leader(&self) -> u32 {
+        self.distance_to_leader
+    }
+}
+
+#[Object]
+impl LapPerformanceRecord {
+    async fn lap_number(&self) -> u32 {
+        self.lap_number
+    }
+
+    async fn boost_used(&self) -> u32 {
+        self.boost_used
+    }
+
+    async fn final_value(&self) -> u32 {
+        self.final_value
+    }
+
+    async fn movement_type(&self) -> enums::MovementType {
+        self.movement_type.clone().into()
+    }
+
+    async fn from_sector(&self) -> u32 {
+        self.from_sector
+    }
+
+    async fn to_sector(&self) -> u32 {
+        self.to_sector
+    }
+}
+
+#[Object]
+impl BoostUsageRecord {
+    async fn lap_number(&self) -> u32 {
+        self.lap_number
+    }
+
+    async fn boost_value(&self) -> u8 {
+        self.boost_value
+    }
+
+    async fn cycle_number(&self) -> u32 {
+        self.cycle_number
+    }
+
+    async fn cards_remaining_after(&self) -> u32 {
+        self.cards_remaining_after
+    }
+
+    async fn replenishment_occurred(&self) -> bool {
+        self.replenishment_occurred
+    }
+
+    async fn lap_characteristic(&self) -> Option<enums::LapCharacteristic> {
+        self.lap_characteristic.clone().map(Into::into)
+    }
+}
+
+#[Object]
+impl BoostCycleSummary {
+    async fn cycle_number(&self) -> u32 {
+        self.cycle_number
+    }
+
+    async fn cards_used(&self) -> &[u8] {
+        &self.cards_used
+    }
+
+    async fn laps_in_cycle(&self) -> &[u32] {
+        &self.laps_in_cycle
+    }
+
+    async fn average_boost(&self) -> f32 {
+        self.average_boost
+    }
+}
+
+#[Object]
+impl RaceMetadata {
+    async fn race_uuid(&self) -> &str {
+        &self.race_uuid
+    }
+
+    async fn race_name(&self) -> &str {
+        &self.race_name
+    }
+
+    async fn track_name(&self) -> &str {
+        &self.track_name
+    }
+
+    async fn start_time(&self) -> Option<DateTime<Utc>> {
+        self.start_time
+    }
+
+    async fn estimated_completion(&self) -> Option<DateTime<Utc>> {
+        self.estimated_completion
+    }
+
+    async fn total_turns(&self) -> u32 {
+        self.total_turns
+    }
+
+    async fn rules_version(&self) -> u32 {
+        self.rules_version
+    }
+
+    async fn spectator_count(&self) -> u64 {
+        self.spectator_count
+    }
+
+    async fn result_hash(&self) -> Option<&str> {
+        self.result_hash.as_deref()
+    }
+
+    async fn anchor_transaction_signature(&self) -> Option<&str> {
+        self.anchor_transaction_signature.as_deref()
+    }
+}
+
+#[Object(name = "StoredTrack")]
+impl TrackDesign {
+    async fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    async fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn description(&self) -> &str {
+        &self.description
+    }
+
+    async fn sectors(&self) -> &[Sector] {
+        &self.sectors
+    }
+
+    async fn author_uuid(&self) -> Option<Uuid> {
+        self.author_uuid
+    }
+
+    async fn is_public(&self) -> bool {
+        self.is_public
+    }
+
+    /// Average star rating, `null` until the track has been rated
+    #[graphql(name = "averageRating")]
+    async fn stars(&self) -> Option<f64> {
+        TrackDesign::average_rating(self)
+    }
+
+    async fn rating_count(&self) -> usize {
+        self.ratings.len()
+    }
+
+    async fn favorite_count(&self) -> usize {
+        self.favorited_by.len()
+    }
+
+    async fn created_at(&self) -> DateTime<Utc> {
+        to_chrono(self.created_at)
+    }
+}
+
+/// Public profile of a player; credentials and garage stay on the REST API
+pub struct PlayerNode(pub Player);
+
+#[Object(name = "Player")]
+impl PlayerNode {
+    async fn uuid(&self) -> Uuid {
+        self.0.uuid
+    }
+
+    async fn team_name(&self) -> &str {
+        self.0.team_name.as_ref()
+    }
+
+    /// Results over every finished race of the player
+    async fn stats(&self, ctx: &Context<'_>) -> Result<PlayerStats> {
+        let races = get_player_races_from_db(
+            ctx.data::<Database>()?,
+            self.0.uuid,
+            &[RaceStatus::Finished],
+        )
+        .await
+        .map_err(|e| internal_error("Failed to fetch player races", &e))?;
+        let entries: Vec<_> = races
+            .iter()
+            .filter_map(|race| RaceHistoryEntry::from_race(race, self.0.uuid))
+            .collect();
+        Ok(PlayerStats::from_history(&entries))
+    }
+
+    /// Most recently finished races first
+    async fn recent_races(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 10, validator(minimum = 1, maximum = 100))] limit: usize,
+    ) -> Result<Vec<RaceHistoryEntry>> {
+        let races =
+            get_player_race_history_from_db(ctx.data::<Database>()?, self.0.uuid, None, limit)
+                .await
+                .map_err(|e| internal_error("Failed to fetch player races", &e))?;
+        Ok(races
+            .iter()
+            .take(limit)
+            .filter_map(|race| RaceHistoryEntry::from_race(race, self.0.uuid))
+            .collect())
+    }
+}
+
+/// Results of a player over the races they finished
+#[derive(SimpleObject)]
+#[graphql(complex)]
+pub struct PlayerStats {
+    pub races_finished: u32,
+    pub wins: u32,
+    pub podiums: u32,
+    /// `null` until the player reaches the finish of a race
+    pub best_finish: Option<u32>,
+    #[graphql(skip)]
+    finish_positions: Vec<u32>,
+}
+
+#[ComplexObject]
+impl PlayerStats {
+    async fn average_finish(&self) -> Option<f64> {
+        if self.finish_positions.is_empty() {
+            return None;
+        }
+        let total: u32 = self.finish_positions.iter().sum();
+        #[allow(clippy::cast_precision_loss)]
+        let count = self.finish_positions.len() as f64;
+        Some(f64::from(total) / count)
+    }
+}
+
+impl PlayerStats {
+    #[must_use]
+    pub fn from_history(entries: &[RaceHistoryEntry]) -> Self {
+        let finish_positions: Vec<u32> = entries
+            .iter()
+            .filter_map(|entry| entry.finish_position)
+            .collect();
+        let count = |podium: u32| {
+            u32::try_from(
+                finish_positions
+                    .iter()
+                    .filter(|position| **position <= podium)
+                    .count(),
+            )
+            .unwrap_or(u32::MAX)
+        };
+
+        Self {
+            races_finished: u32::try_from(entries.len()).unwrap_or(u32::MAX),
+            wins: count(1),
+            podiums: count(3),
+            best_finish: finish_positions.iter().min().copied(),
+            finish_positions,
+        }
+    }
+}
+
+#[Object]
+impl RaceHistoryEntry {
+    async fn race_uuid(&self) -> &str {
+        &self.race_uuid
+    }
+
+    async fn race_name(&self) -> &str {
+        &self.race_name
+    }
+
+    async fn track_name(&self) -> &str {
+        &self.track_name
+    }
+
+    async fn mode(&self) -> enums::RaceMode {
+        self.mode.into()
+    }
+
+    async fn completed_at(&self) -> DateTime<Utc> {
+        self.completed_at
+    }
+
+    /// `null` when the player did not reach the finish
+    async fn finish_position(&self) -> Option<u32> {
+        self.finish_position
+    }
+
+    async fn participant_count(&self) -> u32 {
+        self.participant_count
+    }
+
+    async fn total_value(&self) -> u32 {
+        self.total_value
+    }
+
+    async fn boost_stats(&self) -> &BoostStats {
+        &self.boost_stats
+    }
+}
+
+#[Object]
+impl BoostStats {
+    async fn cards_played(&self) -> u32 {
+        self.cards_played
+    }
+
+    async fn total_boost(&self) -> u32 {
+        self.total_boost
+    }
+
+    async fn average_boost(&self) -> f64 {
+        self.average_boost
+    }
+
+    async fn cycles_completed(&self) -> u32 {
+        self.cycles_completed
+    }
+}
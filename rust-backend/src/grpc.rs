@@ -0,0 +1,536 @@
+//! gRPC race service for game servers and matchmaking services
+//!
+//! Each call goes through the same handler as its REST counterpart, so
+//! validation and side effects match; HTTP error statuses are translated to
+//! gRPC codes. The service listens on its own port, `grpc.port`.
+
+use futures_util::future::BoxFuture;
+use mongodb::Database;
+use tokio::net::TcpListener;
+
+use crate::configuration::GrpcSettings;
+use crate::services::CarDataCache;
+
+/// Code generated from `proto/boardurance/race/v1/race.proto`
+#[cfg(feature = "grpc")]
+#[allow(clippy::all, clippy::pedantic)]
+pub mod proto {
+    tonic::include_proto!("boardurance.race.v1");
+}
+
+/// Serve the race service on `listener`
+#[cfg(feature = "grpc")]
+pub fn serve_grpc(
+    listener: TcpListener,
+    database: Database,
+    car_data_cache: CarDataCache,
+    settings: &GrpcSettings,
+) -> Result<BoxFuture<'static, Result<(), std::io::Error>>, anyhow::Error> {
+    use proto::race_service_server::RaceServiceServer;
+    use tonic::transport::{server::TcpIncoming, Server};
+
+    let service = RaceServiceServer::with_interceptor(
+        service::RaceGrpcService {
+            database,
+            car_data_cache,
+        },
+        service::check_api_key(settings.api_key.clone()),
+    );
+    let incoming = TcpIncoming::from_listener(listener, true, None)
+        .map_err(|e| anyhow::anyhow!("Failed to listen for gRPC connections: {e}"))?;
+
+    Ok(Box::pin(async move {
+        Server::builder()
+            .add_service(service)
+            .serve_with_incoming(incoming)
+            .await
+            .map_err(std::io::Error::other)
+    }))
+}
+
+/// Without the `grpc` feature the service is not compiled in
+#[cfg(not(feature = "grpc"))]
+pub fn serve_grpc(
+    _listener: TcpListener,
+    _database: Database,
+    _car_data_cache: CarDataCache,
+    _settings: &GrpcSettings,
+) -> Result<BoxFuture<'static, Result<(), std::io::Error>>, anyhow::Error> {
+    anyhow::bail!("`grpc.port` requires building with `--features grpc`")
+}
+
+// Every call fails with tonic's `Status`, however large
+#[cfg(feature = "grpc")]
+#[allow(clippy::result_large_err)]
+mod service {
+    use axum::{
+        extract::{Path, State},
+        http::StatusCode,
+        Extension, Json,
+    };
+    use futures_util::{future, Stream, StreamExt};
+    use mongodb::Database;
+    use secrecy::{ExposeSecret, Secret};
+    use std::pin::Pin;
+    use tonic::{Code, Request, Response, Status};
+    use uuid::Uuid;
+
+    use super::proto::{self, create_race_request::Track, race_event::Change};
+    use crate::domain::{
+        CarClass, CatchUpAssist, ComponentRarity, GridOrder, LapCharacteristic, MovementType,
+        PerformanceModelKind, Race, RaceChange, RaceEvent, RaceParticipant, RaceRules, RaceStatus,
+        RatingWindow, SuccessBallast,
+    };
+    use crate::routes::races::{self, RaceProgressStatus, RaceStatusType};
+    use crate::services::{CarDataCache, RaceEventLog};
+
+    pub(super) struct RaceGrpcService {
+        pub(super) database: Database,
+        pub(super) car_data_cache: CarDataCache,
+    }
+
+    /// Reject calls without `authorization: Bearer <api_key>` when a key is set
+    pub(super) fn check_api_key(
+        api_key: Option<Secret<String>>,
+    ) -> impl Fn(Request<()>) -> Result<Request<()>, Status> + Clone {
+        move |request: Request<()>| {
+            let Some(api_key) = &api_key else {
+                return Ok(request);
+            };
+            let token = request
+                .metadata()
+                .get("authorization")
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.strip_prefix("Bearer "));
+            if token == Some(api_key.expose_secret().as_str()) {
+                Ok(request)
+            } else {
+                Err(Status::unauthenticated("Missing or invalid API key"))
+            }
+        }
+    }
+
+    #[tonic::async_trait]
+    impl proto::race_service_server::RaceService for RaceGrpcService {
+        async fn create_race(
+            &self,
+            request: Request<proto::CreateRaceRequest>,
+        ) -> Result<Response<proto::Race>, Status> {
+            let request = request.into_inner();
+            let performance_model =
+                match proto::PerformanceModel::try_from(request.performance_model) {
+                    Ok(proto::PerformanceModel::Additive) => PerformanceModelKind::Additive,
+                    Ok(proto::PerformanceModel::Multiplicative) => {
+                        PerformanceModelKind::Multiplicative
+                    }
+                    Ok(proto::PerformanceModel::DiminishingReturns) => {
+                        PerformanceModelKind::DiminishingReturns
+                    }
+                    Err(_) => return Err(Status::invalid_argument("Unknown performance model")),
+                };
+            let grid_order = match proto::GridOrder::try_from(request.grid_order) {
+                Ok(proto::GridOrder::BestAtFront) => GridOrder::BestAtFront,
+                Ok(proto::GridOrder::BestAtBack) => GridOrder::BestAtBack,
+                Err(_) => return Err(Status::invalid_argument("Unknown grid order")),
+            };
+            let car_classes = request
+                .car_classes
+                .into_iter()
+                .map(car_class)
+                .collect::<Result<Vec<_>, _>>()?;
+            let allowed_rarities = request
+                .allowed_rarities
+                .into_iter()
+                .map(|rarity| {
+                    component_rarity(rarity)?
+                        .ok_or_else(|| Status::invalid_argument("Unspecified component rarity"))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let (track_uuid, template_id) = match request.track {
+                Some(Track::TrackUuid(track_uuid)) => (Some(track_uuid), None),
+                Some(Track::TemplateId(template_id)) => (None, Some(template_id)),
+                None => (None, None),
+            };
+            let payload = races::CreateRaceRequest {
+                name: request.name,
+                track_name: request.track_name,
+                sectors: Vec::new(),
+                template_id,
+                track_uuid,
+                total_laps: request.total_laps,
+                rules: RaceRules {
+                    boost_card_banking: request.boost_card_banking,
+                    performance_model,
+                    turn_time_limit_seconds: request.turn_time_limit_seconds,
+                    start_countdown_seconds: request.start_countdown_seconds,
+                    grid_order,
+                    success_ballast: request.success_ballast.map(|ballast| SuccessBallast {
+                        recent_races: ballast.recent_races,
+                        penalty_per_win: ballast.penalty_per_win,
+                    }),
+                    catch_up_assist: request.catch_up_assist.map(|assist| CatchUpAssist {
+                        bonus_per_sector: assist.bonus_per_sector,
+                        max_bonus: assist.max_bonus,
+                    }),
+                    skill_bracket: request.skill_bracket.map(rating_window),
+                    car_tier: request.car_tier.map(rating_window),
+                    car_classes,
+                    allowed_rarities,
+                    commit_reveal: request.commit_reveal,
+                    fog_of_war: request.fog_of_war,
+                    max_view_radius: request.max_view_radius,
+                    focus_limited_view: request.focus_limited_view,
+                },
+            };
+
+            let (_, Json(created)) =
+                races::create_race(State(self.database.clone()), Json(payload))
+                    .await
+                    .map_err(|(status, Json(error))| status_from_http(status, error.message))?;
+            Ok(Response::new(race_message(&created.race)))
+        }
+
+        async fn register_player(
+            &self,
+            request: Request<proto::RegisterPlayerRequest>,
+        ) -> Result<Response<proto::RegisterPlayerResponse>, Status> {
+            let request = request.into_inner();
+            let payload = races::RegisterPlayerRequest {
+                player_uuid: request.player_uuid,
+                car_uuid: Some(request.car_uuid).filter(|car_uuid| !car_uuid.is_empty()),
+            };
+
+            let Json(registered) = races::register_player(
+                State(self.database.clone()),
+                Extension(self.car_data_cache.clone()),
+                Path(request.race_uuid),
+                Json(payload),
+            )
+            .await
+            .map_err(|(status, Json(error))| status_from_http(status, error.message))?;
+            Ok(Response::new(proto::RegisterPlayerResponse {
+                message: registered.message,
+                starting_sector: registered.player_position.starting_sector,
+                position_in_sector: registered.player_position.position_in_sector,
+                qualification_rank: registered.player_position.qualification_rank,
+                progress: Some(progress_message(&registered.race_status)),
+                car_uuid: registered.car_uuid,
+                is_loaner: registered.is_loaner,
+            }))
+        }
+
+        async fn submit_action(
+            &self,
+            request: Request<proto::SubmitActionRequest>,
+        ) -> Result<Response<proto::SubmitActionResponse>, Status> {
+            let request = request.into_inner();
+            let payload = races::ApplyLapRequest {
+                player_uuid: request.player_uuid,
+                car_uuid: request.car_uuid,
+                boost_value: request.boost_value,
+                pit_stop: request.pit_stop,
+                salt: request.salt,
+            };
+
+            let Json(status) = races::apply_lap_action(
+                State(self.database.clone()),
+                Extension(self.car_data_cache.clone()),
+                Path(request.race_uuid),
+                Json(payload),
+            )
+            .await
+            .map_err(|(status, Json(error))| status_from_http(status, error.message))?;
+            Ok(Response::new(proto::SubmitActionResponse {
+                progress: Some(progress_message(&status.race_progress)),
+            }))
+        }
+
+        type StreamEventsStream =
+            Pin<Box<dyn Stream<Item = Result<proto::RaceEvent, Status>> + Send>>;
+
+        async fn stream_events(
+            &self,
+            request: Request<proto::StreamEventsRequest>,
+        ) -> Result<Response<Self::StreamEventsStream>, Status> {
+            let request = request.into_inner();
+            let race_uuid = Uuid::parse_str(&request.race_uuid)
+                .map_err(|_| Status::invalid_argument("Invalid race UUID"))?;
+            let since = match request.since_unix_millis {
+                Some(millis) => chrono::DateTime::from_timestamp_millis(millis)
+                    .ok_or_else(|| Status::invalid_argument("since_unix_millis is out of range"))?,
+                None => chrono::Utc::now(),
+            };
+            let race = races::get_race_by_uuid(&self.database, race_uuid)
+                .await
+                .map_err(|e| {
+                    tracing::error!("Failed to fetch race {}: {:?}", race_uuid, e);
+                    Status::internal("Failed to fetch race")
+                })?;
+            let Some(mut race) = race else {
+                return Err(Status::not_found("Race not found"));
+            };
+
+            // Shown as spectators see them, hands hidden and fog of war applied
+            let events = RaceEventLog::new(self.database.clone(), race_uuid, since)
+                .into_stream()
+                .filter_map(move |event| {
+                    event.change.follow(&mut race);
+                    let change = event.change.as_seen_by(&race, None, None);
+                    future::ready(
+                        change.map(|change| Ok(event_message(RaceEvent { change, ..event }))),
+                    )
+                });
+            Ok(Response::new(Box::pin(events)))
+        }
+    }
+
+    /// gRPC code for an HTTP error status of the REST handlers
+    pub(super) fn status_from_http(status: StatusCode, message: impl Into<String>) -> Status {
+        let code = match status {
+            StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => Code::InvalidArgument,
+            StatusCode::UNAUTHORIZED => Code::Unauthenticated,
+            StatusCode::FORBIDDEN => Code::PermissionDenied,
+            StatusCode::NOT_FOUND => Code::NotFound,
+            StatusCode::CONFLICT => Code::FailedPrecondition,
+            StatusCode::REQUEST_TIMEOUT | StatusCode::GATEWAY_TIMEOUT => Code::DeadlineExceeded,
+            StatusCode::TOO_MANY_REQUESTS => Code::ResourceExhausted,
+            StatusCode::SERVICE_UNAVAILABLE => Code::Unavailable,
+            _ => Code::Internal,
+        };
+        Status::new(code, message)
+    }
+
+    fn rating_window(window: proto::RatingWindow) -> RatingWindow {
+        RatingWindow {
+            min: window.min,
+            max: window.max,
+        }
+    }
+
+    fn component_rarity(rarity: i32) -> Result<Option<ComponentRarity>, Status> {
+        match proto::ComponentRarity::try_from(rarity) {
+            Ok(proto::ComponentRarity::Unspecified) => Ok(None),
+            Ok(proto::ComponentRarity::Common) => Ok(Some(ComponentRarity::Common)),
+            Ok(proto::ComponentRarity::Uncommon) => Ok(Some(ComponentRarity::Uncommon)),
+            Ok(proto::ComponentRarity::Rare) => Ok(Some(ComponentRarity::Rare)),
+            Ok(proto::ComponentRarity::Epic) => Ok(Some(ComponentRarity::Epic)),
+            Ok(proto::ComponentRarity::Legendary) => Ok(Some(ComponentRarity::Legendary)),
+            Err(_) => Err(Status::invalid_argument("Unknown component rarity")),
+        }
+    }
+
+    fn car_class(class: proto::CarClass) -> Result<CarClass, Status> {
+        Ok(CarClass {
+            name: class.name,
+            rating: class.rating.map(rating_window).unwrap_or_default(),
+            max_rarity: component_rarity(class.max_rarity)?,
+        })
+    }
+
+    fn race_message(race: &Race) -> proto::Race {
+        proto::Race {
+            uuid: race.uuid.to_string(),
+            name: race.name.clone(),
+            track_name: race.track.name.clone(),
+            status: race_status(&race.status).into(),
+            current_lap: race.current_lap,
+            total_laps: race.total_laps,
+            lap_characteristic: lap_characteristic(&race.lap_characteristic).into(),
+            participants: race.participants.iter().map(participant_message).collect(),
+        }
+    }
+
+    fn participant_message(participant: &RaceParticipant) -> proto::Participant {
+        proto::Participant {
+            player_uuid: participant.player_uuid.to_string(),
+            car_uuid: participant.car_uuid.to_string(),
+            current_sector: participant.current_sector,
+            position_in_sector: participant.current_position_in_sector,
+            current_lap: participant.current_lap,
+            total_value: participant.total_value,
+            is_finished: participant.is_finished,
+            finish_position: participant.finish_position,
+            is_bot: participant.bot.is_some(),
+            car_class: participant.car_class.clone(),
+            class_position: participant.class_position,
+            is_loaner: participant.is_loaner,
+        }
+    }
+
+    fn progress_message(progress: &RaceProgressStatus) -> proto::RaceProgress {
+        let status = match progress.status {
+            RaceStatusType::Waiting => proto::RaceStatus::Waiting,
+            RaceStatusType::Starting => proto::RaceStatus::Starting,
+            RaceStatusType::Ongoing => proto::RaceStatus::InProgress,
+            RaceStatusType::Finished => proto::RaceStatus::Finished,
+            RaceStatusType::Error { .. } => proto::RaceStatus::Unspecified,
+        };
+        proto::RaceProgress {
+            status: status.into(),
+            current_lap: progress.current_lap,
+            total_laps: progress.total_laps,
+            lap_characteristic: lap_characteristic(&progress.lap_characteristic).into(),
+            participants_count: progress.participants_count,
+            finished_participants: progress.finished_participants,
+            deadline_unix_millis: progress
+                .deadline_at
+                .map(|deadline| deadline.timestamp_millis()),
+            seconds_remaining: progress.seconds_remaining,
+        }
+    }
+
+    pub(super) fn event_message(event: RaceEvent) -> proto::RaceEvent {
+        let change = match event.change {
+            RaceChange::Movement { lap, movement } => Change::Movement(proto::Movement {
+                lap,
+                player_uuid: movement.player_uuid.to_string(),
+                from_sector: movement.from_sector,
+                to_sector: movement.to_sector,
+                final_value: movement.final_value,
+                movement_type: movement_type(&movement.movement_type).into(),
+            }),
+            RaceChange::PhaseChanged {
+                status,
+                current_lap,
+                lap_characteristic: characteristic,
+            } => Change::PhaseChanged(proto::PhaseChanged {
+                status: race_status(&status).into(),
+                current_lap,
+                lap_characteristic: lap_characteristic(&characteristic).into(),
+            }),
+            RaceChange::ParticipantUpdated { participant } => {
+                Change::ParticipantUpdated(participant_message(&participant))
+            }
+            RaceChange::Reaction {
+                lap,
+                player_uuid,
+                emote,
+            } => Change::Reaction(proto::Reaction {
+                lap,
+                player_uuid: player_uuid.to_string(),
+                emote: serde_json::to_value(emote)
+                    .ok()
+                    .and_then(|value| value.as_str().map(str::to_string))
+                    .unwrap_or_default(),
+            }),
+            RaceChange::CountdownTick {
+                starts_at,
+                seconds_remaining,
+            } => Change::CountdownTick(proto::CountdownTick {
+                starts_at_unix_millis: starts_at.timestamp_millis(),
+                seconds_remaining,
+            }),
+            RaceChange::TurnDeadlineNear {
+                lap,
+                deadline_at,
+                seconds_remaining,
+            } => Change::TurnDeadlineNear(proto::TurnDeadlineNear {
+                lap,
+                deadline_unix_millis: deadline_at.timestamp_millis(),
+                seconds_remaining,
+            }),
+            RaceChange::Commentary {
+                lap,
+                player_uuid,
+                text,
+            } => Change::Commentary(proto::Commentary {
+                lap,
+                player_uuid: player_uuid.to_string(),
+                text,
+            }),
+        };
+        proto::RaceEvent {
+            occurred_at_unix_millis: event.occurred_at.timestamp_millis(),
+            change: Some(change),
+        }
+    }
+
+    fn race_status(status: &RaceStatus) -> proto::RaceStatus {
+        match status {
+            RaceStatus::Waiting => proto::RaceStatus::Waiting,
+            RaceStatus::Starting => proto::RaceStatus::Starting,
+            RaceStatus::InProgress => proto::RaceStatus::InProgress,
+            RaceStatus::Finished => proto::RaceStatus::Finished,
+            RaceStatus::Cancelled => proto::RaceStatus::Cancelled,
+        }
+    }
+
+    fn lap_characteristic(characteristic: &LapCharacteristic) -> proto::LapCharacteristic {
+        match characteristic {
+            LapCharacteristic::Straight => proto::LapCharacteristic::Straight,
+            LapCharacteristic::Curve => proto::LapCharacteristic::Curve,
+        }
+    }
+
+    fn movement_type(movement_type: &MovementType) -> proto::MovementType {
+        match movement_type {
+            MovementType::StayedInSector => proto::MovementType::StayedInSector,
+            MovementType::MovedUp => proto::MovementType::MovedUp,
+            MovementType::MovedDown => proto::MovementType::MovedDown,
+            MovementType::FinishedLap => proto::MovementType::FinishedLap,
+            MovementType::FinishedRace => proto::MovementType::FinishedRace,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "grpc"))]
+mod tests {
+    use super::proto::race_event::Change;
+    use super::service::{check_api_key, event_message, status_from_http};
+    use crate::domain::{Emote, RaceChange, RaceEvent};
+    use axum::http::StatusCode;
+    use mongodb::bson::DateTime as BsonDateTime;
+    use secrecy::Secret;
+    use tonic::{Code, Request};
+    use uuid::Uuid;
+
+    #[test]
+    fn rest_errors_keep_their_meaning_as_grpc_codes() {
+        for (status, code) in [
+            (StatusCode::BAD_REQUEST, Code::InvalidArgument),
+            (StatusCode::NOT_FOUND, Code::NotFound),
+            (StatusCode::CONFLICT, Code::FailedPrecondition),
+            (StatusCode::SERVICE_UNAVAILABLE, Code::Unavailable),
+            (StatusCode::INTERNAL_SERVER_ERROR, Code::Internal),
+        ] {
+            let error = status_from_http(status, "Race not found");
+            assert_eq!(error.code(), code, "{status}");
+            assert_eq!(error.message(), "Race not found");
+        }
+    }
+
+    #[test]
+    fn calls_need_the_api_key_once_one_is_configured() {
+        let check = check_api_key(Some(Secret::new("server-key".to_string())));
+        let mut request = Request::new(());
+        request
+            .metadata_mut()
+            .insert("authorization", "Bearer server-key".parse().unwrap());
+
+        assert!(check(request).is_ok());
+        let error = check(Request::new(())).unwrap_err();
+        assert_eq!(error.code(), Code::Unauthenticated);
+        assert!(check_api_key(None)(Request::new(())).is_ok());
+    }
+
+    #[test]
+    fn reactions_carry_the_emote_name() {
+        let player_uuid = Uuid::new_v4();
+        let event = event_message(RaceEvent::new(
+            Uuid::new_v4(),
+            BsonDateTime::from_millis(1_700_000_000_000),
+            RaceChange::Reaction {
+                lap: 2,
+                player_uuid,
+                emote: Emote::ThumbsUp,
+            },
+        ));
+
+        assert_eq!(event.occurred_at_unix_millis, 1_700_000_000_000);
+        let Some(Change::Reaction(reaction)) = event.change else {
+            panic!("expected a reaction");
+        };
+        assert_eq!(reaction.emote, "thumbs_up");
+        assert_eq!(reaction.player_uuid, player_uuid.to_string());
+    }
+}
@@ -0,0 +1,33 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::run_periodically;
+use crate::configuration::Settings;
+use crate::services::AnalyticsExporter;
+
+/// Ship finished races to the analytics warehouse, as configured under
+/// `analytics_export`
+///
+/// Never returns, and does nothing while the export is disabled.
+pub async fn run_analytics_export_until_stopped(
+    configuration: Settings,
+) -> Result<(), anyhow::Error> {
+    let settings = configuration.analytics_export;
+    if !settings.enabled {
+        return std::future::pending().await;
+    }
+
+    let exporter = Arc::new(AnalyticsExporter::from_settings(&settings)?);
+    let period = Duration::from_secs(settings.interval_minutes.max(1) * 60);
+    run_periodically(
+        "Analytics export",
+        &configuration.database,
+        period,
+        |database| {
+            let exporter = exporter.clone();
+            async move { exporter.export_finished(&database).await }
+        },
+    )
+    .await;
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use std::time::Duration;
+
+use super::run_periodically;
+use crate::configuration::Settings;
+use crate::services::anticheat::AntiCheatAnalyzer;
+
+/// Flag suspicious play periodically, as configured under `anticheat`
+///
+/// Never returns, and does nothing while the analysis is disabled.
+pub async fn run_anticheat_until_stopped(configuration: Settings) -> Result<(), anyhow::Error> {
+    let settings = configuration.anticheat;
+    if !settings.enabled {
+        return std::future::pending().await;
+    }
+
+    let period = Duration::from_secs(settings.interval_minutes.max(1) * 60);
+    let analyzer = std::sync::Arc::new(AntiCheatAnalyzer::new(settings));
+    run_periodically(
+        "Anti-cheat analysis",
+        &configuration.database,
+        period,
+        |database| {
+            let analyzer = analyzer.clone();
+            async move { analyzer.run(&database).await }
+        },
+    )
+    .await;
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::run_periodically;
+use crate::configuration::Settings;
+use crate::services::email::{send_email_digests, Mailer};
+
+/// Mail players digests of their notifications, as configured under `email`
+///
+/// Never returns unless the email provider cannot be set up, and does nothing
+/// while the `none` backend is selected.
+pub async fn run_email_digests_until_stopped(configuration: Settings) -> Result<(), anyhow::Error> {
+    let settings = configuration.email;
+    let mailer = Arc::new(Mailer::from_settings(&settings)?);
+    if !mailer.is_enabled() {
+        return std::future::pending().await;
+    }
+
+    let period = Duration::from_mins(settings.interval_minutes.max(1));
+    run_periodically(
+        "Email digests",
+        &configuration.database,
+        period,
+        |database| {
+            let mailer = Arc::clone(&mailer);
+            async move { send_email_digests(&database, &mailer, settings.batch_size).await }
+        },
+    )
+    .await;
+    Ok(())
+}
@@ -0,0 +1,80 @@
+mod analytics_export;
+mod anticheat;
+mod email_digests;
+mod outbox_relay;
+mod player_erasure;
+mod push_notifications;
+mod race_anchoring;
+mod race_archive;
+mod race_countdowns;
+mod race_janitor;
+mod race_payouts;
+mod turn_deadlines;
+mod webhook_delivery;
+
+pub use analytics_export::*;
+pub use anticheat::*;
+pub use email_digests::*;
+pub use outbox_relay::*;
+pub use player_erasure::*;
+pub use push_notifications::*;
+pub use race_anchoring::*;
+pub use race_archive::*;
+pub use race_countdowns::*;
+pub use race_janitor::*;
+pub use race_payouts::*;
+pub use turn_deadlines::*;
+pub use webhook_delivery::*;
+
+use mongodb::Database;
+use std::future::Future;
+use std::time::Duration;
+
+use crate::configuration::{DatabaseBackend, DatabaseSettings};
+use crate::startup::get_connection_pool;
+
+/// Run `task` every `period` against `MongoDB` until the process stops
+///
+/// Jobs only apply to the `mongodb` backend; with another backend this never
+/// returns and does nothing. The connection is retried on the next tick when
+/// `MongoDB` is unreachable, and a failed run is logged without stopping the job.
+pub(crate) async fn run_periodically<F, Fut>(
+    name: &str,
+    database_settings: &DatabaseSettings,
+    period: Duration,
+    mut task: F,
+) where
+    F: FnMut(Database) -> Fut,
+    Fut: Future<Output = Result<u64, mongodb::error::Error>>,
+{
+    if database_settings.backend != DatabaseBackend::Mongodb {
+        tracing::info!("{} only runs with the mongodb backend", name);
+        return std::future::pending().await;
+    }
+
+    let mut interval = tokio::time::interval(period);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let mut database = None;
+    loop {
+        interval.tick().await;
+
+        if database.is_none() {
+            match get_connection_pool(database_settings).await {
+                Ok(pool) => database = Some(pool),
+                Err(e) => {
+                    tracing::warn!("{} could not connect to MongoDB: {}", name, e);
+                    continue;
+                }
+            }
+        }
+        let Some(database) = database.clone() else {
+            continue;
+        };
+
+        match task(database).await {
+            Ok(0) => tracing::debug!("{}: nothing to do", name),
+            Ok(count) => tracing::info!("{}: {} processed", name, count),
+            Err(e) => tracing::error!("{} failed: {:?}", name, e),
+        }
+    }
+}
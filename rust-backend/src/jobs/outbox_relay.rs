@@ -0,0 +1,28 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::run_periodically;
+use crate::configuration::Settings;
+use crate::services::{EventBus, OutboxRelay};
+
+/// Relay race outboxes to the event bus and webhook queue, as configured under
+/// `outbox` and `events`
+///
+/// Never returns unless the event bus cannot be set up.
+pub async fn run_outbox_relay_until_stopped(configuration: Settings) -> Result<(), anyhow::Error> {
+    let bus = EventBus::from_settings(&configuration.events).await?;
+    let relay = Arc::new(OutboxRelay::new(bus));
+    let settings = configuration.outbox;
+    let period = Duration::from_millis(settings.interval_milliseconds.max(100));
+    run_periodically(
+        "Outbox relay",
+        &configuration.database,
+        period,
+        |database| {
+            let relay = Arc::clone(&relay);
+            async move { relay.relay(&database, settings.batch_size).await }
+        },
+    )
+    .await;
+    Ok(())
+}
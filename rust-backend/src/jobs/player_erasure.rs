@@ -0,0 +1,28 @@
+use std::time::Duration;
+
+use super::run_periodically;
+use crate::configuration::Settings;
+use crate::services::privacy::erase_due_players;
+
+/// Erase the accounts whose grace period is over, as configured under `erasure`
+///
+/// Never returns, and does nothing while erasure is disabled.
+pub async fn run_player_erasure_until_stopped(
+    configuration: Settings,
+) -> Result<(), anyhow::Error> {
+    let settings = configuration.erasure;
+    if !settings.enabled {
+        return std::future::pending().await;
+    }
+
+    let grace = Duration::from_secs(settings.grace_days * 24 * 60 * 60);
+    let period = Duration::from_secs(settings.interval_minutes.max(1) * 60);
+    run_periodically(
+        "Player erasure",
+        &configuration.database,
+        period,
+        |database| async move { erase_due_players(&database, grace, settings.batch_size).await },
+    )
+    .await;
+    Ok(())
+}
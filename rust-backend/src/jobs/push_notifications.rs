@@ -0,0 +1,42 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::run_periodically;
+use crate::configuration::Settings;
+use crate::services::push::remind_waiting_players;
+use crate::services::PushSender;
+
+/// Remind players of laps waiting on them and push pending notifications to
+/// their devices, as configured under `push`
+///
+/// Never returns unless the platform credentials cannot be loaded, and does
+/// nothing while push is disabled.
+pub async fn run_push_notifier_until_stopped(configuration: Settings) -> Result<(), anyhow::Error> {
+    let settings = configuration.push;
+    if !settings.enabled {
+        return std::future::pending().await;
+    }
+
+    let sender = Arc::new(PushSender::from_settings(&settings)?);
+    let remind_after = Duration::from_mins(settings.turn_reminder_after_minutes);
+    let period = Duration::from_secs(settings.interval_seconds.max(1));
+    run_periodically(
+        "Push notifier",
+        &configuration.database,
+        period,
+        |database| {
+            let sender = Arc::clone(&sender);
+            async move {
+                let mut handled = 0;
+                if !remind_after.is_zero() {
+                    handled += remind_waiting_players(&database, remind_after, settings.batch_size)
+                        .await?;
+                }
+                handled += sender.send_pending(&database, settings.batch_size).await?;
+                Ok(handled)
+            }
+        },
+    )
+    .await;
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use std::time::Duration;
+
+use super::run_periodically;
+use crate::configuration::Settings;
+use crate::services::RaceAnchorer;
+
+/// Anchor finished race results on Solana, as configured under `anchoring`
+///
+/// Never returns, and does nothing while anchoring is disabled.
+pub async fn run_race_anchoring_until_stopped(
+    configuration: Settings,
+) -> Result<(), anyhow::Error> {
+    let settings = configuration.anchoring;
+    if !settings.enabled {
+        return std::future::pending().await;
+    }
+
+    let anchorer = RaceAnchorer::from_settings(&settings)?;
+    let period = Duration::from_secs(settings.interval_seconds.max(1));
+    run_periodically(
+        "Race anchoring",
+        &configuration.database,
+        period,
+        |database| {
+            let anchorer = anchorer.clone();
+            async move {
+                anchorer
+                    .anchor_finished(&database, settings.batch_size)
+                    .await
+            }
+        },
+    )
+    .await;
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use mongodb::bson::DateTime as BsonDateTime;
+use std::time::{Duration, SystemTime};
+
+use super::run_periodically;
+use crate::configuration::Settings;
+use crate::database::archive_finished_races;
+
+/// Archive old finished races periodically, as configured under `archive`
+///
+/// Never returns, and does nothing while archiving is disabled.
+pub async fn run_archive_worker_until_stopped(
+    configuration: Settings,
+) -> Result<(), anyhow::Error> {
+    let settings = configuration.archive;
+    let wait_for_anchor = configuration.anchoring.enabled;
+    if !settings.enabled {
+        return std::future::pending().await;
+    }
+
+    let keep_for = Duration::from_secs(settings.finished_after_days * 24 * 60 * 60);
+    let period = Duration::from_secs(settings.interval_minutes.max(1) * 60);
+    run_periodically(
+        "Race archive worker",
+        &configuration.database,
+        period,
+        |database| async move {
+            let cutoff = SystemTime::now()
+                .checked_sub(keep_for)
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            archive_finished_races(
+                &database,
+                BsonDateTime::from_system_time(cutoff),
+                settings.batch_size,
+                wait_for_anchor,
+            )
+            .await
+        },
+    )
+    .await;
+    Ok(())
+}
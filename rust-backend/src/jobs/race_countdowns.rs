@@ -0,0 +1,27 @@
+use std::time::Duration;
+
+use super::run_periodically;
+use crate::configuration::Settings;
+use crate::routes::races::finish_countdowns_in_db;
+
+/// Tick the start countdown of races every second, as configured under
+/// `countdowns`, and open their first lap once it is over
+///
+/// Never returns, and does nothing while countdowns are disabled.
+pub async fn run_race_countdowns_until_stopped(
+    configuration: Settings,
+) -> Result<(), anyhow::Error> {
+    let settings = configuration.countdowns;
+    if !settings.enabled {
+        return std::future::pending().await;
+    }
+
+    run_periodically(
+        "Race countdowns",
+        &configuration.database,
+        Duration::from_secs(1),
+        |database| async move { finish_countdowns_in_db(&database, settings.batch_size).await },
+    )
+    .await;
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use mongodb::{bson::DateTime as BsonDateTime, Database};
+use std::time::{Duration, SystemTime};
+
+use super::run_periodically;
+use crate::configuration::Settings;
+use crate::domain::{Notification, Race};
+use crate::routes::players::insert_notification_in_db;
+use crate::routes::races::cancel_idle_races_in_db;
+
+/// Cancel abandoned waiting races periodically, as configured under `janitor`
+///
+/// Never returns, and does nothing while the janitor is disabled.
+pub async fn run_race_janitor_until_stopped(configuration: Settings) -> Result<(), anyhow::Error> {
+    let settings = configuration.janitor;
+    if !settings.enabled {
+        return std::future::pending().await;
+    }
+
+    let idle_for = Duration::from_secs(settings.idle_after_hours * 60 * 60);
+    let period = Duration::from_secs(settings.interval_minutes.max(1) * 60);
+    let reason = format!(
+        "nobody joined or started it for {} hour(s)",
+        settings.idle_after_hours
+    );
+    run_periodically(
+        "Race janitor",
+        &configuration.database,
+        period,
+        |database| {
+            let reason = reason.clone();
+            async move {
+                let idle_since = SystemTime::now()
+                    .checked_sub(idle_for)
+                    .unwrap_or(SystemTime::UNIX_EPOCH);
+                let cancelled = cancel_idle_races_in_db(
+                    &database,
+                    BsonDateTime::from_system_time(idle_since),
+                    settings.batch_size,
+                )
+                .await?;
+
+                if settings.notify_creator {
+                    for race in &cancelled {
+                        notify_creator(&database, race, &reason).await;
+                    }
+                }
+                Ok(cancelled.len() as u64)
+            }
+        },
+    )
+    .await;
+    Ok(())
+}
+
+/// A failed notification is logged; the race stays cancelled either way
+async fn notify_creator(database: &Database, race: &Race, reason: &str) {
+    let Some(creator) = race.creator() else {
+        return;
+    };
+    let notification = Notification::race_cancelled(creator, race, reason);
+    if let Err(e) = insert_notification_in_db(database, &notification).await {
+        tracing::error!(
+            "Failed to notify player {} of cancelled race {}: {:?}",
+            creator,
+            race.uuid,
+            e
+        );
+    }
+}
@@ -0,0 +1,29 @@
+use std::time::Duration;
+
+use super::run_periodically;
+use crate::configuration::Settings;
+use crate::services::PayoutSender;
+
+/// Queue and pay race prizes, as configured under `payouts`
+///
+/// Never returns, and does nothing while payouts are disabled.
+pub async fn run_payout_worker_until_stopped(configuration: Settings) -> Result<(), anyhow::Error> {
+    let settings = configuration.payouts;
+    if !settings.enabled {
+        return std::future::pending().await;
+    }
+
+    let sender = PayoutSender::from_settings(&settings)?;
+    let period = Duration::from_secs(settings.interval_seconds.max(1));
+    run_periodically(
+        "Payout worker",
+        &configuration.database,
+        period,
+        |database| {
+            let sender = sender.clone();
+            async move { sender.run(&database, settings.batch_size).await }
+        },
+    )
+    .await;
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use mongodb::bson::DateTime as BsonDateTime;
+use std::time::{Duration, SystemTime};
+
+use super::run_periodically;
+use crate::configuration::Settings;
+use crate::routes::races::warn_near_turn_deadlines_in_db;
+
+/// Warn participants of near lap deadlines periodically, as configured under
+/// `turn_deadlines`
+///
+/// Never returns, and does nothing while the warnings are disabled.
+pub async fn run_turn_deadline_warnings_until_stopped(
+    configuration: Settings,
+) -> Result<(), anyhow::Error> {
+    let settings = configuration.turn_deadlines;
+    if !settings.enabled {
+        return std::future::pending().await;
+    }
+
+    let warn_before = Duration::from_secs(settings.warning_seconds);
+    let period = Duration::from_secs(settings.interval_seconds.max(1));
+    run_periodically(
+        "Turn deadline warnings",
+        &configuration.database,
+        period,
+        |database| async move {
+            let warn_until = SystemTime::now() + warn_before;
+            warn_near_turn_deadlines_in_db(
+                &database,
+                BsonDateTime::from_system_time(warn_until),
+                settings.batch_size,
+            )
+            .await
+        },
+    )
+    .await;
+    Ok(())
+}
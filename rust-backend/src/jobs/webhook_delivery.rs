@@ -0,0 +1,31 @@
+use std::time::Duration;
+
+use super::run_periodically;
+use crate::configuration::Settings;
+use crate::services::WebhookSender;
+
+/// Send queued race webhooks, as configured under `webhooks`
+///
+/// Never returns, and does nothing while delivery is disabled.
+pub async fn run_webhook_sender_until_stopped(
+    configuration: Settings,
+) -> Result<(), anyhow::Error> {
+    let settings = configuration.webhooks;
+    if !settings.enabled {
+        return std::future::pending().await;
+    }
+
+    let sender = WebhookSender::from_settings(&settings)?;
+    let period = Duration::from_secs(settings.interval_seconds.max(1));
+    run_periodically(
+        "Webhook sender",
+        &configuration.database,
+        period,
+        |database| {
+            let sender = sender.clone();
+            async move { sender.deliver_due(&database, settings.batch_size).await }
+        },
+    )
+    .await;
+    Ok(())
+}
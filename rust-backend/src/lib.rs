@@ -1,12 +1,20 @@
 pub mod app_state;
 pub mod configuration;
+pub mod database;
 pub mod domain;
+pub mod engine;
+#[cfg(feature = "graphql")]
+pub mod graphql;
+pub mod grpc;
+pub mod jobs;
 pub mod middleware;
 pub mod repositories;
 pub mod routes;
+pub mod secrets;
 pub mod services;
 pub mod startup;
 pub mod telemetry;
+pub mod tls;
 
 // Make test_utils available for integration tests
 #[cfg(any(test, feature = "test-utils"))]
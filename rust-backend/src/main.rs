@@ -1,27 +1,73 @@
 use rust_backend::configuration::get_configuration;
+use rust_backend::jobs::{
+    run_analytics_export_until_stopped, run_anticheat_until_stopped,
+    run_archive_worker_until_stopped, run_email_digests_until_stopped,
+    run_outbox_relay_until_stopped, run_payout_worker_until_stopped,
+    run_player_erasure_until_stopped, run_push_notifier_until_stopped,
+    run_race_anchoring_until_stopped, run_race_countdowns_until_stopped,
+    run_race_janitor_until_stopped, run_turn_deadline_warnings_until_stopped,
+    run_webhook_sender_until_stopped,
+};
 use rust_backend::startup::Application;
-use rust_backend::telemetry::{get_subscriber, init_subscriber};
+use rust_backend::telemetry::{
+    get_otlp_layer, get_sentry_layer, get_subscriber, init_sentry, init_subscriber, shutdown_otlp,
+};
+use std::fmt::{Debug, Display};
+use tracing_subscriber::layer::SubscriberExt;
 
 // Feature #18: CI pipeline fixes applied
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    let configuration = get_configuration().expect("Failed to read configuration.");
+    let _sentry = init_sentry(&configuration.telemetry)?;
+
     let subscriber = get_subscriber("rust-backend".into(), "info".into(), std::io::stdout);
-    init_subscriber(subscriber);
+    let otlp = get_otlp_layer("rust-backend", &configuration.telemetry)?;
+    let sentry = get_sentry_layer(&configuration.telemetry);
+    init_subscriber(subscriber.with(otlp).with(sentry));
 
-    let configuration = get_configuration().expect("Failed to read configuration.");
-    let application = Application::build(configuration).await?;
+    let application = Application::build(configuration.clone()).await?;
     let application_task = tokio::spawn(application.run_until_stopped());
+    let archive_task = tokio::spawn(run_archive_worker_until_stopped(configuration.clone()));
+    let janitor_task = tokio::spawn(run_race_janitor_until_stopped(configuration.clone()));
+    let countdown_task = tokio::spawn(run_race_countdowns_until_stopped(configuration.clone()));
+    let deadline_task = tokio::spawn(run_turn_deadline_warnings_until_stopped(
+        configuration.clone(),
+    ));
+    let outbox_task = tokio::spawn(run_outbox_relay_until_stopped(configuration.clone()));
+    let push_task = tokio::spawn(run_push_notifier_until_stopped(configuration.clone()));
+    let email_task = tokio::spawn(run_email_digests_until_stopped(configuration.clone()));
+    let webhook_task = tokio::spawn(run_webhook_sender_until_stopped(configuration.clone()));
+    let anchoring_task = tokio::spawn(run_race_anchoring_until_stopped(configuration.clone()));
+    let payout_task = tokio::spawn(run_payout_worker_until_stopped(configuration.clone()));
+    let anticheat_task = tokio::spawn(run_anticheat_until_stopped(configuration.clone()));
+    let analytics_task = tokio::spawn(run_analytics_export_until_stopped(configuration.clone()));
+    let erasure_task = tokio::spawn(run_player_erasure_until_stopped(configuration));
 
     tokio::select! {
         o = application_task => report_exit("API", o),
+        o = archive_task => report_exit("Race archive worker", o),
+        o = janitor_task => report_exit("Race janitor", o),
+        o = countdown_task => report_exit("Race countdowns", o),
+        o = deadline_task => report_exit("Turn deadline warnings", o),
+        o = outbox_task => report_exit("Outbox relay", o),
+        o = push_task => report_exit("Push notifier", o),
+        o = email_task => report_exit("Email digests", o),
+        o = webhook_task => report_exit("Webhook sender", o),
+        o = anchoring_task => report_exit("Race anchoring", o),
+        o = payout_task => report_exit("Payout worker", o),
+        o = anticheat_task => report_exit("Anti-cheat analysis", o),
+        o = analytics_task => report_exit("Analytics export", o),
+        o = erasure_task => report_exit("Player erasure", o),
     };
 
+    shutdown_otlp();
     Ok(())
 }
 
 fn report_exit(
     task_name: &str,
-    outcome: Result<Result<(), std::io::Error>, tokio::task::JoinError>,
+    outcome: Result<Result<(), impl Debug + Display>, tokio::task::JoinError>,
 ) {
     match outcome {
         Ok(Ok(())) => {
@@ -6,13 +6,15 @@ use axum::{
 };
 
 use futures_util::future::BoxFuture;
+use mongodb::{bson::doc, Database};
 use serde_json::json;
 use std::sync::Arc;
 use tower::{Layer, Service};
 use uuid::Uuid;
 
-use crate::domain::UserRole;
-use crate::repositories::MockSessionRepository;
+use crate::domain::{ApiScope, ApiToken, Permission, UserRole, API_TOKEN_PREFIX};
+use crate::repositories::InMemorySessionRepository;
+use crate::routes::api_tokens::API_TOKENS;
 use crate::services::{JwtService, SessionManager};
 
 /// User context extracted from valid JWT token
@@ -22,6 +24,27 @@ pub struct UserContext {
     pub email: String,
     pub role: UserRole,
     pub token_id: String,
+    /// Fingerprint of the device the session was opened on
+    pub device_fingerprint: Option<String>,
+    /// Permissions carried in the access token
+    pub permissions: Vec<Permission>,
+}
+
+impl UserContext {
+    /// Whether the access token grants `permission`
+    #[must_use]
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.permissions.contains(&permission)
+    }
+}
+
+/// API token a request was authenticated with, set instead of a
+/// [`UserContext`]
+#[derive(Debug, Clone)]
+pub struct ApiTokenContext {
+    pub token_uuid: Uuid,
+    pub owner_uuid: Uuid,
+    pub scopes: Vec<ApiScope>,
 }
 
 /// Authentication errors
@@ -35,6 +58,8 @@ pub enum AuthError {
     TokenExpired,
     #[error("Token is blacklisted")]
     BlacklistedToken,
+    #[error("Token lacks the {0} scope")]
+    InsufficientScope(&'static str),
     #[error("Internal authentication error: {0}")]
     InternalError(String),
 }
@@ -46,16 +71,25 @@ impl From<AuthError> for StatusCode {
             | AuthError::InvalidToken
             | AuthError::TokenExpired
             | AuthError::BlacklistedToken => StatusCode::UNAUTHORIZED,
+            AuthError::InsufficientScope(_) => StatusCode::FORBIDDEN,
             AuthError::InternalError(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
 }
 
+/// Where API tokens are looked up, and the scope the routes need
+#[derive(Clone)]
+struct ApiTokenPolicy {
+    database: Database,
+    scope: ApiScope,
+}
+
 /// Authentication middleware layer
 #[derive(Clone)]
 pub struct AuthMiddleware {
     jwt_service: Arc<JwtService>,
-    session_manager: Arc<SessionManager<MockSessionRepository>>,
+    session_manager: Arc<SessionManager<InMemorySessionRepository>>,
+    api_tokens: Option<ApiTokenPolicy>,
 }
 
 impl AuthMiddleware {
@@ -63,14 +97,25 @@ impl AuthMiddleware {
     #[must_use]
     pub fn new(
         jwt_service: Arc<JwtService>,
-        session_manager: Arc<SessionManager<MockSessionRepository>>,
+        session_manager: Arc<SessionManager<InMemorySessionRepository>>,
     ) -> Self {
         Self {
             jwt_service,
             session_manager,
+            api_tokens: None,
         }
     }
 
+    /// Also let in API tokens granting `scope`, looked up in `database`
+    ///
+    /// Requests authenticated with an API token get an [`ApiTokenContext`]
+    /// rather than a [`UserContext`], so only read routes should accept them.
+    #[must_use]
+    pub fn accepting_api_tokens(mut self, database: Database, scope: ApiScope) -> Self {
+        self.api_tokens = Some(ApiTokenPolicy { database, scope });
+        self
+    }
+
     /// Extract token from request (Authorization header or cookie)
     fn extract_token_from_request(request: &Request) -> Option<String> {
         // Try Authorization header first (for API clients)
@@ -97,6 +142,32 @@ impl AuthMiddleware {
         None
     }
 
+    /// Look up an API token and check it grants the policy's scope
+    async fn validate_api_token(
+        policy: &ApiTokenPolicy,
+        token: &str,
+    ) -> Result<ApiTokenContext, AuthError> {
+        let api_token = policy
+            .database
+            .collection::<ApiToken>(API_TOKENS)
+            .find_one(
+                doc! { "token_hash": ApiToken::hash(token), "revoked_at": null },
+                None,
+            )
+            .await
+            .map_err(|e| AuthError::InternalError(e.to_string()))?
+            .ok_or(AuthError::InvalidToken)?;
+        if !api_token.allows(policy.scope) {
+            return Err(AuthError::InsufficientScope(policy.scope.as_str()));
+        }
+
+        Ok(ApiTokenContext {
+            token_uuid: api_token.uuid,
+            owner_uuid: api_token.owner_uuid,
+            scopes: api_token.scopes,
+        })
+    }
+
     /// Validate token and create user context
     async fn validate_and_create_context(&self, token: &str) -> Result<UserContext, AuthError> {
         // Validate JWT token
@@ -140,11 +211,20 @@ impl AuthMiddleware {
         // Parse user UUID
         let user_uuid = Uuid::parse_str(&claims.sub).map_err(|_| AuthError::InvalidToken)?;
 
+        let device_fingerprint = self
+            .session_manager
+            .find_session(&claims.jti)
+            .await
+            .map_err(|e| AuthError::InternalError(e.to_string()))?
+            .and_then(|session| session.device_fingerprint);
+
         Ok(UserContext {
             user_uuid,
             email: claims.email,
             role: claims.role,
             token_id: claims.jti,
+            device_fingerprint,
+            permissions: claims.permissions,
         })
     }
 }
@@ -157,6 +237,7 @@ impl<S> Layer<S> for AuthMiddleware {
             inner,
             jwt_service: self.jwt_service.clone(),
             session_manager: self.session_manager.clone(),
+            api_tokens: self.api_tokens.clone(),
         }
     }
 }
@@ -166,7 +247,8 @@ impl<S> Layer<S> for AuthMiddleware {
 pub struct AuthService<S> {
     inner: S,
     jwt_service: Arc<JwtService>,
-    session_manager: Arc<SessionManager<MockSessionRepository>>,
+    session_manager: Arc<SessionManager<InMemorySessionRepository>>,
+    api_tokens: Option<ApiTokenPolicy>,
 }
 
 impl<S> Service<Request> for AuthService<S>
@@ -188,6 +270,7 @@ where
     fn call(&mut self, mut request: Request) -> Self::Future {
         let jwt_service = self.jwt_service.clone();
         let session_manager = self.session_manager.clone();
+        let api_tokens = self.api_tokens.clone();
         let mut inner = self.inner.clone();
 
         Box::pin(async move {
@@ -208,62 +291,80 @@ where
                 return Ok(error_response);
             };
 
+            // API tokens are only looked up where the routes accept them
+            let api_token_policy = api_tokens
+                .as_ref()
+                .filter(|_| token.starts_with(API_TOKEN_PREFIX));
+
             // Create auth middleware instance for validation
             let auth_middleware = AuthMiddleware {
                 jwt_service,
                 session_manager,
+                api_tokens: None,
             };
 
-            // Validate token and create user context
-            let user_context = match auth_middleware.validate_and_create_context(&token).await {
-                Ok(context) => context,
-                Err(error) => {
-                    let (status, error_code, message) = match error {
-                        AuthError::MissingToken => (
-                            StatusCode::UNAUTHORIZED,
-                            "authentication_required",
-                            "Authentication token is required",
-                        ),
-                        AuthError::InvalidToken => (
-                            StatusCode::UNAUTHORIZED,
-                            "invalid_token",
-                            "The provided token is invalid",
-                        ),
-                        AuthError::TokenExpired => (
-                            StatusCode::UNAUTHORIZED,
-                            "token_expired",
-                            "The provided token has expired",
-                        ),
-                        AuthError::BlacklistedToken => (
-                            StatusCode::UNAUTHORIZED,
-                            "token_revoked",
-                            "The provided token has been revoked",
-                        ),
-                        AuthError::InternalError(_) => (
-                            StatusCode::INTERNAL_SERVER_ERROR,
-                            "internal_error",
-                            "An internal authentication error occurred",
-                        ),
-                    };
-
-                    let error_response = Response::builder()
-                        .status(status)
-                        .header("content-type", "application/json")
-                        .body(
-                            json!({
-                                "error": error_code,
-                                "message": message
-                            })
-                            .to_string()
-                            .into(),
-                        )
-                        .unwrap();
-                    return Ok(error_response);
-                }
+            // Validate token and create user or API token context
+            let validated = match api_token_policy {
+                Some(policy) => AuthMiddleware::validate_api_token(policy, &token)
+                    .await
+                    .map(|context| {
+                        request.extensions_mut().insert(context);
+                    }),
+                None => auth_middleware
+                    .validate_and_create_context(&token)
+                    .await
+                    .map(|context| {
+                        request.extensions_mut().insert(context);
+                    }),
             };
+            if let Err(error) = validated {
+                let (status, error_code, message) = match error {
+                    AuthError::MissingToken => (
+                        StatusCode::UNAUTHORIZED,
+                        "authentication_required",
+                        "Authentication token is required",
+                    ),
+                    AuthError::InvalidToken => (
+                        StatusCode::UNAUTHORIZED,
+                        "invalid_token",
+                        "The provided token is invalid",
+                    ),
+                    AuthError::TokenExpired => (
+                        StatusCode::UNAUTHORIZED,
+                        "token_expired",
+                        "The provided token has expired",
+                    ),
+                    AuthError::BlacklistedToken => (
+                        StatusCode::UNAUTHORIZED,
+                        "token_revoked",
+                        "The provided token has been revoked",
+                    ),
+                    AuthError::InsufficientScope(_) => (
+                        StatusCode::FORBIDDEN,
+                        "insufficient_scope",
+                        "The provided token does not grant access to this route",
+                    ),
+                    AuthError::InternalError(_) => (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "internal_error",
+                        "An internal authentication error occurred",
+                    ),
+                };
 
-            // Add user context to request extensions
-            request.extensions_mut().insert(user_context);
+                let error_response = Response::builder()
+                    .status(status)
+                    .header("content-type", "application/json")
+                    .body(
+                        json!({
+                            "error": error_code,
+                            "message": message
+                        })
+                        .to_string()
+                        .into(),
+                    )
+                    .unwrap();
+                return Ok(error_response);
+            }
 
             // Continue to the next middleware/handler
             inner.call(request).await
@@ -313,6 +414,8 @@ mod tests {
             email: "test@example.com".to_string(),
             role: UserRole::Player,
             token_id: "test_token_id".to_string(),
+            device_fingerprint: None,
+            permissions: Vec::new(),
         };
 
         assert_eq!(context.user_uuid, user_uuid);
@@ -339,6 +442,10 @@ mod tests {
             StatusCode::from(AuthError::BlacklistedToken),
             StatusCode::UNAUTHORIZED
         );
+        assert_eq!(
+            StatusCode::from(AuthError::InsufficientScope("read:races")),
+            StatusCode::FORBIDDEN
+        );
         assert_eq!(
             StatusCode::from(AuthError::InternalError("test".to_string())),
             StatusCode::INTERNAL_SERVER_ERROR
@@ -0,0 +1,155 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::Request,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use flate2::{write::GzEncoder, Compression};
+use std::io::Write;
+
+/// Smallest body worth compressing; below it the gzip overhead eats the gain
+const MIN_COMPRESSED_BYTES: usize = 1024;
+
+/// Middleware gzipping JSON and text responses for clients that accept it
+///
+/// Race status and detailed race payloads repeat the same field names for
+/// every participant and shrink several times over.
+pub async fn compress_responses(request: Request, next: Next) -> Response {
+    let accepts_gzip = accepts_gzip(request.headers());
+    let response = next.run(request).await;
+    if !accepts_gzip || !is_compressible(&response) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::warn!("Response body not read for compression: {}", e);
+            return Response::from_parts(parts, Body::empty());
+        }
+    };
+    parts
+        .headers
+        .append(header::VARY, HeaderValue::from_static("accept-encoding"));
+    if bytes.len() < MIN_COMPRESSED_BYTES {
+        return Response::from_parts(parts, Body::from(bytes));
+    }
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    match encoder.write_all(&bytes).and_then(|()| encoder.finish()) {
+        Ok(compressed) => {
+            parts.headers.remove(header::CONTENT_LENGTH);
+            parts
+                .headers
+                .insert(header::CONTENT_ENCODING, HeaderValue::from_static("gzip"));
+            Response::from_parts(parts, Body::from(compressed))
+        }
+        Err(e) => {
+            tracing::warn!("Response not compressed: {}", e);
+            Response::from_parts(parts, Body::from(bytes))
+        }
+    }
+}
+
+/// Whether `Accept-Encoding` lists gzip, or `*`, without `q=0`
+fn accepts_gzip(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::ACCEPT_ENCODING)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|coding| {
+            let mut parameters = coding.split(';').map(str::trim);
+            let name = parameters.next().unwrap_or_default();
+            let refused = parameters.any(|parameter| {
+                parameter
+                    .strip_prefix("q=")
+                    .and_then(|q| q.parse::<f32>().ok())
+                    .is_some_and(|q| q <= 0.0)
+            });
+            (name.eq_ignore_ascii_case("gzip") || name == "*") && !refused
+        })
+}
+
+fn is_compressible(response: &Response) -> bool {
+    let status = response.status();
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    status != StatusCode::SWITCHING_PROTOCOLS
+        && status != StatusCode::NO_CONTENT
+        && status != StatusCode::NOT_MODIFIED
+        && !response.headers().contains_key(header::CONTENT_ENCODING)
+        && (content_type.starts_with("application/json")
+            || (content_type.starts_with("text/")
+                && !content_type.starts_with("text/event-stream")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::get, Json, Router};
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    #[test]
+    fn gzip_is_accepted_unless_refused() {
+        let headers = |value: &'static str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::ACCEPT_ENCODING, HeaderValue::from_static(value));
+            headers
+        };
+        assert!(accepts_gzip(&headers("gzip, deflate, br")));
+        assert!(accepts_gzip(&headers("br;q=1.0, *;q=0.5")));
+        assert!(!accepts_gzip(&headers("gzip;q=0, br")));
+        assert!(!accepts_gzip(&headers("identity")));
+        assert!(!accepts_gzip(&HeaderMap::new()));
+    }
+
+    #[tokio::test]
+    async fn large_json_responses_are_gzipped() {
+        let participants: Vec<_> = (0..100)
+            .map(|position| serde_json::json!({ "player_uuid": "x", "position": position }))
+            .collect();
+        let expected = serde_json::Value::from(participants.clone());
+        let app = Router::new()
+            .route("/race", get(move || async move { Json(participants) }))
+            .route("/small", get(|| async { Json("ok") }))
+            .layer(axum::middleware::from_fn(compress_responses));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        let client = reqwest::Client::new();
+
+        let response = client
+            .get(format!("http://{address}/race"))
+            .header("Accept-Encoding", "gzip")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.headers()["content-encoding"], "gzip");
+        let compressed = response.bytes().await.unwrap();
+        let mut json = String::new();
+        GzDecoder::new(&compressed[..])
+            .read_to_string(&mut json)
+            .unwrap();
+        assert!(compressed.len() < json.len());
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&json).unwrap(),
+            expected
+        );
+
+        let response = client
+            .get(format!("http://{address}/small"))
+            .header("Accept-Encoding", "gzip")
+            .send()
+            .await
+            .unwrap();
+        assert!(response.headers().get("content-encoding").is_none());
+        assert_eq!(response.text().await.unwrap(), "\"ok\"");
+    }
+}
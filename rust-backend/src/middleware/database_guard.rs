@@ -0,0 +1,55 @@
+use axum::{
+    extract::Request,
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use std::time::Duration;
+
+use crate::database::database_breaker;
+use crate::routes::races::ErrorResponse;
+
+/// Middleware failing API requests at once while the database circuit is open
+///
+/// Requests would otherwise queue up behind a database that is not
+/// answering, each holding a connection until it times out. The health check
+/// and metrics stay reachable to report the outage.
+pub async fn reject_while_database_down(request: Request, next: Next) -> Response {
+    if request.uri().path().starts_with("/api/") {
+        if let Some(retry_after) = database_breaker().open_for() {
+            return database_unavailable(retry_after);
+        }
+    }
+    next.run(request).await
+}
+
+/// 503 telling the client when the database may be back
+#[must_use]
+pub fn database_unavailable(retry_after: Duration) -> Response {
+    let seconds = retry_after.as_secs().max(1);
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        [(header::RETRY_AFTER, seconds.to_string())],
+        Json(ErrorResponse {
+            error: "DATABASE_UNAVAILABLE".to_string(),
+            message: "The database is unavailable, please retry shortly".to_string(),
+            details: Some(format!("Retry in {seconds}s")),
+        }),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unavailable_responses_say_when_to_retry() {
+        let response = database_unavailable(Duration::from_millis(4500));
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "4");
+
+        let response = database_unavailable(Duration::ZERO);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "1");
+    }
+}
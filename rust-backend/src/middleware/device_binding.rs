@@ -0,0 +1,78 @@
+use axum::{
+    extract::Request,
+    http::{HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use serde_json::json;
+
+use super::UserContext;
+use crate::services::session::DEVICE_FINGERPRINT_HEADER;
+
+/// Middleware refusing requests from another device than the session's
+///
+/// Layered inside [`super::AuthMiddleware`] on sensitive routes, so a stolen
+/// token is of no use for them without the device fingerprint it was issued
+/// to. Sessions opened without a fingerprint cannot use these routes.
+pub async fn require_bound_device(request: Request, next: Next) -> Response {
+    let bound = request
+        .extensions()
+        .get::<UserContext>()
+        .is_some_and(|user| from_bound_device(user, request.headers()));
+    if !bound {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(json!({
+                "error": "device_mismatch",
+                "message": "This operation must come from the device you signed in on"
+            })),
+        )
+            .into_response();
+    }
+    next.run(request).await
+}
+
+/// Whether the request carries the fingerprint of the session's device
+fn from_bound_device(user: &UserContext, headers: &HeaderMap) -> bool {
+    let fingerprint = headers
+        .get(DEVICE_FINGERPRINT_HEADER)
+        .and_then(|h| h.to_str().ok());
+    matches!(
+        (user.device_fingerprint.as_deref(), fingerprint),
+        (Some(bound), Some(sent)) if bound == sent
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::UserRole;
+    use axum::http::HeaderValue;
+    use uuid::Uuid;
+
+    #[test]
+    fn only_the_sessions_device_is_accepted() {
+        let mut user = UserContext {
+            user_uuid: Uuid::new_v4(),
+            email: "test@example.com".to_string(),
+            role: UserRole::Player,
+            token_id: "token".to_string(),
+            device_fingerprint: Some("phone".to_string()),
+            permissions: Vec::new(),
+        };
+        let mut headers = HeaderMap::new();
+        assert!(!from_bound_device(&user, &headers));
+
+        headers.insert(
+            DEVICE_FINGERPRINT_HEADER,
+            HeaderValue::from_static("laptop"),
+        );
+        assert!(!from_bound_device(&user, &headers));
+
+        headers.insert(DEVICE_FINGERPRINT_HEADER, HeaderValue::from_static("phone"));
+        assert!(from_bound_device(&user, &headers));
+
+        user.device_fingerprint = None;
+        assert!(!from_bound_device(&user, &headers));
+    }
+}
@@ -0,0 +1,142 @@
+use axum::{
+    extract::Request,
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use std::any::Any;
+use uuid::Uuid;
+
+use crate::routes::races::ErrorResponse;
+use crate::services::race_cache::uuid_from_path;
+
+/// Race and player a request is about, for grouping error reports
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ReportTags {
+    pub race_uuid: Option<Uuid>,
+    pub player_uuid: Option<Uuid>,
+}
+
+impl ReportTags {
+    /// Tags read from the path, e.g. `/races/{race_uuid}/...`, or from a
+    /// `player_uuid` query parameter when the path names no player
+    #[must_use]
+    pub fn from_request(request: &Request) -> Self {
+        let path = request.uri().path();
+        let player_in_query = || {
+            request
+                .uri()
+                .query()?
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("player_uuid="))
+                .and_then(|value| Uuid::parse_str(value).ok())
+        };
+        Self {
+            race_uuid: uuid_from_path(path, "races"),
+            player_uuid: uuid_from_path(path, "players").or_else(player_in_query),
+        }
+    }
+}
+
+/// Middleware tagging the errors reported while serving a request
+///
+/// Each request gets its own Sentry scope tagged with the request ID and the
+/// race and player UUIDs, so errors logged by a handler, such as a failed lap
+/// resolution, are reported against the race and player they concern. Without
+/// the `sentry` feature the request passes through untouched.
+pub async fn tag_error_reports(request: Request, next: Next) -> Response {
+    #[cfg(feature = "sentry")]
+    {
+        use sentry::{Hub, SentryFutureExt};
+
+        let tags = ReportTags::from_request(&request);
+        let request_id = request
+            .extensions()
+            .get::<crate::middleware::RequestId>()
+            .cloned();
+        let hub = std::sync::Arc::new(Hub::new_from_top(Hub::current()));
+        hub.configure_scope(|scope| {
+            if let Some(crate::middleware::RequestId(request_id)) = request_id {
+                scope.set_tag("request_id", request_id);
+            }
+            if let Some(race_uuid) = tags.race_uuid {
+                scope.set_tag("race_uuid", race_uuid);
+            }
+            if let Some(player_uuid) = tags.player_uuid {
+                scope.set_tag("player_uuid", player_uuid);
+            }
+            if let Some(route) = request.extensions().get::<axum::extract::MatchedPath>() {
+                scope.set_tag("route", route.as_str());
+            }
+        });
+        next.run(request).bind_hub(hub).await
+    }
+    #[cfg(not(feature = "sentry"))]
+    next.run(request).await
+}
+
+/// Response for a handler that panicked, used with `CatchPanicLayer`
+///
+/// The panic itself is logged, and reported to Sentry when it is enabled;
+/// the client only learns that the request failed.
+// `CatchPanicLayer` hands the panic over boxed
+#[allow(clippy::needless_pass_by_value)]
+pub fn panic_response(panic: Box<dyn Any + Send + 'static>) -> Response {
+    let message = panic
+        .downcast_ref::<String>()
+        .map(String::as_str)
+        .or_else(|| panic.downcast_ref::<&str>().copied())
+        .unwrap_or("unknown panic");
+    tracing::error!("Handler panicked: {}", message);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse {
+            error: "INTERNAL_ERROR".to_string(),
+            message: "Internal server error".to_string(),
+            details: None,
+        }),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+
+    fn request(uri: &str) -> Request {
+        Request::builder().uri(uri).body(Body::empty()).unwrap()
+    }
+
+    #[test]
+    fn race_and_player_are_read_from_path_or_query() {
+        let (race_uuid, player_uuid) = (Uuid::new_v4(), Uuid::new_v4());
+
+        let tags = ReportTags::from_request(&request(&format!(
+            "/api/v1/races/{race_uuid}/turn-phase?player_uuid={player_uuid}"
+        )));
+        assert_eq!(tags.race_uuid, Some(race_uuid));
+        assert_eq!(tags.player_uuid, Some(player_uuid));
+
+        let tags =
+            ReportTags::from_request(&request(&format!("/api/v1/players/{player_uuid}/cars")));
+        assert_eq!(
+            tags,
+            ReportTags {
+                race_uuid: None,
+                player_uuid: Some(player_uuid),
+            }
+        );
+
+        assert_eq!(
+            ReportTags::from_request(&request("/api/v1/races?player_uuid=nope")),
+            ReportTags::default()
+        );
+    }
+
+    #[test]
+    fn panics_become_internal_server_errors() {
+        let response = panic_response(Box::new("lap engine exploded"));
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
@@ -0,0 +1,144 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::Request,
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::services::i18n::{localized_error_message, Language};
+
+/// Largest error body whose message is translated
+const MAX_ERROR_BODY_BYTES: usize = 64 * 1024;
+
+/// Middleware translating the `message` of JSON error bodies into the
+/// language asked for in `Accept-Language`
+///
+/// The message is looked up by the body's `error` (or `error_code`) so codes
+/// stay the same in every language. English, codes missing from the catalog
+/// and bodies without a `message` are left as the handler wrote them.
+pub async fn localize_error_messages(request: Request, next: Next) -> Response {
+    let language = request
+        .headers()
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .map_or(Language::English, Language::negotiate);
+
+    let response = next.run(request).await;
+
+    let status = response.status();
+    if language == Language::English || !(status.is_client_error() || status.is_server_error()) {
+        return response;
+    }
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    if !is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = to_bytes(body, MAX_ERROR_BODY_BYTES).await else {
+        tracing::warn!("Error body too large to translate");
+        return Response::from_parts(parts, Body::empty());
+    };
+    let Ok(serde_json::Value::Object(mut fields)) = serde_json::from_slice(&bytes) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+    let message = fields
+        .get("error")
+        .or_else(|| fields.get("error_code"))
+        .and_then(serde_json::Value::as_str)
+        .and_then(|code| localized_error_message(code, language));
+    match message {
+        Some(message) if fields.contains_key("message") => {
+            fields.insert("message".to_string(), message.into());
+            parts.headers.remove(header::CONTENT_LENGTH);
+            parts.headers.insert(
+                header::CONTENT_LANGUAGE,
+                HeaderValue::from_static(language.tag()),
+            );
+            Response::from_parts(
+                parts,
+                Body::from(serde_json::Value::Object(fields).to_string()),
+            )
+        }
+        _ => Response::from_parts(parts, Body::from(bytes)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, response::Json, routing::get, Router};
+    use serde_json::json;
+
+    async fn serve() -> String {
+        let app = Router::new()
+            .route(
+                "/race",
+                get(|| async {
+                    (
+                        StatusCode::NOT_FOUND,
+                        Json(json!({ "error": "RACE_NOT_FOUND", "message": "Race not found" })),
+                    )
+                }),
+            )
+            .route(
+                "/boost",
+                get(|| async {
+                    (
+                        StatusCode::BAD_REQUEST,
+                        Json(json!({
+                            "error_code": "UNLISTED_CODE",
+                            "message": "Something specific went wrong"
+                        })),
+                    )
+                }),
+            )
+            .layer(axum::middleware::from_fn(localize_error_messages));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        format!("http://{address}")
+    }
+
+    #[tokio::test]
+    async fn error_messages_follow_accept_language_and_codes_do_not() {
+        let base_url = serve().await;
+
+        let response = reqwest::Client::new()
+            .get(format!("{base_url}/race"))
+            .header("Accept-Language", "fr-FR,fr;q=0.9,en;q=0.5")
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.headers()["content-language"], "fr");
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["error"], "RACE_NOT_FOUND");
+        assert_eq!(body["message"], "Course introuvable");
+    }
+
+    #[tokio::test]
+    async fn english_and_unknown_codes_keep_the_handler_message() {
+        let base_url = serve().await;
+        let client = reqwest::Client::new();
+
+        let response = client.get(format!("{base_url}/race")).send().await.unwrap();
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["message"], "Race not found");
+
+        let response = client
+            .get(format!("{base_url}/boost"))
+            .header("Accept-Language", "es")
+            .send()
+            .await
+            .unwrap();
+        assert!(response.headers().get("content-language").is_none());
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["message"], "Something specific went wrong");
+    }
+}
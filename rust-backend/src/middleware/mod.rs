@@ -1,5 +1,21 @@
 pub mod auth;
+pub mod compression;
+pub mod database_guard;
+pub mod device_binding;
+pub mod error_reporting;
+pub mod localization;
 pub mod ownership;
+pub mod request_id;
+pub mod scope;
+pub mod timeout;
 
-pub use auth::{AuthError, AuthMiddleware, UserContext};
+pub use auth::{ApiTokenContext, AuthError, AuthMiddleware, UserContext};
+pub use compression::compress_responses;
+pub use database_guard::{database_unavailable, reject_while_database_down};
+pub use device_binding::require_bound_device;
+pub use error_reporting::{panic_response, tag_error_reports};
+pub use localization::localize_error_messages;
 pub use ownership::{RequireOwnership, RequireRole};
+pub use request_id::{propagate_request_id, request_span, RequestId, X_REQUEST_ID};
+pub use scope::RequireScope;
+pub use timeout::{enforce_route_timeouts, RouteTimeouts};
@@ -10,6 +10,7 @@ use uuid::Uuid;
 
 use crate::domain::UserRole;
 use crate::middleware::auth::UserContext;
+use crate::services::{AssetKind, OwnershipResolver};
 
 /// Ownership validation types
 #[derive(Clone)]
@@ -23,6 +24,7 @@ pub enum OwnershipValidationType {
 #[derive(Clone)]
 pub struct RequireOwnership {
     validation_type: OwnershipValidationType,
+    assets: Option<OwnershipResolver>,
 }
 
 impl RequireOwnership {
@@ -31,6 +33,7 @@ impl RequireOwnership {
     pub fn player(param_name: &str) -> Self {
         Self {
             validation_type: OwnershipValidationType::Player(param_name.to_string()),
+            assets: None,
         }
     }
 
@@ -39,6 +42,7 @@ impl RequireOwnership {
     pub fn race(param_name: &str) -> Self {
         Self {
             validation_type: OwnershipValidationType::Race(param_name.to_string()),
+            assets: None,
         }
     }
 
@@ -46,8 +50,21 @@ impl RequireOwnership {
     pub fn custom(validator: fn(&UserContext, &str) -> bool) -> Self {
         Self {
             validation_type: OwnershipValidationType::Custom(validator),
+            assets: None,
         }
     }
+
+    /// Also require the cars, pilots, engines and bodies named in the path
+    /// (`:car_uuid`, `:pilot_uuid`, ...) to belong to the path's player
+    ///
+    /// The player's assets are then left in the request extensions as
+    /// [`PlayerAssets`](crate::services::PlayerAssets), so handlers need not
+    /// look them up again. Only applies to player ownership.
+    #[must_use]
+    pub fn with_assets(mut self, resolver: OwnershipResolver) -> Self {
+        self.assets = Some(resolver);
+        self
+    }
 }
 
 impl<S> Layer<S> for RequireOwnership {
@@ -57,6 +74,7 @@ impl<S> Layer<S> for RequireOwnership {
         OwnershipService {
             inner,
             validation_type: self.validation_type.clone(),
+            assets: self.assets.clone(),
         }
     }
 }
@@ -66,6 +84,7 @@ impl<S> Layer<S> for RequireOwnership {
 pub struct OwnershipService<S> {
     inner: S,
     validation_type: OwnershipValidationType,
+    assets: Option<OwnershipResolver>,
 }
 
 impl<S> Service<Request> for OwnershipService<S>
@@ -84,8 +103,9 @@ where
         self.inner.poll_ready(cx)
     }
 
-    fn call(&mut self, request: Request) -> Self::Future {
+    fn call(&mut self, mut request: Request) -> Self::Future {
         let validation_type = self.validation_type.clone();
+        let assets = self.assets.clone();
         let mut inner = self.inner.clone();
 
         Box::pin(async move {
@@ -124,19 +144,48 @@ where
 
             if !is_authorized {
                 // Return 404 (not 403) to avoid leaking resource existence
-                let error_response = Response::builder()
-                    .status(StatusCode::NOT_FOUND)
-                    .header("content-type", "application/json")
-                    .body(
-                        json!({
-                            "error": "resource_not_found",
-                            "message": "The requested resource was not found"
-                        })
-                        .to_string()
-                        .into(),
-                    )
-                    .unwrap();
-                return Ok(error_response);
+                return Ok(resource_not_found());
+            }
+
+            // Assets in the path must belong to the player in the path
+            if let (Some(resolver), OwnershipValidationType::Player(param_name)) =
+                (&assets, &validation_type)
+            {
+                let Some(player_uuid) = extract_uuid_from_path(&request, param_name) else {
+                    return Ok(resource_not_found());
+                };
+                let player_assets = match resolver.assets_of(player_uuid).await {
+                    Ok(Some(player_assets)) => player_assets,
+                    Ok(None) => return Ok(resource_not_found()),
+                    Err(e) => {
+                        tracing::error!("Failed to resolve asset ownership: {:?}", e);
+                        let error_response = Response::builder()
+                            .status(StatusCode::INTERNAL_SERVER_ERROR)
+                            .header("content-type", "application/json")
+                            .body(
+                                json!({
+                                    "error": "internal_error",
+                                    "message": "An internal authorization error occurred"
+                                })
+                                .to_string()
+                                .into(),
+                            )
+                            .unwrap();
+                        return Ok(error_response);
+                    }
+                };
+                let owns_path_assets = AssetKind::ALL.into_iter().all(|kind| {
+                    match path_param(&request, kind.path_param()) {
+                        None => true,
+                        Some(value) => {
+                            Uuid::parse_str(&value).is_ok_and(|uuid| player_assets.owns(kind, uuid))
+                        }
+                    }
+                });
+                if !owns_path_assets {
+                    return Ok(resource_not_found());
+                }
+                request.extensions_mut().insert(player_assets);
             }
 
             // Authorization passed, continue to handler
@@ -278,6 +327,21 @@ where
 
 // Validation helper functions
 
+fn resource_not_found() -> Response {
+    Response::builder()
+        .status(StatusCode::NOT_FOUND)
+        .header("content-type", "application/json")
+        .body(
+            json!({
+                "error": "resource_not_found",
+                "message": "The requested resource was not found"
+            })
+            .to_string()
+            .into(),
+        )
+        .unwrap()
+}
+
 /// Validate player ownership
 fn validate_player_ownership(
     user_context: &UserContext,
@@ -321,35 +385,29 @@ fn validate_race_ownership(
 
 /// Helper function to extract UUIDs from path parameters
 fn extract_uuid_from_path(request: &Request, param_name: &str) -> Option<Uuid> {
-    // Get the matched path from Axum
-    let matched_path = request.extensions().get::<MatchedPath>()?;
-    let path = matched_path.as_str();
-
-    // Parse the path to extract parameters
-    // This is a simplified implementation - in a real app you'd use Axum's path extraction
-    extract_param_from_path(path, param_name).and_then(|uuid_str| Uuid::parse_str(&uuid_str).ok())
+    path_param(request, param_name).and_then(|uuid_str| Uuid::parse_str(&uuid_str).ok())
 }
 
-/// Extract parameter value from path string
-fn extract_param_from_path(path: &str, param_name: &str) -> Option<String> {
-    // Simple path parameter extraction
-    // Look for pattern like "/players/{uuid}/cars" where param_name is "player_uuid"
-    let _param_pattern = format!(":{param_name}");
-
-    // This is a simplified implementation
-    // In practice, you'd use Axum's built-in path parameter extraction
-    let parts: Vec<&str> = path.split('/').collect();
-
-    // Find the parameter in the path template
-    // For now, we'll use a simple heuristic based on UUID format
-    for part in parts {
-        if part.len() == 36 && part.contains('-') {
-            // Looks like a UUID
-            return Some(part.to_string());
-        }
-    }
+/// Value of the `:param_name` path parameter, found through the route the
+/// request matched
+fn path_param(request: &Request, param_name: &str) -> Option<String> {
+    let matched_path = request.extensions().get::<MatchedPath>()?;
+    extract_param_from_path(matched_path.as_str(), request.uri().path(), param_name)
+}
 
-    None
+/// Segment of `path` where the route `template` has `:param_name`
+///
+/// Segments are paired from the end, as routers nested under a prefix see
+/// the path without it while the template they matched keeps it.
+fn extract_param_from_path(template: &str, path: &str, param_name: &str) -> Option<String> {
+    let placeholder = format!(":{param_name}");
+    template
+        .trim_end_matches('/')
+        .split('/')
+        .rev()
+        .zip(path.trim_end_matches('/').split('/').rev())
+        .find(|(segment, _)| *segment == placeholder)
+        .map(|(_, value)| value.to_string())
 }
 
 #[cfg(test)]
@@ -364,6 +422,8 @@ mod tests {
             email: "test@example.com".to_string(),
             role,
             token_id: "test_token".to_string(),
+            device_fingerprint: None,
+            permissions: Vec::new(),
         }
     }
 
@@ -440,17 +500,20 @@ mod tests {
     #[test]
     fn extract_param_from_path_works() {
         let uuid_str = "123e4567-e89b-12d3-a456-426614174000";
-        let path = format!("/players/{uuid_str}/cars");
+        let template = "/api/v1/players/:player_uuid/cars/:car_uuid";
+        let path = format!("/players/{uuid_str}/cars/car-1");
 
-        let extracted = extract_param_from_path(&path, "player_uuid");
+        let extracted = extract_param_from_path(template, &path, "player_uuid");
         assert_eq!(extracted, Some(uuid_str.to_string()));
+        let extracted = extract_param_from_path(template, &path, "car_uuid");
+        assert_eq!(extracted, Some("car-1".to_string()));
     }
 
     #[test]
-    fn extract_param_from_path_returns_none_for_no_uuid() {
+    fn extract_param_from_path_returns_none_for_missing_param() {
         let path = "/players/not-a-uuid/cars";
 
-        let extracted = extract_param_from_path(path, "player_uuid");
+        let extracted = extract_param_from_path("/players/:player_uuid/cars", path, "car_uuid");
         assert_eq!(extracted, None);
     }
 
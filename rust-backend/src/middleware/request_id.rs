@@ -0,0 +1,169 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::Request,
+    http::{header, HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use tracing::Span;
+use uuid::Uuid;
+
+/// Header carrying the request ID, both ways
+pub static X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest client-supplied request ID that is kept as is
+const MAX_REQUEST_ID_LENGTH: usize = 128;
+/// Largest error body the request ID is added to
+const MAX_ERROR_BODY_BYTES: usize = 64 * 1024;
+
+/// ID of the request being served, available to handlers as an extension
+#[derive(Debug, Clone)]
+pub struct RequestId(pub String);
+
+/// Middleware giving every request an ID
+///
+/// A client-supplied `X-Request-Id` is kept when it is short printable ASCII,
+/// otherwise a UUID is generated. The ID is echoed in the response header and
+/// added as `request_id` to JSON error bodies, so a user reporting a failure
+/// can quote it and ops can find the matching log lines.
+pub async fn propagate_request_id(mut request: Request, next: Next) -> Response {
+    let request_id = request
+        .headers()
+        .get(&X_REQUEST_ID)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| is_valid_request_id(value))
+        .map_or_else(|| Uuid::new_v4().to_string(), str::to_string);
+    let header_value = HeaderValue::from_str(&request_id).expect("request IDs are printable ASCII");
+    request
+        .headers_mut()
+        .insert(X_REQUEST_ID.clone(), header_value.clone());
+    request
+        .extensions_mut()
+        .insert(RequestId(request_id.clone()));
+
+    let response = next.run(request).await;
+
+    let status = response.status();
+    let mut response = if status.is_client_error() || status.is_server_error() {
+        add_request_id_to_error(response, &request_id).await
+    } else {
+        response
+    };
+    response
+        .headers_mut()
+        .insert(X_REQUEST_ID.clone(), header_value);
+    response
+}
+
+/// Span of an HTTP request, tagged with its request ID so every handler span
+/// and log line inside it carries the ID
+pub fn request_span(request: &Request) -> Span {
+    let request_id = request
+        .headers()
+        .get(&X_REQUEST_ID)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    tracing::info_span!(
+        "HTTP request",
+        method = %request.method(),
+        uri = %request.uri(),
+        request_id = %request_id,
+    )
+}
+
+fn is_valid_request_id(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= MAX_REQUEST_ID_LENGTH
+        && value.bytes().all(|byte| byte.is_ascii_graphic())
+}
+
+async fn add_request_id_to_error(response: Response, request_id: &str) -> Response {
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    if !is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = to_bytes(body, MAX_ERROR_BODY_BYTES).await else {
+        tracing::warn!("Error body too large to tag with request {}", request_id);
+        return Response::from_parts(parts, Body::empty());
+    };
+    let body = match serde_json::from_slice::<serde_json::Value>(&bytes) {
+        Ok(serde_json::Value::Object(mut fields)) => {
+            fields
+                .entry("request_id")
+                .or_insert_with(|| request_id.into());
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Body::from(serde_json::Value::Object(fields).to_string())
+        }
+        _ => Body::from(bytes),
+    };
+    Response::from_parts(parts, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, response::Json, routing::get, Router};
+    use serde_json::json;
+
+    async fn serve() -> String {
+        let app = Router::new()
+            .route("/ok", get(|| async { "ok" }))
+            .route(
+                "/fail",
+                get(|| async {
+                    (
+                        StatusCode::NOT_FOUND,
+                        Json(json!({ "error": "RACE_NOT_FOUND", "message": "Race not found" })),
+                    )
+                }),
+            )
+            .layer(axum::middleware::from_fn(propagate_request_id));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        format!("http://{address}")
+    }
+
+    #[tokio::test]
+    async fn client_request_ids_are_echoed_in_error_bodies() {
+        let base_url = serve().await;
+
+        let response = reqwest::Client::new()
+            .get(format!("{base_url}/fail"))
+            .header("X-Request-Id", "support-ticket-42")
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.headers()["x-request-id"], "support-ticket-42");
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["request_id"], "support-ticket-42");
+        assert_eq!(body["error"], "RACE_NOT_FOUND");
+    }
+
+    #[tokio::test]
+    async fn missing_or_invalid_request_ids_are_generated() {
+        let base_url = serve().await;
+        let client = reqwest::Client::new();
+
+        let response = client.get(format!("{base_url}/ok")).send().await.unwrap();
+        let generated = response.headers()["x-request-id"].to_str().unwrap();
+        assert!(Uuid::parse_str(generated).is_ok());
+        assert_eq!(response.text().await.unwrap(), "ok");
+
+        let response = client
+            .get(format!("{base_url}/ok"))
+            .header("X-Request-Id", "x".repeat(MAX_REQUEST_ID_LENGTH + 1))
+            .send()
+            .await
+            .unwrap();
+        let generated = response.headers()["x-request-id"].to_str().unwrap();
+        assert!(Uuid::parse_str(generated).is_ok());
+    }
+}
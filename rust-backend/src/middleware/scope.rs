@@ -0,0 +1,145 @@
+use axum::{extract::Request, http::StatusCode, response::Response};
+use futures_util::future::BoxFuture;
+use serde_json::json;
+use tower::{Layer, Service};
+
+use crate::domain::Permission;
+use crate::middleware::auth::UserContext;
+
+/// Permission-based authorization middleware
+///
+/// Lets through requests whose access token carries the permission, so a
+/// route declares the capability it needs rather than a role. Runs after
+/// [`AuthMiddleware`](crate::middleware::AuthMiddleware).
+#[derive(Clone)]
+pub struct RequireScope {
+    permission: Permission,
+}
+
+impl RequireScope {
+    #[must_use]
+    pub fn new(permission: Permission) -> Self {
+        Self { permission }
+    }
+}
+
+impl<S> Layer<S> for RequireScope {
+    type Service = ScopeService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ScopeService {
+            inner,
+            permission: self.permission,
+        }
+    }
+}
+
+/// Permission validation service
+#[derive(Clone)]
+pub struct ScopeService<S> {
+    inner: S,
+    permission: Permission,
+}
+
+impl<S> Service<Request> for ScopeService<S>
+where
+    S: Service<Request, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let permission = self.permission;
+        let mut inner = self.inner.clone();
+
+        Box::pin(async move {
+            let granted = request
+                .extensions()
+                .get::<UserContext>()
+                .map(|user_context| user_context.has_permission(permission));
+            let (status, error, message) = match granted {
+                Some(true) => return inner.call(request).await,
+                Some(false) => (
+                    StatusCode::FORBIDDEN,
+                    "insufficient_scope",
+                    format!("This route needs the {} permission", permission.as_str()),
+                ),
+                // No user context means auth middleware didn't run
+                None => (
+                    StatusCode::UNAUTHORIZED,
+                    "authentication_required",
+                    "Authentication is required".to_string(),
+                ),
+            };
+
+            let error_response = Response::builder()
+                .status(status)
+                .header("content-type", "application/json")
+                .body(
+                    json!({
+                        "error": error,
+                        "message": message
+                    })
+                    .to_string()
+                    .into(),
+                )
+                .unwrap();
+            Ok(error_response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::UserRole;
+    use axum::body::Body;
+    use std::convert::Infallible;
+    use tower::{service_fn, ServiceExt};
+    use uuid::Uuid;
+
+    fn user(role: &UserRole) -> UserContext {
+        UserContext {
+            user_uuid: Uuid::new_v4(),
+            email: "test@example.com".to_string(),
+            role: role.clone(),
+            token_id: "token".to_string(),
+            device_fingerprint: None,
+            permissions: role.permissions(),
+        }
+    }
+
+    async fn status_for(user: Option<UserContext>) -> StatusCode {
+        let service =
+            RequireScope::new(Permission::AuditRead).layer(service_fn(|_: Request| async {
+                Ok::<_, Infallible>(Response::new(Body::empty()))
+            }));
+        let mut request = Request::new(Body::empty());
+        if let Some(user) = user {
+            request.extensions_mut().insert(user);
+        }
+        service.oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn only_tokens_with_the_permission_get_through() {
+        assert_eq!(
+            status_for(Some(user(&UserRole::Admin))).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status_for(Some(user(&UserRole::Player))).await,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(status_for(None).await, StatusCode::UNAUTHORIZED);
+    }
+}
@@ -0,0 +1,125 @@
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::configuration::HttpSettings;
+use crate::routes::races::ErrorResponse;
+
+/// Time each route has to answer
+#[derive(Debug, Clone)]
+pub struct RouteTimeouts {
+    default: Duration,
+    routes: Arc<HashMap<String, Duration>>,
+}
+
+impl RouteTimeouts {
+    #[must_use]
+    pub fn from_settings(settings: &HttpSettings) -> Self {
+        Self {
+            default: Duration::from_secs(settings.request_timeout_seconds),
+            routes: Arc::new(
+                settings
+                    .route_timeouts
+                    .iter()
+                    .map(|route| {
+                        (
+                            route.route.clone(),
+                            Duration::from_secs(route.timeout_seconds),
+                        )
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    fn for_route(&self, route: Option<&str>) -> Duration {
+        route
+            .and_then(|route| self.routes.get(route))
+            .copied()
+            .unwrap_or(self.default)
+    }
+}
+
+/// Middleware failing requests whose handler takes longer than its route's
+/// timeout with `408`
+///
+/// Only the handler is timed: a WebSocket keeps running once upgraded.
+pub async fn enforce_route_timeouts(
+    State(timeouts): State<RouteTimeouts>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string());
+    let timeout = timeouts.for_route(route.as_deref());
+
+    if let Ok(response) = tokio::time::timeout(timeout, next.run(request)).await {
+        return response;
+    }
+    tracing::warn!(
+        "{} timed out after {:?}",
+        route.as_deref().unwrap_or("Unmatched request"),
+        timeout
+    );
+    (
+        StatusCode::REQUEST_TIMEOUT,
+        Json(ErrorResponse {
+            error: "REQUEST_TIMEOUT".to_string(),
+            message: "The request took too long to process".to_string(),
+            details: None,
+        }),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configuration::RouteTimeoutSettings;
+    use axum::{routing::get, Router};
+
+    #[tokio::test]
+    async fn slow_handlers_time_out_unless_their_route_allows_more() {
+        let settings = HttpSettings {
+            request_timeout_seconds: 0,
+            route_timeouts: vec![RouteTimeoutSettings {
+                route: "/reports/:report".to_string(),
+                timeout_seconds: 5,
+            }],
+            ..HttpSettings::default()
+        };
+        let slow = || async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            "done"
+        };
+        let app = Router::new()
+            .route("/status", get(slow))
+            .route("/reports/:report", get(slow))
+            .layer(axum::middleware::from_fn_with_state(
+                RouteTimeouts::from_settings(&settings),
+                enforce_route_timeouts,
+            ));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let response = reqwest::get(format!("http://{address}/status"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::REQUEST_TIMEOUT);
+
+        let response = reqwest::get(format!("http://{address}/reports/monthly"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        assert_eq!(response.text().await.unwrap(), "done");
+    }
+}
@@ -0,0 +1,21 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use super::RepositoryResult;
+use crate::domain::Friendship;
+
+#[async_trait]
+pub trait FriendshipRepository: Send + Sync {
+    /// Store a new request; `Conflict` if the pair already has a friendship
+    async fn create(&self, friendship: &Friendship) -> RepositoryResult<Friendship>;
+    async fn find_between(&self, a: Uuid, b: Uuid) -> RepositoryResult<Option<Friendship>>;
+    /// Accept the pending request `requester_uuid` sent to `addressee_uuid`
+    async fn accept(
+        &self,
+        requester_uuid: Uuid,
+        addressee_uuid: Uuid,
+    ) -> RepositoryResult<Option<Friendship>>;
+    async fn delete_between(&self, a: Uuid, b: Uuid) -> RepositoryResult<bool>;
+    /// Friendships and requests involving the player, either way
+    async fn find_for_player(&self, player_uuid: Uuid) -> RepositoryResult<Vec<Friendship>>;
+}
@@ -0,0 +1,1008 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+use super::{
+    FriendshipRepository, PlayerRepository, RaceRepository, RepositoryError, RepositoryResult,
+    SessionRepository, TrackRepository, TradeRepository,
+};
+use crate::domain::{
+    Car, Escrow, Friendship, FriendshipStatus, LapAction, LapResult, OAuthIdentity, OAuthProvider,
+    Pilot, Player, Race, RaceStatus, TeamName, TrackDesign, TradeOffer, TradeStatus, WalletAddress,
+};
+use crate::services::car_validation::ValidatedCarData;
+use crate::services::session::Session;
+
+/// In-memory `PlayerRepository`, holding the auth routes' accounts under the
+/// `memory` database backend, and used in tests
+#[derive(Clone)]
+pub struct InMemoryPlayerRepository {
+    players: Arc<Mutex<HashMap<String, Player>>>, // Using email as key for simplicity
+    players_by_uuid: Arc<Mutex<HashMap<Uuid, Player>>>,
+}
+
+impl InMemoryPlayerRepository {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            players: Arc::new(Mutex::new(HashMap::new())),
+            players_by_uuid: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    #[must_use]
+    pub fn with_players(players: Vec<Player>) -> Self {
+        let mut email_map = HashMap::new();
+        let mut uuid_map = HashMap::new();
+
+        for player in players {
+            email_map.insert(player.email.as_ref().to_string(), player.clone());
+            uuid_map.insert(player.uuid, player);
+        }
+
+        Self {
+            players: Arc::new(Mutex::new(email_map)),
+            players_by_uuid: Arc::new(Mutex::new(uuid_map)),
+        }
+    }
+}
+
+impl Default for InMemoryPlayerRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl PlayerRepository for InMemoryPlayerRepository {
+    async fn create(&self, player: &Player) -> RepositoryResult<Player> {
+        let mut players = self.players.lock().unwrap();
+        let mut players_by_uuid = self.players_by_uuid.lock().unwrap();
+
+        let email_key = player.email.as_ref().to_string();
+        if players.contains_key(&email_key) {
+            return Err(RepositoryError::Conflict(
+                "Player with this email already exists".to_string(),
+            ));
+        }
+
+        players.insert(email_key, player.clone());
+        players_by_uuid.insert(player.uuid, player.clone());
+        Ok(player.clone())
+    }
+
+    async fn find_all(&self) -> RepositoryResult<Vec<Player>> {
+        let players = self.players.lock().unwrap();
+        Ok(players.values().cloned().collect())
+    }
+
+    async fn find_by_wallet_address(
+        &self,
+        wallet_address: &str,
+    ) -> RepositoryResult<Option<Player>> {
+        let players = self.players.lock().unwrap();
+        Ok(players
+            .values()
+            .find(|p| {
+                p.wallet_address.as_ref().map(std::convert::AsRef::as_ref) == Some(wallet_address)
+            })
+            .cloned())
+    }
+
+    async fn find_by_email(&self, email: &str) -> RepositoryResult<Option<Player>> {
+        let players = self.players.lock().unwrap();
+        Ok(players.get(email).cloned())
+    }
+
+    async fn find_by_uuid(&self, player_uuid: Uuid) -> RepositoryResult<Option<Player>> {
+        let players_by_uuid = self.players_by_uuid.lock().unwrap();
+        Ok(players_by_uuid.get(&player_uuid).cloned())
+    }
+
+    async fn update_team_name_by_wallet(
+        &self,
+        wallet_address: &str,
+        team_name: TeamName,
+    ) -> RepositoryResult<Option<Player>> {
+        let mut players = self.players.lock().unwrap();
+        let mut players_by_uuid = self.players_by_uuid.lock().unwrap();
+
+        for player in players.values_mut() {
+            if player
+                .wallet_address
+                .as_ref()
+                .map(std::convert::AsRef::as_ref)
+                == Some(wallet_address)
+            {
+                player.team_name = team_name;
+                player.updated_at = Utc::now();
+                players_by_uuid.insert(player.uuid, player.clone());
+                return Ok(Some(player.clone()));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn update_team_name_by_uuid(
+        &self,
+        player_uuid: Uuid,
+        team_name: TeamName,
+    ) -> RepositoryResult<Option<Player>> {
+        let mut players = self.players.lock().unwrap();
+        let mut players_by_uuid = self.players_by_uuid.lock().unwrap();
+
+        if let Some(player) = players_by_uuid.get_mut(&player_uuid) {
+            player.team_name = team_name;
+            player.updated_at = Utc::now();
+            let email_key = player.email.as_ref().to_string();
+            players.insert(email_key, player.clone());
+            Ok(Some(player.clone()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn update_wallet_address(
+        &self,
+        player_uuid: Uuid,
+        wallet_address: WalletAddress,
+    ) -> RepositoryResult<Option<Player>> {
+        let mut players = self.players.lock().unwrap();
+        let mut players_by_uuid = self.players_by_uuid.lock().unwrap();
+
+        if let Some(player) = players_by_uuid.get_mut(&player_uuid) {
+            player.wallet_address = Some(wallet_address);
+            player.updated_at = Utc::now();
+            let email_key = player.email.as_ref().to_string();
+            players.insert(email_key, player.clone());
+            Ok(Some(player.clone()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn delete_by_wallet_address(&self, wallet_address: &str) -> RepositoryResult<bool> {
+        let mut players = self.players.lock().unwrap();
+        let mut players_by_uuid = self.players_by_uuid.lock().unwrap();
+
+        let mut found_player = None;
+        for (email, player) in players.iter() {
+            if player
+                .wallet_address
+                .as_ref()
+                .map(std::convert::AsRef::as_ref)
+                == Some(wallet_address)
+            {
+                found_player = Some((email.clone(), player.uuid));
+                break;
+            }
+        }
+
+        if let Some((email, uuid)) = found_player {
+            players.remove(&email);
+            players_by_uuid.remove(&uuid);
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    async fn delete_by_uuid(&self, player_uuid: Uuid) -> RepositoryResult<bool> {
+        let mut players = self.players.lock().unwrap();
+        let mut players_by_uuid = self.players_by_uuid.lock().unwrap();
+
+        if let Some(player) = players_by_uuid.remove(&player_uuid) {
+            let email_key = player.email.as_ref().to_string();
+            players.remove(&email_key);
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    async fn add_car_by_wallet(
+        &self,
+        wallet_address: &str,
+        car: Car,
+    ) -> RepositoryResult<Option<Player>> {
+        let mut players = self.players.lock().unwrap();
+        let mut players_by_uuid = self.players_by_uuid.lock().unwrap();
+
+        for player in players.values_mut() {
+            if player
+                .wallet_address
+                .as_ref()
+                .map(std::convert::AsRef::as_ref)
+                == Some(wallet_address)
+            {
+                player.cars.push(car);
+                player.updated_at = Utc::now();
+                players_by_uuid.insert(player.uuid, player.clone());
+                return Ok(Some(player.clone()));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn add_car_by_uuid(
+        &self,
+        player_uuid: Uuid,
+        car: Car,
+    ) -> RepositoryResult<Option<Player>> {
+        let mut players = self.players.lock().unwrap();
+        let mut players_by_uuid = self.players_by_uuid.lock().unwrap();
+
+        if let Some(player) = players_by_uuid.get_mut(&player_uuid) {
+            player.cars.push(car);
+            player.updated_at = Utc::now();
+            let email_key = player.email.as_ref().to_string();
+            players.insert(email_key, player.clone());
+            Ok(Some(player.clone()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn remove_car_by_wallet(
+        &self,
+        wallet_address: &str,
+        car_uuid: Uuid,
+    ) -> RepositoryResult<Option<Player>> {
+        let mut players = self.players.lock().unwrap();
+        let mut players_by_uuid = self.players_by_uuid.lock().unwrap();
+
+        for player in players.values_mut() {
+            if player
+                .wallet_address
+                .as_ref()
+                .map(std::convert::AsRef::as_ref)
+                == Some(wallet_address)
+            {
+                player.cars.retain(|car| car.uuid != car_uuid);
+                player.updated_at = Utc::now();
+                players_by_uuid.insert(player.uuid, player.clone());
+                return Ok(Some(player.clone()));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn remove_car_by_uuid(
+        &self,
+        player_uuid: Uuid,
+        car_uuid: Uuid,
+    ) -> RepositoryResult<Option<Player>> {
+        let mut players = self.players.lock().unwrap();
+        let mut players_by_uuid = self.players_by_uuid.lock().unwrap();
+
+        if let Some(player) = players_by_uuid.get_mut(&player_uuid) {
+            player.cars.retain(|car| car.uuid != car_uuid);
+            player.updated_at = Utc::now();
+            let email_key = player.email.as_ref().to_string();
+            players.insert(email_key, player.clone());
+            Ok(Some(player.clone()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn add_pilot_by_wallet(
+        &self,
+        wallet_address: &str,
+        pilot: Pilot,
+    ) -> RepositoryResult<Option<Player>> {
+        let mut players = self.players.lock().unwrap();
+        let mut players_by_uuid = self.players_by_uuid.lock().unwrap();
+
+        for player in players.values_mut() {
+            if player
+                .wallet_address
+                .as_ref()
+                .map(std::convert::AsRef::as_ref)
+                == Some(wallet_address)
+            {
+                player.pilots.push(pilot);
+                player.updated_at = Utc::now();
+                players_by_uuid.insert(player.uuid, player.clone());
+                return Ok(Some(player.clone()));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn add_pilot_by_uuid(
+        &self,
+        player_uuid: Uuid,
+        pilot: Pilot,
+    ) -> RepositoryResult<Option<Player>> {
+        let mut players = self.players.lock().unwrap();
+        let mut players_by_uuid = self.players_by_uuid.lock().unwrap();
+
+        if let Some(player) = players_by_uuid.get_mut(&player_uuid) {
+            player.pilots.push(pilot);
+            player.updated_at = Utc::now();
+            let email_key = player.email.as_ref().to_string();
+            players.insert(email_key, player.clone());
+            Ok(Some(player.clone()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn remove_pilot_by_wallet(
+        &self,
+        wallet_address: &str,
+        pilot_uuid: Uuid,
+    ) -> RepositoryResult<Option<Player>> {
+        let mut players = self.players.lock().unwrap();
+        let mut players_by_uuid = self.players_by_uuid.lock().unwrap();
+
+        for player in players.values_mut() {
+            if player
+                .wallet_address
+                .as_ref()
+                .map(std::convert::AsRef::as_ref)
+                == Some(wallet_address)
+            {
+                player.pilots.retain(|pilot| pilot.uuid != pilot_uuid);
+                player.updated_at = Utc::now();
+                players_by_uuid.insert(player.uuid, player.clone());
+                return Ok(Some(player.clone()));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn remove_pilot_by_uuid(
+        &self,
+        player_uuid: Uuid,
+        pilot_uuid: Uuid,
+    ) -> RepositoryResult<Option<Player>> {
+        let mut players = self.players.lock().unwrap();
+        let mut players_by_uuid = self.players_by_uuid.lock().unwrap();
+
+        if let Some(player) = players_by_uuid.get_mut(&player_uuid) {
+            player.pilots.retain(|pilot| pilot.uuid != pilot_uuid);
+            player.updated_at = Utc::now();
+            let email_key = player.email.as_ref().to_string();
+            players.insert(email_key, player.clone());
+            Ok(Some(player.clone()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn set_cars_by_uuid(
+        &self,
+        player_uuid: Uuid,
+        cars: Vec<Car>,
+    ) -> RepositoryResult<Option<Player>> {
+        let mut players = self.players.lock().unwrap();
+        let mut players_by_uuid = self.players_by_uuid.lock().unwrap();
+
+        if let Some(player) = players_by_uuid.get_mut(&player_uuid) {
+            player.cars = cars;
+            player.updated_at = Utc::now();
+            let email_key = player.email.as_ref().to_string();
+            players.insert(email_key, player.clone());
+            Ok(Some(player.clone()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn find_by_oauth_identity(
+        &self,
+        provider: OAuthProvider,
+        subject: &str,
+    ) -> RepositoryResult<Option<Player>> {
+        let players_by_uuid = self.players_by_uuid.lock().unwrap();
+        Ok(players_by_uuid
+            .values()
+            .find(|player| {
+                player
+                    .oauth_identities
+                    .iter()
+                    .any(|identity| identity.provider == provider && identity.subject == subject)
+            })
+            .cloned())
+    }
+
+    async fn add_oauth_identity(
+        &self,
+        player_uuid: Uuid,
+        identity: OAuthIdentity,
+    ) -> RepositoryResult<Option<Player>> {
+        let mut players = self.players.lock().unwrap();
+        let mut players_by_uuid = self.players_by_uuid.lock().unwrap();
+
+        if let Some(player) = players_by_uuid.get_mut(&player_uuid) {
+            player.oauth_identities.push(identity);
+            player.updated_at = Utc::now();
+            let email_key = player.email.as_ref().to_string();
+            players.insert(email_key, player.clone());
+            Ok(Some(player.clone()))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// In-memory `RaceRepository`, used in tests
+#[derive(Clone)]
+pub struct InMemoryRaceRepository {
+    races: Arc<Mutex<HashMap<Uuid, Race>>>,
+}
+
+impl InMemoryRaceRepository {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            races: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    #[must_use]
+    pub fn with_races(races: Vec<Race>) -> Self {
+        let mut race_map = HashMap::new();
+        for race in races {
+            race_map.insert(race.uuid, race);
+        }
+
+        Self {
+            races: Arc::new(Mutex::new(race_map)),
+        }
+    }
+}
+
+impl Default for InMemoryRaceRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl RaceRepository for InMemoryRaceRepository {
+    async fn create(&self, race: &Race) -> RepositoryResult<Race> {
+        let mut races = self.races.lock().unwrap();
+        races.insert(race.uuid, race.clone());
+        Ok(race.clone())
+    }
+
+    async fn find_all(&self) -> RepositoryResult<Vec<Race>> {
+        let races = self.races.lock().unwrap();
+        Ok(races.values().cloned().collect())
+    }
+
+    async fn find_by_uuid(&self, race_uuid: Uuid) -> RepositoryResult<Option<Race>> {
+        let races = self.races.lock().unwrap();
+        Ok(races.get(&race_uuid).cloned())
+    }
+
+    async fn find_by_pilot_uuid(&self, pilot_uuid: Uuid) -> RepositoryResult<Option<Race>> {
+        let races = self.races.lock().unwrap();
+        Ok(races
+            .values()
+            .find(|race| {
+                race.participants
+                    .iter()
+                    .any(|participant| participant.pilot_uuid == pilot_uuid)
+            })
+            .cloned())
+    }
+
+    async fn find_active_race_for_pilot(&self, pilot_uuid: Uuid) -> RepositoryResult<Option<Race>> {
+        let races = self.races.lock().unwrap();
+        Ok(races
+            .values()
+            .find(|race| {
+                matches!(
+                    race.status,
+                    RaceStatus::Waiting | RaceStatus::Starting | RaceStatus::InProgress
+                ) && race
+                    .participants
+                    .iter()
+                    .any(|participant| participant.pilot_uuid == pilot_uuid)
+            })
+            .cloned())
+    }
+
+    async fn join_race(
+        &self,
+        race_uuid: Uuid,
+        pilot_uuid: Uuid,
+        car_data: &ValidatedCarData,
+    ) -> RepositoryResult<Option<Race>> {
+        let mut races = self.races.lock().unwrap();
+
+        if let Some(race) = races.get_mut(&race_uuid) {
+            if !matches!(race.status, RaceStatus::Waiting) {
+                return Err(RepositoryError::Validation(
+                    "Race is not accepting new players".to_string(),
+                ));
+            }
+
+            if race.participants.iter().any(|p| p.pilot_uuid == pilot_uuid) {
+                return Err(RepositoryError::Conflict(
+                    "Pilot already in race".to_string(),
+                ));
+            }
+
+            // Add participant using the race's add_participant method
+            // The pilot's UUID doubles as the player UUID here
+            race.add_participant(car_data.pilot.uuid, car_data.car.uuid, pilot_uuid)
+                .map_err(RepositoryError::Validation)?;
+
+            Ok(Some(race.clone()))
+        } else {
+            Err(RepositoryError::NotFound)
+        }
+    }
+
+    async fn process_turn_actions(
+        &self,
+        race_uuid: Uuid,
+        _pilot_uuid: Uuid,
+        actions: Vec<LapAction>,
+    ) -> RepositoryResult<Option<(LapResult, RaceStatus)>> {
+        let mut races = self.races.lock().unwrap();
+
+        if let Some(race) = races.get_mut(&race_uuid) {
+            // Without car data, laps use the simple base-value resolution
+            let lap_result = race
+                .process_lap(&actions)
+                .map_err(RepositoryError::Validation)?;
+
+            let race_status = race.status.clone();
+
+            Ok(Some((lap_result, race_status)))
+        } else {
+            Err(RepositoryError::NotFound)
+        }
+    }
+
+    async fn submit_turn_action(
+        &self,
+        race_uuid: Uuid,
+        pilot_uuid: Uuid,
+        _boost_value: u32,
+    ) -> RepositoryResult<Option<Race>> {
+        let mut races = self.races.lock().unwrap();
+
+        if let Some(race) = races.get_mut(&race_uuid) {
+            // Actions are resolved in batches by `process_turn_actions`
+            if race.participants.iter().any(|p| p.pilot_uuid == pilot_uuid) {
+                Ok(Some(race.clone()))
+            } else {
+                Err(RepositoryError::NotFound)
+            }
+        } else {
+            Err(RepositoryError::NotFound)
+        }
+    }
+
+    async fn update_race_status(
+        &self,
+        race_uuid: Uuid,
+        status: RaceStatus,
+    ) -> RepositoryResult<Option<Race>> {
+        let mut races = self.races.lock().unwrap();
+
+        if let Some(race) = races.get_mut(&race_uuid) {
+            race.status = status;
+            Ok(Some(race.clone()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn get_races_by_status(&self, status: RaceStatus) -> RepositoryResult<Vec<Race>> {
+        let races = self.races.lock().unwrap();
+        Ok(races
+            .values()
+            .filter(|race| race.status == status)
+            .cloned()
+            .collect())
+    }
+}
+
+/// In-memory `SessionRepository`, holding sessions under every database
+/// backend, and used in tests
+#[derive(Clone)]
+pub struct InMemorySessionRepository {
+    sessions: Arc<Mutex<HashMap<String, Session>>>, // Using token as key
+}
+
+impl InMemorySessionRepository {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            sessions: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    #[must_use]
+    pub fn with_sessions(sessions: Vec<Session>) -> Self {
+        let mut session_map = HashMap::new();
+        for session in sessions {
+            session_map.insert(session.token.clone(), session);
+        }
+
+        Self {
+            sessions: Arc::new(Mutex::new(session_map)),
+        }
+    }
+}
+
+impl Default for InMemorySessionRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SessionRepository for InMemorySessionRepository {
+    async fn create(&self, session: &Session) -> RepositoryResult<()> {
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions.insert(session.token.clone(), session.clone());
+        Ok(())
+    }
+
+    async fn find_by_token(&self, token: &str) -> RepositoryResult<Option<Session>> {
+        let sessions = self.sessions.lock().unwrap();
+        if let Some(session) = sessions.get(token) {
+            if session.is_active && session.expires_at > Utc::now() {
+                Ok(Some(session.clone()))
+            } else {
+                Ok(None)
+            }
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn deactivate(&self, token: &str) -> RepositoryResult<()> {
+        let mut sessions = self.sessions.lock().unwrap();
+        if let Some(session) = sessions.get_mut(token) {
+            session.is_active = false;
+            session.updated_at = Utc::now();
+        }
+        Ok(())
+    }
+
+    async fn deactivate_all_for_user(&self, user_uuid: Uuid) -> RepositoryResult<()> {
+        let mut sessions = self.sessions.lock().unwrap();
+        for session in sessions.values_mut() {
+            if session.user_uuid == user_uuid && session.is_active {
+                session.is_active = false;
+                session.updated_at = Utc::now();
+            }
+        }
+        Ok(())
+    }
+
+    async fn cleanup_expired(&self, now: DateTime<Utc>) -> RepositoryResult<u64> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let initial_count = sessions.len();
+        sessions.retain(|_, session| session.expires_at > now);
+        let final_count = sessions.len();
+        Ok((initial_count - final_count) as u64)
+    }
+
+    async fn count_active_for_user(&self, user_uuid: Uuid) -> RepositoryResult<usize> {
+        let sessions = self.sessions.lock().unwrap();
+        let count = sessions
+            .values()
+            .filter(|session| {
+                session.user_uuid == user_uuid
+                    && session.is_active
+                    && session.expires_at > Utc::now()
+            })
+            .count();
+        Ok(count)
+    }
+
+    async fn find_active_for_user(
+        &self,
+        user_uuid: Uuid,
+        now: DateTime<Utc>,
+    ) -> RepositoryResult<Vec<Session>> {
+        let sessions = self.sessions.lock().unwrap();
+        let mut active: Vec<Session> = sessions
+            .values()
+            .filter(|session| {
+                session.user_uuid == user_uuid && session.is_active && session.expires_at > now
+            })
+            .cloned()
+            .collect();
+        active.sort_by_key(|session| session.created_at);
+        Ok(active)
+    }
+
+    async fn count_active(&self, now: DateTime<Utc>) -> RepositoryResult<usize> {
+        let sessions = self.sessions.lock().unwrap();
+        let count = sessions
+            .values()
+            .filter(|session| session.is_active && session.expires_at > now)
+            .count();
+        Ok(count)
+    }
+}
+
+/// In-memory `TrackRepository`, used in tests
+#[derive(Clone)]
+pub struct InMemoryTrackRepository {
+    tracks: Arc<Mutex<HashMap<Uuid, TrackDesign>>>,
+}
+
+impl InMemoryTrackRepository {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            tracks: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    #[must_use]
+    pub fn with_tracks(tracks: Vec<TrackDesign>) -> Self {
+        let mut track_map = HashMap::new();
+        for track in tracks {
+            track_map.insert(track.uuid, track);
+        }
+
+        Self {
+            tracks: Arc::new(Mutex::new(track_map)),
+        }
+    }
+}
+
+impl Default for InMemoryTrackRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl TrackRepository for InMemoryTrackRepository {
+    async fn create(&self, track: &TrackDesign) -> RepositoryResult<TrackDesign> {
+        let mut tracks = self.tracks.lock().unwrap();
+        if tracks.contains_key(&track.uuid) {
+            return Err(RepositoryError::Conflict(format!(
+                "Track {} already exists",
+                track.uuid
+            )));
+        }
+        tracks.insert(track.uuid, track.clone());
+        Ok(track.clone())
+    }
+
+    async fn find_by_uuid(&self, track_uuid: Uuid) -> RepositoryResult<Option<TrackDesign>> {
+        let tracks = self.tracks.lock().unwrap();
+        Ok(tracks.get(&track_uuid).cloned())
+    }
+
+    async fn find_public(&self) -> RepositoryResult<Vec<TrackDesign>> {
+        let tracks = self.tracks.lock().unwrap();
+        Ok(tracks
+            .values()
+            .filter(|track| track.is_public)
+            .cloned()
+            .collect())
+    }
+
+    async fn update(&self, track: &TrackDesign) -> RepositoryResult<Option<TrackDesign>> {
+        let mut tracks = self.tracks.lock().unwrap();
+        match tracks.get_mut(&track.uuid) {
+            Some(existing) => {
+                *existing = track.clone();
+                Ok(Some(track.clone()))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn delete_by_uuid(&self, track_uuid: Uuid) -> RepositoryResult<bool> {
+        let mut tracks = self.tracks.lock().unwrap();
+        Ok(tracks.remove(&track_uuid).is_some())
+    }
+}
+
+/// In-memory `FriendshipRepository`, used in tests
+#[derive(Clone)]
+pub struct InMemoryFriendshipRepository {
+    /// Keyed by `Friendship::pair`
+    friendships: Arc<Mutex<HashMap<String, Friendship>>>,
+}
+
+impl InMemoryFriendshipRepository {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            friendships: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+impl Default for InMemoryFriendshipRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl FriendshipRepository for InMemoryFriendshipRepository {
+    async fn create(&self, friendship: &Friendship) -> RepositoryResult<Friendship> {
+        let mut friendships = self.friendships.lock().unwrap();
+        if friendships.contains_key(&friendship.pair) {
+            return Err(RepositoryError::Conflict(
+                "These players already have a friendship or request".to_string(),
+            ));
+        }
+        friendships.insert(friendship.pair.clone(), friendship.clone());
+        Ok(friendship.clone())
+    }
+
+    async fn find_between(&self, a: Uuid, b: Uuid) -> RepositoryResult<Option<Friendship>> {
+        let friendships = self.friendships.lock().unwrap();
+        Ok(friendships.get(&Friendship::pair_key(a, b)).cloned())
+    }
+
+    async fn accept(
+        &self,
+        requester_uuid: Uuid,
+        addressee_uuid: Uuid,
+    ) -> RepositoryResult<Option<Friendship>> {
+        let mut friendships = self.friendships.lock().unwrap();
+        match friendships.get_mut(&Friendship::pair_key(requester_uuid, addressee_uuid)) {
+            Some(friendship)
+                if friendship.requester_uuid == requester_uuid
+                    && friendship.status == FriendshipStatus::Pending =>
+            {
+                friendship.status = FriendshipStatus::Accepted;
+                friendship.accepted_at = Some(mongodb::bson::DateTime::now());
+                Ok(Some(friendship.clone()))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    async fn delete_between(&self, a: Uuid, b: Uuid) -> RepositoryResult<bool> {
+        let mut friendships = self.friendships.lock().unwrap();
+        Ok(friendships.remove(&Friendship::pair_key(a, b)).is_some())
+    }
+
+    async fn find_for_player(&self, player_uuid: Uuid) -> RepositoryResult<Vec<Friendship>> {
+        let friendships = self.friendships.lock().unwrap();
+        Ok(friendships
+            .values()
+            .filter(|f| f.requester_uuid == player_uuid || f.addressee_uuid == player_uuid)
+            .cloned()
+            .collect())
+    }
+}
+
+/// In-memory `TradeRepository` holding its own players, used in tests
+///
+/// Every operation runs under one lock, so settlements are atomic.
+#[derive(Clone, Default)]
+pub struct InMemoryTradeRepository {
+    state: Arc<Mutex<TradeState>>,
+}
+
+#[derive(Default)]
+struct TradeState {
+    players: HashMap<Uuid, Player>,
+    trades: HashMap<Uuid, TradeOffer>,
+}
+
+impl InMemoryTradeRepository {
+    #[must_use]
+    pub fn with_players(players: Vec<Player>) -> Self {
+        let state = TradeState {
+            players: players.into_iter().map(|p| (p.uuid, p)).collect(),
+            trades: HashMap::new(),
+        };
+        Self {
+            state: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    #[must_use]
+    pub fn player(&self, player_uuid: Uuid) -> Option<Player> {
+        self.state
+            .lock()
+            .unwrap()
+            .players
+            .get(&player_uuid)
+            .cloned()
+    }
+}
+
+#[async_trait]
+impl TradeRepository for InMemoryTradeRepository {
+    async fn open(&self, mut offer: TradeOffer) -> RepositoryResult<TradeOffer> {
+        let mut state = self.state.lock().unwrap();
+        let offerer = state
+            .players
+            .get_mut(&offer.offerer_uuid)
+            .ok_or(RepositoryError::NotFound)?;
+        let escrow = Escrow::take(offerer, &offer.offered).map_err(RepositoryError::Validation)?;
+        offer.offerer_escrow = Some(escrow);
+        offer.status = TradeStatus::Open;
+        state.trades.insert(offer.uuid, offer.clone());
+        Ok(offer)
+    }
+
+    async fn find_by_uuid(&self, trade_uuid: Uuid) -> RepositoryResult<Option<TradeOffer>> {
+        Ok(self.state.lock().unwrap().trades.get(&trade_uuid).cloned())
+    }
+
+    async fn find_for_player(&self, player_uuid: Uuid) -> RepositoryResult<Vec<TradeOffer>> {
+        let state = self.state.lock().unwrap();
+        let mut trades: Vec<TradeOffer> = state
+            .trades
+            .values()
+            .filter(|trade| trade.involves(player_uuid))
+            .cloned()
+            .collect();
+        trades.sort_by_key(|trade| std::cmp::Reverse(trade.created_at));
+        Ok(trades)
+    }
+
+    async fn accept(
+        &self,
+        trade_uuid: Uuid,
+        recipient_uuid: Uuid,
+    ) -> RepositoryResult<Option<TradeOffer>> {
+        let mut state = self.state.lock().unwrap();
+        let TradeState { players, trades } = &mut *state;
+        let Some(trade) = trades.get_mut(&trade_uuid).filter(|trade| {
+            trade.recipient_uuid == recipient_uuid && trade.status == TradeStatus::Open
+        }) else {
+            return Ok(None);
+        };
+        let recipient = players
+            .get_mut(&recipient_uuid)
+            .ok_or(RepositoryError::NotFound)?;
+        let escrow =
+            Escrow::take(recipient, &trade.requested).map_err(RepositoryError::Validation)?;
+        trade
+            .offerer_escrow
+            .take()
+            .unwrap_or_default()
+            .deliver(recipient);
+        let offerer = players
+            .get_mut(&trade.offerer_uuid)
+            .ok_or(RepositoryError::NotFound)?;
+        escrow.deliver(offerer);
+        trade.status = TradeStatus::Completed;
+        trade.settled_at = Some(mongodb::bson::DateTime::now());
+        Ok(Some(trade.clone()))
+    }
+
+    async fn cancel(
+        &self,
+        trade_uuid: Uuid,
+        player_uuid: Uuid,
+    ) -> RepositoryResult<Option<TradeOffer>> {
+        let mut state = self.state.lock().unwrap();
+        let TradeState { players, trades } = &mut *state;
+        let Some(trade) = trades
+            .get_mut(&trade_uuid)
+            .filter(|trade| trade.involves(player_uuid) && trade.status == TradeStatus::Open)
+        else {
+            return Ok(None);
+        };
+        if let Some(escrow) = trade.offerer_escrow.take() {
+            let offerer = players
+                .get_mut(&trade.offerer_uuid)
+                .ok_or(RepositoryError::NotFound)?;
+            escrow.deliver(offerer);
+        }
+        trade.status = TradeStatus::Cancelled;
+        trade.settled_at = Some(mongodb::bson::DateTime::now());
+        Ok(Some(trade.clone()))
+    }
+}
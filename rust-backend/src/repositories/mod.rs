@@ -1,14 +1,42 @@
+pub mod friendship_repository;
 pub mod player_repository;
 pub mod race_repository;
 pub mod session_repository;
+pub mod track_repository;
+pub mod trade_repository;
 
+pub mod in_memory;
 pub mod mocks;
+pub mod mongo_friendship_repository;
+pub mod mongo_track_repository;
+pub mod mongo_trade_repository;
+#[cfg(feature = "postgres")]
+pub mod postgres_player_repository;
+#[cfg(feature = "postgres")]
+pub mod postgres_race_repository;
 
+pub use friendship_repository::FriendshipRepository;
 pub use player_repository::PlayerRepository;
 pub use race_repository::RaceRepository;
 pub use session_repository::SessionRepository;
+pub use track_repository::TrackRepository;
+pub use trade_repository::TradeRepository;
 
-pub use mocks::{MockPlayerRepository, MockRaceRepository, MockSessionRepository};
+pub use in_memory::{
+    InMemoryFriendshipRepository, InMemoryPlayerRepository, InMemoryRaceRepository,
+    InMemorySessionRepository, InMemoryTrackRepository, InMemoryTradeRepository,
+};
+pub use mocks::{
+    MockFriendshipRepository, MockPlayerRepository, MockRaceRepository, MockSessionRepository,
+    MockTrackRepository, MockTradeRepository,
+};
+pub use mongo_friendship_repository::MongoFriendshipRepository;
+pub use mongo_track_repository::MongoTrackRepository;
+pub use mongo_trade_repository::MongoTradeRepository;
+#[cfg(feature = "postgres")]
+pub use postgres_player_repository::PostgresPlayerRepository;
+#[cfg(feature = "postgres")]
+pub use postgres_race_repository::PostgresRaceRepository;
 
 /// Common database error type
 #[derive(Debug, thiserror::Error)]
@@ -19,6 +47,8 @@ pub enum RepositoryError {
     Validation(String),
     #[error("Conflict: {0}")]
     Conflict(String),
+    #[error("Database error: {0}")]
+    Database(String),
 }
 
 /// Result type for repository operations
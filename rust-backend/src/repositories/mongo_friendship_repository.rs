@@ -0,0 +1,116 @@
+use async_trait::async_trait;
+use mongodb::{
+    bson::{doc, to_bson, DateTime as BsonDateTime},
+    options::{FindOneAndUpdateOptions, ReturnDocument},
+    Collection, Database,
+};
+use uuid::Uuid;
+
+use super::{FriendshipRepository, RepositoryError, RepositoryResult};
+use crate::database::{is_duplicate_key, with_retry};
+use crate::domain::{Friendship, FriendshipStatus};
+
+/// `FriendshipRepository` backed by the `friendships` collection
+#[derive(Clone)]
+pub struct MongoFriendshipRepository {
+    collection: Collection<Friendship>,
+}
+
+impl MongoFriendshipRepository {
+    #[must_use]
+    pub fn new(database: &Database) -> Self {
+        Self {
+            collection: database.collection::<Friendship>("friendships"),
+        }
+    }
+}
+
+fn database_error(e: &mongodb::error::Error) -> RepositoryError {
+    RepositoryError::Database(e.to_string())
+}
+
+fn status_bson(status: FriendshipStatus) -> RepositoryResult<mongodb::bson::Bson> {
+    to_bson(&status).map_err(|e| RepositoryError::Database(e.to_string()))
+}
+
+#[async_trait]
+impl FriendshipRepository for MongoFriendshipRepository {
+    async fn create(&self, friendship: &Friendship) -> RepositoryResult<Friendship> {
+        // A unique index on `pair` rejects a second friendship for the pair
+        match self.collection.insert_one(friendship, None).await {
+            Ok(result) => {
+                let mut created = friendship.clone();
+                created.id = result.inserted_id.as_object_id();
+                Ok(created)
+            }
+            Err(e) if is_duplicate_key(&e) => Err(RepositoryError::Conflict(
+                "These players already have a friendship or request".to_string(),
+            )),
+            Err(e) => Err(database_error(&e)),
+        }
+    }
+
+    async fn find_between(&self, a: Uuid, b: Uuid) -> RepositoryResult<Option<Friendship>> {
+        let filter = doc! { "pair": Friendship::pair_key(a, b) };
+        with_retry("Loading friendship", || {
+            self.collection.find_one(filter.clone(), None)
+        })
+        .await
+        .map_err(|e| database_error(&e))
+    }
+
+    async fn accept(
+        &self,
+        requester_uuid: Uuid,
+        addressee_uuid: Uuid,
+    ) -> RepositoryResult<Option<Friendship>> {
+        let filter = doc! {
+            "requester_uuid": requester_uuid.to_string(),
+            "addressee_uuid": addressee_uuid.to_string(),
+            "status": status_bson(FriendshipStatus::Pending)?,
+        };
+        let update = doc! {
+            "$set": {
+                "status": status_bson(FriendshipStatus::Accepted)?,
+                "accepted_at": BsonDateTime::now(),
+            }
+        };
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+        // Not retried: once accepted, a repeat would find no pending request
+        self.collection
+            .find_one_and_update(filter, update, options)
+            .await
+            .map_err(|e| database_error(&e))
+    }
+
+    async fn delete_between(&self, a: Uuid, b: Uuid) -> RepositoryResult<bool> {
+        let result = self
+            .collection
+            .delete_one(doc! { "pair": Friendship::pair_key(a, b) }, None)
+            .await
+            .map_err(|e| database_error(&e))?;
+        Ok(result.deleted_count > 0)
+    }
+
+    async fn find_for_player(&self, player_uuid: Uuid) -> RepositoryResult<Vec<Friendship>> {
+        let player_uuid = player_uuid.to_string();
+        let filter = doc! {
+            "$or": [
+                { "requester_uuid": &player_uuid },
+                { "addressee_uuid": &player_uuid },
+            ]
+        };
+        with_retry("Listing friendships", || async {
+            let mut cursor = self.collection.find(filter.clone(), None).await?;
+            let mut friendships = Vec::new();
+            while cursor.advance().await? {
+                friendships.push(cursor.deserialize_current()?);
+            }
+            Ok(friendships)
+        })
+        .await
+        .map_err(|e| database_error(&e))
+    }
+}
@@ -0,0 +1,89 @@
+use async_trait::async_trait;
+use mongodb::{bson::doc, Collection, Database};
+use uuid::Uuid;
+
+use super::{RepositoryError, RepositoryResult, TrackRepository};
+use crate::database::with_retry;
+use crate::domain::TrackDesign;
+
+/// `TrackRepository` backed by the `tracks` collection
+///
+/// The collection also holds the built-in templates, which have no `uuid`
+/// and are therefore never returned here.
+#[derive(Clone)]
+pub struct MongoTrackRepository {
+    collection: Collection<TrackDesign>,
+}
+
+impl MongoTrackRepository {
+    #[must_use]
+    pub fn new(database: &Database) -> Self {
+        Self {
+            collection: database.collection::<TrackDesign>("tracks"),
+        }
+    }
+}
+
+fn database_error(e: &mongodb::error::Error) -> RepositoryError {
+    RepositoryError::Database(e.to_string())
+}
+
+#[async_trait]
+impl TrackRepository for MongoTrackRepository {
+    async fn create(&self, track: &TrackDesign) -> RepositoryResult<TrackDesign> {
+        // Not retried: a repeat after a lost reply would insert a second copy
+        let result = self
+            .collection
+            .insert_one(track, None)
+            .await
+            .map_err(|e| database_error(&e))?;
+
+        let mut created = track.clone();
+        created.id = result.inserted_id.as_object_id();
+        Ok(created)
+    }
+
+    async fn find_by_uuid(&self, track_uuid: Uuid) -> RepositoryResult<Option<TrackDesign>> {
+        let filter = doc! { "uuid": track_uuid.to_string() };
+        with_retry("Loading track", || {
+            self.collection.find_one(filter.clone(), None)
+        })
+        .await
+        .map_err(|e| database_error(&e))
+    }
+
+    async fn find_public(&self) -> RepositoryResult<Vec<TrackDesign>> {
+        let filter = doc! { "uuid": { "$exists": true }, "is_public": true };
+        with_retry("Listing public tracks", || async {
+            let mut cursor = self.collection.find(filter.clone(), None).await?;
+            let mut tracks = Vec::new();
+            while cursor.advance().await? {
+                tracks.push(cursor.deserialize_current()?);
+            }
+            Ok(tracks)
+        })
+        .await
+        .map_err(|e| database_error(&e))
+    }
+
+    async fn update(&self, track: &TrackDesign) -> RepositoryResult<Option<TrackDesign>> {
+        let filter = doc! { "uuid": track.uuid.to_string() };
+        let result = with_retry("Replacing track", || {
+            self.collection.replace_one(filter.clone(), track, None)
+        })
+        .await
+        .map_err(|e| database_error(&e))?;
+
+        Ok((result.matched_count > 0).then(|| track.clone()))
+    }
+
+    async fn delete_by_uuid(&self, track_uuid: Uuid) -> RepositoryResult<bool> {
+        let filter = doc! { "uuid": track_uuid.to_string() };
+        let result = self
+            .collection
+            .delete_one(filter, None)
+            .await
+            .map_err(|e| database_error(&e))?;
+        Ok(result.deleted_count > 0)
+    }
+}
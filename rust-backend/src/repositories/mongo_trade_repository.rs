@@ -0,0 +1,427 @@
+use async_trait::async_trait;
+use mongodb::{
+    bson::{doc, to_bson, Bson, DateTime as BsonDateTime, Document},
+    options::{FindOneAndUpdateOptions, FindOptions, ReturnDocument},
+    Collection, Database,
+};
+use uuid::Uuid;
+
+use super::{RepositoryError, RepositoryResult, TradeRepository};
+use crate::database::with_retry;
+use crate::domain::{Escrow, Player, TradeOffer, TradeStatus};
+
+/// Escrow moves of a trade, recorded in `Player::trade_steps` once applied
+const ESCROW_OFFERER: &str = "escrow-offerer";
+const ESCROW_RECIPIENT: &str = "escrow-recipient";
+const DELIVER_RECIPIENT: &str = "deliver-recipient";
+const DELIVER_OFFERER: &str = "deliver-offerer";
+const REFUND_OFFERER: &str = "refund-offerer";
+
+/// `TradeRepository` backed by the `trades` and `players` collections
+///
+/// Without multi-document transactions every move is a single-document
+/// update: items leave or reach a player together with a step key pushed to
+/// `trade_steps`, and a move whose key is already there is skipped. The trade
+/// status records how far a settlement got, so repeating an accept or cancel
+/// finishes one that was cut short.
+#[derive(Clone)]
+pub struct MongoTradeRepository {
+    trades: Collection<TradeOffer>,
+    players: Collection<Player>,
+}
+
+impl MongoTradeRepository {
+    #[must_use]
+    pub fn new(database: &Database) -> Self {
+        Self {
+            trades: database.collection::<TradeOffer>("trades"),
+            players: database.collection::<Player>("players"),
+        }
+    }
+
+    async fn find_player(&self, player_uuid: Uuid) -> RepositoryResult<Player> {
+        self.players
+            .find_one(doc! { "uuid": player_uuid.to_string() }, None)
+            .await
+            .map_err(|e| database_error(&e))?
+            .ok_or(RepositoryError::NotFound)
+    }
+
+    /// Take `escrow` from the player; `false` when the player no longer has
+    /// the items and the move was not applied before
+    async fn withdraw(
+        &self,
+        player_uuid: Uuid,
+        key: &str,
+        escrow: &Escrow,
+    ) -> RepositoryResult<bool> {
+        let (engine_uuids, body_uuids) = component_uuids(escrow);
+        let credits = credits_bson(escrow)?;
+        let mut filter = doc! {
+            "uuid": player_uuid.to_string(),
+            "trade_steps": { "$ne": key },
+        };
+        if !engine_uuids.is_empty() {
+            filter.insert("engines.uuid", doc! { "$all": engine_uuids.clone() });
+        }
+        if !body_uuids.is_empty() {
+            filter.insert("bodies.uuid", doc! { "$all": body_uuids.clone() });
+        }
+        if escrow.credits > 0 {
+            filter.insert("credits", doc! { "$gte": credits });
+        }
+        let update = doc! {
+            "$pull": {
+                "engines": { "uuid": { "$in": engine_uuids } },
+                "bodies": { "uuid": { "$in": body_uuids } },
+            },
+            "$inc": { "credits": -credits },
+            "$push": { "trade_steps": key },
+            "$set": { "updated_at": BsonDateTime::now() },
+        };
+        let result = self
+            .players
+            .update_one(filter, update, None)
+            .await
+            .map_err(|e| database_error(&e))?;
+        if result.matched_count > 0 {
+            return Ok(true);
+        }
+        self.step_applied(player_uuid, key).await
+    }
+
+    /// Hand `escrow` to the player unless the move was applied before, and
+    /// only once the `after` move was; an error when neither holds, since the
+    /// items would otherwise be lost
+    async fn deposit(
+        &self,
+        player_uuid: Uuid,
+        key: &str,
+        after: Option<&str>,
+        escrow: &Escrow,
+    ) -> RepositoryResult<()> {
+        let steps = if let Some(after) = after {
+            doc! { "$eq": after, "$ne": key }
+        } else {
+            doc! { "$ne": key }
+        };
+        let filter = doc! { "uuid": player_uuid.to_string(), "trade_steps": steps };
+        let update = doc! {
+            "$push": {
+                "engines": { "$each": to_bson(&escrow.engines).map_err(|e| serialize_error(&e))? },
+                "bodies": { "$each": to_bson(&escrow.bodies).map_err(|e| serialize_error(&e))? },
+                "trade_steps": key,
+            },
+            "$inc": { "credits": credits_bson(escrow)? },
+            "$set": { "updated_at": BsonDateTime::now() },
+        };
+        let result = self
+            .players
+            .update_one(filter, update, None)
+            .await
+            .map_err(|e| database_error(&e))?;
+        if result.matched_count > 0 || self.step_applied(player_uuid, key).await? {
+            return Ok(());
+        }
+        Err(RepositoryError::Database(format!(
+            "Trade step {key} found no player {player_uuid} to deliver to"
+        )))
+    }
+
+    async fn step_applied(&self, player_uuid: Uuid, key: &str) -> RepositoryResult<bool> {
+        let applied = self
+            .players
+            .count_documents(
+                doc! { "uuid": player_uuid.to_string(), "trade_steps": key },
+                None,
+            )
+            .await
+            .map_err(|e| database_error(&e))?;
+        Ok(applied > 0)
+    }
+
+    /// Drop the step keys of a settled trade from both players
+    async fn forget_steps(&self, trade: &TradeOffer) -> RepositoryResult<()> {
+        let filter = doc! {
+            "uuid": { "$in": [trade.offerer_uuid.to_string(), trade.recipient_uuid.to_string()] },
+        };
+        let update = doc! {
+            "$pull": { "trade_steps": { "$regex": format!("^{}:", trade.uuid) } },
+        };
+        self.players
+            .update_many(filter, update, None)
+            .await
+            .map_err(|e| database_error(&e))?;
+        Ok(())
+    }
+
+    /// Apply `set`, which includes the new status, if the trade is still in
+    /// one of the `from` statuses
+    async fn transition(
+        &self,
+        trade_uuid: Uuid,
+        from: &[TradeStatus],
+        set: Document,
+    ) -> RepositoryResult<Option<TradeOffer>> {
+        let filter = doc! {
+            "uuid": trade_uuid.to_string(),
+            "status": { "$in": to_bson(from).map_err(|e| serialize_error(&e))? },
+        };
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+        self.trades
+            .find_one_and_update(filter, doc! { "$set": set }, options)
+            .await
+            .map_err(|e| database_error(&e))
+    }
+}
+
+fn database_error(e: &mongodb::error::Error) -> RepositoryError {
+    RepositoryError::Database(e.to_string())
+}
+
+fn serialize_error(e: &mongodb::bson::ser::Error) -> RepositoryError {
+    RepositoryError::Database(e.to_string())
+}
+
+fn status_bson(status: TradeStatus) -> RepositoryResult<Bson> {
+    to_bson(&status).map_err(|e| serialize_error(&e))
+}
+
+fn step_key(trade: &TradeOffer, step: &str) -> String {
+    format!("{}:{step}", trade.uuid)
+}
+
+fn component_uuids(escrow: &Escrow) -> (Vec<String>, Vec<String>) {
+    (
+        escrow.engines.iter().map(|e| e.uuid.to_string()).collect(),
+        escrow.bodies.iter().map(|b| b.uuid.to_string()).collect(),
+    )
+}
+
+fn credits_bson(escrow: &Escrow) -> RepositoryResult<i64> {
+    i64::try_from(escrow.credits)
+        .map_err(|_| RepositoryError::Validation("Too many credits for one trade".to_string()))
+}
+
+#[async_trait]
+impl TradeRepository for MongoTradeRepository {
+    async fn open(&self, mut offer: TradeOffer) -> RepositoryResult<TradeOffer> {
+        let mut offerer = self.find_player(offer.offerer_uuid).await?;
+        let escrow =
+            Escrow::take(&mut offerer, &offer.offered).map_err(RepositoryError::Validation)?;
+        offer.offerer_escrow = Some(escrow.clone());
+        offer.status = TradeStatus::Escrowing;
+        self.trades
+            .insert_one(&offer, None)
+            .await
+            .map_err(|e| database_error(&e))?;
+
+        let key = step_key(&offer, ESCROW_OFFERER);
+        if !self.withdraw(offer.offerer_uuid, &key, &escrow).await? {
+            // Nothing moved: the offerer's items changed since they were checked
+            self.transition(
+                offer.uuid,
+                &[TradeStatus::Escrowing],
+                doc! {
+                    "status": status_bson(TradeStatus::Cancelled)?,
+                    "settled_at": BsonDateTime::now(),
+                },
+            )
+            .await?;
+            return Err(RepositoryError::Conflict(
+                "The offered items changed meanwhile".to_string(),
+            ));
+        }
+
+        self.transition(
+            offer.uuid,
+            &[TradeStatus::Escrowing],
+            doc! { "status": status_bson(TradeStatus::Open)? },
+        )
+        .await?
+        .ok_or(RepositoryError::NotFound)
+    }
+
+    async fn find_by_uuid(&self, trade_uuid: Uuid) -> RepositoryResult<Option<TradeOffer>> {
+        let filter = doc! { "uuid": trade_uuid.to_string() };
+        with_retry("Loading trade", || {
+            self.trades.find_one(filter.clone(), None)
+        })
+        .await
+        .map_err(|e| database_error(&e))
+    }
+
+    async fn find_for_player(&self, player_uuid: Uuid) -> RepositoryResult<Vec<TradeOffer>> {
+        let player_uuid = player_uuid.to_string();
+        let filter = doc! {
+            "$or": [
+                { "offerer_uuid": &player_uuid },
+                { "recipient_uuid": &player_uuid },
+            ]
+        };
+        let options = FindOptions::builder()
+            .sort(doc! { "created_at": -1 })
+            .build();
+        with_retry("Listing trades", || async {
+            let mut cursor = self.trades.find(filter.clone(), options.clone()).await?;
+            let mut trades = Vec::new();
+            while cursor.advance().await? {
+                trades.push(cursor.deserialize_current()?);
+            }
+            Ok(trades)
+        })
+        .await
+        .map_err(|e| database_error(&e))
+    }
+
+    async fn accept(
+        &self,
+        trade_uuid: Uuid,
+        recipient_uuid: Uuid,
+    ) -> RepositoryResult<Option<TradeOffer>> {
+        let Some(trade) = self
+            .trades
+            .find_one_and_update(
+                doc! {
+                    "uuid": trade_uuid.to_string(),
+                    "recipient_uuid": recipient_uuid.to_string(),
+                    "status": { "$in": to_bson(&[TradeStatus::Open, TradeStatus::Settling])
+                        .map_err(|e| serialize_error(&e))? },
+                },
+                doc! { "$set": { "status": status_bson(TradeStatus::Settling)? } },
+                FindOneAndUpdateOptions::builder()
+                    .return_document(ReturnDocument::After)
+                    .build(),
+            )
+            .await
+            .map_err(|e| database_error(&e))?
+        else {
+            return Ok(None);
+        };
+        let reopen = || async move {
+            self.transition(
+                trade_uuid,
+                &[TradeStatus::Settling],
+                doc! {
+                    "status": status_bson(TradeStatus::Open)?,
+                    "recipient_escrow": Bson::Null,
+                },
+            )
+            .await
+        };
+
+        let escrow = if let Some(escrow) = trade.recipient_escrow.clone() {
+            escrow
+        } else {
+            let mut recipient = self.find_player(recipient_uuid).await?;
+            let escrow = match Escrow::take(&mut recipient, &trade.requested) {
+                Ok(escrow) => escrow,
+                Err(message) => {
+                    reopen().await?;
+                    return Err(RepositoryError::Validation(message));
+                }
+            };
+            // A concurrent accept may have fixed the recipient's escrow first
+            let fixed = self
+                .trades
+                .find_one_and_update(
+                    doc! { "uuid": trade_uuid.to_string(), "recipient_escrow": Bson::Null },
+                    doc! { "$set": {
+                        "recipient_escrow": to_bson(&escrow).map_err(|e| serialize_error(&e))?,
+                    } },
+                    None,
+                )
+                .await
+                .map_err(|e| database_error(&e))?;
+            match fixed {
+                Some(_) => escrow,
+                None => self
+                    .find_by_uuid(trade_uuid)
+                    .await?
+                    .and_then(|trade| trade.recipient_escrow)
+                    .ok_or(RepositoryError::NotFound)?,
+            }
+        };
+
+        let key = step_key(&trade, ESCROW_RECIPIENT);
+        if !self.withdraw(recipient_uuid, &key, &escrow).await? {
+            reopen().await?;
+            return Err(RepositoryError::Conflict(
+                "The requested items changed meanwhile".to_string(),
+            ));
+        }
+        let offered = trade.offerer_escrow.clone().unwrap_or_default();
+        let key = step_key(&trade, DELIVER_RECIPIENT);
+        self.deposit(recipient_uuid, &key, None, &offered).await?;
+        let key = step_key(&trade, DELIVER_OFFERER);
+        self.deposit(trade.offerer_uuid, &key, None, &escrow)
+            .await?;
+
+        let completed = self
+            .transition(
+                trade_uuid,
+                &[TradeStatus::Settling],
+                doc! {
+                    "status": status_bson(TradeStatus::Completed)?,
+                    "settled_at": BsonDateTime::now(),
+                },
+            )
+            .await?;
+        if let Some(completed) = &completed {
+            self.forget_steps(completed).await?;
+        }
+        Ok(completed)
+    }
+
+    async fn cancel(
+        &self,
+        trade_uuid: Uuid,
+        player_uuid: Uuid,
+    ) -> RepositoryResult<Option<TradeOffer>> {
+        let player_uuid = player_uuid.to_string();
+        let Some(trade) = self
+            .trades
+            .find_one_and_update(
+                doc! {
+                    "uuid": trade_uuid.to_string(),
+                    "$or": [
+                        { "offerer_uuid": &player_uuid },
+                        { "recipient_uuid": &player_uuid },
+                    ],
+                    "status": { "$in": to_bson(&[TradeStatus::Open, TradeStatus::Cancelling])
+                        .map_err(|e| serialize_error(&e))? },
+                },
+                doc! { "$set": { "status": status_bson(TradeStatus::Cancelling)? } },
+                None,
+            )
+            .await
+            .map_err(|e| database_error(&e))?
+        else {
+            return Ok(None);
+        };
+
+        if let Some(escrow) = &trade.offerer_escrow {
+            let escrowed = step_key(&trade, ESCROW_OFFERER);
+            let key = step_key(&trade, REFUND_OFFERER);
+            self.deposit(trade.offerer_uuid, &key, Some(&escrowed), escrow)
+                .await?;
+        }
+
+        let cancelled = self
+            .transition(
+                trade_uuid,
+                &[TradeStatus::Cancelling],
+                doc! {
+                    "status": status_bson(TradeStatus::Cancelled)?,
+                    "settled_at": BsonDateTime::now(),
+                },
+            )
+            .await?;
+        if let Some(cancelled) = &cancelled {
+            self.forget_steps(cancelled).await?;
+        }
+        Ok(cancelled)
+    }
+}
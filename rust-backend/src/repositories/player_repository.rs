@@ -2,7 +2,7 @@ use async_trait::async_trait;
 use uuid::Uuid;
 
 use super::RepositoryResult;
-use crate::domain::{Car, Pilot, Player, TeamName, WalletAddress};
+use crate::domain::{Car, OAuthIdentity, OAuthProvider, Pilot, Player, TeamName, WalletAddress};
 
 #[async_trait]
 pub trait PlayerRepository: Send + Sync {
@@ -76,4 +76,15 @@ pub trait PlayerRepository: Send + Sync {
         player_uuid: Uuid,
         cars: Vec<Car>,
     ) -> RepositoryResult<Option<Player>>;
+    /// The player a provider account is linked to
+    async fn find_by_oauth_identity(
+        &self,
+        provider: OAuthProvider,
+        subject: &str,
+    ) -> RepositoryResult<Option<Player>>;
+    async fn add_oauth_identity(
+        &self,
+        player_uuid: Uuid,
+        identity: OAuthIdentity,
+    ) -> RepositoryResult<Option<Player>>;
 }
@@ -0,0 +1,346 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use sqlx::types::Json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::{PlayerRepository, RepositoryError, RepositoryResult};
+use crate::domain::{Car, OAuthIdentity, OAuthProvider, Pilot, Player, TeamName, WalletAddress};
+
+/// `PlayerRepository` backed by the `players` table
+///
+/// Each row holds the whole player as JSON; `email` and `wallet_address` are
+/// copied into their own columns for lookups and uniqueness.
+#[derive(Clone)]
+pub struct PostgresPlayerRepository {
+    pool: PgPool,
+}
+
+/// Column used to find the player an update applies to
+enum PlayerKey<'a> {
+    Uuid(Uuid),
+    Wallet(&'a str),
+}
+
+impl PostgresPlayerRepository {
+    #[must_use]
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    async fn find_one(&self, key: PlayerKey<'_>) -> RepositoryResult<Option<Player>> {
+        let row: Option<(Json<Player>,)> = match key {
+            PlayerKey::Uuid(uuid) => {
+                sqlx::query_as("SELECT document FROM players WHERE uuid = $1")
+                    .bind(uuid)
+                    .fetch_optional(&self.pool)
+                    .await
+            }
+            PlayerKey::Wallet(wallet_address) => {
+                sqlx::query_as("SELECT document FROM players WHERE wallet_address = $1")
+                    .bind(wallet_address)
+                    .fetch_optional(&self.pool)
+                    .await
+            }
+        }
+        .map_err(|e| database_error(&e))?;
+
+        Ok(row.map(|(Json(player),)| player))
+    }
+
+    /// Load a player, apply a change and save it back in one transaction
+    async fn update(
+        &self,
+        key: PlayerKey<'_>,
+        change: impl FnOnce(&mut Player) + Send,
+    ) -> RepositoryResult<Option<Player>> {
+        let mut transaction = self.pool.begin().await.map_err(|e| database_error(&e))?;
+
+        let row: Option<(Json<Player>,)> = match key {
+            PlayerKey::Uuid(uuid) => {
+                sqlx::query_as("SELECT document FROM players WHERE uuid = $1 FOR UPDATE")
+                    .bind(uuid)
+                    .fetch_optional(&mut *transaction)
+                    .await
+            }
+            PlayerKey::Wallet(wallet_address) => {
+                sqlx::query_as("SELECT document FROM players WHERE wallet_address = $1 FOR UPDATE")
+                    .bind(wallet_address)
+                    .fetch_optional(&mut *transaction)
+                    .await
+            }
+        }
+        .map_err(|e| database_error(&e))?;
+
+        let Some((Json(mut player),)) = row else {
+            return Ok(None);
+        };
+
+        change(&mut player);
+        player.updated_at = Utc::now();
+
+        sqlx::query(
+            "UPDATE players SET email = $2, wallet_address = $3, document = $4, updated_at = now() \
+             WHERE uuid = $1",
+        )
+        .bind(player.uuid)
+        .bind(player.email.as_ref())
+        .bind(player.wallet_address.as_ref().map(AsRef::<str>::as_ref))
+        .bind(Json(&player))
+        .execute(&mut *transaction)
+        .await
+        .map_err(|e| write_error(&e))?;
+
+        transaction.commit().await.map_err(|e| database_error(&e))?;
+        Ok(Some(player))
+    }
+
+    async fn delete(&self, key: PlayerKey<'_>) -> RepositoryResult<bool> {
+        let result = match key {
+            PlayerKey::Uuid(uuid) => {
+                sqlx::query("DELETE FROM players WHERE uuid = $1")
+                    .bind(uuid)
+                    .execute(&self.pool)
+                    .await
+            }
+            PlayerKey::Wallet(wallet_address) => {
+                sqlx::query("DELETE FROM players WHERE wallet_address = $1")
+                    .bind(wallet_address)
+                    .execute(&self.pool)
+                    .await
+            }
+        }
+        .map_err(|e| database_error(&e))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+fn database_error(e: &sqlx::Error) -> RepositoryError {
+    RepositoryError::Database(e.to_string())
+}
+
+/// Map unique violations on email or wallet address to a conflict
+fn write_error(e: &sqlx::Error) -> RepositoryError {
+    match e {
+        sqlx::Error::Database(db_error) if db_error.is_unique_violation() => {
+            RepositoryError::Conflict(
+                "Player with this email or wallet address already exists".to_string(),
+            )
+        }
+        _ => database_error(e),
+    }
+}
+
+#[async_trait]
+impl PlayerRepository for PostgresPlayerRepository {
+    async fn create(&self, player: &Player) -> RepositoryResult<Player> {
+        sqlx::query(
+            "INSERT INTO players (uuid, email, wallet_address, document) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(player.uuid)
+        .bind(player.email.as_ref())
+        .bind(player.wallet_address.as_ref().map(AsRef::<str>::as_ref))
+        .bind(Json(player))
+        .execute(&self.pool)
+        .await
+        .map_err(|e| write_error(&e))?;
+
+        Ok(player.clone())
+    }
+
+    async fn find_all(&self) -> RepositoryResult<Vec<Player>> {
+        let rows: Vec<(Json<Player>,)> =
+            sqlx::query_as("SELECT document FROM players ORDER BY created_at")
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| database_error(&e))?;
+
+        Ok(rows.into_iter().map(|(Json(player),)| player).collect())
+    }
+
+    async fn find_by_wallet_address(
+        &self,
+        wallet_address: &str,
+    ) -> RepositoryResult<Option<Player>> {
+        self.find_one(PlayerKey::Wallet(wallet_address)).await
+    }
+
+    async fn find_by_email(&self, email: &str) -> RepositoryResult<Option<Player>> {
+        let row: Option<(Json<Player>,)> =
+            sqlx::query_as("SELECT document FROM players WHERE email = $1")
+                .bind(email)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| database_error(&e))?;
+
+        Ok(row.map(|(Json(player),)| player))
+    }
+
+    async fn find_by_uuid(&self, player_uuid: Uuid) -> RepositoryResult<Option<Player>> {
+        self.find_one(PlayerKey::Uuid(player_uuid)).await
+    }
+
+    async fn update_team_name_by_wallet(
+        &self,
+        wallet_address: &str,
+        team_name: TeamName,
+    ) -> RepositoryResult<Option<Player>> {
+        self.update(PlayerKey::Wallet(wallet_address), |player| {
+            player.team_name = team_name;
+        })
+        .await
+    }
+
+    async fn update_team_name_by_uuid(
+        &self,
+        player_uuid: Uuid,
+        team_name: TeamName,
+    ) -> RepositoryResult<Option<Player>> {
+        self.update(PlayerKey::Uuid(player_uuid), |player| {
+            player.team_name = team_name;
+        })
+        .await
+    }
+
+    async fn update_wallet_address(
+        &self,
+        player_uuid: Uuid,
+        wallet_address: WalletAddress,
+    ) -> RepositoryResult<Option<Player>> {
+        self.update(PlayerKey::Uuid(player_uuid), |player| {
+            player.wallet_address = Some(wallet_address);
+        })
+        .await
+    }
+
+    async fn delete_by_wallet_address(&self, wallet_address: &str) -> RepositoryResult<bool> {
+        self.delete(PlayerKey::Wallet(wallet_address)).await
+    }
+
+    async fn delete_by_uuid(&self, player_uuid: Uuid) -> RepositoryResult<bool> {
+        self.delete(PlayerKey::Uuid(player_uuid)).await
+    }
+
+    async fn add_car_by_wallet(
+        &self,
+        wallet_address: &str,
+        car: Car,
+    ) -> RepositoryResult<Option<Player>> {
+        self.update(PlayerKey::Wallet(wallet_address), |player| {
+            player.cars.push(car);
+        })
+        .await
+    }
+
+    async fn add_car_by_uuid(
+        &self,
+        player_uuid: Uuid,
+        car: Car,
+    ) -> RepositoryResult<Option<Player>> {
+        self.update(PlayerKey::Uuid(player_uuid), |player| player.cars.push(car))
+            .await
+    }
+
+    async fn remove_car_by_wallet(
+        &self,
+        wallet_address: &str,
+        car_uuid: Uuid,
+    ) -> RepositoryResult<Option<Player>> {
+        self.update(PlayerKey::Wallet(wallet_address), |player| {
+            player.cars.retain(|car| car.uuid != car_uuid);
+        })
+        .await
+    }
+
+    async fn remove_car_by_uuid(
+        &self,
+        player_uuid: Uuid,
+        car_uuid: Uuid,
+    ) -> RepositoryResult<Option<Player>> {
+        self.update(PlayerKey::Uuid(player_uuid), |player| {
+            player.cars.retain(|car| car.uuid != car_uuid);
+        })
+        .await
+    }
+
+    async fn add_pilot_by_wallet(
+        &self,
+        wallet_address: &str,
+        pilot: Pilot,
+    ) -> RepositoryResult<Option<Player>> {
+        self.update(PlayerKey::Wallet(wallet_address), |player| {
+            player.pilots.push(pilot);
+        })
+        .await
+    }
+
+    async fn add_pilot_by_uuid(
+        &self,
+        player_uuid: Uuid,
+        pilot: Pilot,
+    ) -> RepositoryResult<Option<Player>> {
+        self.update(PlayerKey::Uuid(player_uuid), |player| {
+            player.pilots.push(pilot);
+        })
+        .await
+    }
+
+    async fn remove_pilot_by_wallet(
+        &self,
+        wallet_address: &str,
+        pilot_uuid: Uuid,
+    ) -> RepositoryResult<Option<Player>> {
+        self.update(PlayerKey::Wallet(wallet_address), |player| {
+            player.pilots.retain(|pilot| pilot.uuid != pilot_uuid);
+        })
+        .await
+    }
+
+    async fn remove_pilot_by_uuid(
+        &self,
+        player_uuid: Uuid,
+        pilot_uuid: Uuid,
+    ) -> RepositoryResult<Option<Player>> {
+        self.update(PlayerKey::Uuid(player_uuid), |player| {
+            player.pilots.retain(|pilot| pilot.uuid != pilot_uuid);
+        })
+        .await
+    }
+
+    async fn set_cars_by_uuid(
+        &self,
+        player_uuid: Uuid,
+        cars: Vec<Car>,
+    ) -> RepositoryResult<Option<Player>> {
+        self.update(PlayerKey::Uuid(player_uuid), |player| player.cars = cars)
+            .await
+    }
+
+    async fn find_by_oauth_identity(
+        &self,
+        provider: OAuthProvider,
+        subject: &str,
+    ) -> RepositoryResult<Option<Player>> {
+        let identity = serde_json::json!([{ "provider": provider, "subject": subject }]);
+        let row: Option<(Json<Player>,)> =
+            sqlx::query_as("SELECT document FROM players WHERE document->'oauth_identities' @> $1")
+                .bind(Json(identity))
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| database_error(&e))?;
+
+        Ok(row.map(|(Json(player),)| player))
+    }
+
+    async fn add_oauth_identity(
+        &self,
+        player_uuid: Uuid,
+        identity: OAuthIdentity,
+    ) -> RepositoryResult<Option<Player>> {
+        self.update(PlayerKey::Uuid(player_uuid), |player| {
+            player.oauth_identities.push(identity);
+        })
+        .await
+    }
+}
@@ -0,0 +1,271 @@
+use async_trait::async_trait;
+use sqlx::types::Json;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use super::{RaceRepository, RepositoryError, RepositoryResult};
+use crate::domain::{LapAction, LapResult, Race, RaceStatus};
+use crate::services::car_validation::ValidatedCarData;
+
+/// `RaceRepository` backed by the `races` and `race_participants` tables
+///
+/// The race itself is stored as JSON. Participants are mirrored into their own
+/// table on every save so races can be looked up by pilot. No route reads
+/// races through it yet: they all still go to `MongoDB`.
+#[derive(Clone)]
+pub struct PostgresRaceRepository {
+    pool: PgPool,
+}
+
+impl PostgresRaceRepository {
+    #[must_use]
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    async fn fetch_all(
+        &self,
+        query: &'static str,
+        bind: Option<&str>,
+    ) -> RepositoryResult<Vec<Race>> {
+        let mut query = sqlx::query_as::<_, (Json<Race>,)>(query);
+        if let Some(value) = bind {
+            query = query.bind(value);
+        }
+        let rows = query
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| database_error(&e))?;
+
+        Ok(rows.into_iter().map(|(Json(race),)| race).collect())
+    }
+
+    /// Load a race, apply a change and save it back in one transaction
+    /// Returns `None` when the race does not exist; a failed change is not saved
+    async fn update<T>(
+        &self,
+        race_uuid: Uuid,
+        change: impl FnOnce(&mut Race) -> RepositoryResult<T> + Send,
+    ) -> RepositoryResult<Option<(Race, T)>> {
+        let mut transaction = self.pool.begin().await.map_err(|e| database_error(&e))?;
+
+        let row: Option<(Json<Race>,)> =
+            sqlx::query_as("SELECT document FROM races WHERE uuid = $1 FOR UPDATE")
+                .bind(race_uuid)
+                .fetch_optional(&mut *transaction)
+                .await
+                .map_err(|e| database_error(&e))?;
+
+        let Some((Json(mut race),)) = row else {
+            return Ok(None);
+        };
+
+        let output = change(&mut race)?;
+        save(&mut transaction, &race).await?;
+
+        transaction.commit().await.map_err(|e| database_error(&e))?;
+        Ok(Some((race, output)))
+    }
+}
+
+fn database_error(e: &sqlx::Error) -> RepositoryError {
+    RepositoryError::Database(e.to_string())
+}
+
+/// Value stored in the `status` column
+fn status_name(status: &RaceStatus) -> &'static str {
+    match status {
+        RaceStatus::Waiting => "Waiting",
+        RaceStatus::Starting => "Starting",
+        RaceStatus::InProgress => "InProgress",
+        RaceStatus::Finished => "Finished",
+        RaceStatus::Cancelled => "Cancelled",
+    }
+}
+
+/// Upsert a race document and rewrite its participant rows
+async fn save(transaction: &mut Transaction<'_, Postgres>, race: &Race) -> RepositoryResult<()> {
+    sqlx::query(
+        "INSERT INTO races (uuid, name, status, document) VALUES ($1, $2, $3, $4) \
+         ON CONFLICT (uuid) DO UPDATE \
+         SET name = EXCLUDED.name, status = EXCLUDED.status, document = EXCLUDED.document, \
+             updated_at = now()",
+    )
+    .bind(race.uuid)
+    .bind(&race.name)
+    .bind(status_name(&race.status))
+    .bind(Json(race))
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| database_error(&e))?;
+
+    sqlx::query("DELETE FROM race_participants WHERE race_uuid = $1")
+        .bind(race.uuid)
+        .execute(&mut **transaction)
+        .await
+        .map_err(|e| database_error(&e))?;
+
+    for participant in &race.participants {
+        sqlx::query(
+            "INSERT INTO race_participants (race_uuid, player_uuid, car_uuid, pilot_uuid) \
+             VALUES ($1, $2, $3, $4)",
+        )
+        .bind(race.uuid)
+        .bind(participant.player_uuid)
+        .bind(participant.car_uuid)
+        .bind(participant.pilot_uuid)
+        .execute(&mut **transaction)
+        .await
+        .map_err(|e| database_error(&e))?;
+    }
+
+    Ok(())
+}
+
+#[async_trait]
+impl RaceRepository for PostgresRaceRepository {
+    async fn create(&self, race: &Race) -> RepositoryResult<Race> {
+        let mut transaction = self.pool.begin().await.map_err(|e| database_error(&e))?;
+        save(&mut transaction, race).await?;
+        transaction.commit().await.map_err(|e| database_error(&e))?;
+        Ok(race.clone())
+    }
+
+    async fn find_all(&self) -> RepositoryResult<Vec<Race>> {
+        self.fetch_all("SELECT document FROM races ORDER BY created_at", None)
+            .await
+    }
+
+    async fn find_by_uuid(&self, race_uuid: Uuid) -> RepositoryResult<Option<Race>> {
+        let row: Option<(Json<Race>,)> =
+            sqlx::query_as("SELECT document FROM races WHERE uuid = $1")
+                .bind(race_uuid)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| database_error(&e))?;
+
+        Ok(row.map(|(Json(race),)| race))
+    }
+
+    async fn find_by_pilot_uuid(&self, pilot_uuid: Uuid) -> RepositoryResult<Option<Race>> {
+        let row: Option<(Json<Race>,)> = sqlx::query_as(
+            "SELECT r.document FROM races r \
+             JOIN race_participants p ON p.race_uuid = r.uuid \
+             WHERE p.pilot_uuid = $1 \
+             ORDER BY r.created_at DESC LIMIT 1",
+        )
+        .bind(pilot_uuid)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| database_error(&e))?;
+
+        Ok(row.map(|(Json(race),)| race))
+    }
+
+    async fn find_active_race_for_pilot(&self, pilot_uuid: Uuid) -> RepositoryResult<Option<Race>> {
+        let row: Option<(Json<Race>,)> = sqlx::query_as(
+            "SELECT r.document FROM races r \
+             JOIN race_participants p ON p.race_uuid = r.uuid \
+             WHERE p.pilot_uuid = $1 AND r.status IN ($2, $3, $4) \
+             ORDER BY r.created_at DESC LIMIT 1",
+        )
+        .bind(pilot_uuid)
+        .bind(status_name(&RaceStatus::Waiting))
+        .bind(status_name(&RaceStatus::Starting))
+        .bind(status_name(&RaceStatus::InProgress))
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| database_error(&e))?;
+
+        Ok(row.map(|(Json(race),)| race))
+    }
+
+    async fn join_race(
+        &self,
+        race_uuid: Uuid,
+        pilot_uuid: Uuid,
+        car_data: &ValidatedCarData,
+    ) -> RepositoryResult<Option<Race>> {
+        let player_uuid = car_data.pilot.uuid;
+        let car_uuid = car_data.car.uuid;
+
+        let updated = self
+            .update(race_uuid, |race| {
+                if !matches!(race.status, RaceStatus::Waiting) {
+                    return Err(RepositoryError::Validation(
+                        "Race is not accepting new players".to_string(),
+                    ));
+                }
+                if race.participants.iter().any(|p| p.pilot_uuid == pilot_uuid) {
+                    return Err(RepositoryError::Conflict(
+                        "Pilot already in race".to_string(),
+                    ));
+                }
+
+                // The pilot's UUID doubles as the player UUID here
+                race.add_participant(player_uuid, car_uuid, pilot_uuid)
+                    .map_err(RepositoryError::Validation)
+            })
+            .await?;
+
+        updated
+            .map(|(race, ())| Some(race))
+            .ok_or(RepositoryError::NotFound)
+    }
+
+    async fn process_turn_actions(
+        &self,
+        race_uuid: Uuid,
+        _pilot_uuid: Uuid,
+        actions: Vec<LapAction>,
+    ) -> RepositoryResult<Option<(LapResult, RaceStatus)>> {
+        let updated = self
+            .update(race_uuid, |race| {
+                race.process_lap(&actions)
+                    .map_err(RepositoryError::Validation)
+            })
+            .await?;
+
+        updated
+            .map(|(race, lap_result)| Some((lap_result, race.status)))
+            .ok_or(RepositoryError::NotFound)
+    }
+
+    async fn submit_turn_action(
+        &self,
+        race_uuid: Uuid,
+        pilot_uuid: Uuid,
+        _boost_value: u32,
+    ) -> RepositoryResult<Option<Race>> {
+        // Actions are resolved in batches by `process_turn_actions`
+        match self.find_by_uuid(race_uuid).await? {
+            Some(race) if race.participants.iter().any(|p| p.pilot_uuid == pilot_uuid) => {
+                Ok(Some(race))
+            }
+            _ => Err(RepositoryError::NotFound),
+        }
+    }
+
+    async fn update_race_status(
+        &self,
+        race_uuid: Uuid,
+        status: RaceStatus,
+    ) -> RepositoryResult<Option<Race>> {
+        let updated = self
+            .update(race_uuid, |race| {
+                race.status = status;
+                Ok(())
+            })
+            .await?;
+
+        Ok(updated.map(|(race, ())| race))
+    }
+
+    async fn get_races_by_status(&self, status: RaceStatus) -> RepositoryResult<Vec<Race>> {
+        self.fetch_all(
+            "SELECT document FROM races WHERE status = $1 ORDER BY created_at",
+            Some(status_name(&status)),
+        )
+        .await
+    }
+}
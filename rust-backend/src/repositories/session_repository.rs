@@ -13,4 +13,12 @@ pub trait SessionRepository: Send + Sync {
     async fn deactivate_all_for_user(&self, user_uuid: Uuid) -> RepositoryResult<()>;
     async fn cleanup_expired(&self, now: DateTime<Utc>) -> RepositoryResult<u64>;
     async fn count_active_for_user(&self, user_uuid: Uuid) -> RepositoryResult<usize>;
+    /// Sessions of a user that are active and unexpired at `now`, oldest first
+    async fn find_active_for_user(
+        &self,
+        user_uuid: Uuid,
+        now: DateTime<Utc>,
+    ) -> RepositoryResult<Vec<Session>>;
+    /// Sessions of any user that are active and unexpired at `now`
+    async fn count_active(&self, now: DateTime<Utc>) -> RepositoryResult<usize>;
 }
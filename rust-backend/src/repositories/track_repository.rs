@@ -0,0 +1,14 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use super::RepositoryResult;
+use crate::domain::TrackDesign;
+
+#[async_trait]
+pub trait TrackRepository: Send + Sync {
+    async fn create(&self, track: &TrackDesign) -> RepositoryResult<TrackDesign>;
+    async fn find_by_uuid(&self, track_uuid: Uuid) -> RepositoryResult<Option<TrackDesign>>;
+    async fn find_public(&self) -> RepositoryResult<Vec<TrackDesign>>;
+    async fn update(&self, track: &TrackDesign) -> RepositoryResult<Option<TrackDesign>>;
+    async fn delete_by_uuid(&self, track_uuid: Uuid) -> RepositoryResult<bool>;
+}
@@ -0,0 +1,34 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use super::RepositoryResult;
+use crate::domain::TradeOffer;
+
+/// Trade offers and the escrow moving their items
+///
+/// Items are always either with a player or in a trade's escrow, never in
+/// both and never in neither, even when a settlement is cut short.
+#[async_trait]
+pub trait TradeRepository: Send + Sync {
+    /// Store the offer and move the offerer's items into escrow;
+    /// `Validation` if the offerer cannot give them
+    async fn open(&self, offer: TradeOffer) -> RepositoryResult<TradeOffer>;
+    async fn find_by_uuid(&self, trade_uuid: Uuid) -> RepositoryResult<Option<TradeOffer>>;
+    /// Offers the player made or received, newest first
+    async fn find_for_player(&self, player_uuid: Uuid) -> RepositoryResult<Vec<TradeOffer>>;
+    /// Escrow the recipient's items and hand both sides over; `None` unless
+    /// the trade is open or settling for `recipient_uuid`, `Validation` if the
+    /// recipient cannot give the requested items
+    async fn accept(
+        &self,
+        trade_uuid: Uuid,
+        recipient_uuid: Uuid,
+    ) -> RepositoryResult<Option<TradeOffer>>;
+    /// Give the offerer's escrowed items back; `None` unless `player_uuid` is
+    /// on the trade and it has not settled
+    async fn cancel(
+        &self,
+        trade_uuid: Uuid,
+        player_uuid: Uuid,
+    ) -> RepositoryResult<Option<TradeOffer>>;
+}
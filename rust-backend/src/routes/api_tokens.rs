@@ -0,0 +1,252 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+    routing::{delete, get},
+    Extension, Router,
+};
+use futures_util::TryStreamExt;
+use mongodb::{
+    bson::{doc, DateTime as BsonDateTime},
+    options::FindOptions,
+    Database,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::{ApiScope, ApiToken};
+use crate::middleware::UserContext;
+use crate::routes::races::ErrorResponse;
+
+pub const API_TOKENS: &str = "api_tokens";
+/// Most unrevoked tokens a player may hold
+const MAX_TOKENS_PER_PLAYER: u64 = 10;
+const MAX_NAME_LENGTH: usize = 64;
+
+type ApiError = (StatusCode, Json<ErrorResponse>);
+
+/// The authenticated player's API tokens; mounted behind the auth middleware
+pub fn routes() -> Router<Database> {
+    Router::new()
+        .route("/api-tokens", get(list_api_tokens).post(issue_api_token))
+        .route("/api-tokens/:token_uuid", delete(revoke_api_token))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct IssueApiTokenRequest {
+    /// What the token is for, e.g. the site using it
+    pub name: String,
+    pub scopes: Vec<ApiScope>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ApiTokenResponse {
+    pub uuid: String,
+    pub name: String,
+    pub scopes: Vec<ApiScope>,
+    pub created_at: String,
+    pub revoked: bool,
+}
+
+impl From<&ApiToken> for ApiTokenResponse {
+    fn from(api_token: &ApiToken) -> Self {
+        Self {
+            uuid: api_token.uuid.to_string(),
+            name: api_token.name.clone(),
+            scopes: api_token.scopes.clone(),
+            created_at: api_token
+                .created_at
+                .try_to_rfc3339_string()
+                .unwrap_or_default(),
+            revoked: api_token.revoked_at.is_some(),
+        }
+    }
+}
+
+/// Issued API token, which is only shown once
+#[derive(Debug, Serialize, ToSchema)]
+pub struct IssuedApiTokenResponse {
+    pub api_token: ApiTokenResponse,
+    /// Sent by the site as `Authorization: Bearer <token>`
+    pub token: String,
+}
+
+fn error(status: StatusCode, error: &str, message: &str) -> ApiError {
+    (
+        status,
+        Json(ErrorResponse {
+            error: error.to_string(),
+            message: message.to_string(),
+            details: None,
+        }),
+    )
+}
+
+fn database_error(e: &mongodb::error::Error) -> ApiError {
+    tracing::error!("API token storage failed: {:?}", e);
+    error(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "DATABASE_ERROR",
+        "Internal server error",
+    )
+}
+
+/// Issue a read-only API token for a third-party site
+#[utoipa::path(
+    post,
+    path = "/api/v1/api-tokens",
+    request_body = IssueApiTokenRequest,
+    responses(
+        (status = 201, description = "Token issued; it is not shown again", body = IssuedApiTokenResponse),
+        (status = 400, description = "Missing name or scopes", body = ErrorResponse),
+        (status = 401, description = "Not authenticated"),
+        (status = 409, description = "Too many tokens", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Issuing API token", skip(database, user, request))]
+pub async fn issue_api_token(
+    State(database): State<Database>,
+    Extension(user): Extension<UserContext>,
+    Json(request): Json<IssueApiTokenRequest>,
+) -> Result<(StatusCode, Json<IssuedApiTokenResponse>), ApiError> {
+    let name = request.name.trim();
+    if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_NAME",
+            "Token names are 1 to 64 characters",
+        ));
+    }
+    let mut scopes: Vec<ApiScope> = Vec::new();
+    for scope in request.scopes {
+        if !scopes.contains(&scope) {
+            scopes.push(scope);
+        }
+    }
+    if scopes.is_empty() {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "NO_SCOPES",
+            "A token needs at least one scope",
+        ));
+    }
+
+    let collection = database.collection::<ApiToken>(API_TOKENS);
+    let held = collection
+        .count_documents(
+            doc! { "owner_uuid": user.user_uuid.to_string(), "revoked_at": null },
+            None,
+        )
+        .await
+        .map_err(|e| database_error(&e))?;
+    if held >= MAX_TOKENS_PER_PLAYER {
+        return Err(error(
+            StatusCode::CONFLICT,
+            "TOO_MANY_TOKENS",
+            "Revoke a token before issuing another",
+        ));
+    }
+
+    let (api_token, token) = ApiToken::issue(user.user_uuid, name.to_string(), scopes);
+    collection
+        .insert_one(&api_token, None)
+        .await
+        .map_err(|e| database_error(&e))?;
+
+    tracing::info!(
+        "Player {} issued API token {}",
+        user.user_uuid,
+        api_token.uuid
+    );
+    Ok((
+        StatusCode::CREATED,
+        Json(IssuedApiTokenResponse {
+            api_token: ApiTokenResponse::from(&api_token),
+            token,
+        }),
+    ))
+}
+
+/// List the authenticated player's API tokens, newest first
+#[utoipa::path(
+    get,
+    path = "/api/v1/api-tokens",
+    responses(
+        (status = 200, description = "Issued tokens, without the tokens themselves", body = Vec<ApiTokenResponse>),
+        (status = 401, description = "Not authenticated"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Listing API tokens", skip(database, user))]
+pub async fn list_api_tokens(
+    State(database): State<Database>,
+    Extension(user): Extension<UserContext>,
+) -> Result<Json<Vec<ApiTokenResponse>>, ApiError> {
+    let options = FindOptions::builder()
+        .sort(doc! { "created_at": -1 })
+        .build();
+    let api_tokens: Vec<ApiToken> = database
+        .collection::<ApiToken>(API_TOKENS)
+        .find(doc! { "owner_uuid": user.user_uuid.to_string() }, options)
+        .await
+        .map_err(|e| database_error(&e))?
+        .try_collect()
+        .await
+        .map_err(|e| database_error(&e))?;
+    Ok(Json(
+        api_tokens.iter().map(ApiTokenResponse::from).collect(),
+    ))
+}
+
+/// Revoke one of the authenticated player's API tokens
+#[utoipa::path(
+    delete,
+    path = "/api/v1/api-tokens/{token_uuid}",
+    params(("token_uuid" = String, Path, description = "Token UUID")),
+    responses(
+        (status = 204, description = "Token revoked"),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "No such unrevoked token", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Revoking API token", skip(database, user))]
+pub async fn revoke_api_token(
+    State(database): State<Database>,
+    Extension(user): Extension<UserContext>,
+    Path(token_uuid): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    let token_uuid = Uuid::parse_str(&token_uuid).map_err(|_| {
+        error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_UUID",
+            "Invalid UUID format",
+        )
+    })?;
+    let result = database
+        .collection::<ApiToken>(API_TOKENS)
+        .update_one(
+            doc! {
+                "uuid": token_uuid.to_string(),
+                "owner_uuid": user.user_uuid.to_string(),
+                "revoked_at": null,
+            },
+            doc! { "$set": { "revoked_at": BsonDateTime::now() } },
+            None,
+        )
+        .await
+        .map_err(|e| database_error(&e))?;
+    if result.modified_count == 0 {
+        return Err(error(
+            StatusCode::NOT_FOUND,
+            "TOKEN_NOT_FOUND",
+            "No such unrevoked token",
+        ));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,103 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::Json,
+    routing::get,
+    Router,
+};
+use mongodb::Database;
+use serde::Deserialize;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use crate::routes::races::ErrorResponse;
+use crate::services::audit::{find_audit_entries, AuditEntry};
+
+const DEFAULT_AUDIT_LIMIT: i64 = 100;
+const MAX_AUDIT_LIMIT: i64 = 1000;
+
+/// Which audit entries to list; at least one of the UUIDs is required
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AuditQuery {
+    /// Operations on this race
+    pub race_uuid: Option<String>,
+    /// Operations on this player or made by them
+    pub player_uuid: Option<String>,
+    /// Most entries returned, 100 by default and at most 1000
+    pub limit: Option<i64>,
+}
+
+/// Routes for administrators; mounted behind authentication and the admin role
+pub fn admin_routes() -> Router<Database> {
+    Router::new().route("/audit", get(get_audit_log))
+}
+
+/// Query the audit log by race or player
+///
+/// Lists mutating operations newest first, with who made them, when, and the
+/// fields they changed.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/audit",
+    params(AuditQuery),
+    responses(
+        (status = 200, description = "Matching audit entries", body = Vec<AuditEntry>),
+        (status = 400, description = "Invalid or missing UUID, or invalid limit", body = ErrorResponse),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Not an administrator"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "admin"
+)]
+#[tracing::instrument(name = "Querying the audit log", skip(database))]
+pub async fn get_audit_log(
+    State(database): State<Database>,
+    Query(params): Query<AuditQuery>,
+) -> Result<Json<Vec<AuditEntry>>, (StatusCode, Json<ErrorResponse>)> {
+    let bad_request = |error: &str, message: &str| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: error.to_string(),
+                message: message.to_string(),
+                details: None,
+            }),
+        )
+    };
+    let parse = |uuid: Option<&String>| uuid.map(|uuid| Uuid::parse_str(uuid)).transpose();
+
+    let (Ok(race_uuid), Ok(player_uuid)) = (
+        parse(params.race_uuid.as_ref()),
+        parse(params.player_uuid.as_ref()),
+    ) else {
+        return Err(bad_request("INVALID_UUID", "Invalid UUID format"));
+    };
+    if race_uuid.is_none() && player_uuid.is_none() {
+        return Err(bad_request(
+            "MISSING_FILTER",
+            "race_uuid or player_uuid is required",
+        ));
+    }
+    let limit = params.limit.unwrap_or(DEFAULT_AUDIT_LIMIT);
+    if !(1..=MAX_AUDIT_LIMIT).contains(&limit) {
+        return Err(bad_request(
+            "INVALID_LIMIT",
+            "limit must be between 1 and 1000",
+        ));
+    }
+
+    match find_audit_entries(&database, race_uuid, player_uuid, limit).await {
+        Ok(entries) => Ok(Json(entries)),
+        Err(e) => {
+            tracing::error!("Failed to query the audit log: {:?}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: "DATABASE_ERROR".to_string(),
+                    message: "Internal server error".to_string(),
+                    details: None,
+                }),
+            ))
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use axum::{
-    extract::{Json, State},
+    extract::{ConnectInfo, Json, State},
     http::{header::SET_COOKIE, HeaderMap, StatusCode},
     response::Json as ResponseJson,
     routing::post,
@@ -8,151 +8,23 @@ use axum::{
 use axum_extra::extract::cookie::{Cookie, SameSite};
 use mongodb::bson::doc;
 use serde_json::{json, Value};
+use std::net::SocketAddr;
 use time::Duration as TimeDuration;
 use uuid;
 
 use crate::app_state::AppState;
-use crate::domain::{
-    Body, BodyName, Car, CarName, ComponentRarity, Email, Engine, EngineName, Password, Pilot,
-    PilotClass, PilotName, PilotRarity, PilotSkills, Player, TeamName, UserCredentials,
-    UserRegistration,
-};
-use crate::repositories::{
-    MockPlayerRepository, MockRaceRepository, MockSessionRepository, PlayerRepository,
-};
+use crate::domain::{Email, Password, Player, TeamName, UserCredentials, UserRegistration};
+use crate::repositories::{InMemorySessionRepository, PlayerRepository, RaceRepository};
 use crate::services::session::SessionMetadata;
+use crate::services::starter_pack::StarterPack;
 
-pub fn routes() -> Router<AppState<MockPlayerRepository, MockRaceRepository, MockSessionRepository>>
-{
+pub fn routes<P: PlayerRepository + 'static, R: RaceRepository + 'static>(
+) -> Router<AppState<P, R, InMemorySessionRepository>> {
     Router::new()
-        .route("/auth/register", post(register_user))
-        .route("/auth/login", post(login_user))
-        .route("/auth/logout", post(logout_user))
-        .route("/auth/refresh", post(refresh_token))
-}
-
-// Helper function to create starter assets for new players
-#[allow(clippy::type_complexity)]
-fn create_starter_assets() -> Result<(Vec<Car>, Vec<Pilot>, Vec<Engine>, Vec<Body>), String> {
-    // Create 6 pilots with different classes and rarities
-    let pilot1 = Pilot::new(
-        PilotName::parse("Speedster Ace").unwrap(),
-        PilotClass::Speedster,
-        PilotRarity::Rookie,
-        PilotSkills::new(7, 5, 6, 4).unwrap(),
-        crate::domain::PilotPerformance::new(8, 5).unwrap(),
-        None,
-    )
-    .map_err(|e| format!("Failed to create pilot 1: {e}"))?;
-
-    let pilot2 = Pilot::new(
-        PilotName::parse("Tech Master").unwrap(),
-        PilotClass::Technician,
-        PilotRarity::Rookie,
-        PilotSkills::new(5, 8, 7, 5).unwrap(),
-        crate::domain::PilotPerformance::new(5, 8).unwrap(),
-        None,
-    )
-    .map_err(|e| format!("Failed to create pilot 2: {e}"))?;
-
-    let pilot3 = Pilot::new(
-        PilotName::parse("Endurance Pro").unwrap(),
-        PilotClass::Endurance,
-        PilotRarity::Rookie,
-        PilotSkills::new(4, 6, 8, 9).unwrap(),
-        crate::domain::PilotPerformance::new(6, 7).unwrap(),
-        None,
-    )
-    .map_err(|e| format!("Failed to create pilot 3: {e}"))?;
-
-    let pilot4 = Pilot::new(
-        PilotName::parse("All-Round Rookie").unwrap(),
-        PilotClass::AllRounder,
-        PilotRarity::Rookie,
-        PilotSkills::new(6, 6, 6, 6).unwrap(),
-        crate::domain::PilotPerformance::new(6, 6).unwrap(),
-        None,
-    )
-    .map_err(|e| format!("Failed to create pilot 4: {e}"))?;
-
-    let pilot5 = Pilot::new(
-        PilotName::parse("Speed Demon").unwrap(),
-        PilotClass::Speedster,
-        PilotRarity::Professional,
-        PilotSkills::new(8, 4, 5, 3).unwrap(),
-        crate::domain::PilotPerformance::new(9, 4).unwrap(),
-        None,
-    )
-    .map_err(|e| format!("Failed to create pilot 5: {e}"))?;
-
-    let pilot6 = Pilot::new(
-        PilotName::parse("Precision Driver").unwrap(),
-        PilotClass::Technician,
-        PilotRarity::Professional,
-        PilotSkills::new(4, 9, 8, 6).unwrap(),
-        crate::domain::PilotPerformance::new(4, 9).unwrap(),
-        None,
-    )
-    .map_err(|e| format!("Failed to create pilot 6: {e}"))?;
-
-    // Create 2 starter cars
-    let mut car1 = Car::new(CarName::parse("Car 1").unwrap(), None)
-        .map_err(|e| format!("Failed to create starter car 1: {e}"))?;
-
-    let mut car2 = Car::new(CarName::parse("Car 2").unwrap(), None)
-        .map_err(|e| format!("Failed to create starter car 2: {e}"))?;
-
-    // Assign pilots to cars (3 pilots per car)
-    car1.assign_pilots(vec![pilot1.uuid, pilot2.uuid, pilot3.uuid])
-        .map_err(|e| format!("Failed to assign pilots to car 1: {e}"))?;
-
-    car2.assign_pilots(vec![pilot4.uuid, pilot5.uuid, pilot6.uuid])
-        .map_err(|e| format!("Failed to assign pilots to car 2: {e}"))?;
-
-    // Create 2 starter engines with different characteristics
-    let engine1 = Engine::new(
-        EngineName::parse("Basic Engine 1").unwrap(),
-        ComponentRarity::Common,
-        7, // straight_value - good for straights (0-10 range)
-        5, // curve_value
-        None,
-    )
-    .map_err(|e| format!("Failed to create starter engine 1: {e}"))?;
-
-    let engine2 = Engine::new(
-        EngineName::parse("Basic Engine 2").unwrap(),
-        ComponentRarity::Common,
-        5, // straight_value
-        7, // curve_value - good for curves
-        None,
-    )
-    .map_err(|e| format!("Failed to create starter engine 2: {e}"))?;
-
-    // Create 2 starter bodies with different characteristics
-    let body1 = Body::new(
-        BodyName::parse("Basic Body 1").unwrap(),
-        ComponentRarity::Common,
-        5, // straight_value (0-10 range)
-        7, // curve_value - good for curves
-        None,
-    )
-    .map_err(|e| format!("Failed to create starter body 1: {e}"))?;
-
-    let body2 = Body::new(
-        BodyName::parse("Basic Body 2").unwrap(),
-        ComponentRarity::Common,
-        7, // straight_value - good for straights
-        5, // curve_value
-        None,
-    )
-    .map_err(|e| format!("Failed to create starter body 2: {e}"))?;
-
-    Ok((
-        vec![car1, car2],
-        vec![pilot1, pilot2, pilot3, pilot4, pilot5, pilot6],
-        vec![engine1, engine2],
-        vec![body1, body2],
-    ))
+        .route("/auth/register", post(register_user::<P, R>))
+        .route("/auth/login", post(login_user::<P, R>))
+        .route("/auth/logout", post(logout_user::<P, R>))
+        .route("/auth/refresh", post(refresh_token::<P, R>))
 }
 
 /// Register a new user
@@ -168,11 +40,8 @@ fn create_starter_assets() -> Result<(Vec<Car>, Vec<Pilot>, Vec<Engine>, Vec<Bod
     ),
     tag = "Authentication"
 )]
-#[allow(clippy::cast_possible_wrap)]
-pub async fn register_user(
-    State(app_state): State<
-        AppState<MockPlayerRepository, MockRaceRepository, MockSessionRepository>,
-    >,
+pub async fn register_user<P: PlayerRepository + 'static, R: RaceRepository + 'static>(
+    State(app_state): State<AppState<P, R, InMemorySessionRepository>>,
     headers: HeaderMap,
     Json(registration): Json<UserRegistration>,
 ) -> Result<
@@ -224,26 +93,16 @@ pub async fn register_user(
         }
     }
 
-    // Create starter assets for new player (2 cars with 3 pilots each, engines, and bodies)
-    let (starter_cars, starter_pilots, starter_engines, starter_bodies) = create_starter_assets()
-        .map_err(|e| {
+    // Grant the starter pack: 2 ready-to-race cars with 3 pilots, an engine and a body each
+    let starter_pack = StarterPack::new().map_err(|e| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
             ResponseJson(json!({"error": e})),
         )
     })?;
-
-    // Create new player with starter assets including 6 pilots assigned to 2 cars
-    let player = Player::new_with_assets(
-        email,
-        password_hash,
-        team_name,
-        starter_cars,
-        starter_pilots,
-        starter_engines,
-        starter_bodies,
-    )
-    .map_err(|e| (StatusCode::BAD_REQUEST, ResponseJson(json!({"error": e}))))?;
+    let player = starter_pack
+        .grant(email, password_hash, team_name)
+        .map_err(|e| (StatusCode::BAD_REQUEST, ResponseJson(json!({"error": e}))))?;
 
     // Insert into database
     let created_player = app_state
@@ -258,81 +117,15 @@ pub async fn register_user(
             )
         })?;
 
-    // Generate JWT tokens
-    let token_pair = app_state
-        .jwt_service
-        .generate_token_pair(&created_player)
-        .map_err(|e| {
-            tracing::error!("Failed to generate tokens: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                ResponseJson(json!({"error": "Failed to generate authentication tokens"})),
-            )
-        })?;
-
-    // Extract token ID from access token for session management
-    let access_claims = app_state
-        .jwt_service
-        .validate_token(&token_pair.access_token)
-        .map_err(|e| {
-            tracing::error!("Failed to validate generated token: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                ResponseJson(json!({"error": "Token generation error"})),
-            )
-        })?;
-
-    // Create session metadata
-    let session_metadata = SessionMetadata {
-        ip_address: headers
-            .get("x-forwarded-for")
-            .or_else(|| headers.get("x-real-ip"))
-            .and_then(|h| h.to_str().ok())
-            .map(std::string::ToString::to_string),
-        user_agent: headers
-            .get("user-agent")
-            .and_then(|h| h.to_str().ok())
-            .map(std::string::ToString::to_string),
-    };
-
-    // Create session
-    app_state
-        .session_manager
-        .create_session(player.uuid, access_claims.jti.clone(), session_metadata)
-        .await
-        .map_err(|e| {
-            tracing::error!("Failed to create session: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                ResponseJson(json!({"error": "Session creation failed"})),
-            )
-        })?;
-
-    // Create secure cookies
-    let access_cookie = Cookie::build(("access_token", token_pair.access_token))
-        .http_only(true)
-        .secure(false) // TODO: Make this configurable for development
-        .same_site(SameSite::Strict)
-        .max_age(TimeDuration::seconds(token_pair.expires_in as i64))
-        .path("/")
-        .build();
-
-    let refresh_cookie = Cookie::build(("refresh_token", token_pair.refresh_token))
-        .http_only(true)
-        .secure(false) // TODO: Make this configurable for development
-        .same_site(SameSite::Strict)
-        .max_age(TimeDuration::seconds(30 * 24 * 60 * 60)) // 30 days
-        .path("/auth/refresh")
-        .build();
+    // Open the session and build the cookies carrying its tokens
+    let session_metadata = SessionMetadata::from_headers(&headers);
+    let cookies = start_session(&app_state, &created_player, session_metadata).await?;
 
     tracing::info!("User registered successfully: {}", player.uuid);
 
     Ok((
         StatusCode::CREATED,
-        [
-            (SET_COOKIE.to_string(), access_cookie.to_string()),
-            (SET_COOKIE.to_string(), refresh_cookie.to_string()),
-        ],
+        cookies,
         ResponseJson(json!({
             "message": "User registered successfully",
             "user": {
@@ -354,15 +147,14 @@ pub async fn register_user(
         (status = 200, description = "Login successful", body = Value),
         (status = 400, description = "Invalid input data", body = Value),
         (status = 401, description = "Invalid credentials", body = Value),
+        (status = 429, description = "Too many failed logins for the account or client; retry after `retry_after_seconds`", body = Value),
         (status = 500, description = "Internal server error", body = Value)
     ),
     tag = "Authentication"
 )]
-#[allow(clippy::cast_possible_wrap)]
-pub async fn login_user(
-    State(app_state): State<
-        AppState<MockPlayerRepository, MockRaceRepository, MockSessionRepository>,
-    >,
+pub async fn login_user<P: PlayerRepository + 'static, R: RaceRepository + 'static>(
+    State(app_state): State<AppState<P, R, InMemorySessionRepository>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
     headers: HeaderMap,
     Json(credentials): Json<UserCredentials>,
 ) -> Result<
@@ -377,6 +169,30 @@ pub async fn login_user(
     let password = Password::new(credentials.password)
         .map_err(|e| (StatusCode::BAD_REQUEST, ResponseJson(json!({"error": e}))))?;
 
+    // Refuse logins to a locked account or from a locked client
+    let session_metadata = SessionMetadata::from_headers(&headers);
+    let client_ip = app_state
+        .login_throttle
+        .client_ip(peer.ip(), &headers)
+        .to_string();
+    let client_ip = Some(client_ip.as_str());
+    if let Some(retry_after) = app_state
+        .login_throttle
+        .locked_for(email.as_ref(), client_ip)
+    {
+        return Err(too_many_attempts(retry_after));
+    }
+    let failed_login = || match app_state
+        .login_throttle
+        .record_failure(email.as_ref(), client_ip)
+    {
+        Some(retry_after) => too_many_attempts(retry_after),
+        None => (
+            StatusCode::UNAUTHORIZED,
+            ResponseJson(json!({"error": "Invalid credentials"})),
+        ),
+    };
+
     // Find user by email
     let user = app_state
         .player_repository
@@ -390,12 +206,7 @@ pub async fn login_user(
             )
         })?;
 
-    let user = user.ok_or_else(|| {
-        (
-            StatusCode::UNAUTHORIZED,
-            ResponseJson(json!({"error": "Invalid credentials"})),
-        )
-    })?;
+    let user = user.ok_or_else(failed_login)?;
 
     // Verify password
     let is_valid = user.verify_password(&password).map_err(|e| {
@@ -407,87 +218,17 @@ pub async fn login_user(
     })?;
 
     if !is_valid {
-        return Err((
-            StatusCode::UNAUTHORIZED,
-            ResponseJson(json!({"error": "Invalid credentials"})),
-        ));
+        return Err(failed_login());
     }
+    app_state.login_throttle.record_success(email.as_ref());
 
-    // Generate JWT tokens
-    let token_pair = app_state
-        .jwt_service
-        .generate_token_pair(&user)
-        .map_err(|e| {
-            tracing::error!("Failed to generate tokens: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                ResponseJson(json!({"error": "Failed to generate authentication tokens"})),
-            )
-        })?;
-
-    // Extract token ID from access token for session management
-    let access_claims = app_state
-        .jwt_service
-        .validate_token(&token_pair.access_token)
-        .map_err(|e| {
-            tracing::error!("Failed to validate generated token: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                ResponseJson(json!({"error": "Token generation error"})),
-            )
-        })?;
-
-    // Create session metadata
-    let session_metadata = SessionMetadata {
-        ip_address: headers
-            .get("x-forwarded-for")
-            .or_else(|| headers.get("x-real-ip"))
-            .and_then(|h| h.to_str().ok())
-            .map(std::string::ToString::to_string),
-        user_agent: headers
-            .get("user-agent")
-            .and_then(|h| h.to_str().ok())
-            .map(std::string::ToString::to_string),
-    };
-
-    // Create session
-    app_state
-        .session_manager
-        .create_session(user.uuid, access_claims.jti.clone(), session_metadata)
-        .await
-        .map_err(|e| {
-            tracing::error!("Failed to create session: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                ResponseJson(json!({"error": "Session creation failed"})),
-            )
-        })?;
-
-    // Create secure cookies
-    let access_cookie = Cookie::build(("access_token", token_pair.access_token))
-        .http_only(true)
-        .secure(false) // TODO: Make this configurable for development
-        .same_site(SameSite::Strict)
-        .max_age(TimeDuration::seconds(token_pair.expires_in as i64))
-        .path("/")
-        .build();
-
-    let refresh_cookie = Cookie::build(("refresh_token", token_pair.refresh_token))
-        .http_only(true)
-        .secure(false) // TODO: Make this configurable for development
-        .same_site(SameSite::Strict)
-        .max_age(TimeDuration::seconds(30 * 24 * 60 * 60)) // 30 days
-        .path("/auth/refresh")
-        .build();
+    let cookies = start_session(&app_state, &user, session_metadata).await?;
 
     tracing::info!("User logged in successfully: {}", user.uuid);
 
     Ok((
         StatusCode::OK,
-        [
-            (SET_COOKIE.to_string(), access_cookie.to_string()),
-            (SET_COOKIE.to_string(), refresh_cookie.to_string()),
-        ],
+        cookies,
         ResponseJson(json!({
             "message": "Login successful",
             "user": {
@@ -511,10 +252,8 @@ pub async fn login_user(
     ),
     tag = "Authentication"
 )]
-pub async fn logout_user(
-    State(app_state): State<
-        AppState<MockPlayerRepository, MockRaceRepository, MockSessionRepository>,
-    >,
+pub async fn logout_user<P: PlayerRepository + 'static, R: RaceRepository + 'static>(
+    State(app_state): State<AppState<P, R, InMemorySessionRepository>>,
     headers: HeaderMap,
 ) -> Result<
     (StatusCode, [(String, String); 2], ResponseJson<Value>),
@@ -575,10 +314,8 @@ pub async fn logout_user(
     ),
     tag = "Authentication"
 )]
-pub async fn refresh_token(
-    State(app_state): State<
-        AppState<MockPlayerRepository, MockRaceRepository, MockSessionRepository>,
-    >,
+pub async fn refresh_token<P: PlayerRepository + 'static, R: RaceRepository + 'static>(
+    State(app_state): State<AppState<P, R, InMemorySessionRepository>>,
     headers: HeaderMap,
 ) -> Result<
     (StatusCode, [(String, String); 1], ResponseJson<Value>),
@@ -681,17 +418,7 @@ pub async fn refresh_token(
             )
         })?;
 
-    let session_metadata = SessionMetadata {
-        ip_address: headers
-            .get("x-forwarded-for")
-            .or_else(|| headers.get("x-real-ip"))
-            .and_then(|h| h.to_str().ok())
-            .map(std::string::ToString::to_string),
-        user_agent: headers
-            .get("user-agent")
-            .and_then(|h| h.to_str().ok())
-            .map(std::string::ToString::to_string),
-    };
+    let session_metadata = SessionMetadata::from_headers(&headers);
 
     if let Err(e) = app_state
         .session_manager
@@ -721,6 +448,87 @@ pub async fn refresh_token(
 }
 
 // Helper functions
+/// Issue a token pair for `player`, open its session and build the cookies
+/// carrying the tokens
+#[allow(clippy::cast_possible_wrap)]
+pub(crate) async fn start_session<P: PlayerRepository, R: RaceRepository>(
+    app_state: &AppState<P, R, InMemorySessionRepository>,
+    player: &Player,
+    session_metadata: SessionMetadata,
+) -> Result<[(String, String); 2], (StatusCode, ResponseJson<Value>)> {
+    // Generate JWT tokens
+    let token_pair = app_state
+        .jwt_service
+        .generate_token_pair(player)
+        .map_err(|e| {
+            tracing::error!("Failed to generate tokens: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ResponseJson(json!({"error": "Failed to generate authentication tokens"})),
+            )
+        })?;
+
+    // Extract token ID from access token for session management
+    let access_claims = app_state
+        .jwt_service
+        .validate_token(&token_pair.access_token)
+        .map_err(|e| {
+            tracing::error!("Failed to validate generated token: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ResponseJson(json!({"error": "Token generation error"})),
+            )
+        })?;
+
+    // Create session
+    app_state
+        .session_manager
+        .create_session(player.uuid, access_claims.jti.clone(), session_metadata)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to create session: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ResponseJson(json!({"error": "Session creation failed"})),
+            )
+        })?;
+
+    // Create secure cookies
+    let access_cookie = Cookie::build(("access_token", token_pair.access_token))
+        .http_only(true)
+        .secure(false) // TODO: Make this configurable for development
+        .same_site(SameSite::Strict)
+        .max_age(TimeDuration::seconds(token_pair.expires_in as i64))
+        .path("/")
+        .build();
+
+    let refresh_cookie = Cookie::build(("refresh_token", token_pair.refresh_token))
+        .http_only(true)
+        .secure(false) // TODO: Make this configurable for development
+        .same_site(SameSite::Strict)
+        .max_age(TimeDuration::seconds(30 * 24 * 60 * 60)) // 30 days
+        .path("/auth/refresh")
+        .build();
+
+    Ok([
+        (SET_COOKIE.to_string(), access_cookie.to_string()),
+        (SET_COOKIE.to_string(), refresh_cookie.to_string()),
+    ])
+}
+
+/// 429 telling the client when it may try logging in again
+fn too_many_attempts(retry_after: std::time::Duration) -> (StatusCode, ResponseJson<Value>) {
+    let seconds = (retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0)).max(1);
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        ResponseJson(json!({
+            "error": "too_many_attempts",
+            "message": "Too many failed logins, try again later",
+            "retry_after_seconds": seconds
+        })),
+    )
+}
+
 fn extract_token_from_headers(headers: &HeaderMap) -> Option<String> {
     // Try Authorization header first
     if let Some(auth_header) = headers.get("authorization") {
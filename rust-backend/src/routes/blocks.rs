@@ -0,0 +1,278 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+    routing::{get, put},
+    Router,
+};
+use mongodb::{
+    bson::{doc, to_bson},
+    options::ReplaceOptions,
+    Database,
+};
+use serde::Deserialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::{BlockKind, PlayerBlock};
+use crate::repositories::{FriendshipRepository, MongoFriendshipRepository};
+use crate::routes::players::get_player_by_uuid_from_db;
+use crate::routes::races::ErrorResponse;
+
+const PLAYER_BLOCKS: &str = "player_blocks";
+
+type ApiError = (StatusCode, Json<ErrorResponse>);
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BlockPlayerRequest {
+    pub kind: BlockKind,
+}
+
+/// Block routes act for the player in the path; mounted behind
+/// `AuthMiddleware` and `RequireOwnership::player("player_uuid")`
+pub fn routes() -> Router<Database> {
+    Router::new()
+        .route("/players/:player_uuid/blocks", get(list_blocks))
+        .route(
+            "/players/:player_uuid/blocks/:blocked_uuid",
+            put(block_player).delete(unblock_player),
+        )
+}
+
+fn error(status: StatusCode, error: &str, message: &str) -> ApiError {
+    (
+        status,
+        Json(ErrorResponse {
+            error: error.to_string(),
+            message: message.to_string(),
+            details: None,
+        }),
+    )
+}
+
+fn database_error(e: &mongodb::error::Error) -> ApiError {
+    tracing::error!("Block storage failed: {:?}", e);
+    error(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "DATABASE_ERROR",
+        "Internal server error",
+    )
+}
+
+fn parse_uuid(value: &str) -> Result<Uuid, ApiError> {
+    Uuid::parse_str(value).map_err(|_| {
+        error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_UUID",
+            "Invalid UUID format",
+        )
+    })
+}
+
+/// List the players a player muted or blocked
+#[utoipa::path(
+    get,
+    path = "/api/v1/players/{player_uuid}/blocks",
+    params(
+        ("player_uuid" = String, Path, description = "Player's UUID")
+    ),
+    responses(
+        (status = 200, description = "Muted and blocked players", body = Vec<PlayerBlock>),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse),
+        (status = 401, description = "Authentication required"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Listing blocked players", skip(database))]
+pub async fn list_blocks(
+    State(database): State<Database>,
+    Path(player_uuid): Path<String>,
+) -> Result<Json<Vec<PlayerBlock>>, ApiError> {
+    let player_uuid = parse_uuid(&player_uuid)?;
+    let blocks = get_blocks_involving_from_db(&database, player_uuid)
+        .await
+        .map_err(|e| database_error(&e))?;
+    Ok(Json(
+        blocks
+            .into_iter()
+            .filter(|block| block.blocker_uuid == player_uuid)
+            .collect(),
+    ))
+}
+
+/// Mute or block another player
+///
+/// A muted player's chat messages are hidden from the player. A block also
+/// hides the player's own messages from them, stops either inviting the
+/// other to races and ends any friendship between them. Chat connections
+/// already open pick the change up when they reconnect.
+#[utoipa::path(
+    put,
+    path = "/api/v1/players/{player_uuid}/blocks/{blocked_uuid}",
+    params(
+        ("player_uuid" = String, Path, description = "Player blocking"),
+        ("blocked_uuid" = String, Path, description = "Player being muted or blocked")
+    ),
+    request_body = BlockPlayerRequest,
+    responses(
+        (status = 200, description = "Player muted or blocked", body = PlayerBlock),
+        (status = 400, description = "Invalid UUID format or own UUID", body = ErrorResponse),
+        (status = 401, description = "Authentication required"),
+        (status = 404, description = "Player not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Blocking player", skip(database, request))]
+pub async fn block_player(
+    State(database): State<Database>,
+    Path((player_uuid, blocked_uuid)): Path<(String, String)>,
+    Json(request): Json<BlockPlayerRequest>,
+) -> Result<Json<PlayerBlock>, ApiError> {
+    let player_uuid = parse_uuid(&player_uuid)?;
+    let blocked_uuid = parse_uuid(&blocked_uuid)?;
+    if player_uuid == blocked_uuid {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "CANNOT_BLOCK_SELF",
+            "Players cannot block themselves",
+        ));
+    }
+    match get_player_by_uuid_from_db(&database, blocked_uuid).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return Err(error(
+                StatusCode::NOT_FOUND,
+                "PLAYER_NOT_FOUND",
+                "Player not found",
+            ))
+        }
+        Err(e) => return Err(database_error(&e)),
+    }
+
+    let block = PlayerBlock::new(player_uuid, blocked_uuid, request.kind);
+    save_block_in_db(&database, &block)
+        .await
+        .map_err(|e| database_error(&e))?;
+    if block.kind == BlockKind::Block {
+        if let Err(e) = MongoFriendshipRepository::new(&database)
+            .delete_between(player_uuid, blocked_uuid)
+            .await
+        {
+            tracing::warn!("Friendship with blocked player not removed: {:?}", e);
+        }
+    }
+    Ok(Json(block))
+}
+
+/// Unmute or unblock a player
+#[utoipa::path(
+    delete,
+    path = "/api/v1/players/{player_uuid}/blocks/{blocked_uuid}",
+    params(
+        ("player_uuid" = String, Path, description = "Player who blocked"),
+        ("blocked_uuid" = String, Path, description = "Player to unblock")
+    ),
+    responses(
+        (status = 204, description = "Player unblocked"),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse),
+        (status = 401, description = "Authentication required"),
+        (status = 404, description = "Player was not muted or blocked", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Unblocking player", skip(database))]
+pub async fn unblock_player(
+    State(database): State<Database>,
+    Path((player_uuid, blocked_uuid)): Path<(String, String)>,
+) -> Result<StatusCode, ApiError> {
+    let player_uuid = parse_uuid(&player_uuid)?;
+    let blocked_uuid = parse_uuid(&blocked_uuid)?;
+
+    let result = database
+        .collection::<PlayerBlock>(PLAYER_BLOCKS)
+        .delete_one(
+            doc! {
+                "blocker_uuid": player_uuid.to_string(),
+                "blocked_uuid": blocked_uuid.to_string(),
+            },
+            None,
+        )
+        .await
+        .map_err(|e| database_error(&e))?;
+    if result.deleted_count == 0 {
+        return Err(error(
+            StatusCode::NOT_FOUND,
+            "BLOCK_NOT_FOUND",
+            "The player was not muted or blocked",
+        ));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Mutes and blocks made by or against the player
+#[tracing::instrument(name = "Getting blocks of player from the database", skip(database))]
+pub async fn get_blocks_involving_from_db(
+    database: &Database,
+    player_uuid: Uuid,
+) -> Result<Vec<PlayerBlock>, mongodb::error::Error> {
+    let player_uuid = player_uuid.to_string();
+    let filter = doc! {
+        "$or": [
+            { "blocker_uuid": &player_uuid },
+            { "blocked_uuid": &player_uuid },
+        ]
+    };
+    let mut cursor = database
+        .collection::<PlayerBlock>(PLAYER_BLOCKS)
+        .find(filter, None)
+        .await?;
+    let mut blocks = Vec::new();
+    while cursor.advance().await? {
+        blocks.push(cursor.deserialize_current()?);
+    }
+    Ok(blocks)
+}
+
+/// Whether either player blocked the other; mutes do not count
+#[tracing::instrument(name = "Checking blocks between players", skip(database))]
+pub async fn is_blocked_between_in_db(
+    database: &Database,
+    a: Uuid,
+    b: Uuid,
+) -> Result<bool, mongodb::error::Error> {
+    let (a, b) = (a.to_string(), b.to_string());
+    let filter = doc! {
+        "kind": to_bson(&BlockKind::Block)?,
+        "$or": [
+            { "blocker_uuid": &a, "blocked_uuid": &b },
+            { "blocker_uuid": &b, "blocked_uuid": &a },
+        ]
+    };
+    let count = database
+        .collection::<PlayerBlock>(PLAYER_BLOCKS)
+        .count_documents(filter, None)
+        .await?;
+    Ok(count > 0)
+}
+
+/// Store a block, replacing an earlier mute or block of the same player
+async fn save_block_in_db(
+    database: &Database,
+    block: &PlayerBlock,
+) -> Result<(), mongodb::error::Error> {
+    database
+        .collection::<PlayerBlock>(PLAYER_BLOCKS)
+        .replace_one(
+            doc! {
+                "blocker_uuid": block.blocker_uuid.to_string(),
+                "blocked_uuid": block.blocked_uuid.to_string(),
+            },
+            block,
+            ReplaceOptions::builder().upsert(true).build(),
+        )
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,393 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+    routing::get,
+    Router,
+};
+use mongodb::{bson::doc, Database};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::domain::performance_model::PerformanceModel;
+use crate::domain::{
+    GhostCar, LapCharacteristic, MovementProbability, PerformanceModelKind, Player, Sector,
+    MAX_CAR_VALUE_TOTAL,
+};
+use crate::engine::calculate_performance;
+use crate::routes::races::{calculate_movement_probability, ErrorResponse};
+use crate::routes::tracks::get_track_template_by_id;
+use crate::services::car_validation::{CarValidationService, ValidatedCarData};
+
+type ApiError = (StatusCode, Json<ErrorResponse>);
+
+/// Track template cars are compared on when the request names none
+const DEFAULT_COMPARISON_TEMPLATE: &str = "beginner-loop";
+
+/// Boost cards of a full hand
+const BOOST_CARDS: std::ops::RangeInclusive<u8> = 0..=4;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CarRatingResponse {
+    pub car_uuid: String,
+    /// Player owning the car
+    pub player_uuid: String,
+    /// Performance rating from 0 to 100, as used for car tiers, matchmaking
+    /// and the starting grid
+    pub rating: u32,
+    /// Sum of the engine, body and pilot values on straight laps
+    pub straight_total: u32,
+    /// Sum of the engine, body and pilot values on curve laps
+    pub curve_total: u32,
+    /// Highest possible sum of both totals
+    pub max_total: u32,
+}
+
+impl CarRatingResponse {
+    fn new(player_uuid: Uuid, car_data: &ValidatedCarData) -> Self {
+        Self {
+            car_uuid: car_data.car.uuid.to_string(),
+            player_uuid: player_uuid.to_string(),
+            rating: car_data.performance_rating(),
+            straight_total: car_data.straight_total(),
+            curve_total: car_data.curve_total(),
+            max_total: MAX_CAR_VALUE_TOTAL,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct CompareCarsQuery {
+    pub car_a: String,
+    pub car_b: String,
+    /// Track template to simulate on; `beginner-loop` when omitted
+    pub template_id: Option<String>,
+    /// Performance formula to simulate with; `Additive` when omitted
+    #[param(inline)]
+    pub performance_model: Option<PerformanceModelKind>,
+}
+
+/// Values of an engine, body or pilot
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ComponentStats {
+    pub uuid: String,
+    pub straight_value: u8,
+    pub curve_value: u8,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ComparedCar {
+    pub car_uuid: String,
+    pub player_uuid: String,
+    pub name: String,
+    /// Performance rating from 0 to 100
+    pub rating: u32,
+    pub engine: ComponentStats,
+    pub body: ComponentStats,
+    pub pilot: ComponentStats,
+}
+
+impl ComparedCar {
+    fn new(player_uuid: Uuid, car_data: &ValidatedCarData) -> Self {
+        Self {
+            car_uuid: car_data.car.uuid.to_string(),
+            player_uuid: player_uuid.to_string(),
+            name: car_data.car.name.as_ref().to_string(),
+            rating: car_data.performance_rating(),
+            engine: ComponentStats {
+                uuid: car_data.engine.uuid.to_string(),
+                straight_value: car_data.engine.straight_value,
+                curve_value: car_data.engine.curve_value,
+            },
+            body: ComponentStats {
+                uuid: car_data.body.uuid.to_string(),
+                straight_value: car_data.body.straight_value,
+                curve_value: car_data.body.curve_value,
+            },
+            pilot: ComponentStats {
+                uuid: car_data.pilot.uuid.to_string(),
+                straight_value: car_data.pilot.performance.straight_value,
+                curve_value: car_data.pilot.performance.curve_value,
+            },
+        }
+    }
+}
+
+/// Final value a car would reach with a boost card, and where it would move
+#[derive(Debug, Serialize, PartialEq, ToSchema)]
+pub struct ExpectedOutcome {
+    pub final_value: u32,
+    pub movement_probability: MovementProbability,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BoostComparison {
+    pub boost_value: u8,
+    pub car_a: ExpectedOutcome,
+    pub car_b: ExpectedOutcome,
+}
+
+/// Both cars in one sector on one kind of lap
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SectorComparison {
+    pub sector_id: u32,
+    pub sector_name: String,
+    pub lap_characteristic: LapCharacteristic,
+    pub boost_options: Vec<BoostComparison>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CarComparisonResponse {
+    pub template_id: String,
+    pub performance_model: PerformanceModelKind,
+    pub car_a: ComparedCar,
+    pub car_b: ComparedCar,
+    /// Every sector of the track, on a straight then a curve lap
+    pub sectors: Vec<SectorComparison>,
+}
+
+pub fn routes() -> Router<Database> {
+    Router::new()
+        .route("/cars/compare", get(compare_cars))
+        .route("/cars/:car_uuid/rating", get(get_car_rating))
+}
+
+fn error(status: StatusCode, error: &str, message: &str) -> ApiError {
+    (
+        status,
+        Json(ErrorResponse {
+            error: error.to_string(),
+            message: message.to_string(),
+            details: None,
+        }),
+    )
+}
+
+fn database_error(e: &mongodb::error::Error) -> ApiError {
+    tracing::error!("Car lookup failed: {:?}", e);
+    error(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "DATABASE_ERROR",
+        "Internal server error",
+    )
+}
+
+fn invalid_uuid() -> ApiError {
+    error(
+        StatusCode::BAD_REQUEST,
+        "INVALID_UUID",
+        "Invalid UUID format",
+    )
+}
+
+/// Player owning the car, with the car validated as ready to race
+pub(crate) async fn find_owned_car(
+    database: &Database,
+    car_uuid: &str,
+) -> Result<(Player, ValidatedCarData), ApiError> {
+    let car_uuid = Uuid::parse_str(car_uuid).map_err(|_| invalid_uuid())?;
+    let player = database
+        .collection::<Player>("players")
+        .find_one(doc! { "cars.uuid": car_uuid.to_string() }, None)
+        .await
+        .map_err(|e| database_error(&e))?
+        .ok_or_else(|| error(StatusCode::NOT_FOUND, "CAR_NOT_FOUND", "Car not found"))?;
+
+    let car_data = CarValidationService::validate_owned_car(&player, car_uuid).map_err(|e| {
+        (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(ErrorResponse {
+                error: e.error_code().to_string(),
+                message: e.user_message(),
+                details: e.suggested_action(),
+            }),
+        )
+    })?;
+    Ok((player, car_data))
+}
+
+/// Performance rating of a car
+///
+/// The straight and curve values of the car's engine, body and pilot are
+/// summed and scaled to 0-100, so cars, bots and ghosts compare on one scale.
+#[utoipa::path(
+    get,
+    path = "/api/v1/cars/{car_uuid}/rating",
+    params(
+        ("car_uuid" = String, Path, description = "Car UUID")
+    ),
+    responses(
+        (status = 200, description = "Car performance rating", body = CarRatingResponse),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse),
+        (status = 404, description = "Car not found", body = ErrorResponse),
+        (status = 422, description = "Car misses an engine, body or pilot", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Rating car", skip(database))]
+pub async fn get_car_rating(
+    State(database): State<Database>,
+    Path(car_uuid): Path<String>,
+) -> Result<Json<CarRatingResponse>, ApiError> {
+    let (player, car_data) = find_owned_car(&database, &car_uuid).await?;
+    Ok(Json(CarRatingResponse::new(player.uuid, &car_data)))
+}
+
+/// Outcomes of both cars in a sector for every boost card
+///
+/// Uses the performance preview math for a car without ballast, DRS or
+/// catch-up bonus, so only the cars themselves make the difference.
+fn compare_in_sector(
+    model: &dyn PerformanceModel,
+    sector: &Sector,
+    lap_characteristic: &LapCharacteristic,
+    car_a: &GhostCar,
+    car_b: &GhostCar,
+) -> Vec<BoostComparison> {
+    let outcome = |car: &GhostCar, boost_value: u8| {
+        let performance = calculate_performance(
+            model,
+            sector,
+            car.values_for(lap_characteristic),
+            u32::from(boost_value),
+            0,
+            false,
+        );
+        ExpectedOutcome {
+            final_value: performance.final_value,
+            movement_probability: calculate_movement_probability(performance.final_value, sector),
+        }
+    };
+    BOOST_CARDS
+        .map(|boost_value| BoostComparison {
+            boost_value,
+            car_a: outcome(car_a, boost_value),
+            car_b: outcome(car_b, boost_value),
+        })
+        .collect()
+}
+
+/// Compare two cars side by side
+///
+/// Returns each car's component values and, for every sector of a track
+/// template and each boost card, the final value it would reach and where it
+/// would move, as the performance preview computes it during a race.
+#[utoipa::path(
+    get,
+    path = "/api/v1/cars/compare",
+    params(CompareCarsQuery),
+    responses(
+        (status = 200, description = "Side-by-side comparison", body = CarComparisonResponse),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse),
+        (status = 404, description = "Car or track template not found", body = ErrorResponse),
+        (status = 422, description = "A car misses an engine, body or pilot", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Comparing cars", skip(database))]
+pub async fn compare_cars(
+    State(database): State<Database>,
+    Query(query): Query<CompareCarsQuery>,
+) -> Result<Json<CarComparisonResponse>, ApiError> {
+    let (player_a, car_a) = find_owned_car(&database, &query.car_a).await?;
+    let (player_b, car_b) = find_owned_car(&database, &query.car_b).await?;
+
+    let template_id = query
+        .template_id
+        .unwrap_or_else(|| DEFAULT_COMPARISON_TEMPLATE.to_string());
+    let template = get_track_template_by_id(&database, &template_id)
+        .await
+        .map_err(|e| database_error(&e))?
+        .ok_or_else(|| {
+            error(
+                StatusCode::NOT_FOUND,
+                "TEMPLATE_NOT_FOUND",
+                &format!("Track template '{template_id}' does not exist"),
+            )
+        })?;
+
+    let performance_model = query.performance_model.unwrap_or_default();
+    let (ghost_a, ghost_b) = (
+        GhostCar::from_car_data(&car_a),
+        GhostCar::from_car_data(&car_b),
+    );
+    let sectors = [LapCharacteristic::Straight, LapCharacteristic::Curve]
+        .iter()
+        .flat_map(|lap_characteristic| {
+            template.sectors.iter().map(|sector| SectorComparison {
+                sector_id: sector.id,
+                sector_name: sector.name.clone(),
+                lap_characteristic: lap_characteristic.clone(),
+                boost_options: compare_in_sector(
+                    performance_model.model(),
+                    sector,
+                    lap_characteristic,
+                    &ghost_a,
+                    &ghost_b,
+                ),
+            })
+        })
+        .collect();
+
+    Ok(Json(CarComparisonResponse {
+        template_id,
+        performance_model,
+        car_a: ComparedCar::new(player_a.uuid, &car_a),
+        car_b: ComparedCar::new(player_b.uuid, &car_b),
+        sectors,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{CarLapValues, SectorType};
+
+    #[test]
+    fn stronger_car_moves_up_with_a_smaller_card() {
+        let sector = Sector {
+            id: 1,
+            name: "Straight".to_string(),
+            min_value: 10,
+            max_value: 20,
+            slot_capacity: None,
+            sector_type: SectorType::Straight,
+            capacity_schedule: Vec::new(),
+            modifiers: None,
+        };
+        let car = |value| GhostCar {
+            straight: CarLapValues {
+                engine: value,
+                body: value,
+                pilot: value,
+            },
+            curve: CarLapValues {
+                engine: 0,
+                body: 0,
+                pilot: 0,
+            },
+        };
+
+        let options = compare_in_sector(
+            PerformanceModelKind::Additive.model(),
+            &sector,
+            &LapCharacteristic::Straight,
+            &car(6),
+            &car(5),
+        );
+
+        assert_eq!(options.len(), 5);
+        assert_eq!(options[2].car_a.final_value, 20);
+        assert_eq!(
+            options[2].car_a.movement_probability,
+            MovementProbability::MoveUp
+        );
+        assert_eq!(options[4].car_b.final_value, 19);
+        assert!(options
+            .iter()
+            .all(|o| o.car_b.movement_probability == MovementProbability::Stay));
+    }
+}
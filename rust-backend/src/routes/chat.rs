@@ -0,0 +1,233 @@
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Path, State,
+    },
+    http::StatusCode,
+    response::{Json, Response},
+    routing::get,
+    Extension, Router,
+};
+use mongodb::Database;
+use serde::Deserialize;
+use tokio::sync::broadcast::error::RecvError;
+use uuid::Uuid;
+
+use crate::domain::{PlayerBlock, RaceStatus};
+use crate::middleware::UserContext;
+use crate::routes::blocks::get_blocks_involving_from_db;
+use crate::routes::players::get_player_by_uuid_from_db;
+use crate::routes::races::{get_race_by_uuid, ErrorResponse};
+use crate::services::chat::{ChatEvent, ChatMember, ChatRoom};
+use crate::services::ChatHub;
+
+type ApiError = (StatusCode, Json<ErrorResponse>);
+
+/// Frame sent by a client to speak in the room
+#[derive(Debug, Deserialize)]
+struct ChatInput {
+    text: String,
+}
+
+/// Chat routes; mounted behind `AuthMiddleware`, which also reads the
+/// `access_token` cookie browsers send with the WebSocket upgrade
+pub fn routes() -> Router<Database> {
+    Router::new()
+        .route("/races/:race_uuid/chat", get(join_lobby_chat))
+        .route("/chat/global", get(join_global_chat))
+}
+
+fn error(status: StatusCode, error: &str, message: &str) -> ApiError {
+    (
+        status,
+        Json(ErrorResponse {
+            error: error.to_string(),
+            message: message.to_string(),
+            details: None,
+        }),
+    )
+}
+
+fn database_error(e: &mongodb::error::Error) -> ApiError {
+    tracing::error!("Chat lookup failed: {:?}", e);
+    error(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "DATABASE_ERROR",
+        "Internal server error",
+    )
+}
+
+/// Join the lobby chat of a race waiting to start
+///
+/// Upgrades to a WebSocket. The server first sends a `Presence` event listing
+/// the room members, then `Joined`, `Left` and `Message` events as they happen;
+/// clients speak by sending `{"text": "..."}` frames. The player speaking is
+/// the one the access token belongs to.
+#[utoipa::path(
+    get,
+    path = "/api/v1/races/{race_uuid}/chat",
+    params(
+        ("race_uuid" = String, Path, description = "Race UUID")
+    ),
+    responses(
+        (status = 101, description = "Switched to the WebSocket chat protocol"),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse),
+        (status = 401, description = "Authentication required"),
+        (status = 404, description = "Race or player not found", body = ErrorResponse),
+        (status = 409, description = "Race already started", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "races"
+)]
+#[tracing::instrument(name = "Joining race lobby chat", skip(database, hub, user, upgrade))]
+pub async fn join_lobby_chat(
+    State(database): State<Database>,
+    Extension(hub): Extension<ChatHub>,
+    Extension(user): Extension<UserContext>,
+    Path(race_uuid): Path<String>,
+    upgrade: WebSocketUpgrade,
+) -> Result<Response, ApiError> {
+    let Ok(race_uuid) = Uuid::parse_str(&race_uuid) else {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_UUID",
+            "Invalid UUID format",
+        ));
+    };
+    let member = chat_member(&database, user.user_uuid).await?;
+    let Some(race) = get_race_by_uuid(&database, race_uuid)
+        .await
+        .map_err(|e| database_error(&e))?
+    else {
+        return Err(error(
+            StatusCode::NOT_FOUND,
+            "RACE_NOT_FOUND",
+            "Race not found",
+        ));
+    };
+    if race.status != RaceStatus::Waiting {
+        return Err(error(
+            StatusCode::CONFLICT,
+            "RACE_NOT_WAITING",
+            "The lobby closes when the race starts",
+        ));
+    }
+
+    let hidden = hidden_players(&database, member.player_uuid).await?;
+    let room = ChatRoom::Lobby(race_uuid);
+    Ok(upgrade.on_upgrade(move |socket| run_chat(socket, hub, room, member, hidden)))
+}
+
+/// Join the global chat room, when the server has it enabled
+///
+/// Speaks the same WebSocket protocol as the race lobby chat.
+#[utoipa::path(
+    get,
+    path = "/api/v1/chat/global",
+    responses(
+        (status = 101, description = "Switched to the WebSocket chat protocol"),
+        (status = 401, description = "Authentication required"),
+        (status = 404, description = "Player not found or global room disabled", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Joining global chat", skip(database, hub, user, upgrade))]
+pub async fn join_global_chat(
+    State(database): State<Database>,
+    Extension(hub): Extension<ChatHub>,
+    Extension(user): Extension<UserContext>,
+    upgrade: WebSocketUpgrade,
+) -> Result<Response, ApiError> {
+    if !hub.global_room_enabled() {
+        return Err(error(
+            StatusCode::NOT_FOUND,
+            "CHAT_ROOM_NOT_FOUND",
+            "The global chat room is disabled",
+        ));
+    }
+    let member = chat_member(&database, user.user_uuid).await?;
+    let hidden = hidden_players(&database, member.player_uuid).await?;
+
+    Ok(upgrade.on_upgrade(move |socket| run_chat(socket, hub, ChatRoom::Global, member, hidden)))
+}
+
+/// Player joining a room, as the other members see them
+async fn chat_member(database: &Database, player_uuid: Uuid) -> Result<ChatMember, ApiError> {
+    match get_player_by_uuid_from_db(database, player_uuid).await {
+        Ok(Some(player)) => Ok(ChatMember {
+            player_uuid,
+            team_name: player.team_name.as_ref().to_string(),
+        }),
+        Ok(None) => Err(error(
+            StatusCode::NOT_FOUND,
+            "PLAYER_NOT_FOUND",
+            "Player not found",
+        )),
+        Err(e) => Err(database_error(&e)),
+    }
+}
+
+/// Players whose messages the player does not get, from their mutes and blocks
+async fn hidden_players(database: &Database, player_uuid: Uuid) -> Result<Vec<Uuid>, ApiError> {
+    let blocks = get_blocks_involving_from_db(database, player_uuid)
+        .await
+        .map_err(|e| database_error(&e))?;
+    Ok(PlayerBlock::hidden_from(player_uuid, &blocks).collect())
+}
+
+/// Relay room events to the socket and the player's messages to the room
+/// until either side closes; dropping the membership leaves the room
+async fn run_chat(
+    mut socket: WebSocket,
+    hub: ChatHub,
+    room: ChatRoom,
+    member: ChatMember,
+    hidden: Vec<Uuid>,
+) {
+    let mut membership = hub.join(room, member);
+    membership.hide(hidden);
+    let presence = ChatEvent::Presence {
+        room,
+        members: hub.members(room),
+    };
+    if send_event(&mut socket, &presence).await.is_err() {
+        return;
+    }
+
+    loop {
+        tokio::select! {
+            event = membership.recv() => match event {
+                Ok(event) => {
+                    if send_event(&mut socket, &event).await.is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(missed)) => {
+                    tracing::debug!("Chat client fell {} events behind", missed);
+                }
+                Err(RecvError::Closed) => break,
+            },
+            frame = socket.recv() => match frame {
+                Some(Ok(Message::Text(text))) => {
+                    let said = serde_json::from_str::<ChatInput>(&text)
+                        .map_err(|_| "Expected {\"text\": \"...\"}".to_string())
+                        .and_then(|input| membership.say(&input.text).map_err(|e| e.to_string()));
+                    if let Err(reason) = said {
+                        if send_event(&mut socket, &ChatEvent::Rejected { reason }).await.is_err() {
+                            break;
+                        }
+                    }
+                }
+                Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+                // Pings are answered by axum
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+async fn send_event(socket: &mut WebSocket, event: &ChatEvent) -> Result<(), axum::Error> {
+    let text = serde_json::to_string(event).map_err(axum::Error::new)?;
+    socket.send(Message::Text(text)).await
+}
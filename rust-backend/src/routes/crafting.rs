@@ -0,0 +1,304 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+    routing::post,
+    Router,
+};
+use mongodb::{
+    bson::{doc, DateTime as BsonDateTime},
+    options::{FindOneAndUpdateOptions, ReturnDocument},
+    Database,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::{
+    Body, BodyName, ComponentRarity, CraftableComponent, Engine, EngineName, FusionRecipe, Player,
+};
+use crate::routes::races::ErrorResponse;
+use crate::services::{record_audit, AuditEntry};
+
+type ApiError = (StatusCode, Json<ErrorResponse>);
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CraftRequest {
+    pub component: CraftableComponent,
+    /// The two engines or bodies consumed by the fusion
+    pub component_uuids: [String; 2],
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CraftResponse {
+    pub component: CraftableComponent,
+    /// Engine or body created by the fusion
+    pub crafted_uuid: String,
+    pub rarity: ComponentRarity,
+    pub straight_value: u8,
+    pub curve_value: u8,
+    pub consumed_uuids: Vec<String>,
+    pub credits_spent: u32,
+    pub credits_remaining: u64,
+}
+
+pub fn routes() -> Router<Database> {
+    Router::new().route("/players/:player_uuid/crafting", post(craft_component))
+}
+
+fn error(status: StatusCode, error: &str, message: &str) -> ApiError {
+    (
+        status,
+        Json(ErrorResponse {
+            error: error.to_string(),
+            message: message.to_string(),
+            details: None,
+        }),
+    )
+}
+
+fn database_error(e: &mongodb::error::Error) -> ApiError {
+    tracing::error!("Crafting failed: {:?}", e);
+    error(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "DATABASE_ERROR",
+        "Internal server error",
+    )
+}
+
+fn invalid_uuid() -> ApiError {
+    error(
+        StatusCode::BAD_REQUEST,
+        "INVALID_UUID",
+        "Invalid UUID format",
+    )
+}
+
+/// Rarity and straight and curve values of an owned engine or body, and
+/// whether a car has it fitted
+fn owned_component(
+    player: &Player,
+    component: CraftableComponent,
+    uuid: Uuid,
+) -> Option<(ComponentRarity, (u8, u8), bool)> {
+    match component {
+        CraftableComponent::Engine => player.engines.iter().find(|e| e.uuid == uuid).map(|e| {
+            let fitted = player.cars.iter().any(|car| car.engine_uuid == Some(uuid));
+            (e.rarity, (e.straight_value, e.curve_value), fitted)
+        }),
+        CraftableComponent::Body => player.bodies.iter().find(|b| b.uuid == uuid).map(|b| {
+            let fitted = player.cars.iter().any(|car| car.body_uuid == Some(uuid));
+            (b.rarity, (b.straight_value, b.curve_value), fitted)
+        }),
+    }
+}
+
+/// Recipe fusing `first` and `second`, two of the player's engines or bodies,
+/// once they are known to be fusable and affordable
+fn fusion_recipe(
+    player: &Player,
+    component: CraftableComponent,
+    first: Uuid,
+    second: Uuid,
+) -> Result<FusionRecipe, ApiError> {
+    let not_found = || {
+        error(
+            StatusCode::NOT_FOUND,
+            "COMPONENT_NOT_FOUND",
+            "Component not found among the player's",
+        )
+    };
+    let (rarity, first_values, first_fitted) =
+        owned_component(player, component, first).ok_or_else(not_found)?;
+    let (second_rarity, second_values, second_fitted) =
+        owned_component(player, component, second).ok_or_else(not_found)?;
+    if rarity != second_rarity {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "RARITY_MISMATCH",
+            "Both components must have the same rarity",
+        ));
+    }
+    if first_fitted || second_fitted {
+        return Err(error(
+            StatusCode::CONFLICT,
+            "COMPONENT_IN_USE",
+            "Remove the component from its car before fusing it",
+        ));
+    }
+    let recipe = FusionRecipe::fuse(rarity, first_values, second_values).ok_or_else(|| {
+        error(
+            StatusCode::BAD_REQUEST,
+            "MAX_RARITY",
+            "Legendary components cannot be fused",
+        )
+    })?;
+    if player.credits < u64::from(recipe.cost) {
+        return Err(error(
+            StatusCode::PAYMENT_REQUIRED,
+            "INSUFFICIENT_CREDITS",
+            "Not enough credits for this recipe",
+        ));
+    }
+    Ok(recipe)
+}
+
+/// Array field the crafted component goes into, its UUID and its document
+fn crafted_component(
+    component: CraftableComponent,
+    recipe: FusionRecipe,
+) -> (&'static str, Uuid, mongodb::bson::Bson) {
+    match component {
+        CraftableComponent::Engine => {
+            let name = EngineName::parse(&format!("{:?} Engine", recipe.rarity))
+                .expect("crafted engine names are valid");
+            let engine = Engine::new(
+                name,
+                recipe.rarity,
+                recipe.straight_value,
+                recipe.curve_value,
+                None,
+            )
+            .expect("recipe values are within component bounds");
+            let bson = mongodb::bson::to_bson(&engine).expect("engines serialize to BSON");
+            ("engines", engine.uuid, bson)
+        }
+        CraftableComponent::Body => {
+            let name = BodyName::parse(&format!("{:?} Body", recipe.rarity))
+                .expect("crafted body names are valid");
+            let body = Body::new(
+                name,
+                recipe.rarity,
+                recipe.straight_value,
+                recipe.curve_value,
+                None,
+            )
+            .expect("recipe values are within component bounds");
+            let bson = mongodb::bson::to_bson(&body).expect("bodies serialize to BSON");
+            ("bodies", body.uuid, bson)
+        }
+    }
+}
+
+/// Fuse two components into one of the next rarity
+///
+/// Consumes two unfitted engines or two unfitted bodies of the same rarity
+/// plus the recipe's credits, and adds the crafted component to the player.
+/// The consumed components are kept in the audit log.
+#[utoipa::path(
+    post,
+    path = "/api/v1/players/{player_uuid}/crafting",
+    params(
+        ("player_uuid" = String, Path, description = "Player's UUID")
+    ),
+    request_body = CraftRequest,
+    responses(
+        (status = 200, description = "Component crafted", body = CraftResponse),
+        (status = 400, description = "Invalid UUIDs, rarities that differ or nothing to fuse into", body = ErrorResponse),
+        (status = 402, description = "Not enough credits", body = ErrorResponse),
+        (status = 404, description = "Player or component not found", body = ErrorResponse),
+        (status = 409, description = "Component fitted to a car or changed meanwhile", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Crafting component", skip(database))]
+pub async fn craft_component(
+    State(database): State<Database>,
+    Path(player_uuid): Path<String>,
+    Json(payload): Json<CraftRequest>,
+) -> Result<Json<CraftResponse>, ApiError> {
+    let player_uuid = Uuid::parse_str(&player_uuid).map_err(|_| invalid_uuid())?;
+    let [first, second] = &payload.component_uuids;
+    let first = Uuid::parse_str(first).map_err(|_| invalid_uuid())?;
+    let second = Uuid::parse_str(second).map_err(|_| invalid_uuid())?;
+    if first == second {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "SAME_COMPONENT",
+            "Two different components are needed",
+        ));
+    }
+
+    let collection = database.collection::<Player>("players");
+    let player = collection
+        .find_one(doc! { "uuid": player_uuid.to_string() }, None)
+        .await
+        .map_err(|e| database_error(&e))?
+        .ok_or_else(|| {
+            error(
+                StatusCode::NOT_FOUND,
+                "PLAYER_NOT_FOUND",
+                "Player not found",
+            )
+        })?;
+
+    let recipe = fusion_recipe(&player, payload.component, first, second)?;
+    let (field, crafted_uuid, crafted) = crafted_component(payload.component, recipe);
+
+    // One pipeline update swaps the components and debits the credits, and
+    // only matches while both components and enough credits are still there
+    let consumed = vec![first.to_string(), second.to_string()];
+    let cost = i64::from(recipe.cost);
+    let filter = doc! {
+        "uuid": player_uuid.to_string(),
+        "credits": { "$gte": cost },
+        format!("{field}.uuid"): { "$all": consumed.clone() },
+    };
+    let update = vec![doc! {
+        "$set": {
+            field: {
+                "$concatArrays": [
+                    {
+                        "$filter": {
+                            "input": format!("${field}"),
+                            "cond": { "$not": [{ "$in": ["$$this.uuid", consumed.clone()] }] },
+                        }
+                    },
+                    [crafted],
+                ]
+            },
+            "credits": { "$subtract": ["$credits", cost] },
+            "updated_at": BsonDateTime::now(),
+        }
+    }];
+    let options = FindOneAndUpdateOptions::builder()
+        .return_document(ReturnDocument::After)
+        .build();
+    let updated = collection
+        .find_one_and_update(filter, update, options)
+        .await
+        .map_err(|e| database_error(&e))?
+        .ok_or_else(|| {
+            error(
+                StatusCode::CONFLICT,
+                "CRAFTING_CONFLICT",
+                "Components or credits changed meanwhile, try again",
+            )
+        })?;
+
+    let audit = AuditEntry::new("player.craft")
+        .by(player_uuid)
+        .player(player_uuid)
+        .values(Some(&player), Some(&updated))
+        .redact("password_hash");
+    record_audit(&database, audit).await;
+    tracing::info!(
+        "Player {} fused {} and {} into {}",
+        player_uuid,
+        first,
+        second,
+        crafted_uuid
+    );
+
+    Ok(Json(CraftResponse {
+        component: payload.component,
+        crafted_uuid: crafted_uuid.to_string(),
+        rarity: recipe.rarity,
+        straight_value: recipe.straight_value,
+        curve_value: recipe.curve_value,
+        consumed_uuids: consumed,
+        credits_spent: recipe.cost,
+        credits_remaining: updated.credits,
+    }))
+}
@@ -0,0 +1,749 @@
+use axum::{
+    extract::{Path, Request, State},
+    http::{header::AUTHORIZATION, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+    routing::{delete, get},
+    Extension, Router,
+};
+use futures_util::TryStreamExt;
+use mongodb::{
+    bson::{doc, DateTime as BsonDateTime},
+    options::FindOptions,
+    Database,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::configuration::DiscordSettings;
+use crate::domain::{GuildApiKey, GuildRace, Player, Race, RaceStatus};
+use crate::engine::assign_finish_positions;
+use crate::middleware::UserContext;
+use crate::routes::races::{create_race, get_race_by_uuid, CreateRaceRequest, ErrorResponse};
+use crate::services::{record_audit, AuditEntry};
+
+pub const GUILD_API_KEYS: &str = "guild_api_keys";
+pub const GUILD_RACES: &str = "guild_races";
+/// Most races listed for a guild
+const MAX_LISTED_RACES: i64 = 25;
+/// Most cars shown in text standings; Discord cuts embeds at 4096 characters
+const MAX_TEXT_STANDINGS: usize = 20;
+/// Width team names are cut to in text standings
+const NAME_WIDTH: usize = 18;
+
+type ApiError = (StatusCode, Json<ErrorResponse>);
+
+/// Guild whose key authenticated a bot request
+#[derive(Debug, Clone)]
+pub struct GuildContext {
+    pub guild_id: String,
+    pub key_uuid: Uuid,
+}
+
+/// Endpoints called by Discord bots; mounted behind [`require_guild_key`]
+pub fn routes() -> Router<Database> {
+    Router::new()
+        .route(
+            "/integrations/discord/races",
+            get(list_guild_races).post(create_guild_race),
+        )
+        .route(
+            "/integrations/discord/races/:race_uuid/join-link",
+            get(get_join_link),
+        )
+        .route(
+            "/integrations/discord/races/:race_uuid/standings",
+            get(get_guild_standings),
+        )
+}
+
+/// Guild key management; mounted under `/admin` behind the admin role
+pub fn admin_routes() -> Router<Database> {
+    Router::new()
+        .route(
+            "/integrations/discord/keys",
+            get(list_guild_keys).post(issue_guild_key),
+        )
+        .route(
+            "/integrations/discord/keys/:key_uuid",
+            delete(revoke_guild_key),
+        )
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct IssueGuildKeyRequest {
+    /// Discord guild (server) ID the key acts for
+    pub guild_id: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GuildKeyResponse {
+    pub uuid: String,
+    pub guild_id: String,
+    pub created_at: String,
+    pub revoked: bool,
+}
+
+impl From<&GuildApiKey> for GuildKeyResponse {
+    fn from(api_key: &GuildApiKey) -> Self {
+        Self {
+            uuid: api_key.uuid.to_string(),
+            guild_id: api_key.guild_id.clone(),
+            created_at: api_key
+                .created_at
+                .try_to_rfc3339_string()
+                .unwrap_or_default(),
+            revoked: api_key.revoked_at.is_some(),
+        }
+    }
+}
+
+/// Issued guild key, which is only shown once
+#[derive(Debug, Serialize, ToSchema)]
+pub struct IssuedGuildKeyResponse {
+    pub api_key: GuildKeyResponse,
+    /// Sent by the bot as `Authorization: Bearer <key>`
+    pub key: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateGuildRaceRequest {
+    /// Channel the race is announced in, kept for the bot's own use
+    #[serde(default)]
+    pub channel_id: Option<String>,
+    #[serde(flatten)]
+    pub race: CreateRaceRequest,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GuildRaceResponse {
+    pub race_uuid: String,
+    pub name: String,
+    pub status: RaceStatus,
+    pub channel_id: Option<String>,
+    pub join_url: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JoinLinkResponse {
+    pub race_uuid: String,
+    pub join_url: String,
+    /// Ready to post in a channel
+    pub message: String,
+}
+
+/// One car in the standings
+#[derive(Debug, Serialize, ToSchema, PartialEq)]
+pub struct StandingEntry {
+    pub position: u32,
+    /// Team name, or a label for bots and ghosts
+    pub name: String,
+    pub lap: u32,
+    pub sector: u32,
+    pub finished: bool,
+}
+
+/// Fields of a Discord embed
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ChatEmbed {
+    pub title: String,
+    /// The standings as a code block
+    pub description: String,
+    pub footer: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GuildStandingsResponse {
+    pub race_uuid: String,
+    pub name: String,
+    pub status: RaceStatus,
+    pub current_lap: u32,
+    pub total_laps: u32,
+    pub standings: Vec<StandingEntry>,
+    pub embed: ChatEmbed,
+}
+
+fn error(status: StatusCode, error: &str, message: &str) -> ApiError {
+    (
+        status,
+        Json(ErrorResponse {
+            error: error.to_string(),
+            message: message.to_string(),
+            details: None,
+        }),
+    )
+}
+
+fn database_error(e: &mongodb::error::Error) -> ApiError {
+    tracing::error!("Discord integration storage failed: {:?}", e);
+    error(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "DATABASE_ERROR",
+        "Internal server error",
+    )
+}
+
+fn parse_uuid(value: &str) -> Result<Uuid, ApiError> {
+    Uuid::parse_str(value).map_err(|_| {
+        error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_UUID",
+            "Invalid UUID format",
+        )
+    })
+}
+
+fn race_not_found() -> ApiError {
+    error(
+        StatusCode::NOT_FOUND,
+        "RACE_NOT_FOUND",
+        "The guild has no such race",
+    )
+}
+
+fn join_url(settings: &DiscordSettings, race_uuid: Uuid) -> String {
+    settings
+        .join_url
+        .replace("{race_uuid}", &race_uuid.to_string())
+}
+
+/// Middleware letting through requests carrying an unrevoked guild key
+///
+/// The guild is added to the request as a [`GuildContext`].
+pub async fn require_guild_key(
+    State(database): State<Database>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let key = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .map(str::trim);
+    let Some(key) = key else {
+        return error(
+            StatusCode::UNAUTHORIZED,
+            "API_KEY_REQUIRED",
+            "A guild API key is required",
+        )
+        .into_response();
+    };
+
+    let api_key = database
+        .collection::<GuildApiKey>(GUILD_API_KEYS)
+        .find_one(
+            doc! { "key_hash": GuildApiKey::hash(key), "revoked_at": null },
+            None,
+        )
+        .await;
+    match api_key {
+        Ok(Some(api_key)) => {
+            request.extensions_mut().insert(GuildContext {
+                guild_id: api_key.guild_id,
+                key_uuid: api_key.uuid,
+            });
+            next.run(request).await
+        }
+        Ok(None) => error(
+            StatusCode::UNAUTHORIZED,
+            "INVALID_API_KEY",
+            "The guild API key is invalid or revoked",
+        )
+        .into_response(),
+        Err(e) => database_error(&e).into_response(),
+    }
+}
+
+/// Issue an API key for a Discord guild's bot
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/integrations/discord/keys",
+    request_body = IssueGuildKeyRequest,
+    responses(
+        (status = 201, description = "Key issued; it is not shown again", body = IssuedGuildKeyResponse),
+        (status = 400, description = "Missing guild ID", body = ErrorResponse),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Not an admin"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "admin"
+)]
+#[tracing::instrument(name = "Issuing guild API key", skip(database, admin, request))]
+pub async fn issue_guild_key(
+    State(database): State<Database>,
+    Extension(admin): Extension<UserContext>,
+    Json(request): Json<IssueGuildKeyRequest>,
+) -> Result<(StatusCode, Json<IssuedGuildKeyResponse>), ApiError> {
+    let guild_id = request.guild_id.trim();
+    if guild_id.is_empty() {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_GUILD_ID",
+            "A guild ID is required",
+        ));
+    }
+
+    let (api_key, key) = GuildApiKey::issue(guild_id.to_string(), admin.user_uuid);
+    database
+        .collection::<GuildApiKey>(GUILD_API_KEYS)
+        .insert_one(&api_key, None)
+        .await
+        .map_err(|e| database_error(&e))?;
+
+    let audit = AuditEntry::new("guild_key.issue")
+        .by(admin.user_uuid)
+        .values(None, Some(&api_key))
+        .redact("key_hash");
+    record_audit(&database, audit).await;
+    Ok((
+        StatusCode::CREATED,
+        Json(IssuedGuildKeyResponse {
+            api_key: GuildKeyResponse::from(&api_key),
+            key,
+        }),
+    ))
+}
+
+/// List the guild API keys, newest first
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/integrations/discord/keys",
+    responses(
+        (status = 200, description = "Issued keys, without the keys themselves", body = Vec<GuildKeyResponse>),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Not an admin"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "admin"
+)]
+#[tracing::instrument(name = "Listing guild API keys", skip(database))]
+pub async fn list_guild_keys(
+    State(database): State<Database>,
+) -> Result<Json<Vec<GuildKeyResponse>>, ApiError> {
+    let options = FindOptions::builder()
+        .sort(doc! { "created_at": -1 })
+        .build();
+    let keys: Vec<GuildApiKey> = database
+        .collection::<GuildApiKey>(GUILD_API_KEYS)
+        .find(doc! {}, options)
+        .await
+        .map_err(|e| database_error(&e))?
+        .try_collect()
+        .await
+        .map_err(|e| database_error(&e))?;
+    Ok(Json(keys.iter().map(GuildKeyResponse::from).collect()))
+}
+
+/// Revoke a guild API key
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/integrations/discord/keys/{key_uuid}",
+    params(("key_uuid" = String, Path, description = "Key UUID")),
+    responses(
+        (status = 204, description = "Key revoked"),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Not an admin"),
+        (status = 404, description = "No such unrevoked key", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "admin"
+)]
+#[tracing::instrument(name = "Revoking guild API key", skip(database, admin))]
+pub async fn revoke_guild_key(
+    State(database): State<Database>,
+    Extension(admin): Extension<UserContext>,
+    Path(key_uuid): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    let key_uuid = parse_uuid(&key_uuid)?;
+    let result = database
+        .collection::<GuildApiKey>(GUILD_API_KEYS)
+        .update_one(
+            doc! { "uuid": key_uuid.to_string(), "revoked_at": null },
+            doc! { "$set": { "revoked_at": BsonDateTime::now() } },
+            None,
+        )
+        .await
+        .map_err(|e| database_error(&e))?;
+    if result.modified_count == 0 {
+        return Err(error(
+            StatusCode::NOT_FOUND,
+            "KEY_NOT_FOUND",
+            "No such unrevoked key",
+        ));
+    }
+
+    record_audit(
+        &database,
+        AuditEntry::new("guild_key.revoke").by(admin.user_uuid),
+    )
+    .await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Create a race for the guild
+///
+/// Takes the same body as `POST /api/v1/races`, plus the channel the race is
+/// announced in.
+#[utoipa::path(
+    post,
+    path = "/api/v1/integrations/discord/races",
+    request_body = CreateGuildRaceRequest,
+    responses(
+        (status = 201, description = "Race created", body = GuildRaceResponse),
+        (status = 400, description = "Invalid track definition", body = ErrorResponse),
+        (status = 401, description = "Missing, invalid or revoked guild key", body = ErrorResponse),
+        (status = 404, description = "Track or track template not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "integrations"
+)]
+#[tracing::instrument(name = "Creating guild race", skip(database, settings, guild, request))]
+pub async fn create_guild_race(
+    State(database): State<Database>,
+    Extension(settings): Extension<DiscordSettings>,
+    Extension(guild): Extension<GuildContext>,
+    Json(request): Json<CreateGuildRaceRequest>,
+) -> Result<(StatusCode, Json<GuildRaceResponse>), ApiError> {
+    let (_, Json(created)) = create_race(State(database.clone()), Json(request.race))
+        .await
+        .map_err(|(status, Json(rejection))| {
+            let details = rejection
+                .field_errors
+                .iter()
+                .map(|e| format!("{}: {}", e.field, e.message))
+                .collect::<Vec<_>>()
+                .join("; ");
+            (
+                status,
+                Json(ErrorResponse {
+                    error: rejection.error,
+                    message: rejection.message,
+                    details: Some(details).filter(|d| !d.is_empty()),
+                }),
+            )
+        })?;
+    let race = created.race;
+
+    let guild_race = GuildRace {
+        id: None,
+        guild_id: guild.guild_id.clone(),
+        race_uuid: race.uuid,
+        channel_id: request.channel_id,
+        created_at: BsonDateTime::now(),
+    };
+    database
+        .collection::<GuildRace>(GUILD_RACES)
+        .insert_one(&guild_race, None)
+        .await
+        .map_err(|e| database_error(&e))?;
+
+    tracing::info!("Guild {} created race {}", guild.guild_id, race.uuid);
+    Ok((
+        StatusCode::CREATED,
+        Json(GuildRaceResponse {
+            race_uuid: race.uuid.to_string(),
+            name: race.name,
+            status: race.status,
+            channel_id: guild_race.channel_id,
+            join_url: join_url(&settings, race.uuid),
+        }),
+    ))
+}
+
+/// The guild's latest races
+#[utoipa::path(
+    get,
+    path = "/api/v1/integrations/discord/races",
+    responses(
+        (status = 200, description = "The guild's races, newest first", body = Vec<GuildRaceResponse>),
+        (status = 401, description = "Missing, invalid or revoked guild key", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "integrations"
+)]
+#[tracing::instrument(name = "Listing guild races", skip(database, settings, guild))]
+pub async fn list_guild_races(
+    State(database): State<Database>,
+    Extension(settings): Extension<DiscordSettings>,
+    Extension(guild): Extension<GuildContext>,
+) -> Result<Json<Vec<GuildRaceResponse>>, ApiError> {
+    let options = FindOptions::builder()
+        .sort(doc! { "created_at": -1 })
+        .limit(MAX_LISTED_RACES)
+        .build();
+    let guild_races: Vec<GuildRace> = database
+        .collection::<GuildRace>(GUILD_RACES)
+        .find(doc! { "guild_id": &guild.guild_id }, options)
+        .await
+        .map_err(|e| database_error(&e))?
+        .try_collect()
+        .await
+        .map_err(|e| database_error(&e))?;
+
+    let mut races = Vec::with_capacity(guild_races.len());
+    for guild_race in guild_races {
+        let Some(race) = get_race_by_uuid(&database, guild_race.race_uuid)
+            .await
+            .map_err(|e| database_error(&e))?
+        else {
+            continue;
+        };
+        races.push(GuildRaceResponse {
+            race_uuid: race.uuid.to_string(),
+            name: race.name,
+            status: race.status,
+            channel_id: guild_race.channel_id,
+            join_url: join_url(&settings, race.uuid),
+        });
+    }
+    Ok(Json(races))
+}
+
+/// A race of the guild, if it has one with this UUID
+async fn load_guild_race(
+    database: &Database,
+    guild: &GuildContext,
+    race_uuid: &str,
+) -> Result<Race, ApiError> {
+    let race_uuid = parse_uuid(race_uuid)?;
+    let owned = database
+        .collection::<GuildRace>(GUILD_RACES)
+        .find_one(
+            doc! { "guild_id": &guild.guild_id, "race_uuid": race_uuid.to_string() },
+            None,
+        )
+        .await
+        .map_err(|e| database_error(&e))?;
+    if owned.is_none() {
+        return Err(race_not_found());
+    }
+    get_race_by_uuid(database, race_uuid)
+        .await
+        .map_err(|e| database_error(&e))?
+        .ok_or_else(race_not_found)
+}
+
+/// Link for players to join a guild race
+#[utoipa::path(
+    get,
+    path = "/api/v1/integrations/discord/races/{race_uuid}/join-link",
+    params(("race_uuid" = String, Path, description = "Race UUID")),
+    responses(
+        (status = 200, description = "Join link and a message to post", body = JoinLinkResponse),
+        (status = 401, description = "Missing, invalid or revoked guild key", body = ErrorResponse),
+        (status = 404, description = "The guild has no such race", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "integrations"
+)]
+#[tracing::instrument(
+    name = "Fetching guild race join link",
+    skip(database, settings, guild)
+)]
+pub async fn get_join_link(
+    State(database): State<Database>,
+    Extension(settings): Extension<DiscordSettings>,
+    Extension(guild): Extension<GuildContext>,
+    Path(race_uuid): Path<String>,
+) -> Result<Json<JoinLinkResponse>, ApiError> {
+    let race = load_guild_race(&database, &guild, &race_uuid).await?;
+    let join_url = join_url(&settings, race.uuid);
+    Ok(Json(JoinLinkResponse {
+        race_uuid: race.uuid.to_string(),
+        message: format!(
+            "🏁 **{}** ({} laps) is open: {}",
+            race.name, race.total_laps, join_url
+        ),
+        join_url,
+    }))
+}
+
+/// Live standings of a guild race, with a text version for a chat embed
+#[utoipa::path(
+    get,
+    path = "/api/v1/integrations/discord/races/{race_uuid}/standings",
+    params(("race_uuid" = String, Path, description = "Race UUID")),
+    responses(
+        (status = 200, description = "Current standings", body = GuildStandingsResponse),
+        (status = 401, description = "Missing, invalid or revoked guild key", body = ErrorResponse),
+        (status = 404, description = "The guild has no such race", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "integrations"
+)]
+#[tracing::instrument(name = "Fetching guild race standings", skip(database, guild))]
+pub async fn get_guild_standings(
+    State(database): State<Database>,
+    Extension(guild): Extension<GuildContext>,
+    Path(race_uuid): Path<String>,
+) -> Result<Json<GuildStandingsResponse>, ApiError> {
+    let race = load_guild_race(&database, &guild, &race_uuid).await?;
+
+    let player_uuids: Vec<String> = race
+        .participants
+        .iter()
+        .filter(|p| p.bot.is_none() && p.ghost.is_none())
+        .map(|p| p.player_uuid.to_string())
+        .collect();
+    let players: Vec<Player> = database
+        .collection::<Player>("players")
+        .find(doc! { "uuid": { "$in": player_uuids } }, None)
+        .await
+        .map_err(|e| database_error(&e))?
+        .try_collect()
+        .await
+        .map_err(|e| database_error(&e))?;
+    let team_names: HashMap<Uuid, String> = players
+        .into_iter()
+        .map(|player| (player.uuid, player.team_name.as_ref().to_string()))
+        .collect();
+
+    let standings = standings(&race, &team_names);
+    let embed = ChatEmbed {
+        title: format!("🏁 {}", race.name),
+        description: standings_text(&standings),
+        footer: format!(
+            "{} · lap {}/{}",
+            status_label(&race.status),
+            race.current_lap.min(race.total_laps),
+            race.total_laps
+        ),
+    };
+    Ok(Json(GuildStandingsResponse {
+        race_uuid: race.uuid.to_string(),
+        name: race.name,
+        status: race.status,
+        current_lap: race.current_lap,
+        total_laps: race.total_laps,
+        standings,
+        embed,
+    }))
+}
+
+/// Cars of `race` from first to last
+///
+/// Finished races keep their classification; running ones are ranked the way
+/// they would finish if the race stopped now.
+fn standings(race: &Race, team_names: &HashMap<Uuid, String>) -> Vec<StandingEntry> {
+    let mut participants = race.participants.clone();
+    if !matches!(race.status, RaceStatus::Finished) {
+        assign_finish_positions(&mut participants);
+    }
+    participants.sort_by_key(|p| p.finish_position.unwrap_or(u32::MAX));
+
+    participants
+        .iter()
+        .enumerate()
+        .map(|(index, p)| {
+            let name = if p.ghost.is_some() {
+                "Ghost".to_string()
+            } else if let Some(bot) = &p.bot {
+                format!("{:?} bot", bot.personality)
+            } else {
+                team_names
+                    .get(&p.player_uuid)
+                    .cloned()
+                    .unwrap_or_else(|| "Unknown team".to_string())
+            };
+            StandingEntry {
+                position: p
+                    .finish_position
+                    .unwrap_or_else(|| u32::try_from(index + 1).unwrap_or(u32::MAX)),
+                name,
+                lap: p.current_lap,
+                sector: p.current_sector,
+                finished: p.is_finished,
+            }
+        })
+        .collect()
+}
+
+/// Standings as a fixed-width code block, one car per line
+fn standings_text(standings: &[StandingEntry]) -> String {
+    if standings.is_empty() {
+        return "No cars have joined yet".to_string();
+    }
+    let mut lines: Vec<String> = standings
+        .iter()
+        .take(MAX_TEXT_STANDINGS)
+        .map(|entry| {
+            let mut name: String = entry.name.chars().take(NAME_WIDTH).collect();
+            if entry.name.chars().count() > NAME_WIDTH {
+                name.pop();
+                name.push('…');
+            }
+            let progress = if entry.finished {
+                "FIN".to_string()
+            } else {
+                format!("L{} S{}", entry.lap, entry.sector)
+            };
+            format!("{:>2}. {name:<NAME_WIDTH$} {progress}", entry.position)
+        })
+        .collect();
+    if standings.len() > MAX_TEXT_STANDINGS {
+        lines.push(format!(
+            "… and {} more",
+            standings.len() - MAX_TEXT_STANDINGS
+        ));
+    }
+    format!("```\n{}\n```", lines.join("\n"))
+}
+
+fn status_label(status: &RaceStatus) -> &'static str {
+    match status {
+        RaceStatus::Waiting => "Waiting for players",
+        RaceStatus::Starting => "Starting",
+        RaceStatus::InProgress => "In progress",
+        RaceStatus::Finished => "Finished",
+        RaceStatus::Cancelled => "Cancelled",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(position: u32, name: &str, lap: u32, sector: u32, finished: bool) -> StandingEntry {
+        StandingEntry {
+            position,
+            name: name.to_string(),
+            lap,
+            sector,
+            finished,
+        }
+    }
+
+    #[test]
+    fn text_standings_fit_a_code_block() {
+        let standings = vec![
+            entry(1, "Scuderia", 3, 0, true),
+            entry(2, "A team with a very long name", 3, 4, false),
+            entry(10, "Aggressive bot", 2, 7, false),
+        ];
+
+        assert_eq!(
+            standings_text(&standings),
+            "```\n\
+             \u{20}1. Scuderia           FIN\n\
+             \u{20}2. A team with a ver… L3 S4\n\
+             10. Aggressive bot     L2 S7\n\
+             ```"
+        );
+        assert_eq!(standings_text(&[]), "No cars have joined yet");
+    }
+
+    #[test]
+    fn long_standings_are_cut_short() {
+        let standings: Vec<StandingEntry> = (1..=25)
+            .map(|position| entry(position, "Team", 1, 1, false))
+            .collect();
+
+        let text = standings_text(&standings);
+
+        assert_eq!(text.lines().count(), MAX_TEXT_STANDINGS + 3);
+        assert!(text.contains("… and 5 more"));
+    }
+}
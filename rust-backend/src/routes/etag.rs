@@ -0,0 +1,116 @@
+use axum::{
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+
+use crate::domain::Race;
+
+/// Entity tag for a representation of a race
+///
+/// Derived from the race's last update rather than the response body, so a
+/// matching request can be answered before the response is built. `variant`
+/// tells apart representations of the same race, e.g. different query strings.
+#[must_use]
+pub fn race_etag(race: &Race, variant: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(race.uuid.as_bytes());
+    hasher.update(race.updated_at.timestamp_millis().to_be_bytes());
+    // Writes within the same millisecond still change one of these
+    hasher.update(race.current_lap.to_be_bytes());
+    hasher.update(format!("{:?}", race.status).as_bytes());
+    hasher.update((race.pending_actions.len() as u64).to_be_bytes());
+    hasher.update(variant.as_bytes());
+
+    format!("W/\"{}\"", &hex::encode(hasher.finalize())[..32])
+}
+
+/// Whether the client's `If-None-Match` header already holds `etag`
+#[must_use]
+pub fn is_not_modified(headers: &HeaderMap, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    let etag = opaque(etag);
+
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|candidate| candidate.trim() == "*" || opaque(candidate) == etag)
+}
+
+/// `304 Not Modified` carrying the current `etag`
+#[must_use]
+pub fn not_modified(etag: &str) -> Response {
+    with_etag(StatusCode::NOT_MODIFIED, etag)
+}
+
+/// Attach `etag` to a response
+pub fn with_etag(response: impl IntoResponse, etag: &str) -> Response {
+    let mut response = response.into_response();
+    if let Ok(value) = HeaderValue::from_str(etag) {
+        response.headers_mut().insert(header::ETAG, value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Track;
+    use mongodb::bson::DateTime as BsonDateTime;
+    use uuid::Uuid;
+
+    fn race() -> Race {
+        let track = Track {
+            uuid: Uuid::new_v4(),
+            name: "Track".to_string(),
+            sectors: Vec::new(),
+        };
+        Race::new("Tagged".to_string(), track, 3)
+    }
+
+    fn headers(if_none_match: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::IF_NONE_MATCH,
+            HeaderValue::from_str(if_none_match).unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn etag_changes_when_the_race_is_updated() {
+        let mut race = race();
+        let before = race_etag(&race, "");
+
+        race.updated_at = BsonDateTime::from_millis(race.updated_at.timestamp_millis() + 1);
+        assert_ne!(race_etag(&race, ""), before);
+    }
+
+    #[test]
+    fn etag_depends_on_the_variant() {
+        let race = race();
+        assert_ne!(
+            race_etag(&race, "player_uuid=a"),
+            race_etag(&race, "player_uuid=b")
+        );
+        assert_eq!(race_etag(&race, "x"), race_etag(&race, "x"));
+    }
+
+    #[test]
+    fn if_none_match_uses_weak_comparison() {
+        let etag = race_etag(&race(), "");
+        let strong = etag.trim_start_matches("W/");
+
+        assert!(is_not_modified(&headers(&etag), &etag));
+        assert!(is_not_modified(&headers(strong), &etag));
+        assert!(is_not_modified(
+            &headers(&format!("\"other\", {etag}")),
+            &etag
+        ));
+        assert!(is_not_modified(&headers("*"), &etag));
+        assert!(!is_not_modified(&headers("\"other\""), &etag));
+        assert!(!is_not_modified(&HeaderMap::new(), &etag));
+    }
+}
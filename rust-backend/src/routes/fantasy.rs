@@ -0,0 +1,343 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+    routing::{get, post, put},
+    Router,
+};
+use mongodb::{
+    bson::{doc, to_bson},
+    Database,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::fantasy::{
+    FantasyDraftError, FantasyLeague, FantasyPick, FantasyStanding, MAX_ROSTER_SIZE,
+};
+use crate::domain::{Race, RaceStatus};
+use crate::routes::players::{find_races_with_archive, get_player_by_uuid_from_db};
+use crate::routes::races::ErrorResponse;
+
+type ApiError = (StatusCode, Json<ErrorResponse>);
+
+/// Collection holding the fantasy leagues and their rosters
+pub const FANTASY_LEAGUES_COLLECTION: &str = "fantasy_leagues";
+
+/// Roster size of leagues created without one
+const DEFAULT_ROSTER_SIZE: usize = 3;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateFantasyLeagueRequest {
+    pub name: String,
+    /// Player creating the league
+    pub owner_uuid: String,
+    /// Races making up the championship
+    pub championship: Vec<String>,
+    /// Picks per roster, 3 when omitted
+    pub roster_size: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DraftRosterRequest {
+    /// Replaces the member's previous picks
+    pub picks: Vec<FantasyPick>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FantasyStandingsResponse {
+    pub league_uuid: String,
+    /// Members by points, best first
+    pub standings: Vec<FantasyStanding>,
+}
+
+pub fn routes() -> Router<Database> {
+    Router::new()
+        .route("/fantasy/leagues", post(create_fantasy_league))
+        .route("/fantasy/leagues/:league_uuid", get(get_fantasy_league))
+        .route(
+            "/fantasy/leagues/:league_uuid/rosters/:owner_uuid",
+            put(draft_fantasy_roster),
+        )
+        .route(
+            "/fantasy/leagues/:league_uuid/standings",
+            get(get_fantasy_standings),
+        )
+}
+
+fn error(status: StatusCode, error: &str, message: &str) -> ApiError {
+    (
+        status,
+        Json(ErrorResponse {
+            error: error.to_string(),
+            message: message.to_string(),
+            details: None,
+        }),
+    )
+}
+
+fn database_error(e: &mongodb::error::Error) -> ApiError {
+    tracing::error!("Fantasy league storage failed: {:?}", e);
+    error(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "DATABASE_ERROR",
+        "Internal server error",
+    )
+}
+
+fn invalid_uuid() -> ApiError {
+    error(
+        StatusCode::BAD_REQUEST,
+        "INVALID_UUID",
+        "Invalid UUID format",
+    )
+}
+
+async fn find_league(database: &Database, league_uuid: &str) -> Result<FantasyLeague, ApiError> {
+    let league_uuid = Uuid::parse_str(league_uuid).map_err(|_| invalid_uuid())?;
+    database
+        .collection::<FantasyLeague>(FANTASY_LEAGUES_COLLECTION)
+        .find_one(doc! { "uuid": league_uuid.to_string() }, None)
+        .await
+        .map_err(|e| database_error(&e))?
+        .ok_or_else(|| {
+            error(
+                StatusCode::NOT_FOUND,
+                "LEAGUE_NOT_FOUND",
+                "Fantasy league not found",
+            )
+        })
+}
+
+/// Championship races, archived ones included
+async fn championship_races(
+    database: &Database,
+    league: &FantasyLeague,
+) -> Result<Vec<Race>, mongodb::error::Error> {
+    find_races_with_archive(
+        database,
+        doc! { "uuid": { "$in": &league.championship } },
+        doc! { "uuid": 1 },
+        None,
+    )
+    .await
+}
+
+/// Create a fantasy league over a championship of races
+#[utoipa::path(
+    post,
+    path = "/api/v1/fantasy/leagues",
+    request_body = CreateFantasyLeagueRequest,
+    responses(
+        (status = 201, description = "League created", body = FantasyLeague),
+        (status = 400, description = "Invalid UUID, roster size or empty championship", body = ErrorResponse),
+        (status = 404, description = "Owner or a championship race not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "fantasy"
+)]
+#[tracing::instrument(name = "Creating fantasy league", skip(database, request))]
+pub async fn create_fantasy_league(
+    State(database): State<Database>,
+    Json(request): Json<CreateFantasyLeagueRequest>,
+) -> Result<(StatusCode, Json<FantasyLeague>), ApiError> {
+    let owner_uuid = Uuid::parse_str(&request.owner_uuid).map_err(|_| invalid_uuid())?;
+    let mut championship = Vec::new();
+    for race_uuid in &request.championship {
+        let race_uuid = Uuid::parse_str(race_uuid).map_err(|_| invalid_uuid())?;
+        if !championship.contains(&race_uuid) {
+            championship.push(race_uuid);
+        }
+    }
+    let roster_size = request.roster_size.unwrap_or(DEFAULT_ROSTER_SIZE);
+    if request.name.trim().is_empty()
+        || championship.is_empty()
+        || !(1..=MAX_ROSTER_SIZE).contains(&roster_size)
+    {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_LEAGUE",
+            &format!(
+                "A league needs a name, at least one race and 1 to {MAX_ROSTER_SIZE} picks per roster"
+            ),
+        ));
+    }
+
+    if get_player_by_uuid_from_db(&database, owner_uuid)
+        .await
+        .map_err(|e| database_error(&e))?
+        .is_none()
+    {
+        return Err(error(
+            StatusCode::NOT_FOUND,
+            "PLAYER_NOT_FOUND",
+            "Player not found",
+        ));
+    }
+
+    let league = FantasyLeague::new(
+        request.name.trim().to_string(),
+        owner_uuid,
+        &championship,
+        roster_size,
+    );
+    let races = championship_races(&database, &league)
+        .await
+        .map_err(|e| database_error(&e))?;
+    if races.len() != championship.len() {
+        return Err(error(
+            StatusCode::NOT_FOUND,
+            "RACE_NOT_FOUND",
+            "A championship race was not found",
+        ));
+    }
+
+    database
+        .collection::<FantasyLeague>(FANTASY_LEAGUES_COLLECTION)
+        .insert_one(&league, None)
+        .await
+        .map_err(|e| database_error(&e))?;
+    Ok((StatusCode::CREATED, Json(league)))
+}
+
+/// Fantasy league with its championship and rosters
+#[utoipa::path(
+    get,
+    path = "/api/v1/fantasy/leagues/{league_uuid}",
+    params(
+        ("league_uuid" = String, Path, description = "Fantasy league UUID")
+    ),
+    responses(
+        (status = 200, description = "Fantasy league", body = FantasyLeague),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse),
+        (status = 404, description = "League not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "fantasy"
+)]
+#[tracing::instrument(name = "Getting fantasy league", skip(database))]
+pub async fn get_fantasy_league(
+    State(database): State<Database>,
+    Path(league_uuid): Path<String>,
+) -> Result<Json<FantasyLeague>, ApiError> {
+    find_league(&database, &league_uuid).await.map(Json)
+}
+
+/// Draft a member's roster, joining the league on the first draft
+///
+/// Rosters can change until the first championship race leaves the lobby.
+/// Each real player or car goes to one roster per league.
+#[utoipa::path(
+    put,
+    path = "/api/v1/fantasy/leagues/{league_uuid}/rosters/{owner_uuid}",
+    params(
+        ("league_uuid" = String, Path, description = "Fantasy league UUID"),
+        ("owner_uuid" = String, Path, description = "Member's player UUID")
+    ),
+    request_body = DraftRosterRequest,
+    responses(
+        (status = 200, description = "Roster drafted", body = FantasyLeague),
+        (status = 400, description = "Invalid UUID format or roster", body = ErrorResponse),
+        (status = 404, description = "League or player not found", body = ErrorResponse),
+        (status = 409, description = "Draft closed, pick taken, or league changed meanwhile", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "fantasy"
+)]
+#[tracing::instrument(name = "Drafting fantasy roster", skip(database, request))]
+pub async fn draft_fantasy_roster(
+    State(database): State<Database>,
+    Path((league_uuid, owner_uuid)): Path<(String, String)>,
+    Json(request): Json<DraftRosterRequest>,
+) -> Result<Json<FantasyLeague>, ApiError> {
+    let owner_uuid = Uuid::parse_str(&owner_uuid).map_err(|_| invalid_uuid())?;
+    let mut league = find_league(&database, &league_uuid).await?;
+
+    if get_player_by_uuid_from_db(&database, owner_uuid)
+        .await
+        .map_err(|e| database_error(&e))?
+        .is_none()
+    {
+        return Err(error(
+            StatusCode::NOT_FOUND,
+            "PLAYER_NOT_FOUND",
+            "Player not found",
+        ));
+    }
+    let races = championship_races(&database, &league)
+        .await
+        .map_err(|e| database_error(&e))?;
+    if races.iter().any(|race| race.status != RaceStatus::Waiting) {
+        return Err(error(
+            StatusCode::CONFLICT,
+            "DRAFT_CLOSED",
+            "The championship has started",
+        ));
+    }
+
+    let previous_rosters = to_bson(&league.rosters).map_err(|e| database_error(&e.into()))?;
+    league
+        .draft(owner_uuid, request.picks)
+        .map_err(|e| match e {
+            FantasyDraftError::PickTaken(_) => {
+                error(StatusCode::CONFLICT, "PICK_TAKEN", &e.to_string())
+            }
+            FantasyDraftError::TooManyPicks | FantasyDraftError::DuplicatePick(_) => {
+                error(StatusCode::BAD_REQUEST, "INVALID_ROSTER", &e.to_string())
+            }
+        })?;
+
+    // Only write over the rosters that were checked, so concurrent drafts cannot both take a pick
+    let rosters = to_bson(&league.rosters).map_err(|e| database_error(&e.into()))?;
+    let updated = database
+        .collection::<FantasyLeague>(FANTASY_LEAGUES_COLLECTION)
+        .update_one(
+            doc! { "uuid": league.uuid.to_string(), "rosters": previous_rosters },
+            doc! { "$set": { "rosters": rosters } },
+            None,
+        )
+        .await
+        .map_err(|e| database_error(&e))?;
+    if updated.matched_count == 0 {
+        return Err(error(
+            StatusCode::CONFLICT,
+            "LEAGUE_CHANGED",
+            "Another roster changed meanwhile, try again",
+        ));
+    }
+    Ok(Json(league))
+}
+
+/// Members of a fantasy league ranked by points from finished championship races
+///
+/// Picks score by finish position: 10, 8, 6, 5, 4, 3, 2 and 1 point for P1 to P8.
+#[utoipa::path(
+    get,
+    path = "/api/v1/fantasy/leagues/{league_uuid}/standings",
+    params(
+        ("league_uuid" = String, Path, description = "Fantasy league UUID")
+    ),
+    responses(
+        (status = 200, description = "League standings", body = FantasyStandingsResponse),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse),
+        (status = 404, description = "League not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "fantasy"
+)]
+#[tracing::instrument(name = "Getting fantasy standings", skip(database))]
+pub async fn get_fantasy_standings(
+    State(database): State<Database>,
+    Path(league_uuid): Path<String>,
+) -> Result<Json<FantasyStandingsResponse>, ApiError> {
+    let league = find_league(&database, &league_uuid).await?;
+    let races = championship_races(&database, &league)
+        .await
+        .map_err(|e| database_error(&e))?;
+
+    Ok(Json(FantasyStandingsResponse {
+        league_uuid: league.uuid.to_string(),
+        standings: league.standings(&races),
+    }))
+}
@@ -0,0 +1,137 @@
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+/// Fields requested with a `fields=` query parameter
+///
+/// The parameter is a comma-separated list of dotted paths, e.g.
+/// `race_progress,player_data.boost_availability`. Selecting a field keeps it
+/// whole; selecting a nested path keeps only that part of its parent. Paths
+/// that do not exist in the response are ignored.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FieldSelection {
+    /// Selected children; an empty map means the whole value is kept
+    children: BTreeMap<String, FieldSelection>,
+}
+
+impl FieldSelection {
+    /// Parse a `fields=` value; `None` when it selects nothing
+    #[must_use]
+    pub fn parse(fields: &str) -> Option<Self> {
+        let mut selection = Self::default();
+        for path in fields.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            selection.insert(path.split('.').filter(|s| !s.is_empty()));
+        }
+        (!selection.children.is_empty()).then_some(selection)
+    }
+
+    fn insert<'a>(&mut self, mut segments: impl Iterator<Item = &'a str>) {
+        let Some(first) = segments.next() else {
+            return;
+        };
+        // A field already selected whole stays whole
+        let was_selected = self.children.contains_key(first);
+        let child = self.children.entry(first.to_string()).or_default();
+        if was_selected && child.children.is_empty() {
+            return;
+        }
+
+        let rest: Vec<&str> = segments.collect();
+        if rest.is_empty() {
+            child.children.clear();
+        } else {
+            child.insert(rest.into_iter());
+        }
+    }
+
+    /// Whether the top-level field `name` is part of the response
+    #[must_use]
+    pub fn includes(&self, name: &str) -> bool {
+        self.children.contains_key(name)
+    }
+
+    /// Remove every field that was not selected
+    /// Selections apply to each element of an array.
+    #[must_use]
+    pub fn prune(&self, value: Value) -> Value {
+        if self.children.is_empty() {
+            return value;
+        }
+        match value {
+            Value::Object(object) => {
+                let pruned: Map<String, Value> = object
+                    .into_iter()
+                    .filter_map(|(key, value)| {
+                        let child = self.children.get(&key)?;
+                        Some((key, child.prune(value)))
+                    })
+                    .collect();
+                Value::Object(pruned)
+            }
+            Value::Array(items) => {
+                Value::Array(items.into_iter().map(|item| self.prune(item)).collect())
+            }
+            other => other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn response() -> Value {
+        json!({
+            "race_progress": { "status": "InProgress", "current_lap": 2 },
+            "track_situation": { "sectors": [] },
+            "player_data": {
+                "boost_availability": { "cards_remaining": 3 },
+                "boost_usage_history": [{ "lap_number": 1 }]
+            }
+        })
+    }
+
+    #[test]
+    fn nested_paths_keep_only_the_selected_part() {
+        let selection =
+            FieldSelection::parse("race_progress,player_data.boost_availability").unwrap();
+
+        assert_eq!(
+            selection.prune(response()),
+            json!({
+                "race_progress": { "status": "InProgress", "current_lap": 2 },
+                "player_data": { "boost_availability": { "cards_remaining": 3 } }
+            })
+        );
+        assert!(selection.includes("player_data"));
+        assert!(!selection.includes("track_situation"));
+    }
+
+    #[test]
+    fn whole_field_wins_over_a_nested_path() {
+        let first = FieldSelection::parse("player_data,player_data.boost_availability");
+        let second = FieldSelection::parse("player_data.boost_availability,player_data");
+
+        for selection in [first, second] {
+            let pruned = selection.unwrap().prune(response());
+            assert!(pruned["player_data"]["boost_usage_history"].is_array());
+        }
+    }
+
+    #[test]
+    fn selections_apply_to_array_elements() {
+        let selection = FieldSelection::parse("items.id").unwrap();
+        let value = json!({ "items": [{ "id": 1, "name": "a" }, { "id": 2, "name": "b" }] });
+
+        assert_eq!(
+            selection.prune(value),
+            json!({ "items": [{ "id": 1 }, { "id": 2 }] })
+        );
+    }
+
+    #[test]
+    fn empty_selection_is_ignored() {
+        assert_eq!(FieldSelection::parse(""), None);
+        assert_eq!(FieldSelection::parse(" , "), None);
+    }
+}
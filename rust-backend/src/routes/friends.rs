@@ -0,0 +1,586 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+    routing::{delete, get, post},
+    Router,
+};
+use mongodb::{
+    bson::{doc, Document},
+    options::FindOptions,
+    Database,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::{Friendship, FriendshipStatus, Player, Race, RaceStatus};
+use crate::repositories::{
+    FriendshipRepository, MongoFriendshipRepository, RepositoryError, RepositoryResult,
+};
+use crate::routes::blocks::is_blocked_between_in_db;
+use crate::routes::players::{get_player_by_uuid_from_db, get_skill_rating_from_db};
+use crate::routes::races::ErrorResponse;
+use crate::services::car_validation::CarValidationService;
+use crate::services::matchmaking::rank_open_races;
+
+type ApiError = (StatusCode, Json<ErrorResponse>);
+
+/// Most races the matchmaking endpoint suggests
+const MAX_SUGGESTIONS: usize = 20;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct FriendRequest {
+    /// Player to befriend
+    pub friend_uuid: String,
+}
+
+/// Friend or pending request, seen from the listing player
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FriendEntry {
+    pub player_uuid: String,
+    pub team_name: Option<String>,
+    pub status: FriendshipStatus,
+    /// When the request was accepted, or sent while still pending
+    pub since: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FriendListResponse {
+    pub friends: Vec<FriendEntry>,
+    /// Requests waiting for this player to accept
+    pub incoming: Vec<FriendEntry>,
+    /// Requests this player sent
+    pub outgoing: Vec<FriendEntry>,
+}
+
+/// Race a friend takes part in, or a player could join
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FriendRaceResponse {
+    pub race_uuid: String,
+    pub name: String,
+    pub status: RaceStatus,
+    pub current_lap: u32,
+    pub total_laps: u32,
+    pub participants: usize,
+    /// The player's friends among the participants
+    pub friends: Vec<String>,
+}
+
+impl FriendRaceResponse {
+    fn new(race: &Race, friends: &[Uuid]) -> Self {
+        Self {
+            race_uuid: race.uuid.to_string(),
+            name: race.name.clone(),
+            status: race.status.clone(),
+            current_lap: race.current_lap,
+            total_laps: race.total_laps,
+            participants: race.participants.len(),
+            friends: friends.iter().map(Uuid::to_string).collect(),
+        }
+    }
+}
+
+/// Friend routes act for the player in the path; mounted behind
+/// `AuthMiddleware` and `RequireOwnership::player("player_uuid")`
+pub fn routes() -> Router<Database> {
+    Router::new()
+        .route(
+            "/players/:player_uuid/friends",
+            get(list_friends).post(request_friend),
+        )
+        .route(
+            "/players/:player_uuid/friends/:friend_uuid/accept",
+            post(accept_friend),
+        )
+        .route(
+            "/players/:player_uuid/friends/:friend_uuid",
+            delete(remove_friend),
+        )
+        .route("/players/:player_uuid/friends/races", get(get_friend_races))
+        .route("/players/:player_uuid/matchmaking", get(get_matchmaking))
+}
+
+fn error(status: StatusCode, error: &str, message: &str) -> ApiError {
+    (
+        status,
+        Json(ErrorResponse {
+            error: error.to_string(),
+            message: message.to_string(),
+            details: None,
+        }),
+    )
+}
+
+fn database_error(e: &mongodb::error::Error) -> ApiError {
+    tracing::error!("Friend storage failed: {:?}", e);
+    error(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "DATABASE_ERROR",
+        "Internal server error",
+    )
+}
+
+fn repository_error(e: &RepositoryError) -> ApiError {
+    tracing::error!("Friend storage failed: {:?}", e);
+    error(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "DATABASE_ERROR",
+        "Internal server error",
+    )
+}
+
+fn parse_uuid(value: &str) -> Result<Uuid, ApiError> {
+    Uuid::parse_str(value).map_err(|_| {
+        error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_UUID",
+            "Invalid UUID format",
+        )
+    })
+}
+
+async fn ensure_player_exists(database: &Database, player_uuid: Uuid) -> Result<(), ApiError> {
+    find_player(database, player_uuid).await.map(|_| ())
+}
+
+async fn find_player(database: &Database, player_uuid: Uuid) -> Result<Player, ApiError> {
+    match get_player_by_uuid_from_db(database, player_uuid).await {
+        Ok(Some(player)) => Ok(player),
+        Ok(None) => Err(error(
+            StatusCode::NOT_FOUND,
+            "PLAYER_NOT_FOUND",
+            "Player not found",
+        )),
+        Err(e) => Err(database_error(&e)),
+    }
+}
+
+/// UUIDs of the player's accepted friends
+pub async fn get_friend_uuids(
+    repository: &impl FriendshipRepository,
+    player_uuid: Uuid,
+) -> RepositoryResult<HashSet<Uuid>> {
+    Ok(repository
+        .find_for_player(player_uuid)
+        .await?
+        .iter()
+        .filter(|friendship| friendship.is_accepted())
+        .map(|friendship| friendship.other(player_uuid))
+        .collect())
+}
+
+/// List a player's friends and pending friend requests
+#[utoipa::path(
+    get,
+    path = "/api/v1/players/{player_uuid}/friends",
+    params(
+        ("player_uuid" = String, Path, description = "Player's UUID")
+    ),
+    responses(
+        (status = 200, description = "Friends and pending requests", body = FriendListResponse),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse),
+        (status = 401, description = "Authentication required"),
+        (status = 404, description = "Player not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Listing friends", skip(database))]
+pub async fn list_friends(
+    State(database): State<Database>,
+    Path(player_uuid): Path<String>,
+) -> Result<Json<FriendListResponse>, ApiError> {
+    let player_uuid = parse_uuid(&player_uuid)?;
+    ensure_player_exists(&database, player_uuid).await?;
+
+    let friendships = MongoFriendshipRepository::new(&database)
+        .find_for_player(player_uuid)
+        .await
+        .map_err(|e| repository_error(&e))?;
+    let others: Vec<Uuid> = friendships.iter().map(|f| f.other(player_uuid)).collect();
+    let team_names = get_team_names_from_db(&database, &others)
+        .await
+        .map_err(|e| database_error(&e))?;
+
+    let mut response = FriendListResponse {
+        friends: Vec::new(),
+        incoming: Vec::new(),
+        outgoing: Vec::new(),
+    };
+    for friendship in friendships {
+        let other = friendship.other(player_uuid);
+        let entry = FriendEntry {
+            player_uuid: other.to_string(),
+            team_name: team_names.get(&other).cloned(),
+            status: friendship.status,
+            since: friendship
+                .accepted_at
+                .unwrap_or(friendship.requested_at)
+                .try_to_rfc3339_string()
+                .unwrap_or_default(),
+        };
+        if friendship.is_accepted() {
+            response.friends.push(entry);
+        } else if friendship.requester_uuid == player_uuid {
+            response.outgoing.push(entry);
+        } else {
+            response.incoming.push(entry);
+        }
+    }
+    Ok(Json(response))
+}
+
+/// Send a friend request
+///
+/// If the other player already asked this one, their request is accepted
+/// instead.
+#[utoipa::path(
+    post,
+    path = "/api/v1/players/{player_uuid}/friends",
+    params(
+        ("player_uuid" = String, Path, description = "Player sending the request")
+    ),
+    request_body = FriendRequest,
+    responses(
+        (status = 201, description = "Friend request sent", body = Friendship),
+        (status = 200, description = "Their pending request was accepted", body = Friendship),
+        (status = 400, description = "Invalid UUID format or own UUID", body = ErrorResponse),
+        (status = 401, description = "Authentication required"),
+        (status = 403, description = "One player blocked the other", body = ErrorResponse),
+        (status = 404, description = "Player not found", body = ErrorResponse),
+        (status = 409, description = "Already friends or request already sent", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Requesting friend", skip(database, request))]
+pub async fn request_friend(
+    State(database): State<Database>,
+    Path(player_uuid): Path<String>,
+    Json(request): Json<FriendRequest>,
+) -> Result<(StatusCode, Json<Friendship>), ApiError> {
+    let player_uuid = parse_uuid(&player_uuid)?;
+    let friend_uuid = parse_uuid(&request.friend_uuid)?;
+    if player_uuid == friend_uuid {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "CANNOT_BEFRIEND_SELF",
+            "Players cannot befriend themselves",
+        ));
+    }
+    ensure_player_exists(&database, player_uuid).await?;
+    ensure_player_exists(&database, friend_uuid).await?;
+    if is_blocked_between_in_db(&database, player_uuid, friend_uuid)
+        .await
+        .map_err(|e| database_error(&e))?
+    {
+        return Err(error(
+            StatusCode::FORBIDDEN,
+            "PLAYER_BLOCKED",
+            "This player cannot be befriended",
+        ));
+    }
+
+    let repository = MongoFriendshipRepository::new(&database);
+    match repository
+        .find_between(player_uuid, friend_uuid)
+        .await
+        .map_err(|e| repository_error(&e))?
+    {
+        Some(existing) if existing.is_accepted() => Err(error(
+            StatusCode::CONFLICT,
+            "ALREADY_FRIENDS",
+            "The players are already friends",
+        )),
+        Some(existing) if existing.requester_uuid == player_uuid => Err(error(
+            StatusCode::CONFLICT,
+            "FRIEND_REQUEST_PENDING",
+            "A friend request was already sent",
+        )),
+        Some(_) => match repository.accept(friend_uuid, player_uuid).await {
+            Ok(Some(friendship)) => Ok((StatusCode::OK, Json(friendship))),
+            Ok(None) => Err(error(
+                StatusCode::CONFLICT,
+                "FRIEND_REQUEST_CHANGED",
+                "The pending request changed meanwhile, try again",
+            )),
+            Err(e) => Err(repository_error(&e)),
+        },
+        None => match repository
+            .create(&Friendship::request(player_uuid, friend_uuid))
+            .await
+        {
+            Ok(friendship) => Ok((StatusCode::CREATED, Json(friendship))),
+            Err(RepositoryError::Conflict(_)) => Err(error(
+                StatusCode::CONFLICT,
+                "FRIEND_REQUEST_PENDING",
+                "A friend request between these players already exists",
+            )),
+            Err(e) => Err(repository_error(&e)),
+        },
+    }
+}
+
+/// Accept a friend request
+#[utoipa::path(
+    post,
+    path = "/api/v1/players/{player_uuid}/friends/{friend_uuid}/accept",
+    params(
+        ("player_uuid" = String, Path, description = "Player who received the request"),
+        ("friend_uuid" = String, Path, description = "Player who sent it")
+    ),
+    responses(
+        (status = 200, description = "Request accepted", body = Friendship),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse),
+        (status = 401, description = "Authentication required"),
+        (status = 404, description = "No pending request from that player", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Accepting friend request", skip(database))]
+pub async fn accept_friend(
+    State(database): State<Database>,
+    Path((player_uuid, friend_uuid)): Path<(String, String)>,
+) -> Result<Json<Friendship>, ApiError> {
+    let player_uuid = parse_uuid(&player_uuid)?;
+    let friend_uuid = parse_uuid(&friend_uuid)?;
+
+    match MongoFriendshipRepository::new(&database)
+        .accept(friend_uuid, player_uuid)
+        .await
+    {
+        Ok(Some(friendship)) => Ok(Json(friendship)),
+        Ok(None) => Err(error(
+            StatusCode::NOT_FOUND,
+            "FRIEND_REQUEST_NOT_FOUND",
+            "No pending friend request from that player",
+        )),
+        Err(e) => Err(repository_error(&e)),
+    }
+}
+
+/// Remove a friend, or decline or cancel a friend request
+#[utoipa::path(
+    delete,
+    path = "/api/v1/players/{player_uuid}/friends/{friend_uuid}",
+    params(
+        ("player_uuid" = String, Path, description = "Player's UUID"),
+        ("friend_uuid" = String, Path, description = "Friend's UUID")
+    ),
+    responses(
+        (status = 204, description = "Friendship removed"),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse),
+        (status = 401, description = "Authentication required"),
+        (status = 404, description = "No friendship or request with that player", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Removing friend", skip(database))]
+pub async fn remove_friend(
+    State(database): State<Database>,
+    Path((player_uuid, friend_uuid)): Path<(String, String)>,
+) -> Result<StatusCode, ApiError> {
+    let player_uuid = parse_uuid(&player_uuid)?;
+    let friend_uuid = parse_uuid(&friend_uuid)?;
+
+    match MongoFriendshipRepository::new(&database)
+        .delete_between(player_uuid, friend_uuid)
+        .await
+    {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err(error(
+            StatusCode::NOT_FOUND,
+            "FRIENDSHIP_NOT_FOUND",
+            "No friendship or request with that player",
+        )),
+        Err(e) => Err(repository_error(&e)),
+    }
+}
+
+/// List the active races of a player's friends
+///
+/// Races waiting to start or in progress with at least one friend among the
+/// participants, most recently updated first.
+#[utoipa::path(
+    get,
+    path = "/api/v1/players/{player_uuid}/friends/races",
+    params(
+        ("player_uuid" = String, Path, description = "Player's UUID")
+    ),
+    responses(
+        (status = 200, description = "Active races of friends", body = Vec<FriendRaceResponse>),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse),
+        (status = 401, description = "Authentication required"),
+        (status = 404, description = "Player not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Fetching races of friends", skip(database))]
+pub async fn get_friend_races(
+    State(database): State<Database>,
+    Path(player_uuid): Path<String>,
+) -> Result<Json<Vec<FriendRaceResponse>>, ApiError> {
+    let player_uuid = parse_uuid(&player_uuid)?;
+    ensure_player_exists(&database, player_uuid).await?;
+    let friends = get_friend_uuids(&MongoFriendshipRepository::new(&database), player_uuid)
+        .await
+        .map_err(|e| repository_error(&e))?;
+    if friends.is_empty() {
+        return Ok(Json(Vec::new()));
+    }
+
+    let races = get_friend_races_from_db(&database, &friends)
+        .await
+        .map_err(|e| database_error(&e))?;
+
+    Ok(Json(
+        races
+            .iter()
+            .map(|race| {
+                let in_race: Vec<Uuid> = race
+                    .participants
+                    .iter()
+                    .map(|p| p.player_uuid)
+                    .filter(|uuid| friends.contains(uuid))
+                    .collect();
+                FriendRaceResponse::new(race, &in_race)
+            })
+            .collect(),
+    ))
+}
+
+/// Suggest waiting races for a player to join
+///
+/// Races some of the player's friends already joined come first, then the
+/// ones closest to full. Races whose skill bracket leaves out the player's
+/// skill rating, or whose car tier leaves out all of the player's complete
+/// cars, are not suggested.
+#[utoipa::path(
+    get,
+    path = "/api/v1/players/{player_uuid}/matchmaking",
+    params(
+        ("player_uuid" = String, Path, description = "Player's UUID")
+    ),
+    responses(
+        (status = 200, description = "Suggested races, best first", body = Vec<FriendRaceResponse>),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse),
+        (status = 401, description = "Authentication required"),
+        (status = 404, description = "Player not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "races"
+)]
+#[tracing::instrument(name = "Matching player with races", skip(database))]
+pub async fn get_matchmaking(
+    State(database): State<Database>,
+    Path(player_uuid): Path<String>,
+) -> Result<Json<Vec<FriendRaceResponse>>, ApiError> {
+    let player_uuid = parse_uuid(&player_uuid)?;
+    let player = find_player(&database, player_uuid).await?;
+    let friends = get_friend_uuids(&MongoFriendshipRepository::new(&database), player_uuid)
+        .await
+        .map_err(|e| repository_error(&e))?;
+
+    let car_ratings: Vec<u32> = player
+        .cars
+        .iter()
+        .filter_map(|car| CarValidationService::validate_owned_car(&player, car.uuid).ok())
+        .map(|car_data| car_data.performance_rating())
+        .collect();
+    let skill_rating = get_skill_rating_from_db(&database, player_uuid)
+        .await
+        .map_err(|e| database_error(&e))?;
+    let races = get_waiting_races_from_db(&database)
+        .await
+        .map_err(|e| database_error(&e))?;
+
+    Ok(Json(
+        rank_open_races(races, player_uuid, &friends, skill_rating, &car_ratings)
+            .iter()
+            .take(MAX_SUGGESTIONS)
+            .map(|ranked| FriendRaceResponse::new(&ranked.race, &ranked.friends))
+            .collect(),
+    ))
+}
+
+/// Races waiting to start or in progress with any of `friends` in them,
+/// most recently updated first
+async fn get_friend_races_from_db(
+    database: &Database,
+    friends: &HashSet<Uuid>,
+) -> Result<Vec<Race>, mongodb::error::Error> {
+    let statuses = [
+        RaceStatus::Waiting,
+        RaceStatus::Starting,
+        RaceStatus::InProgress,
+    ];
+    let filter = doc! {
+        "status": { "$in": mongodb::bson::to_bson(&statuses)? },
+        "participants.player_uuid": {
+            "$in": friends.iter().map(Uuid::to_string).collect::<Vec<_>>()
+        },
+    };
+    let options = FindOptions::builder()
+        .sort(doc! { "updated_at": -1 })
+        .build();
+    find_races(database, filter, options).await
+}
+
+async fn get_waiting_races_from_db(
+    database: &Database,
+) -> Result<Vec<Race>, mongodb::error::Error> {
+    let filter = doc! { "status": mongodb::bson::to_bson(&RaceStatus::Waiting)? };
+    find_races(database, filter, None).await
+}
+
+async fn find_races(
+    database: &Database,
+    filter: Document,
+    options: impl Into<Option<FindOptions>>,
+) -> Result<Vec<Race>, mongodb::error::Error> {
+    let mut cursor = database
+        .collection::<Race>("races")
+        .find(filter, options)
+        .await?;
+    let mut races = Vec::new();
+    while cursor.advance().await? {
+        races.push(cursor.deserialize_current()?);
+    }
+    Ok(races)
+}
+
+/// Team names of the given players, for those who still exist
+async fn get_team_names_from_db(
+    database: &Database,
+    player_uuids: &[Uuid],
+) -> Result<HashMap<Uuid, String>, mongodb::error::Error> {
+    if player_uuids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let filter = doc! {
+        "uuid": { "$in": player_uuids.iter().map(Uuid::to_string).collect::<Vec<_>>() }
+    };
+    let options = FindOptions::builder()
+        .projection(doc! { "uuid": 1, "team_name": 1 })
+        .build();
+    let mut cursor = database
+        .collection::<Document>("players")
+        .find(filter, options)
+        .await?;
+
+    let mut team_names = HashMap::new();
+    while cursor.advance().await? {
+        let player: Document = cursor.deserialize_current()?;
+        if let (Ok(uuid), Ok(team_name)) = (player.get_str("uuid"), player.get_str("team_name")) {
+            if let Ok(uuid) = Uuid::parse_str(uuid) {
+                team_names.insert(uuid, team_name.to_string());
+            }
+        }
+    }
+    Ok(team_names)
+}
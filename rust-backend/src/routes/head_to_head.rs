@@ -0,0 +1,318 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+    routing::get,
+    Router,
+};
+use futures_util::TryStreamExt;
+use mongodb::{
+    bson::{doc, Bson, Document},
+    Database,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::database::union_with_archived_races;
+use crate::domain::RaceStatus;
+use crate::routes::players::get_player_by_uuid_from_db;
+use crate::routes::races::ErrorResponse;
+
+/// Track both players raced on
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CommonTrack {
+    pub track_uuid: String,
+    pub track_name: String,
+    pub races: u32,
+}
+
+/// Results of two players over the finished races they both took part in
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HeadToHeadResponse {
+    pub player_uuid: String,
+    pub opponent_uuid: String,
+    /// Finished races both players took part in
+    pub races: u32,
+    /// Races where the player finished ahead of the opponent
+    pub wins: u32,
+    /// Races where the opponent finished ahead of the player
+    pub opponent_wins: u32,
+    /// Average of the opponent's finish position minus the player's, over races
+    /// both finished; positive when the player usually finishes ahead
+    pub average_finish_gap: Option<f64>,
+    /// Most raced first
+    pub common_tracks: Vec<CommonTrack>,
+}
+
+/// Result of the `$facet` stage
+#[derive(Deserialize)]
+struct HeadToHeadFacets {
+    summary: Vec<HeadToHeadSummary>,
+    tracks: Vec<CommonTrack>,
+}
+
+#[derive(Deserialize)]
+struct HeadToHeadSummary {
+    races: u32,
+    wins: u32,
+    opponent_wins: u32,
+    average_finish_gap: Option<f64>,
+}
+
+pub fn routes() -> Router<Database> {
+    Router::new().route(
+        "/players/:player_uuid/vs/:opponent_uuid",
+        get(get_head_to_head),
+    )
+}
+
+/// Condition on a race that `player_uuid` took part in as a human player
+fn raced_as_human(player_uuid: Uuid) -> Document {
+    doc! { "participants": { "$elemMatch": {
+        "player_uuid": player_uuid.to_string(),
+        "bot": null,
+        "ghost": null,
+    } } }
+}
+
+/// Finish position of `player_uuid` in the race, or null if unplaced
+fn finish_position_of(player_uuid: Uuid) -> Document {
+    doc! { "$ifNull": [
+        { "$arrayElemAt": [
+            { "$map": {
+                "input": { "$filter": {
+                    "input": "$participants",
+                    "cond": { "$and": [
+                        { "$eq": ["$$this.player_uuid", player_uuid.to_string()] },
+                        { "$eq": [{ "$ifNull": ["$$this.ghost", null] }, null] },
+                    ] },
+                } },
+                "in": "$$this.finish_position",
+            } },
+            0,
+        ] },
+        null,
+    ] }
+}
+
+/// Whether the finish position in `ahead` beats the one in `behind`
+/// An unplaced participant is behind every placed one.
+fn finished_ahead(ahead: &str, behind: &str) -> Bson {
+    Bson::Document(doc! { "$and": [
+        { "$ne": [ahead, null] },
+        { "$or": [{ "$eq": [behind, null] }, { "$lt": [ahead, behind] }] },
+    ] })
+}
+
+/// Aggregation comparing two players over the finished races they shared
+///
+/// Ghost replays carry the UUID of the player who recorded them, so races
+/// against a player's ghost are not counted.
+#[must_use]
+pub fn head_to_head_pipeline(player_uuid: Uuid, opponent_uuid: Uuid) -> Vec<Document> {
+    let mut race_filter = doc! {
+        "status": mongodb::bson::to_bson(&RaceStatus::Finished).unwrap_or_default(),
+    };
+    race_filter.insert(
+        "$and",
+        vec![raced_as_human(player_uuid), raced_as_human(opponent_uuid)],
+    );
+    let both_placed = doc! { "$and": [
+        { "$ne": ["$player_position", null] },
+        { "$ne": ["$opponent_position", null] },
+    ] };
+
+    vec![
+        doc! { "$match": race_filter.clone() },
+        union_with_archived_races(race_filter),
+        doc! { "$project": {
+            "_id": 0,
+            "track_uuid": "$track.uuid",
+            "track_name": "$track.name",
+            "player_position": finish_position_of(player_uuid),
+            "opponent_position": finish_position_of(opponent_uuid),
+        } },
+        doc! { "$facet": {
+            "summary": [
+                { "$group": {
+                    "_id": null,
+                    "races": { "$sum": 1 },
+                    "wins": { "$sum": { "$cond": [
+                        finished_ahead("$player_position", "$opponent_position"), 1, 0
+                    ] } },
+                    "opponent_wins": { "$sum": { "$cond": [
+                        finished_ahead("$opponent_position", "$player_position"), 1, 0
+                    ] } },
+                    // `$avg` skips the nulls of races one of them did not finish
+                    "average_finish_gap": { "$avg": { "$cond": [
+                        both_placed,
+                        { "$subtract": ["$opponent_position", "$player_position"] },
+                        null,
+                    ] } },
+                } },
+            ],
+            "tracks": [
+                { "$group": {
+                    "_id": "$track_uuid",
+                    "track_name": { "$first": "$track_name" },
+                    "races": { "$sum": 1 },
+                } },
+                { "$sort": { "races": -1, "_id": 1 } },
+                { "$project": {
+                    "_id": 0,
+                    "track_uuid": "$_id",
+                    "track_name": 1,
+                    "races": 1,
+                } },
+            ],
+        } },
+    ]
+}
+
+/// Head-to-head statistics of two players
+///
+/// Compares the two players over every finished race they both took part in:
+/// how often each finished ahead, the average gap between their finish
+/// positions and the tracks they met on.
+#[utoipa::path(
+    get,
+    path = "/api/v1/players/{player_uuid}/vs/{opponent_uuid}",
+    params(
+        ("player_uuid" = String, Path, description = "Player UUID"),
+        ("opponent_uuid" = String, Path, description = "Opponent UUID")
+    ),
+    responses(
+        (status = 200, description = "Head-to-head statistics", body = HeadToHeadResponse),
+        (status = 400, description = "Invalid UUID or same player twice", body = ErrorResponse),
+        (status = 404, description = "Player not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Getting head-to-head statistics", skip(database))]
+pub async fn get_head_to_head(
+    State(database): State<Database>,
+    Path((player_uuid_str, opponent_uuid_str)): Path<(String, String)>,
+) -> Result<Json<HeadToHeadResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let error = |status: StatusCode, error: &str, message: &str| {
+        (
+            status,
+            Json(ErrorResponse {
+                error: error.to_string(),
+                message: message.to_string(),
+                details: None,
+            }),
+        )
+    };
+    let database_error = |e: mongodb::error::Error| {
+        tracing::error!("Failed to compute head-to-head statistics: {:?}", e);
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "DATABASE_ERROR",
+            "Internal server error",
+        )
+    };
+
+    let (Ok(player_uuid), Ok(opponent_uuid)) = (
+        Uuid::parse_str(&player_uuid_str),
+        Uuid::parse_str(&opponent_uuid_str),
+    ) else {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_UUID",
+            "Invalid UUID format",
+        ));
+    };
+    if player_uuid == opponent_uuid {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "SAME_PLAYER",
+            "A player cannot be compared with themselves",
+        ));
+    }
+
+    for uuid in [player_uuid, opponent_uuid] {
+        if get_player_by_uuid_from_db(&database, uuid)
+            .await
+            .map_err(database_error)?
+            .is_none()
+        {
+            tracing::warn!("Player not found for UUID: {}", uuid);
+            return Err(error(
+                StatusCode::NOT_FOUND,
+                "PLAYER_NOT_FOUND",
+                "Player not found",
+            ));
+        }
+    }
+
+    let pipeline = head_to_head_pipeline(player_uuid, opponent_uuid);
+    let facets = fetch_head_to_head(&database, pipeline)
+        .await
+        .map_err(database_error)?;
+    let summary = facets.summary.into_iter().next();
+
+    Ok(Json(HeadToHeadResponse {
+        player_uuid: player_uuid.to_string(),
+        opponent_uuid: opponent_uuid.to_string(),
+        races: summary.as_ref().map_or(0, |s| s.races),
+        wins: summary.as_ref().map_or(0, |s| s.wins),
+        opponent_wins: summary.as_ref().map_or(0, |s| s.opponent_wins),
+        average_finish_gap: summary.and_then(|s| s.average_finish_gap),
+        common_tracks: facets.tracks,
+    }))
+}
+
+async fn fetch_head_to_head(
+    database: &Database,
+    pipeline: Vec<Document>,
+) -> Result<HeadToHeadFacets, mongodb::error::Error> {
+    let mut cursor = database
+        .collection::<Document>("races")
+        .aggregate(pipeline, None)
+        .await?;
+
+    // `$facet` always yields exactly one document
+    let facets = cursor.try_next().await?.unwrap_or_default();
+    Ok(mongodb::bson::from_document(facets)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_finished_races_with_both_humans_are_matched() {
+        let (player, opponent) = (Uuid::new_v4(), Uuid::new_v4());
+        let pipeline = head_to_head_pipeline(player, opponent);
+        let filter = pipeline[0].get_document("$match").unwrap();
+
+        assert_eq!(filter.get_str("status").unwrap(), "Finished");
+        let conditions = filter.get_array("$and").unwrap();
+        assert_eq!(conditions.len(), 2);
+        for (condition, uuid) in conditions.iter().zip([player, opponent]) {
+            let participant = condition
+                .as_document()
+                .unwrap()
+                .get_document("participants")
+                .unwrap()
+                .get_document("$elemMatch")
+                .unwrap();
+            assert_eq!(
+                participant.get_str("player_uuid").unwrap(),
+                uuid.to_string()
+            );
+            assert_eq!(participant.get("ghost"), Some(&Bson::Null));
+        }
+    }
+
+    #[test]
+    fn summary_and_tracks_are_computed_together() {
+        let pipeline = head_to_head_pipeline(Uuid::new_v4(), Uuid::new_v4());
+        let facet = pipeline.last().unwrap().get_document("$facet").unwrap();
+
+        assert!(facet.contains_key("summary"));
+        assert!(facet.contains_key("tracks"));
+    }
+}
@@ -1,5 +1,7 @@
 use axum::{extract::State, http::StatusCode, response::Json};
 use mongodb::{bson::doc, Database};
+
+use crate::database::database_breaker;
 use serde::Serialize;
 use utoipa::ToSchema;
 
@@ -14,14 +16,29 @@ pub struct HealthResponse {
     get,
     path = "/health_check",
     responses(
-        (status = 200, description = "Service is healthy", body = HealthResponse)
+        (status = 200, description = "Service is healthy", body = HealthResponse),
+        (status = 503, description = "Database circuit is open after repeated failures", body = HealthResponse)
     ),
     tag = "health"
 )]
 #[tracing::instrument(name = "Health check", skip(database))]
 pub async fn health_check(
     State(database): State<Database>,
-) -> Result<Json<HealthResponse>, StatusCode> {
+) -> Result<Json<HealthResponse>, (StatusCode, Json<HealthResponse>)> {
+    if let Some(retry_after) = database_breaker().open_for() {
+        tracing::warn!("Health check failed - database circuit open");
+        return Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(HealthResponse {
+                status: "unavailable".to_string(),
+                message: format!(
+                    "Database requests are failing fast, next attempt in {}s",
+                    retry_after.as_secs().max(1)
+                ),
+            }),
+        ));
+    }
+
     // For health check, we'll try to list collections which is a simple operation
     match database.list_collection_names(None).await {
         Ok(_) => {
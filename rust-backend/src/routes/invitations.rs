@@ -0,0 +1,166 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+    routing::post,
+    Router,
+};
+use mongodb::Database;
+use serde::Deserialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::{Notification, RaceStatus};
+use crate::routes::blocks::is_blocked_between_in_db;
+use crate::routes::players::{get_player_by_uuid_from_db, insert_notification_in_db};
+use crate::routes::races::{get_race_by_uuid, ErrorResponse};
+use crate::services::{record_audit, AuditEntry};
+
+type ApiError = (StatusCode, Json<ErrorResponse>);
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct InvitePlayerRequest {
+    /// Race creator sending the invitation
+    pub player_uuid: String,
+    pub invited_player_uuid: String,
+}
+
+pub fn routes() -> Router<Database> {
+    Router::new().route("/races/:race_uuid/invitations", post(invite_player))
+}
+
+fn error(status: StatusCode, error: &str, message: &str) -> ApiError {
+    (
+        status,
+        Json(ErrorResponse {
+            error: error.to_string(),
+            message: message.to_string(),
+            details: None,
+        }),
+    )
+}
+
+fn database_error(e: &mongodb::error::Error) -> ApiError {
+    tracing::error!("Invitation storage failed: {:?}", e);
+    error(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "DATABASE_ERROR",
+        "Internal server error",
+    )
+}
+
+/// Invite a player to a waiting race
+///
+/// Only the race creator can invite. The invited player finds the invitation
+/// among their notifications, and on their devices and by email depending on
+/// their settings.
+#[utoipa::path(
+    post,
+    path = "/api/v1/races/{race_uuid}/invitations",
+    params(
+        ("race_uuid" = String, Path, description = "Race UUID")
+    ),
+    request_body = InvitePlayerRequest,
+    responses(
+        (status = 201, description = "Player invited", body = Notification),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse),
+        (status = 403, description = "Requester is not the race creator, or one player blocked the other", body = ErrorResponse),
+        (status = 404, description = "Race or invited player not found", body = ErrorResponse),
+        (status = 409, description = "Race already started or player already in it", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "races"
+)]
+#[tracing::instrument(name = "Inviting player to race", skip(database, request))]
+pub async fn invite_player(
+    State(database): State<Database>,
+    Path(race_uuid): Path<String>,
+    Json(request): Json<InvitePlayerRequest>,
+) -> Result<(StatusCode, Json<Notification>), ApiError> {
+    let (Ok(race_uuid), Ok(player_uuid), Ok(invitee_uuid)) = (
+        Uuid::parse_str(&race_uuid),
+        Uuid::parse_str(&request.player_uuid),
+        Uuid::parse_str(&request.invited_player_uuid),
+    ) else {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_UUID",
+            "Invalid UUID format",
+        ));
+    };
+
+    let Some(race) = get_race_by_uuid(&database, race_uuid)
+        .await
+        .map_err(|e| database_error(&e))?
+    else {
+        return Err(error(
+            StatusCode::NOT_FOUND,
+            "RACE_NOT_FOUND",
+            "Race not found",
+        ));
+    };
+    if race.creator() != Some(player_uuid) {
+        return Err(error(
+            StatusCode::FORBIDDEN,
+            "NOT_RACE_CREATOR",
+            "Only the race creator can invite players",
+        ));
+    }
+    if race.status != RaceStatus::Waiting {
+        return Err(error(
+            StatusCode::CONFLICT,
+            "RACE_NOT_WAITING",
+            "Players can only be invited before the race starts",
+        ));
+    }
+    if race
+        .participants
+        .iter()
+        .any(|p| p.player_uuid == invitee_uuid)
+    {
+        return Err(error(
+            StatusCode::CONFLICT,
+            "ALREADY_PARTICIPANT",
+            "The player already takes part in the race",
+        ));
+    }
+
+    let (creator, invitee) = tokio::try_join!(
+        get_player_by_uuid_from_db(&database, player_uuid),
+        get_player_by_uuid_from_db(&database, invitee_uuid),
+    )
+    .map_err(|e| database_error(&e))?;
+    if invitee.is_none() {
+        return Err(error(
+            StatusCode::NOT_FOUND,
+            "PLAYER_NOT_FOUND",
+            "Invited player not found",
+        ));
+    }
+    if is_blocked_between_in_db(&database, player_uuid, invitee_uuid)
+        .await
+        .map_err(|e| database_error(&e))?
+    {
+        return Err(error(
+            StatusCode::FORBIDDEN,
+            "PLAYER_BLOCKED",
+            "This player cannot be invited",
+        ));
+    }
+    let inviter_name = creator.map_or_else(
+        || "A race creator".to_string(),
+        |creator| creator.team_name.as_ref().to_string(),
+    );
+
+    let notification = Notification::race_invitation(invitee_uuid, &race, &inviter_name);
+    insert_notification_in_db(&database, &notification)
+        .await
+        .map_err(|e| database_error(&e))?;
+
+    let audit = AuditEntry::new("race.invite")
+        .by(player_uuid)
+        .race(race_uuid)
+        .player(invitee_uuid);
+    record_audit(&database, audit).await;
+    Ok((StatusCode::CREATED, Json(notification)))
+}
@@ -0,0 +1,290 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::Json,
+    routing::get,
+    Router,
+};
+use futures_util::TryStreamExt;
+use mongodb::{
+    bson::{doc, Document},
+    Database,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::database::union_with_archived_races;
+use crate::domain::{RaceMode, RaceStatus};
+use crate::routes::races::ErrorResponse;
+
+/// Page size used when none is requested
+pub const DEFAULT_LEADERBOARD_PAGE_SIZE: u32 = 20;
+/// Largest page a client may request
+pub const MAX_LEADERBOARD_PAGE_SIZE: u32 = 100;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct LeaderboardQuery {
+    /// 1-based page number (defaults to 1)
+    pub page: Option<u32>,
+    /// Players per page (defaults to 20, at most 100)
+    pub page_size: Option<u32>,
+    /// Only count races run on this track
+    pub track_uuid: Option<String>,
+}
+
+/// Career results of one player over finished standard races
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CareerStanding {
+    #[serde(default)]
+    pub rank: u64,
+    pub player_uuid: String,
+    pub team_name: Option<String>,
+    pub races: u32,
+    pub wins: u32,
+    pub podiums: u32,
+    pub average_finish: f64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LeaderboardResponse {
+    pub standings: Vec<CareerStanding>,
+    /// Players with at least one counted race
+    pub total_players: u64,
+    pub page: u32,
+    pub page_size: u32,
+}
+
+/// Result of the `$facet` stage
+#[derive(Deserialize)]
+struct LeaderboardPage {
+    standings: Vec<CareerStanding>,
+    total: Vec<TotalCount>,
+}
+
+#[derive(Deserialize)]
+struct TotalCount {
+    count: u64,
+}
+
+pub fn routes() -> Router<Database> {
+    Router::new().route("/leaderboard", get(get_leaderboard))
+}
+
+/// Bson value of a unit enum variant as stored in race documents
+fn stored_name<T: Serialize>(value: &T) -> String {
+    mongodb::bson::to_bson(value)
+        .ok()
+        .and_then(|bson| bson.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// Aggregation ranking human players over finished races that count for rankings
+///
+/// Participants are grouped and sorted by the server, so only one page of
+/// standings (with the matching team names) is sent back.
+#[must_use]
+pub fn leaderboard_pipeline(track_uuid: Option<Uuid>, page: u32, page_size: u32) -> Vec<Document> {
+    // Races stored before modes existed have no `mode` and are standard races
+    let mut race_filter = doc! {
+        "status": stored_name(&RaceStatus::Finished),
+        "mode": { "$in": [null, stored_name(&RaceMode::Standard)] },
+    };
+    if let Some(track_uuid) = track_uuid {
+        race_filter.insert("track.uuid", track_uuid.to_string());
+    }
+    let skip = u64::from(page.saturating_sub(1)) * u64::from(page_size);
+
+    vec![
+        doc! { "$match": race_filter.clone() },
+        union_with_archived_races(race_filter),
+        doc! { "$project": {
+            "_id": 0,
+            "participants.player_uuid": 1,
+            "participants.finish_position": 1,
+            "participants.bot": 1,
+            "participants.ghost": 1,
+        } },
+        doc! { "$unwind": "$participants" },
+        // Bots and ghosts are not ranked
+        doc! { "$match": {
+            "participants.bot": null,
+            "participants.ghost": null,
+            "participants.finish_position": { "$ne": null },
+        } },
+        doc! { "$group": {
+            "_id": "$participants.player_uuid",
+            "races": { "$sum": 1 },
+            "wins": { "$sum": {
+                "$cond": [{ "$eq": ["$participants.finish_position", 1] }, 1, 0]
+            } },
+            "podiums": { "$sum": {
+                "$cond": [{ "$lte": ["$participants.finish_position", 3] }, 1, 0]
+            } },
+            "average_finish": { "$avg": "$participants.finish_position" },
+        } },
+        doc! { "$sort": { "wins": -1, "podiums": -1, "average_finish": 1, "_id": 1 } },
+        doc! { "$facet": {
+            "standings": [
+                { "$skip": i64::try_from(skip).unwrap_or(i64::MAX) },
+                { "$limit": i64::from(page_size) },
+                { "$lookup": {
+                    "from": "players",
+                    "let": { "player_uuid": "$_id" },
+                    "pipeline": [
+                        { "$match": { "$expr": { "$eq": ["$uuid", "$$player_uuid"] } } },
+                        { "$project": { "_id": 0, "team_name": 1 } },
+                    ],
+                    "as": "player",
+                } },
+                { "$project": {
+                    "_id": 0,
+                    "player_uuid": "$_id",
+                    "team_name": { "$arrayElemAt": ["$player.team_name", 0] },
+                    "races": 1,
+                    "wins": 1,
+                    "podiums": 1,
+                    "average_finish": 1,
+                } },
+            ],
+            "total": [{ "$count": "count" }],
+        } },
+    ]
+}
+
+/// Career leaderboard
+///
+/// Ranks players by wins, then podiums, then average finishing position over
+/// finished standard races. Practice races, time trials, bots and ghosts are
+/// left out.
+#[utoipa::path(
+    get,
+    path = "/api/v1/leaderboard",
+    params(LeaderboardQuery),
+    responses(
+        (status = 200, description = "One page of career standings", body = LeaderboardResponse),
+        (status = 400, description = "Invalid UUID or page", body = ErrorResponse),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "API token lacks the read:leaderboard scope"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "leaderboard"
+)]
+#[tracing::instrument(name = "Getting career leaderboard", skip(database))]
+pub async fn get_leaderboard(
+    State(database): State<Database>,
+    Query(params): Query<LeaderboardQuery>,
+) -> Result<Json<LeaderboardResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let bad_request = |message: &str| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "INVALID_QUERY".to_string(),
+                message: message.to_string(),
+                details: None,
+            }),
+        )
+    };
+
+    let track_uuid = params
+        .track_uuid
+        .as_deref()
+        .map(Uuid::parse_str)
+        .transpose()
+        .map_err(|_| bad_request("Invalid UUID format"))?;
+    let page = params.page.unwrap_or(1);
+    if page == 0 {
+        return Err(bad_request("Pages start at 1"));
+    }
+    let page_size = params
+        .page_size
+        .unwrap_or(DEFAULT_LEADERBOARD_PAGE_SIZE)
+        .clamp(1, MAX_LEADERBOARD_PAGE_SIZE);
+
+    let pipeline = leaderboard_pipeline(track_uuid, page, page_size);
+    let result = fetch_leaderboard_page(&database, pipeline)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to aggregate leaderboard: {:?}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: "DATABASE_ERROR".to_string(),
+                    message: "Internal server error".to_string(),
+                    details: Some(format!("Failed to aggregate leaderboard: {e}")),
+                }),
+            )
+        })?;
+
+    let first_rank = u64::from(page - 1) * u64::from(page_size) + 1;
+    let mut standings = result.standings;
+    for (rank, standing) in (first_rank..).zip(standings.iter_mut()) {
+        standing.rank = rank;
+    }
+
+    Ok(Json(LeaderboardResponse {
+        standings,
+        total_players: result.total.first().map_or(0, |total| total.count),
+        page,
+        page_size,
+    }))
+}
+
+async fn fetch_leaderboard_page(
+    database: &Database,
+    pipeline: Vec<Document>,
+) -> Result<LeaderboardPage, mongodb::error::Error> {
+    let mut cursor = database
+        .collection::<Document>("races")
+        .aggregate(pipeline, None)
+        .await?;
+
+    // `$facet` always yields exactly one document
+    let page = cursor.try_next().await?.unwrap_or_default();
+    Ok(mongodb::bson::from_document(page)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_finished_standard_races_are_counted() {
+        let pipeline = leaderboard_pipeline(None, 1, 20);
+        let filter = pipeline[0].get_document("$match").unwrap();
+
+        assert_eq!(filter.get_str("status").unwrap(), "Finished");
+        let modes = filter
+            .get_document("mode")
+            .unwrap()
+            .get_array("$in")
+            .unwrap();
+        assert!(modes.contains(&mongodb::bson::Bson::Null));
+        assert!(modes.contains(&mongodb::bson::Bson::String("Standard".to_string())));
+        assert!(!filter.contains_key("track.uuid"));
+    }
+
+    #[test]
+    fn track_filter_is_applied_to_races() {
+        let track_uuid = Uuid::new_v4();
+        let pipeline = leaderboard_pipeline(Some(track_uuid), 1, 20);
+        let filter = pipeline[0].get_document("$match").unwrap();
+
+        assert_eq!(
+            filter.get_str("track.uuid").unwrap(),
+            track_uuid.to_string()
+        );
+    }
+
+    #[test]
+    fn only_the_requested_page_is_returned() {
+        let pipeline = leaderboard_pipeline(None, 3, 25);
+        let facet = pipeline.last().unwrap().get_document("$facet").unwrap();
+        let stages = facet.get_array("standings").unwrap();
+
+        let skip = stages[0].as_document().unwrap().get_i64("$skip").unwrap();
+        let limit = stages[1].as_document().unwrap().get_i64("$limit").unwrap();
+        assert_eq!(skip, 50);
+        assert_eq!(limit, 25);
+    }
+}
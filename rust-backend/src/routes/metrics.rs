@@ -0,0 +1,46 @@
+use axum::{
+    extract::State,
+    http::header,
+    response::{IntoResponse, Response},
+    Extension,
+};
+use metrics_exporter_prometheus::PrometheusHandle;
+use mongodb::{bson::doc, Database};
+
+use crate::domain::Race;
+use crate::services::metrics::RACES_IN_PROGRESS;
+
+/// Prometheus metrics endpoint
+///
+/// Serves request latencies per route, lap resolution times, `MongoDB`
+/// command timings and the races in progress in the Prometheus text format.
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    responses(
+        (status = 200, description = "Metrics in the Prometheus text format", body = String, content_type = "text/plain")
+    ),
+    tag = "health"
+)]
+#[tracing::instrument(name = "Rendering metrics", skip(database, prometheus))]
+pub async fn get_metrics(
+    State(database): State<Database>,
+    Extension(prometheus): Extension<PrometheusHandle>,
+) -> Response {
+    // A gauge read from the database is only as fresh as the last scrape
+    match database
+        .collection::<Race>("races")
+        .count_documents(doc! { "status": "InProgress" }, None)
+        .await
+    {
+        #[allow(clippy::cast_precision_loss)]
+        Ok(count) => metrics::gauge!(RACES_IN_PROGRESS).set(count as f64),
+        Err(e) => tracing::warn!("Races in progress not counted: {:?}", e),
+    }
+
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        prometheus.render(),
+    )
+        .into_response()
+}
@@ -1,6 +1,37 @@
+pub mod api_tokens;
+pub mod audit;
 pub mod auth;
+pub mod blocks;
+pub mod cars;
+pub mod chat;
+pub mod crafting;
+pub mod discord;
+pub mod etag;
+pub mod fantasy;
+pub mod field_selection;
+pub mod friends;
+pub mod head_to_head;
 mod health_check;
+pub mod invitations;
+pub mod leaderboard;
+mod metrics;
+pub mod moderation;
+pub mod oauth;
+pub mod packs;
+pub mod pagination;
+pub mod payouts;
 pub mod players;
+pub mod predictions;
+pub mod privacy;
 pub mod races;
+pub mod reactions;
+pub mod snapshots;
+pub mod spectators;
+pub mod stats;
+pub mod tracks;
+pub mod trades;
+pub mod v2;
+pub mod webhooks;
 
 pub use health_check::*;
+pub use metrics::*;
@@ -0,0 +1,569 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+    routing::{delete, get, post},
+    Extension, Router,
+};
+use mongodb::{
+    bson::{doc, to_bson, DateTime as BsonDateTime},
+    options::{FindOptions, ReplaceOptions},
+    Database,
+};
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::database::is_duplicate_key;
+use crate::domain::{
+    CheatFlag, CheatFlagStatus, CheatFlagVerdict, ModerationAction, PlayerBan, Report,
+    ReportReason, ReportStatus, MAX_REPORT_DETAILS_LENGTH,
+};
+use crate::middleware::UserContext;
+use crate::routes::players::get_player_by_uuid_from_db;
+use crate::routes::races::ErrorResponse;
+use crate::services::anticheat::CHEAT_FLAGS;
+use crate::services::{record_audit, AuditEntry};
+
+const REPORTS: &str = "reports";
+const PLAYER_BANS: &str = "player_bans";
+const DEFAULT_QUEUE_LIMIT: i64 = 100;
+const MAX_QUEUE_LIMIT: i64 = 1000;
+
+type ApiError = (StatusCode, Json<ErrorResponse>);
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateReportRequest {
+    pub reported_uuid: String,
+    pub reason: ReportReason,
+    /// What happened, e.g. the offending chat message; at most 1000 characters
+    pub details: Option<String>,
+    /// Race the abuse happened in, if any
+    pub race_uuid: Option<String>,
+}
+
+/// Which reports the moderation queue lists
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ReportQueueQuery {
+    /// Reports in this status, `Open` by default
+    pub status: Option<ReportStatus>,
+    /// Most reports returned, 100 by default and at most 1000
+    pub limit: Option<i64>,
+}
+
+/// Which flags the anti-cheat review queue lists
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct CheatFlagQueueQuery {
+    /// Flags in this status, `Open` by default
+    pub status: Option<CheatFlagStatus>,
+    /// Most flags returned, 100 by default and at most 1000
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ReviewCheatFlagRequest {
+    pub verdict: CheatFlagVerdict,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ResolveReportRequest {
+    pub action: ModerationAction,
+    /// Explanation kept with the report and the ban
+    pub note: Option<String>,
+}
+
+/// Routes for players; mounted behind authentication, as the reporter is the
+/// token's player
+pub fn routes() -> Router<Database> {
+    Router::new().route("/reports", post(create_report))
+}
+
+/// Routes for administrators; mounted behind authentication and the admin role
+pub fn admin_routes() -> Router<Database> {
+    Router::new()
+        .route("/reports", get(get_report_queue))
+        .route("/reports/:report_uuid/resolve", post(resolve_report))
+        .route("/players/:player_uuid/ban", delete(lift_ban))
+        .route("/cheat-flags", get(get_cheat_flag_queue))
+        .route("/cheat-flags/:flag_uuid/review", post(review_cheat_flag))
+}
+
+fn error(status: StatusCode, error: &str, message: &str) -> ApiError {
+    (
+        status,
+        Json(ErrorResponse {
+            error: error.to_string(),
+            message: message.to_string(),
+            details: None,
+        }),
+    )
+}
+
+fn database_error(e: &mongodb::error::Error) -> ApiError {
+    tracing::error!("Moderation storage failed: {:?}", e);
+    error(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "DATABASE_ERROR",
+        "Internal server error",
+    )
+}
+
+fn parse_uuid(value: &str) -> Result<Uuid, ApiError> {
+    Uuid::parse_str(value).map_err(|_| {
+        error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_UUID",
+            "Invalid UUID format",
+        )
+    })
+}
+
+/// Report a player for an abusive name or abusive chat
+///
+/// The reporter is the player the access token belongs to, who can have one
+/// open report against the same player at a time.
+#[utoipa::path(
+    post,
+    path = "/api/v1/reports",
+    request_body = CreateReportRequest,
+    responses(
+        (status = 201, description = "Report filed", body = Report),
+        (status = 400, description = "Invalid UUID, own UUID or details too long", body = ErrorResponse),
+        (status = 401, description = "Authentication required"),
+        (status = 404, description = "Player not found", body = ErrorResponse),
+        (status = 409, description = "An open report against this player already exists", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Filing player report", skip(database, user, request))]
+pub async fn create_report(
+    State(database): State<Database>,
+    Extension(user): Extension<UserContext>,
+    Json(request): Json<CreateReportRequest>,
+) -> Result<(StatusCode, Json<Report>), ApiError> {
+    let reporter_uuid = user.user_uuid;
+    let target_uuid = parse_uuid(&request.reported_uuid)?;
+    let race_uuid = request.race_uuid.as_deref().map(parse_uuid).transpose()?;
+    if reporter_uuid == target_uuid {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "CANNOT_REPORT_SELF",
+            "Players cannot report themselves",
+        ));
+    }
+    let details = request
+        .details
+        .map(|details| details.trim().to_string())
+        .filter(|details| !details.is_empty());
+    if details
+        .as_ref()
+        .is_some_and(|details| details.chars().count() > MAX_REPORT_DETAILS_LENGTH)
+    {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "DETAILS_TOO_LONG",
+            "details must be at most 1000 characters",
+        ));
+    }
+
+    let (reporter, target) = tokio::try_join!(
+        get_player_by_uuid_from_db(&database, reporter_uuid),
+        get_player_by_uuid_from_db(&database, target_uuid),
+    )
+    .map_err(|e| database_error(&e))?;
+    if reporter.is_none() || target.is_none() {
+        return Err(error(
+            StatusCode::NOT_FOUND,
+            "PLAYER_NOT_FOUND",
+            "Player not found",
+        ));
+    }
+
+    let report = Report::new(
+        reporter_uuid,
+        target_uuid,
+        request.reason,
+        details,
+        race_uuid,
+    );
+    match database
+        .collection::<Report>(REPORTS)
+        .insert_one(&report, None)
+        .await
+    {
+        Ok(_) => Ok((StatusCode::CREATED, Json(report))),
+        // A partial unique index allows one open report per pair
+        Err(e) if is_duplicate_key(&e) => Err(error(
+            StatusCode::CONFLICT,
+            "ALREADY_REPORTED",
+            "Your earlier report of this player is still open",
+        )),
+        Err(e) => Err(database_error(&e)),
+    }
+}
+
+/// List the moderation queue
+///
+/// Reports come oldest first, so the queue is worked through in order.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/reports",
+    params(ReportQueueQuery),
+    responses(
+        (status = 200, description = "Reports in the requested status", body = Vec<Report>),
+        (status = 400, description = "Invalid limit", body = ErrorResponse),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Not an administrator"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "admin"
+)]
+#[tracing::instrument(name = "Listing the moderation queue", skip(database))]
+pub async fn get_report_queue(
+    State(database): State<Database>,
+    Query(params): Query<ReportQueueQuery>,
+) -> Result<Json<Vec<Report>>, ApiError> {
+    let limit = params.limit.unwrap_or(DEFAULT_QUEUE_LIMIT);
+    if !(1..=MAX_QUEUE_LIMIT).contains(&limit) {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_LIMIT",
+            "limit must be between 1 and 1000",
+        ));
+    }
+    let status = params.status.unwrap_or(ReportStatus::Open);
+    get_reports_from_db(&database, status, limit)
+        .await
+        .map(Json)
+        .map_err(|e| database_error(&e))
+}
+
+/// Resolve a report by dismissing it or banning the reported player
+///
+/// A ban also closes the other open reports against the player.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/reports/{report_uuid}/resolve",
+    params(
+        ("report_uuid" = String, Path, description = "Report UUID")
+    ),
+    request_body = ResolveReportRequest,
+    responses(
+        (status = 200, description = "Report resolved", body = Report),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Not an administrator"),
+        (status = 404, description = "Report not found", body = ErrorResponse),
+        (status = 409, description = "Report already resolved", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "admin"
+)]
+#[tracing::instrument(name = "Resolving report", skip(database, admin, request))]
+pub async fn resolve_report(
+    State(database): State<Database>,
+    Extension(admin): Extension<UserContext>,
+    Path(report_uuid): Path<String>,
+    Json(request): Json<ResolveReportRequest>,
+) -> Result<Json<Report>, ApiError> {
+    let report_uuid = parse_uuid(&report_uuid)?;
+    let reports = database.collection::<Report>(REPORTS);
+    let Some(mut report) = reports
+        .find_one(doc! { "uuid": report_uuid.to_string() }, None)
+        .await
+        .map_err(|e| database_error(&e))?
+    else {
+        return Err(error(
+            StatusCode::NOT_FOUND,
+            "REPORT_NOT_FOUND",
+            "Report not found",
+        ));
+    };
+
+    let open = to_bson(&ReportStatus::Open).map_err(|e| database_error(&e.into()))?;
+    report.resolve(request.action, admin.user_uuid, request.note.clone());
+    // Only an open report is replaced, so two moderators cannot both resolve it
+    let replaced = reports
+        .replace_one(
+            doc! { "uuid": report_uuid.to_string(), "status": &open },
+            &report,
+            None,
+        )
+        .await
+        .map_err(|e| database_error(&e))?;
+    if replaced.matched_count == 0 {
+        return Err(error(
+            StatusCode::CONFLICT,
+            "REPORT_ALREADY_RESOLVED",
+            "The report was already resolved",
+        ));
+    }
+
+    let audit = AuditEntry::new("report.resolve")
+        .by(admin.user_uuid)
+        .player(report.reported_uuid);
+    record_audit(&database, audit).await;
+
+    if request.action == ModerationAction::Ban {
+        let ban = PlayerBan {
+            id: None,
+            player_uuid: report.reported_uuid,
+            report_uuid,
+            banned_by: admin.user_uuid,
+            note: request.note,
+            banned_at: BsonDateTime::now(),
+        };
+        ban_player_in_db(&database, &ban)
+            .await
+            .map_err(|e| database_error(&e))?;
+        let audit = AuditEntry::new("player.ban")
+            .by(admin.user_uuid)
+            .player(ban.player_uuid);
+        record_audit(&database, audit).await;
+    }
+    Ok(Json(report))
+}
+
+/// Lift a player's ban
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/players/{player_uuid}/ban",
+    params(
+        ("player_uuid" = String, Path, description = "Banned player's UUID")
+    ),
+    responses(
+        (status = 204, description = "Ban lifted"),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Not an administrator"),
+        (status = 404, description = "Player is not banned", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "admin"
+)]
+#[tracing::instrument(name = "Lifting player ban", skip(database, admin))]
+pub async fn lift_ban(
+    State(database): State<Database>,
+    Extension(admin): Extension<UserContext>,
+    Path(player_uuid): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    let player_uuid = parse_uuid(&player_uuid)?;
+    let result = database
+        .collection::<PlayerBan>(PLAYER_BANS)
+        .delete_one(doc! { "player_uuid": player_uuid.to_string() }, None)
+        .await
+        .map_err(|e| database_error(&e))?;
+    if result.deleted_count == 0 {
+        return Err(error(
+            StatusCode::NOT_FOUND,
+            "BAN_NOT_FOUND",
+            "The player is not banned",
+        ));
+    }
+
+    let audit = AuditEntry::new("player.unban")
+        .by(admin.user_uuid)
+        .player(player_uuid);
+    record_audit(&database, audit).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List the anti-cheat review queue
+///
+/// Flags come oldest first, each with the evidence the analysis measured.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/cheat-flags",
+    params(CheatFlagQueueQuery),
+    responses(
+        (status = 200, description = "Flags in the requested status", body = Vec<CheatFlag>),
+        (status = 400, description = "Invalid limit", body = ErrorResponse),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Not an administrator"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "admin"
+)]
+#[tracing::instrument(name = "Listing cheat flags", skip(database))]
+pub async fn get_cheat_flag_queue(
+    State(database): State<Database>,
+    Query(params): Query<CheatFlagQueueQuery>,
+) -> Result<Json<Vec<CheatFlag>>, ApiError> {
+    let limit = params.limit.unwrap_or(DEFAULT_QUEUE_LIMIT);
+    if !(1..=MAX_QUEUE_LIMIT).contains(&limit) {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_LIMIT",
+            "limit must be between 1 and 1000",
+        ));
+    }
+    let status = params.status.unwrap_or(CheatFlagStatus::Open);
+    get_cheat_flags_from_db(&database, status, limit)
+        .await
+        .map(Json)
+        .map_err(|e| database_error(&e))
+}
+
+/// Dismiss or confirm a cheat flag
+///
+/// Confirming records the verdict only; banning stays a separate decision.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/cheat-flags/{flag_uuid}/review",
+    params(
+        ("flag_uuid" = String, Path, description = "Cheat flag UUID")
+    ),
+    request_body = ReviewCheatFlagRequest,
+    responses(
+        (status = 200, description = "Flag reviewed", body = CheatFlag),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Not an administrator"),
+        (status = 404, description = "Flag not found", body = ErrorResponse),
+        (status = 409, description = "Flag already reviewed", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "admin"
+)]
+#[tracing::instrument(name = "Reviewing cheat flag", skip(database, admin, request))]
+pub async fn review_cheat_flag(
+    State(database): State<Database>,
+    Extension(admin): Extension<UserContext>,
+    Path(flag_uuid): Path<String>,
+    Json(request): Json<ReviewCheatFlagRequest>,
+) -> Result<Json<CheatFlag>, ApiError> {
+    let flag_uuid = parse_uuid(&flag_uuid)?;
+    let flags = database.collection::<CheatFlag>(CHEAT_FLAGS);
+    let Some(mut flag) = flags
+        .find_one(doc! { "uuid": flag_uuid.to_string() }, None)
+        .await
+        .map_err(|e| database_error(&e))?
+    else {
+        return Err(error(
+            StatusCode::NOT_FOUND,
+            "FLAG_NOT_FOUND",
+            "Cheat flag not found",
+        ));
+    };
+
+    let open = to_bson(&CheatFlagStatus::Open).map_err(|e| database_error(&e.into()))?;
+    flag.review(request.verdict, admin.user_uuid, request.note);
+    // Only an open flag is replaced, so two administrators cannot both review it
+    let replaced = flags
+        .replace_one(
+            doc! { "uuid": flag_uuid.to_string(), "status": &open },
+            &flag,
+            None,
+        )
+        .await
+        .map_err(|e| database_error(&e))?;
+    if replaced.matched_count == 0 {
+        return Err(error(
+            StatusCode::CONFLICT,
+            "FLAG_ALREADY_REVIEWED",
+            "The flag was already reviewed",
+        ));
+    }
+
+    let mut audit = AuditEntry::new("anticheat.review")
+        .by(admin.user_uuid)
+        .player(flag.player_uuid);
+    if let Some(race_uuid) = flag
+        .race_uuid
+        .as_deref()
+        .and_then(|uuid| Uuid::parse_str(uuid).ok())
+    {
+        audit = audit.race(race_uuid);
+    }
+    record_audit(&database, audit).await;
+    Ok(Json(flag))
+}
+
+/// Whether moderators banned the player
+#[tracing::instrument(name = "Checking player ban", skip(database))]
+pub async fn is_player_banned_in_db(
+    database: &Database,
+    player_uuid: Uuid,
+) -> Result<bool, mongodb::error::Error> {
+    let count = database
+        .collection::<PlayerBan>(PLAYER_BANS)
+        .count_documents(doc! { "player_uuid": player_uuid.to_string() }, None)
+        .await?;
+    Ok(count > 0)
+}
+
+/// Reports in `status`, oldest first
+async fn get_reports_from_db(
+    database: &Database,
+    status: ReportStatus,
+    limit: i64,
+) -> Result<Vec<Report>, mongodb::error::Error> {
+    let options = FindOptions::builder()
+        .sort(doc! { "created_at": 1 })
+        .limit(limit)
+        .build();
+    let mut cursor = database
+        .collection::<Report>(REPORTS)
+        .find(doc! { "status": to_bson(&status)? }, options)
+        .await?;
+    let mut reports = Vec::new();
+    while cursor.advance().await? {
+        reports.push(cursor.deserialize_current()?);
+    }
+    Ok(reports)
+}
+
+/// Cheat flags in `status`, oldest first
+async fn get_cheat_flags_from_db(
+    database: &Database,
+    status: CheatFlagStatus,
+    limit: i64,
+) -> Result<Vec<CheatFlag>, mongodb::error::Error> {
+    let options = FindOptions::builder()
+        .sort(doc! { "flagged_at": 1 })
+        .limit(limit)
+        .build();
+    let mut cursor = database
+        .collection::<CheatFlag>(CHEAT_FLAGS)
+        .find(doc! { "status": to_bson(&status)? }, options)
+        .await?;
+    let mut flags = Vec::new();
+    while cursor.advance().await? {
+        flags.push(cursor.deserialize_current()?);
+    }
+    Ok(flags)
+}
+
+/// Store the ban and close the player's other open reports with it
+async fn ban_player_in_db(
+    database: &Database,
+    ban: &PlayerBan,
+) -> Result<(), mongodb::error::Error> {
+    database
+        .collection::<PlayerBan>(PLAYER_BANS)
+        .replace_one(
+            doc! { "player_uuid": ban.player_uuid.to_string() },
+            ban,
+            ReplaceOptions::builder().upsert(true).build(),
+        )
+        .await?;
+    database
+        .collection::<Report>(REPORTS)
+        .update_many(
+            doc! {
+                "reported_uuid": ban.player_uuid.to_string(),
+                "status": to_bson(&ReportStatus::Open)?,
+            },
+            doc! { "$set": {
+                "status": to_bson(&ReportStatus::Actioned)?,
+                "resolved_at": ban.banned_at,
+                "resolved_by": ban.banned_by.to_string(),
+                "resolution_note": format!("Closed by the ban from report {}", ban.report_uuid),
+            } },
+            None,
+        )
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,454 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{
+        header::{COOKIE, LOCATION, SET_COOKIE},
+        HeaderMap, StatusCode,
+    },
+    response::Json as ResponseJson,
+    routing::get,
+    Extension, Router,
+};
+use axum_extra::extract::cookie::{Cookie, SameSite};
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use time::Duration as TimeDuration;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use crate::app_state::AppState;
+use crate::domain::{Email, OAuthIdentity, OAuthProvider, Password, Player, TeamName};
+use crate::repositories::{InMemorySessionRepository, PlayerRepository, RaceRepository};
+use crate::routes::auth::start_session;
+use crate::services::oauth::{pkce_challenge, pkce_verifier, OAuthError, OAuthProfile};
+use crate::services::session::SessionMetadata;
+use crate::services::starter_pack::StarterPack;
+use crate::services::OAuthClient;
+
+type ApiError = (StatusCode, ResponseJson<Value>);
+
+/// Cookie tying a callback to the browser that started the sign-in
+const STATE_COOKIE: &str = "oauth_state";
+/// Cookie holding the PKCE verifier the provider's code is bound to
+const VERIFIER_COOKIE: &str = "oauth_verifier";
+
+pub fn routes<P: PlayerRepository + 'static, R: RaceRepository + 'static>(
+) -> Router<AppState<P, R, InMemorySessionRepository>> {
+    Router::new()
+        .route("/auth/oauth/:provider/authorize", get(authorize))
+        .route(
+            "/auth/oauth/:provider/callback",
+            get(oauth_callback::<P, R>),
+        )
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct OAuthCallbackParams {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    /// Set by the provider when the player declined
+    pub error: Option<String>,
+}
+
+fn error(status: StatusCode, message: &str) -> ApiError {
+    (status, ResponseJson(json!({ "error": message })))
+}
+
+fn provider_error(e: &OAuthError) -> ApiError {
+    match e {
+        OAuthError::NotConfigured(_) => error(StatusCode::NOT_FOUND, &e.to_string()),
+        OAuthError::Provider(details) => {
+            tracing::warn!("OAuth sign-in failed: {}", details);
+            error(StatusCode::UNAUTHORIZED, "OAuth sign-in failed")
+        }
+    }
+}
+
+fn repository_error(e: &crate::repositories::RepositoryError) -> ApiError {
+    tracing::error!("Database error during OAuth sign-in: {:?}", e);
+    error(StatusCode::INTERNAL_SERVER_ERROR, "Database error")
+}
+
+/// Start signing in with a provider account
+#[utoipa::path(
+    get,
+    path = "/auth/oauth/{provider}/authorize",
+    params(("provider" = OAuthProvider, Path, description = "google or discord")),
+    responses(
+        (status = 302, description = "Redirect to the provider's sign-in page"),
+        (status = 404, description = "Provider not configured", body = Value)
+    ),
+    tag = "Authentication"
+)]
+pub async fn authorize(
+    Extension(oauth): Extension<OAuthClient>,
+    Path(provider): Path<OAuthProvider>,
+) -> Result<(StatusCode, [(String, String); 3]), ApiError> {
+    let state = Uuid::new_v4().simple().to_string();
+    let verifier = pkce_verifier();
+    let url = oauth
+        .authorize_url(provider, &state, &pkce_challenge(&verifier))
+        .map_err(|e| provider_error(&e))?;
+
+    Ok((
+        StatusCode::FOUND,
+        [
+            (LOCATION.to_string(), url.to_string()),
+            (SET_COOKIE.to_string(), flow_cookie(STATE_COOKIE, state)),
+            (
+                SET_COOKIE.to_string(),
+                flow_cookie(VERIFIER_COOKIE, verifier),
+            ),
+        ],
+    ))
+}
+
+/// Cookie carrying part of a sign-in in progress to the callback
+fn flow_cookie(name: &'static str, value: String) -> String {
+    // Lax, as the provider brings the browser back with a cross-site redirect
+    Cookie::build((name, value))
+        .http_only(true)
+        .secure(false) // TODO: Make this configurable for development
+        .same_site(SameSite::Lax)
+        .max_age(TimeDuration::minutes(10))
+        .path("/")
+        .build()
+        .to_string()
+}
+
+/// Cookie clearing `name` once the sign-in is over
+fn cleared_cookie(name: &'static str) -> String {
+    Cookie::build((name, ""))
+        .max_age(TimeDuration::ZERO)
+        .path("/")
+        .build()
+        .to_string()
+}
+
+/// Finish signing in with a provider account
+///
+/// The player linked to the provider account is signed in. Otherwise, a
+/// player whose email the provider verified gets the account linked, and a
+/// new player is created with the starter pack when there is none.
+#[utoipa::path(
+    get,
+    path = "/auth/oauth/{provider}/callback",
+    params(
+        ("provider" = OAuthProvider, Path, description = "google or discord"),
+        OAuthCallbackParams
+    ),
+    responses(
+        (status = 200, description = "Signed in", body = Value),
+        (status = 201, description = "Player created and signed in", body = Value),
+        (status = 400, description = "Missing code, or state or PKCE verifier not matching the sign-in", body = Value),
+        (status = 401, description = "Sign-in declined or refused by the provider", body = Value),
+        (status = 404, description = "Provider not configured", body = Value),
+        (status = 409, description = "The email belongs to a player, but the provider did not verify it", body = Value),
+        (status = 500, description = "Internal server error", body = Value)
+    ),
+    tag = "Authentication"
+)]
+pub async fn oauth_callback<P: PlayerRepository + 'static, R: RaceRepository + 'static>(
+    State(app_state): State<AppState<P, R, InMemorySessionRepository>>,
+    Extension(oauth): Extension<OAuthClient>,
+    Path(provider): Path<OAuthProvider>,
+    Query(params): Query<OAuthCallbackParams>,
+    headers: HeaderMap,
+) -> Result<(StatusCode, [(String, String); 4], ResponseJson<Value>), ApiError> {
+    let (code, verifier) = check_callback(params, &headers)?;
+    let profile = oauth
+        .profile(provider, &code, &verifier)
+        .await
+        .map_err(|e| provider_error(&e))?;
+    let (player, created) = find_or_create_player(&app_state, provider, profile).await?;
+
+    let [access_cookie, refresh_cookie] =
+        start_session(&app_state, &player, SessionMetadata::from_headers(&headers)).await?;
+
+    tracing::info!("User signed in with {}: {}", provider.as_str(), player.uuid);
+
+    Ok((
+        if created {
+            StatusCode::CREATED
+        } else {
+            StatusCode::OK
+        },
+        [
+            access_cookie,
+            refresh_cookie,
+            (SET_COOKIE.to_string(), cleared_cookie(STATE_COOKIE)),
+            (SET_COOKIE.to_string(), cleared_cookie(VERIFIER_COOKIE)),
+        ],
+        ResponseJson(json!({
+            "message": "Login successful",
+            "created": created,
+            "user": {
+                "uuid": player.uuid,
+                "email": player.email.as_ref(),
+                "team_name": player.team_name.as_ref(),
+                "role": player.role
+            }
+        })),
+    ))
+}
+
+/// The authorization code and PKCE verifier of a callback, once it is known
+/// to return to the browser that started the sign-in
+fn check_callback(
+    params: OAuthCallbackParams,
+    headers: &HeaderMap,
+) -> Result<(String, String), ApiError> {
+    if params.error.is_some() {
+        return Err(error(
+            StatusCode::UNAUTHORIZED,
+            "OAuth sign-in was declined",
+        ));
+    }
+    let expected_state = cookie_value(headers, STATE_COOKIE);
+    if expected_state.is_none() || params.state != expected_state {
+        return Err(error(StatusCode::BAD_REQUEST, "Invalid OAuth state"));
+    }
+    let verifier = cookie_value(headers, VERIFIER_COOKIE)
+        .ok_or_else(|| error(StatusCode::BAD_REQUEST, "Missing PKCE verifier"))?;
+    let code = params
+        .code
+        .ok_or_else(|| error(StatusCode::BAD_REQUEST, "Missing authorization code"))?;
+    Ok((code, verifier))
+}
+
+/// The player signing in with `profile`, and whether they were just created
+async fn find_or_create_player<P: PlayerRepository, R: RaceRepository>(
+    app_state: &AppState<P, R, InMemorySessionRepository>,
+    provider: OAuthProvider,
+    profile: OAuthProfile,
+) -> Result<(Player, bool), ApiError> {
+    let players = &app_state.player_repository;
+    if let Some(player) = players
+        .find_by_oauth_identity(provider, &profile.subject)
+        .await
+        .map_err(|e| repository_error(&e))?
+    {
+        return Ok((player, false));
+    }
+
+    let email = profile
+        .email
+        .as_deref()
+        .and_then(|email| Email::parse(email).ok())
+        .ok_or_else(|| {
+            error(
+                StatusCode::BAD_REQUEST,
+                "The provider account has no usable email",
+            )
+        })?;
+    let identity = OAuthIdentity {
+        provider,
+        subject: profile.subject,
+        email: profile.email.clone(),
+        linked_at: Utc::now(),
+    };
+
+    if let Some(existing) = players
+        .find_by_email(email.as_ref())
+        .await
+        .map_err(|e| repository_error(&e))?
+    {
+        // Linking on an unverified email would hand the account to whoever
+        // typed it in at the provider
+        if !profile.email_verified {
+            return Err(error(
+                StatusCode::CONFLICT,
+                "User with this email already exists",
+            ));
+        }
+        let player = players
+            .add_oauth_identity(existing.uuid, identity)
+            .await
+            .map_err(|e| repository_error(&e))?
+            .ok_or_else(|| error(StatusCode::INTERNAL_SERVER_ERROR, "Failed to link account"))?;
+        tracing::info!("Linked {} account to {}", provider.as_str(), player.uuid);
+        return Ok((player, false));
+    }
+
+    // Provider players have no password until they set one
+    let password_hash = Password::new(format!("{}Aa1", Uuid::new_v4().simple()))
+        .and_then(|password| password.hash())
+        .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, &e))?;
+    let team_name = match profile
+        .display_name
+        .as_deref()
+        .and_then(|name| TeamName::parse(name).ok())
+    {
+        Some(team_name) => team_name,
+        None => TeamName::parse(&format!(
+            "Team {}",
+            &Uuid::new_v4().simple().to_string()[..8]
+        ))
+        .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, &e))?,
+    };
+
+    let starter_pack =
+        StarterPack::new().map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, &e))?;
+    let mut player = starter_pack
+        .grant(email, password_hash, team_name)
+        .map_err(|e| error(StatusCode::BAD_REQUEST, &e))?;
+    player.oauth_identities.push(identity);
+
+    let player = players.create(&player).await.map_err(|e| {
+        tracing::error!("Database error inserting user: {:?}", e);
+        error(StatusCode::INTERNAL_SERVER_ERROR, "Failed to create user")
+    })?;
+    Ok((player, true))
+}
+
+/// A cookie set by [`authorize`], if the browser sent it back
+fn cookie_value(headers: &HeaderMap, name: &str) -> Option<String> {
+    let prefix = format!("{name}=");
+    headers
+        .get_all(COOKIE)
+        .iter()
+        .filter_map(|header| header.to_str().ok())
+        .flat_map(|cookies| cookies.split(';'))
+        .find_map(|cookie| cookie.trim().strip_prefix(&prefix).map(str::to_string))
+        .filter(|state| !state.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configuration::LoginThrottleSettings;
+    use crate::repositories::{InMemoryPlayerRepository, InMemoryRaceRepository};
+    use crate::services::{JwtConfig, JwtService, LoginThrottle, SessionConfig, SessionManager};
+    use axum::http::HeaderValue;
+    use std::sync::Arc;
+
+    type TestState =
+        AppState<InMemoryPlayerRepository, InMemoryRaceRepository, InMemorySessionRepository>;
+
+    fn app_state(players: Vec<Player>) -> TestState {
+        let sessions = Arc::new(InMemorySessionRepository::new());
+        AppState::new(
+            Arc::new(InMemoryPlayerRepository::with_players(players)),
+            Arc::new(InMemoryRaceRepository::new()),
+            sessions.clone(),
+            Arc::new(JwtService::new(JwtConfig::default())),
+            Arc::new(SessionManager::new(sessions, SessionConfig::default())),
+            LoginThrottle::new(LoginThrottleSettings::default()),
+        )
+    }
+
+    fn player(email: &str) -> Player {
+        StarterPack::new()
+            .unwrap()
+            .grant(
+                Email::parse(email).unwrap(),
+                Password::new("RacerPass123".to_string())
+                    .unwrap()
+                    .hash()
+                    .unwrap(),
+                TeamName::parse("Pit Crew").unwrap(),
+            )
+            .unwrap()
+    }
+
+    fn profile(email: &str, email_verified: bool) -> OAuthProfile {
+        OAuthProfile {
+            subject: "80351110224678912".to_string(),
+            email: Some(email.to_string()),
+            email_verified,
+            display_name: Some("nelly".to_string()),
+        }
+    }
+
+    fn callback(state: Option<&str>, cookies: &str) -> Result<(String, String), ApiError> {
+        let mut headers = HeaderMap::new();
+        headers.insert(COOKIE, HeaderValue::from_str(cookies).unwrap());
+        check_callback(
+            OAuthCallbackParams {
+                code: Some("code".to_string()),
+                state: state.map(str::to_string),
+                error: None,
+            },
+            &headers,
+        )
+    }
+
+    #[test]
+    fn callbacks_must_return_the_state_and_verifier_of_the_sign_in() {
+        let cookies = "oauth_state=abc; oauth_verifier=v3r1f13r";
+        assert_eq!(
+            callback(Some("abc"), cookies).unwrap(),
+            ("code".to_string(), "v3r1f13r".to_string())
+        );
+
+        for (state, cookies) in [
+            (Some("xyz"), cookies),
+            (None, cookies),
+            (Some("abc"), "oauth_verifier=v3r1f13r"),
+            (Some(""), "oauth_state=; oauth_verifier=v3r1f13r"),
+            (Some("abc"), "oauth_state=abc"),
+            (Some("abc"), "oauth_state=abc; oauth_verifier="),
+        ] {
+            let (status, _) = callback(state, cookies).unwrap_err();
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{state:?} with {cookies}");
+        }
+    }
+
+    #[tokio::test]
+    async fn verified_emails_link_the_provider_account_to_the_player() {
+        let existing = player("nelly@example.com");
+        let state = app_state(vec![existing.clone()]);
+
+        let (linked, created) = find_or_create_player(
+            &state,
+            OAuthProvider::Discord,
+            profile("nelly@example.com", true),
+        )
+        .await
+        .unwrap();
+        assert!(!created);
+        assert_eq!(linked.uuid, existing.uuid);
+        assert_eq!(linked.oauth_identities.len(), 1);
+
+        // Later sign-ins find the player by the provider account alone
+        let (again, created) = find_or_create_player(
+            &state,
+            OAuthProvider::Discord,
+            profile("changed@example.com", false),
+        )
+        .await
+        .unwrap();
+        assert!(!created);
+        assert_eq!(again.uuid, existing.uuid);
+    }
+
+    #[tokio::test]
+    async fn unverified_emails_are_not_linked() {
+        let state = app_state(vec![player("nelly@example.com")]);
+
+        let (status, _) = find_or_create_player(
+            &state,
+            OAuthProvider::Google,
+            profile("nelly@example.com", false),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn new_provider_accounts_get_a_player() {
+        let state = app_state(Vec::new());
+
+        let (player, created) = find_or_create_player(
+            &state,
+            OAuthProvider::Google,
+            profile("new@example.com", true),
+        )
+        .await
+        .unwrap();
+        assert!(created);
+        assert_eq!(player.email.as_ref(), "new@example.com");
+        assert_eq!(player.oauth_identities[0].provider, OAuthProvider::Google);
+    }
+}
@@ -0,0 +1,320 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+    routing::{get, post},
+    Extension, Router,
+};
+use futures_util::TryStreamExt;
+use mongodb::{
+    bson::{doc, Bson, DateTime as BsonDateTime},
+    options::{FindOneAndUpdateOptions, FindOptions, ReturnDocument},
+    Database,
+};
+use rand::Rng;
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::configuration::{PackDefinition, PackSettings};
+use crate::domain::{Body, BodyName, CraftableComponent, Engine, EngineName, Player};
+use crate::routes::races::ErrorResponse;
+use crate::services::packs::{open_pack, PackDrop, PackOpening, PACK_OPENINGS};
+
+type ApiError = (StatusCode, Json<ErrorResponse>);
+
+/// Most pack openings listed at once
+const MAX_PACK_OPENINGS: i64 = 50;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OpenPackResponse {
+    pub opening: PackOpening,
+    pub credits_remaining: u64,
+}
+
+pub fn routes() -> Router<Database> {
+    Router::new().route("/packs", get(list_packs)).route(
+        "/players/:player_uuid/packs/openings",
+        get(list_pack_openings),
+    )
+}
+
+/// Routes spending the player's credits; mounted behind auth and ownership
+pub fn asset_routes() -> Router<Database> {
+    Router::new().route(
+        "/players/:player_uuid/packs/:pack_id/open",
+        post(open_pack_for_player),
+    )
+}
+
+fn error(status: StatusCode, error: &str, message: &str) -> ApiError {
+    (
+        status,
+        Json(ErrorResponse {
+            error: error.to_string(),
+            message: message.to_string(),
+            details: None,
+        }),
+    )
+}
+
+fn database_error(e: &mongodb::error::Error) -> ApiError {
+    tracing::error!("Pack operation failed: {:?}", e);
+    error(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "DATABASE_ERROR",
+        "Internal server error",
+    )
+}
+
+fn pack_misconfigured(e: &dyn std::fmt::Display) -> ApiError {
+    tracing::error!("Pack cannot be opened: {}", e);
+    error(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "PACK_MISCONFIGURED",
+        "Pack cannot be opened",
+    )
+}
+
+fn invalid_uuid() -> ApiError {
+    error(
+        StatusCode::BAD_REQUEST,
+        "INVALID_UUID",
+        "Invalid UUID format",
+    )
+}
+
+/// Engines and bodies of a pack's drops, ready to push onto the player
+struct DrawnComponents {
+    engines: Vec<Bson>,
+    bodies: Vec<Bson>,
+    component_uuids: Vec<String>,
+}
+
+impl DrawnComponents {
+    /// Drops whose names or values the catalog's settings make invalid are
+    /// reported as a misconfigured pack
+    fn build(drops: &[PackDrop]) -> Result<Self, ApiError> {
+        let mut drawn = Self {
+            engines: Vec::new(),
+            bodies: Vec::new(),
+            component_uuids: Vec::new(),
+        };
+        for drop in drops {
+            let name = format!("{:?} {:?}", drop.rarity, drop.component);
+            match drop.component {
+                CraftableComponent::Engine => {
+                    let engine = Engine::new(
+                        EngineName::parse(&name).map_err(|e| pack_misconfigured(&e))?,
+                        drop.rarity,
+                        drop.straight_value,
+                        drop.curve_value,
+                        None,
+                    )
+                    .map_err(|e| pack_misconfigured(&e))?;
+                    drawn.component_uuids.push(engine.uuid.to_string());
+                    drawn
+                        .engines
+                        .push(mongodb::bson::to_bson(&engine).map_err(|e| pack_misconfigured(&e))?);
+                }
+                CraftableComponent::Body => {
+                    let body = Body::new(
+                        BodyName::parse(&name).map_err(|e| pack_misconfigured(&e))?,
+                        drop.rarity,
+                        drop.straight_value,
+                        drop.curve_value,
+                        None,
+                    )
+                    .map_err(|e| pack_misconfigured(&e))?;
+                    drawn.component_uuids.push(body.uuid.to_string());
+                    drawn
+                        .bodies
+                        .push(mongodb::bson::to_bson(&body).map_err(|e| pack_misconfigured(&e))?);
+                }
+            }
+        }
+        Ok(drawn)
+    }
+}
+
+/// Card packs on sale with their drop rates and pity rules
+#[utoipa::path(
+    get,
+    path = "/api/v1/packs",
+    responses(
+        (status = 200, description = "Packs on sale", body = Vec<PackDefinition>)
+    ),
+    tag = "players"
+)]
+pub async fn list_packs(Extension(packs): Extension<PackSettings>) -> Json<Vec<PackDefinition>> {
+    Json(packs.catalog)
+}
+
+/// Buy and open a card pack
+///
+/// The pack's price is debited from the player's credits and the drawn
+/// engines and bodies are added to the player. Every opening is stored with
+/// the seed it was drawn from, so its drops can be replayed.
+#[utoipa::path(
+    post,
+    path = "/api/v1/players/{player_uuid}/packs/{pack_id}/open",
+    params(
+        ("player_uuid" = String, Path, description = "Player's UUID"),
+        ("pack_id" = String, Path, description = "Pack id from the catalog")
+    ),
+    responses(
+        (status = 200, description = "Pack opened", body = OpenPackResponse),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse),
+        (status = 401, description = "Authentication required"),
+        (status = 402, description = "Not enough credits", body = ErrorResponse),
+        (status = 404, description = "Player or pack not found", body = ErrorResponse),
+        (status = 409, description = "Credits or pity counter changed meanwhile", body = ErrorResponse),
+        (status = 500, description = "Internal server error or misconfigured pack", body = ErrorResponse)
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Opening card pack", skip(database, packs))]
+pub async fn open_pack_for_player(
+    State(database): State<Database>,
+    Extension(packs): Extension<PackSettings>,
+    Path((player_uuid, pack_id)): Path<(String, String)>,
+) -> Result<Json<OpenPackResponse>, ApiError> {
+    let player_uuid = Uuid::parse_str(&player_uuid).map_err(|_| invalid_uuid())?;
+    let pack = packs
+        .catalog
+        .iter()
+        .find(|pack| pack.id == pack_id)
+        .ok_or_else(|| error(StatusCode::NOT_FOUND, "PACK_NOT_FOUND", "Pack not found"))?;
+
+    let collection = database.collection::<Player>("players");
+    let player = collection
+        .find_one(doc! { "uuid": player_uuid.to_string() }, None)
+        .await
+        .map_err(|e| database_error(&e))?
+        .ok_or_else(|| {
+            error(
+                StatusCode::NOT_FOUND,
+                "PLAYER_NOT_FOUND",
+                "Player not found",
+            )
+        })?;
+    if player.credits < u64::from(pack.price) {
+        return Err(error(
+            StatusCode::PAYMENT_REQUIRED,
+            "INSUFFICIENT_CREDITS",
+            "Not enough credits for this pack",
+        ));
+    }
+
+    // Seeds stay below `i64::MAX` so BSON stores them as-is
+    let seed = rand::thread_rng().gen_range(0..=i64::MAX.unsigned_abs());
+    let pity_before = player.pack_pity.get(&pack.id).copied().unwrap_or(0);
+    let draw = open_pack(pack, seed, pity_before).map_err(|e| pack_misconfigured(&e))?;
+
+    let drawn = DrawnComponents::build(&draw.drops)?;
+
+    // Only matches while the credits and the pity counter the draw started
+    // from are still there, so concurrent openings cannot share a counter
+    let pity_field = format!("pack_pity.{}", pack.id);
+    let pity_filter = if pity_before == 0 {
+        Bson::Document(doc! { "$in": [Bson::Null, 0] })
+    } else {
+        Bson::Int64(i64::from(pity_before))
+    };
+    let price = i64::from(pack.price);
+    let filter = doc! {
+        "uuid": player_uuid.to_string(),
+        "credits": { "$gte": price },
+        pity_field.clone(): pity_filter,
+    };
+    let update = doc! {
+        "$push": {
+            "engines": { "$each": drawn.engines },
+            "bodies": { "$each": drawn.bodies },
+        },
+        "$inc": { "credits": -price },
+        "$set": {
+            pity_field: i64::from(draw.pity_counter),
+            "updated_at": BsonDateTime::now(),
+        },
+    };
+    let options = FindOneAndUpdateOptions::builder()
+        .return_document(ReturnDocument::After)
+        .build();
+    let updated = collection
+        .find_one_and_update(filter, update, options)
+        .await
+        .map_err(|e| database_error(&e))?
+        .ok_or_else(|| {
+            error(
+                StatusCode::CONFLICT,
+                "PACK_CONFLICT",
+                "Credits or pity counter changed meanwhile, try again",
+            )
+        })?;
+
+    let opening = PackOpening {
+        id: None,
+        uuid: Uuid::new_v4().to_string(),
+        player_uuid: player_uuid.to_string(),
+        pack_id: pack.id.clone(),
+        seed,
+        price: pack.price,
+        pity_before,
+        pity_after: draw.pity_counter,
+        drops: draw.drops,
+        component_uuids: drawn.component_uuids,
+        opened_at: BsonDateTime::now(),
+    };
+    // The components are already granted, so a failure is only logged
+    if let Err(e) = database
+        .collection::<PackOpening>(PACK_OPENINGS)
+        .insert_one(&opening, None)
+        .await
+    {
+        tracing::error!("Failed to record pack opening {}: {:?}", opening.uuid, e);
+    }
+
+    Ok(Json(OpenPackResponse {
+        opening,
+        credits_remaining: updated.credits,
+    }))
+}
+
+/// Latest card packs a player opened
+///
+/// Returns up to 50 openings, newest first, each with the seed its drops
+/// were drawn from.
+#[utoipa::path(
+    get,
+    path = "/api/v1/players/{player_uuid}/packs/openings",
+    params(
+        ("player_uuid" = String, Path, description = "Player's UUID")
+    ),
+    responses(
+        (status = 200, description = "Pack openings of the player", body = Vec<PackOpening>),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Listing pack openings", skip(database))]
+pub async fn list_pack_openings(
+    State(database): State<Database>,
+    Path(player_uuid): Path<String>,
+) -> Result<Json<Vec<PackOpening>>, ApiError> {
+    let player_uuid = Uuid::parse_str(&player_uuid).map_err(|_| invalid_uuid())?;
+    let options = FindOptions::builder()
+        .sort(doc! { "opened_at": -1 })
+        .limit(MAX_PACK_OPENINGS)
+        .build();
+    let openings = database
+        .collection::<PackOpening>(PACK_OPENINGS)
+        .find(doc! { "player_uuid": player_uuid.to_string() }, options)
+        .await
+        .map_err(|e| database_error(&e))?
+        .try_collect()
+        .await
+        .map_err(|e| database_error(&e))?;
+    Ok(Json(openings))
+}
@@ -0,0 +1,201 @@
+use axum::{http::StatusCode, response::Json};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use crate::routes::races::ErrorResponse;
+
+/// Largest page a client may request
+pub const MAX_PAGE_LIMIT: u32 = 100;
+
+/// Prefix of every cursor, so the encoding can change without misreading old tokens
+const CURSOR_VERSION: &str = "v1:";
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct CursorQuery {
+    /// `next_cursor` from the previous page; omit for the first page
+    pub cursor: Option<String>,
+    /// Items per page, at most 100; every remaining item when omitted
+    pub limit: Option<u32>,
+}
+
+impl CursorQuery {
+    /// Requested page size, capped at [`MAX_PAGE_LIMIT`]
+    #[must_use]
+    pub fn limit(&self) -> Option<usize> {
+        self.limit
+            .map(|limit| limit.clamp(1, MAX_PAGE_LIMIT) as usize)
+    }
+
+    /// Position the cursor points after, or `Err` for a malformed cursor
+    pub fn after<K: CursorKey>(&self) -> Result<Option<K>, InvalidCursor> {
+        self.cursor
+            .as_deref()
+            .map(|cursor| decode_cursor(cursor).ok_or(InvalidCursor))
+            .transpose()
+    }
+}
+
+/// The client sent a cursor this server did not issue
+#[derive(Debug, PartialEq, Eq)]
+pub struct InvalidCursor;
+
+impl From<InvalidCursor> for (StatusCode, Json<ErrorResponse>) {
+    fn from(_: InvalidCursor) -> Self {
+        tracing::warn!("Invalid pagination cursor");
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "INVALID_CURSOR".to_string(),
+                message: "Invalid pagination cursor".to_string(),
+                details: None,
+            }),
+        )
+    }
+}
+
+/// Position in an ordered list that a cursor can hold
+pub trait CursorKey: Sized {
+    fn to_cursor_string(&self) -> String;
+    fn from_cursor_string(value: &str) -> Option<Self>;
+}
+
+impl CursorKey for u32 {
+    fn to_cursor_string(&self) -> String {
+        self.to_string()
+    }
+
+    fn from_cursor_string(value: &str) -> Option<Self> {
+        value.parse().ok()
+    }
+}
+
+/// Position in a list ordered by a timestamp, with the UUID breaking ties
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimePosition {
+    /// Timestamp in Unix milliseconds
+    pub millis: i64,
+    pub uuid: Uuid,
+}
+
+impl CursorKey for TimePosition {
+    fn to_cursor_string(&self) -> String {
+        format!("{}:{}", self.millis, self.uuid)
+    }
+
+    fn from_cursor_string(value: &str) -> Option<Self> {
+        let (millis, uuid) = value.split_once(':')?;
+        Some(Self {
+            millis: millis.parse().ok()?,
+            uuid: Uuid::parse_str(uuid).ok()?,
+        })
+    }
+}
+
+/// Opaque token for a position; clients must not rely on its contents
+#[must_use]
+pub fn encode_cursor<K: CursorKey>(key: &K) -> String {
+    hex::encode(format!("{CURSOR_VERSION}{}", key.to_cursor_string()))
+}
+
+#[must_use]
+pub fn decode_cursor<K: CursorKey>(cursor: &str) -> Option<K> {
+    let bytes = hex::decode(cursor).ok()?;
+    let value = String::from_utf8(bytes).ok()?;
+    K::from_cursor_string(value.strip_prefix(CURSOR_VERSION)?)
+}
+
+/// One page of `items`, which must be sorted by ascending `key`
+///
+/// Returns the items after `after`, at most `limit` of them, and the cursor for
+/// the next page when more items remain. Keys are positions rather than
+/// offsets, so items appended between requests do not shift later pages.
+pub fn paginate<T, K: CursorKey + PartialOrd>(
+    items: impl IntoIterator<Item = T>,
+    after: Option<&K>,
+    limit: Option<usize>,
+    key: impl Fn(&T) -> K,
+) -> (Vec<T>, Option<String>) {
+    let mut remaining = items
+        .into_iter()
+        .filter(|item| after.is_none_or(|after| key(item) > *after))
+        .peekable();
+
+    let page: Vec<T> = match limit {
+        Some(limit) => remaining.by_ref().take(limit).collect(),
+        None => remaining.by_ref().collect(),
+    };
+    let next_cursor = match (remaining.peek(), page.last()) {
+        (Some(_), Some(last)) => Some(encode_cursor(&key(last))),
+        _ => None,
+    };
+    (page, next_cursor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursors_round_trip() {
+        let cursor = encode_cursor(&42_u32);
+        assert_eq!(decode_cursor::<u32>(&cursor), Some(42));
+    }
+
+    #[test]
+    fn malformed_cursors_are_rejected() {
+        assert_eq!(decode_cursor::<u32>("not hex"), None);
+        assert_eq!(decode_cursor::<u32>(&hex::encode("42")), None);
+        assert_eq!(decode_cursor::<u32>(&hex::encode("v1:abc")), None);
+
+        let query = CursorQuery {
+            cursor: Some("zz".to_string()),
+            limit: None,
+        };
+        assert_eq!(query.after::<u32>(), Err(InvalidCursor));
+    }
+
+    #[test]
+    fn time_positions_round_trip() {
+        let position = TimePosition {
+            millis: 1_700_000_000_000,
+            uuid: Uuid::new_v4(),
+        };
+        assert_eq!(decode_cursor(&encode_cursor(&position)), Some(position));
+    }
+
+    #[test]
+    fn pages_follow_each_other_without_gaps() {
+        let items: Vec<u32> = (1..=7).collect();
+
+        let (first, cursor) = paginate(items.clone(), None, Some(3), |&n| n);
+        assert_eq!(first, vec![1, 2, 3]);
+
+        let after: u32 = decode_cursor(&cursor.unwrap()).unwrap();
+        let (second, cursor) = paginate(items.clone(), Some(&after), Some(3), |&n| n);
+        assert_eq!(second, vec![4, 5, 6]);
+
+        let after: u32 = decode_cursor(&cursor.unwrap()).unwrap();
+        let (last, cursor) = paginate(items, Some(&after), Some(3), |&n| n);
+        assert_eq!(last, vec![7]);
+        assert_eq!(cursor, None);
+    }
+
+    #[test]
+    fn items_added_after_a_page_do_not_shift_the_next_one() {
+        let (first, cursor) = paginate(vec![1_u32, 2, 3, 4], None, Some(2), |&n| n);
+        assert_eq!(first, vec![1, 2]);
+
+        // A new lap is recorded before the client asks for the next page
+        let after: u32 = decode_cursor(&cursor.unwrap()).unwrap();
+        let (second, _) = paginate(vec![1_u32, 2, 3, 4, 5], Some(&after), Some(2), |&n| n);
+        assert_eq!(second, vec![3, 4]);
+    }
+
+    #[test]
+    fn without_a_limit_everything_is_returned() {
+        let (page, cursor) = paginate(vec![1_u32, 2, 3], None, None, |&n| n);
+        assert_eq!(page, vec![1, 2, 3]);
+        assert_eq!(cursor, None);
+    }
+}
@@ -0,0 +1,153 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+    routing::{get, put},
+    Extension, Router,
+};
+use mongodb::{
+    bson::{doc, to_bson},
+    options::{FindOneAndUpdateOptions, ReturnDocument},
+    Database,
+};
+use uuid::Uuid;
+
+use crate::domain::{PrizePool, Race, RacePayout, RaceStatus};
+use crate::middleware::UserContext;
+use crate::routes::races::ErrorResponse;
+use crate::services::payouts::find_race_payouts;
+use crate::services::{record_audit, AuditEntry};
+
+type ApiError = (StatusCode, Json<ErrorResponse>);
+
+pub fn routes() -> Router<Database> {
+    Router::new().route("/races/:race_uuid/payouts", get(get_race_payouts))
+}
+
+/// Routes for administrators; mounted behind authentication and the admin role
+pub fn admin_routes() -> Router<Database> {
+    Router::new().route("/races/:race_uuid/prize-pool", put(set_prize_pool))
+}
+
+fn error(status: StatusCode, error: &str, message: &str) -> ApiError {
+    (
+        status,
+        Json(ErrorResponse {
+            error: error.to_string(),
+            message: message.to_string(),
+            details: None,
+        }),
+    )
+}
+
+fn database_error(e: &mongodb::error::Error) -> ApiError {
+    tracing::error!("Payout storage failed: {:?}", e);
+    error(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "DATABASE_ERROR",
+        "Internal server error",
+    )
+}
+
+fn parse_uuid(value: &str) -> Result<Uuid, ApiError> {
+    Uuid::parse_str(value).map_err(|_| {
+        error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_UUID",
+            "Invalid UUID format",
+        )
+    })
+}
+
+/// Prize payouts of a finished race
+///
+/// One entry per prized participant, by finishing position, with its
+/// status and the Solana transaction once paid. Empty until the payouts are
+/// queued, shortly after the race finishes.
+#[utoipa::path(
+    get,
+    path = "/api/v1/races/{race_uuid}/payouts",
+    params(
+        ("race_uuid" = String, Path, description = "Race UUID")
+    ),
+    responses(
+        (status = 200, description = "Payouts of the race", body = Vec<RacePayout>),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "races"
+)]
+#[tracing::instrument(name = "Listing race payouts", skip(database))]
+pub async fn get_race_payouts(
+    State(database): State<Database>,
+    Path(race_uuid): Path<String>,
+) -> Result<Json<Vec<RacePayout>>, ApiError> {
+    let race_uuid = parse_uuid(&race_uuid)?;
+    find_race_payouts(&database, race_uuid)
+        .await
+        .map(Json)
+        .map_err(|e| database_error(&e))
+}
+
+/// Set the on-chain prizes of a race that has not finished yet
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/races/{race_uuid}/prize-pool",
+    params(
+        ("race_uuid" = String, Path, description = "Race UUID")
+    ),
+    request_body = PrizePool,
+    responses(
+        (status = 200, description = "Prize pool set", body = PrizePool),
+        (status = 400, description = "Invalid UUID or prizes", body = ErrorResponse),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Not an administrator"),
+        (status = 404, description = "No unfinished race with this UUID", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "admin"
+)]
+#[tracing::instrument(name = "Setting prize pool", skip(database, admin, prize_pool))]
+pub async fn set_prize_pool(
+    State(database): State<Database>,
+    Extension(admin): Extension<UserContext>,
+    Path(race_uuid): Path<String>,
+    Json(prize_pool): Json<PrizePool>,
+) -> Result<Json<PrizePool>, ApiError> {
+    let race_uuid = parse_uuid(&race_uuid)?;
+    prize_pool
+        .validate()
+        .map_err(|message| error(StatusCode::BAD_REQUEST, "INVALID_PRIZE_POOL", &message))?;
+
+    let over = vec![
+        to_bson(&RaceStatus::Finished).map_err(|e| database_error(&e.into()))?,
+        to_bson(&RaceStatus::Cancelled).map_err(|e| database_error(&e.into()))?,
+    ];
+    let update = to_bson(&prize_pool).map_err(|e| database_error(&e.into()))?;
+    let options = FindOneAndUpdateOptions::builder()
+        .return_document(ReturnDocument::Before)
+        .build();
+    let previous = database
+        .collection::<Race>("races")
+        .find_one_and_update(
+            doc! { "uuid": race_uuid.to_string(), "status": { "$nin": over } },
+            doc! { "$set": { "prize_pool": update } },
+            options,
+        )
+        .await
+        .map_err(|e| database_error(&e))?
+        .ok_or_else(|| {
+            error(
+                StatusCode::NOT_FOUND,
+                "RACE_NOT_FOUND",
+                "No unfinished race with this UUID",
+            )
+        })?;
+
+    let audit = AuditEntry::new("race.prize_pool")
+        .by(admin.user_uuid)
+        .race(race_uuid)
+        .values(previous.prize_pool.as_ref(), Some(&prize_pool));
+    record_audit(&database, audit).await;
+    Ok(Json(prize_pool))
+}
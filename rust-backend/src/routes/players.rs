@@ -1,22 +1,165 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::Json,
     routing::{delete, get, post, put},
-    Router,
+    Extension, Router,
 };
+use chrono::{DateTime, Utc};
+use futures_util::TryStreamExt;
 use mongodb::{
-    bson::{doc, DateTime as BsonDateTime},
+    bson::{doc, DateTime as BsonDateTime, Document},
     Database,
 };
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
+use crate::database::union_with_archived_races;
 use crate::domain::{
-    Car, CarName, Pilot, PilotClass, PilotName, PilotRarity, PilotSkills, Player, TeamName,
-    WalletAddress,
+    AwardKind, BoostAnalytics, BoostUsageRecord, Car, CarName, DevicePlatform, DeviceToken,
+    Notification, NotificationPreferences, Pilot, PilotClass, PilotName, PilotRarity, PilotSkills,
+    Player, PlayerInventory, Race, RaceMode, RaceParticipant, RaceStatus, TeamName, WalletAddress,
 };
+use crate::routes::pagination::{encode_cursor, CursorQuery, TimePosition};
+use crate::routes::races::viewer_focus;
+use crate::services::matchmaking::skill_rating;
+use crate::services::push::DEVICE_TOKENS;
+use crate::services::{record_audit, AuditEntry, OwnershipResolver, PlayerAssets};
+
+/// Races per history page when no limit is requested
+pub const DEFAULT_HISTORY_PAGE_LIMIT: u32 = 20;
+/// Latest finished races boost analytics cover
+pub const BOOST_ANALYTICS_RACES: usize = 200;
+
+const NOTIFICATION_PREFERENCES: &str = "notification_preferences";
+
+/// Longest device token accepted; real ones are a few hundred characters
+const MAX_DEVICE_TOKEN_LENGTH: usize = 4096;
+
+/// Which of a player's races to list
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PlayerRaceFilter {
+    /// Races waiting to start or in progress
+    #[default]
+    Active,
+    Finished,
+    All,
+}
+
+impl PlayerRaceFilter {
+    /// Statuses the filter matches; empty for every status
+    #[must_use]
+    pub fn statuses(self) -> Vec<RaceStatus> {
+        match self {
+            Self::Active => vec![
+                RaceStatus::Waiting,
+                RaceStatus::Starting,
+                RaceStatus::InProgress,
+            ],
+            Self::Finished => vec![RaceStatus::Finished],
+            Self::All => Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct PlayerRacesQuery {
+    /// `active` (default), `finished` or `all`
+    #[serde(default)]
+    #[param(inline)]
+    pub status: PlayerRaceFilter,
+}
+
+/// Boost cards a player played over one race
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BoostStats {
+    pub cards_played: u32,
+    pub total_boost: u32,
+    pub average_boost: f64,
+    pub cycles_completed: u32,
+}
+
+impl BoostStats {
+    #[must_use]
+    pub fn of(participant: &RaceParticipant) -> Self {
+        let history = &participant.boost_usage_history;
+        let cards_played = u32::try_from(history.len()).unwrap_or(u32::MAX);
+        let total_boost: u32 = history
+            .iter()
+            .map(|record| u32::from(record.boost_value))
+            .sum();
+        let average_boost = if cards_played == 0 {
+            0.0
+        } else {
+            f64::from(total_boost) / f64::from(cards_played)
+        };
+
+        Self {
+            cards_played,
+            total_boost,
+            average_boost,
+            cycles_completed: participant.boost_hand.cycles_completed,
+        }
+    }
+}
+
+/// A finished race seen from one player
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RaceHistoryEntry {
+    pub race_uuid: String,
+    pub race_name: String,
+    pub track_name: String,
+    pub mode: RaceMode,
+    /// When the race finished
+    pub completed_at: DateTime<Utc>,
+    /// `None` when the player did not reach the finish
+    pub finish_position: Option<u32>,
+    pub participant_count: u32,
+    pub total_value: u32,
+    pub boost_stats: BoostStats,
+    /// Awards the player won in the race
+    pub awards: Vec<AwardKind>,
+}
+
+impl RaceHistoryEntry {
+    /// Entry for `player_uuid`, or `None` when the player was not in the race
+    #[must_use]
+    pub fn from_race(race: &Race, player_uuid: Uuid) -> Option<Self> {
+        let participant = race
+            .participants
+            .iter()
+            .find(|p| p.player_uuid == player_uuid)?;
+
+        Some(Self {
+            race_uuid: race.uuid.to_string(),
+            race_name: race.name.clone(),
+            track_name: race.track.name.clone(),
+            mode: race.mode,
+            // Finished races are never written again
+            completed_at: race.updated_at.to_system_time().into(),
+            finish_position: participant.finish_position,
+            participant_count: u32::try_from(race.participants.len()).unwrap_or(u32::MAX),
+            total_value: participant.total_value,
+            boost_stats: BoostStats::of(participant),
+            awards: race
+                .awards
+                .iter()
+                .filter(|award| award.player_uuid == player_uuid)
+                .map(|award| award.kind)
+                .collect(),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RaceHistoryResponse {
+    /// Most recently finished first
+    pub races: Vec<RaceHistoryEntry>,
+    /// Cursor for the next page; `None` on the last page
+    pub next_cursor: Option<String>,
+}
 
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct ConnectWalletRequest {
@@ -57,6 +200,13 @@ pub struct PilotSkillsRequest {
     pub stamina: u8,
 }
 
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RegisterDeviceRequest {
+    pub platform: DevicePlatform,
+    /// Registration token from FCM or device token from `APNs`
+    pub token: String,
+}
+
 #[derive(Serialize, ToSchema)]
 pub struct PlayerResponse {
     pub player: Player,
@@ -72,13 +222,56 @@ pub fn routes() -> Router<Database> {
         // Routes that require player ownership or admin role:
         .route("/players/:player_uuid", get(get_player_by_uuid))
         .route("/players/:player_uuid", put(update_player_team_name))
+        .route("/players/:player_uuid", delete(delete_player))
+        .route("/players/:player_uuid/races", get(get_player_races))
+        .route("/players/:player_uuid/inventory", get(get_player_inventory))
+        .route(
+            "/players/:player_uuid/races/history",
+            get(get_player_race_history),
+        )
+        .route(
+            "/players/:player_uuid/analytics/boost",
+            get(get_player_boost_analytics),
+        )
+        .route(
+            "/players/:player_uuid/notifications",
+            get(get_player_notifications),
+        )
+        .route(
+            "/players/:player_uuid/notification-preferences",
+            get(get_notification_preferences).put(update_notification_preferences),
+        )
+}
+
+/// Routes changing where a player is paid and notified
+///
+/// Mounted behind `AuthMiddleware` and `RequireOwnership::player("player_uuid")`,
+/// since race prizes are sent to the connected wallet and push notifications to
+/// the registered devices.
+pub fn account_routes() -> Router<Database> {
+    Router::new()
+        .route(
+            "/players/:player_uuid/wallet",
+            post(connect_wallet).delete(disconnect_wallet),
+        )
+        .route("/players/:player_uuid/devices", post(register_device))
+        .route(
+            "/players/:player_uuid/devices/:token",
+            delete(unregister_device),
+        )
+}
+
+/// Routes changing a player's cars and components
+///
+/// Mounted behind `AuthMiddleware` and `RequireOwnership::player("player_uuid")`
+/// with assets, so only the owner (or an admin) changes them, and only cars
+/// and pilots the player owns.
+pub fn asset_routes() -> Router<Database> {
+    Router::new()
         .route(
             "/players/:player_uuid/configuration",
             put(update_player_configuration),
         )
-        .route("/players/:player_uuid", delete(delete_player))
-        .route("/players/:player_uuid/wallet", post(connect_wallet))
-        .route("/players/:player_uuid/wallet", delete(disconnect_wallet))
         .route("/players/:player_uuid/cars", post(add_car_to_player))
         .route(
             "/players/:player_uuid/cars/:car_uuid",
@@ -92,13 +285,10 @@ pub fn routes() -> Router<Database> {
 }
 
 /// Admin-only routes that require authentication and admin role
-pub fn admin_routes() -> Router<
-    crate::app_state::AppState<
-        crate::repositories::MockPlayerRepository,
-        crate::repositories::MockRaceRepository,
-        crate::repositories::MockSessionRepository,
-    >,
-> {
+pub fn admin_routes<
+    P: crate::repositories::PlayerRepository + 'static,
+    R: crate::repositories::RaceRepository + 'static,
+>() -> Router<crate::app_state::AppState<P, R, crate::repositories::InMemorySessionRepository>> {
     // Temporarily disabled due to tracing format issues in admin functions
     Router::new()
     // TODO: Re-enable admin routes after fixing tracing format issues
@@ -176,6 +366,565 @@ pub async fn get_player_by_uuid(
     }
 }
 
+/// Get the races a player takes part in
+///
+/// Only active races (waiting to start or in progress) are listed unless
+/// `status` says otherwise. Newest races come first. Other participants'
+/// boost hands and pending actions are hidden.
+#[utoipa::path(
+    get,
+    path = "/api/v1/players/{player_uuid}/races",
+    params(
+        ("player_uuid" = String, Path, description = "Player's UUID"),
+        PlayerRacesQuery
+    ),
+    responses(
+        (status = 200, description = "Races the player takes part in", body = Vec<Race>),
+        (status = 400, description = "Invalid UUID format"),
+        (status = 404, description = "Player not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Fetching races of player", skip(database))]
+pub async fn get_player_races(
+    State(database): State<Database>,
+    Path(player_uuid_str): Path<String>,
+    Query(params): Query<PlayerRacesQuery>,
+) -> Result<Json<Vec<Race>>, StatusCode> {
+    let player_uuid = match Uuid::parse_str(&player_uuid_str) {
+        Ok(uuid) => uuid,
+        Err(e) => {
+            tracing::warn!("Invalid player UUID: {}", e);
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    };
+
+    match get_player_by_uuid_from_db(&database, player_uuid).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            tracing::warn!("Player not found for UUID: {}", player_uuid);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch player: {:?}", e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+
+    match get_player_races_from_db(&database, player_uuid, &params.status.statuses()).await {
+        Ok(races) => {
+            tracing::info!("Found {} race(s) for player {}", races.len(), player_uuid);
+            let mut seen = Vec::with_capacity(races.len());
+            for race in &races {
+                let focus = viewer_focus(&database, race, Some(player_uuid))
+                    .await
+                    .map_err(|e| {
+                        tracing::error!("Failed to fetch pilot: {:?}", e);
+                        StatusCode::INTERNAL_SERVER_ERROR
+                    })?;
+                seen.push(race.as_seen_by(Some(player_uuid), focus));
+            }
+            Ok(Json(seen))
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch races of player: {:?}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Get everything a player owns
+///
+/// Lists the player's cars, pilots, engines and bodies in one go, each with
+/// the car it is assigned to and the race it is locked in while that race is
+/// waiting to start or in progress.
+#[utoipa::path(
+    get,
+    path = "/api/v1/players/{player_uuid}/inventory",
+    params(
+        ("player_uuid" = String, Path, description = "Player's UUID")
+    ),
+    responses(
+        (status = 200, description = "Inventory of the player", body = PlayerInventory),
+        (status = 400, description = "Invalid UUID format"),
+        (status = 404, description = "Player not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Fetching inventory of player", skip(database))]
+pub async fn get_player_inventory(
+    State(database): State<Database>,
+    Path(player_uuid_str): Path<String>,
+) -> Result<Json<PlayerInventory>, StatusCode> {
+    let player_uuid = match Uuid::parse_str(&player_uuid_str) {
+        Ok(uuid) => uuid,
+        Err(e) => {
+            tracing::warn!("Invalid player UUID: {}", e);
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    };
+
+    let player = match get_player_by_uuid_from_db(&database, player_uuid).await {
+        Ok(Some(player)) => player,
+        Ok(None) => {
+            tracing::warn!("Player not found for UUID: {}", player_uuid);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch player: {:?}", e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let statuses = PlayerRaceFilter::Active.statuses();
+    match get_player_races_from_db(&database, player_uuid, &statuses).await {
+        Ok(active_races) => Ok(Json(PlayerInventory::new(&player, &active_races))),
+        Err(e) => {
+            tracing::error!("Failed to fetch active races of player: {:?}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Get the latest notifications of a player
+///
+/// Returns up to 50 notifications, newest first, such as races of theirs
+/// that were cancelled for inactivity.
+#[utoipa::path(
+    get,
+    path = "/api/v1/players/{player_uuid}/notifications",
+    params(
+        ("player_uuid" = String, Path, description = "Player's UUID")
+    ),
+    responses(
+        (status = 200, description = "Notifications of the player", body = Vec<Notification>),
+        (status = 400, description = "Invalid UUID format"),
+        (status = 404, description = "Player not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Fetching notifications of player", skip(database))]
+pub async fn get_player_notifications(
+    State(database): State<Database>,
+    Path(player_uuid_str): Path<String>,
+) -> Result<Json<Vec<Notification>>, StatusCode> {
+    let player_uuid = match Uuid::parse_str(&player_uuid_str) {
+        Ok(uuid) => uuid,
+        Err(e) => {
+            tracing::warn!("Invalid player UUID: {}", e);
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    };
+
+    match get_player_by_uuid_from_db(&database, player_uuid).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            tracing::warn!("Player not found for UUID: {}", player_uuid);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch player: {:?}", e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+
+    match get_player_notifications_from_db(&database, player_uuid).await {
+        Ok(notifications) => Ok(Json(notifications)),
+        Err(e) => {
+            tracing::error!("Failed to fetch notifications of player: {:?}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Get which notifications the player also receives by email
+///
+/// Players who never changed them get every kind.
+#[utoipa::path(
+    get,
+    path = "/api/v1/players/{player_uuid}/notification-preferences",
+    params(
+        ("player_uuid" = String, Path, description = "Player's UUID")
+    ),
+    responses(
+        (status = 200, description = "Email preferences of the player", body = NotificationPreferences),
+        (status = 400, description = "Invalid UUID format"),
+        (status = 404, description = "Player not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Fetching notification preferences of player", skip(database))]
+pub async fn get_notification_preferences(
+    State(database): State<Database>,
+    Path(player_uuid_str): Path<String>,
+) -> Result<Json<NotificationPreferences>, StatusCode> {
+    let player_uuid = match Uuid::parse_str(&player_uuid_str) {
+        Ok(uuid) => uuid,
+        Err(e) => {
+            tracing::warn!("Invalid player UUID: {}", e);
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    };
+
+    match get_player_by_uuid_from_db(&database, player_uuid).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            tracing::warn!("Player not found for UUID: {}", player_uuid);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch player: {:?}", e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+
+    match get_notification_preferences_from_db(&database, player_uuid).await {
+        Ok(preferences) => Ok(Json(preferences)),
+        Err(e) => {
+            tracing::error!("Failed to fetch notification preferences: {:?}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Choose which notifications the player also receives by email
+#[utoipa::path(
+    put,
+    path = "/api/v1/players/{player_uuid}/notification-preferences",
+    params(
+        ("player_uuid" = String, Path, description = "Player's UUID")
+    ),
+    request_body = NotificationPreferences,
+    responses(
+        (status = 200, description = "Preferences saved", body = NotificationPreferences),
+        (status = 400, description = "Invalid UUID format"),
+        (status = 404, description = "Player not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(
+    name = "Updating notification preferences of player",
+    skip(database, preferences)
+)]
+pub async fn update_notification_preferences(
+    State(database): State<Database>,
+    Path(player_uuid_str): Path<String>,
+    Json(preferences): Json<NotificationPreferences>,
+) -> Result<Json<NotificationPreferences>, StatusCode> {
+    let player_uuid = match Uuid::parse_str(&player_uuid_str) {
+        Ok(uuid) => uuid,
+        Err(e) => {
+            tracing::warn!("Invalid player UUID: {}", e);
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    };
+
+    match get_player_by_uuid_from_db(&database, player_uuid).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            tracing::warn!("Player not found for UUID: {}", player_uuid);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch player: {:?}", e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+
+    let previous = get_notification_preferences_from_db(&database, player_uuid)
+        .await
+        .ok();
+    match save_notification_preferences_in_db(&database, player_uuid, preferences).await {
+        Ok(()) => {
+            let audit = AuditEntry::new("player.update_notification_preferences")
+                .by(player_uuid)
+                .player(player_uuid)
+                .values(previous.as_ref(), Some(&preferences));
+            record_audit(&database, audit).await;
+            Ok(Json(preferences))
+        }
+        Err(e) => {
+            tracing::error!("Failed to save notification preferences: {:?}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Register a device to receive the player's push notifications
+///
+/// Registering a token again, e.g. after another player signed in on the
+/// device, moves it to this player.
+#[utoipa::path(
+    post,
+    path = "/api/v1/players/{player_uuid}/devices",
+    params(
+        ("player_uuid" = String, Path, description = "Player's UUID")
+    ),
+    request_body = RegisterDeviceRequest,
+    responses(
+        (status = 201, description = "Device registered", body = DeviceToken),
+        (status = 400, description = "Invalid UUID format or device token"),
+        (status = 401, description = "Authentication required"),
+        (status = 404, description = "Player not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Registering device of player", skip(database, payload))]
+pub async fn register_device(
+    State(database): State<Database>,
+    Path(player_uuid_str): Path<String>,
+    Json(payload): Json<RegisterDeviceRequest>,
+) -> Result<(StatusCode, Json<DeviceToken>), StatusCode> {
+    let player_uuid = match Uuid::parse_str(&player_uuid_str) {
+        Ok(uuid) => uuid,
+        Err(e) => {
+            tracing::warn!("Invalid player UUID: {}", e);
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    };
+
+    let token = payload.token.trim();
+    if token.is_empty() || token.len() > MAX_DEVICE_TOKEN_LENGTH {
+        tracing::warn!("Invalid device token of {} characters", token.len());
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    match get_player_by_uuid_from_db(&database, player_uuid).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            tracing::warn!("Player not found for UUID: {}", player_uuid);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch player: {:?}", e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+
+    let device = DeviceToken::new(player_uuid, payload.platform, token.to_string());
+    match save_device_token_in_db(&database, &device).await {
+        Ok(previous) => {
+            let audit = AuditEntry::new("player.register_device")
+                .by(player_uuid)
+                .player(player_uuid)
+                .values(previous.as_ref(), Some(&device))
+                .redact("token");
+            record_audit(&database, audit).await;
+            Ok((StatusCode::CREATED, Json(device)))
+        }
+        Err(e) => {
+            tracing::error!("Failed to register device: {:?}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Stop pushing the player's notifications to a device, e.g. on sign out
+#[utoipa::path(
+    delete,
+    path = "/api/v1/players/{player_uuid}/devices/{token}",
+    params(
+        ("player_uuid" = String, Path, description = "Player's UUID"),
+        ("token" = String, Path, description = "Device token to unregister")
+    ),
+    responses(
+        (status = 204, description = "Device unregistered"),
+        (status = 400, description = "Invalid UUID format"),
+        (status = 401, description = "Authentication required"),
+        (status = 404, description = "Device not registered for the player"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Unregistering device of player", skip(database, token))]
+pub async fn unregister_device(
+    State(database): State<Database>,
+    Path((player_uuid_str, token)): Path<(String, String)>,
+) -> Result<StatusCode, StatusCode> {
+    let player_uuid = match Uuid::parse_str(&player_uuid_str) {
+        Ok(uuid) => uuid,
+        Err(e) => {
+            tracing::warn!("Invalid player UUID: {}", e);
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    };
+
+    match delete_device_token_from_db(&database, player_uuid, &token).await {
+        Ok(true) => {
+            let audit = AuditEntry::new("player.unregister_device")
+                .by(player_uuid)
+                .player(player_uuid);
+            record_audit(&database, audit).await;
+            Ok(StatusCode::NO_CONTENT)
+        }
+        Ok(false) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            tracing::error!("Failed to unregister device: {:?}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Get the finished races of a player with their results
+///
+/// Each entry holds the player's finish position, total value and boost card
+/// statistics. Races are ordered by completion date, most recent first; pass
+/// `limit` (20 by default) and follow `next_cursor` until it is `null`.
+#[utoipa::path(
+    get,
+    path = "/api/v1/players/{player_uuid}/races/history",
+    params(
+        ("player_uuid" = String, Path, description = "Player's UUID"),
+        CursorQuery
+    ),
+    responses(
+        (status = 200, description = "One page of finished races", body = RaceHistoryResponse),
+        (status = 400, description = "Invalid UUID format or cursor"),
+        (status = 404, description = "Player not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Fetching race history of player", skip(database))]
+pub async fn get_player_race_history(
+    State(database): State<Database>,
+    Path(player_uuid_str): Path<String>,
+    Query(params): Query<CursorQuery>,
+) -> Result<Json<RaceHistoryResponse>, StatusCode> {
+    let player_uuid = match Uuid::parse_str(&player_uuid_str) {
+        Ok(uuid) => uuid,
+        Err(e) => {
+            tracing::warn!("Invalid player UUID: {}", e);
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    };
+    let page = CursorQuery {
+        cursor: params.cursor,
+        limit: Some(params.limit.unwrap_or(DEFAULT_HISTORY_PAGE_LIMIT)),
+    };
+    let Ok(after) = page.after::<TimePosition>() else {
+        tracing::warn!("Invalid pagination cursor");
+        return Err(StatusCode::BAD_REQUEST);
+    };
+    let limit = page.limit().unwrap_or_default();
+
+    match get_player_by_uuid_from_db(&database, player_uuid).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            tracing::warn!("Player not found for UUID: {}", player_uuid);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch player: {:?}", e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+
+    let mut races = get_player_race_history_from_db(&database, player_uuid, after, limit)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to fetch race history: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    // One extra race was fetched to tell whether another page follows
+    let next_cursor = if races.len() > limit {
+        races.truncate(limit);
+        races.last().map(|race| {
+            encode_cursor(&TimePosition {
+                millis: race.updated_at.timestamp_millis(),
+                uuid: race.uuid,
+            })
+        })
+    } else {
+        None
+    };
+
+    Ok(Json(RaceHistoryResponse {
+        races: races
+            .iter()
+            .filter_map(|race| RaceHistoryEntry::from_race(race, player_uuid))
+            .collect(),
+        next_cursor,
+    }))
+}
+
+/// Summarize how a player spends boost cards
+///
+/// Covers the player's latest finished races: how often each card is
+/// played, early versus late in a cycle, at each place in a cycle and on
+/// straight versus curve laps. Running races are left out so current plays
+/// stay hidden.
+#[utoipa::path(
+    get,
+    path = "/api/v1/players/{player_uuid}/analytics/boost",
+    params(
+        ("player_uuid" = String, Path, description = "Player's UUID")
+    ),
+    responses(
+        (status = 200, description = "Boost usage of the player", body = BoostAnalytics),
+        (status = 400, description = "Invalid UUID format"),
+        (status = 404, description = "Player not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Computing boost analytics of player", skip(database))]
+pub async fn get_player_boost_analytics(
+    State(database): State<Database>,
+    Path(player_uuid_str): Path<String>,
+) -> Result<Json<BoostAnalytics>, StatusCode> {
+    let player_uuid = match Uuid::parse_str(&player_uuid_str) {
+        Ok(uuid) => uuid,
+        Err(e) => {
+            tracing::warn!("Invalid player UUID: {}", e);
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    };
+
+    match get_player_by_uuid_from_db(&database, player_uuid).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            tracing::warn!("Player not found for UUID: {}", player_uuid);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch player: {:?}", e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+
+    let races =
+        get_player_race_history_from_db(&database, player_uuid, None, BOOST_ANALYTICS_RACES)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to fetch race history: {:?}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+    let histories: Vec<&[BoostUsageRecord]> = races
+        .iter()
+        .take(BOOST_ANALYTICS_RACES)
+        .filter_map(|race| {
+            race.participants
+                .iter()
+                // Races against the player's ghost carry their UUID too
+                .find(|p| p.player_uuid == player_uuid && p.ghost.is_none())
+                .map(|p| p.boost_usage_history.as_slice())
+        })
+        .collect();
+
+    Ok(Json(BoostAnalytics::from_histories(
+        player_uuid,
+        &histories,
+    )))
+}
+
 /// Get player by wallet address
 #[utoipa::path(
     get,
@@ -257,6 +1006,7 @@ pub async fn get_player_by_email(
     responses(
         (status = 200, description = "Wallet connected successfully", body = PlayerResponse),
         (status = 400, description = "Bad request"),
+        (status = 401, description = "Authentication required"),
         (status = 404, description = "Player not found"),
         (status = 409, description = "Wallet already connected"),
         (status = 500, description = "Internal server error")
@@ -296,8 +1046,11 @@ pub async fn connect_wallet(
         }
     }
 
+    let previous = get_player_for_audit(&database, player_uuid).await;
     match connect_wallet_to_player(&database, player_uuid, wallet_address).await {
         Ok(Some(updated_player)) => {
+            let audit = AuditEntry::new("player.connect_wallet").by(player_uuid);
+            audit_player_change(&database, audit, previous.as_ref(), Some(&updated_player)).await;
             tracing::info!("Wallet connected successfully to player: {}", player_uuid);
             Ok(Json(PlayerResponse {
                 player: updated_player,
@@ -324,6 +1077,7 @@ pub async fn connect_wallet(
     ),
     responses(
         (status = 200, description = "Wallet disconnected successfully", body = PlayerResponse),
+        (status = 401, description = "Authentication required"),
         (status = 404, description = "Player not found"),
         (status = 500, description = "Internal server error")
     ),
@@ -342,8 +1096,11 @@ pub async fn disconnect_wallet(
         }
     };
 
+    let previous = get_player_for_audit(&database, player_uuid).await;
     match disconnect_wallet_from_player(&database, player_uuid).await {
         Ok(Some(updated_player)) => {
+            let audit = AuditEntry::new("player.disconnect_wallet").by(player_uuid);
+            audit_player_change(&database, audit, previous.as_ref(), Some(&updated_player)).await;
             tracing::info!(
                 "Wallet disconnected successfully from player: {}",
                 player_uuid
@@ -375,14 +1132,19 @@ pub async fn disconnect_wallet(
     responses(
         (status = 200, description = "Configuration updated successfully", body = PlayerResponse),
         (status = 400, description = "Bad request"),
+        (status = 403, description = "A car or component is not the player's"),
         (status = 404, description = "Player not found"),
         (status = 500, description = "Internal server error")
     ),
     tag = "players"
 )]
-#[tracing::instrument(name = "Updating player configuration", skip(database, payload))]
+#[tracing::instrument(
+    name = "Updating player configuration",
+    skip(database, assets, payload)
+)]
 pub async fn update_player_configuration(
     State(database): State<Database>,
+    assets: Option<Extension<PlayerAssets>>,
     Path(player_uuid_str): Path<String>,
     Json(payload): Json<UpdatePlayerConfigurationRequest>,
 ) -> Result<Json<PlayerResponse>, StatusCode> {
@@ -402,10 +1164,34 @@ pub async fn update_player_configuration(
         }
     };
 
+    // Cars may only be fitted with the player's own pilots, engines and bodies;
+    // the ownership middleware has usually resolved them already
+    let assets = match assets {
+        Some(Extension(assets)) => Some(assets),
+        None => OwnershipResolver::new(database.clone())
+            .assets_of(player_uuid)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to resolve player assets: {:?}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?,
+    };
+    let Some(assets) = assets else {
+        tracing::warn!("Player not found for UUID: {}", player_uuid);
+        return Err(StatusCode::NOT_FOUND);
+    };
+    if let Some((kind, uuid)) = assets.first_unowned(&payload.cars) {
+        tracing::warn!("Player {} does not own {:?} {}", player_uuid, kind, uuid);
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let previous = get_player_for_audit(&database, player_uuid).await;
     match update_player_configuration_by_uuid(&database, player_uuid, new_team_name, payload.cars)
         .await
     {
         Ok(Some(updated_player)) => {
+            let audit = AuditEntry::new("player.update_configuration").by(player_uuid);
+            audit_player_change(&database, audit, previous.as_ref(), Some(&updated_player)).await;
             tracing::info!(
                 "Configuration updated successfully for player: {}",
                 player_uuid
@@ -464,8 +1250,11 @@ pub async fn update_player_team_name(
         }
     };
 
+    let previous = get_player_for_audit(&database, player_uuid).await;
     match update_player_team_name_by_uuid(&database, player_uuid, new_team_name).await {
         Ok(Some(updated_player)) => {
+            let audit = AuditEntry::new("player.update_team_name").by(player_uuid);
+            audit_player_change(&database, audit, previous.as_ref(), Some(&updated_player)).await;
             tracing::info!("Team name updated successfully for player: {}", player_uuid);
             Ok(Json(PlayerResponse {
                 player: updated_player,
@@ -510,8 +1299,11 @@ pub async fn delete_player(
         }
     };
 
+    let previous = get_player_for_audit(&database, player_uuid).await;
     match delete_player_by_uuid(&database, player_uuid).await {
         Ok(true) => {
+            let audit = AuditEntry::new("player.delete").by(player_uuid);
+            audit_player_change(&database, audit, previous.as_ref(), None).await;
             tracing::info!("Player deleted successfully: {}", player_uuid);
             Ok(StatusCode::OK)
         }
@@ -571,8 +1363,11 @@ pub async fn add_car_to_player(
         }
     };
 
+    let previous = get_player_for_audit(&database, player_uuid).await;
     match add_car_to_player_by_uuid(&database, player_uuid, car).await {
         Ok(Some(updated_player)) => {
+            let audit = AuditEntry::new("player.add_car").by(player_uuid);
+            audit_player_change(&database, audit, previous.as_ref(), Some(&updated_player)).await;
             tracing::info!("Car added successfully to player: {}", player_uuid);
             Ok(Json(PlayerResponse {
                 player: updated_player,
@@ -627,8 +1422,11 @@ pub async fn remove_car_from_player(
         }
     };
 
+    let previous = get_player_for_audit(&database, player_uuid).await;
     match remove_car_from_player_by_uuid(&database, player_uuid, car_uuid).await {
         Ok(Some(updated_player)) => {
+            let audit = AuditEntry::new("player.remove_car").by(player_uuid);
+            audit_player_change(&database, audit, previous.as_ref(), Some(&updated_player)).await;
             tracing::info!("Car removed successfully from player: {}", player_uuid);
             Ok(Json(PlayerResponse {
                 player: updated_player,
@@ -723,8 +1521,11 @@ pub async fn add_pilot_to_player(
         }
     };
 
+    let previous = get_player_for_audit(&database, player_uuid).await;
     match add_pilot_to_player_by_uuid(&database, player_uuid, pilot).await {
         Ok(Some(updated_player)) => {
+            let audit = AuditEntry::new("player.add_pilot").by(player_uuid);
+            audit_player_change(&database, audit, previous.as_ref(), Some(&updated_player)).await;
             tracing::info!("Pilot added successfully to player: {}", player_uuid);
             Ok(Json(PlayerResponse {
                 player: updated_player,
@@ -779,8 +1580,11 @@ pub async fn remove_pilot_from_player(
         }
     };
 
+    let previous = get_player_for_audit(&database, player_uuid).await;
     match remove_pilot_from_player_by_uuid(&database, player_uuid, pilot_uuid).await {
         Ok(Some(updated_player)) => {
+            let audit = AuditEntry::new("player.remove_pilot").by(player_uuid);
+            audit_player_change(&database, audit, previous.as_ref(), Some(&updated_player)).await;
             tracing::info!("Pilot removed successfully from player: {}", player_uuid);
             Ok(Json(PlayerResponse {
                 player: updated_player,
@@ -959,6 +1763,256 @@ pub async fn get_player_by_uuid_from_db(
     collection.find_one(filter, None).await
 }
 
+/// Skill rating of the player from their finished ranked races, used by matchmaking
+pub async fn get_skill_rating_from_db(
+    database: &Database,
+    player_uuid: Uuid,
+) -> Result<u32, mongodb::error::Error> {
+    let races = get_player_races_from_db(database, player_uuid, &[RaceStatus::Finished]).await?;
+    Ok(skill_rating(&races, player_uuid))
+}
+
+/// Races with the player among the participants, newest first
+/// An empty `statuses` matches races in any status.
+#[tracing::instrument(name = "Getting races of player from the database", skip(database))]
+pub async fn get_player_races_from_db(
+    database: &Database,
+    player_uuid: Uuid,
+    statuses: &[RaceStatus],
+) -> Result<Vec<Race>, mongodb::error::Error> {
+    let mut filter = doc! { "participants.player_uuid": player_uuid.to_string() };
+    if !statuses.is_empty() {
+        filter.insert("status", doc! { "$in": mongodb::bson::to_bson(statuses)? });
+    }
+    find_races_with_archive(database, filter, doc! { "created_at": -1 }, None).await
+}
+
+/// Finished races of a player after `after`, most recently finished first
+/// Fetches up to `limit + 1` races so the caller can tell whether more follow.
+#[tracing::instrument(
+    name = "Getting race history of player from the database",
+    skip(database)
+)]
+pub async fn get_player_race_history_from_db(
+    database: &Database,
+    player_uuid: Uuid,
+    after: Option<TimePosition>,
+    limit: usize,
+) -> Result<Vec<Race>, mongodb::error::Error> {
+    let mut filter = doc! {
+        "participants.player_uuid": player_uuid.to_string(),
+        "status": mongodb::bson::to_bson(&RaceStatus::Finished)?,
+    };
+    if let Some(after) = after {
+        let updated_at = BsonDateTime::from_millis(after.millis);
+        filter.insert(
+            "$or",
+            vec![
+                doc! { "updated_at": { "$lt": updated_at } },
+                doc! { "updated_at": updated_at, "uuid": { "$lt": after.uuid.to_string() } },
+            ],
+        );
+    }
+
+    find_races_with_archive(
+        database,
+        filter,
+        doc! { "updated_at": -1, "uuid": -1 },
+        Some(limit + 1),
+    )
+    .await
+}
+
+/// Player as it was before a change, for the audit log
+async fn get_player_for_audit(database: &Database, player_uuid: Uuid) -> Option<Player> {
+    get_player_by_uuid_from_db(database, player_uuid)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!(
+                "Failed to load player {} for the audit log: {:?}",
+                player_uuid,
+                e
+            );
+            None
+        })
+}
+
+async fn audit_player_change(
+    database: &Database,
+    audit: AuditEntry,
+    previous: Option<&Player>,
+    new: Option<&Player>,
+) {
+    let player_uuid = previous.or(new).map(|player| player.uuid);
+    let audit = match player_uuid {
+        Some(player_uuid) => audit.player(player_uuid),
+        None => audit,
+    };
+    let audit = audit.values(previous, new).redact("password_hash");
+    record_audit(database, audit).await;
+}
+
+/// Most notifications returned at once
+const MAX_NOTIFICATIONS: i64 = 50;
+
+/// Latest notifications of a player, newest first
+#[tracing::instrument(
+    name = "Getting notifications of player from the database",
+    skip(database)
+)]
+pub async fn get_player_notifications_from_db(
+    database: &Database,
+    player_uuid: Uuid,
+) -> Result<Vec<Notification>, mongodb::error::Error> {
+    let collection = database.collection::<Notification>("notifications");
+    let options = mongodb::options::FindOptions::builder()
+        .sort(doc! { "created_at": -1, "_id": -1 })
+        .limit(MAX_NOTIFICATIONS)
+        .build();
+    let mut cursor = collection
+        .find(doc! { "player_uuid": player_uuid.to_string() }, options)
+        .await?;
+
+    let mut notifications = Vec::new();
+    while cursor.advance().await? {
+        notifications.push(cursor.deserialize_current()?);
+    }
+    Ok(notifications)
+}
+
+#[tracing::instrument(
+    name = "Saving notification in the database",
+    skip(database, notification),
+    fields(player_uuid = %notification.player_uuid)
+)]
+pub async fn insert_notification_in_db(
+    database: &Database,
+    notification: &Notification,
+) -> Result<(), mongodb::error::Error> {
+    database
+        .collection::<Notification>("notifications")
+        .insert_one(notification, None)
+        .await?;
+    Ok(())
+}
+
+/// Notification preferences of a player as stored
+#[derive(Serialize, Deserialize)]
+struct StoredNotificationPreferences {
+    player_uuid: String,
+    #[serde(flatten)]
+    preferences: NotificationPreferences,
+}
+
+/// Email preferences of a player, the defaults if they never saved any
+#[tracing::instrument(
+    name = "Getting notification preferences from the database",
+    skip(database)
+)]
+pub async fn get_notification_preferences_from_db(
+    database: &Database,
+    player_uuid: Uuid,
+) -> Result<NotificationPreferences, mongodb::error::Error> {
+    let stored = database
+        .collection::<StoredNotificationPreferences>(NOTIFICATION_PREFERENCES)
+        .find_one(doc! { "player_uuid": player_uuid.to_string() }, None)
+        .await?;
+    Ok(stored.map(|stored| stored.preferences).unwrap_or_default())
+}
+
+#[tracing::instrument(
+    name = "Saving notification preferences in the database",
+    skip(database)
+)]
+pub async fn save_notification_preferences_in_db(
+    database: &Database,
+    player_uuid: Uuid,
+    preferences: NotificationPreferences,
+) -> Result<(), mongodb::error::Error> {
+    let stored = StoredNotificationPreferences {
+        player_uuid: player_uuid.to_string(),
+        preferences,
+    };
+    database
+        .collection::<StoredNotificationPreferences>(NOTIFICATION_PREFERENCES)
+        .replace_one(
+            doc! { "player_uuid": &stored.player_uuid },
+            &stored,
+            mongodb::options::ReplaceOptions::builder()
+                .upsert(true)
+                .build(),
+        )
+        .await?;
+    Ok(())
+}
+
+/// Store a device token, replacing any registration of the same token
+/// Returns the replaced registration.
+#[tracing::instrument(
+    name = "Saving device token in the database",
+    skip(database, device),
+    fields(player_uuid = %device.player_uuid)
+)]
+pub async fn save_device_token_in_db(
+    database: &Database,
+    device: &DeviceToken,
+) -> Result<Option<DeviceToken>, mongodb::error::Error> {
+    let options = mongodb::options::FindOneAndReplaceOptions::builder()
+        .upsert(true)
+        .build();
+    database
+        .collection::<DeviceToken>(DEVICE_TOKENS)
+        .find_one_and_replace(doc! { "token": &device.token }, device, options)
+        .await
+}
+
+/// Returns whether the player had the device registered.
+#[tracing::instrument(
+    name = "Deleting device token from the database",
+    skip(database, token)
+)]
+pub async fn delete_device_token_from_db(
+    database: &Database,
+    player_uuid: Uuid,
+    token: &str,
+) -> Result<bool, mongodb::error::Error> {
+    let deleted = database
+        .collection::<DeviceToken>(DEVICE_TOKENS)
+        .delete_one(
+            doc! { "player_uuid": player_uuid.to_string(), "token": token },
+            None,
+        )
+        .await?;
+    Ok(deleted.deleted_count > 0)
+}
+
+/// Live and archived races matching `filter`, sorted by `sort`
+pub(crate) async fn find_races_with_archive(
+    database: &Database,
+    filter: Document,
+    sort: Document,
+    limit: Option<usize>,
+) -> Result<Vec<Race>, mongodb::error::Error> {
+    let mut pipeline = vec![
+        doc! { "$match": filter.clone() },
+        union_with_archived_races(filter),
+        doc! { "$sort": sort },
+    ];
+    if let Some(limit) = limit {
+        pipeline.push(doc! { "$limit": i64::try_from(limit).unwrap_or(i64::MAX) });
+    }
+    let mut cursor = database
+        .collection::<Race>("races")
+        .aggregate(pipeline, None)
+        .await?;
+
+    let mut races = Vec::new();
+    while let Some(race) = cursor.try_next().await? {
+        races.push(mongodb::bson::from_document(race)?);
+    }
+    Ok(races)
+}
+
 #[tracing::instrument(
     name = "Connecting wallet to player in the database",
     skip(database, wallet_address)
@@ -0,0 +1,222 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+    routing::{get, post},
+    Router,
+};
+use mongodb::{
+    bson::{doc, DateTime as BsonDateTime},
+    Database,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::database::is_duplicate_key;
+use crate::domain::prediction::{
+    prediction_leaderboard, top_movers, PredictionStanding, SpectatorPrediction,
+};
+use crate::domain::{Race, RaceStatus};
+use crate::routes::races::{get_race_events_since, ErrorResponse};
+
+type ApiError = (StatusCode, Json<ErrorResponse>);
+
+/// Collection holding the predictions of every spectator
+pub const PREDICTIONS_COLLECTION: &str = "spectator_predictions";
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PredictionRequest {
+    /// Identifier the client generates once per viewer, as sent with heartbeats
+    pub viewer_id: String,
+    /// Lap whose top mover is predicted; omit to predict the race winner
+    pub lap: Option<u32>,
+    /// Participant predicted
+    pub player_uuid: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PredictionLeaderboardResponse {
+    pub race_uuid: String,
+    /// Spectators by points, best first
+    pub standings: Vec<PredictionStanding>,
+}
+
+pub fn routes() -> Router<Database> {
+    Router::new()
+        .route("/races/:race_uuid/predictions", post(make_prediction))
+        .route(
+            "/races/:race_uuid/predictions/leaderboard",
+            get(get_prediction_leaderboard),
+        )
+}
+
+fn error(status: StatusCode, error: &str, message: &str) -> ApiError {
+    (
+        status,
+        Json(ErrorResponse {
+            error: error.to_string(),
+            message: message.to_string(),
+            details: None,
+        }),
+    )
+}
+
+fn database_error(e: &mongodb::error::Error) -> ApiError {
+    tracing::error!("Prediction storage failed: {:?}", e);
+    error(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "DATABASE_ERROR",
+        "Internal server error",
+    )
+}
+
+fn invalid_uuid() -> ApiError {
+    error(
+        StatusCode::BAD_REQUEST,
+        "INVALID_UUID",
+        "Invalid UUID format",
+    )
+}
+
+async fn find_race(database: &Database, race_uuid: Uuid) -> Result<Race, ApiError> {
+    database
+        .collection::<Race>("races")
+        .find_one(doc! { "uuid": race_uuid.to_string() }, None)
+        .await
+        .map_err(|e| database_error(&e))?
+        .ok_or_else(|| error(StatusCode::NOT_FOUND, "RACE_NOT_FOUND", "Race not found"))
+}
+
+/// Predict the top mover of a lap or the winner of a race
+///
+/// The top mover of a lap is the car with the best final value. Lap
+/// predictions are open for the lap about to be resolved, winner predictions
+/// until the race finishes. Each spectator gets one prediction per lap and one
+/// for the winner, scored 1 and 5 points when right.
+#[utoipa::path(
+    post,
+    path = "/api/v1/races/{race_uuid}/predictions",
+    params(
+        ("race_uuid" = String, Path, description = "Race UUID")
+    ),
+    request_body = PredictionRequest,
+    responses(
+        (status = 201, description = "Prediction recorded", body = SpectatorPrediction),
+        (status = 400, description = "Invalid UUID format or player not in the race", body = ErrorResponse),
+        (status = 404, description = "Race not found", body = ErrorResponse),
+        (status = 409, description = "Predictions closed, or already predicted", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "races"
+)]
+#[tracing::instrument(name = "Recording spectator prediction", skip(database, request))]
+pub async fn make_prediction(
+    State(database): State<Database>,
+    Path(race_uuid): Path<String>,
+    Json(request): Json<PredictionRequest>,
+) -> Result<(StatusCode, Json<SpectatorPrediction>), ApiError> {
+    let (Ok(race_uuid), Ok(viewer_id), Ok(player_uuid)) = (
+        Uuid::parse_str(&race_uuid),
+        Uuid::parse_str(&request.viewer_id),
+        Uuid::parse_str(&request.player_uuid),
+    ) else {
+        return Err(invalid_uuid());
+    };
+
+    let race = find_race(&database, race_uuid).await?;
+    if !race
+        .participants
+        .iter()
+        .any(|p| p.player_uuid == player_uuid)
+    {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "NOT_PARTICIPANT",
+            "The predicted player is not in this race",
+        ));
+    }
+    let open = match request.lap {
+        Some(lap) => match race.status {
+            RaceStatus::Waiting | RaceStatus::Starting => lap == 1,
+            RaceStatus::InProgress => lap == race.current_lap,
+            RaceStatus::Finished | RaceStatus::Cancelled => false,
+        },
+        None => !matches!(race.status, RaceStatus::Finished | RaceStatus::Cancelled),
+    };
+    if !open {
+        return Err(error(
+            StatusCode::CONFLICT,
+            "PREDICTION_CLOSED",
+            "Predictions for this lap or race are closed",
+        ));
+    }
+
+    let prediction = SpectatorPrediction::new(race_uuid, viewer_id, request.lap, player_uuid);
+    let inserted = database
+        .collection::<SpectatorPrediction>(PREDICTIONS_COLLECTION)
+        .insert_one(&prediction, None)
+        .await;
+    match inserted {
+        Ok(_) => Ok((StatusCode::CREATED, Json(prediction))),
+        // A unique index allows one prediction per spectator and lap
+        Err(e) if is_duplicate_key(&e) => Err(error(
+            StatusCode::CONFLICT,
+            "ALREADY_PREDICTED",
+            "You already made this prediction",
+        )),
+        Err(e) => Err(database_error(&e)),
+    }
+}
+
+/// Spectators of a race ranked by prediction points
+#[utoipa::path(
+    get,
+    path = "/api/v1/races/{race_uuid}/predictions/leaderboard",
+    params(
+        ("race_uuid" = String, Path, description = "Race UUID")
+    ),
+    responses(
+        (status = 200, description = "Prediction leaderboard", body = PredictionLeaderboardResponse),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse),
+        (status = 404, description = "Race not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "races"
+)]
+#[tracing::instrument(name = "Getting prediction leaderboard", skip(database))]
+pub async fn get_prediction_leaderboard(
+    State(database): State<Database>,
+    Path(race_uuid): Path<String>,
+) -> Result<Json<PredictionLeaderboardResponse>, ApiError> {
+    let race_uuid = Uuid::parse_str(&race_uuid).map_err(|_| invalid_uuid())?;
+    let race = find_race(&database, race_uuid).await?;
+
+    let mut cursor = database
+        .collection::<SpectatorPrediction>(PREDICTIONS_COLLECTION)
+        .find(doc! { "race_uuid": race_uuid.to_string() }, None)
+        .await
+        .map_err(|e| database_error(&e))?;
+    let mut predictions = Vec::new();
+    while cursor.advance().await.map_err(|e| database_error(&e))? {
+        predictions.push(
+            cursor
+                .deserialize_current()
+                .map_err(|e| database_error(&e))?,
+        );
+    }
+
+    let changes: Vec<_> =
+        get_race_events_since(&database, race_uuid, BsonDateTime::MIN, usize::MAX)
+            .await
+            .map_err(|e| database_error(&e))?
+            .into_iter()
+            .map(|event| event.change)
+            .collect();
+    let standings = prediction_leaderboard(&predictions, &race, &top_movers(&changes));
+
+    Ok(Json(PredictionLeaderboardResponse {
+        race_uuid: race_uuid.to_string(),
+        standings,
+    }))
+}
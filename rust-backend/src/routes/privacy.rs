@@ -0,0 +1,189 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::Json,
+    routing::{delete, get, post},
+    Extension, Router,
+};
+use mongodb::{
+    bson::{doc, DateTime as BsonDateTime},
+    Database,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::domain::Player;
+use crate::middleware::UserContext;
+use crate::routes::races::ErrorResponse;
+use crate::services::privacy::{export_player_data, PlayerDataExport};
+use crate::services::{record_audit, AuditEntry};
+
+type ApiError = (StatusCode, Json<ErrorResponse>);
+
+/// Routes acting on the authenticated player; mounted behind authentication
+pub fn routes() -> Router<Database> {
+    Router::new()
+        .route("/players/me/export", get(export_my_data))
+        .route("/players/me", delete(request_erasure))
+        .route("/players/me/restore", post(cancel_erasure))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErasureResponse {
+    /// When the erasure was requested; absent once it was cancelled
+    pub erasure_requested_at: Option<String>,
+    pub message: String,
+}
+
+fn error(status: StatusCode, error: &str, message: &str) -> ApiError {
+    (
+        status,
+        Json(ErrorResponse {
+            error: error.to_string(),
+            message: message.to_string(),
+            details: None,
+        }),
+    )
+}
+
+fn database_error(e: &mongodb::error::Error) -> ApiError {
+    tracing::error!("Player data request failed: {:?}", e);
+    error(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "DATABASE_ERROR",
+        "Internal server error",
+    )
+}
+
+fn player_not_found() -> ApiError {
+    error(
+        StatusCode::NOT_FOUND,
+        "PLAYER_NOT_FOUND",
+        "Player not found",
+    )
+}
+
+/// Everything stored about the authenticated player, as one JSON archive
+#[utoipa::path(
+    get,
+    path = "/api/v1/players/me/export",
+    responses(
+        (status = 200, description = "The player's data", body = PlayerDataExport),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "Player not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Exporting player data", skip(database, user))]
+pub async fn export_my_data(
+    State(database): State<Database>,
+    Extension(user): Extension<UserContext>,
+) -> Result<Json<PlayerDataExport>, ApiError> {
+    let export = export_player_data(&database, user.user_uuid)
+        .await
+        .map_err(|e| database_error(&e))?
+        .ok_or_else(player_not_found)?;
+    record_audit(
+        &database,
+        AuditEntry::new("player.export").by(user.user_uuid),
+    )
+    .await;
+    Ok(Json(export))
+}
+
+/// Erase the authenticated player's account after the grace period
+///
+/// The profile stays until `erasure.grace_days` have passed, then it and the
+/// player's personal records are purged; their race results are kept under
+/// their UUID alone. Asking again keeps the first request.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/players/me",
+    responses(
+        (status = 202, description = "Erasure scheduled", body = ErasureResponse),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "Player not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Requesting player erasure", skip(database, user))]
+pub async fn request_erasure(
+    State(database): State<Database>,
+    Extension(user): Extension<UserContext>,
+) -> Result<(StatusCode, Json<ErasureResponse>), ApiError> {
+    let players = database.collection::<Player>("players");
+    let filter = doc! { "uuid": user.user_uuid.to_string() };
+    players
+        .update_one(
+            doc! { "uuid": user.user_uuid.to_string(), "erasure_requested_at": null },
+            doc! { "$set": { "erasure_requested_at": BsonDateTime::now() } },
+            None,
+        )
+        .await
+        .map_err(|e| database_error(&e))?;
+    let player = players
+        .find_one(filter, None)
+        .await
+        .map_err(|e| database_error(&e))?
+        .ok_or_else(player_not_found)?;
+
+    record_audit(
+        &database,
+        AuditEntry::new("player.request_erasure").by(user.user_uuid),
+    )
+    .await;
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(ErasureResponse {
+            erasure_requested_at: player
+                .erasure_requested_at
+                .and_then(|at| at.try_to_rfc3339_string().ok()),
+            message: "Account scheduled for erasure".to_string(),
+        }),
+    ))
+}
+
+/// Cancel the authenticated player's pending erasure
+#[utoipa::path(
+    post,
+    path = "/api/v1/players/me/restore",
+    responses(
+        (status = 200, description = "Erasure cancelled", body = ErasureResponse),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "Player not found, or already erased", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Cancelling player erasure", skip(database, user))]
+pub async fn cancel_erasure(
+    State(database): State<Database>,
+    Extension(user): Extension<UserContext>,
+) -> Result<Json<ErasureResponse>, ApiError> {
+    let result = database
+        .collection::<Player>("players")
+        .update_one(
+            doc! { "uuid": user.user_uuid.to_string() },
+            doc! { "$unset": { "erasure_requested_at": "" } },
+            None,
+        )
+        .await
+        .map_err(|e| database_error(&e))?;
+    if result.matched_count == 0 {
+        return Err(player_not_found());
+    }
+
+    if result.modified_count > 0 {
+        record_audit(
+            &database,
+            AuditEntry::new("player.cancel_erasure").by(user.user_uuid),
+        )
+        .await;
+    }
+    Ok(Json(ErasureResponse {
+        erasure_requested_at: None,
+        message: "Account erasure cancelled".to_string(),
+    }))
+}
@@ -1,28 +1,48 @@
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
-    response::Json,
+    extract::{Extension, Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{Json, Response},
     routing::{get, post},
     Router,
 };
 use chrono::{DateTime, Utc};
 use mongodb::{
-    bson::{doc, DateTime as BsonDateTime},
+    bson::{doc, DateTime as BsonDateTime, Document},
     Database,
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
+use crate::database::{is_circuit_open, with_retry};
+use crate::domain::award::race_awards;
 use crate::domain::boost_hand_manager::{
     BoostAvailability, BoostCardErrorResponse, BoostHandManager,
 };
+use crate::domain::commentary;
 use crate::domain::{
-    LapAction, LapCharacteristic, LapResult, MovementProbability, MovementType,
-    PerformanceCalculation, Race, RaceStatus, Sector, SectorType, Track,
+    BoostCommitError, BoostHand, BotPersonality, BotProfile, CapacityChange, ComponentRarity,
+    Ghost, IndividualLapResult, LapAction, LapCharacteristic, LapResult, MovementProbability,
+    MovementType, PerformanceCalculation, PerformanceModelKind, PilotClass, PilotRarity, Race,
+    RaceChange, RaceEvent, RaceMode, RaceRules, RaceStatus, Sector, SectorModifiers, SectorType,
+    Track, TrackTemplate, TrackValidationError,
 };
+use crate::middleware::UserContext;
+use crate::routes::etag::{is_not_modified, not_modified, race_etag, with_etag};
+use crate::routes::field_selection::FieldSelection;
+use crate::routes::moderation::is_player_banned_in_db;
+use crate::routes::pagination::{encode_cursor, paginate, CursorQuery, TimePosition};
+use crate::routes::players::{get_player_by_uuid_from_db, get_skill_rating_from_db};
+use crate::routes::spectators::count_spectators;
+use crate::routes::tracks::{get_track_template_by_id, load_track_design, parse_uuid};
+use crate::routes::v2;
 use crate::services::car_validation::{CarValidationService, ValidatedCarData};
+use crate::services::loaner_cars::{is_loaner_car, loaner_cars};
+use crate::services::outbox::{push_outbox, OutboxPayload};
+use crate::services::{
+    record_audit, AuditEntry, CarDataCache, DomainEvent, OutboxMessage, RaceCache,
+};
 
 // Helper function to convert to BSON with proper error handling
 fn to_bson_safe<T: serde::Serialize>(
@@ -37,9 +57,22 @@ fn to_bson_safe<T: serde::Serialize>(
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateRaceRequest {
     pub name: String,
+    /// Track name; defaults to the template name when `template_id` is used
+    #[serde(default)]
     pub track_name: String,
+    /// Hand-authored sectors; leave empty when using `template_id`
+    #[serde(default)]
     pub sectors: Vec<CreateSectorRequest>,
+    /// Built-in track template to use instead of `sectors` (see `GET /api/v1/tracks/templates`)
+    #[serde(default)]
+    pub template_id: Option<String>,
+    /// Stored track to use instead of `sectors` (see `GET /api/v1/tracks`)
+    #[serde(default)]
+    pub track_uuid: Option<String>,
     pub total_laps: u32,
+    /// Optional rules for the race; every option is off when omitted
+    #[serde(default)]
+    pub rules: RaceRules,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -50,6 +83,10 @@ pub struct CreateSectorRequest {
     pub max_value: u32,
     pub slot_capacity: Option<u32>,
     pub sector_type: SectorType,
+    #[serde(default)]
+    pub capacity_schedule: Vec<CapacityChange>,
+    #[serde(default)]
+    pub modifiers: Option<SectorModifiers>,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -59,6 +96,79 @@ pub struct JoinRaceRequest {
     pub pilot_uuid: String,
 }
 
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AddBotRequest {
+    #[serde(default)]
+    pub personality: BotPersonality,
+    /// 0.0 (random cards) to 1.0 (always follows its personality), defaults to 0.5
+    #[serde(default = "default_bot_difficulty")]
+    pub difficulty: f64,
+}
+
+fn default_bot_difficulty() -> f64 {
+    0.5
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreatePracticeRaceRequest {
+    pub player_uuid: String,
+    pub car_uuid: String,
+    pub pilot_uuid: String,
+    /// Built-in track template to practice on, defaults to `beginner-loop`
+    #[serde(default = "default_practice_template")]
+    pub template_id: String,
+    /// Defaults to the template's recommended lap count
+    #[serde(default)]
+    pub total_laps: Option<u32>,
+    #[serde(default = "default_practice_bot_count")]
+    pub bot_count: u32,
+    #[serde(default = "default_bot_difficulty")]
+    pub bot_difficulty: f64,
+}
+
+fn default_practice_template() -> String {
+    "beginner-loop".to_string()
+}
+
+fn default_practice_bot_count() -> u32 {
+    3
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateTimeTrialRequest {
+    pub player_uuid: String,
+    pub car_uuid: String,
+    pub pilot_uuid: String,
+    /// Built-in track template to run on, defaults to `beginner-loop`; ignored with `ghost_uuid`
+    #[serde(default = "default_practice_template")]
+    pub template_id: String,
+    /// Turns available to complete as many laps as possible; ignored with `ghost_uuid`
+    #[serde(default = "default_time_trial_turns")]
+    pub turns: u32,
+    /// Recorded run to race against, on its track and turn count
+    #[serde(default)]
+    pub ghost_uuid: Option<String>,
+}
+
+fn default_time_trial_turns() -> u32 {
+    10
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct FastForwardRequest {
+    pub player_uuid: String,
+    pub car_uuid: String,
+    /// Number of laps to play on the player's behalf
+    pub laps: u32,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FastForwardResponse {
+    pub race: Race,
+    /// Results of the laps processed, in order
+    pub lap_results: Vec<LapResult>,
+}
+
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct ProcessLapRequest {
     pub actions: Vec<LapActionRequest>,
@@ -75,6 +185,28 @@ pub struct LapActionRequest {
 pub struct SubmitTurnActionRequest {
     pub player_uuid: String,
     pub boost_value: u32,
+    /// Salt the boost was committed with, in races using commit–reveal
+    #[serde(default)]
+    pub salt: Option<String>,
+}
+
+/// Request to commit to a boost in a race using commit–reveal
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CommitBoostRequest {
+    /// Hex SHA-256 of `"{boost_value}:{salt}"`, with a random salt of at
+    /// least 16 bytes kept secret until the reveal
+    pub commitment: String,
+}
+
+/// Response after committing to a boost
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CommitBoostResponse {
+    pub success: bool,
+    pub message: String,
+    /// Whether every player has committed, so boosts can now be revealed
+    pub reveals_open: bool,
+    /// Players still to commit for this lap
+    pub awaiting_players: Vec<String>,
 }
 
 /// Response after submitting a turn action
@@ -82,7 +214,8 @@ pub struct SubmitTurnActionRequest {
 pub struct SubmitTurnActionResponse {
     pub success: bool,
     pub message: String,
-    pub turn_phase: String, // "WaitingForPlayers", "Processing", "TurnProcessed"
+    /// `WaitingForPlayers` or `TurnProcessed`
+    pub turn_phase: TurnPhase,
     pub players_submitted: u32,
     pub total_players: u32,
 }
@@ -104,7 +237,10 @@ pub struct LapResultResponse {
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct RegisterPlayerRequest {
     pub player_uuid: String,
-    pub car_uuid: String,
+    /// Car to race; omit to borrow a loaner car when the player has no
+    /// eligible car of their own
+    #[serde(default)]
+    pub car_uuid: Option<String>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -113,6 +249,9 @@ pub struct RegisterPlayerResponse {
     pub message: String,
     pub race_status: RaceProgressStatus,
     pub player_position: PlayerRacePosition,
+    /// Car the player races, the loaner's when one was lent
+    pub car_uuid: String,
+    pub is_loaner: bool,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -124,15 +263,16 @@ pub struct PlayerRacePosition {
 
 #[derive(Debug, Deserialize)]
 pub struct StatusQueryParams {
-    pub player_uuid: Option<String>,   // For player-specific data
     pub include_history: Option<bool>, // Include lap history
+    /// Comma-separated fields to return, e.g. `race_progress,player_data.boost_availability`
+    pub fields: Option<String>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct DetailedRaceStatusResponse {
     pub race_progress: RaceProgressStatus,
     pub track_situation: TrackSituationData,
-    pub player_data: Option<PlayerSpecificData>, // Only if player_uuid provided
+    pub player_data: Option<PlayerSpecificData>, // Only for a participant viewing
     pub race_metadata: RaceMetadata,
 }
 
@@ -144,12 +284,10 @@ pub struct DetailedRaceStatusResponse {
 /// When all cards are used, the hand automatically replenishes.
 ///
 /// # Performance Calculation
-/// Final performance = `base_performance` * (1 + `boost_value` * 0.08)
-/// - `boost_value` 0: No boost (1.0x multiplier)
-/// - `boost_value` 1: 8% boost (1.08x multiplier)
-/// - `boost_value` 2: 16% boost (1.16x multiplier)
-/// - `boost_value` 3: 24% boost (1.24x multiplier)
-/// - `boost_value` 4: 32% boost (1.32x multiplier)
+/// Final performance depends on the race's `performance_model` rule:
+/// - `Additive` (default): capped base value + `boost_value`
+/// - `Multiplicative`: capped base value * (1 + `boost_value` * 0.08)
+/// - `DiminishingReturns`: like multiplicative, each extra step worth half the previous one
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct ApplyLapRequest {
     /// UUID of the player making the lap action
@@ -163,6 +301,16 @@ pub struct ApplyLapRequest {
     /// Boost card value to use (0-4). Must be available in current cycle.
     #[schema(example = 3, minimum = 0, maximum = 4)]
     pub boost_value: u32,
+
+    /// Pit instead of racing this lap (only from a `PitLane` sector).
+    /// The car holds its position and its boost hand is refilled; `boost_value` is ignored.
+    #[serde(default)]
+    pub pit_stop: bool,
+
+    /// Salt the boost was committed with, in races using commit–reveal.
+    /// The action is the reveal, accepted once every player has committed.
+    #[serde(default)]
+    pub salt: Option<String>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -174,14 +322,24 @@ pub struct RaceProgressStatus {
     pub turn_phase: TurnPhase,
     pub participants_count: u32,
     pub finished_participants: u32,
+    /// When the first lap opens, while the race is `Starting`
+    pub starts_at: Option<DateTime<Utc>>,
+    /// When the current lap stops waiting for actions, if the race has a turn time limit
+    pub deadline_at: Option<DateTime<Utc>>,
+    /// Whole seconds left before `deadline_at`
+    pub seconds_remaining: Option<u64>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
 pub enum RaceStatusType {
     Waiting,
+    /// Counting down to the first lap
+    Starting,
     Ongoing,
     Finished,
-    Error { message: String },
+    Error {
+        message: String,
+    },
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -234,11 +392,15 @@ pub struct PerformanceThresholds {
     pub max_value: u32,
     pub move_up_threshold: u32,
     pub move_down_threshold: u32,
+    /// Grip/elevation applied to car contributions before the ceiling
+    pub modifiers: Option<SectorModifiers>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct ParticipantMovement {
     pub player_uuid: String,
+    /// Lap the movement was resolved on
+    pub lap: u32,
     pub from_sector: u32,
     pub to_sector: u32,
     pub movement_type: MovementType,
@@ -303,6 +465,14 @@ pub struct RaceMetadata {
     pub start_time: Option<DateTime<Utc>>,
     pub estimated_completion: Option<DateTime<Utc>>,
     pub total_turns: u32,
+    /// Version of the game rules the race resolves under
+    pub rules_version: u32,
+    /// Viewers currently watching, from their heartbeats
+    pub spectator_count: u64,
+    /// Hash of the final classification and event log written on-chain
+    pub result_hash: Option<String>,
+    /// Solana transaction holding `result_hash`, once the finished race is anchored
+    pub anchor_transaction_signature: Option<String>,
 }
 
 // Car Data Endpoint Response Models
@@ -326,8 +496,8 @@ pub struct CarInfo {
 pub struct PilotInfo {
     pub uuid: String,
     pub name: String,
-    pub pilot_class: String,
-    pub rarity: String,
+    pub pilot_class: PilotClass,
+    pub rarity: PilotRarity,
     pub skills: PilotSkills,
     pub performance: PilotPerformance,
     pub nft_mint_address: Option<String>,
@@ -351,7 +521,7 @@ pub struct PilotPerformance {
 pub struct EngineInfo {
     pub uuid: String,
     pub name: String,
-    pub rarity: String,
+    pub rarity: ComponentRarity,
     pub straight_value: u8,
     pub curve_value: u8,
     pub nft_mint_address: Option<String>,
@@ -361,7 +531,7 @@ pub struct EngineInfo {
 pub struct BodyInfo {
     pub uuid: String,
     pub name: String,
-    pub rarity: String,
+    pub rarity: ComponentRarity,
     pub straight_value: u8,
     pub curve_value: u8,
     pub nft_mint_address: Option<String>,
@@ -371,12 +541,16 @@ pub struct BodyInfo {
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct TurnPhaseResponse {
-    pub turn_phase: String, // "WaitingForPlayers", "AllSubmitted", "Processing", "TurnProcessed", "Complete"
+    pub turn_phase: TurnPhase,
     pub current_lap: u32,
-    pub lap_characteristic: String,
+    pub lap_characteristic: LapCharacteristic,
     pub submitted_players: Vec<String>, // UUIDs
     pub pending_players: Vec<String>,   // UUIDs
     pub total_active_players: u32,
+    /// When the current lap stops waiting for actions, if the race has a turn time limit
+    pub deadline_at: Option<DateTime<Utc>>,
+    /// Whole seconds left before `deadline_at`, as of the response
+    pub seconds_remaining: Option<u64>,
 }
 
 // Local View Endpoint Response Models
@@ -384,6 +558,8 @@ pub struct TurnPhaseResponse {
 #[derive(Debug, Serialize, ToSchema)]
 pub struct LocalViewResponse {
     pub center_sector: u32,
+    /// Sectors either side of the center that are visible
+    pub radius: u32,
     pub visible_sectors: Vec<SectorInfo>,
     pub visible_participants: Vec<ParticipantInfo>,
 }
@@ -395,7 +571,7 @@ pub struct SectorInfo {
     pub min_value: u32,
     pub max_value: u32,
     pub slot_capacity: Option<u32>,
-    pub sector_type: String,
+    pub sector_type: SectorType,
     pub current_occupancy: u32,
 }
 
@@ -418,6 +594,8 @@ pub struct PerformancePreviewResponse {
     pub base_performance: BasePerformance,
     pub boost_options: Vec<BoostOption>,
     pub boost_cycle_info: BoostCycleInfo,
+    /// Performance formula used by this race to compute `final_value`
+    pub performance_model: PerformanceModelKind,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -425,10 +603,14 @@ pub struct BasePerformance {
     pub engine_contribution: u32,
     pub body_contribution: u32,
     pub pilot_contribution: u32,
+    /// Success ballast already taken off `base_value`
+    pub ballast: u32,
     pub base_value: u32,
     pub sector_ceiling: u32,
     pub capped_base_value: u32,
-    pub lap_characteristic: String, // "Straight" or "Curve"
+    /// Catch-up assist bonus included in every boost option's final value
+    pub catch_up_bonus: u32,
+    pub lap_characteristic: LapCharacteristic,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -436,7 +618,7 @@ pub struct BoostOption {
     pub boost_value: u8,
     pub is_available: bool,
     pub final_value: u32,
-    pub movement_probability: String, // "MoveUp", "Stay", "MoveDown"
+    pub movement_probability: MovementProbability,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -457,6 +639,18 @@ pub struct BoostAvailabilityResponse {
     pub cycles_completed: u32,
     pub cards_remaining: u32,
     pub next_replenishment_at: Option<u32>,
+    /// Card banked this cycle, handed back when the hand replenishes
+    pub banked_card: Option<u8>,
+    /// Extra card carried over from the previous cycle
+    pub carried_over_card: Option<u8>,
+}
+
+/// Request to bank an unused boost card into the next cycle
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BankCardRequest {
+    /// Boost card value to bank (0-4). Must be unused in the current cycle.
+    #[schema(example = 4, minimum = 0, maximum = 4)]
+    pub boost_value: u8,
 }
 
 // Lap History Endpoint Response Models
@@ -465,18 +659,82 @@ pub struct BoostAvailabilityResponse {
 pub struct LapHistoryResponse {
     pub laps: Vec<LapRecord>,
     pub cycle_summaries: Vec<CycleSummary>,
+    /// Cursor for the next page of `laps`; `None` on the last page
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BoostUsageHistoryResponse {
+    pub records: Vec<crate::domain::BoostUsageRecord>,
+    /// Cursor for the next page; `None` on the last page
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RaceListResponse {
+    pub races: Vec<Race>,
+    /// Cursor for the next page; `None` on the last page
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct LocalViewQuery {
+    /// Sectors to see either side of the player's own; 2 when omitted, and
+    /// lowered to what the race and the pilot allow
+    pub radius: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct RaceListQuery {
+    /// Only races with this status
+    #[param(inline)]
+    pub status: Option<RaceStatus>,
+    /// `next_cursor` from the previous page; omit for the first page
+    pub cursor: Option<String>,
+    /// Races per page (defaults to 20, at most 100)
+    pub limit: Option<u32>,
 }
 
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct RaceChangesQuery {
+    /// Only changes after this time, as RFC 3339 or Unix milliseconds
+    pub since: String,
+}
+
+/// Most changes returned by one request to the changes endpoint
+pub const MAX_RACE_CHANGES: usize = 500;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RaceChangeEntry {
+    pub occurred_at: DateTime<Utc>,
+    pub change: RaceChange,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RaceChangesResponse {
+    /// Changes in the order they happened
+    pub changes: Vec<RaceChangeEntry>,
+    /// Time of the last change returned, or `since`; pass it as `since` next time
+    pub latest: DateTime<Utc>,
+    /// `false` when there were more changes than one response holds, in which
+    /// case the client should reload the whole race instead
+    pub complete: bool,
+}
+
+/// Races listed per page when no limit is requested
+pub const DEFAULT_RACE_PAGE_LIMIT: u32 = 20;
+
 #[derive(Debug, Serialize, ToSchema)]
 pub struct LapRecord {
     pub lap_number: u32,
-    pub lap_characteristic: String,
+    pub lap_characteristic: LapCharacteristic,
     pub boost_used: u8,
     pub boost_cycle: u32,
     pub base_value: u32,
     pub final_value: u32,
     pub from_sector: u32,
     pub to_sector: u32,
+    /// Always `Unknown`: movements of past laps are not stored (v2 drops the field)
     pub movement_type: String,
 }
 
@@ -488,6 +746,33 @@ pub struct CycleSummary {
     pub average_boost: f32,
 }
 
+// v1 lap history is the v2 one with the placeholders v2 dropped
+
+impl From<v2::LapHistoryResponse> for LapHistoryResponse {
+    fn from(response: v2::LapHistoryResponse) -> Self {
+        Self {
+            laps: response
+                .laps
+                .into_iter()
+                .map(|lap| LapRecord {
+                    lap_number: lap.lap_number,
+                    lap_characteristic: lap.lap_characteristic,
+                    boost_used: lap.boost_used,
+                    boost_cycle: lap.boost_cycle,
+                    // Not tracked for past laps
+                    base_value: 0,
+                    final_value: 0,
+                    from_sector: 0,
+                    to_sector: 0,
+                    movement_type: "Unknown".to_string(),
+                })
+                .collect(),
+            cycle_summaries: response.cycle_summaries,
+            next_cursor: response.next_cursor,
+        }
+    }
+}
+
 // Error Response Model
 
 /// Standard error response format used across all endpoints
@@ -504,6 +789,17 @@ pub struct ErrorResponse {
     pub details: Option<String>,
 }
 
+/// Error response for race creation
+///
+/// Invalid track definitions list every offending field in `field_errors`;
+/// other failures leave it empty.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TrackValidationErrorResponse {
+    pub error: String,
+    pub message: String,
+    pub field_errors: Vec<TrackValidationError>,
+}
+
 // Helper Functions
 
 /// Calculate movement probability based on performance value and sector thresholds
@@ -514,7 +810,10 @@ pub struct ErrorResponse {
 ///
 /// # Returns
 /// * `MovementProbability` - `MoveUp` if >= max, Stay if between min/max, `MoveDown` if < min
-fn calculate_movement_probability(final_value: u32, sector: &Sector) -> MovementProbability {
+pub(crate) fn calculate_movement_probability(
+    final_value: u32,
+    sector: &Sector,
+) -> MovementProbability {
     if final_value >= sector.max_value {
         MovementProbability::MoveUp
     } else if final_value < sector.min_value {
@@ -524,71 +823,136 @@ fn calculate_movement_probability(final_value: u32, sector: &Sector) -> Movement
     }
 }
 
-/// Calculate visible sector IDs for local view (center Â±2 sectors)
-///
-/// This function handles circular track wrapping by using modulo arithmetic.
-/// For a track with N sectors, it returns 5 sector IDs centered on the given sector.
-///
-/// # Arguments
-/// * `center` - The center sector ID (player's current sector)
-/// * `total_sectors` - Total number of sectors in the track
-///
-/// # Returns
-/// * `Vec<u32>` - Vector of 5 sector IDs in order (center-2, center-1, center, center+1, center+2)
-///
-/// # Examples
-/// ```
-/// // For a track with 10 sectors, player at sector 1:
-/// // Returns [9, 0, 1, 2, 3] (wraps around at track boundaries)
-/// let visible = get_visible_sector_ids(1, 10);
-/// ```
-fn get_visible_sector_ids(center: u32, total_sectors: usize) -> Vec<u32> {
-    let mut ids = Vec::new();
-    #[allow(clippy::cast_possible_truncation)]
-    let total = total_sectors as i32;
+/// Focus of the pilot a player races with, if the player still has them
+async fn pilot_focus(
+    database: &Database,
+    player_uuid: Uuid,
+    pilot_uuid: Uuid,
+) -> Result<Option<u8>, mongodb::error::Error> {
+    let player = get_player_by_uuid_from_db(database, player_uuid).await?;
+    Ok(player.and_then(|player| {
+        player
+            .pilots
+            .iter()
+            .find(|pilot| pilot.uuid == pilot_uuid)
+            .map(|pilot| pilot.skills.focus)
+    }))
+}
 
-    // Calculate center Â±2 sectors with proper wrapping
-    for offset in -2..=2 {
-        let sector_id = (center as i32 + offset).rem_euclid(total) as u32;
-        ids.push(sector_id);
+/// Focus of `viewer`'s pilot, when it bounds how far fog of war lets them see
+///
+/// Only looked up in races with both `fog_of_war` and `focus_limited_view`
+/// that the viewer races in; `None` otherwise.
+pub(crate) async fn viewer_focus(
+    database: &Database,
+    race: &Race,
+    viewer: Option<Uuid>,
+) -> Result<Option<u8>, mongodb::error::Error> {
+    if !race.rules.fog_of_war || !race.rules.focus_limited_view {
+        return Ok(None);
+    }
+    match race
+        .participants
+        .iter()
+        .find(|p| Some(p.player_uuid) == viewer)
+    {
+        Some(participant) => {
+            pilot_focus(database, participant.player_uuid, participant.pilot_uuid).await
+        }
+        None => Ok(None),
     }
+}
+
+/// Build the boost availability response for a player's hand
+fn build_boost_availability_response(boost_hand: &BoostHand) -> BoostAvailabilityResponse {
+    // When cards_remaining reaches 0, replenishment happens automatically
+    let next_replenishment_at = if boost_hand.cards_remaining > 0 {
+        Some(boost_hand.cards_remaining)
+    } else {
+        None
+    };
 
-    ids
+    BoostAvailabilityResponse {
+        available_cards: boost_hand.get_available_cards(),
+        hand_state: boost_hand.cards.clone(),
+        current_cycle: boost_hand.current_cycle,
+        cycles_completed: boost_hand.cycles_completed,
+        cards_remaining: boost_hand.cards_remaining,
+        next_replenishment_at,
+        banked_card: boost_hand.banked_card,
+        carried_over_card: boost_hand.carried_over_card,
+    }
 }
 
-pub fn routes() -> Router<Database> {
+/// Race listings and details; mounted behind the auth middleware, which also
+/// lets in third-party sites with a `read:races` API token
+///
+/// The signed-in player is the viewer that boost hands and fog of war are
+/// applied for.
+pub fn read_routes() -> Router<Database> {
     Router::new()
-        // Public routes (no authentication required)
         .route("/races", get(get_all_races))
+        .route("/races/paged", get(get_races_page))
         .route("/races/:race_uuid", get(get_race))
         .route("/races/:race_uuid/status", get(get_race_status))
-        // Enhanced API endpoints
-        .route("/races/:race_uuid/register", post(register_player))
         .route(
             "/races/:race_uuid/status-detailed",
             get(get_race_status_detailed),
         )
-        .route("/races/:race_uuid/apply-lap", post(apply_lap_action))
-        // New player-specific endpoints
+        .route("/races/:race_uuid/changes", get(get_race_changes))
         .route(
-            "/races/:race_uuid/players/:player_uuid/car-data",
-            get(get_car_data),
+            "/races/:race_uuid/players/:player_uuid/local-view",
+            get(get_local_view),
         )
+}
+
+/// v1 lap history; mounted with the read routes, behind the auth middleware
+pub fn lap_history_routes() -> Router<Database> {
+    Router::new().route(
+        "/races/:race_uuid/players/:player_uuid/lap-history",
+        get(get_lap_history),
+    )
+}
+
+/// A participant's boost hand, what each card would do and the boosts they played
+///
+/// Mounted behind `AuthMiddleware` and `RequireOwnership::player("player_uuid")`,
+/// so only the participant (or an admin) sees them.
+pub fn hand_routes() -> Router<Database> {
+    Router::new()
         .route(
             "/races/:race_uuid/players/:player_uuid/performance-preview",
             get(get_performance_preview),
         )
-        .route(
-            "/races/:race_uuid/players/:player_uuid/local-view",
-            get(get_local_view),
-        )
         .route(
             "/races/:race_uuid/players/:player_uuid/boost-availability",
             get(get_boost_availability),
         )
         .route(
-            "/races/:race_uuid/players/:player_uuid/lap-history",
-            get(get_lap_history),
+            "/races/:race_uuid/players/:player_uuid/boost-usage-history",
+            get(get_boost_usage_history),
+        )
+}
+
+/// Race routes acting as the token's player; mounted behind `AuthMiddleware`
+pub fn player_routes() -> Router<Database> {
+    Router::new().route("/races/:race_uuid/commit-boost", post(commit_boost))
+}
+
+/// Race routes answering the same way in every API version
+pub fn routes() -> Router<Database> {
+    Router::new()
+        // Enhanced API endpoints
+        .route("/races/:race_uuid/register", post(register_player))
+        .route("/races/:race_uuid/apply-lap", post(apply_lap_action))
+        // New player-specific endpoints
+        .route(
+            "/races/:race_uuid/players/:player_uuid/car-data",
+            get(get_car_data),
+        )
+        .route(
+            "/races/:race_uuid/players/:player_uuid/bank-card",
+            post(bank_boost_card),
         )
         // Race-level endpoint
         .route("/races/:race_uuid/turn-phase", get(get_turn_phase))
@@ -600,6 +964,12 @@ pub fn routes() -> Router<Database> {
         // Routes that require authentication:
         .route("/races", post(create_race)) // Any authenticated user can create
         .route("/races/:race_uuid/join", post(join_race)) // Any authenticated user can join
+        .route("/races/:race_uuid/bots", post(add_bot)) // Race creator or admin
+        // Practice races skip registration and never count toward rankings
+        .route("/races/practice", post(create_practice_race))
+        .route("/races/:race_uuid/fast-forward", post(fast_forward_race))
+        .route("/races/time-trial", post(create_time_trial))
+        .route("/races/ghosts/:ghost_uuid", get(get_ghost))
         // Routes that require race ownership or admin role:
         .route("/races/:race_uuid/start", post(start_race)) // Race creator or admin
         .route("/races/:race_uuid/turn", post(process_turn)) // Race participants or admin
@@ -607,12 +977,187 @@ pub fn routes() -> Router<Database> {
 
 // Helper Functions for Enhanced API
 
+/// Reject entries outside the race's skill bracket or car tier, and find the
+/// car class the entry competes in
+///
+/// `car_data` is the already validated car, if any; it is looked up when the
+/// race has a car tier or car classes.
+async fn check_entry_window(
+    database: &Database,
+    race: &Race,
+    player_uuid: Uuid,
+    car_uuid: Uuid,
+    car_data: Option<&ValidatedCarData>,
+) -> Result<Option<String>, (StatusCode, Json<ErrorResponse>)> {
+    let refused = |status: StatusCode, code: &str, message: String| {
+        (
+            status,
+            Json(ErrorResponse {
+                error: code.to_string(),
+                message,
+                details: None,
+            }),
+        )
+    };
+
+    let mut car_class = None;
+    if race.rules.car_tier.is_some() || !race.rules.car_classes.is_empty() {
+        let validated;
+        let car_data = match car_data {
+            Some(car_data) => car_data,
+            None => {
+                validated = CarValidationService::validate_car_for_race(
+                    database,
+                    player_uuid,
+                    car_uuid,
+                    &race.rules.allowed_rarities,
+                )
+                .await
+                .map_err(|e| {
+                    tracing::warn!("Car validation failed: {}", e);
+                    refused(StatusCode::BAD_REQUEST, e.error_code(), e.user_message())
+                })?;
+                &validated
+            }
+        };
+        car_class = check_car_entry(race, car_data).map_err(|(code, message)| {
+            tracing::warn!(
+                "Car {} refused from race {}: {}",
+                car_uuid,
+                race.uuid,
+                message
+            );
+            refused(StatusCode::FORBIDDEN, code, message)
+        })?;
+    }
+
+    if let Some(skill_bracket) = race.rules.skill_bracket {
+        let skill_rating = get_skill_rating_from_db(database, player_uuid)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to rate player {}: {:?}", player_uuid, e);
+                refused(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "DATABASE_ERROR",
+                    "Internal server error".to_string(),
+                )
+            })?;
+        if !skill_bracket.contains(skill_rating) {
+            tracing::warn!(
+                "Player {} rated {} is outside the skill bracket of race {}",
+                player_uuid,
+                skill_rating,
+                race.uuid
+            );
+            return Err(refused(
+                StatusCode::FORBIDDEN,
+                "PLAYER_OUTSIDE_BRACKET",
+                format!("Players rated {skill_rating} cannot enter this race"),
+            ));
+        }
+    }
+    Ok(car_class)
+}
+
+/// Check a car against the race's car tier and find the car class it competes in
+///
+/// Returns the error code and message when the car may not enter.
+fn check_car_entry(
+    race: &Race,
+    car_data: &ValidatedCarData,
+) -> Result<Option<String>, (&'static str, String)> {
+    let car_rating = car_data.performance_rating();
+    if let Some(car_tier) = race.rules.car_tier {
+        if !car_tier.contains(car_rating) {
+            return Err((
+                "CAR_OUTSIDE_TIER",
+                format!("Cars rated {car_rating} cannot enter this race"),
+            ));
+        }
+    }
+
+    if race.rules.car_classes.is_empty() {
+        return Ok(None);
+    }
+    match race.rules.car_class_for(car_rating, car_data.top_rarity()) {
+        Some(class) => Ok(Some(class.name.clone())),
+        None => Err((
+            "NO_CAR_CLASS",
+            "The car fits none of the race's car classes".to_string(),
+        )),
+    }
+}
+
+/// Lend a loaner car fitting the race to a player without an eligible car
+///
+/// The weakest fitting loaner is lent. Players with a car of their own that
+/// could enter the race must register with it instead.
+async fn lend_loaner_car(
+    database: &Database,
+    race: &Race,
+    player_uuid: Uuid,
+) -> Result<ValidatedCarData, (StatusCode, Json<ErrorResponse>)> {
+    let error = |status: StatusCode, code: &str, message: &str| {
+        (
+            status,
+            Json(ErrorResponse {
+                error: code.to_string(),
+                message: message.to_string(),
+                details: None,
+            }),
+        )
+    };
+    let fits = |car_data: &ValidatedCarData| {
+        CarValidationService::check_allowed_rarities(car_data, &race.rules.allowed_rarities).is_ok()
+            && check_car_entry(race, car_data).is_ok()
+    };
+
+    let player = match get_player_by_uuid_from_db(database, player_uuid).await {
+        Ok(Some(player)) => player,
+        Ok(None) => {
+            return Err(error(
+                StatusCode::NOT_FOUND,
+                "PLAYER_NOT_FOUND",
+                "Player not found",
+            ))
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch player: {:?}", e);
+            return Err(error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "DATABASE_ERROR",
+                "Internal server error",
+            ));
+        }
+    };
+    let has_eligible_car = player.cars.iter().any(|car| {
+        CarValidationService::validate_owned_car(&player, car.uuid).is_ok_and(|data| fits(&data))
+    });
+    if has_eligible_car {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "CAR_REQUIRED",
+            "Register with one of your cars; loaners are for players without an eligible car",
+        ));
+    }
+
+    loaner_cars().into_iter().find(fits).ok_or_else(|| {
+        error(
+            StatusCode::FORBIDDEN,
+            "NO_LOANER_AVAILABLE",
+            "No loaner car can enter this race",
+        )
+    })
+}
+
 async fn register_player_in_race(
     database: &Database,
     race_uuid: Uuid,
     player_uuid: Uuid,
     car_uuid: Uuid,
     pilot_uuid: Uuid,
+    car_class: Option<String>,
+    is_loaner: bool,
 ) -> Result<Option<Race>, mongodb::error::Error> {
     let collection = database.collection::<Race>("races");
 
@@ -621,10 +1166,16 @@ async fn register_player_in_race(
         return Ok(None);
     };
 
+    let before = race.clone();
+
     // Try to add participant
     if let Err(e) = race.add_participant(player_uuid, car_uuid, pilot_uuid) {
         return Err(mongodb::error::Error::custom(e));
     }
+    if let Some(participant) = race.participants.last_mut() {
+        participant.car_class = car_class;
+        participant.is_loaner = is_loaner;
+    }
 
     // Update the race in database
     let filter = doc! { "uuid": race_uuid.to_string() };
@@ -638,7 +1189,16 @@ async fn register_player_in_race(
         }
     };
 
-    collection.find_one_and_update(filter, update, None).await
+    let result = collection.find_one_and_update(filter, update, None).await?;
+    record_race_changes(
+        database,
+        AuditEntry::new("race.register").by(player_uuid),
+        &before,
+        &race,
+        &[],
+    )
+    .await;
+    Ok(result)
 }
 
 fn get_player_race_position(race: &Race, player_uuid: Uuid) -> Result<PlayerRacePosition, String> {
@@ -668,9 +1228,10 @@ fn get_player_race_position(race: &Race, player_uuid: Uuid) -> Result<PlayerRace
     })
 }
 
-fn build_race_progress_status(race: &Race) -> RaceProgressStatus {
+pub(crate) fn build_race_progress_status(race: &Race) -> RaceProgressStatus {
     let status = match race.status {
         RaceStatus::Waiting => RaceStatusType::Waiting,
+        RaceStatus::Starting => RaceStatusType::Starting,
         RaceStatus::InProgress => RaceStatusType::Ongoing,
         RaceStatus::Finished => RaceStatusType::Finished,
         RaceStatus::Cancelled => RaceStatusType::Error {
@@ -698,18 +1259,43 @@ fn build_race_progress_status(race: &Race) -> RaceProgressStatus {
         turn_phase,
         participants_count: race.participants.len() as u32,
         finished_participants,
+        starts_at: race
+            .starts_at
+            .filter(|_| race.status == RaceStatus::Starting)
+            .and_then(|starts_at| DateTime::from_timestamp_millis(starts_at.timestamp_millis())),
+        deadline_at: turn_deadline_at(race),
+        seconds_remaining: race.seconds_until_deadline(),
     }
 }
 
+/// Deadline of the race's current lap, as returned by the API
+pub(crate) fn turn_deadline_at(race: &Race) -> Option<DateTime<Utc>> {
+    race.turn_deadline()
+        .and_then(|deadline| DateTime::from_timestamp_millis(deadline.timestamp_millis()))
+}
+
+/// Sectors, movements and standings of a race as `viewer` may see them
+///
+/// Under fog of war, only the sectors of the viewer's local view are listed
+/// and the leaderboard stays empty until the race ends.
 #[allow(clippy::unused_async)]
-async fn build_track_situation_data(
-    _database: &Database,
+pub(crate) async fn build_track_situation_data(
+    database: &Database,
     race: &Race,
+    viewer: Option<Uuid>,
 ) -> Result<TrackSituationData, mongodb::error::Error> {
+    let focus = viewer_focus(database, race, viewer).await?;
+    let visible = race.visible_sectors(viewer, focus);
     let mut sectors = Vec::new();
 
     // Build sector situation for each sector
     for sector in &race.track.sectors {
+        if visible
+            .as_ref()
+            .is_some_and(|visible| !visible.contains(&sector.id))
+        {
+            continue;
+        }
         let participants_in_sector: Vec<_> = race
             .participants
             .iter()
@@ -738,12 +1324,12 @@ async fn build_track_situation_data(
         // Sort by position in sector
         sector_participants.sort_by_key(|p| p.position_in_sector);
 
+        let lap_capacity = sector.capacity_for_lap(race.current_lap);
         #[allow(clippy::cast_possible_truncation)]
         let capacity_info = SectorCapacityInfo {
-            max_capacity: sector.slot_capacity,
+            max_capacity: lap_capacity,
             current_occupancy: sector_participants.len() as u32,
-            available_slots: sector
-                .slot_capacity
+            available_slots: lap_capacity
                 .map(|cap| cap.saturating_sub(sector_participants.len() as u32)),
         };
 
@@ -752,6 +1338,7 @@ async fn build_track_situation_data(
             max_value: sector.max_value,
             move_up_threshold: sector.max_value,
             move_down_threshold: sector.min_value,
+            modifiers: sector.modifiers,
         };
 
         sectors.push(SectorSituation {
@@ -764,13 +1351,24 @@ async fn build_track_situation_data(
         });
     }
 
-    // Build recent movements (placeholder for now)
-    let recent_movements = Vec::new();
+    // Under fog of war, only the movements of cars in view
+    let recent_movements = recent_movements(database, race)
+        .await?
+        .into_iter()
+        .filter(|(_, movement)| race.shows_car(viewer, focus, movement.player_uuid))
+        .map(|(lap, movement)| ParticipantMovement {
+            player_uuid: movement.player_uuid.to_string(),
+            lap,
+            from_sector: movement.from_sector,
+            to_sector: movement.to_sector,
+            movement_type: movement.movement_type,
+        })
+        .collect();
 
     // Build lap leaderboard
     let mut leaderboard_entries = Vec::new();
     for (index, participant) in race.participants.iter().enumerate() {
-        if !participant.is_finished {
+        if !participant.is_finished && visible.is_none() {
             // TODO: Fetch player and car names from database
             let player_name = None;
             let car_name = format!("Car {}", participant.car_uuid);
@@ -809,7 +1407,15 @@ async fn build_track_situation_data(
     })
 }
 
-fn build_race_metadata(race: &Race) -> RaceMetadata {
+pub(crate) async fn build_race_metadata(database: &Database, race: &Race) -> RaceMetadata {
+    // The count is informational, so a failed lookup does not fail the response
+    let spectator_count = count_spectators(database, race.uuid)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to count spectators of race {}: {:?}", race.uuid, e);
+            0
+        });
+
     RaceMetadata {
         race_uuid: race.uuid.to_string(),
         race_name: race.name.clone(),
@@ -825,11 +1431,18 @@ fn build_race_metadata(race: &Race) -> RaceMetadata {
         },
         estimated_completion: None, // TODO: Calculate based on current progress
         total_turns: 0,             // TODO: Implement turn tracking
+        rules_version: race.rules_version,
+        spectator_count,
+        result_hash: race.anchor.as_ref().map(|anchor| anchor.hash.clone()),
+        anchor_transaction_signature: race
+            .anchor
+            .as_ref()
+            .map(|anchor| anchor.transaction_signature.clone()),
     }
 }
 
 #[allow(clippy::unused_async)]
-async fn build_player_specific_data(
+pub(crate) async fn build_player_specific_data(
     _database: &Database,
     race: &Race,
     player_uuid: Uuid,
@@ -854,6 +1467,7 @@ async fn build_player_specific_data(
         &participant.boost_hand,
         current_sector,
         base_performance,
+        race.performance_model(),
     );
 
     // Build performance preview
@@ -863,7 +1477,9 @@ async fn build_player_specific_data(
         pilot_contribution: 2,  // TODO: Get from actual car components
         base_value: base_performance,
         sector_ceiling: current_sector.max_value,
-        capped_base_value: std::cmp::min(base_performance, current_sector.max_value),
+        capped_base_value: race
+            .performance_model()
+            .cap_base_value(base_performance, current_sector),
     };
 
     // Build current position
@@ -910,104 +1526,272 @@ async fn process_individual_lap_action(
     race_uuid: Uuid,
     player_uuid: Uuid,
     boost_value: u32,
+    pit_stop: bool,
+    salt: Option<&str>,
     car_data: &ValidatedCarData,
 ) -> Result<Option<Race>, mongodb::error::Error> {
-    let collection = database.collection::<Race>("races");
-
     // Get the race first
     let Some(mut race) = get_race_by_uuid(database, race_uuid).await? else {
         return Ok(None);
     };
 
-    // Process individual lap action using the new method
-    match race.process_individual_lap_action(player_uuid, boost_value, car_data) {
-        Ok(_individual_result) => {
-            // Update the race in database with new fields
-            let filter = doc! { "uuid": race_uuid.to_string() };
-            let update = doc! {
-                "$set": {
-                    "participants": to_bson_safe(&race.participants, "participants")?,
-                    "current_lap": race.current_lap,
-                    "lap_characteristic": to_bson_safe(&race.lap_characteristic, "lap_characteristic")?,
-                    "status": to_bson_safe(&race.status, "status")?,
-                    "pending_actions": to_bson_safe(&race.pending_actions, "pending_actions")?,
-                    "action_submissions": to_bson_safe(&race.action_submissions, "action_submissions")?,
-                    "pending_performance_calculations": to_bson_safe(&race.pending_performance_calculations, "pending_performance_calculations")?,
-                    "updated_at": BsonDateTime::now()
-                }
-            };
-
-            collection.find_one_and_update(filter, update, None).await
-        }
-        Err(e) => Err(mongodb::error::Error::custom(e)),
+    if !pit_stop {
+        race.check_reveal(player_uuid, boost_value, salt)
+            .map_err(mongodb::error::Error::custom)?;
     }
-}
 
-// Enhanced API Endpoint Implementations
+    let before = race.clone();
 
-/// Register a player for a race
-#[utoipa::path(
-    post,
-    path = "/api/v1/races/{race_uuid}/register",
-    params(("race_uuid" = String, Path, description = "Race UUID")),
+    // Process individual lap action using the new method
+    let result = if pit_stop {
+        race.process_pit_stop(player_uuid)
+    } else {
+        race.process_individual_lap_action(player_uuid, boost_value, car_data)
+    };
+
+    let lap_results = match result {
+        Ok(IndividualLapResult::LapProcessed(lap_result)) => vec![lap_result],
+        Ok(IndividualLapResult::ActionRecorded { .. }) => Vec::new(),
+        Err(e) => return Err(mongodb::error::Error::custom(e)),
+    };
+
+    let submitted = (!pit_stop).then(|| {
+        OutboxMessage::new(OutboxPayload::Event(DomainEvent::ActionSubmitted {
+            race_uuid,
+            player_uuid,
+            lap: before.current_lap,
+            boost_value,
+        }))
+    });
+    let audit = AuditEntry::new("race.lap_action").by(player_uuid);
+    let outbox = submitted.into_iter().collect();
+    save_race_progress_in_db(database, audit, outbox, &before, &mut race, &lap_results).await
+}
+
+/// Persist lap progress (positions, lap, status and pending actions) of a race
+/// and log how it changed since `before`
+async fn save_race_progress_in_db(
+    database: &Database,
+    audit: AuditEntry,
+    mut outbox: Vec<OutboxMessage>,
+    before: &Race,
+    race: &mut Race,
+    lap_results: &[LapResult],
+) -> Result<Option<Race>, mongodb::error::Error> {
+    award_finished_race(database, before, race, lap_results).await?;
+
+    let collection = database.collection::<Race>("races");
+    let filter = doc! { "uuid": race.uuid.to_string() };
+    let mut update = doc! {
+        "$set": {
+            "participants": to_bson_safe(&race.participants, "participants")?,
+            "awards": to_bson_safe(&race.awards, "awards")?,
+            "current_lap": race.current_lap,
+            "lap_characteristic": to_bson_safe(&race.lap_characteristic, "lap_characteristic")?,
+            "status": to_bson_safe(&race.status, "status")?,
+            "pending_actions": to_bson_safe(&race.pending_actions, "pending_actions")?,
+            "action_submissions": to_bson_safe(&race.action_submissions, "action_submissions")?,
+            "pending_performance_calculations": to_bson_safe(&race.pending_performance_calculations, "pending_performance_calculations")?,
+            "boost_commitments": to_bson_safe(&race.boost_commitments, "boost_commitments")?,
+            "turn_deadline_at": race.turn_deadline_at,
+            "updated_at": BsonDateTime::now()
+        }
+    };
+    outbox.extend(OutboxMessage::between(before, race, lap_results));
+    push_outbox(&mut update, &outbox)?;
+
+    // Every field is set outright and outbox consumers deduplicate on the
+    // message uuid, so repeating the write after a lost reply is harmless
+    let result = with_retry("Saving race progress", || {
+        collection.find_one_and_update(filter.clone(), update.clone(), None)
+    })
+    .await?;
+    record_race_changes(database, audit, before, race, lap_results).await;
+    Ok(result)
+}
+
+/// Hand out the awards of a race that `lap_results` just finished
+///
+/// Awards are computed from the race's event log, completed with the changes
+/// about to be logged for these laps.
+async fn award_finished_race(
+    database: &Database,
+    before: &Race,
+    race: &mut Race,
+    lap_results: &[LapResult],
+) -> Result<(), mongodb::error::Error> {
+    if before.status == RaceStatus::Finished || race.status != RaceStatus::Finished {
+        return Ok(());
+    }
+
+    let mut changes: Vec<RaceChange> =
+        get_race_events_since(database, race.uuid, BsonDateTime::MIN, usize::MAX)
+            .await?
+            .into_iter()
+            .map(|event| event.change)
+            .collect();
+    changes.extend(RaceChange::between(before, race, lap_results));
+    race.awards = race_awards(race, &changes);
+    Ok(())
+}
+
+/// Persist participant state (boost hands, positions) after an in-memory update
+async fn update_race_participants_in_db(
+    database: &Database,
+    race: &Race,
+) -> Result<(), mongodb::error::Error> {
+    let collection = database.collection::<Race>("races");
+
+    let filter = doc! { "uuid": race.uuid.to_string() };
+    let update = doc! {
+        "$set": {
+            "participants": to_bson_safe(&race.participants, "participants")?,
+            "updated_at": BsonDateTime::now()
+        }
+    };
+
+    with_retry("Saving race participants", || {
+        collection.update_one(filter.clone(), update.clone(), None)
+    })
+    .await?;
+    Ok(())
+}
+
+// Enhanced API Endpoint Implementations
+
+/// Register a player for a race
+#[utoipa::path(
+    post,
+    path = "/api/v1/races/{race_uuid}/register",
+    params(("race_uuid" = String, Path, description = "Race UUID")),
     request_body = RegisterPlayerRequest,
     responses(
         (status = 200, description = "Successfully registered for race", body = RegisterPlayerResponse),
-        (status = 400, description = "Invalid request or car validation failed"),
-        (status = 404, description = "Race not found"),
-        (status = 409, description = "Cannot register (race started, player already registered, etc.)"),
-        (status = 500, description = "Internal server error")
+        (status = 400, description = "Invalid request or car validation failed, e.g. a component rarer than the race allows, or no car given by a player with an eligible car", body = ErrorResponse),
+        (status = 403, description = "Player is banned, player or car outside the race's skill bracket or car tier, or no loaner car fits the race", body = ErrorResponse),
+        (status = 404, description = "Race or player not found", body = ErrorResponse),
+        (status = 409, description = "Cannot register (race started, player already registered, etc.)", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     tag = "races"
 )]
 #[tracing::instrument(
     name = "Registering player for race",
-    skip(database, payload),
+    skip(database, car_data_cache, payload),
     fields(
         race_uuid = %race_uuid_str,
         player_uuid = %payload.player_uuid,
-        car_uuid = %payload.car_uuid
+        car_uuid = ?payload.car_uuid
     )
 )]
 pub async fn register_player(
     State(database): State<Database>,
+    Extension(car_data_cache): Extension<CarDataCache>,
     Path(race_uuid_str): Path<String>,
     Json(payload): Json<RegisterPlayerRequest>,
-) -> Result<Json<RegisterPlayerResponse>, StatusCode> {
+) -> Result<Json<RegisterPlayerResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let error = |status: StatusCode, code: &str, message: String| {
+        (
+            status,
+            Json(ErrorResponse {
+                error: code.to_string(),
+                message,
+                details: None,
+            }),
+        )
+    };
+
     // 1. Parse and validate UUIDs
-    let race_uuid = match Uuid::parse_str(&race_uuid_str) {
-        Ok(uuid) => uuid,
-        Err(e) => {
-            tracing::warn!("Invalid race UUID: {}", e);
-            return Err(StatusCode::BAD_REQUEST);
-        }
+    let (Ok(race_uuid), Ok(player_uuid), Ok(car_uuid)) = (
+        Uuid::parse_str(&race_uuid_str),
+        Uuid::parse_str(&payload.player_uuid),
+        payload.car_uuid.as_deref().map(Uuid::parse_str).transpose(),
+    ) else {
+        tracing::warn!("Invalid UUID in registration");
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_UUID",
+            "Invalid UUID format".to_string(),
+        ));
     };
 
-    let player_uuid = match Uuid::parse_str(&payload.player_uuid) {
-        Ok(uuid) => uuid,
+    match is_player_banned_in_db(&database, player_uuid).await {
+        Ok(false) => {}
+        Ok(true) => {
+            tracing::warn!(
+                "Banned player {} tried to join race {}",
+                player_uuid,
+                race_uuid
+            );
+            return Err(error(
+                StatusCode::FORBIDDEN,
+                "PLAYER_BANNED",
+                "Player is banned".to_string(),
+            ));
+        }
         Err(e) => {
-            tracing::warn!("Invalid player UUID: {}", e);
-            return Err(StatusCode::BAD_REQUEST);
+            tracing::error!("Failed to check player ban: {:?}", e);
+            return Err(error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "DATABASE_ERROR",
+                "Internal server error".to_string(),
+            ));
         }
-    };
+    }
 
-    let car_uuid = match Uuid::parse_str(&payload.car_uuid) {
-        Ok(uuid) => uuid,
+    let race = match get_race_by_uuid(&database, race_uuid).await {
+        Ok(Some(race)) => race,
+        Ok(None) => {
+            tracing::warn!("Race not found for UUID: {}", race_uuid);
+            return Err(error(
+                StatusCode::NOT_FOUND,
+                "RACE_NOT_FOUND",
+                "Race not found".to_string(),
+            ));
+        }
         Err(e) => {
-            tracing::warn!("Invalid car UUID: {}", e);
-            return Err(StatusCode::BAD_REQUEST);
+            tracing::error!("Failed to fetch race: {:?}", e);
+            return Err(error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "DATABASE_ERROR",
+                "Internal server error".to_string(),
+            ));
         }
     };
 
-    // 2. Validate car and get components
-    let car_data =
-        match CarValidationService::validate_car_for_race(&database, player_uuid, car_uuid).await {
-            Ok(data) => data,
+    // 2. Validate car and get components, within the race's rarity caps;
+    // players without an eligible car of their own borrow a loaner
+    let (car_data, is_loaner) = match car_uuid.filter(|uuid| !is_loaner_car(*uuid)) {
+        Some(car_uuid) => match CarValidationService::validate_car_for_race(
+            &database,
+            player_uuid,
+            car_uuid,
+            &race.rules.allowed_rarities,
+        )
+        .await
+        {
+            Ok(data) => (data, false),
             Err(e) => {
                 tracing::warn!("Car validation failed: {}", e);
-                return Err(StatusCode::BAD_REQUEST);
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    Json(ErrorResponse {
+                        error: e.error_code().to_string(),
+                        message: e.user_message(),
+                        details: e.suggested_action(),
+                    }),
+                ));
             }
-        };
+        },
+        None => (lend_loaner_car(&database, &race, player_uuid).await?, true),
+    };
+    let car_uuid = car_data.car.uuid;
+
+    // Registration always validates afresh; later lap actions reuse the result
+    car_data_cache.put(player_uuid, &car_data);
+
+    let car_class =
+        check_entry_window(&database, &race, player_uuid, car_uuid, Some(&car_data)).await?;
 
     // 3. Register player in race
     let updated_race = match register_player_in_race(
@@ -1016,22 +1800,36 @@ pub async fn register_player(
         player_uuid,
         car_uuid,
         car_data.pilot.uuid,
+        car_class,
+        is_loaner,
     )
     .await
     {
         Ok(Some(race)) => race,
         Ok(None) => {
             tracing::warn!("Race not found for UUID: {}", race_uuid);
-            return Err(StatusCode::NOT_FOUND);
+            return Err(error(
+                StatusCode::NOT_FOUND,
+                "RACE_NOT_FOUND",
+                "Race not found".to_string(),
+            ));
         }
         Err(e) => {
             tracing::error!("Failed to register player: {:?}", e);
             if e.to_string().contains("already participating")
                 || e.to_string().contains("already started")
             {
-                return Err(StatusCode::CONFLICT);
+                return Err(error(
+                    StatusCode::CONFLICT,
+                    "CANNOT_REGISTER",
+                    e.to_string(),
+                ));
             }
-            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            return Err(error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "DATABASE_ERROR",
+                "Internal server error".to_string(),
+            ));
         }
     };
 
@@ -1040,7 +1838,11 @@ pub async fn register_player(
         Ok(position) => position,
         Err(e) => {
             tracing::error!("Failed to get player position: {}", e);
-            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            return Err(error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "INTERNAL_ERROR",
+                e,
+            ));
         }
     };
 
@@ -1057,13 +1859,15 @@ pub async fn register_player(
         message: "Successfully registered for race".to_string(),
         race_status,
         player_position,
+        car_uuid: car_uuid.to_string(),
+        is_loaner,
     }))
 }
 
 /// Get detailed race status with comprehensive boost hand information
 ///
 /// This endpoint provides complete race status including boost card system state.
-/// When `player_uuid` is provided, returns player-specific data including:
+/// For a signed-in participant, it also returns their own data, including:
 /// - Current boost hand state (available/used cards)
 /// - Boost cycle information (current cycle, cycles completed)
 /// - Boost usage history and cycle summaries
@@ -1080,13 +1884,24 @@ pub async fn register_player(
 /// # Usage History
 /// - `boost_usage_history`: Lap-by-lap record of boost card usage
 /// - `boost_cycle_summaries`: Aggregated statistics per cycle
+///
+/// # Sparse Responses
+/// Pass `fields` with dotted paths to receive only part of the response, e.g.
+/// `fields=race_progress,player_data.boost_availability`. Sections left out are
+/// not computed at all.
+///
+/// # Conditional Requests
+/// Responses carry an `ETag` derived from the race's last update. Sending it
+/// back in `If-None-Match` returns `304 Not Modified` without a body while the
+/// race is unchanged.
 #[utoipa::path(
     get,
     path = "/api/v1/races/{race_uuid}/status-detailed",
     params(
         ("race_uuid" = String, Path, description = "Race UUID"),
-        ("player_uuid" = Option<String>, Query, description = "Player UUID for player-specific boost hand data"),
-        ("include_history" = Option<bool>, Query, description = "Include detailed lap and boost usage history")
+        ("include_history" = Option<bool>, Query, description = "Include detailed lap and boost usage history"),
+        ("fields" = Option<String>, Query, description = "Comma-separated dotted paths of the fields to return"),
+        ("If-None-Match" = Option<String>, Header, description = "ETag of the copy the client already has")
     ),
     responses(
         (
@@ -1173,6 +1988,9 @@ pub async fn register_player(
                 }
             })
         ),
+        (status = 304, description = "Race unchanged since the given ETag"),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "API token lacks the read:races scope"),
         (status = 404, description = "Race not found"),
         (status = 500, description = "Internal server error")
     ),
@@ -1180,17 +1998,16 @@ pub async fn register_player(
 )]
 #[tracing::instrument(
     name = "Getting detailed race status",
-    skip(database),
-    fields(
-        race_uuid = %race_uuid_str,
-        player_uuid = ?params.player_uuid
-    )
+    skip(database, user),
+    fields(race_uuid = %race_uuid_str)
 )]
 pub async fn get_race_status_detailed(
     State(database): State<Database>,
     Path(race_uuid_str): Path<String>,
     Query(params): Query<StatusQueryParams>,
-) -> Result<Json<DetailedRaceStatusResponse>, StatusCode> {
+    user: Option<Extension<UserContext>>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
     let race_uuid = match Uuid::parse_str(&race_uuid_str) {
         Ok(uuid) => uuid,
         Err(e) => {
@@ -1211,27 +2028,50 @@ pub async fn get_race_status_detailed(
         }
     };
 
+    // Each viewer and field selection is a separate representation
+    let viewer = user.map(|Extension(user)| user.user_uuid);
+    let variant = format!(
+        "viewer={}&fields={}",
+        viewer.map(|viewer| viewer.to_string()).unwrap_or_default(),
+        params.fields.as_deref().unwrap_or_default()
+    );
+    let etag = race_etag(&race, &variant);
+    if is_not_modified(&headers, &etag) {
+        return Ok(not_modified(&etag));
+    }
+
+    let selection = params.fields.as_deref().and_then(FieldSelection::parse);
+    let wanted = |field: &str| selection.as_ref().is_none_or(|s| s.includes(field));
+
     // Build comprehensive status response
     let race_progress = build_race_progress_status(&race);
-    let track_situation = match build_track_situation_data(&database, &race).await {
-        Ok(data) => data,
-        Err(e) => {
-            tracing::error!("Failed to build track situation: {:?}", e);
-            return Err(StatusCode::INTERNAL_SERVER_ERROR);
-        }
-    };
-    let race_metadata = build_race_metadata(&race);
-
-    // Include player-specific data if requested
-    let player_data = if let Some(player_uuid_str) = params.player_uuid {
-        let player_uuid = match Uuid::parse_str(&player_uuid_str) {
-            Ok(uuid) => uuid,
+    let track_situation = if wanted("track_situation") {
+        match build_track_situation_data(&database, &race, viewer).await {
+            Ok(data) => data,
             Err(e) => {
-                tracing::warn!("Invalid player UUID: {}", e);
-                return Err(StatusCode::BAD_REQUEST);
+                tracing::error!("Failed to build track situation: {:?}", e);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
             }
-        };
+        }
+    } else {
+        // Pruned from the response below
+        TrackSituationData {
+            sectors: Vec::new(),
+            recent_movements: Vec::new(),
+            lap_leaderboard: Vec::new(),
+        }
+    };
+    let race_metadata = build_race_metadata(&database, &race).await;
 
+    // Include the viewer's own data while they race in it
+    let player_data = if let Some(player_uuid) = viewer
+        .filter(|viewer| {
+            race.participants
+                .iter()
+                .any(|p| p.player_uuid == *viewer && !p.is_finished)
+        })
+        .filter(|_| wanted("player_data"))
+    {
         match build_player_specific_data(&database, &race, player_uuid).await {
             Ok(data) => Some(data),
             Err(e) => {
@@ -1245,12 +2085,22 @@ pub async fn get_race_status_detailed(
 
     tracing::info!("Detailed race status retrieved for UUID: {}", race_uuid);
 
-    Ok(Json(DetailedRaceStatusResponse {
+    let response = serde_json::to_value(DetailedRaceStatusResponse {
         race_progress,
         track_situation,
         player_data,
         race_metadata,
-    }))
+    })
+    .map_err(|e| {
+        tracing::error!("Failed to serialize race status: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let response = match selection {
+        Some(selection) => selection.prune(response),
+        None => response,
+    };
+    Ok(with_etag(Json(response), &etag))
 }
 
 /// Apply individual lap action for a player with boost card validation
@@ -1260,7 +2110,7 @@ pub async fn get_race_status_detailed(
 /// - Players have 5 boost cards (values 0-4) available per cycle
 /// - Each card can only be used once per cycle
 /// - When all 5 cards are used, the hand automatically replenishes
-/// - Boost cards multiply performance: `base_value` * (1 + `boost_value` * 0.08)
+/// - Boost cards are applied with the race's performance model (additive by default)
 ///
 /// # Boost Card Usage Flow
 /// 1. Player selects an available boost card (0-4)
@@ -1347,13 +2197,14 @@ pub async fn get_race_status_detailed(
         ),
         (status = 404, description = "Race not found"),
         (status = 409, description = "Cannot process action (race not in progress, etc.)"),
-        (status = 500, description = "Internal server error")
+        (status = 500, description = "Internal server error"),
+        (status = 503, description = "Database unavailable, retry later")
     ),
     tag = "boost-cards"
 )]
 #[tracing::instrument(
     name = "Applying lap action",
-    skip(database, payload),
+    skip(database, car_data_cache, payload),
     fields(
         race_uuid = %race_uuid_str,
         player_uuid = %payload.player_uuid,
@@ -1362,6 +2213,7 @@ pub async fn get_race_status_detailed(
 )]
 pub async fn apply_lap_action(
     State(database): State<Database>,
+    Extension(car_data_cache): Extension<CarDataCache>,
     Path(race_uuid_str): Path<String>,
     Json(payload): Json<ApplyLapRequest>,
 ) -> Result<Json<DetailedRaceStatusResponse>, (StatusCode, Json<BoostCardErrorResponse>)> {
@@ -1417,23 +2269,25 @@ pub async fn apply_lap_action(
     };
 
     // Validate car data
-    let car_data =
-        match CarValidationService::validate_car_for_race(&database, player_uuid, car_uuid).await {
-            Ok(data) => data,
-            Err(e) => {
-                tracing::warn!("Car validation failed: {}", e);
-                return Err((
-                    StatusCode::BAD_REQUEST,
-                    Json(BoostCardErrorResponse {
-                        error_code: "CAR_VALIDATION_FAILED".to_string(),
-                        message: format!("Car validation failed: {e}"),
-                        available_cards: vec![],
-                        current_cycle: 0,
-                        cards_remaining: 0,
-                    }),
-                ));
-            }
-        };
+    let car_data = match car_data_cache
+        .validate_car_for_race(&database, player_uuid, car_uuid)
+        .await
+    {
+        Ok(data) => data,
+        Err(e) => {
+            tracing::warn!("Car validation failed: {}", e);
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(BoostCardErrorResponse {
+                    error_code: "CAR_VALIDATION_FAILED".to_string(),
+                    message: format!("Car validation failed: {e}"),
+                    available_cards: vec![],
+                    current_cycle: 0,
+                    cards_remaining: 0,
+                }),
+            ));
+        }
+    };
 
     // Get race to validate boost card before processing
     let race = match get_race_by_uuid(&database, race_uuid).await {
@@ -1472,7 +2326,7 @@ pub async fn apply_lap_action(
         .iter()
         .find(|p| p.player_uuid == player_uuid);
 
-    if let Some(participant) = participant {
+    if let Some(participant) = participant.filter(|_| !payload.pit_stop) {
         // Validate boost card selection before processing
         #[allow(clippy::cast_possible_truncation)]
         let boost_value_u8 = payload.boost_value as u8;
@@ -1493,6 +2347,8 @@ pub async fn apply_lap_action(
         race_uuid,
         player_uuid,
         payload.boost_value,
+        payload.pit_stop,
+        payload.salt.as_deref(),
         &car_data,
     )
     .await
@@ -1515,6 +2371,19 @@ pub async fn apply_lap_action(
             tracing::error!("Failed to process lap action: {:?}", e);
             let error_msg = e.to_string();
 
+            if is_circuit_open(&e) {
+                return Err((
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    Json(BoostCardErrorResponse {
+                        error_code: "DATABASE_UNAVAILABLE".to_string(),
+                        message: error_msg,
+                        available_cards: vec![],
+                        current_cycle: 0,
+                        cards_remaining: 0,
+                    }),
+                ));
+            }
+
             // Check for boost card errors in the error message
             if error_msg.contains("not available") || error_msg.contains("Invalid boost") {
                 return Err((
@@ -1529,7 +2398,36 @@ pub async fn apply_lap_action(
                 ));
             }
 
-            if error_msg.contains("not in progress") || error_msg.contains("already submitted") {
+            if error_msg.contains("does not match") || error_msg.contains("salt") {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    Json(BoostCardErrorResponse {
+                        error_code: "INVALID_REVEAL".to_string(),
+                        message: error_msg,
+                        available_cards: vec![],
+                        current_cycle: 0,
+                        cards_remaining: 0,
+                    }),
+                ));
+            }
+
+            if error_msg.contains("pit lane") {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    Json(BoostCardErrorResponse {
+                        error_code: "PIT_STOP_NOT_ALLOWED".to_string(),
+                        message: error_msg,
+                        available_cards: vec![],
+                        current_cycle: 0,
+                        cards_remaining: 0,
+                    }),
+                ));
+            }
+
+            if error_msg.contains("not in progress")
+                || error_msg.contains("already submitted")
+                || error_msg.contains("committed")
+            {
                 return Err((
                     StatusCode::CONFLICT,
                     Json(BoostCardErrorResponse {
@@ -1555,25 +2453,31 @@ pub async fn apply_lap_action(
         }
     };
 
+    // A finished time trial is kept as a ghost other players can race against
+    if updated_race.mode == RaceMode::TimeTrial && updated_race.status == RaceStatus::Finished {
+        record_ghost(&database, &updated_race, player_uuid, &car_data).await;
+    }
+
     // Return same format as status endpoint with updated boost hand state
     let race_progress = build_race_progress_status(&updated_race);
-    let track_situation = match build_track_situation_data(&database, &updated_race).await {
-        Ok(data) => data,
-        Err(e) => {
-            tracing::error!("Failed to build track situation: {:?}", e);
-            return Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(BoostCardErrorResponse {
-                    error_code: "INTERNAL_ERROR".to_string(),
-                    message: "Failed to build track situation".to_string(),
-                    available_cards: vec![],
-                    current_cycle: 0,
-                    cards_remaining: 0,
-                }),
-            ));
-        }
-    };
-    let race_metadata = build_race_metadata(&updated_race);
+    let track_situation =
+        match build_track_situation_data(&database, &updated_race, Some(player_uuid)).await {
+            Ok(data) => data,
+            Err(e) => {
+                tracing::error!("Failed to build track situation: {:?}", e);
+                return Err((
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(BoostCardErrorResponse {
+                        error_code: "INTERNAL_ERROR".to_string(),
+                        message: "Failed to build track situation".to_string(),
+                        available_cards: vec![],
+                        current_cycle: 0,
+                        cards_remaining: 0,
+                    }),
+                ));
+            }
+        };
+    let race_metadata = build_race_metadata(&database, &updated_race).await;
     let player_data = match build_player_specific_data(&database, &updated_race, player_uuid).await
     {
         Ok(data) => Some(data),
@@ -1706,7 +2610,7 @@ pub async fn apply_lap_action(
 )]
 #[tracing::instrument(
     name = "Getting car data for player in race",
-    skip(database),
+    skip(database, car_data_cache),
     fields(
         race_uuid = %race_uuid_str,
         player_uuid = %player_uuid_str
@@ -1714,6 +2618,7 @@ pub async fn apply_lap_action(
 )]
 pub async fn get_car_data(
     State(database): State<Database>,
+    Extension(car_data_cache): Extension<CarDataCache>,
     Path((race_uuid_str, player_uuid_str)): Path<(String, String)>,
 ) -> Result<Json<CarDataResponse>, (StatusCode, Json<ErrorResponse>)> {
     // 1. Parse and validate UUIDs
@@ -1790,12 +2695,9 @@ pub async fn get_car_data(
         })?;
 
     // 4. Use CarValidationService to get car data
-    let car_data = match CarValidationService::validate_car_for_race(
-        &database,
-        player_uuid,
-        participant.car_uuid,
-    )
-    .await
+    let car_data = match car_data_cache
+        .validate_car_for_race(&database, player_uuid, participant.car_uuid)
+        .await
     {
         Ok(data) => data,
         Err(e) => {
@@ -1821,8 +2723,8 @@ pub async fn get_car_data(
         pilot: PilotInfo {
             uuid: car_data.pilot.uuid.to_string(),
             name: car_data.pilot.name.as_ref().to_string(),
-            pilot_class: format!("{:?}", car_data.pilot.pilot_class),
-            rarity: format!("{:?}", car_data.pilot.rarity),
+            pilot_class: car_data.pilot.pilot_class.clone(),
+            rarity: car_data.pilot.rarity.clone(),
             skills: PilotSkills {
                 reaction_time: car_data.pilot.skills.reaction_time,
                 precision: car_data.pilot.skills.precision,
@@ -1838,7 +2740,7 @@ pub async fn get_car_data(
         engine: EngineInfo {
             uuid: car_data.engine.uuid.to_string(),
             name: car_data.engine.name.as_ref().to_string(),
-            rarity: format!("{:?}", car_data.engine.rarity),
+            rarity: car_data.engine.rarity,
             straight_value: car_data.engine.straight_value,
             curve_value: car_data.engine.curve_value,
             nft_mint_address: car_data.engine.nft_mint_address.clone(),
@@ -1846,7 +2748,7 @@ pub async fn get_car_data(
         body: BodyInfo {
             uuid: car_data.body.uuid.to_string(),
             name: car_data.body.name.as_ref().to_string(),
-            rarity: format!("{:?}", car_data.body.rarity),
+            rarity: car_data.body.rarity,
             straight_value: car_data.body.straight_value,
             curve_value: car_data.body.curve_value,
             nft_mint_address: car_data.body.nft_mint_address.clone(),
@@ -1871,8 +2773,11 @@ pub async fn get_car_data(
 /// - Movement probability for each boost option
 /// - Boost cycle information (available cards, cycle status)
 ///
-/// The performance calculation follows the boost multiplier formula:
-/// `final_value = base_value * (1.0 + boost_value * 0.08)`
+/// The performance calculation follows the race's configured performance model
+/// (reported as `performance_model` in the response):
+/// - `Additive`: `final_value = capped_base_value + boost_value` (default)
+/// - `Multiplicative`: `final_value = capped_base_value * (1.0 + boost_value * 0.08)`
+/// - `DiminishingReturns`: each extra boost step adds half the bonus of the previous one
 ///
 /// Movement probabilities are determined by comparing final values to sector thresholds:
 /// - `MoveUp`: `final_value` >= `sector.max_value`
@@ -1937,7 +2842,8 @@ pub async fn get_car_data(
                     "cycles_completed": 0,
                     "cards_remaining": 4,
                     "available_cards": [1, 2, 3, 4]
-                }
+                },
+                "performance_model": "Multiplicative"
             })
         ),
         (
@@ -1950,6 +2856,8 @@ pub async fn get_car_data(
                 "details": null
             })
         ),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Not the player's hand"),
         (
             status = 404,
             description = "Player not found in race or race not found",
@@ -1985,7 +2893,7 @@ pub async fn get_car_data(
 )]
 #[tracing::instrument(
     name = "Getting performance preview for player in race",
-    skip(database),
+    skip(database, car_data_cache),
     fields(
         race_uuid = %race_uuid_str,
         player_uuid = %player_uuid_str
@@ -1993,6 +2901,7 @@ pub async fn get_car_data(
 )]
 pub async fn get_performance_preview(
     State(database): State<Database>,
+    Extension(car_data_cache): Extension<CarDataCache>,
     Path((race_uuid_str, player_uuid_str)): Path<(String, String)>,
 ) -> Result<Json<PerformancePreviewResponse>, (StatusCode, Json<ErrorResponse>)> {
     // 1. Parse and validate UUIDs
@@ -2095,12 +3004,9 @@ pub async fn get_performance_preview(
     }
 
     // 6. Validate car data using CarValidationService
-    let car_data = match CarValidationService::validate_car_for_race(
-        &database,
-        player_uuid,
-        participant.car_uuid,
-    )
-    .await
+    let car_data = match car_data_cache
+        .validate_car_for_race(&database, player_uuid, participant.car_uuid)
+        .await
     {
         Ok(data) => data,
         Err(e) => {
@@ -2134,19 +3040,25 @@ pub async fn get_performance_preview(
             u32::from(car_data.pilot.performance.curve_value),
         ),
     };
+    let (engine_contribution, body_contribution) =
+        current_sector.apply_modifiers(engine_contribution, body_contribution);
 
-    let base_value = engine_contribution + body_contribution + pilot_contribution;
-    let capped_base_value = std::cmp::min(base_value, current_sector.max_value);
+    let base_value = (engine_contribution + body_contribution + pilot_contribution)
+        .saturating_sub(participant.ballast);
+    let model = race.performance_model();
+    let capped_base_value = model.cap_base_value(base_value, current_sector);
 
     // 8. Build base performance response
     let base_performance = BasePerformance {
         engine_contribution,
         body_contribution,
         pilot_contribution,
+        ballast: participant.ballast,
         base_value,
         sector_ceiling: current_sector.max_value,
         capped_base_value,
-        lap_characteristic: format!("{:?}", race.lap_characteristic),
+        catch_up_bonus: race.catch_up_bonus(participant),
+        lap_characteristic: race.lap_characteristic.clone(),
     };
 
     // 9. Calculate boost options for each boost card (0-4)
@@ -2155,10 +3067,9 @@ pub async fn get_performance_preview(
     for boost_value in 0..=4 {
         let is_available = participant.boost_hand.is_card_available(boost_value);
 
-        // Calculate final value using boost multiplier formula: base * (1.0 + boost * 0.08)
-        let boost_multiplier = 1.0 + (f64::from(boost_value) * 0.08);
-        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-        let final_value = (f64::from(capped_base_value) * boost_multiplier).round() as u32;
+        // Calculate final value using the race's performance model and sector effects
+        let final_value =
+            race.boosted_value(participant, capped_base_value, u32::from(boost_value));
 
         // Determine movement probability
         let movement_probability = calculate_movement_probability(final_value, current_sector);
@@ -2167,7 +3078,7 @@ pub async fn get_performance_preview(
             boost_value,
             is_available,
             final_value,
-            movement_probability: format!("{movement_probability:?}"),
+            movement_probability,
         });
     }
 
@@ -2184,6 +3095,7 @@ pub async fn get_performance_preview(
         base_performance,
         boost_options,
         boost_cycle_info,
+        performance_model: race.rules.performance_model,
     };
 
     tracing::info!(
@@ -2203,16 +3115,22 @@ pub async fn get_performance_preview(
 /// - List of players who have submitted actions
 /// - List of players who are still pending action submission
 /// - Total number of active players
+/// - The lap's deadline and the seconds left, when the race has a turn time limit
 ///
 /// Turn phases are determined by:
 /// - Complete: Race status is not `InProgress`
 /// - `AllSubmitted`: All active participants have submitted actions
 /// - `WaitingForPlayers`: Some participants haven't submitted actions yet
+///
+/// Pollers should send the returned `ETag` in `If-None-Match`; an unchanged
+/// phase is answered with `304 Not Modified`, so clients count down from
+/// `deadline_at` rather than `seconds_remaining`.
 #[utoipa::path(
     get,
     path = "/api/v1/races/{race_uuid}/turn-phase",
     params(
-        ("race_uuid" = String, Path, description = "Race UUID")
+        ("race_uuid" = String, Path, description = "Race UUID"),
+        ("If-None-Match" = Option<String>, Header, description = "ETag of the copy the client already has")
     ),
     responses(
         (
@@ -2230,9 +3148,12 @@ pub async fn get_performance_preview(
                 "pending_players": [
                     "550e8400-e29b-41d4-a716-446655440002"
                 ],
-                "total_active_players": 3
+                "total_active_players": 3,
+                "deadline_at": "2024-01-15T10:31:00Z",
+                "seconds_remaining": 42
             })
         ),
+        (status = 304, description = "Turn phase unchanged since the given ETag"),
         (
             status = 400,
             description = "Invalid UUID format",
@@ -2268,15 +3189,17 @@ pub async fn get_performance_preview(
 )]
 #[tracing::instrument(
     name = "Getting turn phase for race",
-    skip(database),
+    skip(database, cache),
     fields(
         race_uuid = %race_uuid_str
     )
 )]
 pub async fn get_turn_phase(
     State(database): State<Database>,
+    Extension(cache): Extension<RaceCache>,
     Path(race_uuid_str): Path<String>,
-) -> Result<Json<TurnPhaseResponse>, (StatusCode, Json<ErrorResponse>)> {
+    headers: HeaderMap,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
     // 1. Parse and validate UUID
     let race_uuid = if let Ok(uuid) = Uuid::parse_str(&race_uuid_str) {
         uuid
@@ -2293,7 +3216,7 @@ pub async fn get_turn_phase(
     };
 
     // 2. Fetch race from database
-    let race = match get_race_by_uuid(&database, race_uuid).await {
+    let race = match get_race_cached(&database, &cache, race_uuid).await {
         Ok(Some(race)) => race,
         Ok(None) => {
             tracing::warn!("Race not found for UUID: {}", race_uuid);
@@ -2319,13 +3242,18 @@ pub async fn get_turn_phase(
         }
     };
 
+    let etag = race_etag(&race, "turn-phase");
+    if is_not_modified(&headers, &etag) {
+        return Ok(not_modified(&etag));
+    }
+
     // 3. Determine turn phase using race.all_actions_submitted() and race status
     let turn_phase = if race.status != RaceStatus::InProgress {
-        "Complete".to_string()
+        TurnPhase::Complete
     } else if race.all_actions_submitted() {
-        "AllSubmitted".to_string()
+        TurnPhase::AllSubmitted
     } else {
-        "WaitingForPlayers".to_string()
+        TurnPhase::WaitingForPlayers
     };
 
     // 4. Get submitted players from race.pending_actions
@@ -2350,42 +3278,197 @@ pub async fn get_turn_phase(
     let response = TurnPhaseResponse {
         turn_phase,
         current_lap: race.current_lap,
-        lap_characteristic: format!("{:?}", race.lap_characteristic),
+        lap_characteristic: race.lap_characteristic.clone(),
         submitted_players,
         pending_players,
         total_active_players,
+        deadline_at: turn_deadline_at(&race),
+        seconds_remaining: race.seconds_until_deadline(),
     };
 
     tracing::info!(
-        "Turn phase retrieved for race {}: {}",
+        "Turn phase retrieved for race {}: {:?}",
         race_uuid,
         response.turn_phase
     );
-    Ok(Json(response))
+    Ok(with_etag(Json(response), &etag))
+}
+
+/// Parse a `since` value given as RFC 3339 or Unix milliseconds
+fn parse_since(since: &str) -> Option<DateTime<Utc>> {
+    since.parse::<i64>().map_or_else(
+        |_| {
+            DateTime::parse_from_rfc3339(since)
+                .ok()
+                .map(|time| time.with_timezone(&Utc))
+        },
+        DateTime::from_timestamp_millis,
+    )
+}
+
+/// Get the changes to a race since a point in time
+///
+/// Returns the movements, phase changes and participant updates recorded in
+/// the race event log after `since`, so a client that lost its connection can
+/// catch up without reloading the whole race. Participant updates carry the
+/// participant's new state, with other players' boost hands hidden as in the
+/// race view; under fog of war, changes about cars out of view are left out.
+/// Pass `latest` from the response as `since` on the next call; when
+/// `complete` is `false`, reload the race instead.
+#[utoipa::path(
+    get,
+    path = "/api/v1/races/{race_uuid}/changes",
+    params(
+        ("race_uuid" = String, Path, description = "Race UUID"),
+        RaceChangesQuery
+    ),
+    responses(
+        (status = 200, description = "Changes after `since`", body = RaceChangesResponse),
+        (status = 400, description = "Invalid UUID format or timestamp", body = ErrorResponse),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "API token lacks the read:races scope"),
+        (status = 404, description = "Race not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "races"
+)]
+#[tracing::instrument(
+    name = "Getting race changes",
+    skip(database, user),
+    fields(race_uuid = %race_uuid_str, since = %params.since)
+)]
+pub async fn get_race_changes(
+    State(database): State<Database>,
+    Path(race_uuid_str): Path<String>,
+    Query(params): Query<RaceChangesQuery>,
+    user: Option<Extension<UserContext>>,
+) -> Result<Json<RaceChangesResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let error = |status: StatusCode, error: &str, message: &str| {
+        (
+            status,
+            Json(ErrorResponse {
+                error: error.to_string(),
+                message: message.to_string(),
+                details: None,
+            }),
+        )
+    };
+    let database_error = |e: mongodb::error::Error| {
+        tracing::error!("Failed to fetch race changes: {:?}", e);
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "DATABASE_ERROR",
+            "Internal server error",
+        )
+    };
+
+    let Ok(race_uuid) = Uuid::parse_str(&race_uuid_str) else {
+        tracing::warn!("Invalid race UUID format: {}", race_uuid_str);
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_UUID",
+            "Invalid UUID format",
+        ));
+    };
+    let Some(since) = parse_since(&params.since) else {
+        tracing::warn!("Invalid since timestamp: {}", params.since);
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_TIMESTAMP",
+            "since must be an RFC 3339 timestamp or Unix milliseconds",
+        ));
+    };
+
+    let Some(race) = get_race_by_uuid(&database, race_uuid)
+        .await
+        .map_err(database_error)?
+    else {
+        return Err(error(
+            StatusCode::NOT_FOUND,
+            "RACE_NOT_FOUND",
+            "Race not found",
+        ));
+    };
+
+    let since_bson = BsonDateTime::from_millis(since.timestamp_millis());
+    let mut events = get_race_events_since(&database, race_uuid, since_bson, MAX_RACE_CHANGES + 1)
+        .await
+        .map_err(database_error)?;
+    // The log of an archived race moves to a compressed blob with it
+    if events.is_empty() && race.status == RaceStatus::Finished {
+        events = crate::database::find_archived_race_events(&database, race_uuid)
+            .await
+            .map_err(database_error)?
+            .into_iter()
+            .filter(|event| event.occurred_at > since_bson)
+            .take(MAX_RACE_CHANGES + 1)
+            .collect();
+    }
+    let complete = events.len() <= MAX_RACE_CHANGES;
+    events.truncate(MAX_RACE_CHANGES);
+
+    let occurred_at = |event: &RaceEvent| {
+        DateTime::from_timestamp_millis(event.occurred_at.timestamp_millis()).unwrap_or(since)
+    };
+    // Hidden changes still move `latest` on, so they are not asked for again
+    let latest = events.last().map_or(since, occurred_at);
+
+    let viewer = user.map(|Extension(user)| user.user_uuid);
+    let focus = viewer_focus(&database, &race, viewer)
+        .await
+        .map_err(database_error)?;
+    let changes: Vec<RaceChangeEntry> = events
+        .iter()
+        .filter_map(|event| {
+            Some(RaceChangeEntry {
+                occurred_at: occurred_at(event),
+                change: event.change.as_seen_by(&race, viewer, focus)?,
+            })
+        })
+        .collect();
+
+    Ok(Json(RaceChangesResponse {
+        changes,
+        latest,
+        complete,
+    }))
 }
 
 /// Get local view for a player in a race
 ///
-/// This endpoint calculates and returns the player's 5-sector local view (current sector Â±2).
+/// This endpoint calculates and returns the player's local view, the sectors within
+/// `radius` of their own (current sector Â±2 by default).
 /// It provides:
 /// - The player's current sector as the center
-/// - 5 visible sectors with their details (id, name, thresholds, capacity, type)
+/// - The visible sectors with their details (id, name, thresholds, capacity, type)
 /// - All participants within the visible range with their positions
 /// - Current occupancy for each visible sector
+/// - The radius granted
 ///
 /// The local view handles circular track wrapping automatically, so sectors at the
 /// beginning/end of the track are properly included when the player is near track boundaries.
 ///
 /// # Sector Range Calculation
-/// For a player at sector N on a track with M sectors:
-/// - Visible sectors: [N-2, N-1, N, N+1, N+2] (with modulo wrapping)
-/// - Example: Player at sector 1 on 10-sector track sees [9, 0, 1, 2, 3]
+/// For a player at sector N on a track with M sectors and a radius R:
+/// - Visible sectors: [N-R, ..., N, ..., N+R] (with modulo wrapping)
+/// - Example: Player at sector 1 on 10-sector track sees [9, 0, 1, 2, 3] with R = 2
+///
+/// # Radius
+/// The requested radius is lowered to the race's `max_view_radius` (2 by default) and,
+/// in races with `focus_limited_view`, to what the pilot's focus allows
+/// (1 + focus / 4).
+///
+/// # Fog of War
+/// While fog of war is on, sectors outside the viewer's own sight are left out
+/// along with their cars, even when the granted radius reaches them, and
+/// another player's view is only found while their car is in sight.
 #[utoipa::path(
     get,
     path = "/api/v1/races/{race_uuid}/players/{player_uuid}/local-view",
     params(
         ("race_uuid" = String, Path, description = "Race UUID"),
-        ("player_uuid" = String, Path, description = "Player UUID")
+        ("player_uuid" = String, Path, description = "Player UUID"),
+        LocalViewQuery
     ),
     responses(
         (
@@ -2394,6 +3477,7 @@ pub async fn get_turn_phase(
             body = LocalViewResponse,
             example = json!({
                 "center_sector": 5,
+                "radius": 2,
                 "visible_sectors": [
                     {
                         "id": 3,
@@ -2475,6 +3559,8 @@ pub async fn get_turn_phase(
                 "details": null
             })
         ),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "API token lacks the read:races scope"),
         (
             status = 404,
             description = "Player not found in race or race not found",
@@ -2500,7 +3586,7 @@ pub async fn get_turn_phase(
 )]
 #[tracing::instrument(
     name = "Getting local view for player in race",
-    skip(database),
+    skip(database, cache, user),
     fields(
         race_uuid = %race_uuid_str,
         player_uuid = %player_uuid_str
@@ -2508,7 +3594,10 @@ pub async fn get_turn_phase(
 )]
 pub async fn get_local_view(
     State(database): State<Database>,
+    Extension(cache): Extension<RaceCache>,
     Path((race_uuid_str, player_uuid_str)): Path<(String, String)>,
+    Query(params): Query<LocalViewQuery>,
+    user: Option<Extension<UserContext>>,
 ) -> Result<Json<LocalViewResponse>, (StatusCode, Json<ErrorResponse>)> {
     // 1. Parse and validate UUIDs
     let race_uuid = if let Ok(uuid) = Uuid::parse_str(&race_uuid_str) {
@@ -2540,7 +3629,7 @@ pub async fn get_local_view(
     };
 
     // 2. Fetch race from database
-    let race = match get_race_by_uuid(&database, race_uuid).await {
+    let race = match get_race_cached(&database, &cache, race_uuid).await {
         Ok(Some(race)) => race,
         Ok(None) => {
             tracing::warn!("Race not found for UUID: {}", race_uuid);
@@ -2566,7 +3655,22 @@ pub async fn get_local_view(
         }
     };
 
-    // 3. Find participant by player_uuid
+    // 3. Find participant by player_uuid, among the cars the viewer sees
+    let viewer = user.map(|Extension(user)| user.user_uuid);
+    let focus = viewer_focus(&database, &race, viewer).await.map_err(|e| {
+        tracing::error!("Failed to fetch pilot: {:?}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: "DATABASE_ERROR".to_string(),
+                message: "Internal server error".to_string(),
+                details: Some(format!("Failed to fetch pilot: {e}")),
+            }),
+        )
+    })?;
+    // Sectors the viewer may see cars in; no local view reaches past them
+    let sight = race.visible_sectors(viewer, focus);
+    let race = race.as_seen_by(viewer, focus);
     let participant = race
         .participants
         .iter()
@@ -2585,8 +3689,28 @@ pub async fn get_local_view(
 
     let center_sector = participant.current_sector;
 
-    // 4. Calculate visible sector IDs using helper function
-    let visible_sector_ids = get_visible_sector_ids(center_sector, race.track.sectors.len());
+    // 4. Work out how far the player may see, then the visible sector IDs
+    let focus = if race.rules.focus_limited_view {
+        pilot_focus(&database, player_uuid, participant.pilot_uuid)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to fetch pilot: {:?}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse {
+                        error: "DATABASE_ERROR".to_string(),
+                        message: "Internal server error".to_string(),
+                        details: Some(format!("Failed to fetch pilot: {e}")),
+                    }),
+                )
+            })?
+    } else {
+        None
+    };
+    let (radius, mut visible_sector_ids) = race.local_view(center_sector, params.radius, focus);
+    if let Some(sight) = &sight {
+        visible_sector_ids.retain(|id| sight.contains(id));
+    }
 
     // 5. Filter sectors to visible range
     let mut visible_sectors = Vec::new();
@@ -2605,8 +3729,8 @@ pub async fn get_local_view(
                 name: sector.name.clone(),
                 min_value: sector.min_value,
                 max_value: sector.max_value,
-                slot_capacity: sector.slot_capacity,
-                sector_type: format!("{:?}", sector.sector_type),
+                slot_capacity: sector.capacity_for_lap(race.current_lap),
+                sector_type: sector.sector_type.clone(),
                 current_occupancy,
             });
         }
@@ -2642,9 +3766,10 @@ pub async fn get_local_view(
             .then_with(|| a.position_in_sector.cmp(&b.position_in_sector))
     });
 
-    // 7. Return local view data with 5 sectors
+    // 7. Return local view data
     let response = LocalViewResponse {
         center_sector,
+        radius,
         visible_sectors,
         visible_participants,
     };
@@ -2675,6 +3800,8 @@ pub async fn get_local_view(
 /// - Cards can only be used once per cycle
 /// - When all 5 cards are used, the hand replenishes automatically
 /// - Next replenishment occurs when `cards_remaining` reaches 0
+/// - In races with banking enabled, `banked_card` and `carried_over_card` show
+///   the card set aside for the next cycle and the extra card carried into this one
 #[utoipa::path(
     get,
     path = "/api/v1/races/{race_uuid}/players/{player_uuid}/boost-availability",
@@ -2699,7 +3826,9 @@ pub async fn get_local_view(
                 "current_cycle": 1,
                 "cycles_completed": 0,
                 "cards_remaining": 3,
-                "next_replenishment_at": 3
+                "next_replenishment_at": 3,
+                "banked_card": null,
+                "carried_over_card": null
             })
         ),
         (
@@ -2712,6 +3841,8 @@ pub async fn get_local_view(
                 "details": null
             })
         ),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Not the player's hand"),
         (
             status = 404,
             description = "Player not found in race or race not found",
@@ -2856,37 +3987,222 @@ pub async fn get_boost_availability(
         ));
     }
 
-    // 6. Get boost hand from participant
-    let boost_hand = &participant.boost_hand;
+    // 6. Build availability data from the participant's boost hand
+    let response = build_boost_availability_response(&participant.boost_hand);
 
-    // 7. Extract availability information
-    let available_cards = boost_hand.get_available_cards();
-    let hand_state = boost_hand.cards.clone();
+    tracing::info!(
+        "Boost availability retrieved for player {} in race {}",
+        player_uuid,
+        race_uuid
+    );
+    Ok(Json(response))
+}
 
-    // 8. Calculate next replenishment lap (current_lap + cards_remaining)
-    // When cards_remaining reaches 0, replenishment happens automatically
-    let next_replenishment_at = if boost_hand.cards_remaining > 0 {
-        Some(boost_hand.cards_remaining)
-    } else {
-        None
+/// Bank an unused boost card into the next cycle
+///
+/// Only available in races created with the `boost_card_banking` rule. Banking
+/// takes the card out of the current cycle straight away (it counts towards
+/// replenishment like a played card) and hands it back as an extra card when the
+/// hand replenishes. The extra card can be played once during that cycle, after
+/// the regular copy of the same value, and is discarded if still unused when the
+/// hand replenishes again.
+///
+/// # Rules
+/// - One card can be banked per cycle
+/// - Only unused cards from the regular hand can be banked
+/// - Banking the last card of a cycle replenishes the hand immediately
+#[utoipa::path(
+    post,
+    path = "/api/v1/races/{race_uuid}/players/{player_uuid}/bank-card",
+    params(
+        ("race_uuid" = String, Path, description = "Race UUID"),
+        ("player_uuid" = String, Path, description = "Player UUID")
+    ),
+    request_body = BankCardRequest,
+    responses(
+        (
+            status = 200,
+            description = "Card banked successfully",
+            body = BoostAvailabilityResponse,
+            example = json!({
+                "available_cards": [0, 1, 2, 3],
+                "hand_state": {
+                    "0": true,
+                    "1": true,
+                    "2": true,
+                    "3": true,
+                    "4": false
+                },
+                "current_cycle": 1,
+                "cycles_completed": 0,
+                "cards_remaining": 4,
+                "next_replenishment_at": 4,
+                "banked_card": 4,
+                "carried_over_card": null
+            })
+        ),
+        (
+            status = 400,
+            description = "Invalid UUID or card cannot be banked",
+            body = BoostCardErrorResponse,
+            example = json!({
+                "error_code": "BOOST_CARD_ALREADY_BANKED",
+                "message": "Boost card 4 is already banked for the next cycle",
+                "available_cards": [0, 1, 2, 3],
+                "current_cycle": 1,
+                "cards_remaining": 4
+            })
+        ),
+        (status = 404, description = "Race or player not found", body = BoostCardErrorResponse),
+        (
+            status = 409,
+            description = "Banking disabled, race not in progress or player finished",
+            body = BoostCardErrorResponse
+        ),
+        (status = 500, description = "Internal server error", body = BoostCardErrorResponse)
+    ),
+    tag = "races"
+)]
+#[tracing::instrument(
+    name = "Banking boost card for player in race",
+    skip(database, payload),
+    fields(
+        race_uuid = %race_uuid_str,
+        player_uuid = %player_uuid_str,
+        boost_value = payload.boost_value
+    )
+)]
+pub async fn bank_boost_card(
+    State(database): State<Database>,
+    Path((race_uuid_str, player_uuid_str)): Path<(String, String)>,
+    Json(payload): Json<BankCardRequest>,
+) -> Result<Json<BoostAvailabilityResponse>, (StatusCode, Json<BoostCardErrorResponse>)> {
+    let simple_error = |status: StatusCode, error_code: &str, message: String| {
+        (
+            status,
+            Json(BoostCardErrorResponse {
+                error_code: error_code.to_string(),
+                message,
+                available_cards: vec![],
+                current_cycle: 0,
+                cards_remaining: 0,
+            }),
+        )
     };
 
-    // 9. Return availability data
-    let response = BoostAvailabilityResponse {
-        available_cards,
-        hand_state,
-        current_cycle: boost_hand.current_cycle,
-        cycles_completed: boost_hand.cycles_completed,
-        cards_remaining: boost_hand.cards_remaining,
-        next_replenishment_at,
+    // 1. Parse and validate UUIDs
+    let race_uuid = Uuid::parse_str(&race_uuid_str).map_err(|e| {
+        tracing::warn!("Invalid race UUID: {}", e);
+        simple_error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_UUID",
+            format!("Invalid race UUID: {e}"),
+        )
+    })?;
+
+    let player_uuid = Uuid::parse_str(&player_uuid_str).map_err(|e| {
+        tracing::warn!("Invalid player UUID: {}", e);
+        simple_error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_UUID",
+            format!("Invalid player UUID: {e}"),
+        )
+    })?;
+
+    // 2. Fetch race from database
+    let mut race = match get_race_by_uuid(&database, race_uuid).await {
+        Ok(Some(race)) => race,
+        Ok(None) => {
+            tracing::warn!("Race not found for UUID: {}", race_uuid);
+            return Err(simple_error(
+                StatusCode::NOT_FOUND,
+                "RACE_NOT_FOUND",
+                "Race not found".to_string(),
+            ));
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch race: {:?}", e);
+            return Err(simple_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "DATABASE_ERROR",
+                "Failed to fetch race".to_string(),
+            ));
+        }
     };
 
+    // 3. Check the race allows banking
+    if !race.rules.boost_card_banking {
+        tracing::warn!("Boost card banking is not enabled for race {}", race_uuid);
+        return Err(simple_error(
+            StatusCode::CONFLICT,
+            "BANKING_DISABLED",
+            "Boost card banking is not enabled for this race".to_string(),
+        ));
+    }
+
+    // 4. Validate the card against the player's hand so errors carry the hand state
+    if let Some(participant) = race
+        .participants
+        .iter()
+        .find(|p| p.player_uuid == player_uuid)
+    {
+        let mut preview_hand = participant.boost_hand.clone();
+        if let Err(boost_error) =
+            BoostHandManager::bank_boost_card(&mut preview_hand, payload.boost_value)
+        {
+            tracing::warn!("Boost card banking rejected: {}", boost_error);
+            let error_response =
+                BoostCardErrorResponse::from_error(&boost_error, &participant.boost_hand);
+            return Err((StatusCode::BAD_REQUEST, Json(error_response)));
+        }
+    }
+
+    // 5. Apply the decision on the race
+    let before = race.clone();
+    if let Err(e) = race.bank_boost_card(player_uuid, payload.boost_value) {
+        tracing::warn!("Failed to bank boost card: {}", e);
+        return Err(if e.contains("not found") {
+            simple_error(StatusCode::NOT_FOUND, "PLAYER_NOT_FOUND", e)
+        } else if e.contains("not in progress") || e.contains("already finished") {
+            simple_error(StatusCode::CONFLICT, "RACE_STATE_ERROR", e)
+        } else {
+            simple_error(StatusCode::BAD_REQUEST, "BOOST_CARD_ERROR", e)
+        });
+    }
+
+    // 6. Persist the updated hands
+    if let Err(e) = update_race_participants_in_db(&database, &race).await {
+        tracing::error!("Failed to save banked card: {:?}", e);
+        return Err(simple_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "DATABASE_ERROR",
+            "Failed to save banked card".to_string(),
+        ));
+    }
+    let audit = AuditEntry::new("race.bank_card").by(player_uuid);
+    record_race_changes(&database, audit, &before, &race, &[]).await;
+
+    let participant = race
+        .participants
+        .iter()
+        .find(|p| p.player_uuid == player_uuid)
+        .ok_or_else(|| {
+            simple_error(
+                StatusCode::NOT_FOUND,
+                "PLAYER_NOT_FOUND",
+                "Player not found in race".to_string(),
+            )
+        })?;
+
     tracing::info!(
-        "Boost availability retrieved for player {} in race {}",
+        "Player {} banked boost card {} in race {}",
         player_uuid,
+        payload.boost_value,
         race_uuid
     );
-    Ok(Json(response))
+    Ok(Json(build_boost_availability_response(
+        &participant.boost_hand,
+    )))
 }
 
 /// Get lap history for a player in a race
@@ -2908,6 +4224,9 @@ pub async fn get_boost_availability(
 /// - Laps when cards were used
 /// - Average boost value per cycle
 ///
+/// Other players' boosts of the lap being played are left out, and under fog
+/// of war only cars in the viewer's sight are found.
+///
 /// # Note
 /// Historical performance and movement data is currently limited to boost usage tracking.
 /// Full lap-by-lap performance reconstruction would require storing additional historical data.
@@ -2916,7 +4235,8 @@ pub async fn get_boost_availability(
     path = "/api/v1/races/{race_uuid}/players/{player_uuid}/lap-history",
     params(
         ("race_uuid" = String, Path, description = "Race UUID"),
-        ("player_uuid" = String, Path, description = "Player UUID")
+        ("player_uuid" = String, Path, description = "Player UUID"),
+        CursorQuery
     ),
     responses(
         (
@@ -2955,7 +4275,8 @@ pub async fn get_boost_availability(
                         "laps_in_cycle": [1, 2, 3],
                         "average_boost": 1.67
                     }
-                ]
+                ],
+                "next_cursor": null
             })
         ),
         (
@@ -2968,6 +4289,8 @@ pub async fn get_boost_availability(
                 "details": null
             })
         ),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "API token lacks the read:races scope"),
         (
             status = 404,
             description = "Player not found in race or race not found",
@@ -2991,6 +4314,18 @@ pub async fn get_boost_availability(
     ),
     tag = "races"
 )]
+pub async fn get_lap_history(
+    State(database): State<Database>,
+    Path((race_uuid_str, player_uuid_str)): Path<(String, String)>,
+    Query(page): Query<CursorQuery>,
+    user: Option<Extension<UserContext>>,
+) -> Result<Json<LapHistoryResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let viewer = user.map(|Extension(user)| user.user_uuid);
+    lap_history(database, race_uuid_str, player_uuid_str, page, viewer).await
+}
+
+/// Lap history in the response shape of either API version, as `viewer`
+/// may see it
 #[tracing::instrument(
     name = "Getting lap history for player in race",
     skip(database),
@@ -2999,10 +4334,13 @@ pub async fn get_boost_availability(
         player_uuid = %player_uuid_str
     )
 )]
-pub async fn get_lap_history(
-    State(database): State<Database>,
-    Path((race_uuid_str, player_uuid_str)): Path<(String, String)>,
-) -> Result<Json<LapHistoryResponse>, (StatusCode, Json<ErrorResponse>)> {
+pub(crate) async fn lap_history<T: From<v2::LapHistoryResponse>>(
+    database: Database,
+    race_uuid_str: String,
+    player_uuid_str: String,
+    page: CursorQuery,
+    viewer: Option<Uuid>,
+) -> Result<Json<T>, (StatusCode, Json<ErrorResponse>)> {
     // 1. Parse and validate UUIDs
     let race_uuid = if let Ok(uuid) = Uuid::parse_str(&race_uuid_str) {
         uuid
@@ -3059,7 +4397,19 @@ pub async fn get_lap_history(
         }
     };
 
-    // 3. Find participant by player_uuid
+    // 3. Find participant by player_uuid, among the cars the viewer sees
+    let focus = viewer_focus(&database, &race, viewer).await.map_err(|e| {
+        tracing::error!("Failed to fetch pilot: {:?}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: "DATABASE_ERROR".to_string(),
+                message: "Internal server error".to_string(),
+                details: Some(format!("Failed to fetch pilot: {e}")),
+            }),
+        )
+    })?;
+    let race = race.as_seen_by(viewer, focus);
     let participant = race
         .participants
         .iter()
@@ -3081,34 +4431,33 @@ pub async fn get_lap_history(
 
     // 5. Build lap records from usage history
     // Note: We only have boost usage data. Historical performance values and movements
-    // are not currently stored, so we use placeholder values.
+    // are not currently stored; v1 fills them with placeholder values.
     let mut lap_records = Vec::new();
 
     for usage_record in boost_usage_history {
         // Determine lap characteristic based on lap number
         // This is a simplified approach - ideally we'd store historical lap characteristics
         let lap_characteristic = if usage_record.lap_number % 2 == 1 {
-            "Straight"
+            LapCharacteristic::Straight
         } else {
-            "Curve"
+            LapCharacteristic::Curve
         };
 
-        // Create lap record with available data
-        // Note: base_value, final_value, from_sector, to_sector, and movement_type
-        // are not historically tracked, so we use placeholder values
-        lap_records.push(LapRecord {
+        lap_records.push(v2::LapRecord {
             lap_number: usage_record.lap_number,
-            lap_characteristic: lap_characteristic.to_string(),
+            lap_characteristic,
             boost_used: usage_record.boost_value,
             boost_cycle: usage_record.cycle_number,
-            base_value: 0,                        // Historical base value not tracked
-            final_value: 0,                       // Historical final value not tracked
-            from_sector: 0,                       // Historical sector movement not tracked
-            to_sector: 0,                         // Historical sector movement not tracked
-            movement_type: "Unknown".to_string(), // Historical movement type not tracked
         });
     }
 
+    // Only the requested page of laps is returned; cycle summaries always cover the race
+    let after_lap = page.after::<u32>()?;
+    let (lap_records, next_cursor) =
+        paginate(lap_records, after_lap.as_ref(), page.limit(), |lap| {
+            lap.lap_number
+        });
+
     // 6. Get cycle summaries using participant.get_boost_cycle_summaries()
     let cycle_summaries_domain = participant.get_boost_cycle_summaries();
 
@@ -3124,9 +4473,10 @@ pub async fn get_lap_history(
         .collect();
 
     // 8. Return history data with lap records and cycle summaries
-    let response = LapHistoryResponse {
+    let response = v2::LapHistoryResponse {
         laps: lap_records,
         cycle_summaries,
+        next_cursor,
     };
 
     tracing::info!(
@@ -3134,72 +4484,248 @@ pub async fn get_lap_history(
         player_uuid,
         race_uuid
     );
-    Ok(Json(response))
+    Ok(Json(response.into()))
 }
 
-// Existing endpoint implementations...
-
-/// Create a new race
+/// Get the boost card usage history of a player in a race
+///
+/// Records are ordered by lap. Pass `limit` to page through long races and
+/// follow `next_cursor` until it is `null`.
 #[utoipa::path(
-    post,
-    path = "/api/v1/races",
-    request_body = CreateRaceRequest,
+    get,
+    path = "/api/v1/races/{race_uuid}/players/{player_uuid}/boost-usage-history",
+    params(
+        ("race_uuid" = String, Path, description = "Race UUID"),
+        ("player_uuid" = String, Path, description = "Player UUID"),
+        CursorQuery
+    ),
     responses(
-        (status = 201, description = "Race created successfully", body = RaceResponse),
-        (status = 400, description = "Bad request"),
-        (status = 500, description = "Internal server error")
+        (status = 200, description = "Boost usage records", body = BoostUsageHistoryResponse),
+        (status = 400, description = "Invalid UUID format or cursor", body = ErrorResponse),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Not the player's history"),
+        (status = 404, description = "Player not found in race or race not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
-    tag = "races"
+    tag = "boost-cards"
 )]
 #[tracing::instrument(
-    name = "Creating a new race",
-    skip(database, payload),
+    name = "Getting boost usage history for player in race",
+    skip(database),
     fields(
-        race_name = %payload.name,
-        track_name = %payload.track_name,
-        total_laps = payload.total_laps
+        race_uuid = %race_uuid_str,
+        player_uuid = %player_uuid_str
     )
 )]
-pub async fn create_race(
+pub async fn get_boost_usage_history(
     State(database): State<Database>,
-    Json(payload): Json<CreateRaceRequest>,
-) -> Result<(StatusCode, Json<RaceResponse>), StatusCode> {
-    // Create sectors from request
-    let sectors: Vec<Sector> = payload
-        .sectors
-        .into_iter()
-        .map(|s| Sector {
-            id: s.id,
-            name: s.name,
-            min_value: s.min_value,
-            max_value: s.max_value,
-            slot_capacity: s.slot_capacity,
-            sector_type: s.sector_type,
-        })
-        .collect();
-
-    // Create track
-    let track = match Track::new(payload.track_name, sectors) {
-        Ok(track) => track,
-        Err(e) => {
-            tracing::warn!("Invalid track configuration: {}", e);
-            return Err(StatusCode::BAD_REQUEST);
-        }
+    Path((race_uuid_str, player_uuid_str)): Path<(String, String)>,
+    Query(page): Query<CursorQuery>,
+) -> Result<Json<BoostUsageHistoryResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let error = |status: StatusCode, error: &str, message: &str| {
+        (
+            status,
+            Json(ErrorResponse {
+                error: error.to_string(),
+                message: message.to_string(),
+                details: None,
+            }),
+        )
+    };
+
+    let (Ok(race_uuid), Ok(player_uuid)) = (
+        Uuid::parse_str(&race_uuid_str),
+        Uuid::parse_str(&player_uuid_str),
+    ) else {
+        tracing::warn!(
+            "Invalid UUID format: {}, {}",
+            race_uuid_str,
+            player_uuid_str
+        );
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_UUID",
+            "Invalid UUID format",
+        ));
+    };
+    let after_lap = page.after::<u32>()?;
+
+    let race = get_race_by_uuid(&database, race_uuid)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to fetch race: {:?}", e);
+            error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "DATABASE_ERROR",
+                "Internal server error",
+            )
+        })?
+        .ok_or_else(|| error(StatusCode::NOT_FOUND, "RACE_NOT_FOUND", "Race not found"))?;
+
+    let participant = race
+        .participants
+        .into_iter()
+        .find(|p| p.player_uuid == player_uuid)
+        .ok_or_else(|| {
+            error(
+                StatusCode::NOT_FOUND,
+                "PLAYER_NOT_FOUND",
+                "Player not found in race",
+            )
+        })?;
+
+    let (records, next_cursor) = paginate(
+        participant.boost_usage_history,
+        after_lap.as_ref(),
+        page.limit(),
+        |record| record.lap_number,
+    );
+
+    Ok(Json(BoostUsageHistoryResponse {
+        records,
+        next_cursor,
+    }))
+}
+
+// Existing endpoint implementations...
+
+/// Create a new race
+///
+/// The track definition is validated before the race is created. Sector ids must
+/// be contiguous starting at 0, each sector needs `min_value < max_value`, thresholds
+/// must not go backwards along the track, inner sectors need a non-zero capacity,
+/// and the first/last sectors must be infinite `Start`/`Finish` sectors.
+/// Every problem found is reported in `field_errors`.
+///
+/// Set `template_id` to use one of the built-in track templates, or `track_uuid`
+/// to use a stored track, instead of sending `sectors`. Stored tracks are copied
+/// into the race when it starts, so editing them later does not change the race.
+#[utoipa::path(
+    post,
+    path = "/api/v1/races",
+    request_body = CreateRaceRequest,
+    responses(
+        (status = 201, description = "Race created successfully", body = RaceResponse),
+        (
+            status = 400,
+            description = "Invalid track definition",
+            body = TrackValidationErrorResponse,
+            example = json!({
+                "error": "INVALID_TRACK",
+                "message": "Track definition is invalid",
+                "field_errors": [
+                    {
+                        "field": "sectors[1].min_value",
+                        "message": "min_value (20) must be lower than max_value (15)"
+                    },
+                    {
+                        "field": "sectors[2].slot_capacity",
+                        "message": "Last sector must have infinite capacity"
+                    }
+                ]
+            })
+        ),
+        (status = 404, description = "Track or track template not found", body = TrackValidationErrorResponse),
+        (status = 500, description = "Internal server error", body = TrackValidationErrorResponse)
+    ),
+    tag = "races"
+)]
+#[tracing::instrument(
+    name = "Creating a new race",
+    skip(database, payload),
+    fields(
+        race_name = %payload.name,
+        track_name = %payload.track_name,
+        template_id = ?payload.template_id,
+        track_uuid = ?payload.track_uuid,
+        total_laps = payload.total_laps
+    )
+)]
+pub async fn create_race(
+    State(database): State<Database>,
+    Json(payload): Json<CreateRaceRequest>,
+) -> Result<(StatusCode, Json<RaceResponse>), (StatusCode, Json<TrackValidationErrorResponse>)> {
+    // Create track from a stored track, a template, or the sectors in the request
+    let track_sources = [
+        payload.track_uuid.is_some(),
+        payload.template_id.is_some(),
+        !payload.sectors.is_empty(),
+    ];
+    if track_sources.iter().filter(|given| **given).count() > 1 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(TrackValidationErrorResponse {
+                error: "INVALID_TRACK".to_string(),
+                message: "Provide only one of track_uuid, template_id or sectors".to_string(),
+                field_errors: vec![],
+            }),
+        ));
+    }
+
+    let custom_track_name = Some(payload.track_name.clone()).filter(|name| !name.is_empty());
+    let mut source_track_uuid = None;
+    let track_result = if let Some(track_uuid) = payload.track_uuid.as_deref() {
+        let track_uuid = parse_uuid(track_uuid)?;
+        let design = load_track_design(&database, track_uuid).await?;
+        source_track_uuid = Some(design.uuid);
+        design.to_track(custom_track_name)
+    } else if let Some(template_id) = payload.template_id.as_deref() {
+        let template = load_track_template(&database, template_id).await?;
+        template.to_track(custom_track_name)
+    } else {
+        let sectors: Vec<Sector> = payload
+            .sectors
+            .into_iter()
+            .map(|s| Sector {
+                id: s.id,
+                name: s.name,
+                min_value: s.min_value,
+                max_value: s.max_value,
+                slot_capacity: s.slot_capacity,
+                sector_type: s.sector_type,
+                capacity_schedule: s.capacity_schedule,
+                modifiers: s.modifiers,
+            })
+            .collect();
+
+        Track::new_validated(payload.track_name, sectors)
+    };
+
+    let track = match track_result {
+        Ok(track) => track,
+        Err(field_errors) => {
+            tracing::warn!(
+                "Invalid track configuration: {} problem(s) found",
+                field_errors.len()
+            );
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(TrackValidationErrorResponse {
+                    error: "INVALID_TRACK".to_string(),
+                    message: "Track definition is invalid".to_string(),
+                    field_errors,
+                }),
+            ));
+        }
     };
 
     // Create race
-    let mut race = Race::new(payload.name, track, payload.total_laps);
+    // The race keeps its own copy of the track from the moment it starts
+    let mut race = Race::with_rules(payload.name, track, payload.total_laps, payload.rules);
+    race.source_track_uuid = source_track_uuid;
 
     // Auto-start the race immediately for better UX
     // This eliminates the need for manual race starting
     race.status = RaceStatus::InProgress;
     race.lap_characteristic = LapCharacteristic::Straight; // Start with straight characteristic
     race.current_lap = 1;
+    race.open_turn();
 
     tracing::info!("Auto-starting race {} for improved UX", race.uuid);
 
     match insert_race(&database, &race).await {
         Ok(created_race) => {
+            audit_race_creation(&database, AuditEntry::new("race.create"), &created_race).await;
             tracing::info!(
                 "Race created and auto-started successfully with UUID: {}",
                 created_race.uuid
@@ -3214,7 +4740,46 @@ pub async fn create_race(
         }
         Err(e) => {
             tracing::error!("Failed to create race: {:?}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(TrackValidationErrorResponse {
+                    error: "DATABASE_ERROR".to_string(),
+                    message: "Failed to create race".to_string(),
+                    field_errors: vec![],
+                }),
+            ))
+        }
+    }
+}
+
+/// Load a track template for race creation, mapping failures to API errors
+async fn load_track_template(
+    database: &Database,
+    template_id: &str,
+) -> Result<TrackTemplate, (StatusCode, Json<TrackValidationErrorResponse>)> {
+    match get_track_template_by_id(database, template_id).await {
+        Ok(Some(template)) => Ok(template),
+        Ok(None) => {
+            tracing::warn!("Track template not found: {}", template_id);
+            Err((
+                StatusCode::NOT_FOUND,
+                Json(TrackValidationErrorResponse {
+                    error: "TEMPLATE_NOT_FOUND".to_string(),
+                    message: format!("Track template '{template_id}' does not exist"),
+                    field_errors: vec![],
+                }),
+            ))
+        }
+        Err(e) => {
+            tracing::error!("Failed to load track template: {:?}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(TrackValidationErrorResponse {
+                    error: "DATABASE_ERROR".to_string(),
+                    message: "Failed to load track template".to_string(),
+                    field_errors: vec![],
+                }),
+            ))
         }
     }
 }
@@ -3225,6 +4790,8 @@ pub async fn create_race(
     path = "/api/v1/races",
     responses(
         (status = 200, description = "List of all races", body = Vec<Race>),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "API token lacks the read:races scope"),
         (status = 500, description = "Internal server error")
     ),
     tag = "races"
@@ -3236,7 +4803,12 @@ pub async fn get_all_races(
     match get_all_races_from_db(&database).await {
         Ok(races) => {
             tracing::info!("Successfully fetched {} races", races.len());
-            Ok(Json(races))
+            Ok(Json(
+                races
+                    .iter()
+                    .map(|race| race.as_seen_by(None, None))
+                    .collect(),
+            ))
         }
         Err(e) => {
             tracing::error!("Failed to fetch races: {:?}", e);
@@ -3245,7 +4817,74 @@ pub async fn get_all_races(
     }
 }
 
+/// List races one page at a time, newest first
+///
+/// Unlike `GET /api/v1/races`, which returns every race, this returns at most
+/// `limit` races and a `next_cursor` to fetch the following page. Races created
+/// while paging do not shift later pages.
+#[utoipa::path(
+    get,
+    path = "/api/v1/races/paged",
+    params(RaceListQuery),
+    responses(
+        (status = 200, description = "One page of races", body = RaceListResponse),
+        (status = 400, description = "Invalid cursor", body = ErrorResponse),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "API token lacks the read:races scope"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "races"
+)]
+#[tracing::instrument(name = "Fetching a page of races", skip(database))]
+pub async fn get_races_page(
+    State(database): State<Database>,
+    Query(params): Query<RaceListQuery>,
+) -> Result<Json<RaceListResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let page = CursorQuery {
+        cursor: params.cursor,
+        limit: Some(params.limit.unwrap_or(DEFAULT_RACE_PAGE_LIMIT)),
+    };
+    let after = page.after::<TimePosition>()?;
+    let limit = page.limit().unwrap_or_default();
+
+    let mut races = get_races_page_from_db(&database, params.status, after, limit)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to fetch races: {:?}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: "DATABASE_ERROR".to_string(),
+                    message: "Internal server error".to_string(),
+                    details: Some(format!("Failed to fetch races: {e}")),
+                }),
+            )
+        })?;
+
+    // One extra race was fetched to tell whether another page follows
+    let next_cursor = if races.len() > limit {
+        races.truncate(limit);
+        races.last().map(|race| {
+            encode_cursor(&TimePosition {
+                millis: race.created_at.timestamp_millis(),
+                uuid: race.uuid,
+            })
+        })
+    } else {
+        None
+    };
+
+    let races = races
+        .iter()
+        .map(|race| race.as_seen_by(None, None))
+        .collect();
+    Ok(Json(RaceListResponse { races, next_cursor }))
+}
+
 /// Get race by UUID
+///
+/// Other players' boost hands and pending actions are hidden; a signed-in
+/// participant also sees their own.
 #[utoipa::path(
     get,
     path = "/api/v1/races/{race_uuid}",
@@ -3254,15 +4893,19 @@ pub async fn get_all_races(
     ),
     responses(
         (status = 200, description = "Race found", body = Race),
+        (status = 400, description = "Invalid UUID"),
         (status = 404, description = "Race not found"),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "API token lacks the read:races scope"),
         (status = 500, description = "Internal server error")
     ),
     tag = "races"
 )]
-#[tracing::instrument(name = "Fetching race by UUID", skip(database))]
+#[tracing::instrument(name = "Fetching race by UUID", skip(database, user))]
 pub async fn get_race(
     State(database): State<Database>,
     Path(race_uuid_str): Path<String>,
+    user: Option<Extension<UserContext>>,
 ) -> Result<Json<Race>, StatusCode> {
     let race_uuid = match Uuid::parse_str(&race_uuid_str) {
         Ok(uuid) => uuid,
@@ -3271,11 +4914,16 @@ pub async fn get_race(
             return Err(StatusCode::BAD_REQUEST);
         }
     };
+    let viewer = user.map(|Extension(user)| user.user_uuid);
 
     match get_race_by_uuid(&database, race_uuid).await {
         Ok(Some(race)) => {
             tracing::info!("Race found for UUID: {}", race_uuid);
-            Ok(Json(race))
+            let focus = viewer_focus(&database, &race, viewer).await.map_err(|e| {
+                tracing::error!("Failed to fetch pilot: {:?}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+            Ok(Json(race.as_seen_by(viewer, focus)))
         }
         Ok(None) => {
             tracing::warn!("Race not found for UUID: {}", race_uuid);
@@ -3299,6 +4947,7 @@ pub async fn get_race(
     responses(
         (status = 200, description = "Successfully joined race", body = RaceResponse),
         (status = 400, description = "Bad request"),
+        (status = 403, description = "Player is banned, or player or car outside the race's skill bracket or car tier"),
         (status = 404, description = "Race not found"),
         (status = 409, description = "Cannot join race"),
         (status = 500, description = "Internal server error")
@@ -3343,11 +4992,51 @@ pub async fn join_race(
         }
     };
 
-    match join_race_in_db(&database, race_uuid, player_uuid, car_uuid, pilot_uuid).await {
+    match is_player_banned_in_db(&database, player_uuid).await {
+        Ok(false) => {}
+        Ok(true) => {
+            tracing::warn!(
+                "Banned player {} tried to join race {}",
+                player_uuid,
+                race_uuid
+            );
+            return Err(StatusCode::FORBIDDEN);
+        }
+        Err(e) => {
+            tracing::error!("Failed to check player ban: {:?}", e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+
+    let race = match get_race_by_uuid(&database, race_uuid).await {
+        Ok(Some(race)) => race,
+        Ok(None) => {
+            tracing::warn!("Race not found for UUID: {}", race_uuid);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch race: {:?}", e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    let car_class = check_entry_window(&database, &race, player_uuid, car_uuid, None)
+        .await
+        .map_err(|(status, _)| status)?;
+
+    let joined = join_race_in_db(
+        &database,
+        race_uuid,
+        player_uuid,
+        car_uuid,
+        pilot_uuid,
+        car_class,
+    )
+    .await;
+    match joined {
         Ok(Some(updated_race)) => {
             tracing::info!("Player {} joined race {}", player_uuid, race_uuid);
             Ok(Json(RaceResponse {
-                race: updated_race,
+                race: updated_race.as_seen_by(Some(player_uuid), None),
                 message: "Successfully joined race".to_string(),
             }))
         }
@@ -3368,7 +5057,527 @@ pub async fn join_race(
     }
 }
 
+/// Add a computer-controlled participant to a race
+///
+/// Bots submit their lap actions automatically once a player has acted for the turn.
+#[utoipa::path(
+    post,
+    path = "/api/v1/races/{race_uuid}/bots",
+    params(
+        ("race_uuid" = String, Path, description = "Race UUID")
+    ),
+    request_body = AddBotRequest,
+    responses(
+        (status = 200, description = "Bot added to race", body = RaceResponse),
+        (status = 400, description = "Invalid UUID or difficulty"),
+        (status = 404, description = "Race not found"),
+        (status = 409, description = "Cannot add participants to this race"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "races"
+)]
+#[tracing::instrument(name = "Adding bot to race", skip(database, payload))]
+pub async fn add_bot(
+    State(database): State<Database>,
+    Path(race_uuid_str): Path<String>,
+    Json(payload): Json<AddBotRequest>,
+) -> Result<Json<RaceResponse>, StatusCode> {
+    let race_uuid = match Uuid::parse_str(&race_uuid_str) {
+        Ok(uuid) => uuid,
+        Err(e) => {
+            tracing::warn!("Invalid race UUID: {}", e);
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    };
+
+    let profile = match BotProfile::new(payload.personality, payload.difficulty) {
+        Ok(profile) => profile,
+        Err(e) => {
+            tracing::warn!("Invalid bot profile: {}", e);
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    };
+
+    match add_bot_in_db(&database, race_uuid, profile).await {
+        Ok(Some(updated_race)) => {
+            tracing::info!("Bot added to race {}", race_uuid);
+            Ok(Json(RaceResponse {
+                race: updated_race.as_seen_by(None, None),
+                message: "Bot added to race".to_string(),
+            }))
+        }
+        Ok(None) => {
+            tracing::warn!("Race not found for UUID: {}", race_uuid);
+            Err(StatusCode::NOT_FOUND)
+        }
+        Err(e) => {
+            tracing::error!("Failed to add bot: {:?}", e);
+            if e.to_string().contains("Cannot") {
+                Err(StatusCode::CONFLICT)
+            } else {
+                Err(StatusCode::INTERNAL_SERVER_ERROR)
+            }
+        }
+    }
+}
+
+/// Create a practice race against bots
+///
+/// The player is entered directly, without registration, and the race starts
+/// immediately. Practice results never count toward rankings.
+#[utoipa::path(
+    post,
+    path = "/api/v1/races/practice",
+    request_body = CreatePracticeRaceRequest,
+    responses(
+        (status = 201, description = "Practice race created and started", body = RaceResponse),
+        (status = 400, description = "Invalid UUID, bot count or difficulty", body = TrackValidationErrorResponse),
+        (status = 404, description = "Track template not found", body = TrackValidationErrorResponse),
+        (status = 500, description = "Internal server error", body = TrackValidationErrorResponse)
+    ),
+    tag = "races"
+)]
+#[tracing::instrument(
+    name = "Creating a practice race",
+    skip(database, payload),
+    fields(template_id = %payload.template_id, bot_count = payload.bot_count)
+)]
+pub async fn create_practice_race(
+    State(database): State<Database>,
+    Json(payload): Json<CreatePracticeRaceRequest>,
+) -> Result<(StatusCode, Json<RaceResponse>), (StatusCode, Json<TrackValidationErrorResponse>)> {
+    let bad_request = |message: String| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(TrackValidationErrorResponse {
+                error: "INVALID_PRACTICE_RACE".to_string(),
+                message,
+                field_errors: vec![],
+            }),
+        )
+    };
+
+    let (Ok(player_uuid), Ok(car_uuid), Ok(pilot_uuid)) = (
+        Uuid::parse_str(&payload.player_uuid),
+        Uuid::parse_str(&payload.car_uuid),
+        Uuid::parse_str(&payload.pilot_uuid),
+    ) else {
+        tracing::warn!("Invalid UUID in practice race request");
+        return Err(bad_request("Invalid player, car or pilot UUID".to_string()));
+    };
+
+    let template = load_track_template(&database, &payload.template_id).await?;
+    let track = template.to_track(None).map_err(|field_errors| {
+        tracing::error!("Track template {} is invalid", template.template_id);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(TrackValidationErrorResponse {
+                error: "INVALID_TRACK".to_string(),
+                message: "Track template is invalid".to_string(),
+                field_errors,
+            }),
+        )
+    })?;
+
+    let race = Race::new_practice(
+        format!("Practice - {}", template.name),
+        track,
+        payload.total_laps.unwrap_or(template.recommended_laps),
+        (player_uuid, car_uuid, pilot_uuid),
+        payload.bot_count,
+        payload.bot_difficulty,
+    )
+    .map_err(|e| {
+        tracing::warn!("Invalid practice race: {}", e);
+        bad_request(e)
+    })?;
+
+    match insert_race(&database, &race).await {
+        Ok(created_race) => {
+            let audit = AuditEntry::new("race.create_practice").by(player_uuid);
+            audit_race_creation(&database, audit, &created_race).await;
+            tracing::info!("Practice race created with UUID: {}", created_race.uuid);
+            Ok((
+                StatusCode::CREATED,
+                Json(RaceResponse {
+                    race: created_race.as_seen_by(Some(player_uuid), None),
+                    message: "Practice race created and started successfully".to_string(),
+                }),
+            ))
+        }
+        Err(e) => {
+            tracing::error!("Failed to create practice race: {:?}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(TrackValidationErrorResponse {
+                    error: "DATABASE_ERROR".to_string(),
+                    message: "Failed to create race".to_string(),
+                    field_errors: vec![],
+                }),
+            ))
+        }
+    }
+}
+
+/// Create a time trial
+///
+/// The player races alone, or against a recorded ghost when `ghost_uuid` is
+/// given. The score is the number of laps completed within the turn limit, and
+/// the run is saved as a ghost once the time trial finishes.
+#[utoipa::path(
+    post,
+    path = "/api/v1/races/time-trial",
+    request_body = CreateTimeTrialRequest,
+    responses(
+        (status = 201, description = "Time trial created and started", body = RaceResponse),
+        (status = 400, description = "Invalid UUID or turn count", body = TrackValidationErrorResponse),
+        (status = 404, description = "Track template or ghost not found", body = TrackValidationErrorResponse),
+        (status = 500, description = "Internal server error", body = TrackValidationErrorResponse)
+    ),
+    tag = "races"
+)]
+#[tracing::instrument(
+    name = "Creating a time trial",
+    skip(database, payload),
+    fields(template_id = %payload.template_id, ghost_uuid = ?payload.ghost_uuid)
+)]
+pub async fn create_time_trial(
+    State(database): State<Database>,
+    Json(payload): Json<CreateTimeTrialRequest>,
+) -> Result<(StatusCode, Json<RaceResponse>), (StatusCode, Json<TrackValidationErrorResponse>)> {
+    let error = |status: StatusCode, code: &str, message: String| {
+        (
+            status,
+            Json(TrackValidationErrorResponse {
+                error: code.to_string(),
+                message,
+                field_errors: vec![],
+            }),
+        )
+    };
+
+    let (Ok(player_uuid), Ok(car_uuid), Ok(pilot_uuid)) = (
+        Uuid::parse_str(&payload.player_uuid),
+        Uuid::parse_str(&payload.car_uuid),
+        Uuid::parse_str(&payload.pilot_uuid),
+    ) else {
+        tracing::warn!("Invalid UUID in time trial request");
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_UUID",
+            "Invalid player, car or pilot UUID".to_string(),
+        ));
+    };
+    let player = (player_uuid, car_uuid, pilot_uuid);
+
+    let race_result = if let Some(ghost_uuid) = payload.ghost_uuid.as_deref() {
+        let ghost_uuid = Uuid::parse_str(ghost_uuid).map_err(|e| {
+            tracing::warn!("Invalid ghost UUID: {}", e);
+            error(
+                StatusCode::BAD_REQUEST,
+                "INVALID_UUID",
+                format!("Invalid ghost UUID: {e}"),
+            )
+        })?;
+        let ghost = match get_ghost_by_uuid(&database, ghost_uuid).await {
+            Ok(Some(ghost)) => ghost,
+            Ok(None) => {
+                tracing::warn!("Ghost not found: {}", ghost_uuid);
+                return Err(error(
+                    StatusCode::NOT_FOUND,
+                    "GHOST_NOT_FOUND",
+                    "Ghost not found".to_string(),
+                ));
+            }
+            Err(e) => {
+                tracing::error!("Failed to load ghost: {:?}", e);
+                return Err(error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "DATABASE_ERROR",
+                    "Failed to load ghost".to_string(),
+                ));
+            }
+        };
+        Race::new_time_trial_against(format!("Time Trial - {}", ghost.track.name), player, &ghost)
+    } else {
+        let template = load_track_template(&database, &payload.template_id).await?;
+        let track = template.to_track(None).map_err(|field_errors| {
+            tracing::error!("Track template {} is invalid", template.template_id);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(TrackValidationErrorResponse {
+                    error: "INVALID_TRACK".to_string(),
+                    message: "Track template is invalid".to_string(),
+                    field_errors,
+                }),
+            )
+        })?;
+        Race::new_time_trial(
+            format!("Time Trial - {}", template.name),
+            track,
+            payload.turns,
+            player,
+        )
+    };
+
+    let race = race_result.map_err(|e| {
+        tracing::warn!("Invalid time trial: {}", e);
+        error(StatusCode::BAD_REQUEST, "INVALID_TIME_TRIAL", e)
+    })?;
+
+    match insert_race(&database, &race).await {
+        Ok(created_race) => {
+            let audit = AuditEntry::new("race.create_time_trial").by(player_uuid);
+            audit_race_creation(&database, audit, &created_race).await;
+            tracing::info!("Time trial created with UUID: {}", created_race.uuid);
+            Ok((
+                StatusCode::CREATED,
+                Json(RaceResponse {
+                    race: created_race.as_seen_by(Some(player_uuid), None),
+                    message: "Time trial created and started successfully".to_string(),
+                }),
+            ))
+        }
+        Err(e) => {
+            tracing::error!("Failed to create time trial: {:?}", e);
+            Err(error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "DATABASE_ERROR",
+                "Failed to create race".to_string(),
+            ))
+        }
+    }
+}
+
+/// Get a recorded time trial ghost
+#[utoipa::path(
+    get,
+    path = "/api/v1/races/ghosts/{ghost_uuid}",
+    params(
+        ("ghost_uuid" = String, Path, description = "Ghost UUID")
+    ),
+    responses(
+        (status = 200, description = "Ghost found", body = Ghost),
+        (status = 400, description = "Invalid UUID"),
+        (status = 404, description = "Ghost not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "races"
+)]
+#[tracing::instrument(name = "Fetching ghost", skip(database))]
+pub async fn get_ghost(
+    State(database): State<Database>,
+    Path(ghost_uuid_str): Path<String>,
+) -> Result<Json<Ghost>, StatusCode> {
+    let ghost_uuid = match Uuid::parse_str(&ghost_uuid_str) {
+        Ok(uuid) => uuid,
+        Err(e) => {
+            tracing::warn!("Invalid ghost UUID: {}", e);
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    };
+
+    match get_ghost_by_uuid(&database, ghost_uuid).await {
+        Ok(Some(ghost)) => Ok(Json(ghost)),
+        Ok(None) => {
+            tracing::warn!("Ghost not found for UUID: {}", ghost_uuid);
+            Err(StatusCode::NOT_FOUND)
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch ghost: {:?}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Save a player's finished time trial as a ghost
+/// Failures are logged only; the lap itself has already been processed
+async fn record_ghost(
+    database: &Database,
+    race: &Race,
+    player_uuid: Uuid,
+    car_data: &ValidatedCarData,
+) {
+    let ghost = match Ghost::record(race, player_uuid, car_data) {
+        Ok(ghost) => ghost,
+        Err(e) => {
+            tracing::warn!("Could not record ghost for race {}: {}", race.uuid, e);
+            return;
+        }
+    };
+
+    match database
+        .collection::<Ghost>("ghosts")
+        .insert_one(&ghost, None)
+        .await
+    {
+        Ok(_) => tracing::info!("Recorded ghost {} from race {}", ghost.uuid, race.uuid),
+        Err(e) => tracing::error!("Failed to save ghost for race {}: {:?}", race.uuid, e),
+    }
+}
+
+pub async fn get_ghost_by_uuid(
+    database: &Database,
+    ghost_uuid: Uuid,
+) -> Result<Option<Ghost>, mongodb::error::Error> {
+    database
+        .collection::<Ghost>("ghosts")
+        .find_one(doc! { "uuid": ghost_uuid.to_string() }, None)
+        .await
+}
+
+/// Fast-forward a practice race
+///
+/// Plays the requested number of laps on the player's behalf, choosing boost
+/// cards automatically. Stops early once the player finishes.
+#[utoipa::path(
+    post,
+    path = "/api/v1/races/{race_uuid}/fast-forward",
+    params(
+        ("race_uuid" = String, Path, description = "Race UUID")
+    ),
+    request_body = FastForwardRequest,
+    responses(
+        (status = 200, description = "Laps processed", body = FastForwardResponse),
+        (status = 400, description = "Invalid request or car", body = ErrorResponse),
+        (status = 404, description = "Race not found", body = ErrorResponse),
+        (status = 409, description = "Race is not a practice race in progress", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "races"
+)]
+#[tracing::instrument(
+    name = "Fast-forwarding practice race",
+    skip(database, payload),
+    fields(race_uuid = %race_uuid_str, laps = payload.laps)
+)]
+pub async fn fast_forward_race(
+    State(database): State<Database>,
+    Path(race_uuid_str): Path<String>,
+    Json(payload): Json<FastForwardRequest>,
+) -> Result<Json<FastForwardResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let error = |status: StatusCode, code: &str, message: String| {
+        (
+            status,
+            Json(ErrorResponse {
+                error: code.to_string(),
+                message,
+                details: None,
+            }),
+        )
+    };
+
+    let (Ok(race_uuid), Ok(player_uuid), Ok(car_uuid)) = (
+        Uuid::parse_str(&race_uuid_str),
+        Uuid::parse_str(&payload.player_uuid),
+        Uuid::parse_str(&payload.car_uuid),
+    ) else {
+        tracing::warn!("Invalid UUID in fast-forward request");
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_UUID",
+            "Invalid race, player or car UUID".to_string(),
+        ));
+    };
+
+    if payload.laps == 0 {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_LAPS",
+            "laps must be at least 1".to_string(),
+        ));
+    }
+
+    // Practice races are created without rarity caps
+    let car_data =
+        CarValidationService::validate_car_for_race(&database, player_uuid, car_uuid, &[])
+            .await
+            .map_err(|e| {
+                tracing::warn!("Car validation failed: {}", e);
+                error(
+                    StatusCode::BAD_REQUEST,
+                    "CAR_VALIDATION_FAILED",
+                    format!("Car validation failed: {e}"),
+                )
+            })?;
+
+    let mut race = match get_race_by_uuid(&database, race_uuid).await {
+        Ok(Some(race)) => race,
+        Ok(None) => {
+            tracing::warn!("Race not found for UUID: {}", race_uuid);
+            return Err(error(
+                StatusCode::NOT_FOUND,
+                "RACE_NOT_FOUND",
+                "Race not found".to_string(),
+            ));
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch race: {:?}", e);
+            return Err(error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "DATABASE_ERROR",
+                "Failed to fetch race".to_string(),
+            ));
+        }
+    };
+
+    let before = race.clone();
+    let lap_results = match race.fast_forward(player_uuid, payload.laps, &car_data) {
+        Ok(lap_results) => lap_results,
+        Err(e) if e.contains("not found") => {
+            return Err(error(StatusCode::NOT_FOUND, "PLAYER_NOT_FOUND", e));
+        }
+        Err(e) if e.contains("practice") || e.contains("not in progress") => {
+            return Err(error(StatusCode::CONFLICT, "RACE_STATE_ERROR", e));
+        }
+        Err(e) => {
+            tracing::warn!("Fast-forward failed: {}", e);
+            return Err(error(StatusCode::BAD_REQUEST, "FAST_FORWARD_FAILED", e));
+        }
+    };
+
+    let audit = AuditEntry::new("race.fast_forward").by(player_uuid);
+    match save_race_progress_in_db(
+        &database,
+        audit,
+        Vec::new(),
+        &before,
+        &mut race,
+        &lap_results,
+    )
+    .await
+    {
+        Ok(Some(race)) => {
+            tracing::info!(
+                "Fast-forwarded {} lap(s) in race {}",
+                lap_results.len(),
+                race_uuid
+            );
+            Ok(Json(FastForwardResponse {
+                race: race.as_seen_by(Some(player_uuid), None),
+                lap_results,
+            }))
+        }
+        Ok(None) => Err(error(
+            StatusCode::NOT_FOUND,
+            "RACE_NOT_FOUND",
+            "Race not found".to_string(),
+        )),
+        Err(e) => {
+            tracing::error!("Failed to save fast-forwarded race: {:?}", e);
+            Err(error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "DATABASE_ERROR",
+                "Failed to save race".to_string(),
+            ))
+        }
+    }
+}
+
 /// Start a race
+///
+/// A race with a start countdown enters `Starting` and opens its first lap
+/// once the countdown is over; no actions are accepted until then.
 #[utoipa::path(
     post,
     path = "/api/v1/races/{race_uuid}/start",
@@ -3376,7 +5585,7 @@ pub async fn join_race(
         ("race_uuid" = String, Path, description = "Race UUID")
     ),
     responses(
-        (status = 200, description = "Race started successfully", body = RaceResponse),
+        (status = 200, description = "Race started, or counting down to its first lap", body = RaceResponse),
         (status = 400, description = "Bad request"),
         (status = 404, description = "Race not found"),
         (status = 409, description = "Cannot start race"),
@@ -3401,7 +5610,7 @@ pub async fn start_race(
         Ok(Some(updated_race)) => {
             tracing::info!("Race {} started successfully", race_uuid);
             Ok(Json(RaceResponse {
-                race: updated_race,
+                race: updated_race.as_seen_by(None, None),
                 message: "Race started successfully".to_string(),
             }))
         }
@@ -3504,13 +5713,16 @@ pub async fn process_turn(
     responses(
         (status = 200, description = "Race status", body = RaceStatus),
         (status = 404, description = "Race not found"),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "API token lacks the read:races scope"),
         (status = 500, description = "Internal server error")
     ),
     tag = "races"
 )]
-#[tracing::instrument(name = "Getting race status", skip(database))]
+#[tracing::instrument(name = "Getting race status", skip(database, cache))]
 pub async fn get_race_status(
     State(database): State<Database>,
+    Extension(cache): Extension<RaceCache>,
     Path(race_uuid_str): Path<String>,
 ) -> Result<Json<RaceStatus>, StatusCode> {
     let race_uuid = match Uuid::parse_str(&race_uuid_str) {
@@ -3521,57 +5733,401 @@ pub async fn get_race_status(
         }
     };
 
-    match get_race_by_uuid(&database, race_uuid).await {
-        Ok(Some(race)) => {
-            tracing::info!("Race status retrieved for UUID: {}", race_uuid);
-            Ok(Json(race.status))
-        }
-        Ok(None) => {
-            tracing::warn!("Race not found for UUID: {}", race_uuid);
-            Err(StatusCode::NOT_FOUND)
+    match get_race_cached(&database, &cache, race_uuid).await {
+        Ok(Some(race)) => {
+            tracing::info!("Race status retrieved for UUID: {}", race_uuid);
+            Ok(Json(race.status))
+        }
+        Ok(None) => {
+            tracing::warn!("Race not found for UUID: {}", race_uuid);
+            Err(StatusCode::NOT_FOUND)
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch race status: {:?}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+// Database operations
+#[tracing::instrument(name = "Saving new race in the database", skip(database, race))]
+pub async fn insert_race(database: &Database, race: &Race) -> Result<Race, mongodb::error::Error> {
+    let collection = database.collection::<Race>("races");
+    let result = collection.insert_one(race, None).await?;
+
+    let mut created_race = race.clone();
+    created_race.id = result.inserted_id.as_object_id();
+    Ok(created_race)
+}
+
+#[tracing::instrument(name = "Getting all races from the database", skip(database))]
+pub async fn get_all_races_from_db(
+    database: &Database,
+) -> Result<Vec<Race>, mongodb::error::Error> {
+    let collection = database.collection::<Race>("races");
+    let mut cursor = collection.find(None, None).await?;
+
+    let mut races = Vec::new();
+    while cursor.advance().await? {
+        let race = cursor.deserialize_current()?;
+        races.push(race);
+    }
+
+    Ok(races)
+}
+
+/// Up to `limit + 1` races after `after`, newest first
+#[tracing::instrument(name = "Getting a page of races from the database", skip(database))]
+pub(crate) async fn get_races_page_from_db(
+    database: &Database,
+    status: Option<RaceStatus>,
+    after: Option<TimePosition>,
+    limit: usize,
+) -> Result<Vec<Race>, mongodb::error::Error> {
+    let collection = database.collection::<Race>("races");
+
+    let mut filter = doc! {};
+    if let Some(status) = status {
+        filter.insert("status", to_bson_safe(&status, "status")?);
+    }
+    if let Some(after) = after {
+        let created_at = BsonDateTime::from_millis(after.millis);
+        filter.insert(
+            "$or",
+            vec![
+                doc! { "created_at": { "$lt": created_at } },
+                doc! { "created_at": created_at, "uuid": { "$lt": after.uuid.to_string() } },
+            ],
+        );
+    }
+
+    let options = mongodb::options::FindOptions::builder()
+        .sort(doc! { "created_at": -1, "uuid": -1 })
+        .limit(i64::try_from(limit + 1).unwrap_or(i64::MAX))
+        .build();
+    let mut cursor = collection.find(filter, options).await?;
+
+    let mut races = Vec::new();
+    while cursor.advance().await? {
+        races.push(cursor.deserialize_current()?);
+    }
+    Ok(races)
+}
+
+#[tracing::instrument(name = "Getting race by UUID from the database", skip(database))]
+pub async fn get_race_by_uuid(
+    database: &Database,
+    race_uuid: Uuid,
+) -> Result<Option<Race>, mongodb::error::Error> {
+    let collection = database.collection::<Race>("races");
+    let filter = doc! { "uuid": race_uuid.to_string() };
+    match with_retry("Loading race", || collection.find_one(filter.clone(), None)).await? {
+        Some(race) => Ok(Some(race)),
+        // Old finished races only remain in the archive
+        None => crate::database::find_archived_race(database, race_uuid).await,
+    }
+}
+
+async fn audit_race_creation(database: &Database, audit: AuditEntry, race: &Race) {
+    let audit = audit.race(race.uuid).values(None, Some(race));
+    record_audit(database, audit).await;
+}
+
+/// Cancel waiting races not written to since `idle_since`, at most `batch_size`
+///
+/// A race written to after it was selected is left alone. Each cancellation
+/// is recorded in the race event log. Returns the cancelled races.
+#[tracing::instrument(name = "Cancelling idle races in the database", skip(database))]
+pub async fn cancel_idle_races_in_db(
+    database: &Database,
+    idle_since: BsonDateTime,
+    batch_size: u32,
+) -> Result<Vec<Race>, mongodb::error::Error> {
+    let collection = database.collection::<Race>("races");
+    let waiting = mongodb::bson::to_bson(&RaceStatus::Waiting)?;
+    let filter = doc! { "status": waiting.clone(), "updated_at": { "$lt": idle_since } };
+    let options = mongodb::options::FindOptions::builder()
+        .sort(doc! { "updated_at": 1 })
+        .limit(i64::from(batch_size))
+        .build();
+    let mut cursor = collection.find(filter, options).await?;
+
+    let mut idle_races: Vec<Race> = Vec::new();
+    while cursor.advance().await? {
+        idle_races.push(cursor.deserialize_current()?);
+    }
+
+    let mut cancelled = Vec::new();
+    for before in idle_races {
+        let mut race = before.clone();
+        race.status = RaceStatus::Cancelled;
+        race.updated_at = BsonDateTime::now();
+
+        let result = collection
+            .update_one(
+                doc! {
+                    "uuid": race.uuid.to_string(),
+                    "status": waiting.clone(),
+                    "updated_at": before.updated_at,
+                },
+                doc! { "$set": {
+                    "status": mongodb::bson::to_bson(&race.status)?,
+                    "updated_at": race.updated_at,
+                } },
+                None,
+            )
+            .await?;
+        if result.modified_count == 0 {
+            continue;
+        }
+
+        tracing::info!("Cancelled idle race {}", race.uuid);
+        record_race_changes(
+            database,
+            AuditEntry::new("race.cancel_idle"),
+            &before,
+            &race,
+            &[],
+        )
+        .await;
+        cancelled.push(race);
+    }
+    Ok(cancelled)
+}
+
+/// Warn in the race event log of laps whose deadline falls before `warn_until`
+/// while participants have yet to act, at most `batch_size` races
+///
+/// Each lap is announced once, even when several instances run this job.
+/// Returns the number of warnings recorded.
+#[tracing::instrument(
+    name = "Warning of near turn deadlines in the database",
+    skip(database)
+)]
+pub async fn warn_near_turn_deadlines_in_db(
+    database: &Database,
+    warn_until: BsonDateTime,
+    batch_size: u32,
+) -> Result<u64, mongodb::error::Error> {
+    let collection = database.collection::<Race>("races");
+    let in_progress = mongodb::bson::to_bson(&RaceStatus::InProgress)?;
+    let filter = doc! {
+        "status": in_progress,
+        "turn_deadline_at": { "$lte": warn_until },
+        "$expr": { "$ne": ["$deadline_warning_lap", "$current_lap"] },
+    };
+    let options = mongodb::options::FindOptions::builder()
+        .sort(doc! { "turn_deadline_at": 1 })
+        .limit(i64::from(batch_size))
+        .build();
+    let mut cursor = collection.find(filter, options).await?;
+
+    let mut races: Vec<Race> = Vec::new();
+    while cursor.advance().await? {
+        races.push(cursor.deserialize_current()?);
+    }
+
+    let mut warned = 0;
+    for race in races {
+        if race.get_pending_players().is_empty() {
+            continue;
         }
-        Err(e) => {
-            tracing::error!("Failed to fetch race status: {:?}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        let (Some(deadline_at), Some(seconds_remaining)) =
+            (turn_deadline_at(&race), race.seconds_until_deadline())
+        else {
+            continue;
+        };
+
+        // Whoever records the lap owns the warning
+        let claimed = collection
+            .update_one(
+                doc! {
+                    "uuid": race.uuid.to_string(),
+                    "current_lap": race.current_lap,
+                    "deadline_warning_lap": { "$ne": race.current_lap },
+                },
+                doc! { "$set": { "deadline_warning_lap": race.current_lap } },
+                None,
+            )
+            .await?;
+        if claimed.modified_count == 0 {
+            continue;
         }
+
+        let change = RaceChange::TurnDeadlineNear {
+            lap: race.current_lap,
+            deadline_at,
+            seconds_remaining,
+        };
+        database
+            .collection::<RaceEvent>("race_events")
+            .insert_one(RaceEvent::new(race.uuid, BsonDateTime::now(), change), None)
+            .await?;
+        warned += 1;
     }
+    Ok(warned)
 }
 
-// Database operations
-#[tracing::instrument(name = "Saving new race in the database", skip(database, race))]
-pub async fn insert_race(database: &Database, race: &Race) -> Result<Race, mongodb::error::Error> {
-    let collection = database.collection::<Race>("races");
-    let result = collection.insert_one(race, None).await?;
+/// Append the changes between two states of a race to its event log, the
+/// operation that made them to the audit log and the board after each
+/// processed lap to the race snapshots
+///
+/// Writes that can raise domain events or webhooks push them to the race's
+/// outbox themselves (see [`push_outbox`]), as part of the same update.
+///
+/// The race itself is already saved at this point, so a failure is logged
+/// rather than failing the request; clients missing an entry fall back to
+/// reloading the race.
+async fn record_race_changes(
+    database: &Database,
+    audit: AuditEntry,
+    before: &Race,
+    after: &Race,
+    lap_results: &[LapResult],
+) {
+    let audit = audit.race(after.uuid).values(Some(before), Some(after));
+    record_audit(database, audit).await;
+
+    let mut changes = RaceChange::between(before, after, lap_results);
+    if !lap_results.is_empty() {
+        let names = team_names(database, after).await;
+        changes.extend(commentary::lap_commentary(
+            before,
+            after,
+            lap_results,
+            &names,
+        ));
+    }
 
-    let mut created_race = race.clone();
-    created_race.id = result.inserted_id.as_object_id();
-    Ok(created_race)
+    let occurred_at = BsonDateTime::now();
+    let events: Vec<RaceEvent> = changes
+        .into_iter()
+        .map(|change| RaceEvent::new(after.uuid, occurred_at, change))
+        .collect();
+    if events.is_empty() {
+        return;
+    }
+
+    let collection = database.collection::<RaceEvent>("race_events");
+    if let Err(e) = collection.insert_many(events, None).await {
+        tracing::error!("Failed to record changes of race {}: {:?}", after.uuid, e);
+    }
+    if let Err(e) = crate::database::record_race_snapshots(database, after.uuid, lap_results).await
+    {
+        tracing::error!("Failed to record snapshots of race {}: {:?}", after.uuid, e);
+    }
 }
 
-#[tracing::instrument(name = "Getting all races from the database", skip(database))]
-pub async fn get_all_races_from_db(
-    database: &Database,
-) -> Result<Vec<Race>, mongodb::error::Error> {
-    let collection = database.collection::<Race>("races");
-    let mut cursor = collection.find(None, None).await?;
+/// Resolved laps whose movements a track situation shows
+const RECENT_MOVEMENT_LAPS: u32 = 3;
 
-    let mut races = Vec::new();
+/// Movements of the last `RECENT_MOVEMENT_LAPS` resolved laps with their lap,
+/// latest lap first, read from the race event log
+async fn recent_movements(
+    database: &Database,
+    race: &Race,
+) -> Result<Vec<(u32, crate::domain::ParticipantMovement)>, mongodb::error::Error> {
+    let options = mongodb::options::FindOptions::builder()
+        .sort(doc! { "change.lap": -1, "_id": 1 })
+        .build();
+    let mut cursor = database
+        .collection::<RaceEvent>("race_events")
+        .find(
+            doc! {
+                "race_uuid": race.uuid.to_string(),
+                "change.type": "Movement",
+                "change.lap": { "$gte": race.current_lap.saturating_sub(RECENT_MOVEMENT_LAPS) },
+            },
+            options,
+        )
+        .await?;
+    let mut movements = Vec::new();
     while cursor.advance().await? {
-        let race = cursor.deserialize_current()?;
-        races.push(race);
+        let event: RaceEvent = cursor.deserialize_current()?;
+        if let RaceChange::Movement { lap, movement } = event.change {
+            movements.push((lap, movement));
+        }
     }
+    Ok(movements)
+}
 
-    Ok(races)
+/// Team names of the players in a race, for commentary
+///
+/// Commentary falls back to car names, so a failed lookup is only logged.
+async fn team_names(database: &Database, race: &Race) -> HashMap<Uuid, String> {
+    let player_uuids: Vec<String> = race
+        .participants
+        .iter()
+        .filter(|p| p.bot.is_none() && p.ghost.is_none())
+        .map(|p| p.player_uuid.to_string())
+        .collect();
+    let options = mongodb::options::FindOptions::builder()
+        .projection(doc! { "uuid": 1, "team_name": 1 })
+        .build();
+    let lookup = async {
+        let mut cursor = database
+            .collection::<Document>("players")
+            .find(doc! { "uuid": { "$in": player_uuids } }, options)
+            .await?;
+        let mut names = HashMap::new();
+        while cursor.advance().await? {
+            let player = cursor.deserialize_current()?;
+            if let (Ok(uuid), Ok(team_name)) = (player.get_str("uuid"), player.get_str("team_name"))
+            {
+                if let Ok(uuid) = Uuid::parse_str(uuid) {
+                    names.insert(uuid, team_name.to_string());
+                }
+            }
+        }
+        Ok::<_, mongodb::error::Error>(names)
+    };
+    lookup.await.unwrap_or_else(|e| {
+        tracing::warn!("Failed to load team names of race {}: {:?}", race.uuid, e);
+        HashMap::new()
+    })
 }
 
-#[tracing::instrument(name = "Getting race by UUID from the database", skip(database))]
-pub async fn get_race_by_uuid(
+/// Logged changes of a race after `since`, oldest first, at most `limit`
+pub(crate) async fn get_race_events_since(
     database: &Database,
     race_uuid: Uuid,
+    since: BsonDateTime,
+    limit: usize,
+) -> Result<Vec<RaceEvent>, mongodb::error::Error> {
+    let collection = database.collection::<RaceEvent>("race_events");
+    let filter = doc! {
+        "race_uuid": race_uuid.to_string(),
+        "occurred_at": { "$gt": since },
+    };
+    // Changes recorded together share a timestamp; ids keep their order
+    let options = mongodb::options::FindOptions::builder()
+        .sort(doc! { "occurred_at": 1, "_id": 1 })
+        .limit(i64::try_from(limit).unwrap_or(i64::MAX))
+        .build();
+    let mut cursor = collection.find(filter, options).await?;
+
+    let mut events = Vec::new();
+    while cursor.advance().await? {
+        events.push(cursor.deserialize_current()?);
+    }
+    Ok(events)
+}
+
+/// Race by UUID, served from the cache when possible
+pub async fn get_race_cached(
+    database: &Database,
+    cache: &RaceCache,
+    race_uuid: Uuid,
 ) -> Result<Option<Race>, mongodb::error::Error> {
-    let collection = database.collection::<Race>("races");
-    let filter = doc! { "uuid": race_uuid.to_string() };
-    collection.find_one(filter, None).await
+    if let Some(race) = cache.get(race_uuid).await {
+        return Ok(Some(race));
+    }
+
+    let race = get_race_by_uuid(database, race_uuid).await?;
+    if let Some(race) = &race {
+        cache.put(race).await;
+    }
+    Ok(race)
 }
 
 #[tracing::instrument(name = "Joining race in the database", skip(database))]
@@ -3581,6 +6137,7 @@ pub async fn join_race_in_db(
     player_uuid: Uuid,
     car_uuid: Uuid,
     pilot_uuid: Uuid,
+    car_class: Option<String>,
 ) -> Result<Option<Race>, mongodb::error::Error> {
     let collection = database.collection::<Race>("races");
 
@@ -3589,10 +6146,15 @@ pub async fn join_race_in_db(
         return Ok(None);
     };
 
+    let before = race.clone();
+
     // Try to add participant
     if let Err(e) = race.add_participant(player_uuid, car_uuid, pilot_uuid) {
         return Err(mongodb::error::Error::custom(e));
     }
+    if let Some(participant) = race.participants.last_mut() {
+        participant.car_class = car_class;
+    }
 
     // Update the race in database
     let filter = doc! { "uuid": race_uuid.to_string() };
@@ -3606,7 +6168,41 @@ pub async fn join_race_in_db(
         }
     };
 
-    collection.find_one_and_update(filter, update, None).await
+    let result = collection.find_one_and_update(filter, update, None).await?;
+    let audit = AuditEntry::new("race.join").by(player_uuid);
+    record_race_changes(database, audit, &before, &race, &[]).await;
+    Ok(result)
+}
+
+#[tracing::instrument(name = "Adding bot to race in the database", skip(database))]
+pub async fn add_bot_in_db(
+    database: &Database,
+    race_uuid: Uuid,
+    profile: BotProfile,
+) -> Result<Option<Race>, mongodb::error::Error> {
+    let collection = database.collection::<Race>("races");
+
+    let Some(mut race) = get_race_by_uuid(database, race_uuid).await? else {
+        return Ok(None);
+    };
+
+    let before = race.clone();
+    if let Err(e) = race.add_bot(profile) {
+        return Err(mongodb::error::Error::custom(e));
+    }
+
+    let filter = doc! { "uuid": race_uuid.to_string() };
+    let update = doc! {
+        "$set": {
+            "participants": to_bson_safe(&race.participants, "participants")?,
+            "updated_at": BsonDateTime::now()
+        }
+    };
+
+    let result = collection.find_one_and_update(filter, update, None).await?;
+    let audit = AuditEntry::new("race.add_bot");
+    record_race_changes(database, audit, &before, &race, &[]).await;
+    Ok(result)
 }
 
 #[tracing::instrument(name = "Starting race in the database", skip(database))]
@@ -3646,14 +6242,55 @@ pub async fn start_race_in_db(
         race.participants.len()
     );
 
+    // With a countdown, the first lap opens once `finish_countdowns_in_db` sees it over
+    if race.start_countdown().is_some() {
+        let before = race.clone();
+        race.start_race().map_err(mongodb::error::Error::custom)?;
+        let filter = doc! { "uuid": race_uuid.to_string(), "status": "Waiting" };
+        let update = doc! {
+            "$set": {
+                "status": to_bson_safe(&race.status, "status")?,
+                "starts_at": race.starts_at,
+                "updated_at": race.updated_at
+            }
+        };
+        let result = collection
+            .find_one_and_update(filter, update, return_updated())
+            .await?;
+        if result.is_some() {
+            let audit = AuditEntry::new("race.start_countdown");
+            record_race_changes(database, audit, &before, &race, &[]).await;
+        }
+        return Ok(result);
+    }
+
+    launch_race_in_db(database, &race, AuditEntry::new("race.start")).await
+}
+
+/// Open the first lap of a race that is starting, placing its cars on the grid
+///
+/// Only updates the race while it is still in the status of `before`, so two
+/// callers cannot both start it. Returns the started race.
+async fn launch_race_in_db(
+    database: &Database,
+    before: &Race,
+    audit: AuditEntry,
+) -> Result<Option<Race>, mongodb::error::Error> {
+    let collection = database.collection::<Race>("races");
+    let mut race = before.clone();
+
     // Update race status and initialize lap characteristic
     race.status = RaceStatus::InProgress;
+    race.starts_at = None;
     race.lap_characteristic = LapCharacteristic::Straight; // Start with straight characteristic
     race.current_lap = 1;
+    race.open_turn();
 
-    // Sort participants in their starting sectors (simple position assignment)
-    for (index, participant) in race.participants.iter_mut().enumerate() {
-        participant.current_position_in_sector = index as u32 + 1;
+    race.line_up(&car_ratings(database, &race).await);
+    if let Some(ballast) = race.rules.success_ballast {
+        race.apply_ballast(&recent_wins(database, &race, ballast.recent_races).await?);
+    }
+    for participant in &race.participants {
         tracing::debug!(
             "Participant {} positioned at sector {} position {}",
             participant.player_uuid,
@@ -3663,29 +6300,204 @@ pub async fn start_race_in_db(
     }
 
     // Update the race in database - only update essential fields
-    let filter = doc! { "uuid": race_uuid.to_string() };
-    let update = doc! {
+    let filter = doc! {
+        "uuid": race.uuid.to_string(),
+        "status": to_bson_safe(&before.status, "status")?,
+    };
+    let mut update = doc! {
         "$set": {
             "status": "InProgress",
             "current_lap": race.current_lap,
             "lap_characteristic": "Straight",
+            "participants": to_bson_safe(&race.participants, "participants")?,
+            "turn_deadline_at": race.turn_deadline_at,
+            "starts_at": race.starts_at,
             "updated_at": BsonDateTime::now()
         }
     };
+    push_outbox(&mut update, &OutboxMessage::between(before, &race, &[]))?;
 
-    tracing::info!("Updating race {} in database", race_uuid);
-    match collection.find_one_and_update(filter, update, None).await {
+    tracing::info!("Updating race {} in database", race.uuid);
+    match collection
+        .find_one_and_update(filter, update, return_updated())
+        .await
+    {
         Ok(result) => {
-            tracing::info!("Successfully started race {}", race_uuid);
+            if result.is_some() {
+                tracing::info!("Successfully started race {}", race.uuid);
+                record_race_changes(database, audit, before, &race, &[]).await;
+            }
             Ok(result)
         }
         Err(e) => {
-            tracing::error!("Failed to update race {} in database: {:?}", race_uuid, e);
+            tracing::error!("Failed to update race {} in database: {:?}", race.uuid, e);
             Err(e)
         }
     }
 }
 
+/// Performance ratings of the players' cars, for lining up the starting grid
+///
+/// Cars that no longer validate are rated zero, so they start at the far end
+/// of the grid the race rules favour.
+async fn car_ratings(database: &Database, race: &Race) -> HashMap<Uuid, u32> {
+    let mut ratings = HashMap::new();
+    for participant in race
+        .participants
+        .iter()
+        .filter(|p| p.bot.is_none() && p.ghost.is_none())
+    {
+        match CarValidationService::validate_car_for_race(
+            database,
+            participant.player_uuid,
+            participant.car_uuid,
+            &race.rules.allowed_rarities,
+        )
+        .await
+        {
+            Ok(car_data) => {
+                ratings.insert(participant.player_uuid, car_data.performance_rating());
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Could not rate car {} of player {} for race {}: {}",
+                    participant.car_uuid,
+                    participant.player_uuid,
+                    race.uuid,
+                    e
+                );
+            }
+        }
+    }
+    ratings
+}
+
+/// How many of their latest `recent_races` finished races each player of a
+/// race won, for the success ballast
+async fn recent_wins(
+    database: &Database,
+    race: &Race,
+    recent_races: u32,
+) -> Result<HashMap<Uuid, u32>, mongodb::error::Error> {
+    let collection = database.collection::<Race>("races");
+    let mut wins = HashMap::new();
+    if recent_races == 0 {
+        return Ok(wins);
+    }
+
+    for participant in race
+        .participants
+        .iter()
+        .filter(|p| p.bot.is_none() && p.ghost.is_none())
+    {
+        let filter = doc! {
+            "participants.player_uuid": participant.player_uuid.to_string(),
+            "status": "Finished",
+        };
+        let options = mongodb::options::FindOptions::builder()
+            .sort(doc! { "updated_at": -1 })
+            .limit(i64::from(recent_races))
+            .build();
+        let mut cursor = collection.find(filter, options).await?;
+
+        let mut won = 0;
+        while cursor.advance().await? {
+            let finished: Race = cursor.deserialize_current()?;
+            if finished
+                .participants
+                .iter()
+                .any(|p| p.player_uuid == participant.player_uuid && p.finish_position == Some(1))
+            {
+                won += 1;
+            }
+        }
+        wins.insert(participant.player_uuid, won);
+    }
+    Ok(wins)
+}
+
+/// Options returning the document as it is after the update
+fn return_updated() -> mongodb::options::FindOneAndUpdateOptions {
+    mongodb::options::FindOneAndUpdateOptions::builder()
+        .return_document(mongodb::options::ReturnDocument::After)
+        .build()
+}
+
+/// Count down the races that are starting, at most `batch_size`
+///
+/// Races whose countdown is over get their first lap opened; the others get a
+/// `CountdownTick` in the race event log, at most one per remaining second
+/// even when several instances run this job. Returns the number of races
+/// started or ticked.
+#[tracing::instrument(name = "Counting down starting races in the database", skip(database))]
+pub async fn finish_countdowns_in_db(
+    database: &Database,
+    batch_size: u32,
+) -> Result<u64, mongodb::error::Error> {
+    let collection = database.collection::<Race>("races");
+    let starting = mongodb::bson::to_bson(&RaceStatus::Starting)?;
+    let options = mongodb::options::FindOptions::builder()
+        .sort(doc! { "starts_at": 1 })
+        .limit(i64::from(batch_size))
+        .build();
+    let mut cursor = collection
+        .find(doc! { "status": starting }, options)
+        .await?;
+
+    let mut races: Vec<Race> = Vec::new();
+    while cursor.advance().await? {
+        races.push(cursor.deserialize_current()?);
+    }
+
+    let mut handled = 0;
+    for race in races {
+        let Some(seconds_remaining) = race.seconds_until_start() else {
+            continue;
+        };
+        if seconds_remaining == 0 {
+            let audit = AuditEntry::new("race.start");
+            if launch_race_in_db(database, &race, audit).await?.is_some() {
+                handled += 1;
+            }
+            continue;
+        }
+
+        // Whoever records the second owns the tick
+        let tick = mongodb::bson::to_bson(&seconds_remaining)?;
+        let claimed = collection
+            .update_one(
+                doc! {
+                    "uuid": race.uuid.to_string(),
+                    "status": "Starting",
+                    "last_countdown_tick": { "$ne": tick.clone() },
+                },
+                doc! { "$set": { "last_countdown_tick": tick } },
+                None,
+            )
+            .await?;
+        if claimed.modified_count == 0 {
+            continue;
+        }
+
+        let Some(starts_at) = race
+            .starts_at
+            .and_then(|starts_at| DateTime::from_timestamp_millis(starts_at.timestamp_millis()))
+        else {
+            continue;
+        };
+        let change = RaceChange::CountdownTick {
+            starts_at,
+            seconds_remaining,
+        };
+        database
+            .collection::<RaceEvent>("race_events")
+            .insert_one(RaceEvent::new(race.uuid, BsonDateTime::now(), change), None)
+            .await?;
+        handled += 1;
+    }
+    Ok(handled)
+}
+
 #[tracing::instrument(name = "Processing turn in the database", skip(database, actions))]
 pub async fn process_lap_in_db(
     database: &Database,
@@ -3699,6 +6511,8 @@ pub async fn process_lap_in_db(
         return Ok(None);
     };
 
+    let before = race.clone();
+
     // Create placeholder performance calculations for manual processing
     let mut performance_calculations = HashMap::new();
     for action in &actions {
@@ -3707,11 +6521,13 @@ pub async fn process_lap_in_db(
             engine_contribution: 5,
             body_contribution: 3,
             pilot_contribution: 2,
+            ballast: 0,
             base_value: 10,
             sector_ceiling: 30, // Default ceiling
             capped_base_value: 10,
             boost_value: action.boost_value,
-            final_value: 10 + action.boost_value,
+            catch_up_bonus: 0,
+            final_value: race.performance_model().apply_boost(10, action.boost_value),
         };
         performance_calculations.insert(action.player_uuid, performance);
     }
@@ -3726,23 +6542,36 @@ pub async fn process_lap_in_db(
     race.pending_actions.clear();
     race.action_submissions.clear();
     race.pending_performance_calculations.clear();
+    race.boost_commitments.clear();
+
+    let lap_results = std::slice::from_ref(&lap_result);
+    award_finished_race(database, &before, &mut race, lap_results).await?;
 
     // Update the race in database
     let filter = doc! { "uuid": race_uuid.to_string() };
-    let update = doc! {
+    let mut update = doc! {
         "$set": {
             "participants": to_bson_safe(&race.participants, "participants")?,
+            "awards": to_bson_safe(&race.awards, "awards")?,
             "current_lap": race.current_lap,
             "lap_characteristic": to_bson_safe(&race.lap_characteristic, "lap_characteristic")?,
             "status": to_bson_safe(&race.status, "status")?,
             "pending_actions": to_bson_safe(&race.pending_actions, "pending_actions")?,
             "action_submissions": to_bson_safe(&race.action_submissions, "action_submissions")?,
             "pending_performance_calculations": to_bson_safe(&race.pending_performance_calculations, "pending_performance_calculations")?,
+            "boost_commitments": to_bson_safe(&race.boost_commitments, "boost_commitments")?,
+            "turn_deadline_at": race.turn_deadline_at,
             "updated_at": BsonDateTime::now()
         }
     };
+    push_outbox(
+        &mut update,
+        &OutboxMessage::between(&before, &race, lap_results),
+    )?;
 
     collection.find_one_and_update(filter, update, None).await?;
+    let audit = AuditEntry::new("race.process_lap");
+    record_race_changes(database, audit, &before, &race, lap_results).await;
 
     tracing::info!(
         "Turn processing completed for race {}. Ready for next turn.",
@@ -3752,6 +6581,154 @@ pub async fn process_lap_in_db(
     Ok(Some((lap_result, race.status)))
 }
 
+/// Commit to a boost for the current lap, in a race using commit–reveal
+///
+/// The player the access token belongs to sends a hash of their boost and a
+/// secret salt, see
+/// [`boost_commitment`](crate::domain::boost_commitment). Once every active
+/// player has committed, each reveals their boost and salt through
+/// `apply-lap` or `submit-action`, and reveals that do not match the
+/// commitment are refused.
+#[utoipa::path(
+    post,
+    path = "/api/v1/races/{race_uuid}/commit-boost",
+    params(
+        ("race_uuid" = String, Path, description = "Race UUID")
+    ),
+    request_body = CommitBoostRequest,
+    responses(
+        (status = 200, description = "Commitment recorded", body = CommitBoostResponse),
+        (status = 400, description = "Invalid UUID or commitment", body = ErrorResponse),
+        (status = 401, description = "Authentication required"),
+        (status = 404, description = "Race not found or player not in it", body = ErrorResponse),
+        (
+            status = 409,
+            description = "Race does not use commit-reveal, is not in progress, or the player already committed",
+            body = ErrorResponse
+        ),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "races"
+)]
+#[tracing::instrument(
+    name = "Committing to a boost",
+    skip(database, user, payload),
+    fields(race_uuid = %race_uuid_str, player_uuid = %user.user_uuid)
+)]
+pub async fn commit_boost(
+    State(database): State<Database>,
+    Extension(user): Extension<UserContext>,
+    Path(race_uuid_str): Path<String>,
+    Json(payload): Json<CommitBoostRequest>,
+) -> Result<Json<CommitBoostResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let error = |status: StatusCode, code: &str, message: String| {
+        (
+            status,
+            Json(ErrorResponse {
+                error: code.to_string(),
+                message,
+                details: None,
+            }),
+        )
+    };
+
+    let player_uuid = user.user_uuid;
+    let Ok(race_uuid) = Uuid::parse_str(&race_uuid_str) else {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_UUID",
+            "Invalid race UUID".to_string(),
+        ));
+    };
+
+    let database_error = |e: mongodb::error::Error| {
+        tracing::error!("Failed to commit boost: {:?}", e);
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "DATABASE_ERROR",
+            "Failed to commit boost".to_string(),
+        )
+    };
+
+    let mut race = get_race_by_uuid(&database, race_uuid)
+        .await
+        .map_err(database_error)?
+        .ok_or_else(|| {
+            error(
+                StatusCode::NOT_FOUND,
+                "RACE_NOT_FOUND",
+                "Race not found".to_string(),
+            )
+        })?;
+
+    if let Err(e) = race.commit_boost(player_uuid, &payload.commitment) {
+        tracing::warn!("Boost commitment rejected: {}", e);
+        let (status, code) = match e {
+            BoostCommitError::NotParticipant => (StatusCode::NOT_FOUND, "PLAYER_NOT_FOUND"),
+            BoostCommitError::InvalidCommitment => (StatusCode::BAD_REQUEST, "INVALID_COMMITMENT"),
+            BoostCommitError::NotCommitReveal
+            | BoostCommitError::NotInProgress
+            | BoostCommitError::AlreadyFinished
+            | BoostCommitError::AlreadyCommitted => (StatusCode::CONFLICT, "RACE_STATE_ERROR"),
+        };
+        return Err(error(status, code, e.to_string()));
+    }
+    let commitment = &race.boost_commitments[&player_uuid];
+
+    // Only record the commitment if the lap has not moved on and no other
+    // request committed for the player in the meantime
+    let field = format!("boost_commitments.{player_uuid}");
+    let recorded = database
+        .collection::<Race>("races")
+        .update_one(
+            doc! {
+                "uuid": race_uuid.to_string(),
+                "status": to_bson_safe(&RaceStatus::InProgress, "status").map_err(database_error)?,
+                "current_lap": race.current_lap,
+                field.clone(): { "$exists": false },
+            },
+            doc! {
+                "$set": {
+                    field: commitment,
+                    "updated_at": BsonDateTime::now()
+                }
+            },
+            None,
+        )
+        .await
+        .map_err(database_error)?;
+    if recorded.matched_count == 0 {
+        return Err(error(
+            StatusCode::CONFLICT,
+            "RACE_STATE_ERROR",
+            "The lap moved on or the player already committed".to_string(),
+        ));
+    }
+
+    let audit = AuditEntry::new("race.commit_boost")
+        .by(player_uuid)
+        .race(race_uuid);
+    record_audit(&database, audit).await;
+
+    let awaiting_players: Vec<String> = race
+        .awaiting_commitments()
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    tracing::info!(
+        "Player {} committed to a boost in race {}, {} still to commit",
+        player_uuid,
+        race_uuid,
+        awaiting_players.len()
+    );
+    Ok(Json(CommitBoostResponse {
+        success: true,
+        message: "Commitment recorded".to_string(),
+        reveals_open: awaiting_players.is_empty(),
+        awaiting_players,
+    }))
+}
+
 /// Submit a single player's turn action (boost selection)
 ///
 /// This endpoint allows individual players to submit their boost selection for the current turn.
@@ -3799,7 +6776,15 @@ pub async fn submit_turn_action(
         return Err(StatusCode::BAD_REQUEST);
     }
 
-    match submit_player_action_in_db(&database, race_uuid, player_uuid, payload.boost_value).await {
+    match submit_player_action_in_db(
+        &database,
+        race_uuid,
+        player_uuid,
+        payload.boost_value,
+        payload.salt.as_deref(),
+    )
+    .await
+    {
         Ok(Some(response)) => {
             tracing::info!(
                 "Action submitted successfully for player {} in race {}",
@@ -3816,8 +6801,11 @@ pub async fn submit_turn_action(
             tracing::error!("Failed to submit action: {:?}", e);
             if e.to_string().contains("not found") {
                 Err(StatusCode::NOT_FOUND)
+            } else if e.to_string().contains("does not match") || e.to_string().contains("salt") {
+                Err(StatusCode::BAD_REQUEST)
             } else if e.to_string().contains("already submitted")
                 || e.to_string().contains("not in progress")
+                || e.to_string().contains("committed")
             {
                 Err(StatusCode::CONFLICT)
             } else {
@@ -3833,6 +6821,7 @@ async fn submit_player_action_in_db(
     race_uuid: Uuid,
     player_uuid: Uuid,
     boost_value: u32,
+    salt: Option<&str>,
 ) -> Result<Option<SubmitTurnActionResponse>, mongodb::error::Error> {
     let collection = database.collection::<Race>("races");
 
@@ -3885,6 +6874,10 @@ async fn submit_player_action_in_db(
         )));
     }
 
+    // Under commit–reveal, the action must reveal the committed boost
+    race.check_reveal(player_uuid, boost_value, salt)
+        .map_err(mongodb::error::Error::custom)?;
+
     // Create the lap action
     let lap_action = LapAction {
         player_uuid,
@@ -3892,18 +6885,33 @@ async fn submit_player_action_in_db(
     };
 
     // Add the action to pending_actions in memory
+    let before = race.clone();
     race.pending_actions.push(lap_action);
 
     // Update the race in database
     let filter = doc! { "uuid": race_uuid.to_string() };
-    let update = doc! {
+    let mut update = doc! {
         "$set": {
             "pending_actions": to_bson_safe(&race.pending_actions, "pending_actions")?,
             "updated_at": BsonDateTime::now()
         }
     };
+    let submitted = DomainEvent::ActionSubmitted {
+        race_uuid,
+        player_uuid,
+        lap: race.current_lap,
+        boost_value,
+    };
+    push_outbox(
+        &mut update,
+        &[OutboxMessage::new(OutboxPayload::Event(submitted))],
+    )?;
 
     collection.update_one(filter, update, None).await?;
+    let audit = AuditEntry::new("race.submit_action")
+        .by(player_uuid)
+        .race(race_uuid);
+    record_audit(database, audit.values(Some(&before), Some(&race))).await;
 
     // Calculate response data
     let players_submitted = race.pending_actions.len() as u32;
@@ -3947,8 +6955,8 @@ async fn submit_player_action_in_db(
                 return Ok(Some(SubmitTurnActionResponse {
                     success: true,
                     message: "Turn processed successfully. Ready for next turn.".to_string(),
-                    turn_phase: "TurnProcessed".to_string(), // Clear signal that turn completed
-                    players_submitted: 0,                    // Reset counter for next turn
+                    turn_phase: TurnPhase::TurnProcessed, // Clear signal that turn completed
+                    players_submitted: 0,                 // Reset counter for next turn
                     total_players,
                 }));
             }
@@ -3971,7 +6979,7 @@ async fn submit_player_action_in_db(
     Ok(Some(SubmitTurnActionResponse {
         success: true,
         message: "Action submitted successfully".to_string(),
-        turn_phase: "WaitingForPlayers".to_string(),
+        turn_phase: TurnPhase::WaitingForPlayers,
         players_submitted,
         total_players,
     }))
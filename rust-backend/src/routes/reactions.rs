@@ -0,0 +1,193 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+    routing::post,
+    Router,
+};
+use mongodb::{
+    bson::{doc, DateTime as BsonDateTime, Document},
+    Database,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::database::is_duplicate_key;
+use crate::domain::{Emote, Race, RaceChange, RaceEvent};
+use crate::routes::races::ErrorResponse;
+use crate::services::outbox::{push_outbox, OutboxPayload};
+use crate::services::{DomainEvent, OutboxMessage};
+
+type ApiError = (StatusCode, Json<ErrorResponse>);
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SendReactionRequest {
+    /// Participant reacting
+    pub player_uuid: String,
+    pub emote: Emote,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReactionResponse {
+    pub player_uuid: String,
+    /// Lap the reaction is about
+    pub lap: u32,
+    pub emote: Emote,
+}
+
+pub fn routes() -> Router<Database> {
+    Router::new().route("/races/:race_uuid/reactions", post(send_reaction))
+}
+
+fn error(status: StatusCode, error: &str, message: &str) -> ApiError {
+    (
+        status,
+        Json(ErrorResponse {
+            error: error.to_string(),
+            message: message.to_string(),
+            details: None,
+        }),
+    )
+}
+
+fn database_error(e: &mongodb::error::Error) -> ApiError {
+    tracing::error!("Reaction storage failed: {:?}", e);
+    error(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "DATABASE_ERROR",
+        "Internal server error",
+    )
+}
+
+/// React to the lap just resolved with a predefined emote
+///
+/// Each participant gets one reaction per lap. Reactions are recorded in the
+/// race event log, so spectators following `/changes` and replays see them,
+/// and published on the event bus as `reaction_sent`.
+#[utoipa::path(
+    post,
+    path = "/api/v1/races/{race_uuid}/reactions",
+    params(
+        ("race_uuid" = String, Path, description = "Race UUID")
+    ),
+    request_body = SendReactionRequest,
+    responses(
+        (status = 201, description = "Reaction sent", body = ReactionResponse),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse),
+        (status = 403, description = "Player is not a participant", body = ErrorResponse),
+        (status = 404, description = "Race not found or archived", body = ErrorResponse),
+        (status = 409, description = "No lap resolved yet, or already reacted to it", body = ErrorResponse),
+        (status = 422, description = "Unknown emote"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "races"
+)]
+#[tracing::instrument(name = "Sending race reaction", skip(database, request))]
+pub async fn send_reaction(
+    State(database): State<Database>,
+    Path(race_uuid): Path<String>,
+    Json(request): Json<SendReactionRequest>,
+) -> Result<(StatusCode, Json<ReactionResponse>), ApiError> {
+    let (Ok(race_uuid), Ok(player_uuid)) = (
+        Uuid::parse_str(&race_uuid),
+        Uuid::parse_str(&request.player_uuid),
+    ) else {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_UUID",
+            "Invalid UUID format",
+        ));
+    };
+
+    // Archived races keep their event log in a blob that no longer grows
+    let Some(race) = database
+        .collection::<Race>("races")
+        .find_one(doc! { "uuid": race_uuid.to_string() }, None)
+        .await
+        .map_err(|e| database_error(&e))?
+    else {
+        return Err(error(
+            StatusCode::NOT_FOUND,
+            "RACE_NOT_FOUND",
+            "Race not found",
+        ));
+    };
+    if !race
+        .participants
+        .iter()
+        .any(|p| p.player_uuid == player_uuid)
+    {
+        return Err(error(
+            StatusCode::FORBIDDEN,
+            "NOT_PARTICIPANT",
+            "Only participants can react",
+        ));
+    }
+    let Some(lap) = race.last_resolved_lap() else {
+        return Err(error(
+            StatusCode::CONFLICT,
+            "NO_RESOLVED_LAP",
+            "Reactions open once the first lap is resolved",
+        ));
+    };
+
+    let change = RaceChange::Reaction {
+        lap,
+        player_uuid,
+        emote: request.emote,
+    };
+    let inserted = database
+        .collection::<RaceEvent>("race_events")
+        .insert_one(RaceEvent::new(race_uuid, BsonDateTime::now(), change), None)
+        .await;
+    match inserted {
+        Ok(_) => {}
+        // A unique index allows one reaction per participant and lap
+        Err(e) if is_duplicate_key(&e) => {
+            return Err(error(
+                StatusCode::CONFLICT,
+                "ALREADY_REACTED",
+                "You already reacted to this lap",
+            ));
+        }
+        Err(e) => return Err(database_error(&e)),
+    }
+
+    let event = DomainEvent::ReactionSent {
+        race_uuid,
+        player_uuid,
+        lap,
+        emote: request.emote,
+    };
+    if let Err(e) = publish(&database, race_uuid, event).await {
+        tracing::warn!("Reaction in race {} not published: {:?}", race_uuid, e);
+    }
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ReactionResponse {
+            player_uuid: player_uuid.to_string(),
+            lap,
+            emote: request.emote,
+        }),
+    ))
+}
+
+/// Queue the event in the race outbox, from where the relay publishes it
+async fn publish(
+    database: &Database,
+    race_uuid: Uuid,
+    event: DomainEvent,
+) -> Result<(), mongodb::error::Error> {
+    let mut update = Document::new();
+    push_outbox(
+        &mut update,
+        &[OutboxMessage::new(OutboxPayload::Event(event))],
+    )?;
+    database
+        .collection::<Document>("races")
+        .update_one(doc! { "uuid": race_uuid.to_string() }, update, None)
+        .await?;
+    Ok(())
+}
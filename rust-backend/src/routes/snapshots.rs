@@ -0,0 +1,130 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+    routing::get,
+    Router,
+};
+use mongodb::Database;
+use uuid::Uuid;
+
+use crate::database::{find_race_snapshot, find_race_snapshots};
+use crate::domain::{Race, RaceSnapshot};
+use crate::routes::races::{get_race_by_uuid, ErrorResponse};
+
+type ApiError = (StatusCode, Json<ErrorResponse>);
+
+pub fn routes() -> Router<Database> {
+    Router::new()
+        .route("/races/:race_uuid/snapshots", get(get_race_snapshots))
+        .route("/races/:race_uuid/snapshots/:lap", get(get_race_snapshot))
+}
+
+fn error(status: StatusCode, error: &str, message: &str) -> ApiError {
+    (
+        status,
+        Json(ErrorResponse {
+            error: error.to_string(),
+            message: message.to_string(),
+            details: None,
+        }),
+    )
+}
+
+fn database_error(e: &mongodb::error::Error) -> ApiError {
+    tracing::error!("Snapshot lookup failed: {:?}", e);
+    error(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "DATABASE_ERROR",
+        "Internal server error",
+    )
+}
+
+/// Race whose snapshots may be shown
+///
+/// Snapshots show every car, so they stay hidden while fog of war is on.
+async fn viewable_race(database: &Database, race_uuid: &str) -> Result<Race, ApiError> {
+    let race_uuid = Uuid::parse_str(race_uuid).map_err(|_| {
+        error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_UUID",
+            "Invalid UUID format",
+        )
+    })?;
+    let race = get_race_by_uuid(database, race_uuid)
+        .await
+        .map_err(|e| database_error(&e))?
+        .ok_or_else(|| error(StatusCode::NOT_FOUND, "RACE_NOT_FOUND", "Race not found"))?;
+    if race.fog_of_war_active() {
+        return Err(error(
+            StatusCode::FORBIDDEN,
+            "FOG_OF_WAR",
+            "Snapshots are hidden until the race ends",
+        ));
+    }
+    Ok(race)
+}
+
+/// Board of a race after each processed lap, for replays
+#[utoipa::path(
+    get,
+    path = "/api/v1/races/{race_uuid}/snapshots",
+    params(
+        ("race_uuid" = String, Path, description = "Race UUID")
+    ),
+    responses(
+        (status = 200, description = "Snapshots, first lap first", body = Vec<RaceSnapshot>),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse),
+        (status = 403, description = "Fog of war is on", body = ErrorResponse),
+        (status = 404, description = "Race not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "races"
+)]
+#[tracing::instrument(name = "Listing race snapshots", skip(database))]
+pub async fn get_race_snapshots(
+    State(database): State<Database>,
+    Path(race_uuid): Path<String>,
+) -> Result<Json<Vec<RaceSnapshot>>, ApiError> {
+    let race = viewable_race(&database, &race_uuid).await?;
+    find_race_snapshots(&database, race.uuid)
+        .await
+        .map(Json)
+        .map_err(|e| database_error(&e))
+}
+
+/// Board of a race right after lap `lap`, to rewind to it
+#[utoipa::path(
+    get,
+    path = "/api/v1/races/{race_uuid}/snapshots/{lap}",
+    params(
+        ("race_uuid" = String, Path, description = "Race UUID"),
+        ("lap" = u32, Path, description = "Processed lap")
+    ),
+    responses(
+        (status = 200, description = "Snapshot of the lap", body = RaceSnapshot),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse),
+        (status = 403, description = "Fog of war is on", body = ErrorResponse),
+        (status = 404, description = "Race not found or lap not processed", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "races"
+)]
+#[tracing::instrument(name = "Getting race snapshot", skip(database))]
+pub async fn get_race_snapshot(
+    State(database): State<Database>,
+    Path((race_uuid, lap)): Path<(String, u32)>,
+) -> Result<Json<RaceSnapshot>, ApiError> {
+    let race = viewable_race(&database, &race_uuid).await?;
+    find_race_snapshot(&database, race.uuid, lap)
+        .await
+        .map_err(|e| database_error(&e))?
+        .map(Json)
+        .ok_or_else(|| {
+            error(
+                StatusCode::NOT_FOUND,
+                "SNAPSHOT_NOT_FOUND",
+                "No snapshot of this lap",
+            )
+        })
+}
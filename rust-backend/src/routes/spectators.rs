@@ -0,0 +1,158 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+    routing::post,
+    Router,
+};
+use mongodb::{
+    bson::{doc, DateTime as BsonDateTime, Document},
+    options::UpdateOptions,
+    Database,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::routes::races::ErrorResponse;
+
+type ApiError = (StatusCode, Json<ErrorResponse>);
+
+/// Collection holding the last heartbeat of every spectator
+pub const SPECTATORS_COLLECTION: &str = "race_spectators";
+
+/// A spectator stops counting this long after their last heartbeat
+pub const SPECTATOR_TIMEOUT_SECONDS: i64 = 30;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SpectatorHeartbeatRequest {
+    /// Identifier the client generates once per viewer and keeps sending
+    pub viewer_id: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SpectatorCountResponse {
+    pub race_uuid: String,
+    /// Viewers with a heartbeat in the last `SPECTATOR_TIMEOUT_SECONDS`
+    pub spectator_count: u64,
+}
+
+pub fn routes() -> Router<Database> {
+    Router::new().route(
+        "/races/:race_uuid/spectators/heartbeat",
+        post(spectator_heartbeat),
+    )
+}
+
+fn error(status: StatusCode, error: &str, message: &str) -> ApiError {
+    (
+        status,
+        Json(ErrorResponse {
+            error: error.to_string(),
+            message: message.to_string(),
+            details: None,
+        }),
+    )
+}
+
+fn database_error(e: &mongodb::error::Error) -> ApiError {
+    tracing::error!("Spectator tracking failed: {:?}", e);
+    error(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "DATABASE_ERROR",
+        "Internal server error",
+    )
+}
+
+/// Report that a viewer is watching a race
+///
+/// Clients watching a race send a heartbeat at least every
+/// `SPECTATOR_TIMEOUT_SECONDS` (30); viewers that stop doing so drop out of
+/// the count. The count is also returned in the race metadata.
+#[utoipa::path(
+    post,
+    path = "/api/v1/races/{race_uuid}/spectators/heartbeat",
+    params(
+        ("race_uuid" = String, Path, description = "Race UUID")
+    ),
+    request_body = SpectatorHeartbeatRequest,
+    responses(
+        (status = 200, description = "Heartbeat recorded", body = SpectatorCountResponse),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse),
+        (status = 404, description = "Race not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "races"
+)]
+#[tracing::instrument(name = "Recording spectator heartbeat", skip(database, request))]
+pub async fn spectator_heartbeat(
+    State(database): State<Database>,
+    Path(race_uuid): Path<String>,
+    Json(request): Json<SpectatorHeartbeatRequest>,
+) -> Result<Json<SpectatorCountResponse>, ApiError> {
+    let (Ok(race_uuid), Ok(viewer_id)) = (
+        Uuid::parse_str(&race_uuid),
+        Uuid::parse_str(&request.viewer_id),
+    ) else {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_UUID",
+            "Invalid UUID format",
+        ));
+    };
+
+    let race_exists = database
+        .collection::<Document>("races")
+        .count_documents(doc! { "uuid": race_uuid.to_string() }, None)
+        .await
+        .map_err(|e| database_error(&e))?
+        > 0;
+    if !race_exists {
+        return Err(error(
+            StatusCode::NOT_FOUND,
+            "RACE_NOT_FOUND",
+            "Race not found",
+        ));
+    }
+
+    database
+        .collection::<Document>(SPECTATORS_COLLECTION)
+        .update_one(
+            doc! {
+                "race_uuid": race_uuid.to_string(),
+                "viewer_id": viewer_id.to_string(),
+            },
+            doc! { "$set": { "last_seen_at": BsonDateTime::now() } },
+            UpdateOptions::builder().upsert(true).build(),
+        )
+        .await
+        .map_err(|e| database_error(&e))?;
+
+    let spectator_count = count_spectators(&database, race_uuid)
+        .await
+        .map_err(|e| database_error(&e))?;
+    Ok(Json(SpectatorCountResponse {
+        race_uuid: race_uuid.to_string(),
+        spectator_count,
+    }))
+}
+
+/// Viewers of a race with a heartbeat in the last `SPECTATOR_TIMEOUT_SECONDS`
+pub async fn count_spectators(
+    database: &Database,
+    race_uuid: Uuid,
+) -> Result<u64, mongodb::error::Error> {
+    let since = BsonDateTime::from_millis(
+        BsonDateTime::now().timestamp_millis() - SPECTATOR_TIMEOUT_SECONDS * 1000,
+    );
+    database
+        .collection::<Document>(SPECTATORS_COLLECTION)
+        .count_documents(
+            doc! {
+                "race_uuid": race_uuid.to_string(),
+                "last_seen_at": { "$gte": since },
+            },
+            None,
+        )
+        .await
+}
@@ -0,0 +1,420 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+    routing::get,
+    Extension, Router,
+};
+use chrono::Utc;
+use futures_util::TryStreamExt;
+use mongodb::{
+    bson::{doc, from_bson, Bson, DateTime as BsonDateTime, Document},
+    Database,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::database::{find_archived_race_events, is_duplicate_key, union_with_archived_races};
+use crate::domain::{ParticipantMovement, Race, RaceChange, RaceEvent, RaceStats, RaceStatus};
+use crate::repositories::SessionRepository;
+use crate::routes::races::{get_race_by_uuid, ErrorResponse};
+
+/// Collection holding the figures of finished races, computed once
+pub const RACE_STATS: &str = "race_stats";
+
+const DEFAULT_WINDOW_HOURS: u32 = 24;
+/// One week
+const MAX_WINDOW_HOURS: u32 = 168;
+const MILLIS_PER_HOUR: i64 = 60 * 60 * 1000;
+
+/// Audit log actions of players acting in a turn
+const TURN_ACTIONS: [&str; 2] = ["race.lap_action", "race.submit_action"];
+
+type ApiError = (StatusCode, Json<ErrorResponse>);
+
+/// Session storage the stats endpoint counts active sessions in
+pub type SessionStore = Arc<dyn SessionRepository>;
+
+/// How far back the activity figures look
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct StatsQuery {
+    /// Hours of activity covered, 24 by default and at most 168
+    pub window_hours: Option<u32>,
+}
+
+/// Races in each status, live and archived together
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct RaceStatusCounts {
+    pub waiting: u64,
+    pub starting: u64,
+    pub in_progress: u64,
+    pub finished: u64,
+    pub cancelled: u64,
+}
+
+/// Turn actions players submitted within one hour
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct HourlyActions {
+    /// Start of the hour
+    #[schema(value_type = String, format = "date-time")]
+    pub hour: BsonDateTime,
+    pub actions: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OperationalStats {
+    #[schema(value_type = String, format = "date-time")]
+    pub generated_at: BsonDateTime,
+    pub window_hours: u32,
+    pub races_by_status: RaceStatusCounts,
+    /// Hours with at least one action, oldest first
+    pub actions_per_hour: Vec<HourlyActions>,
+    /// Mean time between lap resolutions of running races within the
+    /// window; absent when no race resolved two laps in it
+    pub average_turn_seconds: Option<f64>,
+    pub active_sessions: usize,
+}
+
+/// Routes for administrators; mounted behind authentication and the admin role
+pub fn admin_routes() -> Router<Database> {
+    Router::new().route("/stats", get(get_stats))
+}
+
+pub fn routes() -> Router<Database> {
+    Router::new().route("/races/:race_uuid/stats", get(get_race_stats))
+}
+
+fn error(status: StatusCode, error: &str, message: &str) -> ApiError {
+    (
+        status,
+        Json(ErrorResponse {
+            error: error.to_string(),
+            message: message.to_string(),
+            details: None,
+        }),
+    )
+}
+
+/// Operational figures for dashboards
+///
+/// Counts races by status, turn actions per hour, the average turn duration
+/// and the sessions currently active.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/stats",
+    params(StatsQuery),
+    responses(
+        (status = 200, description = "Current operational figures", body = OperationalStats),
+        (status = 400, description = "Invalid window", body = ErrorResponse),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Not an administrator"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "admin"
+)]
+#[tracing::instrument(name = "Computing operational stats", skip(database, sessions))]
+pub async fn get_stats(
+    State(database): State<Database>,
+    Extension(sessions): Extension<SessionStore>,
+    Query(params): Query<StatsQuery>,
+) -> Result<Json<OperationalStats>, ApiError> {
+    let window_hours = params.window_hours.unwrap_or(DEFAULT_WINDOW_HOURS);
+    if !(1..=MAX_WINDOW_HOURS).contains(&window_hours) {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_WINDOW",
+            "window_hours must be between 1 and 168",
+        ));
+    }
+    let now = BsonDateTime::now();
+    let since = BsonDateTime::from_millis(
+        now.timestamp_millis() - i64::from(window_hours) * MILLIS_PER_HOUR,
+    );
+
+    let (races_by_status, actions_per_hour, average_turn_seconds) = tokio::try_join!(
+        count_races_by_status(&database),
+        count_actions_per_hour(&database, since),
+        average_turn_seconds(&database, since),
+    )
+    .map_err(|e| {
+        tracing::error!("Failed to aggregate operational stats: {:?}", e);
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "DATABASE_ERROR",
+            "Internal server error",
+        )
+    })?;
+    let active_sessions = sessions.count_active(Utc::now()).await.map_err(|e| {
+        tracing::error!("Failed to count active sessions: {:?}", e);
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "SESSION_ERROR",
+            "Internal server error",
+        )
+    })?;
+
+    Ok(Json(OperationalStats {
+        generated_at: now,
+        window_hours,
+        races_by_status,
+        actions_per_hour,
+        average_turn_seconds,
+        active_sessions,
+    }))
+}
+
+/// Figures of a race: overtakes, average boost and sector occupancy lap by
+/// lap, and lead changes
+///
+/// Computed from the race event log; a finished race's figures are computed
+/// once and kept. Hidden while fog of war is on.
+#[utoipa::path(
+    get,
+    path = "/api/v1/races/{race_uuid}/stats",
+    params(
+        ("race_uuid" = String, Path, description = "Race UUID")
+    ),
+    responses(
+        (status = 200, description = "Figures of the race", body = RaceStats),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse),
+        (status = 403, description = "Fog of war is on", body = ErrorResponse),
+        (status = 404, description = "Race not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "races"
+)]
+#[tracing::instrument(name = "Computing race stats", skip(database))]
+pub async fn get_race_stats(
+    State(database): State<Database>,
+    Path(race_uuid): Path<String>,
+) -> Result<Json<RaceStats>, ApiError> {
+    let race_uuid = Uuid::parse_str(&race_uuid).map_err(|_| {
+        error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_UUID",
+            "Invalid UUID format",
+        )
+    })?;
+    let database_error = |e: mongodb::error::Error| {
+        tracing::error!("Failed to compute stats of race {}: {:?}", race_uuid, e);
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "DATABASE_ERROR",
+            "Internal server error",
+        )
+    };
+
+    let cached = database
+        .collection::<RaceStats>(RACE_STATS)
+        .find_one(doc! { "race_uuid": race_uuid.to_string() }, None)
+        .await
+        .map_err(database_error)?;
+    if let Some(stats) = cached {
+        return Ok(Json(stats));
+    }
+
+    let race = get_race_by_uuid(&database, race_uuid)
+        .await
+        .map_err(database_error)?
+        .ok_or_else(|| error(StatusCode::NOT_FOUND, "RACE_NOT_FOUND", "Race not found"))?;
+    if race.fog_of_war_active() {
+        return Err(error(
+            StatusCode::FORBIDDEN,
+            "FOG_OF_WAR",
+            "Race stats are hidden until the race ends",
+        ));
+    }
+
+    let movements = race_movements(&database, &race)
+        .await
+        .map_err(database_error)?;
+    let stats = RaceStats::compute(&race, &movements);
+    if race.status == RaceStatus::Finished {
+        match database
+            .collection::<RaceStats>(RACE_STATS)
+            .insert_one(&stats, None)
+            .await
+        {
+            Ok(_) => {}
+            // Another request cached them first
+            Err(e) if is_duplicate_key(&e) => {}
+            Err(e) => return Err(database_error(e)),
+        }
+    }
+    Ok(Json(stats))
+}
+
+/// Movements of a race with their lap, in the order they were resolved
+///
+/// Archived races keep their event log compressed in the archive.
+pub(crate) async fn race_movements(
+    database: &Database,
+    race: &Race,
+) -> Result<Vec<(u32, ParticipantMovement)>, mongodb::error::Error> {
+    let options = mongodb::options::FindOptions::builder()
+        .sort(doc! { "occurred_at": 1, "_id": 1 })
+        .build();
+    let mut events: Vec<RaceEvent> = database
+        .collection::<RaceEvent>("race_events")
+        .find(
+            doc! { "race_uuid": race.uuid.to_string(), "change.type": "Movement" },
+            options,
+        )
+        .await?
+        .try_collect()
+        .await?;
+    if events.is_empty() {
+        events = find_archived_race_events(database, race.uuid).await?;
+    }
+    Ok(events
+        .into_iter()
+        .filter_map(|event| match event.change {
+            RaceChange::Movement { lap, movement } => Some((lap, movement)),
+            _ => None,
+        })
+        .collect())
+}
+
+async fn aggregate(
+    database: &Database,
+    collection: &str,
+    pipeline: Vec<Document>,
+) -> Result<Vec<Document>, mongodb::error::Error> {
+    database
+        .collection::<Document>(collection)
+        .aggregate(pipeline, None)
+        .await?
+        .try_collect()
+        .await
+}
+
+fn count_of(document: &Document, field: &str) -> u64 {
+    match document.get(field) {
+        Some(Bson::Int32(count)) => u64::try_from(*count).unwrap_or(0),
+        Some(Bson::Int64(count)) => u64::try_from(*count).unwrap_or(0),
+        _ => 0,
+    }
+}
+
+fn races_by_status_pipeline() -> Vec<Document> {
+    vec![
+        union_with_archived_races(Document::new()),
+        doc! { "$group": { "_id": "$status", "count": { "$sum": 1 } } },
+    ]
+}
+
+fn actions_per_hour_pipeline(since: BsonDateTime) -> Vec<Document> {
+    vec![
+        doc! { "$match": { "action": { "$in": TURN_ACTIONS.to_vec() }, "occurred_at": { "$gte": since } } },
+        doc! { "$group": {
+            "_id": { "$dateTrunc": { "date": "$occurred_at", "unit": "hour" } },
+            "actions": { "$sum": 1 },
+        } },
+        doc! { "$sort": { "_id": 1 } },
+        doc! { "$project": { "_id": 0, "hour": "$_id", "actions": 1 } },
+    ]
+}
+
+/// Each race's lap resolutions span `phases - 1` turns between the first
+/// and the last, so the totals give the mean without window functions
+fn turn_duration_pipeline(since: BsonDateTime) -> Vec<Document> {
+    vec![
+        doc! { "$match": { "change.type": "PhaseChanged", "occurred_at": { "$gte": since } } },
+        doc! { "$group": {
+            "_id": "$race_uuid",
+            "first": { "$min": "$occurred_at" },
+            "last": { "$max": "$occurred_at" },
+            "phases": { "$sum": 1 },
+        } },
+        doc! { "$match": { "phases": { "$gt": 1 } } },
+        doc! { "$group": {
+            "_id": Bson::Null,
+            "span_ms": { "$sum": { "$subtract": ["$last", "$first"] } },
+            "turns": { "$sum": { "$subtract": ["$phases", 1] } },
+        } },
+    ]
+}
+
+async fn count_races_by_status(
+    database: &Database,
+) -> Result<RaceStatusCounts, mongodb::error::Error> {
+    let mut counts = RaceStatusCounts::default();
+    for group in aggregate(database, "races", races_by_status_pipeline()).await? {
+        let count = count_of(&group, "count");
+        match group.get("_id").cloned().map(from_bson::<RaceStatus>) {
+            Some(Ok(RaceStatus::Waiting)) => counts.waiting += count,
+            Some(Ok(RaceStatus::Starting)) => counts.starting += count,
+            Some(Ok(RaceStatus::InProgress)) => counts.in_progress += count,
+            Some(Ok(RaceStatus::Finished)) => counts.finished += count,
+            Some(Ok(RaceStatus::Cancelled)) => counts.cancelled += count,
+            _ => tracing::warn!(
+                "Races with unknown status {:?} not counted",
+                group.get("_id")
+            ),
+        }
+    }
+    Ok(counts)
+}
+
+async fn count_actions_per_hour(
+    database: &Database,
+    since: BsonDateTime,
+) -> Result<Vec<HourlyActions>, mongodb::error::Error> {
+    aggregate(database, "audit_log", actions_per_hour_pipeline(since))
+        .await?
+        .into_iter()
+        .map(|hour| Ok(mongodb::bson::from_document(hour)?))
+        .collect()
+}
+
+async fn average_turn_seconds(
+    database: &Database,
+    since: BsonDateTime,
+) -> Result<Option<f64>, mongodb::error::Error> {
+    let totals = aggregate(database, "race_events", turn_duration_pipeline(since)).await?;
+    Ok(totals.first().and_then(|totals| {
+        let turns = count_of(totals, "turns");
+        let span_ms = count_of(totals, "span_ms");
+        #[allow(clippy::cast_precision_loss)]
+        (turns > 0).then(|| span_ms as f64 / turns as f64 / 1000.0)
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_turn_actions_in_the_window_are_counted() {
+        let since = BsonDateTime::from_millis(1_000);
+        let pipeline = actions_per_hour_pipeline(since);
+        let filter = pipeline[0].get_document("$match").unwrap();
+
+        let actions = filter
+            .get_document("action")
+            .unwrap()
+            .get_array("$in")
+            .unwrap();
+        assert_eq!(actions.len(), TURN_ACTIONS.len());
+        assert!(actions.contains(&Bson::String("race.lap_action".to_string())));
+        assert_eq!(
+            filter
+                .get_document("occurred_at")
+                .unwrap()
+                .get_datetime("$gte")
+                .unwrap(),
+            &since
+        );
+    }
+
+    #[test]
+    fn counts_of_either_integer_width_are_read() {
+        let totals = doc! { "turns": 3_i32, "span_ms": 90_000_i64, "other": "x" };
+        assert_eq!(count_of(&totals, "turns"), 3);
+        assert_eq!(count_of(&totals, "span_ms"), 90_000);
+        assert_eq!(count_of(&totals, "other"), 0);
+        assert_eq!(count_of(&totals, "missing"), 0);
+    }
+}
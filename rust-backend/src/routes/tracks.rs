@@ -0,0 +1,888 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+    routing::{get, post},
+    Extension, Router,
+};
+use mongodb::{
+    bson::{doc, DateTime as BsonDateTime},
+    options::ReplaceOptions,
+    Database,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::domain::{
+    builtin_track_templates, sort_track_designs, Sector, TrackDesign, TrackExport,
+    TrackImportError, TrackSort, TrackTemplate,
+};
+use crate::middleware::UserContext;
+use crate::repositories::{MongoTrackRepository, TrackRepository};
+use crate::routes::players::get_player_by_uuid_from_db;
+use crate::routes::races::TrackValidationErrorResponse;
+use crate::services::{record_audit, AuditEntry};
+
+type TrackApiError = (StatusCode, Json<TrackValidationErrorResponse>);
+
+/// New track, authored by the authenticated player
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateTrackRequest {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub sectors: Vec<Sector>,
+    /// List the track for every player; private by default
+    #[serde(default)]
+    pub is_public: bool,
+}
+
+/// Partial track update; only the author may edit a track
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateTrackRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub sectors: Option<Vec<Sector>>,
+    pub is_public: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RateTrackRequest {
+    /// 1 to 5 stars
+    pub stars: u8,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListTracksQuery {
+    /// Ordering of the results (defaults to `popular`)
+    pub sort: Option<TrackSort>,
+    /// Only tracks created by this player
+    pub author_uuid: Option<String>,
+}
+
+/// A community track as every player sees it
+///
+/// Which players rated or favorited the track is left out; only the totals
+/// are shown.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CommunityTrackResponse {
+    #[schema(value_type = String, format = "uuid")]
+    pub uuid: Uuid,
+    pub name: String,
+    pub description: String,
+    pub sectors: Vec<Sector>,
+    /// Player who created the track; imported tracks have no author
+    #[schema(value_type = Option<String>, format = "uuid")]
+    pub author_uuid: Option<Uuid>,
+    pub is_public: bool,
+    pub average_rating: Option<f64>,
+    pub rating_count: usize,
+    pub favorite_count: usize,
+    #[schema(value_type = String, format = "date-time")]
+    pub created_at: BsonDateTime,
+    #[schema(value_type = String, format = "date-time")]
+    pub updated_at: BsonDateTime,
+}
+
+impl From<TrackDesign> for CommunityTrackResponse {
+    fn from(track: TrackDesign) -> Self {
+        Self {
+            average_rating: track.average_rating(),
+            rating_count: track.ratings.len(),
+            favorite_count: track.favorited_by.len(),
+            uuid: track.uuid,
+            name: track.name,
+            description: track.description,
+            sectors: track.sectors,
+            author_uuid: track.author_uuid,
+            is_public: track.is_public,
+            created_at: track.created_at,
+            updated_at: track.updated_at,
+        }
+    }
+}
+
+pub fn routes() -> Router<Database> {
+    Router::new()
+        .route("/tracks", get(list_tracks))
+        .route("/tracks/templates", get(get_track_templates))
+        .route("/tracks/import", post(import_track))
+        .route("/tracks/:track_uuid", get(get_track))
+        .route("/tracks/:track_uuid/export", get(export_track))
+}
+
+/// Routes acting as the authenticated player, who authors, rates and
+/// favorites tracks; mounted behind `AuthMiddleware`
+pub fn player_routes() -> Router<Database> {
+    Router::new()
+        .route("/tracks", post(create_track))
+        .route("/tracks/favorites", get(list_favorite_tracks))
+        .route(
+            "/tracks/:track_uuid",
+            axum::routing::put(update_track).delete(delete_track),
+        )
+        .route("/tracks/:track_uuid/ratings", post(rate_track))
+        .route(
+            "/tracks/:track_uuid/favorite",
+            post(favorite_track).delete(unfavorite_track),
+        )
+}
+
+fn track_error(status: StatusCode, error: &str, message: impl Into<String>) -> TrackApiError {
+    (
+        status,
+        Json(TrackValidationErrorResponse {
+            error: error.to_string(),
+            message: message.into(),
+            field_errors: vec![],
+        }),
+    )
+}
+
+pub(crate) fn parse_uuid(value: &str) -> Result<Uuid, TrackApiError> {
+    Uuid::parse_str(value).map_err(|_| {
+        tracing::warn!("Invalid UUID format: {}", value);
+        track_error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_UUID",
+            "Invalid UUID format",
+        )
+    })
+}
+
+/// Load a stored track, mapping a missing track or repository failure to an API error
+pub(crate) async fn load_track_design(
+    database: &Database,
+    track_uuid: Uuid,
+) -> Result<TrackDesign, TrackApiError> {
+    match MongoTrackRepository::new(database)
+        .find_by_uuid(track_uuid)
+        .await
+    {
+        Ok(Some(design)) => Ok(design),
+        Ok(None) => {
+            tracing::warn!("Track not found: {}", track_uuid);
+            Err(track_error(
+                StatusCode::NOT_FOUND,
+                "TRACK_NOT_FOUND",
+                "Track not found",
+            ))
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch track: {:?}", e);
+            Err(track_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "DATABASE_ERROR",
+                "Failed to fetch track",
+            ))
+        }
+    }
+}
+
+/// Only the track's author may change it
+fn require_author(
+    design: &TrackDesign,
+    user: &UserContext,
+    action: &str,
+) -> Result<(), TrackApiError> {
+    if design.is_authored_by(user.user_uuid) {
+        return Ok(());
+    }
+    Err(track_error(
+        StatusCode::FORBIDDEN,
+        "NOT_TRACK_AUTHOR",
+        format!("Only the track author can {action} this track"),
+    ))
+}
+
+fn fetch_tracks_error(e: &crate::repositories::RepositoryError) -> TrackApiError {
+    tracing::error!("Failed to fetch tracks: {:?}", e);
+    track_error(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "DATABASE_ERROR",
+        "Failed to fetch tracks",
+    )
+}
+
+async fn require_player(database: &Database, player_uuid: Uuid) -> Result<(), TrackApiError> {
+    match get_player_by_uuid_from_db(database, player_uuid).await {
+        Ok(Some(_)) => Ok(()),
+        Ok(None) => {
+            tracing::warn!("Player not found: {}", player_uuid);
+            Err(track_error(
+                StatusCode::NOT_FOUND,
+                "PLAYER_NOT_FOUND",
+                "Player not found",
+            ))
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch player: {:?}", e);
+            Err(track_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "DATABASE_ERROR",
+                "Failed to fetch player",
+            ))
+        }
+    }
+}
+
+async fn save_track_design(database: &Database, design: &TrackDesign) -> Result<(), TrackApiError> {
+    match MongoTrackRepository::new(database).update(design).await {
+        Ok(Some(_)) => Ok(()),
+        Ok(None) => Err(track_error(
+            StatusCode::NOT_FOUND,
+            "TRACK_NOT_FOUND",
+            "Track not found",
+        )),
+        Err(e) => {
+            tracing::error!("Failed to update track: {:?}", e);
+            Err(track_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "DATABASE_ERROR",
+                "Failed to update track",
+            ))
+        }
+    }
+}
+
+/// List public community tracks
+///
+/// Tracks are ordered by popularity unless another `sort` is requested, so the
+/// most favorited player-made tracks come first when picking a track for a race.
+#[utoipa::path(
+    get,
+    path = "/api/v1/tracks",
+    params(ListTracksQuery),
+    responses(
+        (status = 200, description = "Public community tracks", body = Vec<CommunityTrackResponse>),
+        (status = 400, description = "Invalid UUID format", body = TrackValidationErrorResponse),
+        (status = 500, description = "Internal server error", body = TrackValidationErrorResponse)
+    ),
+    tag = "tracks"
+)]
+#[tracing::instrument(name = "Listing community tracks", skip(database))]
+pub async fn list_tracks(
+    State(database): State<Database>,
+    Query(params): Query<ListTracksQuery>,
+) -> Result<Json<Vec<CommunityTrackResponse>>, TrackApiError> {
+    let author_uuid = params.author_uuid.as_deref().map(parse_uuid).transpose()?;
+
+    let mut tracks = MongoTrackRepository::new(&database)
+        .find_public()
+        .await
+        .map_err(|e| fetch_tracks_error(&e))?;
+
+    if let Some(author_uuid) = author_uuid {
+        tracks.retain(|track| track.is_authored_by(author_uuid));
+    }
+    sort_track_designs(&mut tracks, params.sort.unwrap_or_default());
+
+    tracing::info!("Successfully fetched {} community tracks", tracks.len());
+    Ok(Json(tracks.into_iter().map(Into::into).collect()))
+}
+
+/// List the public tracks the authenticated player favorited
+#[utoipa::path(
+    get,
+    path = "/api/v1/tracks/favorites",
+    params(ListTracksQuery),
+    responses(
+        (status = 200, description = "The player's favorite tracks", body = Vec<CommunityTrackResponse>),
+        (status = 400, description = "Invalid UUID format", body = TrackValidationErrorResponse),
+        (status = 401, description = "Authentication required"),
+        (status = 500, description = "Internal server error", body = TrackValidationErrorResponse)
+    ),
+    tag = "tracks"
+)]
+#[tracing::instrument(name = "Listing favorite tracks", skip(database, user))]
+pub async fn list_favorite_tracks(
+    State(database): State<Database>,
+    Extension(user): Extension<UserContext>,
+    Query(params): Query<ListTracksQuery>,
+) -> Result<Json<Vec<CommunityTrackResponse>>, TrackApiError> {
+    let author_uuid = params.author_uuid.as_deref().map(parse_uuid).transpose()?;
+
+    let mut tracks = MongoTrackRepository::new(&database)
+        .find_public()
+        .await
+        .map_err(|e| fetch_tracks_error(&e))?;
+
+    tracks.retain(|track| {
+        track.favorited_by.contains(&user.user_uuid)
+            && author_uuid.is_none_or(|author_uuid| track.is_authored_by(author_uuid))
+    });
+    sort_track_designs(&mut tracks, params.sort.unwrap_or_default());
+    Ok(Json(tracks.into_iter().map(Into::into).collect()))
+}
+
+/// Create a community track
+#[utoipa::path(
+    post,
+    path = "/api/v1/tracks",
+    request_body = CreateTrackRequest,
+    responses(
+        (status = 201, description = "Track created", body = CommunityTrackResponse),
+        (status = 400, description = "Invalid track definition", body = TrackValidationErrorResponse),
+        (status = 401, description = "Authentication required"),
+        (status = 404, description = "Author not found", body = TrackValidationErrorResponse),
+        (status = 500, description = "Internal server error", body = TrackValidationErrorResponse)
+    ),
+    tag = "tracks"
+)]
+#[tracing::instrument(
+    name = "Creating community track",
+    skip(database, user, payload),
+    fields(track_name = %payload.name, author_uuid = %user.user_uuid)
+)]
+pub async fn create_track(
+    State(database): State<Database>,
+    Extension(user): Extension<UserContext>,
+    Json(payload): Json<CreateTrackRequest>,
+) -> Result<(StatusCode, Json<CommunityTrackResponse>), TrackApiError> {
+    let author_uuid = user.user_uuid;
+    require_player(&database, author_uuid).await?;
+
+    let mut design = TrackDesign::new(
+        payload.name,
+        payload.description,
+        payload.sectors,
+        Some(author_uuid),
+    )
+    .map_err(|field_errors| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(TrackValidationErrorResponse {
+                error: "INVALID_TRACK".to_string(),
+                message: "Track definition is invalid".to_string(),
+                field_errors,
+            }),
+        )
+    })?;
+    design.is_public = payload.is_public;
+
+    match MongoTrackRepository::new(&database).create(&design).await {
+        Ok(created) => {
+            let audit = AuditEntry::new("track.create").by(author_uuid);
+            record_audit(&database, audit.values(None, Some(&created))).await;
+            tracing::info!("Community track created with UUID: {}", created.uuid);
+            Ok((StatusCode::CREATED, Json(created.into())))
+        }
+        Err(e) => {
+            tracing::error!("Failed to create track: {:?}", e);
+            Err(track_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "DATABASE_ERROR",
+                "Failed to create track",
+            ))
+        }
+    }
+}
+
+/// Get a community track by UUID
+#[utoipa::path(
+    get,
+    path = "/api/v1/tracks/{track_uuid}",
+    params(
+        ("track_uuid" = String, Path, description = "Track UUID")
+    ),
+    responses(
+        (status = 200, description = "Track found", body = CommunityTrackResponse),
+        (status = 400, description = "Invalid UUID format", body = TrackValidationErrorResponse),
+        (status = 404, description = "Track not found", body = TrackValidationErrorResponse),
+        (status = 500, description = "Internal server error", body = TrackValidationErrorResponse)
+    ),
+    tag = "tracks"
+)]
+#[tracing::instrument(name = "Fetching community track", skip(database))]
+pub async fn get_track(
+    State(database): State<Database>,
+    Path(track_uuid): Path<String>,
+) -> Result<Json<CommunityTrackResponse>, TrackApiError> {
+    let track_uuid = parse_uuid(&track_uuid)?;
+    let design = load_track_design(&database, track_uuid).await?;
+    Ok(Json(design.into()))
+}
+
+/// Update a community track
+///
+/// Only the author, identified by their access token, can edit a track. Omitted fields are left unchanged and a new
+/// sector layout is validated like on creation.
+#[utoipa::path(
+    put,
+    path = "/api/v1/tracks/{track_uuid}",
+    params(
+        ("track_uuid" = String, Path, description = "Track UUID")
+    ),
+    request_body = UpdateTrackRequest,
+    responses(
+        (status = 200, description = "Track updated", body = CommunityTrackResponse),
+        (status = 400, description = "Invalid UUID or track definition", body = TrackValidationErrorResponse),
+        (status = 401, description = "Authentication required"),
+        (status = 403, description = "Player is not the track author", body = TrackValidationErrorResponse),
+        (status = 404, description = "Track not found", body = TrackValidationErrorResponse),
+        (status = 500, description = "Internal server error", body = TrackValidationErrorResponse)
+    ),
+    tag = "tracks"
+)]
+#[tracing::instrument(name = "Updating community track", skip(database, user, payload))]
+pub async fn update_track(
+    State(database): State<Database>,
+    Extension(user): Extension<UserContext>,
+    Path(track_uuid): Path<String>,
+    Json(payload): Json<UpdateTrackRequest>,
+) -> Result<Json<CommunityTrackResponse>, TrackApiError> {
+    let track_uuid = parse_uuid(&track_uuid)?;
+    let mut design = load_track_design(&database, track_uuid).await?;
+    require_author(&design, &user, "edit")?;
+    let previous = design.clone();
+
+    if let Some(sectors) = payload.sectors {
+        design.update_sectors(sectors).map_err(|field_errors| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(TrackValidationErrorResponse {
+                    error: "INVALID_TRACK".to_string(),
+                    message: "Track definition is invalid".to_string(),
+                    field_errors,
+                }),
+            )
+        })?;
+    }
+    if let Some(name) = payload.name {
+        design.name = name;
+    }
+    if let Some(description) = payload.description {
+        design.description = description;
+    }
+    if let Some(is_public) = payload.is_public {
+        design.is_public = is_public;
+    }
+    design.updated_at = BsonDateTime::now();
+
+    save_track_design(&database, &design).await?;
+    let audit = AuditEntry::new("track.update").by(user.user_uuid);
+    record_audit(&database, audit.values(Some(&previous), Some(&design))).await;
+    tracing::info!("Community track {} updated", design.uuid);
+    Ok(Json(design.into()))
+}
+
+/// Delete a community track
+///
+/// Only the author, identified by their access token, can delete a track.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/tracks/{track_uuid}",
+    params(
+        ("track_uuid" = String, Path, description = "Track UUID")
+    ),
+    responses(
+        (status = 204, description = "Track deleted"),
+        (status = 400, description = "Invalid UUID format", body = TrackValidationErrorResponse),
+        (status = 401, description = "Authentication required"),
+        (status = 403, description = "Player is not the track author", body = TrackValidationErrorResponse),
+        (status = 404, description = "Track not found", body = TrackValidationErrorResponse),
+        (status = 500, description = "Internal server error", body = TrackValidationErrorResponse)
+    ),
+    tag = "tracks"
+)]
+#[tracing::instrument(name = "Deleting community track", skip(database, user))]
+pub async fn delete_track(
+    State(database): State<Database>,
+    Extension(user): Extension<UserContext>,
+    Path(track_uuid): Path<String>,
+) -> Result<StatusCode, TrackApiError> {
+    let track_uuid = parse_uuid(&track_uuid)?;
+    let design = load_track_design(&database, track_uuid).await?;
+    require_author(&design, &user, "delete")?;
+
+    match MongoTrackRepository::new(&database)
+        .delete_by_uuid(track_uuid)
+        .await
+    {
+        Ok(_) => {
+            let audit = AuditEntry::new("track.delete").by(user.user_uuid);
+            record_audit(&database, audit.values(Some(&design), None)).await;
+            tracing::info!("Community track {} deleted", track_uuid);
+            Ok(StatusCode::NO_CONTENT)
+        }
+        Err(e) => {
+            tracing::error!("Failed to delete track: {:?}", e);
+            Err(track_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "DATABASE_ERROR",
+                "Failed to delete track",
+            ))
+        }
+    }
+}
+
+/// Rate a community track from 1 to 5 stars
+///
+/// Rating again replaces the player's previous rating. Authors cannot rate
+/// their own tracks.
+#[utoipa::path(
+    post,
+    path = "/api/v1/tracks/{track_uuid}/ratings",
+    params(
+        ("track_uuid" = String, Path, description = "Track UUID")
+    ),
+    request_body = RateTrackRequest,
+    responses(
+        (status = 200, description = "Rating recorded", body = CommunityTrackResponse),
+        (status = 400, description = "Invalid UUID or rating", body = TrackValidationErrorResponse),
+        (status = 401, description = "Authentication required"),
+        (status = 404, description = "Track or player not found", body = TrackValidationErrorResponse),
+        (status = 500, description = "Internal server error", body = TrackValidationErrorResponse)
+    ),
+    tag = "tracks"
+)]
+#[tracing::instrument(name = "Rating community track", skip(database, user, payload))]
+pub async fn rate_track(
+    State(database): State<Database>,
+    Extension(user): Extension<UserContext>,
+    Path(track_uuid): Path<String>,
+    Json(payload): Json<RateTrackRequest>,
+) -> Result<Json<CommunityTrackResponse>, TrackApiError> {
+    let track_uuid = parse_uuid(&track_uuid)?;
+    let player_uuid = user.user_uuid;
+    let mut design = load_track_design(&database, track_uuid).await?;
+    require_player(&database, player_uuid).await?;
+
+    let previous = design.clone();
+    if let Err(message) = design.rate(player_uuid, payload.stars) {
+        return Err(track_error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_RATING",
+            message,
+        ));
+    }
+
+    save_track_design(&database, &design).await?;
+    let audit = AuditEntry::new("track.rate").by(player_uuid);
+    record_audit(&database, audit.values(Some(&previous), Some(&design))).await;
+    Ok(Json(design.into()))
+}
+
+/// Add a community track to the player's favorites
+#[utoipa::path(
+    post,
+    path = "/api/v1/tracks/{track_uuid}/favorite",
+    params(
+        ("track_uuid" = String, Path, description = "Track UUID")
+    ),
+    responses(
+        (status = 200, description = "Track favorited", body = CommunityTrackResponse),
+        (status = 400, description = "Invalid UUID format", body = TrackValidationErrorResponse),
+        (status = 401, description = "Authentication required"),
+        (status = 404, description = "Track or player not found", body = TrackValidationErrorResponse),
+        (status = 500, description = "Internal server error", body = TrackValidationErrorResponse)
+    ),
+    tag = "tracks"
+)]
+#[tracing::instrument(name = "Favoriting community track", skip(database, user))]
+pub async fn favorite_track(
+    State(database): State<Database>,
+    Extension(user): Extension<UserContext>,
+    Path(track_uuid): Path<String>,
+) -> Result<Json<CommunityTrackResponse>, TrackApiError> {
+    let track_uuid = parse_uuid(&track_uuid)?;
+    let player_uuid = user.user_uuid;
+    let mut design = load_track_design(&database, track_uuid).await?;
+    require_player(&database, player_uuid).await?;
+
+    let previous = design.clone();
+    if design.add_favorite(player_uuid) {
+        save_track_design(&database, &design).await?;
+        let audit = AuditEntry::new("track.favorite").by(player_uuid);
+        record_audit(&database, audit.values(Some(&previous), Some(&design))).await;
+    }
+    Ok(Json(design.into()))
+}
+
+/// Remove a community track from the player's favorites
+#[utoipa::path(
+    delete,
+    path = "/api/v1/tracks/{track_uuid}/favorite",
+    params(
+        ("track_uuid" = String, Path, description = "Track UUID")
+    ),
+    responses(
+        (status = 200, description = "Favorite removed", body = CommunityTrackResponse),
+        (status = 400, description = "Invalid UUID format", body = TrackValidationErrorResponse),
+        (status = 401, description = "Authentication required"),
+        (status = 404, description = "Track not found", body = TrackValidationErrorResponse),
+        (status = 500, description = "Internal server error", body = TrackValidationErrorResponse)
+    ),
+    tag = "tracks"
+)]
+#[tracing::instrument(name = "Unfavoriting community track", skip(database, user))]
+pub async fn unfavorite_track(
+    State(database): State<Database>,
+    Extension(user): Extension<UserContext>,
+    Path(track_uuid): Path<String>,
+) -> Result<Json<CommunityTrackResponse>, TrackApiError> {
+    let track_uuid = parse_uuid(&track_uuid)?;
+    let player_uuid = user.user_uuid;
+    let mut design = load_track_design(&database, track_uuid).await?;
+
+    let previous = design.clone();
+    if design.remove_favorite(player_uuid) {
+        save_track_design(&database, &design).await?;
+        let audit = AuditEntry::new("track.unfavorite").by(player_uuid);
+        record_audit(&database, audit.values(Some(&previous), Some(&design))).await;
+    }
+    Ok(Json(design.into()))
+}
+
+/// List the built-in track templates
+///
+/// Any `template_id` returned here can be passed to `POST /api/v1/races`
+/// instead of hand-authored sectors.
+#[utoipa::path(
+    get,
+    path = "/api/v1/tracks/templates",
+    responses(
+        (status = 200, description = "List of track templates", body = Vec<TrackTemplate>),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "tracks"
+)]
+#[tracing::instrument(name = "Fetching track templates", skip(database))]
+pub async fn get_track_templates(
+    State(database): State<Database>,
+) -> Result<Json<Vec<TrackTemplate>>, StatusCode> {
+    match get_all_track_templates_from_db(&database).await {
+        Ok(templates) => {
+            tracing::info!("Successfully fetched {} track templates", templates.len());
+            Ok(Json(templates))
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch track templates: {:?}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Export a track in the portable JSON format
+///
+/// The export carries a schema version and a checksum so another server can
+/// verify it before importing it with `POST /api/v1/tracks/import`.
+#[utoipa::path(
+    get,
+    path = "/api/v1/tracks/{track_uuid}/export",
+    params(
+        ("track_uuid" = String, Path, description = "Track UUID")
+    ),
+    responses(
+        (status = 200, description = "Portable track export", body = TrackExport),
+        (status = 400, description = "Invalid UUID format"),
+        (status = 404, description = "Track not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "tracks"
+)]
+#[tracing::instrument(name = "Exporting track", skip(database))]
+pub async fn export_track(
+    State(database): State<Database>,
+    Path(track_uuid): Path<String>,
+) -> Result<Json<TrackExport>, StatusCode> {
+    let Ok(uuid) = Uuid::parse_str(&track_uuid) else {
+        tracing::warn!("Invalid track UUID format: {}", track_uuid);
+        return Err(StatusCode::BAD_REQUEST);
+    };
+
+    match MongoTrackRepository::new(&database)
+        .find_by_uuid(uuid)
+        .await
+    {
+        Ok(Some(design)) => Ok(Json(TrackExport::from_design(&design))),
+        Ok(None) => {
+            tracing::warn!("Track not found: {}", uuid);
+            Err(StatusCode::NOT_FOUND)
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch track: {:?}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Import a track exported from this or another server
+///
+/// The format, schema version and checksum are checked and the sectors are
+/// validated like any other track definition. The imported track gets a new UUID.
+#[utoipa::path(
+    post,
+    path = "/api/v1/tracks/import",
+    request_body = TrackExport,
+    responses(
+        (status = 201, description = "Track imported", body = TrackDesign),
+        (
+            status = 400,
+            description = "Unsupported format or schema version, checksum mismatch, or invalid track",
+            body = TrackValidationErrorResponse,
+            example = json!({
+                "error": "CHECKSUM_MISMATCH",
+                "message": "Checksum does not match the track content",
+                "field_errors": []
+            })
+        ),
+        (status = 500, description = "Internal server error", body = TrackValidationErrorResponse)
+    ),
+    tag = "tracks"
+)]
+#[tracing::instrument(
+    name = "Importing track",
+    skip(database, payload),
+    fields(track_name = %payload.name, schema_version = payload.schema_version)
+)]
+pub async fn import_track(
+    State(database): State<Database>,
+    Json(payload): Json<TrackExport>,
+) -> Result<(StatusCode, Json<TrackDesign>), (StatusCode, Json<TrackValidationErrorResponse>)> {
+    let design = match payload.into_design() {
+        Ok(design) => design,
+        Err(e) => {
+            tracing::warn!("Rejected track import: {}", e);
+            let message = e.to_string();
+            let error = e.error_code().to_string();
+            let field_errors = match e {
+                TrackImportError::InvalidTrack(field_errors) => field_errors,
+                _ => vec![],
+            };
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(TrackValidationErrorResponse {
+                    error,
+                    message,
+                    field_errors,
+                }),
+            ));
+        }
+    };
+
+    match MongoTrackRepository::new(&database).create(&design).await {
+        Ok(created) => {
+            let audit = AuditEntry::new("track.import");
+            record_audit(&database, audit.values(None, Some(&created))).await;
+            tracing::info!("Track imported with UUID: {}", created.uuid);
+            Ok((StatusCode::CREATED, Json(created)))
+        }
+        Err(e) => {
+            tracing::error!("Failed to store imported track: {:?}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(TrackValidationErrorResponse {
+                    error: "DATABASE_ERROR".to_string(),
+                    message: "Failed to store imported track".to_string(),
+                    field_errors: vec![],
+                }),
+            ))
+        }
+    }
+}
+
+// Database operations
+
+/// Upsert every built-in template into the `tracks` collection
+/// Existing templates are replaced so balance changes ship with the server
+#[tracing::instrument(name = "Seeding track templates", skip(database))]
+pub async fn seed_track_templates(database: &Database) -> Result<usize, mongodb::error::Error> {
+    let collection = database.collection::<TrackTemplate>("tracks");
+    let options = ReplaceOptions::builder().upsert(true).build();
+
+    let templates = builtin_track_templates();
+    for template in &templates {
+        let filter = doc! { "template_id": &template.template_id };
+        collection
+            .replace_one(filter, template, options.clone())
+            .await?;
+    }
+
+    Ok(templates.len())
+}
+
+#[tracing::instrument(name = "Getting all track templates from the database", skip(database))]
+pub async fn get_all_track_templates_from_db(
+    database: &Database,
+) -> Result<Vec<TrackTemplate>, mongodb::error::Error> {
+    let collection = database.collection::<TrackTemplate>("tracks");
+    let filter = doc! { "template_id": { "$exists": true } };
+    let mut cursor = collection.find(filter, None).await?;
+
+    let mut templates = Vec::new();
+    while cursor.advance().await? {
+        templates.push(cursor.deserialize_current()?);
+    }
+
+    Ok(templates)
+}
+
+#[tracing::instrument(
+    name = "Getting track template by id from the database",
+    skip(database)
+)]
+pub async fn get_track_template_by_id(
+    database: &Database,
+    template_id: &str,
+) -> Result<Option<TrackTemplate>, mongodb::error::Error> {
+    let collection = database.collection::<TrackTemplate>("tracks");
+    let filter = doc! { "template_id": template_id };
+    collection.find_one(filter, None).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::UserRole;
+
+    fn user(user_uuid: Uuid) -> UserContext {
+        UserContext {
+            user_uuid,
+            email: "author@example.com".to_string(),
+            role: UserRole::Player,
+            token_id: "token".to_string(),
+            device_fingerprint: None,
+            permissions: Vec::new(),
+        }
+    }
+
+    fn design(author_uuid: Uuid) -> TrackDesign {
+        TrackDesign::new(
+            "Harbour".to_string(),
+            String::new(),
+            builtin_track_templates()[0].sectors.clone(),
+            Some(author_uuid),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn only_the_author_may_change_a_track() {
+        let author_uuid = Uuid::new_v4();
+        let track = design(author_uuid);
+
+        assert!(require_author(&track, &user(author_uuid), "edit").is_ok());
+        let (status, Json(body)) = require_author(&track, &user(Uuid::new_v4()), "delete")
+            .expect_err("a non-author is rejected");
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body.error, "NOT_TRACK_AUTHOR");
+    }
+
+    #[test]
+    fn public_track_hides_who_rated_and_favorited_it() {
+        let mut track = design(Uuid::new_v4());
+        let fan = Uuid::new_v4();
+        track.rate(fan, 4).unwrap();
+        track.add_favorite(fan);
+
+        let response = CommunityTrackResponse::from(track);
+        assert_eq!(response.rating_count, 1);
+        assert_eq!(response.favorite_count, 1);
+        let json = serde_json::to_string(&response).unwrap();
+        assert!(!json.contains("favorited_by"));
+        assert!(!json.contains("ratings"));
+        assert!(!json.contains(&fan.to_string()));
+    }
+}
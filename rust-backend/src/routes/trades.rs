@@ -0,0 +1,288 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+    routing::{get, post},
+    Router,
+};
+use mongodb::Database;
+use serde::Deserialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::{TradeItems, TradeOffer};
+use crate::repositories::{MongoTradeRepository, RepositoryError, TradeRepository};
+use crate::routes::blocks::is_blocked_between_in_db;
+use crate::routes::players::get_player_by_uuid_from_db;
+use crate::routes::races::ErrorResponse;
+
+type ApiError = (StatusCode, Json<ErrorResponse>);
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct TradeOfferRequest {
+    /// Player the offer is made to
+    pub recipient_uuid: String,
+    /// Engines, bodies and credits the offering player gives
+    pub offered: TradeItems,
+    /// Engines, bodies and credits wanted from the recipient
+    pub requested: TradeItems,
+}
+
+pub fn routes() -> Router<Database> {
+    Router::new()
+        .route(
+            "/players/:player_uuid/trades",
+            get(list_trades).post(offer_trade),
+        )
+        .route(
+            "/players/:player_uuid/trades/:trade_uuid/accept",
+            post(accept_trade),
+        )
+        .route(
+            "/players/:player_uuid/trades/:trade_uuid/cancel",
+            post(cancel_trade),
+        )
+}
+
+fn error(status: StatusCode, error: &str, message: &str) -> ApiError {
+    (
+        status,
+        Json(ErrorResponse {
+            error: error.to_string(),
+            message: message.to_string(),
+            details: None,
+        }),
+    )
+}
+
+fn database_error(e: &mongodb::error::Error) -> ApiError {
+    tracing::error!("Trade storage failed: {:?}", e);
+    error(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "DATABASE_ERROR",
+        "Internal server error",
+    )
+}
+
+fn repository_error(e: RepositoryError) -> ApiError {
+    match e {
+        RepositoryError::NotFound => error(
+            StatusCode::NOT_FOUND,
+            "PLAYER_NOT_FOUND",
+            "Player not found",
+        ),
+        RepositoryError::Validation(message) => {
+            error(StatusCode::BAD_REQUEST, "TRADE_ITEMS_UNAVAILABLE", &message)
+        }
+        RepositoryError::Conflict(message) => {
+            error(StatusCode::CONFLICT, "TRADE_CONFLICT", &message)
+        }
+        RepositoryError::Database(message) => {
+            tracing::error!("Trade storage failed: {}", message);
+            error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "DATABASE_ERROR",
+                "Internal server error",
+            )
+        }
+    }
+}
+
+fn parse_uuid(value: &str) -> Result<Uuid, ApiError> {
+    Uuid::parse_str(value).map_err(|_| {
+        error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_UUID",
+            "Invalid UUID format",
+        )
+    })
+}
+
+fn trade_not_found() -> ApiError {
+    error(
+        StatusCode::NOT_FOUND,
+        "TRADE_NOT_FOUND",
+        "No trade in that state for this player",
+    )
+}
+
+/// Offer a trade to another player
+///
+/// The offered engines, bodies and credits leave the player's inventory
+/// right away and are held in escrow until the trade is accepted or
+/// cancelled. Components fitted to a car cannot be offered.
+#[utoipa::path(
+    post,
+    path = "/api/v1/players/{player_uuid}/trades",
+    params(
+        ("player_uuid" = String, Path, description = "Player making the offer")
+    ),
+    request_body = TradeOfferRequest,
+    responses(
+        (status = 201, description = "Offer made", body = TradeOffer),
+        (status = 400, description = "Invalid UUID, empty offer or items the player cannot give", body = ErrorResponse),
+        (status = 403, description = "The players blocked each other", body = ErrorResponse),
+        (status = 404, description = "Player not found", body = ErrorResponse),
+        (status = 409, description = "The offered items changed meanwhile", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Offering trade", skip(database))]
+pub async fn offer_trade(
+    State(database): State<Database>,
+    Path(player_uuid): Path<String>,
+    Json(request): Json<TradeOfferRequest>,
+) -> Result<(StatusCode, Json<TradeOffer>), ApiError> {
+    let player_uuid = parse_uuid(&player_uuid)?;
+    let recipient_uuid = parse_uuid(&request.recipient_uuid)?;
+    if player_uuid == recipient_uuid {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "CANNOT_TRADE_WITH_SELF",
+            "Players cannot trade with themselves",
+        ));
+    }
+    if request.offered.is_empty() && request.requested.is_empty() {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "EMPTY_TRADE",
+            "A trade must move at least one item or some credits",
+        ));
+    }
+    match get_player_by_uuid_from_db(&database, recipient_uuid).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return Err(error(
+                StatusCode::NOT_FOUND,
+                "PLAYER_NOT_FOUND",
+                "Recipient not found",
+            ))
+        }
+        Err(e) => return Err(database_error(&e)),
+    }
+    if is_blocked_between_in_db(&database, player_uuid, recipient_uuid)
+        .await
+        .map_err(|e| database_error(&e))?
+    {
+        return Err(error(
+            StatusCode::FORBIDDEN,
+            "PLAYER_BLOCKED",
+            "This player cannot be traded with",
+        ));
+    }
+
+    let offer = TradeOffer::new(
+        player_uuid,
+        recipient_uuid,
+        request.offered,
+        request.requested,
+    );
+    let offer = MongoTradeRepository::new(&database)
+        .open(offer)
+        .await
+        .map_err(repository_error)?;
+    tracing::info!(
+        "Player {} offered trade {} to {}",
+        player_uuid,
+        offer.uuid,
+        recipient_uuid
+    );
+    Ok((StatusCode::CREATED, Json(offer)))
+}
+
+/// Trades the player offered or was offered, newest first
+#[utoipa::path(
+    get,
+    path = "/api/v1/players/{player_uuid}/trades",
+    params(
+        ("player_uuid" = String, Path, description = "Player's UUID")
+    ),
+    responses(
+        (status = 200, description = "Trades of the player", body = Vec<TradeOffer>),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Listing trades", skip(database))]
+pub async fn list_trades(
+    State(database): State<Database>,
+    Path(player_uuid): Path<String>,
+) -> Result<Json<Vec<TradeOffer>>, ApiError> {
+    let player_uuid = parse_uuid(&player_uuid)?;
+    MongoTradeRepository::new(&database)
+        .find_for_player(player_uuid)
+        .await
+        .map(Json)
+        .map_err(repository_error)
+}
+
+/// Accept a trade offered to the player
+///
+/// The requested items are taken from the player and both sides change
+/// hands. Repeating the call finishes a settlement that was cut short.
+#[utoipa::path(
+    post,
+    path = "/api/v1/players/{player_uuid}/trades/{trade_uuid}/accept",
+    params(
+        ("player_uuid" = String, Path, description = "Player the trade was offered to"),
+        ("trade_uuid" = String, Path, description = "Trade UUID")
+    ),
+    responses(
+        (status = 200, description = "Trade completed", body = TradeOffer),
+        (status = 400, description = "Invalid UUID or requested items the player cannot give", body = ErrorResponse),
+        (status = 404, description = "No open trade offered to the player", body = ErrorResponse),
+        (status = 409, description = "The requested items changed meanwhile", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Accepting trade", skip(database))]
+pub async fn accept_trade(
+    State(database): State<Database>,
+    Path((player_uuid, trade_uuid)): Path<(String, String)>,
+) -> Result<Json<TradeOffer>, ApiError> {
+    let player_uuid = parse_uuid(&player_uuid)?;
+    let trade_uuid = parse_uuid(&trade_uuid)?;
+    MongoTradeRepository::new(&database)
+        .accept(trade_uuid, player_uuid)
+        .await
+        .map_err(repository_error)?
+        .map(Json)
+        .ok_or_else(trade_not_found)
+}
+
+/// Cancel or decline an open trade
+///
+/// Either player may call it; the offered items go back to the player who
+/// offered them.
+#[utoipa::path(
+    post,
+    path = "/api/v1/players/{player_uuid}/trades/{trade_uuid}/cancel",
+    params(
+        ("player_uuid" = String, Path, description = "Player on either side of the trade"),
+        ("trade_uuid" = String, Path, description = "Trade UUID")
+    ),
+    responses(
+        (status = 200, description = "Trade cancelled", body = TradeOffer),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse),
+        (status = 404, description = "No open trade involving the player", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "players"
+)]
+#[tracing::instrument(name = "Cancelling trade", skip(database))]
+pub async fn cancel_trade(
+    State(database): State<Database>,
+    Path((player_uuid, trade_uuid)): Path<(String, String)>,
+) -> Result<Json<TradeOffer>, ApiError> {
+    let player_uuid = parse_uuid(&player_uuid)?;
+    let trade_uuid = parse_uuid(&trade_uuid)?;
+    MongoTradeRepository::new(&database)
+        .cancel(trade_uuid, player_uuid)
+        .await
+        .map_err(repository_error)?
+        .map(Json)
+        .ok_or_else(trade_not_found)
+}
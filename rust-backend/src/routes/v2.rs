@@ -0,0 +1,105 @@
+//! Routes under `/api/v2`
+//!
+//! v2 serves every v1 race route, except where a response changed shape: lap
+//! history drops the placeholder fields v1 fills with zeros. The handlers share
+//! their logic with v1, which converts these shapes back into its own, so v1
+//! responses stay as they were.
+
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    response::Json,
+    routing::get,
+    Router,
+};
+use mongodb::Database;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::domain::LapCharacteristic;
+use crate::middleware::UserContext;
+use crate::routes::pagination::CursorQuery;
+use crate::routes::races::{self, CycleSummary, ErrorResponse};
+
+/// v2 lap history; the rest of the API is the same in both versions
+///
+/// Mounted with the race read routes, behind the auth middleware.
+pub fn lap_history_routes() -> Router<Database> {
+    Router::new().route(
+        "/races/:race_uuid/players/:player_uuid/lap-history",
+        get(get_lap_history),
+    )
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = v2::LapHistoryResponse)]
+pub struct LapHistoryResponse {
+    #[schema(value_type = Vec<v2::LapRecord>)]
+    pub laps: Vec<LapRecord>,
+    pub cycle_summaries: Vec<CycleSummary>,
+    /// Cursor for the next page of `laps`; `None` on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// Boost card played on a lap; values and movements of past laps are not stored
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = v2::LapRecord)]
+pub struct LapRecord {
+    pub lap_number: u32,
+    pub lap_characteristic: LapCharacteristic,
+    pub boost_used: u8,
+    pub boost_cycle: u32,
+}
+
+/// Lap history without the values and movements past laps do not store
+#[utoipa::path(
+    get,
+    path = "/api/v2/races/{race_uuid}/players/{player_uuid}/lap-history",
+    params(
+        ("race_uuid" = String, Path, description = "Race UUID"),
+        ("player_uuid" = String, Path, description = "Player UUID"),
+        CursorQuery
+    ),
+    responses(
+        (status = 200, description = "Boost cards played per lap and cycle", body = v2::LapHistoryResponse),
+        (status = 400, description = "Invalid UUID format or cursor", body = ErrorResponse),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "API token lacks the read:races scope"),
+        (status = 404, description = "Player not found in race or race not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "races"
+)]
+pub async fn get_lap_history(
+    State(database): State<Database>,
+    Path((race_uuid_str, player_uuid_str)): Path<(String, String)>,
+    Query(page): Query<CursorQuery>,
+    user: Option<Extension<UserContext>>,
+) -> Result<Json<LapHistoryResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let viewer = user.map(|Extension(user)| user.user_uuid);
+    races::lap_history(database, race_uuid_str, player_uuid_str, page, viewer).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn v1_lap_history_keeps_its_placeholders() {
+        let v1 = races::LapHistoryResponse::from(LapHistoryResponse {
+            laps: vec![LapRecord {
+                lap_number: 2,
+                lap_characteristic: LapCharacteristic::Curve,
+                boost_used: 3,
+                boost_cycle: 1,
+            }],
+            cycle_summaries: Vec::new(),
+            next_cursor: None,
+        });
+
+        let lap = serde_json::to_value(&v1.laps[0]).unwrap();
+        assert_eq!(lap["lap_characteristic"], "Curve");
+        assert_eq!(lap["movement_type"], "Unknown");
+        assert_eq!(lap["final_value"], 0);
+    }
+}
@@ -0,0 +1,482 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+    routing::{delete, get},
+    Extension, Router,
+};
+use chrono::{DateTime, Utc};
+use mongodb::{
+    bson::{doc, DateTime as BsonDateTime},
+    options::FindOptions,
+    Database,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::{DeliveryStatus, Race, RaceStatus, Webhook, WebhookDelivery, WebhookEvent};
+use crate::middleware::UserContext;
+use crate::routes::races::{get_race_by_uuid, ErrorResponse};
+use crate::services::webhooks::{check_webhook_url, WEBHOOKS, WEBHOOK_DELIVERIES};
+use crate::services::{record_audit, AuditEntry};
+
+const MAX_WEBHOOKS_PER_RACE: u64 = 5;
+/// Most deliveries listed at once
+const MAX_LISTED_DELIVERIES: i64 = 50;
+
+type ApiError = (StatusCode, Json<ErrorResponse>);
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RegisterWebhookRequest {
+    /// `http` or `https` URL the events are posted to, on a public address
+    pub url: String,
+    /// Events to receive; every event when omitted
+    #[serde(default)]
+    pub events: Option<Vec<WebhookEvent>>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WebhookResponse {
+    pub uuid: String,
+    pub race_uuid: String,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<&Webhook> for WebhookResponse {
+    fn from(webhook: &Webhook) -> Self {
+        Self {
+            uuid: webhook.uuid.to_string(),
+            race_uuid: webhook.race_uuid.to_string(),
+            url: webhook.url.clone(),
+            events: webhook.events.clone(),
+            created_at: to_utc(webhook.created_at),
+        }
+    }
+}
+
+/// Registered webhook with its signing secret, which is only shown once
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RegisteredWebhookResponse {
+    pub webhook: WebhookResponse,
+    /// Key of the `X-Webhook-Signature` HMAC-SHA256 over `"{timestamp}.{body}"`
+    pub secret: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WebhookDeliveryResponse {
+    pub uuid: String,
+    pub event: WebhookEvent,
+    pub status: DeliveryStatus,
+    pub attempts: u32,
+    pub last_status_code: Option<u16>,
+    pub last_error: Option<String>,
+    /// When the next attempt is due, while the delivery is pending
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+impl From<WebhookDelivery> for WebhookDeliveryResponse {
+    fn from(delivery: WebhookDelivery) -> Self {
+        Self {
+            uuid: delivery.uuid.to_string(),
+            event: delivery.event,
+            status: delivery.status,
+            attempts: delivery.attempts,
+            last_status_code: delivery.last_status_code,
+            last_error: delivery.last_error,
+            next_attempt_at: (delivery.status == DeliveryStatus::Pending)
+                .then(|| to_utc(delivery.next_attempt_at)),
+            created_at: to_utc(delivery.created_at),
+            delivered_at: delivery.delivered_at.map(to_utc),
+        }
+    }
+}
+
+fn to_utc(date: BsonDateTime) -> DateTime<Utc> {
+    DateTime::from_timestamp_millis(date.timestamp_millis()).unwrap_or_default()
+}
+
+/// Webhook routes; mounted behind `AuthMiddleware`, since only the race
+/// creator manages them
+pub fn routes() -> Router<Database> {
+    Router::new()
+        .route(
+            "/races/:race_uuid/webhooks",
+            get(list_webhooks).post(register_webhook),
+        )
+        .route(
+            "/races/:race_uuid/webhooks/:webhook_uuid",
+            delete(remove_webhook),
+        )
+        .route(
+            "/races/:race_uuid/webhooks/:webhook_uuid/deliveries",
+            get(list_webhook_deliveries),
+        )
+}
+
+fn error(status: StatusCode, error: &str, message: &str) -> ApiError {
+    (
+        status,
+        Json(ErrorResponse {
+            error: error.to_string(),
+            message: message.to_string(),
+            details: None,
+        }),
+    )
+}
+
+fn database_error(e: &mongodb::error::Error) -> ApiError {
+    tracing::error!("Webhook storage failed: {:?}", e);
+    error(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "DATABASE_ERROR",
+        "Internal server error",
+    )
+}
+
+/// Only the race creator manages its webhooks
+fn require_creator(race: &Race, user: &UserContext) -> Result<(), ApiError> {
+    if race.creator() == Some(user.user_uuid) {
+        return Ok(());
+    }
+    Err(error(
+        StatusCode::FORBIDDEN,
+        "NOT_RACE_CREATOR",
+        "Only the race creator can manage its webhooks",
+    ))
+}
+
+/// Race whose creator is the authenticated player
+async fn race_of_creator(
+    database: &Database,
+    race_uuid: &str,
+    user: &UserContext,
+) -> Result<Race, ApiError> {
+    let Ok(race_uuid) = Uuid::parse_str(race_uuid) else {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_UUID",
+            "Invalid UUID format",
+        ));
+    };
+    let Some(race) = get_race_by_uuid(database, race_uuid)
+        .await
+        .map_err(|e| database_error(&e))?
+    else {
+        return Err(error(
+            StatusCode::NOT_FOUND,
+            "RACE_NOT_FOUND",
+            "Race not found",
+        ));
+    };
+    require_creator(&race, user)?;
+    Ok(race)
+}
+
+/// Webhook of the race, or 404
+async fn webhook_of_race(
+    database: &Database,
+    race: &Race,
+    webhook_uuid: &str,
+) -> Result<Webhook, ApiError> {
+    let not_found = || {
+        error(
+            StatusCode::NOT_FOUND,
+            "WEBHOOK_NOT_FOUND",
+            "Webhook not found",
+        )
+    };
+    let webhook_uuid = Uuid::parse_str(webhook_uuid).map_err(|_| not_found())?;
+    database
+        .collection::<Webhook>(WEBHOOKS)
+        .find_one(
+            doc! { "uuid": webhook_uuid.to_string(), "race_uuid": race.uuid.to_string() },
+            None,
+        )
+        .await
+        .map_err(|e| database_error(&e))?
+        .ok_or_else(not_found)
+}
+
+/// Register a webhook for a race
+///
+/// The URL receives a signed JSON POST when the race starts, after each
+/// processed lap and when it finishes. Each request carries
+/// `X-Webhook-Event`, `X-Webhook-Delivery`, `X-Webhook-Timestamp` and
+/// `X-Webhook-Signature` (`sha256=` and the hex HMAC-SHA256 of
+/// `"{timestamp}.{body}"` keyed with the returned secret). Failed deliveries
+/// are retried with exponential backoff.
+///
+/// Only the race creator (its first human participant), identified by their
+/// access token, can register webhooks, at most five per race.
+#[utoipa::path(
+    post,
+    path = "/api/v1/races/{race_uuid}/webhooks",
+    params(
+        ("race_uuid" = String, Path, description = "Race UUID")
+    ),
+    request_body = RegisterWebhookRequest,
+    responses(
+        (status = 201, description = "Webhook registered", body = RegisteredWebhookResponse),
+        (status = 400, description = "Invalid UUID, URL or events", body = ErrorResponse),
+        (status = 401, description = "Authentication required"),
+        (status = 403, description = "Player is not the race creator", body = ErrorResponse),
+        (status = 404, description = "Race not found", body = ErrorResponse),
+        (status = 409, description = "Race is over or has too many webhooks", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "races"
+)]
+#[tracing::instrument(name = "Registering race webhook", skip(database, user, payload))]
+pub async fn register_webhook(
+    State(database): State<Database>,
+    Extension(user): Extension<UserContext>,
+    Path(race_uuid): Path<String>,
+    Json(payload): Json<RegisterWebhookRequest>,
+) -> Result<(StatusCode, Json<RegisteredWebhookResponse>), ApiError> {
+    let race = race_of_creator(&database, &race_uuid, &user).await?;
+    if race.status == RaceStatus::Finished || race.status == RaceStatus::Cancelled {
+        return Err(error(
+            StatusCode::CONFLICT,
+            "RACE_OVER",
+            "The race is over and will not send any more events",
+        ));
+    }
+
+    if let Err(e) = check_webhook_url(&payload.url).await {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_URL",
+            &e.to_string(),
+        ));
+    }
+    let events: Vec<WebhookEvent> = match payload.events {
+        None => WebhookEvent::ALL.to_vec(),
+        Some(events) if events.is_empty() => {
+            return Err(error(
+                StatusCode::BAD_REQUEST,
+                "INVALID_EVENTS",
+                "events must not be empty",
+            ));
+        }
+        Some(events) => WebhookEvent::ALL
+            .into_iter()
+            .filter(|event| events.contains(event))
+            .collect(),
+    };
+
+    let collection = database.collection::<Webhook>(WEBHOOKS);
+    let registered = collection
+        .count_documents(doc! { "race_uuid": race.uuid.to_string() }, None)
+        .await
+        .map_err(|e| database_error(&e))?;
+    if registered >= MAX_WEBHOOKS_PER_RACE {
+        return Err(error(
+            StatusCode::CONFLICT,
+            "TOO_MANY_WEBHOOKS",
+            "A race can have at most 5 webhooks",
+        ));
+    }
+
+    let creator = user.user_uuid;
+    let webhook = Webhook::new(race.uuid, creator, payload.url, events);
+    collection
+        .insert_one(&webhook, None)
+        .await
+        .map_err(|e| database_error(&e))?;
+    let audit = AuditEntry::new("webhook.register")
+        .by(creator)
+        .race(race.uuid);
+    record_audit(
+        &database,
+        audit.values(None, Some(&webhook)).redact("secret"),
+    )
+    .await;
+    tracing::info!("Webhook {} registered for race {}", webhook.uuid, race.uuid);
+
+    Ok((
+        StatusCode::CREATED,
+        Json(RegisteredWebhookResponse {
+            webhook: WebhookResponse::from(&webhook),
+            secret: webhook.secret,
+        }),
+    ))
+}
+
+/// List the webhooks of a race
+#[utoipa::path(
+    get,
+    path = "/api/v1/races/{race_uuid}/webhooks",
+    params(
+        ("race_uuid" = String, Path, description = "Race UUID")
+    ),
+    responses(
+        (status = 200, description = "Webhooks of the race", body = Vec<WebhookResponse>),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse),
+        (status = 401, description = "Authentication required"),
+        (status = 403, description = "Player is not the race creator", body = ErrorResponse),
+        (status = 404, description = "Race not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "races"
+)]
+#[tracing::instrument(name = "Listing race webhooks", skip(database, user))]
+pub async fn list_webhooks(
+    State(database): State<Database>,
+    Extension(user): Extension<UserContext>,
+    Path(race_uuid): Path<String>,
+) -> Result<Json<Vec<WebhookResponse>>, ApiError> {
+    let race = race_of_creator(&database, &race_uuid, &user).await?;
+
+    let options = FindOptions::builder()
+        .sort(doc! { "created_at": 1 })
+        .build();
+    let mut cursor = database
+        .collection::<Webhook>(WEBHOOKS)
+        .find(doc! { "race_uuid": race.uuid.to_string() }, options)
+        .await
+        .map_err(|e| database_error(&e))?;
+    let mut webhooks = Vec::new();
+    while cursor.advance().await.map_err(|e| database_error(&e))? {
+        let webhook: Webhook = cursor
+            .deserialize_current()
+            .map_err(|e| database_error(&e))?;
+        webhooks.push(WebhookResponse::from(&webhook));
+    }
+    Ok(Json(webhooks))
+}
+
+/// Remove a webhook from a race
+///
+/// Deliveries still queued for it are marked failed on their next attempt.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/races/{race_uuid}/webhooks/{webhook_uuid}",
+    params(
+        ("race_uuid" = String, Path, description = "Race UUID"),
+        ("webhook_uuid" = String, Path, description = "Webhook UUID")
+    ),
+    responses(
+        (status = 204, description = "Webhook removed"),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse),
+        (status = 401, description = "Authentication required"),
+        (status = 403, description = "Player is not the race creator", body = ErrorResponse),
+        (status = 404, description = "Race or webhook not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "races"
+)]
+#[tracing::instrument(name = "Removing race webhook", skip(database, user))]
+pub async fn remove_webhook(
+    State(database): State<Database>,
+    Extension(user): Extension<UserContext>,
+    Path((race_uuid, webhook_uuid)): Path<(String, String)>,
+) -> Result<StatusCode, ApiError> {
+    let race = race_of_creator(&database, &race_uuid, &user).await?;
+    let webhook = webhook_of_race(&database, &race, &webhook_uuid).await?;
+
+    database
+        .collection::<Webhook>(WEBHOOKS)
+        .delete_one(doc! { "uuid": webhook.uuid.to_string() }, None)
+        .await
+        .map_err(|e| database_error(&e))?;
+    let audit = AuditEntry::new("webhook.delete")
+        .by(webhook.player_uuid)
+        .race(race.uuid);
+    record_audit(
+        &database,
+        audit.values(Some(&webhook), None).redact("secret"),
+    )
+    .await;
+    tracing::info!("Webhook {} removed from race {}", webhook.uuid, race.uuid);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List the latest deliveries of a webhook
+///
+/// Shows up to 50 deliveries, newest first, with their status, attempts and
+/// the last error.
+#[utoipa::path(
+    get,
+    path = "/api/v1/races/{race_uuid}/webhooks/{webhook_uuid}/deliveries",
+    params(
+        ("race_uuid" = String, Path, description = "Race UUID"),
+        ("webhook_uuid" = String, Path, description = "Webhook UUID")
+    ),
+    responses(
+        (status = 200, description = "Deliveries of the webhook", body = Vec<WebhookDeliveryResponse>),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse),
+        (status = 401, description = "Authentication required"),
+        (status = 403, description = "Player is not the race creator", body = ErrorResponse),
+        (status = 404, description = "Race or webhook not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "races"
+)]
+#[tracing::instrument(name = "Listing webhook deliveries", skip(database, user))]
+pub async fn list_webhook_deliveries(
+    State(database): State<Database>,
+    Extension(user): Extension<UserContext>,
+    Path((race_uuid, webhook_uuid)): Path<(String, String)>,
+) -> Result<Json<Vec<WebhookDeliveryResponse>>, ApiError> {
+    let race = race_of_creator(&database, &race_uuid, &user).await?;
+    let webhook = webhook_of_race(&database, &race, &webhook_uuid).await?;
+
+    let options = FindOptions::builder()
+        .sort(doc! { "created_at": -1, "_id": -1 })
+        .limit(MAX_LISTED_DELIVERIES)
+        .build();
+    let mut cursor = database
+        .collection::<WebhookDelivery>(WEBHOOK_DELIVERIES)
+        .find(doc! { "webhook_uuid": webhook.uuid.to_string() }, options)
+        .await
+        .map_err(|e| database_error(&e))?;
+    let mut deliveries = Vec::new();
+    while cursor.advance().await.map_err(|e| database_error(&e))? {
+        let delivery: WebhookDelivery = cursor
+            .deserialize_current()
+            .map_err(|e| database_error(&e))?;
+        deliveries.push(delivery.into());
+    }
+    Ok(Json(deliveries))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{builtin_track_templates, UserRole};
+
+    fn user(user_uuid: Uuid) -> UserContext {
+        UserContext {
+            user_uuid,
+            email: "creator@example.com".to_string(),
+            role: UserRole::Player,
+            token_id: "token".to_string(),
+            device_fingerprint: None,
+            permissions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn only_the_race_creator_manages_webhooks() {
+        let track = builtin_track_templates()[0].to_track(None).unwrap();
+        let mut race = Race::new("Webhooks".to_string(), track, 3);
+        let (creator, rival) = (Uuid::new_v4(), Uuid::new_v4());
+        for player_uuid in [creator, rival] {
+            race.add_participant(player_uuid, Uuid::new_v4(), Uuid::new_v4())
+                .unwrap();
+        }
+
+        assert!(require_creator(&race, &user(creator)).is_ok());
+        for player_uuid in [rival, Uuid::new_v4()] {
+            let (status, Json(body)) =
+                require_creator(&race, &user(player_uuid)).expect_err("not the creator");
+            assert_eq!(status, StatusCode::FORBIDDEN);
+            assert_eq!(body.error, "NOT_RACE_CREATOR");
+        }
+    }
+}
@@ -0,0 +1,339 @@
+use anyhow::{anyhow, bail, Context};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use secrecy::{ExposeSecret, Secret};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+use std::time::Duration;
+
+use crate::configuration::{
+    AwsSecretsSettings, JwtKeySettings, SecretProvider, SecretSettings, Settings, VaultSettings,
+};
+
+const SECRET_STORE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Secrets kept in the secret store, as one JSON object
+///
+/// ```json
+/// { "mongo_uri": "mongodb://...", "jwt_keys": [{ "kid": "2024-06", "secret": "..." }] }
+/// ```
+///
+/// Both are optional; what is missing is left as configured.
+#[derive(Deserialize, Default)]
+pub struct StoredSecrets {
+    #[serde(default)]
+    pub mongo_uri: Option<Secret<String>>,
+    /// Newest first, as in [`crate::configuration::JwtSettings`]
+    #[serde(default)]
+    pub jwt_keys: Vec<JwtKeySettings>,
+}
+
+impl StoredSecrets {
+    pub fn apply(self, settings: &mut Settings) {
+        if let Some(uri) = self.mongo_uri {
+            settings.database.uri = Some(uri);
+        }
+        if !self.jwt_keys.is_empty() {
+            settings.jwt.keys = self.jwt_keys;
+        }
+    }
+}
+
+/// Fill in `settings` from the configured secret store, if any
+///
+/// The store is read on a thread of its own, as configuration is loaded
+/// synchronously, possibly from within the async runtime.
+pub fn load_secrets(settings: &mut Settings) -> Result<(), anyhow::Error> {
+    if settings.secrets.provider == SecretProvider::None {
+        return Ok(());
+    }
+    let secret_settings = settings.secrets.clone();
+    let secrets = std::thread::spawn(move || {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?
+            .block_on(fetch_secrets(&secret_settings))
+    })
+    .join()
+    .map_err(|_| anyhow!("Secret store thread panicked"))??;
+    secrets.apply(settings);
+    Ok(())
+}
+
+/// Read the secrets from the configured store
+pub async fn fetch_secrets(settings: &SecretSettings) -> Result<StoredSecrets, anyhow::Error> {
+    let client = reqwest::Client::builder()
+        .timeout(SECRET_STORE_TIMEOUT)
+        .build()?;
+    let secrets = match settings.provider {
+        SecretProvider::None => return Ok(StoredSecrets::default()),
+        SecretProvider::Vault => {
+            let vault = settings
+                .vault
+                .as_ref()
+                .context("`secrets.vault` is required by the vault provider")?;
+            fetch_from_vault(&client, vault).await?
+        }
+        SecretProvider::Aws => {
+            let aws = settings
+                .aws
+                .as_ref()
+                .context("`secrets.aws` is required by the aws provider")?;
+            fetch_from_aws(&client, aws, &AwsCredentials::from_env()?, Utc::now()).await?
+        }
+    };
+    tracing::info!(
+        "Loaded secrets from the {:?} secret store",
+        settings.provider
+    );
+    Ok(secrets)
+}
+
+#[derive(Deserialize)]
+struct VaultResponse {
+    data: VaultData,
+}
+
+#[derive(Deserialize)]
+struct VaultData {
+    data: StoredSecrets,
+}
+
+async fn fetch_from_vault(
+    client: &reqwest::Client,
+    vault: &VaultSettings,
+) -> Result<StoredSecrets, anyhow::Error> {
+    let token = match &vault.token {
+        Some(token) => token.expose_secret().clone(),
+        None => std::env::var("VAULT_TOKEN")
+            .context("Neither `secrets.vault.token` nor VAULT_TOKEN is set")?,
+    };
+    let url = format!(
+        "{}/v1/{}/data/{}",
+        vault.address.trim_end_matches('/'),
+        vault.mount.trim_matches('/'),
+        vault.path.trim_matches('/')
+    );
+    let response: VaultResponse = client
+        .get(&url)
+        .header("X-Vault-Token", token)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await
+        .context("Unexpected Vault response")?;
+    Ok(response.data.data)
+}
+
+/// Credentials signing AWS requests
+pub struct AwsCredentials {
+    pub access_key_id: String,
+    pub secret_access_key: Secret<String>,
+    pub session_token: Option<String>,
+}
+
+impl AwsCredentials {
+    pub fn from_env() -> Result<Self, anyhow::Error> {
+        Ok(Self {
+            access_key_id: std::env::var("AWS_ACCESS_KEY_ID")
+                .context("AWS_ACCESS_KEY_ID is not set")?,
+            secret_access_key: Secret::new(
+                std::env::var("AWS_SECRET_ACCESS_KEY")
+                    .context("AWS_SECRET_ACCESS_KEY is not set")?,
+            ),
+            session_token: std::env::var("AWS_SESSION_TOKEN").ok(),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GetSecretValueResponse {
+    secret_string: Option<String>,
+}
+
+async fn fetch_from_aws(
+    client: &reqwest::Client,
+    aws: &AwsSecretsSettings,
+    credentials: &AwsCredentials,
+    now: DateTime<Utc>,
+) -> Result<StoredSecrets, anyhow::Error> {
+    const TARGET: &str = "secretsmanager.GetSecretValue";
+    const CONTENT_TYPE: &str = "application/x-amz-json-1.1";
+
+    let host = format!("secretsmanager.{}.amazonaws.com", aws.region);
+    let body = serde_json::json!({ "SecretId": aws.secret_id }).to_string();
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+
+    let mut headers = vec![
+        ("content-type", CONTENT_TYPE.to_string()),
+        ("host", host.clone()),
+        ("x-amz-date", amz_date.clone()),
+        ("x-amz-target", TARGET.to_string()),
+    ];
+    if let Some(token) = &credentials.session_token {
+        headers.push(("x-amz-security-token", token.clone()));
+    }
+    headers.sort_unstable();
+    let authorization = sign_v4(
+        credentials,
+        &aws.region,
+        "secretsmanager",
+        "POST",
+        "/",
+        &headers,
+        &body,
+        now,
+    );
+
+    let mut request = client
+        .post(format!("https://{host}/"))
+        .header("Authorization", authorization)
+        .body(body);
+    for (name, value) in headers.into_iter().filter(|(name, _)| *name != "host") {
+        request = request.header(name, value);
+    }
+    let response: GetSecretValueResponse = request
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await
+        .context("Unexpected Secrets Manager response")?;
+    let Some(secret_string) = response.secret_string else {
+        bail!(
+            "Secret {} holds binary data, not a JSON string",
+            aws.secret_id
+        );
+    };
+    serde_json::from_str(&secret_string).context("The secret is not the expected JSON object")
+}
+
+/// `Authorization` header of a request signed with AWS Signature Version 4
+///
+/// `path` is the URI-encoded path of a request without a query string;
+/// `headers` are lowercase, sorted and include `host`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn sign_v4(
+    credentials: &AwsCredentials,
+    region: &str,
+    service: &str,
+    method: &str,
+    path: &str,
+    headers: &[(&str, String)],
+    body: &str,
+    now: DateTime<Utc>,
+) -> String {
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let scope = format!("{date}/{region}/{service}/aws4_request");
+
+    let canonical_headers = headers
+        .iter()
+        .fold(String::new(), |mut lines, (name, value)| {
+            let _ = writeln!(lines, "{name}:{}", value.trim());
+            lines
+        });
+    let signed_headers = headers
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(";");
+    let canonical_request = format!(
+        "{method}\n{path}\n\n{canonical_headers}\n{signed_headers}\n{}",
+        hex::encode(Sha256::digest(body))
+    );
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+        hex::encode(Sha256::digest(canonical_request))
+    );
+
+    let signing_key = signing_key(
+        credentials.secret_access_key.expose_secret(),
+        &date,
+        region,
+        service,
+    );
+    let signature = hex::encode(hmac_sha256(&signing_key, &string_to_sign));
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+        credentials.access_key_id
+    )
+}
+
+fn signing_key(secret_access_key: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let key = hmac_sha256(format!("AWS4{secret_access_key}").as_bytes(), date);
+    let key = hmac_sha256(&key, region);
+    let key = hmac_sha256(&key, service);
+    hmac_sha256(&key, "aws4_request")
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::HeaderMap, routing::get, Json, Router};
+
+    #[test]
+    fn signing_keys_match_the_aws_reference() {
+        // Example from the AWS Signature Version 4 documentation
+        let key = signing_key(
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            "20120215",
+            "us-east-1",
+            "iam",
+        );
+        assert_eq!(
+            hex::encode(key),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+    }
+
+    #[tokio::test]
+    async fn vault_secrets_are_read_with_the_token() {
+        let app = Router::new().route(
+            "/v1/kv/data/boardurance/prod",
+            get(|headers: HeaderMap| async move {
+                assert_eq!(headers["x-vault-token"], "s.test");
+                Json(serde_json::json!({ "data": { "data": {
+                    "mongo_uri": "mongodb://app:pw@mongo:27017",
+                    "jwt_keys": [
+                        { "kid": "2024-06", "secret": "new" },
+                        { "kid": "2024-01", "secret": "old" },
+                    ],
+                } } }))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let vault = VaultSettings {
+            address: format!("http://{address}/"),
+            token: Some(Secret::new("s.test".to_string())),
+            mount: "kv".to_string(),
+            path: "boardurance/prod".to_string(),
+        };
+        let secrets = fetch_from_vault(&reqwest::Client::new(), &vault)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            secrets.mongo_uri.unwrap().expose_secret(),
+            "mongodb://app:pw@mongo:27017"
+        );
+        let kids: Vec<_> = secrets
+            .jwt_keys
+            .iter()
+            .map(|key| key.kid.as_str())
+            .collect();
+        assert_eq!(kids, ["2024-06", "2024-01"]);
+    }
+}
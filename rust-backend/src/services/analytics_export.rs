@@ -0,0 +1,544 @@
+use anyhow::{bail, Context};
+use async_trait::async_trait;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use mongodb::{bson::doc, options::FindOptions, Database};
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::configuration::{
+    AnalyticsExportSettings, AnalyticsSinkKind, BigQueryExportSettings, S3ExportSettings,
+};
+use crate::database::RACES_ARCHIVE;
+use crate::domain::{
+    LapCharacteristic, MovementType, ParticipantMovement, Race, RaceMode, RaceStatus,
+};
+use crate::routes::stats::race_movements;
+use crate::secrets::{sign_v4, AwsCredentials};
+
+const GCP_TOKEN_URL: &str =
+    "http://metadata.google.internal/computeMetadata/v1/instance/service-accounts/default/token";
+
+/// One row shipped to the warehouse
+///
+/// `race` and `player` are pseudonyms: the same UUID always gives the same
+/// pseudonym under one key.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AnalyticsRecord {
+    /// Where a car went on one lap
+    LapResult {
+        race: String,
+        player: String,
+        lap: u32,
+        from_sector: u32,
+        to_sector: u32,
+        final_value: u32,
+        movement_type: MovementType,
+    },
+    /// A boost card played
+    BoostPick {
+        race: String,
+        player: String,
+        lap: u32,
+        cycle_number: u32,
+        boost_value: u8,
+        cards_remaining_after: u32,
+        lap_characteristic: Option<LapCharacteristic>,
+    },
+    /// How a participant's race ended
+    RaceOutcome {
+        race: String,
+        player: String,
+        mode: RaceMode,
+        rules_version: u32,
+        total_laps: u32,
+        sectors: u32,
+        participants: u32,
+        finish_position: Option<u32>,
+        laps_completed: u32,
+        bot: bool,
+    },
+}
+
+/// Replaces UUIDs by a keyed hash
+pub struct Pseudonymizer {
+    key: Secret<String>,
+}
+
+impl Pseudonymizer {
+    #[must_use]
+    pub fn new(key: Secret<String>) -> Self {
+        Self { key }
+    }
+
+    /// First 128 bits of the HMAC-SHA256 of `uuid`, in hex
+    #[must_use]
+    pub fn pseudonym(&self, uuid: Uuid) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.key.expose_secret().as_bytes())
+            .expect("HMAC accepts keys of any size");
+        mac.update(uuid.to_string().as_bytes());
+        hex::encode(&mac.finalize().into_bytes()[..16])
+    }
+}
+
+/// Records of a finished race, from its participants and the movements of
+/// its event log
+///
+/// Ghosts replay a race already recorded, so they are left out.
+#[must_use]
+pub fn race_records(
+    race: &Race,
+    movements: &[(u32, ParticipantMovement)],
+    pseudonymizer: &Pseudonymizer,
+) -> Vec<AnalyticsRecord> {
+    let race_pseudonym = pseudonymizer.pseudonym(race.uuid);
+    let racers: Vec<_> = race
+        .participants
+        .iter()
+        .filter(|p| p.ghost.is_none())
+        .collect();
+    let players: HashSet<Uuid> = racers.iter().map(|p| p.player_uuid).collect();
+    let count = |n: usize| u32::try_from(n).unwrap_or(u32::MAX);
+
+    let mut records: Vec<AnalyticsRecord> = movements
+        .iter()
+        .filter(|(_, movement)| players.contains(&movement.player_uuid))
+        .map(|(lap, movement)| AnalyticsRecord::LapResult {
+            race: race_pseudonym.clone(),
+            player: pseudonymizer.pseudonym(movement.player_uuid),
+            lap: *lap,
+            from_sector: movement.from_sector,
+            to_sector: movement.to_sector,
+            final_value: movement.final_value,
+            movement_type: movement.movement_type.clone(),
+        })
+        .collect();
+
+    for participant in &racers {
+        let player = pseudonymizer.pseudonym(participant.player_uuid);
+        records.extend(participant.boost_usage_history.iter().map(|usage| {
+            AnalyticsRecord::BoostPick {
+                race: race_pseudonym.clone(),
+                player: player.clone(),
+                lap: usage.lap_number,
+                cycle_number: usage.cycle_number,
+                boost_value: usage.boost_value,
+                cards_remaining_after: usage.cards_remaining_after,
+                lap_characteristic: usage.lap_characteristic.clone(),
+            }
+        }));
+        records.push(AnalyticsRecord::RaceOutcome {
+            race: race_pseudonym.clone(),
+            player,
+            mode: race.mode,
+            rules_version: race.rules_version,
+            total_laps: race.total_laps,
+            sectors: count(race.track.sectors.len()),
+            participants: count(racers.len()),
+            finish_position: participant.finish_position,
+            laps_completed: participant.current_lap,
+            bot: participant.bot.is_some(),
+        });
+    }
+    records
+}
+
+/// Warehouse the records are shipped to
+#[async_trait]
+pub trait AnalyticsSink: Send + Sync {
+    /// Store `records` as one batch
+    ///
+    /// A batch whose races are shipped again after a failure has the same
+    /// `batch_id`, for sinks able to drop duplicates.
+    async fn write(&self, batch_id: &str, records: &[AnalyticsRecord])
+        -> Result<(), anyhow::Error>;
+}
+
+/// Writes batches to the application log, for local development
+pub struct LogSink;
+
+#[async_trait]
+impl AnalyticsSink for LogSink {
+    async fn write(
+        &self,
+        batch_id: &str,
+        records: &[AnalyticsRecord],
+    ) -> Result<(), anyhow::Error> {
+        tracing::info!(
+            "Analytics batch {}: {}",
+            batch_id,
+            serde_json::to_string(records)?
+        );
+        Ok(())
+    }
+}
+
+/// Puts each batch in an S3 bucket as `{prefix}/{batch_id}.ndjson`
+pub struct S3Sink {
+    client: reqwest::Client,
+    settings: S3ExportSettings,
+}
+
+#[async_trait]
+impl AnalyticsSink for S3Sink {
+    async fn write(
+        &self,
+        batch_id: &str,
+        records: &[AnalyticsRecord],
+    ) -> Result<(), anyhow::Error> {
+        let S3ExportSettings {
+            bucket,
+            region,
+            prefix,
+        } = &self.settings;
+        let credentials = AwsCredentials::from_env()?;
+        let body = newline_delimited(records)?;
+        let host = format!("{bucket}.s3.{region}.amazonaws.com");
+        let path = format!("/{}/{batch_id}.ndjson", prefix.trim_matches('/'));
+        let now = Utc::now();
+
+        let mut headers = vec![
+            ("content-type", "application/x-ndjson".to_string()),
+            ("host", host.clone()),
+            ("x-amz-content-sha256", hex::encode(Sha256::digest(&body))),
+            ("x-amz-date", now.format("%Y%m%dT%H%M%SZ").to_string()),
+        ];
+        if let Some(token) = &credentials.session_token {
+            headers.push(("x-amz-security-token", token.clone()));
+        }
+        headers.sort_unstable();
+        let authorization = sign_v4(
+            &credentials,
+            region,
+            "s3",
+            "PUT",
+            &path,
+            &headers,
+            &body,
+            now,
+        );
+
+        let mut request = self
+            .client
+            .put(format!("https://{host}{path}"))
+            .header("Authorization", authorization)
+            .body(body);
+        for (name, value) in headers.into_iter().filter(|(name, _)| *name != "host") {
+            request = request.header(name, value);
+        }
+        request.send().await?.error_for_status()?;
+        Ok(())
+    }
+}
+
+/// Streams batches into a `BigQuery` table
+///
+/// Rows get an `insertId` made of the batch id and their index, so `BigQuery`
+/// drops a batch shipped twice in a short while.
+pub struct BigQuerySink {
+    client: reqwest::Client,
+    settings: BigQueryExportSettings,
+}
+
+#[derive(Deserialize)]
+struct AccessToken {
+    access_token: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InsertAllResponse {
+    #[serde(default)]
+    insert_errors: Vec<serde_json::Value>,
+}
+
+impl BigQuerySink {
+    async fn access_token(&self) -> Result<String, anyhow::Error> {
+        if let Some(token) = &self.settings.access_token {
+            return Ok(token.expose_secret().clone());
+        }
+        let token: AccessToken = self
+            .client
+            .get(GCP_TOKEN_URL)
+            .header("Metadata-Flavor", "Google")
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .context("Unexpected GCP metadata server response")?;
+        Ok(token.access_token)
+    }
+}
+
+#[async_trait]
+impl AnalyticsSink for BigQuerySink {
+    async fn write(
+        &self,
+        batch_id: &str,
+        records: &[AnalyticsRecord],
+    ) -> Result<(), anyhow::Error> {
+        let BigQueryExportSettings {
+            project_id,
+            dataset_id,
+            table_id,
+            ..
+        } = &self.settings;
+        let url = format!(
+            "https://bigquery.googleapis.com/bigquery/v2/projects/{project_id}/datasets/{dataset_id}/tables/{table_id}/insertAll"
+        );
+        let rows: Vec<_> = records
+            .iter()
+            .enumerate()
+            .map(|(index, record)| {
+                serde_json::json!({ "insertId": format!("{batch_id}-{index}"), "json": record })
+            })
+            .collect();
+        let response: InsertAllResponse = self
+            .client
+            .post(&url)
+            .bearer_auth(self.access_token().await?)
+            .json(&serde_json::json!({ "rows": rows }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .context("Unexpected BigQuery response")?;
+        if !response.insert_errors.is_empty() {
+            bail!(
+                "BigQuery refused {} rows: {}",
+                response.insert_errors.len(),
+                serde_json::Value::from(response.insert_errors)
+            );
+        }
+        Ok(())
+    }
+}
+
+fn newline_delimited(records: &[AnalyticsRecord]) -> Result<String, serde_json::Error> {
+    let mut lines = String::new();
+    for record in records {
+        lines.push_str(&serde_json::to_string(record)?);
+        lines.push('\n');
+    }
+    Ok(lines)
+}
+
+/// Ships finished races to the configured sink
+pub struct AnalyticsExporter {
+    sink: Box<dyn AnalyticsSink>,
+    pseudonymizer: Pseudonymizer,
+    batch_size: u32,
+}
+
+impl AnalyticsExporter {
+    pub fn from_settings(settings: &AnalyticsExportSettings) -> Result<Self, anyhow::Error> {
+        let Some(key) = settings
+            .pseudonym_key
+            .clone()
+            .filter(|key| !key.expose_secret().is_empty())
+        else {
+            bail!("The analytics export requires `analytics_export.pseudonym_key`");
+        };
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(settings.timeout_seconds))
+            .build()?;
+        let sink: Box<dyn AnalyticsSink> = match settings.sink {
+            AnalyticsSinkKind::Log => Box::new(LogSink),
+            AnalyticsSinkKind::S3 => Box::new(S3Sink {
+                client,
+                settings: settings
+                    .s3
+                    .clone()
+                    .context("The s3 analytics sink requires `analytics_export.s3`")?,
+            }),
+            AnalyticsSinkKind::Bigquery => Box::new(BigQuerySink {
+                client,
+                settings: settings
+                    .bigquery
+                    .clone()
+                    .context("The bigquery analytics sink requires `analytics_export.bigquery`")?,
+            }),
+        };
+        Ok(Self::new(
+            sink,
+            Pseudonymizer::new(key),
+            settings.batch_size,
+        ))
+    }
+
+    #[must_use]
+    pub fn new(
+        sink: Box<dyn AnalyticsSink>,
+        pseudonymizer: Pseudonymizer,
+        batch_size: u32,
+    ) -> Self {
+        Self {
+            sink,
+            pseudonymizer,
+            batch_size,
+        }
+    }
+
+    /// Ship up to `batch_size` finished races not shipped yet, live ones
+    /// first, then archived ones
+    ///
+    /// A batch the sink fails for is logged and its races are tried again on
+    /// the next run. Returns the number of races shipped.
+    pub async fn export_finished(&self, database: &Database) -> Result<u64, mongodb::error::Error> {
+        let filter = doc! {
+            "status": mongodb::bson::to_bson(&RaceStatus::Finished)?,
+            "analytics_exported": { "$ne": true },
+        };
+        let mut races = Vec::new();
+        for collection in ["races", RACES_ARCHIVE] {
+            let remaining = i64::from(self.batch_size) - i64::try_from(races.len()).unwrap_or(0);
+            if remaining <= 0 {
+                break;
+            }
+            let options = FindOptions::builder()
+                .sort(doc! { "updated_at": 1 })
+                .limit(remaining)
+                .build();
+            let mut cursor = database
+                .collection::<Race>(collection)
+                .find(filter.clone(), options)
+                .await?;
+            while cursor.advance().await? {
+                races.push((collection, cursor.deserialize_current()?));
+            }
+        }
+        if races.is_empty() {
+            return Ok(0);
+        }
+
+        let mut records = Vec::new();
+        let mut batch_hash = Sha256::new();
+        for (_, race) in &races {
+            let movements = race_movements(database, race).await?;
+            records.extend(race_records(race, &movements, &self.pseudonymizer));
+            batch_hash.update(race.uuid.as_bytes());
+        }
+        let batch_id = hex::encode(&batch_hash.finalize()[..16]);
+        if let Err(e) = self.sink.write(&batch_id, &records).await {
+            tracing::warn!("Failed to ship analytics batch {}: {:?}", batch_id, e);
+            return Ok(0);
+        }
+
+        for (collection, race) in &races {
+            database
+                .collection::<Race>(collection)
+                .update_one(
+                    doc! { "uuid": race.uuid.to_string() },
+                    doc! { "$set": { "analytics_exported": true } },
+                    None,
+                )
+                .await?;
+        }
+        tracing::debug!(
+            "Shipped analytics batch {} of {} records",
+            batch_id,
+            records.len()
+        );
+        Ok(u64::try_from(races.len()).unwrap_or(u64::MAX))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        BoostUsageRecord, CarLapValues, GhostCar, GhostReplay, Sector, SectorType, Track,
+    };
+
+    #[test]
+    fn records_carry_pseudonyms_and_leave_ghosts_out() {
+        let sector = |id, sector_type| Sector {
+            id,
+            name: format!("Sector {id}"),
+            min_value: id * 10,
+            max_value: id * 10 + 10,
+            slot_capacity: None,
+            sector_type,
+            capacity_schedule: Vec::new(),
+            modifiers: None,
+        };
+        let track = Track::new(
+            "Track".to_string(),
+            vec![sector(0, SectorType::Start), sector(1, SectorType::Finish)],
+        )
+        .unwrap();
+        let mut race = Race::new("Export race".to_string(), track, 1);
+        let (player, ghost) = (Uuid::new_v4(), Uuid::new_v4());
+        for uuid in [player, ghost] {
+            race.add_participant(uuid, Uuid::new_v4(), Uuid::new_v4())
+                .unwrap();
+        }
+        race.participants[0].finish_position = Some(1);
+        race.participants[0]
+            .boost_usage_history
+            .push(BoostUsageRecord {
+                lap_number: 1,
+                boost_value: 3,
+                cycle_number: 1,
+                cards_remaining_after: 4,
+                replenishment_occurred: false,
+                lap_characteristic: Some(LapCharacteristic::Curve),
+            });
+        let values = CarLapValues {
+            engine: 5,
+            body: 5,
+            pilot: 5,
+        };
+        race.participants[1].ghost = Some(GhostReplay {
+            ghost_uuid: Uuid::new_v4(),
+            car: GhostCar {
+                straight: values,
+                curve: values,
+            },
+            boosts: Vec::new(),
+        });
+        let movement = |player_uuid| ParticipantMovement {
+            player_uuid,
+            from_sector: 0,
+            to_sector: 1,
+            final_value: 12,
+            movement_type: MovementType::MovedUp,
+            steps: Vec::new(),
+        };
+        let movements = vec![(1, movement(player)), (1, movement(ghost))];
+        let pseudonymizer = Pseudonymizer::new(Secret::new("key".to_string()));
+
+        let records = race_records(&race, &movements, &pseudonymizer);
+
+        let pseudonym = pseudonymizer.pseudonym(player);
+        assert_eq!(pseudonym, pseudonymizer.pseudonym(player));
+        assert_ne!(
+            pseudonym,
+            Pseudonymizer::new(Secret::new("other".to_string())).pseudonym(player)
+        );
+        assert_eq!(records.len(), 3);
+        assert!(records.iter().all(|record| match record {
+            AnalyticsRecord::LapResult { player, .. }
+            | AnalyticsRecord::BoostPick { player, .. }
+            | AnalyticsRecord::RaceOutcome { player, .. } => *player == pseudonym,
+        }));
+        let exported = serde_json::to_string(&records).unwrap();
+        assert!(!exported.contains(&player.to_string()));
+        assert!(!exported.contains(&race.uuid.to_string()));
+        assert!(matches!(
+            records[2],
+            AnalyticsRecord::RaceOutcome {
+                participants: 1,
+                finish_position: Some(1),
+                ..
+            }
+        ));
+    }
+}
@@ -0,0 +1,130 @@
+use mongodb::{
+    bson::{doc, DateTime as BsonDateTime},
+    options::FindOptions,
+    Database,
+};
+use secrecy::{ExposeSecret, Secret};
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::configuration::AnchoringSettings;
+use crate::domain::{anchor_hash, Race, RaceAnchor, RaceEvent, RaceStatus};
+
+/// Answer of the signing service
+#[derive(Debug, Deserialize)]
+struct SignedAnchor {
+    signature: String,
+}
+
+/// Writes the results of finished races to Solana through the signing service
+#[derive(Clone)]
+pub struct RaceAnchorer {
+    client: reqwest::Client,
+    signing_service_url: String,
+    signing_service_token: Option<Secret<String>>,
+}
+
+impl RaceAnchorer {
+    pub fn from_settings(settings: &AnchoringSettings) -> Result<Self, reqwest::Error> {
+        Ok(Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(settings.timeout_seconds))
+                .build()?,
+            signing_service_url: settings.signing_service_url.clone(),
+            signing_service_token: settings.signing_service_token.clone(),
+        })
+    }
+
+    /// Anchor up to `batch_size` finished races that have no anchor yet
+    ///
+    /// A race the signing service fails for is logged and tried again on the
+    /// next run. Returns the number of races anchored.
+    pub async fn anchor_finished(
+        &self,
+        database: &Database,
+        batch_size: u32,
+    ) -> Result<u64, mongodb::error::Error> {
+        let races = database.collection::<Race>("races");
+        let options = FindOptions::builder()
+            .sort(doc! { "updated_at": 1 })
+            .limit(i64::from(batch_size))
+            .build();
+        let mut cursor = races
+            .find(
+                doc! {
+                    "status": mongodb::bson::to_bson(&RaceStatus::Finished)?,
+                    "anchor": null,
+                },
+                options,
+            )
+            .await?;
+
+        let mut anchored = 0;
+        while cursor.advance().await? {
+            let race: Race = cursor.deserialize_current()?;
+            let events = race_events(database, &race).await?;
+            let hash = anchor_hash(&race, &events);
+            let transaction_signature = match self.sign(&race, &hash).await {
+                Ok(signature) => signature,
+                Err(e) => {
+                    tracing::warn!("Failed to anchor race {}: {}", race.uuid, e);
+                    continue;
+                }
+            };
+
+            let anchor = RaceAnchor {
+                hash,
+                transaction_signature,
+                anchored_at: BsonDateTime::now(),
+            };
+            races
+                .update_one(
+                    doc! { "uuid": race.uuid.to_string(), "anchor": null },
+                    doc! { "$set": { "anchor": mongodb::bson::to_bson(&anchor)? } },
+                    None,
+                )
+                .await?;
+            tracing::info!(
+                "Anchored race {} in transaction {}",
+                race.uuid,
+                anchor.transaction_signature
+            );
+            anchored += 1;
+        }
+        Ok(anchored)
+    }
+
+    /// Have the signing service write `hash` on-chain; returns the transaction signature
+    async fn sign(&self, race: &Race, hash: &str) -> Result<String, reqwest::Error> {
+        let mut request = self
+            .client
+            .post(&self.signing_service_url)
+            .json(&serde_json::json!({
+                "race_uuid": race.uuid.to_string(),
+                "hash": hash,
+            }));
+        if let Some(token) = &self.signing_service_token {
+            request = request.bearer_auth(token.expose_secret());
+        }
+        let signed: SignedAnchor = request.send().await?.error_for_status()?.json().await?;
+        Ok(signed.signature)
+    }
+}
+
+async fn race_events(
+    database: &Database,
+    race: &Race,
+) -> Result<Vec<RaceEvent>, mongodb::error::Error> {
+    let options = FindOptions::builder()
+        .sort(doc! { "occurred_at": 1, "_id": 1 })
+        .build();
+    let mut cursor = database
+        .collection::<RaceEvent>("race_events")
+        .find(doc! { "race_uuid": race.uuid.to_string() }, options)
+        .await?;
+    let mut events = Vec::new();
+    while cursor.advance().await? {
+        events.push(cursor.deserialize_current()?);
+    }
+    Ok(events)
+}
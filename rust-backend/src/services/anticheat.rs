@@ -0,0 +1,371 @@
+use mongodb::{
+    bson::{doc, DateTime as BsonDateTime},
+    options::FindOptions,
+    Database,
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::{Duration, SystemTime};
+use uuid::Uuid;
+
+use crate::configuration::AntiCheatSettings;
+use crate::database::is_duplicate_key;
+use crate::domain::{
+    CheatFlag, CheatFlagKind, ParticipantMovement, Race, RaceChange, RaceEvent, RaceStatus,
+};
+use crate::services::audit::AUDIT_LOG;
+use crate::services::{record_audit, AuditEntry};
+
+pub const CHEAT_FLAGS: &str = "cheat_flags";
+
+/// Delays, in milliseconds, of each player's submissions made sooner than
+/// `min_reaction_millis` after the lap they answer opened
+///
+/// `lap_openings` are sorted Unix milliseconds; a submission answers the
+/// latest lap opened before it.
+#[must_use]
+pub fn fast_submissions(
+    lap_openings: &[i64],
+    submissions: &[(Uuid, i64)],
+    min_reaction_millis: i64,
+) -> HashMap<Uuid, Vec<i64>> {
+    let mut fast: HashMap<Uuid, Vec<i64>> = HashMap::new();
+    for &(player_uuid, submitted_at) in submissions {
+        let opened = lap_openings.partition_point(|&opened_at| opened_at <= submitted_at);
+        let Some(&opened_at) = opened.checked_sub(1).and_then(|i| lap_openings.get(i)) else {
+            continue;
+        };
+        let delay = submitted_at - opened_at;
+        if delay < min_reaction_millis {
+            fast.entry(player_uuid).or_default().push(delay);
+        }
+    }
+    fast
+}
+
+/// Contested sectors each player raced in, and how many of them they won by
+/// at most `tight_margin`
+///
+/// A sector is contested on a lap when several cars started the lap in it;
+/// `movements` pairs each movement with its lap.
+#[must_use]
+pub fn contested_wins(
+    movements: &[(u32, &ParticipantMovement)],
+    tight_margin: u32,
+) -> HashMap<Uuid, (u32, u32)> {
+    let mut sectors: BTreeMap<(u32, u32), Vec<&ParticipantMovement>> = BTreeMap::new();
+    for &(lap, movement) in movements {
+        sectors
+            .entry((lap, movement.from_sector))
+            .or_default()
+            .push(movement);
+    }
+
+    let mut wins: HashMap<Uuid, (u32, u32)> = HashMap::new();
+    for mut cars in sectors.into_values().filter(|cars| cars.len() > 1) {
+        cars.sort_by_key(|car| std::cmp::Reverse(car.final_value));
+        let margin = cars[0].final_value - cars[1].final_value;
+        for (i, car) in cars.iter().enumerate() {
+            let entry = wins.entry(car.player_uuid).or_default();
+            entry.0 += 1;
+            if i == 0 && margin > 0 && margin <= tight_margin {
+                entry.1 += 1;
+            }
+        }
+    }
+    wins
+}
+
+/// Looks for cheating in finished races and device registrations
+pub struct AntiCheatAnalyzer {
+    settings: AntiCheatSettings,
+}
+
+impl AntiCheatAnalyzer {
+    #[must_use]
+    pub fn new(settings: AntiCheatSettings) -> Self {
+        Self { settings }
+    }
+
+    /// Analyse up to `batch_size` races finished within the lookback window,
+    /// then the devices registered within it
+    ///
+    /// Flags are unique per finding, so looking at the same data again raises
+    /// nothing new. Returns the number of flags raised.
+    pub async fn run(&self, database: &Database) -> Result<u64, mongodb::error::Error> {
+        let lookback = Duration::from_secs(self.settings.lookback_hours * 60 * 60);
+        let since = BsonDateTime::from_system_time(
+            SystemTime::now()
+                .checked_sub(lookback)
+                .unwrap_or(SystemTime::UNIX_EPOCH),
+        );
+
+        let races = database.collection::<Race>("races");
+        let options = FindOptions::builder()
+            .sort(doc! { "updated_at": 1 })
+            .limit(i64::from(self.settings.batch_size))
+            .build();
+        let mut cursor = races
+            .find(
+                doc! {
+                    "status": mongodb::bson::to_bson(&RaceStatus::Finished)?,
+                    "updated_at": { "$gte": since },
+                    "integrity_checked": { "$ne": true },
+                },
+                options,
+            )
+            .await?;
+
+        let mut flags = Vec::new();
+        while cursor.advance().await? {
+            let race: Race = cursor.deserialize_current()?;
+            flags.extend(self.analyse_race(database, &race).await?);
+            races
+                .update_one(
+                    doc! { "uuid": race.uuid.to_string() },
+                    doc! { "$set": { "integrity_checked": true } },
+                    None,
+                )
+                .await?;
+        }
+        flags.extend(shared_devices(database, since).await?);
+
+        let mut raised = 0;
+        for flag in flags {
+            if raise(database, &flag).await? {
+                raised += 1;
+            }
+        }
+        Ok(raised)
+    }
+
+    async fn analyse_race(
+        &self,
+        database: &Database,
+        race: &Race,
+    ) -> Result<Vec<CheatFlag>, mongodb::error::Error> {
+        let humans: HashSet<Uuid> = race
+            .participants
+            .iter()
+            .filter(|p| p.bot.is_none() && p.ghost.is_none())
+            .map(|p| p.player_uuid)
+            .collect();
+
+        let options = FindOptions::builder()
+            .sort(doc! { "occurred_at": 1, "_id": 1 })
+            .build();
+        let mut cursor = database
+            .collection::<RaceEvent>("race_events")
+            .find(doc! { "race_uuid": race.uuid.to_string() }, options)
+            .await?;
+        // Races open their first lap when created, or when the countdown ends
+        let mut lap_openings = vec![race.created_at.timestamp_millis()];
+        let mut events = Vec::new();
+        while cursor.advance().await? {
+            let event: RaceEvent = cursor.deserialize_current()?;
+            if matches!(event.change, RaceChange::PhaseChanged { .. }) {
+                lap_openings.push(event.occurred_at.timestamp_millis());
+            }
+            events.push(event);
+        }
+        let movements: Vec<(u32, &ParticipantMovement)> = events
+            .iter()
+            .filter_map(|event| match &event.change {
+                RaceChange::Movement { lap, movement } => Some((*lap, movement)),
+                _ => None,
+            })
+            .collect();
+
+        let mut cursor = database
+            .collection::<AuditEntry>(AUDIT_LOG)
+            .find(
+                doc! {
+                    "race_uuid": race.uuid.to_string(),
+                    "action": { "$in": ["race.submit_action", "race.lap_action"] },
+                },
+                None,
+            )
+            .await?;
+        let mut submissions = Vec::new();
+        while cursor.advance().await? {
+            let entry: AuditEntry = cursor.deserialize_current()?;
+            if let Some(actor) = entry.actor {
+                submissions.push((actor, entry.occurred_at.timestamp_millis()));
+            }
+        }
+
+        let settings = &self.settings;
+        let mut flags = Vec::new();
+        for (player_uuid, delays) in
+            fast_submissions(&lap_openings, &submissions, settings.min_reaction_millis)
+        {
+            if !humans.contains(&player_uuid) || delays.len() < settings.fast_submissions as usize {
+                continue;
+            }
+            let fastest = delays.iter().min().copied().unwrap_or_default();
+            let evidence = format!(
+                "{} actions submitted less than {} ms after their lap opened, the fastest after {} ms",
+                delays.len(),
+                settings.min_reaction_millis,
+                fastest
+            );
+            flags.push(race_flag(
+                CheatFlagKind::ImpossibleTiming,
+                player_uuid,
+                race,
+                evidence,
+            ));
+        }
+        for (player_uuid, (contested, tight_wins)) in
+            contested_wins(&movements, settings.tight_margin)
+        {
+            if !humans.contains(&player_uuid) || contested < settings.min_contested_laps {
+                continue;
+            }
+            if f64::from(tight_wins) / f64::from(contested) < settings.tight_win_ratio {
+                continue;
+            }
+            let evidence = format!(
+                "Won {} of {} contested sectors by at most {} over the runner-up",
+                tight_wins, contested, settings.tight_margin
+            );
+            flags.push(race_flag(
+                CheatFlagKind::SuspiciousBoosts,
+                player_uuid,
+                race,
+                evidence,
+            ));
+        }
+        Ok(flags)
+    }
+}
+
+fn race_flag(kind: CheatFlagKind, player_uuid: Uuid, race: &Race, evidence: String) -> CheatFlag {
+    let race_uuid = race.uuid.to_string();
+    let mut flag = CheatFlag::new(kind, player_uuid, &race_uuid, evidence);
+    flag.race_uuid = Some(race_uuid);
+    flag
+}
+
+/// Devices that moved from one account to another since `since`
+///
+/// Registering a device token already held by another player moves it, and
+/// the audit log keeps the previous owner.
+async fn shared_devices(
+    database: &Database,
+    since: BsonDateTime,
+) -> Result<Vec<CheatFlag>, mongodb::error::Error> {
+    let mut cursor = database
+        .collection::<AuditEntry>(AUDIT_LOG)
+        .find(
+            doc! {
+                "action": "player.register_device",
+                "occurred_at": { "$gte": since },
+                "previous.player_uuid": { "$exists": true },
+            },
+            None,
+        )
+        .await?;
+
+    let mut flags = Vec::new();
+    while cursor.advance().await? {
+        let entry: AuditEntry = cursor.deserialize_current()?;
+        let previous_owner = entry
+            .previous
+            .as_ref()
+            .and_then(|previous| previous.get_str("player_uuid").ok())
+            .map(str::to_string);
+        let (Some(player_uuid), Some(previous_owner)) = (entry.player_uuid, previous_owner) else {
+            continue;
+        };
+        let evidence = format!("Registered a device previously registered to {previous_owner}");
+        let mut flag = CheatFlag::new(
+            CheatFlagKind::SharedDevice,
+            player_uuid,
+            &previous_owner,
+            evidence,
+        );
+        flag.related_player_uuid = Some(previous_owner);
+        flags.push(flag);
+    }
+    Ok(flags)
+}
+
+/// Store `flag` and note it in the audit log; `false` if it was raised before
+async fn raise(database: &Database, flag: &CheatFlag) -> Result<bool, mongodb::error::Error> {
+    match database
+        .collection::<CheatFlag>(CHEAT_FLAGS)
+        .insert_one(flag, None)
+        .await
+    {
+        Ok(_) => {}
+        Err(e) if is_duplicate_key(&e) => return Ok(false),
+        Err(e) => return Err(e),
+    }
+
+    tracing::warn!(
+        "Flagged player {} for {:?}: {}",
+        flag.player_uuid,
+        flag.kind,
+        flag.evidence
+    );
+    let mut audit = AuditEntry::new("anticheat.flag")
+        .player(flag.player_uuid)
+        .values(None, Some(flag));
+    if let Some(race_uuid) = flag
+        .race_uuid
+        .as_deref()
+        .and_then(|uuid| Uuid::parse_str(uuid).ok())
+    {
+        audit = audit.race(race_uuid);
+    }
+    record_audit(database, audit).await;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::MovementType;
+
+    fn movement(player_uuid: Uuid, from_sector: u32, final_value: u32) -> ParticipantMovement {
+        ParticipantMovement {
+            player_uuid,
+            from_sector,
+            to_sector: from_sector,
+            final_value,
+            movement_type: MovementType::StayedInSector,
+            steps: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn submissions_right_after_the_lap_opened_are_too_fast() {
+        let (quick, human) = (Uuid::new_v4(), Uuid::new_v4());
+        let openings = [1_000, 20_000];
+        let submissions = [(quick, 1_050), (human, 4_000), (quick, 20_100)];
+
+        let fast = fast_submissions(&openings, &submissions, 300);
+
+        assert_eq!(fast.get(&quick), Some(&vec![50, 100]));
+        assert!(!fast.contains_key(&human));
+    }
+
+    #[test]
+    fn only_wins_by_a_hair_in_contested_sectors_count() {
+        let (sharp, rival, alone) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let movements = [
+            movement(sharp, 1, 21),
+            movement(rival, 1, 20),
+            movement(alone, 3, 40),
+        ];
+        let mut laps: Vec<(u32, &ParticipantMovement)> = movements.iter().map(|m| (1, m)).collect();
+        let clear_win = [movement(sharp, 2, 30), movement(rival, 2, 20)];
+        laps.extend(clear_win.iter().map(|m| (2, m)));
+
+        let wins = contested_wins(&laps, 1);
+
+        assert_eq!(wins.get(&sharp), Some(&(2, 1)));
+        assert_eq!(wins.get(&rival), Some(&(2, 0)));
+        assert!(!wins.contains_key(&alone));
+    }
+}
@@ -0,0 +1,260 @@
+use mongodb::{
+    bson::{doc, DateTime as BsonDateTime, Document},
+    options::FindOptions,
+    Database,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub const AUDIT_LOG: &str = "audit_log";
+
+/// One mutating operation, as stored in the `audit_log` collection
+///
+/// `previous` and `new` hold only the top-level fields the operation changed,
+/// so a lap update does not copy the whole race twice. A created document has
+/// no `previous`, a deleted one no `new`.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct AuditEntry {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub id: Option<mongodb::bson::oid::ObjectId>,
+    #[schema(value_type = String, format = "date-time")]
+    pub occurred_at: BsonDateTime,
+    /// Operation, such as `race.join` or `player.add_car`
+    pub action: String,
+    /// Player the request acted for; absent for background jobs and
+    /// anonymous requests
+    #[serde(with = "option_uuid_as_string", default)]
+    #[schema(value_type = Option<String>)]
+    pub actor: Option<Uuid>,
+    #[serde(with = "option_uuid_as_string", default)]
+    #[schema(value_type = Option<String>)]
+    pub race_uuid: Option<Uuid>,
+    #[serde(with = "option_uuid_as_string", default)]
+    #[schema(value_type = Option<String>)]
+    pub player_uuid: Option<Uuid>,
+    #[serde(default)]
+    #[schema(value_type = Option<Object>)]
+    pub previous: Option<Document>,
+    #[serde(default)]
+    #[schema(value_type = Option<Object>)]
+    pub new: Option<Document>,
+}
+
+impl AuditEntry {
+    #[must_use]
+    pub fn new(action: &str) -> Self {
+        Self {
+            id: None,
+            occurred_at: BsonDateTime::now(),
+            action: action.to_string(),
+            actor: None,
+            race_uuid: None,
+            player_uuid: None,
+            previous: None,
+            new: None,
+        }
+    }
+
+    #[must_use]
+    pub fn by(mut self, actor: Uuid) -> Self {
+        self.actor = Some(actor);
+        self
+    }
+
+    #[must_use]
+    pub fn race(mut self, race_uuid: Uuid) -> Self {
+        self.race_uuid = Some(race_uuid);
+        self
+    }
+
+    #[must_use]
+    pub fn player(mut self, player_uuid: Uuid) -> Self {
+        self.player_uuid = Some(player_uuid);
+        self
+    }
+
+    /// Leave `field` out of the recorded values, for secrets
+    #[must_use]
+    pub fn redact(mut self, field: &str) -> Self {
+        for values in [&mut self.previous, &mut self.new].into_iter().flatten() {
+            values.remove(field);
+        }
+        self
+    }
+
+    /// Keep the fields that differ between `previous` and `new`
+    #[must_use]
+    pub fn values<T: Serialize>(mut self, previous: Option<&T>, new: Option<&T>) -> Self {
+        let to_document =
+            |value: Option<&T>| value.and_then(|v| mongodb::bson::to_document(v).ok());
+        (self.previous, self.new) = changed_fields(to_document(previous), to_document(new));
+        self
+    }
+}
+
+fn changed_fields(
+    previous: Option<Document>,
+    new: Option<Document>,
+) -> (Option<Document>, Option<Document>) {
+    match (previous, new) {
+        (Some(mut previous), Some(mut new)) => {
+            let unchanged: Vec<String> = previous
+                .iter()
+                .filter(|(field, value)| new.get(*field) == Some(value))
+                .map(|(field, _)| field.clone())
+                .collect();
+            for field in unchanged {
+                previous.remove(&field);
+                new.remove(&field);
+            }
+            (Some(previous), Some(new))
+        }
+        other => other,
+    }
+}
+
+/// Store `entry` in the audit log
+///
+/// Called once the operation itself succeeded, so a failure is logged rather
+/// than failing the request.
+pub async fn record_audit(database: &Database, entry: AuditEntry) {
+    let collection = database.collection::<AuditEntry>(AUDIT_LOG);
+    if let Err(e) = collection.insert_one(&entry, None).await {
+        tracing::error!(
+            "Failed to record {} in the audit log: {:?}",
+            entry.action,
+            e
+        );
+    }
+}
+
+/// Audit entries about a race and/or player, newest first, at most `limit`
+pub async fn find_audit_entries(
+    database: &Database,
+    race_uuid: Option<Uuid>,
+    player_uuid: Option<Uuid>,
+    limit: i64,
+) -> Result<Vec<AuditEntry>, mongodb::error::Error> {
+    let mut filter = Document::new();
+    if let Some(race_uuid) = race_uuid {
+        filter.insert("race_uuid", race_uuid.to_string());
+    }
+    if let Some(player_uuid) = player_uuid {
+        // Operations on a player's races name the player as the actor
+        filter.insert(
+            "$or",
+            vec![
+                doc! { "player_uuid": player_uuid.to_string() },
+                doc! { "actor": player_uuid.to_string() },
+            ],
+        );
+    }
+    let options = FindOptions::builder()
+        .sort(doc! { "occurred_at": -1, "_id": -1 })
+        .limit(limit)
+        .build();
+    let mut cursor = database
+        .collection::<AuditEntry>(AUDIT_LOG)
+        .find(filter, options)
+        .await?;
+
+    let mut entries = Vec::new();
+    while cursor.advance().await? {
+        entries.push(cursor.deserialize_current()?);
+    }
+    Ok(entries)
+}
+
+mod option_uuid_as_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use uuid::Uuid;
+
+    #[allow(clippy::ref_option)]
+    pub fn serialize<S>(uuid: &Option<Uuid>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match uuid {
+            Some(uuid) => serializer.serialize_some(&uuid.to_string()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Uuid>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<String>::deserialize(deserializer)?
+            .map(|s| Uuid::parse_str(&s).map_err(serde::de::Error::custom))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Doc {
+        name: &'static str,
+        laps: i32,
+    }
+
+    #[test]
+    fn only_changed_fields_are_kept() {
+        let before = Doc {
+            name: "Monza",
+            laps: 3,
+        };
+        let after = Doc {
+            name: "Monza",
+            laps: 5,
+        };
+        let entry = AuditEntry::new("race.update").values(Some(&before), Some(&after));
+
+        assert_eq!(entry.previous, Some(doc! { "laps": 3 }));
+        assert_eq!(entry.new, Some(doc! { "laps": 5 }));
+    }
+
+    #[test]
+    fn redacted_fields_are_not_recorded() {
+        let created = Doc {
+            name: "Monza",
+            laps: 3,
+        };
+        let entry = AuditEntry::new("race.create")
+            .values(None, Some(&created))
+            .redact("name");
+
+        assert_eq!(entry.new, Some(doc! { "laps": 3 }));
+    }
+
+    #[test]
+    fn created_document_has_no_previous_value() {
+        let created = Doc {
+            name: "Monza",
+            laps: 3,
+        };
+        let entry = AuditEntry::new("race.create").values(None, Some(&created));
+
+        assert_eq!(entry.previous, None);
+        assert_eq!(entry.new, Some(doc! { "name": "Monza", "laps": 3 }));
+    }
+
+    #[test]
+    fn uuids_are_stored_as_strings() {
+        let race_uuid = Uuid::new_v4();
+        let entry = AuditEntry::new("race.start").race(race_uuid);
+        let document = mongodb::bson::to_document(&entry).unwrap();
+
+        assert_eq!(
+            document.get_str("race_uuid").unwrap(),
+            race_uuid.to_string()
+        );
+        let restored: AuditEntry = mongodb::bson::from_document(document).unwrap();
+        assert_eq!(restored.race_uuid, Some(race_uuid));
+        assert_eq!(restored.actor, None);
+    }
+}
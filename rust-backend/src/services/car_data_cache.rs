@@ -0,0 +1,188 @@
+use axum::{
+    extract::{Request, State},
+    http::Method,
+    middleware::Next,
+    response::Response,
+};
+use mongodb::Database;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use super::car_validation::{CarValidationError, CarValidationService, ValidatedCarData};
+use super::race_cache::uuid_from_path;
+
+/// Cached entries keyed by (player, car)
+type Entries = HashMap<(Uuid, Uuid), (Instant, ValidatedCarData)>;
+
+/// Short-lived cache of validated car data keyed by (player, car)
+///
+/// Lap actions and previews validate the same car on every request. Only
+/// successful validations are stored, and a player's entries are dropped when
+/// a request writes to that player (see [`invalidate_car_data_on_write`]).
+#[derive(Clone)]
+pub struct CarDataCache {
+    entries: Arc<RwLock<Entries>>,
+    ttl: Duration,
+}
+
+impl CarDataCache {
+    /// A zero `ttl` disables the cache
+    #[must_use]
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            entries: Arc::default(),
+            ttl,
+        }
+    }
+
+    #[must_use]
+    pub fn get(&self, player_uuid: Uuid, car_uuid: Uuid) -> Option<ValidatedCarData> {
+        let entries = self.entries.read().unwrap();
+        entries
+            .get(&(player_uuid, car_uuid))
+            .filter(|(stored_at, _)| stored_at.elapsed() < self.ttl)
+            .map(|(_, car_data)| car_data.clone())
+    }
+
+    pub fn put(&self, player_uuid: Uuid, car_data: &ValidatedCarData) {
+        if self.ttl.is_zero() {
+            return;
+        }
+        let mut entries = self.entries.write().unwrap();
+        entries.retain(|_, (stored_at, _)| stored_at.elapsed() < self.ttl);
+        entries.insert(
+            (player_uuid, car_data.car.uuid),
+            (Instant::now(), car_data.clone()),
+        );
+    }
+
+    /// Drop every cached car of a player
+    pub fn invalidate_player(&self, player_uuid: Uuid) {
+        self.entries
+            .write()
+            .unwrap()
+            .retain(|(player, _), _| *player != player_uuid);
+    }
+
+    /// Validated car data, reusing a recent validation when there is one
+    ///
+    /// Race rarity caps are left to registration, the only place enforcing them.
+    pub async fn validate_car_for_race(
+        &self,
+        database: &Database,
+        player_uuid: Uuid,
+        car_uuid: Uuid,
+    ) -> Result<ValidatedCarData, CarValidationError> {
+        if let Some(car_data) = self.get(player_uuid, car_uuid) {
+            return Ok(car_data);
+        }
+
+        let car_data =
+            CarValidationService::validate_car_for_race(database, player_uuid, car_uuid, &[])
+                .await?;
+        self.put(player_uuid, &car_data);
+        Ok(car_data)
+    }
+}
+
+/// Middleware dropping a player's cached cars after any request that may have changed them
+pub async fn invalidate_car_data_on_write(
+    State(cache): State<CarDataCache>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let player_uuid = match *request.method() {
+        Method::GET | Method::HEAD | Method::OPTIONS => None,
+        _ => uuid_from_path(request.uri().path(), "players"),
+    };
+
+    let response = next.run(request).await;
+
+    if let Some(player_uuid) = player_uuid {
+        cache.invalidate_player(player_uuid);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        Body, BodyName, Car, CarName, ComponentRarity, Engine, EngineName, Pilot, PilotClass,
+        PilotName, PilotPerformance, PilotRarity, PilotSkills,
+    };
+
+    fn car_data() -> ValidatedCarData {
+        let engine = Engine::new(
+            EngineName::parse("Test Engine").unwrap(),
+            ComponentRarity::Common,
+            5,
+            4,
+            None,
+        )
+        .unwrap();
+        let body = Body::new(
+            BodyName::parse("Test Body").unwrap(),
+            ComponentRarity::Common,
+            4,
+            5,
+            None,
+        )
+        .unwrap();
+        let pilot = Pilot::new(
+            PilotName::parse("Test Pilot").unwrap(),
+            PilotClass::AllRounder,
+            PilotRarity::Professional,
+            PilotSkills::new(6, 6, 7, 5).unwrap(),
+            PilotPerformance::new(3, 3).unwrap(),
+            None,
+        )
+        .unwrap();
+
+        ValidatedCarData {
+            car: Car::new(CarName::parse("Test Car").unwrap(), None).unwrap(),
+            engine,
+            body,
+            pilot,
+        }
+    }
+
+    #[test]
+    fn stored_car_data_is_returned_for_the_same_player_and_car() {
+        let cache = CarDataCache::new(Duration::from_mins(1));
+        let player_uuid = Uuid::new_v4();
+        let car_data = car_data();
+
+        cache.put(player_uuid, &car_data);
+
+        let cached = cache.get(player_uuid, car_data.car.uuid).unwrap();
+        assert_eq!(cached.engine.uuid, car_data.engine.uuid);
+        assert!(cache.get(Uuid::new_v4(), car_data.car.uuid).is_none());
+    }
+
+    #[test]
+    fn invalidating_a_player_drops_only_their_cars() {
+        let cache = CarDataCache::new(Duration::from_mins(1));
+        let (player_uuid, other_uuid) = (Uuid::new_v4(), Uuid::new_v4());
+        let car_data = car_data();
+
+        cache.put(player_uuid, &car_data);
+        cache.put(other_uuid, &car_data);
+        cache.invalidate_player(player_uuid);
+
+        assert!(cache.get(player_uuid, car_data.car.uuid).is_none());
+        assert!(cache.get(other_uuid, car_data.car.uuid).is_some());
+    }
+
+    #[test]
+    fn zero_ttl_disables_the_cache() {
+        let cache = CarDataCache::new(Duration::ZERO);
+        let player_uuid = Uuid::new_v4();
+        let car_data = car_data();
+
+        cache.put(player_uuid, &car_data);
+        assert!(cache.get(player_uuid, car_data.car.uuid).is_none());
+    }
+}
@@ -1,7 +1,8 @@
 use mongodb::{bson::doc, Database};
 use uuid::Uuid;
 
-use crate::domain::{Body, Car, Engine, Pilot, Player};
+use super::loaner_cars::loaner_car;
+use crate::domain::{performance_score, Body, Car, ComponentRarity, Engine, Pilot, Player};
 
 /// Service for validating cars and their components for race participation
 pub struct CarValidationService;
@@ -15,6 +16,37 @@ pub struct ValidatedCarData {
     pub pilot: Pilot,
 }
 
+impl ValidatedCarData {
+    /// Sum of the engine, body and pilot values on straight laps
+    #[must_use]
+    pub fn straight_total(&self) -> u32 {
+        u32::from(self.engine.straight_value)
+            + u32::from(self.body.straight_value)
+            + u32::from(self.pilot.performance.straight_value)
+    }
+
+    /// Sum of the engine, body and pilot values on curve laps
+    #[must_use]
+    pub fn curve_total(&self) -> u32 {
+        u32::from(self.engine.curve_value)
+            + u32::from(self.body.curve_value)
+            + u32::from(self.pilot.performance.curve_value)
+    }
+
+    /// Performance rating from 0 to 100 of the car's values on both kinds of
+    /// lap, used for car tiers, matchmaking and lining up the starting grid
+    #[must_use]
+    pub fn performance_rating(&self) -> u32 {
+        performance_score(self.straight_total() + self.curve_total())
+    }
+
+    /// Rarest of the car's engine and body
+    #[must_use]
+    pub fn top_rarity(&self) -> ComponentRarity {
+        self.engine.rarity.max(self.body.rarity)
+    }
+}
+
 /// Errors that can occur during car validation
 #[derive(Debug, thiserror::Error)]
 pub enum CarValidationError {
@@ -52,6 +84,15 @@ pub enum CarValidationError {
     InvalidConfiguration(String),
     #[error("Database serialization error: {0}")]
     DatabaseSerializationError(String),
+    #[error(
+        "{component_type} {component_uuid} is {rarity:?}, but the race only allows {allowed:?}"
+    )]
+    RarityNotAllowed {
+        component_type: String,
+        component_uuid: Uuid,
+        rarity: ComponentRarity,
+        allowed: Vec<ComponentRarity>,
+    },
 }
 
 impl CarValidationError {
@@ -74,6 +115,7 @@ impl CarValidationError {
             CarValidationError::DatabaseQueryError(_) => "DATABASE_QUERY_ERROR",
             CarValidationError::DatabaseSerializationError(_) => "DATABASE_SERIALIZATION_ERROR",
             CarValidationError::InvalidConfiguration(_) => "INVALID_CAR_CONFIGURATION",
+            CarValidationError::RarityNotAllowed { .. } => "COMPONENT_RARITY_NOT_ALLOWED",
         }
     }
 
@@ -126,6 +168,18 @@ impl CarValidationError {
             CarValidationError::InvalidConfiguration(msg) => {
                 format!("Invalid car configuration: {msg}")
             }
+            CarValidationError::RarityNotAllowed {
+                component_type,
+                rarity,
+                allowed,
+                ..
+            } => {
+                let allowed: Vec<String> = allowed.iter().map(|r| format!("{r:?}")).collect();
+                format!(
+                    "This race only allows {} components, but your {component_type} is {rarity:?}",
+                    allowed.join(", ")
+                )
+            }
         }
     }
 
@@ -182,6 +236,9 @@ impl CarValidationError {
                 "Please check your car configuration and ensure all pilots are properly assigned."
                     .to_string(),
             ),
+            CarValidationError::RarityNotAllowed { component_type, .. } => Some(format!(
+                "Equip a {component_type} of an allowed rarity, or pick another car."
+            )),
         }
     }
 }
@@ -192,12 +249,16 @@ impl CarValidationService {
     /// This method performs comprehensive validation:
     /// 1. Verifies the car exists and belongs to the specified player
     /// 2. Validates the car has all required components (engine, body, pilot)
-    /// 3. Returns validated car data with all components
+    /// 3. Checks the engine and body rarities against the race's caps
+    /// 4. Returns validated car data with all components
+    ///
+    /// Loaner cars are lent to any player, so their ownership is not checked.
     ///
     /// # Arguments
     /// * `database` - `MongoDB` database connection
     /// * `player_uuid` - UUID of the player who owns the car
     /// * `car_uuid` - UUID of the car to validate
+    /// * `allowed_rarities` - Component rarities the race accepts; any when empty
     ///
     /// # Returns
     /// * `Ok(ValidatedCarData)` - Car and all components if validation passes
@@ -206,17 +267,49 @@ impl CarValidationService {
         database: &Database,
         player_uuid: Uuid,
         car_uuid: Uuid,
+        allowed_rarities: &[ComponentRarity],
     ) -> Result<ValidatedCarData, CarValidationError> {
-        // 1. Get the player and verify car ownership
-        let player = Self::get_player_by_uuid(database, player_uuid).await?;
-        let car = Self::verify_car_ownership(&player, car_uuid)?;
+        let car_data = if let Some(loaner) = loaner_car(car_uuid) {
+            // Loaners belong to the server and are lent to any player
+            loaner
+        } else {
+            // 1. Get the player
+            let player = Self::get_player_by_uuid(database, player_uuid).await?;
+
+            // 2. Verify car ownership and that the car has all required components
+            Self::validate_owned_car(&player, car_uuid)?
+        };
+
+        // 3. Enforce the race's rarity caps
+        Self::check_allowed_rarities(&car_data, allowed_rarities)?;
+
+        Ok(car_data)
+    }
 
-        // 2. Validate car has all required components
-        let engine = Self::get_car_engine(&car, &player)?;
-        let body = Self::get_car_body(&car, &player)?;
-        let pilot = Self::get_car_pilot(&car, &player)?;
+    /// Rejects a car whose engine or body rarity the race does not allow
+    pub fn check_allowed_rarities(
+        car_data: &ValidatedCarData,
+        allowed_rarities: &[ComponentRarity],
+    ) -> Result<(), CarValidationError> {
+        let (engine, body) = (&car_data.engine, &car_data.body);
+        Self::check_rarity("engine", engine.uuid, engine.rarity, allowed_rarities)?;
+        Self::check_rarity("body", body.uuid, body.rarity, allowed_rarities)
+    }
 
-        // 3. Return validated car data
+    /// Validates a car of an already loaded player, without race restrictions
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the player does not own the car or the car misses
+    /// a component.
+    pub fn validate_owned_car(
+        player: &Player,
+        car_uuid: Uuid,
+    ) -> Result<ValidatedCarData, CarValidationError> {
+        let car = Self::verify_car_ownership(player, car_uuid)?;
+        let engine = Self::get_car_engine(&car, player)?;
+        let body = Self::get_car_body(&car, player)?;
+        let pilot = Self::get_car_pilot(&car, player)?;
         Ok(ValidatedCarData {
             car,
             engine,
@@ -225,6 +318,24 @@ impl CarValidationService {
         })
     }
 
+    /// Rejects a component whose rarity the race does not allow
+    fn check_rarity(
+        component_type: &str,
+        component_uuid: Uuid,
+        rarity: ComponentRarity,
+        allowed_rarities: &[ComponentRarity],
+    ) -> Result<(), CarValidationError> {
+        if allowed_rarities.is_empty() || allowed_rarities.contains(&rarity) {
+            return Ok(());
+        }
+        Err(CarValidationError::RarityNotAllowed {
+            component_type: component_type.to_string(),
+            component_uuid,
+            rarity,
+            allowed: allowed_rarities.to_vec(),
+        })
+    }
+
     /// Gets a player by UUID from the database
     async fn get_player_by_uuid(
         database: &Database,
@@ -331,7 +442,7 @@ mod tests {
     use super::*;
     use crate::domain::{
         BodyName, CarName, ComponentRarity, Email, EngineName, Password, PilotClass, PilotName,
-        PilotPerformance, PilotRarity, PilotSkills, TeamName,
+        PilotPerformance, PilotRarity, PilotSkills, TeamName, MAX_CAR_VALUE_TOTAL,
     };
 
     fn create_test_engine() -> Engine {
@@ -518,6 +629,43 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_performance_score_scales_to_100() {
+        assert_eq!(performance_score(0), 0);
+        assert_eq!(performance_score(36), 60);
+        assert_eq!(performance_score(MAX_CAR_VALUE_TOTAL), 100);
+        assert_eq!(performance_score(MAX_CAR_VALUE_TOTAL + 5), 100);
+        // Every extra value point still raises the rating
+        assert!((0..MAX_CAR_VALUE_TOTAL).all(|t| performance_score(t) < performance_score(t + 1)));
+    }
+
+    #[test]
+    fn test_rarity_caps() {
+        let engine = create_test_engine();
+        let allowed = [ComponentRarity::Common];
+
+        assert!(
+            CarValidationService::check_rarity("engine", engine.uuid, engine.rarity, &[]).is_ok()
+        );
+        assert!(
+            CarValidationService::check_rarity("engine", engine.uuid, engine.rarity, &allowed)
+                .is_ok()
+        );
+
+        let error = CarValidationService::check_rarity(
+            "body",
+            engine.uuid,
+            ComponentRarity::Epic,
+            &allowed,
+        )
+        .unwrap_err();
+        assert_eq!(error.error_code(), "COMPONENT_RARITY_NOT_ALLOWED");
+        assert!(error
+            .user_message()
+            .contains("only allows Common components"));
+        assert!(error.user_message().contains("body is Epic"));
+    }
+
     #[test]
     fn test_error_codes() {
         let car_uuid = Uuid::new_v4();
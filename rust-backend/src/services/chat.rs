@@ -0,0 +1,345 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast::{self, error::RecvError};
+use uuid::Uuid;
+
+use crate::configuration::ChatSettings;
+
+/// Events a slow client may fall behind on before it misses some
+const ROOM_BUFFER: usize = 256;
+
+/// Room players chat in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "race_uuid")]
+pub enum ChatRoom {
+    /// Players gathering before a race starts
+    Lobby(Uuid),
+    /// Every connected player, when enabled
+    Global,
+}
+
+/// Player as shown to the other members of a room
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChatMember {
+    pub player_uuid: Uuid,
+    pub team_name: String,
+}
+
+/// Sent to the members of a room over their WebSocket
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type")]
+pub enum ChatEvent {
+    /// Sent to a player once they joined: everyone in the room, them included
+    Presence {
+        room: ChatRoom,
+        members: Vec<ChatMember>,
+    },
+    Joined {
+        room: ChatRoom,
+        member: ChatMember,
+    },
+    /// The player's last connection to the room closed
+    Left {
+        room: ChatRoom,
+        member: ChatMember,
+    },
+    Message {
+        room: ChatRoom,
+        member: ChatMember,
+        text: String,
+        sent_at: DateTime<Utc>,
+    },
+    /// Sent only to the player whose message was refused
+    Rejected {
+        reason: String,
+    },
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ChatError {
+    EmptyMessage,
+    MessageTooLong { max_length: usize },
+}
+
+impl std::fmt::Display for ChatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::EmptyMessage => write!(f, "Message is empty"),
+            Self::MessageTooLong { max_length } => {
+                write!(f, "Message is longer than {max_length} characters")
+            }
+        }
+    }
+}
+
+struct Room {
+    events: broadcast::Sender<ChatEvent>,
+    /// Members with their number of open connections
+    members: HashMap<Uuid, (ChatMember, usize)>,
+}
+
+/// Chat rooms of this server instance
+///
+/// Members only see the players connected to the same instance, so servers
+/// behind a load balancer need sticky sessions per room.
+#[derive(Clone)]
+pub struct ChatHub {
+    rooms: Arc<Mutex<HashMap<ChatRoom, Room>>>,
+    settings: ChatSettings,
+}
+
+/// Connection of a player to a room; leaves the room when dropped
+pub struct ChatMembership {
+    hub: ChatHub,
+    room: ChatRoom,
+    member: ChatMember,
+    events: broadcast::Receiver<ChatEvent>,
+    /// Players whose messages this member does not receive
+    hidden: HashSet<Uuid>,
+}
+
+impl ChatHub {
+    #[must_use]
+    pub fn new(settings: ChatSettings) -> Self {
+        Self {
+            rooms: Arc::default(),
+            settings,
+        }
+    }
+
+    #[must_use]
+    pub fn global_room_enabled(&self) -> bool {
+        self.settings.global_room
+    }
+
+    /// Add a connection of `member` to `room`
+    ///
+    /// The other members are told the player joined unless they already had
+    /// a connection open, e.g. from another tab.
+    #[must_use]
+    pub fn join(&self, room: ChatRoom, member: ChatMember) -> ChatMembership {
+        let mut rooms = self.rooms.lock().unwrap();
+        let entry = rooms.entry(room).or_insert_with(|| Room {
+            events: broadcast::channel(ROOM_BUFFER).0,
+            members: HashMap::new(),
+        });
+        let connections = &mut entry
+            .members
+            .entry(member.player_uuid)
+            .or_insert_with(|| (member.clone(), 0))
+            .1;
+        *connections += 1;
+        if *connections == 1 {
+            let _ = entry.events.send(ChatEvent::Joined {
+                room,
+                member: member.clone(),
+            });
+        }
+        // Subscribed after the announcement: the player learns who is there
+        // from `members` instead
+        let events = entry.events.subscribe();
+        ChatMembership {
+            hub: self.clone(),
+            room,
+            member,
+            events,
+            hidden: HashSet::new(),
+        }
+    }
+
+    /// Players currently in `room`, by team name
+    #[must_use]
+    pub fn members(&self, room: ChatRoom) -> Vec<ChatMember> {
+        let rooms = self.rooms.lock().unwrap();
+        let mut members: Vec<ChatMember> = rooms
+            .get(&room)
+            .map(|room| room.members.values().map(|(m, _)| m.clone()).collect())
+            .unwrap_or_default();
+        members.sort_by(|a, b| a.team_name.cmp(&b.team_name));
+        members
+    }
+
+    fn leave(&self, room: ChatRoom, player_uuid: Uuid) {
+        let mut rooms = self.rooms.lock().unwrap();
+        let Some(entry) = rooms.get_mut(&room) else {
+            return;
+        };
+        let Some((member, connections)) = entry.members.get_mut(&player_uuid) else {
+            return;
+        };
+        *connections -= 1;
+        if *connections == 0 {
+            let member = member.clone();
+            entry.members.remove(&player_uuid);
+            let _ = entry.events.send(ChatEvent::Left { room, member });
+        }
+        if entry.members.is_empty() {
+            rooms.remove(&room);
+        }
+    }
+}
+
+impl ChatMembership {
+    /// Stop receiving messages from `players`, e.g. those the member blocked
+    pub fn hide(&mut self, players: impl IntoIterator<Item = Uuid>) {
+        self.hidden.extend(players);
+    }
+
+    /// Next room event for this member, skipping messages from hidden players
+    pub async fn recv(&mut self) -> Result<ChatEvent, RecvError> {
+        loop {
+            let event = self.events.recv().await?;
+            if self.receives(&event) {
+                return Ok(event);
+            }
+        }
+    }
+
+    fn receives(&self, event: &ChatEvent) -> bool {
+        match event {
+            ChatEvent::Message { member, .. } => !self.hidden.contains(&member.player_uuid),
+            _ => true,
+        }
+    }
+
+    /// Send a message to everyone in the room, the sender included
+    pub fn say(&self, text: &str) -> Result<(), ChatError> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(ChatError::EmptyMessage);
+        }
+        let max_length = self.hub.settings.max_message_length;
+        if text.chars().count() > max_length {
+            return Err(ChatError::MessageTooLong { max_length });
+        }
+
+        let rooms = self.hub.rooms.lock().unwrap();
+        if let Some(room) = rooms.get(&self.room) {
+            let _ = room.events.send(ChatEvent::Message {
+                room: self.room,
+                member: self.member.clone(),
+                text: text.to_string(),
+                sent_at: Utc::now(),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ChatMembership {
+    fn drop(&mut self) {
+        self.hub.leave(self.room, self.member.player_uuid);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(team_name: &str) -> ChatMember {
+        ChatMember {
+            player_uuid: Uuid::new_v4(),
+            team_name: team_name.to_string(),
+        }
+    }
+
+    fn hub() -> ChatHub {
+        ChatHub::new(ChatSettings::default())
+    }
+
+    #[test]
+    fn members_see_others_join_and_leave() {
+        let hub = hub();
+        let room = ChatRoom::Lobby(Uuid::new_v4());
+        let mut first = hub.join(room, member("Alpha"));
+        let second = hub.join(room, member("Bravo"));
+
+        assert!(
+            matches!(first.events.try_recv(), Ok(ChatEvent::Joined { member, .. }) if member.team_name == "Bravo")
+        );
+        assert_eq!(hub.members(room).len(), 2);
+
+        drop(second);
+        assert!(
+            matches!(first.events.try_recv(), Ok(ChatEvent::Left { member, .. }) if member.team_name == "Bravo")
+        );
+        assert_eq!(hub.members(room).len(), 1);
+    }
+
+    #[test]
+    fn a_second_connection_is_not_announced() {
+        let hub = hub();
+        let room = ChatRoom::Global;
+        let player = member("Alpha");
+        let mut watcher = hub.join(room, member("Bravo"));
+        let tab = hub.join(room, player.clone());
+        let other_tab = hub.join(room, player);
+        assert!(matches!(
+            watcher.events.try_recv(),
+            Ok(ChatEvent::Joined { .. })
+        ));
+
+        drop(tab);
+        assert!(watcher.events.try_recv().is_err());
+        drop(other_tab);
+        assert!(matches!(
+            watcher.events.try_recv(),
+            Ok(ChatEvent::Left { .. })
+        ));
+    }
+
+    #[test]
+    fn messages_are_trimmed_and_bounded() {
+        let hub = hub();
+        let mut speaker = hub.join(ChatRoom::Global, member("Alpha"));
+
+        assert_eq!(speaker.say("   "), Err(ChatError::EmptyMessage));
+        let too_long = "a".repeat(ChatSettings::default().max_message_length + 1);
+        assert!(matches!(
+            speaker.say(&too_long),
+            Err(ChatError::MessageTooLong { .. })
+        ));
+
+        speaker.say("  good luck  ").unwrap();
+        assert!(
+            matches!(speaker.events.try_recv(), Ok(ChatEvent::Message { text, .. }) if text == "good luck")
+        );
+    }
+
+    #[test]
+    fn empty_rooms_are_dropped() {
+        let hub = hub();
+        let room = ChatRoom::Lobby(Uuid::new_v4());
+        drop(hub.join(room, member("Alpha")));
+
+        assert!(hub.rooms.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn messages_from_hidden_players_are_skipped() {
+        let hub = hub();
+        let room = ChatRoom::Global;
+        let blocked = member("Alpha");
+        let mut listener = hub.join(room, member("Bravo"));
+        let speaker = hub.join(room, blocked.clone());
+        let other = hub.join(room, member("Charlie"));
+        listener.hide([blocked.player_uuid]);
+
+        speaker.say("hello").unwrap();
+        other.say("hi").unwrap();
+
+        assert!(matches!(
+            listener.recv().await,
+            Ok(ChatEvent::Joined { .. })
+        ));
+        assert!(matches!(
+            listener.recv().await,
+            Ok(ChatEvent::Joined { .. })
+        ));
+        assert!(
+            matches!(listener.recv().await, Ok(ChatEvent::Message { text, .. }) if text == "hi")
+        );
+    }
+}
@@ -0,0 +1,322 @@
+use mongodb::{
+    bson::{doc, DateTime as BsonDateTime, Document},
+    options::FindOptions,
+    Database,
+};
+use secrecy::{ExposeSecret, Secret};
+use serde_json::json;
+use std::fmt::Write as _;
+use std::time::{Duration, SystemTime};
+use uuid::Uuid;
+
+use crate::configuration::{EmailBackend, EmailSettings};
+use crate::domain::{Notification, Player};
+use crate::routes::players::{get_notification_preferences_from_db, get_player_by_uuid_from_db};
+
+const NOTIFICATIONS: &str = "notifications";
+/// Digest run a pending notification is being mailed by, and until when
+const CLAIM_FIELD: &str = "email_claim";
+const CLAIMED_UNTIL_FIELD: &str = "email_claimed_until";
+const CLAIM: Duration = Duration::from_mins(5);
+/// Notifications older than this are dropped from digests, e.g. after email
+/// was disabled for a while
+const MAX_EMAIL_AGE: Duration = Duration::from_hours(24);
+const SENDGRID_URL: &str = "https://api.sendgrid.com/v3/mail/send";
+
+#[derive(Debug, Clone)]
+pub struct EmailMessage {
+    pub to: String,
+    pub subject: String,
+    /// Plain text body
+    pub body: String,
+}
+
+/// Client of the configured email provider
+#[derive(Clone, Default)]
+pub struct Mailer {
+    transport: Option<Transport>,
+    sender: String,
+}
+
+#[derive(Clone)]
+enum Transport {
+    Log,
+    #[cfg(feature = "smtp")]
+    Smtp(lettre::AsyncSmtpTransport<lettre::Tokio1Executor>),
+    Sendgrid {
+        client: reqwest::Client,
+        api_key: Secret<String>,
+    },
+}
+
+impl Mailer {
+    /// Mailer that drops every email
+    #[must_use]
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    pub fn from_settings(settings: &EmailSettings) -> Result<Self, anyhow::Error> {
+        let transport = match settings.backend {
+            EmailBackend::None => return Ok(Self::disabled()),
+            EmailBackend::Log => Transport::Log,
+            #[cfg(feature = "smtp")]
+            EmailBackend::Smtp => {
+                let Some(smtp) = &settings.smtp else {
+                    anyhow::bail!("The smtp email backend requires `email.smtp`");
+                };
+                let credentials = lettre::transport::smtp::authentication::Credentials::new(
+                    smtp.username.clone(),
+                    smtp.password.expose_secret().clone(),
+                );
+                let transport =
+                    lettre::AsyncSmtpTransport::<lettre::Tokio1Executor>::starttls_relay(
+                        &smtp.host,
+                    )?
+                    .port(smtp.port)
+                    .credentials(credentials)
+                    .build();
+                Transport::Smtp(transport)
+            }
+            #[cfg(not(feature = "smtp"))]
+            EmailBackend::Smtp => {
+                anyhow::bail!("The smtp email backend requires building with `--features smtp`")
+            }
+            EmailBackend::Sendgrid => {
+                let Some(api_key) = &settings.sendgrid_api_key else {
+                    anyhow::bail!("The sendgrid email backend requires `email.sendgrid_api_key`");
+                };
+                Transport::Sendgrid {
+                    client: reqwest::Client::builder()
+                        .timeout(Duration::from_secs(10))
+                        .build()?,
+                    api_key: api_key.clone(),
+                }
+            }
+        };
+        Ok(Self {
+            transport: Some(transport),
+            sender: settings.sender.clone(),
+        })
+    }
+
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.transport.is_some()
+    }
+
+    pub async fn send(&self, message: &EmailMessage) -> Result<(), anyhow::Error> {
+        let Some(transport) = &self.transport else {
+            return Ok(());
+        };
+        match transport {
+            Transport::Log => {
+                tracing::info!(
+                    "Email to {}: {}\n{}",
+                    message.to,
+                    message.subject,
+                    message.body
+                );
+            }
+            #[cfg(feature = "smtp")]
+            Transport::Smtp(smtp) => {
+                use lettre::AsyncTransport;
+                let email = lettre::Message::builder()
+                    .from(self.sender.parse()?)
+                    .to(message.to.parse()?)
+                    .subject(&message.subject)
+                    .header(lettre::message::header::ContentType::TEXT_PLAIN)
+                    .body(message.body.clone())?;
+                smtp.send(email).await?;
+            }
+            Transport::Sendgrid { client, api_key } => {
+                let body = json!({
+                    "personalizations": [{ "to": [{ "email": message.to }] }],
+                    "from": sendgrid_address(&self.sender),
+                    "subject": message.subject,
+                    "content": [{ "type": "text/plain", "value": message.body }],
+                });
+                client
+                    .post(SENDGRID_URL)
+                    .bearer_auth(api_key.expose_secret())
+                    .json(&body)
+                    .send()
+                    .await?
+                    .error_for_status()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `SendGrid` address object for `Name <address>` or a bare address
+fn sendgrid_address(sender: &str) -> serde_json::Value {
+    match sender
+        .trim_end()
+        .strip_suffix('>')
+        .and_then(|s| s.split_once('<'))
+    {
+        Some((name, email)) => json!({ "name": name.trim(), "email": email.trim() }),
+        None => json!({ "email": sender.trim() }),
+    }
+}
+
+/// Mail up to `batch_size` players one digest of their pending notifications,
+/// keeping the kinds their preferences allow
+/// Returns the number of digests sent.
+pub async fn send_email_digests(
+    database: &Database,
+    mailer: &Mailer,
+    batch_size: u32,
+) -> Result<u64, mongodb::error::Error> {
+    let notifications = database.collection::<Notification>(NOTIFICATIONS);
+    let pipeline = vec![
+        doc! { "$match": claimable() },
+        doc! { "$group": { "_id": "$player_uuid" } },
+        doc! { "$limit": i64::from(batch_size) },
+    ];
+    let mut cursor = notifications.aggregate(pipeline, None).await?;
+    let mut players = Vec::new();
+    while cursor.advance().await? {
+        let group: Document = cursor.deserialize_current()?;
+        let player_uuid = group
+            .get_str("_id")
+            .map_err(mongodb::error::Error::custom)
+            .and_then(|uuid| Uuid::parse_str(uuid).map_err(mongodb::error::Error::custom))?;
+        players.push(player_uuid);
+    }
+
+    let mut sent = 0;
+    for player_uuid in players {
+        if send_digest(database, mailer, player_uuid).await? {
+            sent += 1;
+        }
+    }
+    Ok(sent)
+}
+
+/// Pending notifications nobody is mailing right now
+fn claimable() -> Document {
+    doc! {
+        "email_pending": true,
+        CLAIMED_UNTIL_FIELD: { "$not": { "$gt": BsonDateTime::now() } },
+    }
+}
+
+/// Claim the player's pending notifications and mail the wanted ones
+///
+/// A failed send releases the claim, so the notifications are retried by the
+/// next run until they are too old.
+async fn send_digest(
+    database: &Database,
+    mailer: &Mailer,
+    player_uuid: Uuid,
+) -> Result<bool, mongodb::error::Error> {
+    let notifications = database.collection::<Notification>(NOTIFICATIONS);
+    let claim = Uuid::new_v4().to_string();
+    let mut filter = claimable();
+    filter.insert("player_uuid", player_uuid.to_string());
+    let claimed_until = BsonDateTime::from_system_time(SystemTime::now() + CLAIM);
+    let claimed = notifications
+        .update_many(
+            filter,
+            doc! { "$set": { CLAIM_FIELD: &claim, CLAIMED_UNTIL_FIELD: claimed_until } },
+            None,
+        )
+        .await?;
+    if claimed.modified_count == 0 {
+        return Ok(false);
+    }
+
+    let options = FindOptions::builder()
+        .sort(doc! { "created_at": 1 })
+        .build();
+    let mut cursor = notifications
+        .find(doc! { CLAIM_FIELD: &claim }, options)
+        .await?;
+    let mut pending = Vec::new();
+    while cursor.advance().await? {
+        pending.push(cursor.deserialize_current()?);
+    }
+
+    let player = get_player_by_uuid_from_db(database, player_uuid).await?;
+    let preferences = get_notification_preferences_from_db(database, player_uuid).await?;
+    let wanted: Vec<&Notification> = pending
+        .iter()
+        .filter(|notification| {
+            let age = SystemTime::now()
+                .duration_since(notification.created_at.to_system_time())
+                .unwrap_or_default();
+            preferences.emails(notification.kind) && age < MAX_EMAIL_AGE
+        })
+        .collect();
+
+    let mut sent = false;
+    if let (Some(player), false) = (player, wanted.is_empty()) {
+        if let Err(e) = mailer.send(&digest(&player, &wanted)).await {
+            tracing::warn!("Email digest to player {} failed: {:?}", player_uuid, e);
+            notifications
+                .update_many(
+                    doc! { CLAIM_FIELD: &claim },
+                    doc! { "$unset": { CLAIM_FIELD: "", CLAIMED_UNTIL_FIELD: "" } },
+                    None,
+                )
+                .await?;
+            return Ok(false);
+        }
+        sent = true;
+    }
+
+    notifications
+        .update_many(
+            doc! { CLAIM_FIELD: &claim },
+            doc! {
+                "$set": { "email_pending": false },
+                "$unset": { CLAIM_FIELD: "", CLAIMED_UNTIL_FIELD: "" },
+            },
+            None,
+        )
+        .await?;
+    Ok(sent)
+}
+
+fn digest(player: &Player, notifications: &[&Notification]) -> EmailMessage {
+    let subject = match notifications {
+        [notification] => notification.kind.title().to_string(),
+        _ => format!("{} updates from your races", notifications.len()),
+    };
+    let mut body = format!("Hi {},\n\n", player.team_name.as_ref());
+    for notification in notifications {
+        let _ = writeln!(
+            body,
+            "- {}: {}",
+            notification.kind.title(),
+            notification.message
+        );
+    }
+    body.push_str(
+        "\nYou can choose which of these you get by email in your notification settings.\n",
+    );
+    EmailMessage {
+        to: player.email.as_ref().to_string(),
+        subject,
+        body,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sendgrid_sender_keeps_the_display_name() {
+        assert_eq!(
+            sendgrid_address("Boardurance <noreply@boardurance.local>"),
+            json!({ "name": "Boardurance", "email": "noreply@boardurance.local" })
+        );
+        assert_eq!(
+            sendgrid_address("noreply@boardurance.local"),
+            json!({ "email": "noreply@boardurance.local" })
+        );
+    }
+}
@@ -0,0 +1,255 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::configuration::{EventBackend, EventSettings};
+use crate::domain::{
+    Emote, LapCharacteristic, LapResult, ParticipantMovement, Race, RaceAward, RaceStatus,
+};
+
+/// Race activity published to the message broker
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type")]
+pub enum DomainEvent {
+    /// A player chose their boost for the current lap
+    ActionSubmitted {
+        race_uuid: Uuid,
+        player_uuid: Uuid,
+        lap: u32,
+        boost_value: u32,
+    },
+    LapProcessed {
+        race_uuid: Uuid,
+        lap: u32,
+        lap_characteristic: LapCharacteristic,
+        movements: Vec<ParticipantMovement>,
+    },
+    RaceFinished {
+        race_uuid: Uuid,
+        /// Participants by finish position
+        finish_order: Vec<Uuid>,
+        #[serde(default)]
+        awards: Vec<RaceAward>,
+    },
+    /// A participant reacted to a resolved lap
+    ReactionSent {
+        race_uuid: Uuid,
+        player_uuid: Uuid,
+        lap: u32,
+        emote: Emote,
+    },
+}
+
+impl DomainEvent {
+    /// Events raised by turning `before` into `after`, in order
+    #[must_use]
+    pub fn between(before: &Race, after: &Race, lap_results: &[LapResult]) -> Vec<Self> {
+        let mut events: Vec<Self> = lap_results
+            .iter()
+            .map(|lap_result| Self::LapProcessed {
+                race_uuid: after.uuid,
+                lap: lap_result.lap,
+                lap_characteristic: lap_result.lap_characteristic.clone(),
+                movements: lap_result.movements.clone(),
+            })
+            .collect();
+
+        if before.status != RaceStatus::Finished && after.status == RaceStatus::Finished {
+            let mut finishers: Vec<_> = after
+                .participants
+                .iter()
+                .map(|p| (p.finish_position.unwrap_or(u32::MAX), p.player_uuid))
+                .collect();
+            finishers.sort_unstable();
+            events.push(Self::RaceFinished {
+                race_uuid: after.uuid,
+                finish_order: finishers.into_iter().map(|(_, uuid)| uuid).collect(),
+                awards: after.awards.clone(),
+            });
+        }
+        events
+    }
+
+    #[must_use]
+    pub fn race_uuid(&self) -> Uuid {
+        match self {
+            Self::ActionSubmitted { race_uuid, .. }
+            | Self::LapProcessed { race_uuid, .. }
+            | Self::RaceFinished { race_uuid, .. }
+            | Self::ReactionSent { race_uuid, .. } => *race_uuid,
+        }
+    }
+
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ActionSubmitted { .. } => "action_submitted",
+            Self::LapProcessed { .. } => "lap_processed",
+            Self::RaceFinished { .. } => "race_finished",
+            Self::ReactionSent { .. } => "reaction_sent",
+        }
+    }
+
+    /// Subject the event is published on, so consumers can subscribe to one
+    /// race or one kind of event with wildcards
+    #[must_use]
+    pub fn subject(&self, prefix: &str) -> String {
+        format!("{prefix}.races.{}.{}", self.race_uuid(), self.name())
+    }
+}
+
+/// Message body: the event with an id consumers can deduplicate on
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EventEnvelope {
+    pub id: Uuid,
+    pub occurred_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub event: DomainEvent,
+}
+
+impl EventEnvelope {
+    #[must_use]
+    pub fn new(event: DomainEvent) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            occurred_at: Utc::now(),
+            event,
+        }
+    }
+}
+
+/// Publisher of domain events to the configured broker
+#[derive(Clone, Default)]
+pub struct EventBus {
+    publisher: Option<Publisher>,
+    subject_prefix: String,
+}
+
+#[derive(Clone)]
+enum Publisher {
+    Log,
+    #[cfg(feature = "nats")]
+    Nats(async_nats::Client),
+}
+
+// Only the NATS publisher awaits anything
+#[cfg_attr(not(feature = "nats"), allow(clippy::unused_async))]
+impl EventBus {
+    /// Bus that drops every event
+    #[must_use]
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    pub async fn from_settings(settings: &EventSettings) -> Result<Self, anyhow::Error> {
+        let publisher = match settings.backend {
+            EventBackend::None => return Ok(Self::disabled()),
+            EventBackend::Log => Publisher::Log,
+            #[cfg(feature = "nats")]
+            EventBackend::Nats => Publisher::Nats(async_nats::connect(&settings.nats_url).await?),
+            #[cfg(not(feature = "nats"))]
+            EventBackend::Nats => {
+                anyhow::bail!("The nats event backend requires building with `--features nats`")
+            }
+        };
+        Ok(Self {
+            publisher: Some(publisher),
+            subject_prefix: settings.subject_prefix.clone(),
+        })
+    }
+
+    /// Publish one event, flushed to the broker before returning
+    pub async fn publish(&self, envelope: &EventEnvelope) -> Result<(), anyhow::Error> {
+        let Some(publisher) = &self.publisher else {
+            return Ok(());
+        };
+        let subject = envelope.event.subject(&self.subject_prefix);
+        let body = serde_json::to_vec(envelope)?;
+        match publisher {
+            Publisher::Log => {
+                tracing::info!("{}: {}", subject, String::from_utf8_lossy(&body));
+            }
+            #[cfg(feature = "nats")]
+            Publisher::Nats(client) => {
+                client.publish(subject, body.into()).await?;
+                client.flush().await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Sector, SectorType, Track};
+
+    fn race() -> Race {
+        let sector = |id, sector_type| Sector {
+            id,
+            name: format!("Sector {id}"),
+            min_value: id * 10,
+            max_value: id * 10 + 10,
+            slot_capacity: None,
+            sector_type,
+            capacity_schedule: Vec::new(),
+            modifiers: None,
+        };
+        let sectors = vec![sector(0, SectorType::Start), sector(1, SectorType::Finish)];
+        let track = Track::new("Track".to_string(), sectors).unwrap();
+        Race::new("Published".to_string(), track, 1)
+    }
+
+    #[test]
+    fn subject_names_race_and_event() {
+        let event = DomainEvent::RaceFinished {
+            race_uuid: Uuid::nil(),
+            finish_order: Vec::new(),
+            awards: Vec::new(),
+        };
+
+        assert_eq!(
+            event.subject("boardurance"),
+            format!("boardurance.races.{}.race_finished", Uuid::nil())
+        );
+    }
+
+    #[test]
+    fn finishing_lists_participants_by_position() {
+        let mut before = race();
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+        for player_uuid in [second, first] {
+            before
+                .add_participant(player_uuid, Uuid::new_v4(), Uuid::new_v4())
+                .unwrap();
+        }
+        before.status = RaceStatus::InProgress;
+        let mut after = before.clone();
+        after.status = RaceStatus::Finished;
+        after.participants[0].finish_position = Some(2);
+        after.participants[1].finish_position = Some(1);
+
+        let events = DomainEvent::between(&before, &after, &[]);
+
+        assert_eq!(events.len(), 1);
+        let DomainEvent::RaceFinished { finish_order, .. } = &events[0] else {
+            panic!("expected RaceFinished, got {:?}", events[0]);
+        };
+        assert_eq!(finish_order, &vec![first, second]);
+    }
+
+    #[test]
+    fn envelope_carries_the_event_type() {
+        let envelope = EventEnvelope::new(DomainEvent::ActionSubmitted {
+            race_uuid: Uuid::nil(),
+            player_uuid: Uuid::nil(),
+            lap: 2,
+            boost_value: 3,
+        });
+        let json = serde_json::to_value(&envelope).unwrap();
+
+        assert_eq!(json["type"], "ActionSubmitted");
+        assert_eq!(json["boost_value"], 3);
+        assert!(json["id"].is_string());
+    }
+}
@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Language error messages are rendered in
+///
+/// Handlers write their messages in English; the other languages come from
+/// the catalogs in `locales/`, keyed by error code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Language {
+    English,
+    French,
+    Spanish,
+}
+
+impl Language {
+    /// Language tag, as sent in `Content-Language`
+    #[must_use]
+    pub fn tag(self) -> &'static str {
+        match self {
+            Self::English => "en",
+            Self::French => "fr",
+            Self::Spanish => "es",
+        }
+    }
+
+    /// Preferred supported language of an `Accept-Language` header, English
+    /// when none is supported
+    ///
+    /// Only the primary subtag is compared, so `fr-CA` picks French.
+    #[must_use]
+    pub fn negotiate(accept_language: &str) -> Self {
+        let mut ranges: Vec<(&str, f32)> = accept_language
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .find_map(|parameter| parameter.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+                (!tag.is_empty() && quality > 0.0).then_some((tag, quality))
+            })
+            .collect();
+        // Stable, so equally weighted languages keep the client's order
+        ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        ranges
+            .into_iter()
+            .find_map(|(tag, _)| {
+                let primary = tag.split('-').next().unwrap_or(tag);
+                match primary.to_ascii_lowercase().as_str() {
+                    "en" => Some(Self::English),
+                    "fr" => Some(Self::French),
+                    "es" => Some(Self::Spanish),
+                    _ => None,
+                }
+            })
+            .unwrap_or(Self::English)
+    }
+}
+
+type Catalog = HashMap<String, String>;
+
+/// Message for an error code in `language`, `None` for English or a code the
+/// catalog does not have
+#[must_use]
+pub fn localized_error_message(code: &str, language: Language) -> Option<&'static str> {
+    static CATALOGS: OnceLock<HashMap<Language, Catalog>> = OnceLock::new();
+    let catalogs = CATALOGS.get_or_init(|| {
+        HashMap::from([
+            (
+                Language::French,
+                parse_catalog(include_str!("../../locales/fr.json")),
+            ),
+            (
+                Language::Spanish,
+                parse_catalog(include_str!("../../locales/es.json")),
+            ),
+        ])
+    });
+    catalogs.get(&language)?.get(code).map(String::as_str)
+}
+
+fn parse_catalog(source: &str) -> Catalog {
+    serde_json::from_str(source).expect("error message catalogs are valid JSON")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_most_wanted_supported_language_is_picked() {
+        assert_eq!(
+            Language::negotiate("fr-CH, fr;q=0.9, en;q=0.8"),
+            Language::French
+        );
+        assert_eq!(
+            Language::negotiate("de, es;q=0.5, fr;q=0.4"),
+            Language::Spanish
+        );
+        assert_eq!(Language::negotiate("es;q=0, fr;q=0.1"), Language::French);
+        assert_eq!(Language::negotiate("de-DE, *;q=0.5"), Language::English);
+        assert_eq!(Language::negotiate(""), Language::English);
+    }
+
+    #[test]
+    fn every_catalog_translates_the_same_codes() {
+        let french = parse_catalog(include_str!("../../locales/fr.json"));
+        let spanish = parse_catalog(include_str!("../../locales/es.json"));
+
+        let mut french_codes: Vec<_> = french.keys().collect();
+        let mut spanish_codes: Vec<_> = spanish.keys().collect();
+        french_codes.sort();
+        spanish_codes.sort();
+        assert_eq!(french_codes, spanish_codes);
+        assert_eq!(
+            localized_error_message("RACE_NOT_FOUND", Language::French),
+            Some("Course introuvable")
+        );
+        assert_eq!(
+            localized_error_message("RACE_NOT_FOUND", Language::English),
+            None
+        );
+    }
+}
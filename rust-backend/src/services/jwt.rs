@@ -1,15 +1,26 @@
 use chrono::{Duration, Utc};
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use jsonwebtoken::{decode, decode_header, encode, DecodingKey, EncodingKey, Header, Validation};
+use secrecy::ExposeSecret;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::time::Duration as StdDuration;
 use uuid::Uuid;
 
-use crate::domain::{Player, UserRole};
+use crate::configuration::JwtSettings;
+use crate::domain::{Permission, Player, UserRole};
+
+/// Signing secret used when neither the configuration nor `JWT_SECRET` sets one
+const DEVELOPMENT_SECRET: &str = "your-super-secret-jwt-key-change-this-in-production";
 
 /// JWT service configuration
 #[derive(Debug, Clone)]
 pub struct JwtConfig {
+    /// Secret signing new tokens
     pub secret: String,
+    /// Put in the `kid` header of new tokens, when set
+    pub key_id: Option<String>,
+    /// Secrets of earlier keys by `kid`, still accepted until their tokens expire
+    pub previous_keys: HashMap<String, String>,
     pub access_token_expiry: StdDuration,
     pub refresh_token_expiry: StdDuration,
     pub issuer: String,
@@ -20,6 +31,8 @@ impl Default for JwtConfig {
     fn default() -> Self {
         Self {
             secret: "your-super-secret-key-here".to_string(),
+            key_id: None,
+            previous_keys: HashMap::new(),
             access_token_expiry: StdDuration::from_secs(30 * 60), // 30 minutes
             refresh_token_expiry: StdDuration::from_secs(30 * 24 * 60 * 60), // 30 days
             issuer: "racing-game-api".to_string(),
@@ -28,6 +41,30 @@ impl Default for JwtConfig {
     }
 }
 
+impl JwtConfig {
+    /// Configuration signing with the first configured key, or with the
+    /// `JWT_SECRET` environment variable when no key is configured
+    #[must_use]
+    pub fn from_settings(settings: &JwtSettings) -> Self {
+        let mut keys = settings.keys.iter();
+        let (secret, key_id) = match keys.next() {
+            Some(key) => (key.secret.expose_secret().clone(), Some(key.kid.clone())),
+            None => (
+                std::env::var("JWT_SECRET").unwrap_or_else(|_| DEVELOPMENT_SECRET.to_string()),
+                None,
+            ),
+        };
+        Self {
+            secret,
+            key_id,
+            previous_keys: keys
+                .map(|key| (key.kid.clone(), key.secret.expose_secret().clone()))
+                .collect(),
+            ..Self::default()
+        }
+    }
+}
+
 /// JWT claims structure
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Claims {
@@ -39,6 +76,9 @@ pub struct Claims {
     pub iss: String,    // Issuer
     pub aud: String,    // Audience
     pub jti: String,    // JWT ID for blacklisting
+    /// Capabilities of the user's role when the token was issued
+    #[serde(default)]
+    pub permissions: Vec<Permission>,
 }
 
 /// Token pair for access and refresh tokens
@@ -54,6 +94,7 @@ pub struct TokenPair {
 pub struct JwtService {
     encoding_key: EncodingKey,
     decoding_key: DecodingKey,
+    previous_decoding_keys: HashMap<String, DecodingKey>,
     config: JwtConfig,
 }
 
@@ -86,14 +127,43 @@ impl JwtService {
     pub fn new(config: JwtConfig) -> Self {
         let encoding_key = EncodingKey::from_secret(config.secret.as_bytes());
         let decoding_key = DecodingKey::from_secret(config.secret.as_bytes());
+        let previous_decoding_keys = config
+            .previous_keys
+            .iter()
+            .map(|(kid, secret)| (kid.clone(), DecodingKey::from_secret(secret.as_bytes())))
+            .collect();
 
         Self {
             encoding_key,
             decoding_key,
+            previous_decoding_keys,
             config,
         }
     }
 
+    fn header(&self) -> Header {
+        Header {
+            kid: self.config.key_id.clone(),
+            ..Header::default()
+        }
+    }
+
+    /// Key a token was signed with, by the `kid` in its header
+    ///
+    /// Tokens without a `kid` predate key rotation and are checked against
+    /// the current key.
+    fn decoding_key_for(&self, token: &str) -> Result<&DecodingKey, JwtError> {
+        let header = decode_header(token).map_err(|e| JwtError::TokenValidation(e.to_string()))?;
+        match header.kid {
+            None => Ok(&self.decoding_key),
+            Some(kid) if self.config.key_id.as_ref() == Some(&kid) => Ok(&self.decoding_key),
+            Some(kid) => self
+                .previous_decoding_keys
+                .get(&kid)
+                .ok_or(JwtError::InvalidToken),
+        }
+    }
+
     /// Generate an access token for the given user
     pub fn generate_access_token(&self, user: &Player) -> Result<String, JwtError> {
         let now = Utc::now();
@@ -111,9 +181,10 @@ impl JwtService {
             iss: self.config.issuer.clone(),
             aud: self.config.audience.clone(),
             jti: Uuid::new_v4().to_string(),
+            permissions: user.role.permissions(),
         };
 
-        encode(&Header::default(), &claims, &self.encoding_key)
+        encode(&self.header(), &claims, &self.encoding_key)
             .map_err(|e| JwtError::TokenGeneration(e.to_string()))
     }
 
@@ -134,9 +205,10 @@ impl JwtService {
             iss: self.config.issuer.clone(),
             aud: self.config.audience.clone(),
             jti: Uuid::new_v4().to_string(),
+            permissions: user.role.permissions(),
         };
 
-        encode(&Header::default(), &claims, &self.encoding_key)
+        encode(&self.header(), &claims, &self.encoding_key)
             .map_err(|e| JwtError::TokenGeneration(e.to_string()))
     }
 
@@ -146,12 +218,12 @@ impl JwtService {
         validation.set_issuer(&[&self.config.issuer]);
         validation.set_audience(&[&self.config.audience]);
 
-        let token_data = decode::<Claims>(token, &self.decoding_key, &validation).map_err(|e| {
-            match e.kind() {
+        let decoding_key = self.decoding_key_for(token)?;
+        let token_data =
+            decode::<Claims>(token, decoding_key, &validation).map_err(|e| match e.kind() {
                 jsonwebtoken::errors::ErrorKind::ExpiredSignature => JwtError::TokenExpired,
                 _ => JwtError::TokenValidation(e.to_string()),
-            }
-        })?;
+            })?;
 
         Ok(token_data.claims)
     }
@@ -272,6 +344,25 @@ mod tests {
         assert!(token_str.contains('.')); // JWT format check
     }
 
+    #[test]
+    fn access_tokens_carry_the_role_permissions() {
+        let jwt_service = JwtService::new(JwtConfig::default());
+        let mut player = create_test_player();
+
+        let token = jwt_service.generate_access_token(&player).unwrap();
+        assert!(jwt_service
+            .validate_token(&token)
+            .unwrap()
+            .permissions
+            .is_empty());
+
+        player.role = UserRole::Admin;
+        let token = jwt_service.generate_access_token(&player).unwrap();
+        let claims = jwt_service.validate_token(&token).unwrap();
+        assert!(claims.permissions.contains(&Permission::AuditRead));
+        assert_eq!(claims.permissions, UserRole::Admin.permissions());
+    }
+
     #[test]
     fn refresh_token_generation_works() {
         let config = JwtConfig::default();
@@ -389,4 +480,51 @@ mod tests {
         // Each token should have a unique JWT ID
         assert_ne!(claims1.jti, claims2.jti);
     }
+
+    #[test]
+    fn tokens_signed_before_a_key_rotation_stay_valid() {
+        let player = create_test_player();
+        let before = JwtService::new(JwtConfig {
+            secret: "old-secret".to_string(),
+            key_id: Some("2024-01".to_string()),
+            ..JwtConfig::default()
+        });
+        let unversioned = JwtService::new(JwtConfig {
+            secret: "older-secret".to_string(),
+            ..JwtConfig::default()
+        });
+        let after = JwtService::new(JwtConfig {
+            secret: "new-secret".to_string(),
+            key_id: Some("2024-06".to_string()),
+            previous_keys: HashMap::from([("2024-01".to_string(), "old-secret".to_string())]),
+            ..JwtConfig::default()
+        });
+
+        let new_token = after.generate_access_token(&player).unwrap();
+        assert_eq!(
+            decode_header(&new_token).unwrap().kid.as_deref(),
+            Some("2024-06")
+        );
+        assert!(after.validate_token(&new_token).is_ok());
+
+        let old_token = before.generate_access_token(&player).unwrap();
+        assert_eq!(
+            after.validate_token(&old_token).unwrap().sub,
+            player.uuid.to_string()
+        );
+
+        // Tokens without a `kid` are only checked against the current key
+        let unversioned_token = unversioned.generate_access_token(&player).unwrap();
+        assert!(after.validate_token(&unversioned_token).is_err());
+
+        let retired = JwtService::new(JwtConfig {
+            key_id: Some("2023-06".to_string()),
+            ..JwtConfig::default()
+        });
+        let retired_token = retired.generate_access_token(&player).unwrap();
+        assert!(matches!(
+            after.validate_token(&retired_token),
+            Err(JwtError::InvalidToken)
+        ));
+    }
 }
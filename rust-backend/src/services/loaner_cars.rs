@@ -0,0 +1,154 @@
+use chrono::Utc;
+use uuid::Uuid;
+
+use super::car_validation::ValidatedCarData;
+use crate::domain::{
+    Body, BodyName, Car, CarName, ComponentRarity, Engine, EngineName, Pilot, PilotClass,
+    PilotName, PilotPerformance, PilotRarity, PilotSkills,
+};
+
+/// Server-owned starter car, lent for a race to players without an eligible car
+struct LoanerSpec {
+    /// Stable UUIDs of the car, engine, body and pilot
+    uuids: [u128; 4],
+    name: &'static str,
+    /// Straight and curve values of the engine, body and pilot
+    engine: (u8, u8),
+    body: (u8, u8),
+    pilot: (u8, u8),
+}
+
+/// Loaner garage, weakest car first
+const LOANERS: [LoanerSpec; 2] = [
+    LoanerSpec {
+        uuids: [
+            0x1a0e_0001_0000_4000_8000_0000_0000_0001,
+            0x1a0e_0001_0000_4000_8000_0000_0000_0002,
+            0x1a0e_0001_0000_4000_8000_0000_0000_0003,
+            0x1a0e_0001_0000_4000_8000_0000_0000_0004,
+        ],
+        name: "Loaner Rookie",
+        engine: (5, 5),
+        body: (5, 5),
+        pilot: (5, 5),
+    },
+    LoanerSpec {
+        uuids: [
+            0x1a0e_0002_0000_4000_8000_0000_0000_0001,
+            0x1a0e_0002_0000_4000_8000_0000_0000_0002,
+            0x1a0e_0002_0000_4000_8000_0000_0000_0003,
+            0x1a0e_0002_0000_4000_8000_0000_0000_0004,
+        ],
+        name: "Loaner Club",
+        engine: (7, 5),
+        body: (5, 7),
+        pilot: (6, 6),
+    },
+];
+
+impl LoanerSpec {
+    fn car_data(&self) -> ValidatedCarData {
+        let [car_uuid, engine_uuid, body_uuid, pilot_uuid] = self.uuids.map(Uuid::from_u128);
+        let now = Utc::now();
+        let name = |part: &str| format!("{} {part}", self.name);
+
+        let pilot = Pilot {
+            uuid: pilot_uuid,
+            nft_mint_address: None,
+            name: PilotName::parse(&name("Pilot")).expect("loaner pilot name is valid"),
+            pilot_class: PilotClass::AllRounder,
+            rarity: PilotRarity::Rookie,
+            skills: PilotSkills::new(5, 5, 5, 5).expect("loaner pilot skills are valid"),
+            performance: PilotPerformance::new(self.pilot.0, self.pilot.1)
+                .expect("loaner pilot values are valid"),
+            experience_level: 1,
+            is_active: true,
+            created_at: now,
+            updated_at: now,
+        };
+        let engine = Engine {
+            uuid: engine_uuid,
+            nft_mint_address: None,
+            name: EngineName::parse(&name("Engine")).expect("loaner engine name is valid"),
+            rarity: ComponentRarity::Common,
+            straight_value: self.engine.0,
+            curve_value: self.engine.1,
+            created_at: now,
+            updated_at: now,
+        };
+        let body = Body {
+            uuid: body_uuid,
+            nft_mint_address: None,
+            name: BodyName::parse(&name("Body")).expect("loaner body name is valid"),
+            rarity: ComponentRarity::Common,
+            straight_value: self.body.0,
+            curve_value: self.body.1,
+            created_at: now,
+            updated_at: now,
+        };
+        let car = Car {
+            uuid: car_uuid,
+            nft_mint_address: None,
+            name: CarName::parse(self.name).expect("loaner car name is valid"),
+            pilot_uuids: vec![pilot_uuid; 3],
+            engine_uuid: Some(engine_uuid),
+            body_uuid: Some(body_uuid),
+            is_equipped: true,
+            created_at: now,
+            updated_at: now,
+        };
+
+        ValidatedCarData {
+            car,
+            engine,
+            body,
+            pilot,
+        }
+    }
+}
+
+/// Every loaner car, weakest first
+#[must_use]
+pub fn loaner_cars() -> Vec<ValidatedCarData> {
+    LOANERS.iter().map(LoanerSpec::car_data).collect()
+}
+
+/// Loaner car with this UUID, if it is one
+#[must_use]
+pub fn loaner_car(car_uuid: Uuid) -> Option<ValidatedCarData> {
+    LOANERS
+        .iter()
+        .find(|spec| Uuid::from_u128(spec.uuids[0]) == car_uuid)
+        .map(LoanerSpec::car_data)
+}
+
+#[must_use]
+pub fn is_loaner_car(car_uuid: Uuid) -> bool {
+    LOANERS
+        .iter()
+        .any(|spec| Uuid::from_u128(spec.uuids[0]) == car_uuid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loaners_are_complete_common_cars_with_stable_uuids() {
+        let loaners = loaner_cars();
+
+        assert!(loaners.iter().all(
+            |loaner| loaner.car.is_complete() && loaner.top_rarity() == ComponentRarity::Common
+        ));
+        assert!(loaners
+            .windows(2)
+            .all(|pair| pair[0].performance_rating() <= pair[1].performance_rating()));
+        let first = loaners[0].car.uuid;
+        assert!(is_loaner_car(first));
+        assert_eq!(
+            loaner_car(first).unwrap().engine.uuid,
+            loaners[0].engine.uuid
+        );
+        assert!(!is_loaner_car(Uuid::new_v4()));
+    }
+}
@@ -0,0 +1,286 @@
+use axum::http::HeaderMap;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::configuration::LoginThrottleSettings;
+
+/// Counters kept past this many entries are pruned of the stale ones
+const PRUNE_ABOVE: usize = 10_000;
+
+/// Recent failed logins of one account or client IP
+#[derive(Debug, Clone, Copy)]
+struct Failures {
+    count: u32,
+    last_failure: Instant,
+    locked_until: Option<Instant>,
+}
+
+/// Failed login counters per account and per client IP
+///
+/// Kept in memory like sessions, so each instance counts the attempts it
+/// served and a restart forgets them.
+#[derive(Clone)]
+pub struct LoginThrottle {
+    settings: Arc<LoginThrottleSettings>,
+    accounts: Arc<Mutex<HashMap<String, Failures>>>,
+    ips: Arc<Mutex<HashMap<String, Failures>>>,
+}
+
+impl LoginThrottle {
+    #[must_use]
+    pub fn new(settings: LoginThrottleSettings) -> Self {
+        Self {
+            settings: Arc::new(settings),
+            accounts: Arc::default(),
+            ips: Arc::default(),
+        }
+    }
+
+    /// IP failures are counted against: the peer, or the client a trusted
+    /// proxy forwarded the request for
+    ///
+    /// `X-Forwarded-For` is read from the right, as proxies append to it,
+    /// and the first hop that is not a trusted proxy is the client. Entries
+    /// left of it were written by the client and are ignored.
+    #[must_use]
+    pub fn client_ip(&self, peer: IpAddr, headers: &HeaderMap) -> IpAddr {
+        let trusted = |ip: &IpAddr| self.settings.trusted_proxies.contains(&ip.to_canonical());
+        let mut client = peer.to_canonical();
+        if !trusted(&client) {
+            return client;
+        }
+        let hops: Vec<&str> = headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .collect();
+        for hop in hops.into_iter().rev() {
+            let Ok(ip) = hop.trim().parse::<IpAddr>() else {
+                break;
+            };
+            client = ip.to_canonical();
+            if !trusted(&client) {
+                break;
+            }
+        }
+        client
+    }
+
+    /// How long logins to `email` or from `ip` are refused, if they are
+    #[must_use]
+    pub fn locked_for(&self, email: &str, ip: Option<&str>) -> Option<Duration> {
+        self.locked_for_at(email, ip, Instant::now())
+    }
+
+    /// Count a failed login, locking the account or IP once over its limit
+    ///
+    /// Returns how long logins are now refused, if they are.
+    #[must_use]
+    pub fn record_failure(&self, email: &str, ip: Option<&str>) -> Option<Duration> {
+        self.record_failure_at(email, ip, Instant::now())
+    }
+
+    /// Forget the failures of an account after a successful login
+    ///
+    /// The IP's failures stand, as one client may be trying many accounts.
+    pub fn record_success(&self, email: &str) {
+        self.accounts.lock().unwrap().remove(&account_key(email));
+    }
+
+    fn locked_for_at(&self, email: &str, ip: Option<&str>, now: Instant) -> Option<Duration> {
+        if !self.settings.enabled {
+            return None;
+        }
+        let account = remaining(self.accounts.lock().unwrap().get(&account_key(email)), now);
+        let ip = ip.and_then(|ip| remaining(self.ips.lock().unwrap().get(ip), now));
+        account.max(ip)
+    }
+
+    fn record_failure_at(&self, email: &str, ip: Option<&str>, now: Instant) -> Option<Duration> {
+        if !self.settings.enabled {
+            return None;
+        }
+        let account = self.fail(
+            &self.accounts,
+            account_key(email),
+            self.settings.max_failures_per_account,
+            now,
+        );
+        let ip = ip.and_then(|ip| {
+            self.fail(
+                &self.ips,
+                ip.to_string(),
+                self.settings.max_failures_per_ip,
+                now,
+            )
+        });
+        account.max(ip)
+    }
+
+    fn fail(
+        &self,
+        counters: &Mutex<HashMap<String, Failures>>,
+        key: String,
+        max_failures: u32,
+        now: Instant,
+    ) -> Option<Duration> {
+        let window = Duration::from_secs(self.settings.window_seconds);
+        let mut counters = counters.lock().unwrap();
+        if counters.len() > PRUNE_ABOVE {
+            counters.retain(|_, failures| !is_stale(failures, window, now));
+        }
+
+        let failures = counters.entry(key).or_insert(Failures {
+            count: 0,
+            last_failure: now,
+            locked_until: None,
+        });
+        if is_stale(failures, window, now) {
+            failures.count = 0;
+        }
+        failures.count += 1;
+        failures.last_failure = now;
+        if failures.count >= max_failures.max(1) {
+            let lockout = self.lockout(failures.count - max_failures.max(1));
+            failures.locked_until = Some(now + lockout);
+        }
+        remaining(Some(failures), now)
+    }
+
+    /// Lockout after `excess` failures past the limit
+    fn lockout(&self, excess: u32) -> Duration {
+        let seconds = self
+            .settings
+            .base_lockout_seconds
+            .saturating_mul(1_u64 << excess.min(32))
+            .min(self.settings.max_lockout_seconds);
+        Duration::from_secs(seconds)
+    }
+}
+
+/// Accounts are counted by their email, whatever its case
+fn account_key(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+fn remaining(failures: Option<&Failures>, now: Instant) -> Option<Duration> {
+    failures
+        .and_then(|failures| failures.locked_until)
+        .filter(|until| *until > now)
+        .map(|until| until - now)
+}
+
+/// Whether failures no longer count: unlocked and none within `window`
+fn is_stale(failures: &Failures, window: Duration, now: Instant) -> bool {
+    !matches!(failures.locked_until, Some(until) if until > now)
+        && now.duration_since(failures.last_failure) >= window
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn throttle() -> LoginThrottle {
+        LoginThrottle::new(LoginThrottleSettings {
+            max_failures_per_account: 3,
+            max_failures_per_ip: 5,
+            base_lockout_seconds: 30,
+            max_lockout_seconds: 100,
+            window_seconds: 900,
+            ..LoginThrottleSettings::default()
+        })
+    }
+
+    #[test]
+    fn lockouts_double_with_each_failure_past_the_limit() {
+        let throttle = throttle();
+        let start = Instant::now();
+
+        assert_eq!(throttle.record_failure_at("Ann@x.io", None, start), None);
+        assert_eq!(throttle.record_failure_at("ann@x.io", None, start), None);
+        assert_eq!(
+            throttle.record_failure_at("ann@x.io", None, start),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            throttle.locked_for_at("ANN@x.io", None, start + Duration::from_secs(10)),
+            Some(Duration::from_secs(20))
+        );
+
+        let later = start + Duration::from_secs(31);
+        assert_eq!(throttle.locked_for_at("ann@x.io", None, later), None);
+        assert_eq!(
+            throttle.record_failure_at("ann@x.io", None, later),
+            Some(Duration::from_secs(60))
+        );
+        let later = later + Duration::from_secs(61);
+        assert_eq!(
+            throttle.record_failure_at("ann@x.io", None, later),
+            Some(Duration::from_secs(100))
+        );
+
+        throttle.record_success("ann@x.io");
+        assert_eq!(throttle.locked_for_at("ann@x.io", None, later), None);
+    }
+
+    #[test]
+    fn one_ip_trying_many_accounts_is_locked() {
+        let throttle = throttle();
+        let now = Instant::now();
+        for n in 0..4 {
+            let email = format!("player{n}@x.io");
+            assert_eq!(
+                throttle.record_failure_at(&email, Some("10.0.0.1"), now),
+                None
+            );
+        }
+
+        assert_eq!(
+            throttle.record_failure_at("player9@x.io", Some("10.0.0.1"), now),
+            Some(Duration::from_secs(30))
+        );
+        assert!(throttle
+            .locked_for_at("fresh@x.io", Some("10.0.0.1"), now)
+            .is_some());
+        assert_eq!(
+            throttle.locked_for_at("fresh@x.io", Some("10.0.0.2"), now),
+            None
+        );
+    }
+
+    #[test]
+    fn failures_are_forgotten_after_the_window() {
+        let throttle = throttle();
+        let start = Instant::now();
+        throttle.record_failure_at("ann@x.io", None, start);
+        throttle.record_failure_at("ann@x.io", None, start);
+
+        let later = start + Duration::from_secs(900);
+        assert_eq!(throttle.record_failure_at("ann@x.io", None, later), None);
+    }
+
+    #[test]
+    fn forwarded_addresses_are_only_believed_from_trusted_proxies() {
+        let proxy: IpAddr = "10.0.0.5".parse().unwrap();
+        let throttle = LoginThrottle::new(LoginThrottleSettings {
+            trusted_proxies: vec![proxy],
+            ..LoginThrottleSettings::default()
+        });
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-for",
+            "1.2.3.4, 203.0.113.7, 10.0.0.5".parse().unwrap(),
+        );
+
+        let direct: IpAddr = "198.51.100.9".parse().unwrap();
+        assert_eq!(throttle.client_ip(direct, &headers), direct);
+        assert_eq!(
+            throttle.client_ip(proxy, &headers),
+            "203.0.113.7".parse::<IpAddr>().unwrap()
+        );
+        assert_eq!(throttle.client_ip(proxy, &HeaderMap::new()), proxy);
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::HashSet;
+use std::hash::BuildHasher;
+use uuid::Uuid;
+
+use crate::domain::{Race, RaceMode, RaceStatus};
+
+/// Skill rating of a player without any ranked finish
+pub const BASE_SKILL_RATING: u32 = 1000;
+
+/// Rating won for each opponent finishing behind, and lost for each finishing ahead
+pub const SKILL_RATING_STEP: u32 = 25;
+
+/// Skill rating from a player's finished ranked races
+///
+/// Every race counts as a duel against each other finisher: the player gains
+/// `SKILL_RATING_STEP` per opponent beaten and loses as much per opponent ahead.
+#[must_use]
+pub fn skill_rating(races: &[Race], player_uuid: Uuid) -> u32 {
+    let mut rating = i64::from(BASE_SKILL_RATING);
+    for race in races.iter().filter(|race| race.counts_for_rankings()) {
+        let Some(position) = race
+            .participants
+            .iter()
+            .find(|p| p.player_uuid == player_uuid)
+            .and_then(|p| p.finish_position)
+        else {
+            continue;
+        };
+        for other in race.participants.iter().filter_map(|p| p.finish_position) {
+            match other.cmp(&position) {
+                std::cmp::Ordering::Greater => rating += i64::from(SKILL_RATING_STEP),
+                std::cmp::Ordering::Less => rating -= i64::from(SKILL_RATING_STEP),
+                std::cmp::Ordering::Equal => {}
+            }
+        }
+    }
+    u32::try_from(rating.max(0)).unwrap_or(u32::MAX)
+}
+
+/// Open race offered to a player, with the friends already in it
+#[derive(Debug, Clone)]
+pub struct RankedRace {
+    pub race: Race,
+    pub friends: Vec<Uuid>,
+}
+
+/// Races `player_uuid` could join, best match first
+///
+/// Only waiting standard races the player is not already in, whose skill
+/// bracket admits `skill_rating` and whose car tier admits one of
+/// `car_ratings`, the performance ratings of the player's cars, are offered.
+/// Races with more of the player's friends come first, then fuller races,
+/// which start sooner, then the longest waiting.
+#[must_use]
+pub fn rank_open_races<S: BuildHasher>(
+    races: Vec<Race>,
+    player_uuid: Uuid,
+    friends: &HashSet<Uuid, S>,
+    skill_rating: u32,
+    car_ratings: &[u32],
+) -> Vec<RankedRace> {
+    let mut ranked: Vec<RankedRace> = races
+        .into_iter()
+        .filter(|race| {
+            race.status == RaceStatus::Waiting
+                && race.mode == RaceMode::Standard
+                && race
+                    .rules
+                    .skill_bracket
+                    .is_none_or(|bracket| bracket.contains(skill_rating))
+                && race
+                    .rules
+                    .car_tier
+                    .is_none_or(|tier| car_ratings.iter().any(|&rating| tier.contains(rating)))
+                && !race
+                    .participants
+                    .iter()
+                    .any(|p| p.player_uuid == player_uuid)
+        })
+        .map(|race| {
+            let friends = race
+                .participants
+                .iter()
+                .map(|p| p.player_uuid)
+                .filter(|uuid| friends.contains(uuid))
+                .collect();
+            RankedRace { race, friends }
+        })
+        .collect();
+
+    ranked.sort_by(|a, b| {
+        b.friends
+            .len()
+            .cmp(&a.friends.len())
+            .then(b.race.participants.len().cmp(&a.race.participants.len()))
+            .then(a.race.created_at.cmp(&b.race.created_at))
+    });
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{RatingWindow, Sector, SectorType, Track};
+
+    fn race(participants: &[Uuid]) -> Race {
+        let sector = |id, sector_type| Sector {
+            id,
+            name: format!("Sector {id}"),
+            min_value: id * 10,
+            max_value: id * 10 + 10,
+            slot_capacity: None,
+            sector_type,
+            capacity_schedule: Vec::new(),
+            modifiers: None,
+        };
+        let sectors = vec![sector(0, SectorType::Start), sector(1, SectorType::Finish)];
+        let track = Track::new("Track".to_string(), sectors).unwrap();
+        let mut race = Race::new("Open".to_string(), track, 3);
+        for player_uuid in participants {
+            race.add_participant(*player_uuid, Uuid::new_v4(), Uuid::new_v4())
+                .unwrap();
+        }
+        race
+    }
+
+    #[test]
+    fn races_with_friends_come_first() {
+        let (player, friend) = (Uuid::new_v4(), Uuid::new_v4());
+        let crowded = race(&[Uuid::new_v4(), Uuid::new_v4()]);
+        let with_friend = race(&[friend]);
+        let joined = race(&[player]);
+        let mut started = race(&[friend]);
+        started.status = RaceStatus::InProgress;
+
+        let ranked = rank_open_races(
+            vec![crowded.clone(), joined, started, with_friend.clone()],
+            player,
+            &HashSet::from([friend]),
+            BASE_SKILL_RATING,
+            &[],
+        );
+
+        let order: Vec<Uuid> = ranked.iter().map(|r| r.race.uuid).collect();
+        assert_eq!(order, vec![with_friend.uuid, crowded.uuid]);
+        assert_eq!(ranked[0].friends, vec![friend]);
+    }
+
+    #[test]
+    fn races_outside_the_skill_bracket_are_not_offered() {
+        let player = Uuid::new_v4();
+        let open = race(&[]);
+        let mut experts = race(&[]);
+        experts.rules.skill_bracket = Some(RatingWindow {
+            min: Some(BASE_SKILL_RATING + 1),
+            max: None,
+        });
+
+        let ranked = rank_open_races(
+            vec![open.clone(), experts],
+            player,
+            &HashSet::new(),
+            BASE_SKILL_RATING,
+            &[],
+        );
+
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].race.uuid, open.uuid);
+    }
+
+    #[test]
+    fn races_whose_car_tier_fits_none_of_the_cars_are_not_offered() {
+        let player = Uuid::new_v4();
+        let mut rookies = race(&[]);
+        rookies.rules.car_tier = Some(RatingWindow {
+            min: None,
+            max: Some(50),
+        });
+        let mut pros = race(&[]);
+        pros.rules.car_tier = Some(RatingWindow {
+            min: Some(80),
+            max: None,
+        });
+
+        let ranked = rank_open_races(
+            vec![rookies.clone(), pros],
+            player,
+            &HashSet::new(),
+            BASE_SKILL_RATING,
+            &[40, 70],
+        );
+
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].race.uuid, rookies.uuid);
+    }
+
+    #[test]
+    fn skill_rating_counts_opponents_beaten_and_ahead() {
+        let (player, rival, third) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let mut won = race(&[player, rival, third]);
+        won.status = RaceStatus::Finished;
+        for (participant, position) in won.participants.iter_mut().zip(1..) {
+            participant.finish_position = Some(position);
+        }
+        let mut lost = race(&[rival, player]);
+        lost.status = RaceStatus::Finished;
+        for (participant, position) in lost.participants.iter_mut().zip(1..) {
+            participant.finish_position = Some(position);
+        }
+
+        assert_eq!(
+            skill_rating(&[won, lost], player),
+            BASE_SKILL_RATING + SKILL_RATING_STEP
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use axum::{
+    extract::{MatchedPath, Request},
+    middleware::Next,
+    response::Response,
+};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+use mongodb::event::command::{CommandEventHandler, CommandFailedEvent, CommandSucceededEvent};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// Time to answer an HTTP request, by method, route and status
+pub const HTTP_REQUEST_DURATION: &str = "http_request_duration_seconds";
+/// Time to resolve a lap once every action is in, by race mode
+pub const TURN_PROCESSING_DURATION: &str = "turn_processing_duration_seconds";
+/// Laps resolved, by race mode
+pub const LAPS_PROCESSED: &str = "laps_processed_total";
+/// Time `MongoDB` took to run a command, by command and outcome
+pub const MONGO_COMMAND_DURATION: &str = "mongodb_command_duration_seconds";
+/// Races currently in progress, refreshed on every scrape
+pub const RACES_IN_PROGRESS: &str = "races_in_progress";
+
+/// Histogram buckets in seconds, from a quick lookup to a slow aggregation
+const DURATION_BUCKETS: [f64; 12] = [
+    0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0,
+];
+
+static PROMETHEUS: OnceLock<PrometheusHandle> = OnceLock::new();
+
+/// Handle rendering the metrics of the process-wide Prometheus recorder
+///
+/// The recorder is installed by the first call; later calls, such as a
+/// second server started by the tests, share it.
+pub fn prometheus_handle() -> PrometheusHandle {
+    PROMETHEUS
+        .get_or_init(|| {
+            PrometheusBuilder::new()
+                .set_buckets_for_metric(Matcher::Suffix("_seconds".to_string()), &DURATION_BUCKETS)
+                .and_then(PrometheusBuilder::install_recorder)
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Prometheus recorder not installed: {}", e);
+                    PrometheusBuilder::new().build_recorder().handle()
+                })
+        })
+        .clone()
+}
+
+/// Record how long a lap took to resolve
+pub fn record_turn_processed(mode: &'static str, duration: Duration) {
+    metrics::histogram!(TURN_PROCESSING_DURATION, "mode" => mode).record(duration.as_secs_f64());
+    metrics::counter!(LAPS_PROCESSED, "mode" => mode).increment(1);
+}
+
+/// Middleware timing each request under the route it matched
+///
+/// Requests no route matched share the `unmatched` label, so unknown paths
+/// cannot grow the number of series.
+pub async fn track_http_metrics(request: Request, next: Next) -> Response {
+    let started = Instant::now();
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or_else(|| "unmatched".to_string(), |path| path.as_str().to_string());
+
+    let response = next.run(request).await;
+
+    metrics::histogram!(
+        HTTP_REQUEST_DURATION,
+        "method" => method,
+        "route" => route,
+        "status" => response.status().as_u16().to_string(),
+    )
+    .record(started.elapsed().as_secs_f64());
+    response
+}
+
+/// Times every command the `MongoDB` client runs
+#[derive(Debug, Default)]
+pub struct MongoCommandMetrics;
+
+impl CommandEventHandler for MongoCommandMetrics {
+    fn handle_command_succeeded_event(&self, event: CommandSucceededEvent) {
+        metrics::histogram!(
+            MONGO_COMMAND_DURATION,
+            "command" => event.command_name,
+            "outcome" => "success",
+        )
+        .record(event.duration.as_secs_f64());
+    }
+
+    fn handle_command_failed_event(&self, event: CommandFailedEvent) {
+        metrics::histogram!(
+            MONGO_COMMAND_DURATION,
+            "command" => event.command_name,
+            "outcome" => "failure",
+        )
+        .record(event.duration.as_secs_f64());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::get, Router};
+
+    #[tokio::test]
+    async fn requests_are_timed_under_their_route_template() {
+        let prometheus = prometheus_handle();
+        let app = Router::new()
+            .nest(
+                "/api/v1",
+                Router::new().route("/metrics-test/:race_uuid", get(|| async { "ok" })),
+            )
+            .layer(axum::middleware::from_fn(track_http_metrics));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        reqwest::get(format!("http://{address}/api/v1/metrics-test/42"))
+            .await
+            .unwrap();
+
+        let rendered = prometheus.render();
+        assert!(rendered.contains(r#"route="/api/v1/metrics-test/:race_uuid""#));
+        assert!(!rendered.contains("/api/v1/metrics-test/42"));
+    }
+}
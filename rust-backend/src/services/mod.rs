@@ -1,7 +1,46 @@
+pub mod analytics_export;
+pub mod anchoring;
+pub mod anticheat;
+pub mod audit;
+pub mod car_data_cache;
 pub mod car_validation;
+pub mod chat;
+pub mod email;
+pub mod events;
+pub mod i18n;
 pub mod jwt;
+pub mod loaner_cars;
+pub mod login_throttle;
+pub mod matchmaking;
+pub mod metrics;
+pub mod oauth;
+pub mod outbox;
+pub mod ownership;
+pub mod packs;
+pub mod payouts;
+pub mod privacy;
+pub mod push;
+pub mod race_cache;
+pub mod race_event_log;
 pub mod session;
+pub mod starter_pack;
+pub mod webhooks;
 
+pub use analytics_export::{AnalyticsExporter, AnalyticsSink};
+pub use anchoring::RaceAnchorer;
+pub use audit::{record_audit, AuditEntry};
+pub use car_data_cache::CarDataCache;
 pub use car_validation::{CarValidationError, CarValidationService, ValidatedCarData};
+pub use chat::ChatHub;
+pub use events::{DomainEvent, EventBus};
 pub use jwt::{Claims, JwtConfig, JwtService};
+pub use login_throttle::LoginThrottle;
+pub use oauth::OAuthClient;
+pub use outbox::{OutboxMessage, OutboxRelay};
+pub use ownership::{AssetKind, OwnershipResolver, PlayerAssets};
+pub use payouts::PayoutSender;
+pub use push::PushSender;
+pub use race_cache::RaceCache;
+pub use race_event_log::RaceEventLog;
 pub use session::{Session, SessionConfig, SessionManager};
+pub use webhooks::WebhookSender;
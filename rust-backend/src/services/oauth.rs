@@ -0,0 +1,291 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use rand::RngCore;
+use reqwest::Url;
+use secrecy::ExposeSecret;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+
+use crate::configuration::{OAuthClientSettings, OAuthSettings};
+use crate::domain::OAuthProvider;
+
+/// Why signing in with a provider failed
+#[derive(Debug, thiserror::Error)]
+pub enum OAuthError {
+    #[error("Signing in with {0} is not configured")]
+    NotConfigured(&'static str),
+    #[error("The provider refused the sign-in: {0}")]
+    Provider(String),
+}
+
+impl From<reqwest::Error> for OAuthError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Provider(error.to_string())
+    }
+}
+
+/// A provider account, as its provider describes it
+#[derive(Debug, Clone, PartialEq)]
+pub struct OAuthProfile {
+    /// The account's ID at the provider
+    pub subject: String,
+    pub email: Option<String>,
+    /// Whether the provider checked the player owns `email`
+    pub email_verified: bool,
+    pub display_name: Option<String>,
+}
+
+/// Where a provider's authorization-code flow happens
+struct Endpoints {
+    authorize: &'static str,
+    token: &'static str,
+    profile: &'static str,
+    scope: &'static str,
+}
+
+fn endpoints(provider: OAuthProvider) -> Endpoints {
+    match provider {
+        OAuthProvider::Google => Endpoints {
+            authorize: "https://accounts.google.com/o/oauth2/v2/auth",
+            token: "https://oauth2.googleapis.com/token",
+            profile: "https://openidconnect.googleapis.com/v1/userinfo",
+            scope: "openid email profile",
+        },
+        OAuthProvider::Discord => Endpoints {
+            authorize: "https://discord.com/oauth2/authorize",
+            token: "https://discord.com/api/oauth2/token",
+            profile: "https://discord.com/api/users/@me",
+            scope: "identify email",
+        },
+    }
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+}
+
+#[derive(Deserialize)]
+struct GoogleUser {
+    sub: String,
+    email: Option<String>,
+    #[serde(default)]
+    email_verified: bool,
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct DiscordUser {
+    id: String,
+    username: String,
+    global_name: Option<String>,
+    email: Option<String>,
+    #[serde(default)]
+    verified: bool,
+}
+
+/// New PKCE code verifier: 32 random bytes, hex-encoded
+#[must_use]
+pub fn pkce_verifier() -> String {
+    let mut verifier = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut verifier);
+    hex::encode(verifier)
+}
+
+/// The `S256` code challenge sent to the provider for `verifier`
+#[must_use]
+pub fn pkce_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+/// Authorization-code client for the configured OAuth providers
+#[derive(Clone)]
+pub struct OAuthClient {
+    http: reqwest::Client,
+    google: Option<OAuthClientSettings>,
+    discord: Option<OAuthClientSettings>,
+}
+
+impl OAuthClient {
+    pub fn from_settings(settings: &OAuthSettings) -> Result<Self, anyhow::Error> {
+        Ok(Self {
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()?,
+            google: settings.google.clone(),
+            discord: settings.discord.clone(),
+        })
+    }
+
+    fn client(&self, provider: OAuthProvider) -> Result<&OAuthClientSettings, OAuthError> {
+        match provider {
+            OAuthProvider::Google => self.google.as_ref(),
+            OAuthProvider::Discord => self.discord.as_ref(),
+        }
+        .ok_or(OAuthError::NotConfigured(provider.as_str()))
+    }
+
+    /// Provider page asking the player to sign in, which then redirects to the
+    /// callback with a code and `state`
+    ///
+    /// The code is bound to `code_challenge`, see [`pkce_challenge`].
+    pub fn authorize_url(
+        &self,
+        provider: OAuthProvider,
+        state: &str,
+        code_challenge: &str,
+    ) -> Result<Url, OAuthError> {
+        let client = self.client(provider)?;
+        let endpoints = endpoints(provider);
+        Url::parse_with_params(
+            endpoints.authorize,
+            &[
+                ("response_type", "code"),
+                ("client_id", client.client_id.as_str()),
+                ("redirect_uri", client.redirect_uri.as_str()),
+                ("scope", endpoints.scope),
+                ("state", state),
+                ("code_challenge", code_challenge),
+                ("code_challenge_method", "S256"),
+                ("prompt", "consent"),
+            ],
+        )
+        .map_err(|e| OAuthError::Provider(e.to_string()))
+    }
+
+    /// The account that signed in, from the code the provider redirected with
+    /// and the verifier its challenge was made from
+    pub async fn profile(
+        &self,
+        provider: OAuthProvider,
+        code: &str,
+        code_verifier: &str,
+    ) -> Result<OAuthProfile, OAuthError> {
+        let client = self.client(provider)?;
+        let endpoints = endpoints(provider);
+        let token: TokenResponse = self
+            .http
+            .post(endpoints.token)
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("code_verifier", code_verifier),
+                ("redirect_uri", client.redirect_uri.as_str()),
+                ("client_id", client.client_id.as_str()),
+                (
+                    "client_secret",
+                    client.client_secret.expose_secret().as_str(),
+                ),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let response = self
+            .http
+            .get(endpoints.profile)
+            .bearer_auth(&token.access_token)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(match provider {
+            OAuthProvider::Google => response.json::<GoogleUser>().await?.into(),
+            OAuthProvider::Discord => response.json::<DiscordUser>().await?.into(),
+        })
+    }
+}
+
+impl From<GoogleUser> for OAuthProfile {
+    fn from(user: GoogleUser) -> Self {
+        Self {
+            subject: user.sub,
+            email: user.email,
+            email_verified: user.email_verified,
+            display_name: user.name,
+        }
+    }
+}
+
+impl From<DiscordUser> for OAuthProfile {
+    fn from(user: DiscordUser) -> Self {
+        Self {
+            subject: user.id,
+            email: user.email,
+            email_verified: user.verified,
+            display_name: Some(user.global_name.unwrap_or(user.username)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use secrecy::Secret;
+
+    #[test]
+    fn only_configured_providers_are_offered() {
+        let client = OAuthClient::from_settings(&OAuthSettings {
+            google: None,
+            discord: Some(OAuthClientSettings {
+                client_id: "1234".to_string(),
+                client_secret: Secret::new("shh".to_string()),
+                redirect_uri: "https://api.example.com/api/v1/auth/oauth/discord/callback"
+                    .to_string(),
+            }),
+        })
+        .unwrap();
+
+        let url = client
+            .authorize_url(OAuthProvider::Discord, "xyz", "challenge")
+            .unwrap();
+        let query: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+        assert!(query.contains(&("client_id".to_string(), "1234".to_string())));
+        assert!(query.contains(&("state".to_string(), "xyz".to_string())));
+        assert!(query.contains(&("scope".to_string(), "identify email".to_string())));
+        assert!(query.contains(&("code_challenge".to_string(), "challenge".to_string())));
+        assert!(query.contains(&("code_challenge_method".to_string(), "S256".to_string())));
+        assert!(!url.as_str().contains("shh"));
+
+        assert!(matches!(
+            client.authorize_url(OAuthProvider::Google, "xyz", "challenge"),
+            Err(OAuthError::NotConfigured("google"))
+        ));
+    }
+
+    #[test]
+    fn pkce_challenges_follow_rfc_7636() {
+        // The example from RFC 7636, appendix B
+        assert_eq!(
+            pkce_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+        let verifier = pkce_verifier();
+        assert_eq!(verifier.len(), 64);
+        assert_ne!(verifier, pkce_verifier());
+    }
+
+    #[test]
+    fn discord_profiles_fall_back_to_the_username() {
+        let user: DiscordUser = serde_json::from_value(serde_json::json!({
+            "id": "80351110224678912",
+            "username": "nelly",
+            "global_name": null,
+            "email": "nelly@example.com",
+            "verified": true
+        }))
+        .unwrap();
+
+        assert_eq!(
+            OAuthProfile::from(user),
+            OAuthProfile {
+                subject: "80351110224678912".to_string(),
+                email: Some("nelly@example.com".to_string()),
+                email_verified: true,
+                display_name: Some("nelly".to_string()),
+            }
+        );
+    }
+}
@@ -0,0 +1,318 @@
+use chrono::DateTime;
+use mongodb::{
+    bson::{doc, DateTime as BsonDateTime, Document},
+    options::FindOneAndUpdateOptions,
+    Database,
+};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime};
+use uuid::Uuid;
+
+use crate::database::is_duplicate_key;
+use crate::domain::{LapResult, Notification, Race, WebhookEvent, WebhookPayload};
+use crate::services::events::{DomainEvent, EventBus, EventEnvelope};
+use crate::services::webhooks::enqueue_webhook_deliveries;
+
+/// Race document field holding the messages not relayed yet
+const OUTBOX_FIELD: &str = "outbox";
+/// Until when a relay owns the outbox of a race
+const LEASE_FIELD: &str = "outbox_lease_until";
+const LEASE: Duration = Duration::from_mins(1);
+
+/// Race change to announce once it is saved
+///
+/// Messages are pushed onto the race document by the same update that saves
+/// the change (see [`push_outbox`]), so a message exists if and only if its
+/// change was persisted, without needing a multi-document transaction.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OutboxMessage {
+    #[serde(with = "uuid_as_string")]
+    pub uuid: Uuid,
+    pub created_at: BsonDateTime,
+    pub payload: OutboxPayload,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "kind", content = "data")]
+pub enum OutboxPayload {
+    /// Published on the event bus
+    Event(DomainEvent),
+    /// Queued for the webhooks of the race subscribed to `event`
+    Webhook { event: WebhookEvent, body: String },
+    /// Stored in a player's inbox, from where it is pushed to their devices
+    Notification(Notification),
+}
+
+impl OutboxMessage {
+    #[must_use]
+    pub fn new(payload: OutboxPayload) -> Self {
+        Self {
+            uuid: Uuid::new_v4(),
+            created_at: BsonDateTime::now(),
+            payload,
+        }
+    }
+
+    /// Messages announcing the change from `before` to `after`: domain events
+    /// first, then webhook payloads and player notifications
+    #[must_use]
+    pub fn between(before: &Race, after: &Race, lap_results: &[LapResult]) -> Vec<Self> {
+        let events = DomainEvent::between(before, after, lap_results)
+            .into_iter()
+            .map(OutboxPayload::Event);
+        let webhooks = WebhookEvent::between(before, after, lap_results)
+            .into_iter()
+            .filter_map(|event| {
+                serde_json::to_string(&WebhookPayload::new(event, after, lap_results))
+                    .map_err(|e| tracing::error!("Failed to serialize webhook payload: {}", e))
+                    .ok()
+                    .map(|body| OutboxPayload::Webhook { event, body })
+            });
+        let notifications = Notification::between(before, after)
+            .into_iter()
+            .map(OutboxPayload::Notification);
+        events
+            .chain(webhooks)
+            .chain(notifications)
+            .map(Self::new)
+            .collect()
+    }
+}
+
+/// Make a race `update` also append `messages` to the race's outbox
+pub fn push_outbox(
+    update: &mut Document,
+    messages: &[OutboxMessage],
+) -> Result<(), mongodb::error::Error> {
+    if !messages.is_empty() {
+        let messages = mongodb::bson::to_bson(messages)?;
+        update.insert("$push", doc! { OUTBOX_FIELD: { "$each": messages } });
+    }
+    Ok(())
+}
+
+/// Filter matching races with messages left to relay
+fn has_pending_outbox() -> Document {
+    doc! { format!("{OUTBOX_FIELD}.uuid"): { "$exists": true } }
+}
+
+/// Relays race outboxes to the event bus and the webhook queue
+///
+/// A message is removed from the outbox only after it was handed over, so a
+/// crash in between relays it again with the same id: the event envelope
+/// carries the message uuid for consumers to deduplicate on, webhook
+/// deliveries derive their id from it and notifications keep their own, so
+/// each is queued or stored once.
+pub struct OutboxRelay {
+    bus: EventBus,
+}
+
+impl OutboxRelay {
+    #[must_use]
+    pub fn new(bus: EventBus) -> Self {
+        Self { bus }
+    }
+
+    /// Relay the outboxes of up to `batch_size` races, oldest messages first
+    /// Returns the number of messages relayed.
+    pub async fn relay(
+        &self,
+        database: &Database,
+        batch_size: u32,
+    ) -> Result<u64, mongodb::error::Error> {
+        let mut relayed = 0;
+        for _ in 0..batch_size {
+            let Some((race_uuid, messages)) = claim_outbox(database).await? else {
+                break;
+            };
+            relayed += self.relay_race(database, race_uuid, messages).await?;
+        }
+        Ok(relayed)
+    }
+
+    /// Relay the messages of one race in order, stopping at the first failure
+    /// so the rest wait for the lease to expire and keep their order
+    async fn relay_race(
+        &self,
+        database: &Database,
+        race_uuid: Uuid,
+        messages: Vec<OutboxMessage>,
+    ) -> Result<u64, mongodb::error::Error> {
+        let races = database.collection::<Document>("races");
+        let filter = doc! { "uuid": race_uuid.to_string() };
+        let mut relayed = 0;
+        for message in messages {
+            if let Err(e) = self.hand_over(database, race_uuid, &message).await {
+                tracing::warn!(
+                    "Outbox message {} of race {} not relayed: {:?}",
+                    message.uuid,
+                    race_uuid,
+                    e
+                );
+                return Ok(relayed);
+            }
+            races
+                .update_one(
+                    filter.clone(),
+                    doc! { "$pull": { OUTBOX_FIELD: { "uuid": message.uuid.to_string() } } },
+                    None,
+                )
+                .await?;
+            relayed += 1;
+        }
+        races
+            .update_one(filter, doc! { "$unset": { LEASE_FIELD: "" } }, None)
+            .await?;
+        Ok(relayed)
+    }
+
+    async fn hand_over(
+        &self,
+        database: &Database,
+        race_uuid: Uuid,
+        message: &OutboxMessage,
+    ) -> Result<(), anyhow::Error> {
+        match &message.payload {
+            OutboxPayload::Event(event) => {
+                let envelope = EventEnvelope {
+                    id: message.uuid,
+                    occurred_at: DateTime::from_timestamp_millis(
+                        message.created_at.timestamp_millis(),
+                    )
+                    .unwrap_or_default(),
+                    event: event.clone(),
+                };
+                self.bus.publish(&envelope).await?;
+            }
+            OutboxPayload::Webhook { event, body } => {
+                enqueue_webhook_deliveries(database, race_uuid, message.uuid, *event, body).await?;
+            }
+            OutboxPayload::Notification(notification) => {
+                let inserted = database
+                    .collection::<Notification>("notifications")
+                    .insert_one(notification, None)
+                    .await;
+                match inserted {
+                    Err(e) if !is_duplicate_key(&e) => return Err(e.into()),
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Take the lease on a race with a pending outbox and return its messages
+async fn claim_outbox(
+    database: &Database,
+) -> Result<Option<(Uuid, Vec<OutboxMessage>)>, mongodb::error::Error> {
+    let now = SystemTime::now();
+    let mut filter = has_pending_outbox();
+    filter.insert(
+        LEASE_FIELD,
+        doc! { "$not": { "$gt": BsonDateTime::from_system_time(now) } },
+    );
+    let options = FindOneAndUpdateOptions::builder()
+        .projection(doc! { "uuid": 1, OUTBOX_FIELD: 1 })
+        .build();
+    let claimed = database
+        .collection::<Document>("races")
+        .find_one_and_update(
+            filter,
+            doc! { "$set": { LEASE_FIELD: BsonDateTime::from_system_time(now + LEASE) } },
+            options,
+        )
+        .await?;
+
+    let Some(race) = claimed else {
+        return Ok(None);
+    };
+    let race_uuid = race
+        .get_str("uuid")
+        .map_err(mongodb::error::Error::custom)
+        .and_then(|uuid| Uuid::parse_str(uuid).map_err(mongodb::error::Error::custom))?;
+    let messages = race
+        .get_array(OUTBOX_FIELD)
+        .map_err(mongodb::error::Error::custom)?
+        .iter()
+        .cloned()
+        .map(mongodb::bson::from_bson)
+        .collect::<Result<Vec<OutboxMessage>, _>>()?;
+    Ok(Some((race_uuid, messages)))
+}
+
+mod uuid_as_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use uuid::Uuid;
+
+    pub fn serialize<S>(uuid: &Uuid, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&uuid.to_string())
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Uuid, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Uuid::parse_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_round_trip_through_bson() {
+        let message = OutboxMessage::new(OutboxPayload::Event(DomainEvent::ActionSubmitted {
+            race_uuid: Uuid::new_v4(),
+            player_uuid: Uuid::new_v4(),
+            lap: 3,
+            boost_value: 2,
+        }));
+        let stored = mongodb::bson::to_bson(&message).unwrap();
+        let restored: OutboxMessage = mongodb::bson::from_bson(stored).unwrap();
+
+        assert_eq!(restored.uuid, message.uuid);
+        let OutboxPayload::Event(DomainEvent::ActionSubmitted { lap, .. }) = restored.payload
+        else {
+            panic!("expected ActionSubmitted, got {:?}", restored.payload);
+        };
+        assert_eq!(lap, 3);
+    }
+
+    #[test]
+    fn messages_are_pushed_with_the_update() {
+        let mut update = doc! { "$set": { "current_lap": 2 } };
+        let message = OutboxMessage::new(OutboxPayload::Webhook {
+            event: WebhookEvent::RaceStarted,
+            body: "{}".to_string(),
+        });
+
+        push_outbox(&mut update, &[message]).unwrap();
+
+        let pushed = update.get_document("$push").unwrap();
+        assert_eq!(
+            pushed
+                .get_document("outbox")
+                .unwrap()
+                .get_array("$each")
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(update.contains_key("$set"));
+    }
+
+    #[test]
+    fn nothing_is_pushed_without_messages() {
+        let mut update = doc! { "$set": { "current_lap": 2 } };
+
+        push_outbox(&mut update, &[]).unwrap();
+
+        assert!(!update.contains_key("$push"));
+    }
+}
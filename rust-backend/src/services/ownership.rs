@@ -0,0 +1,184 @@
+use mongodb::{
+    bson::{doc, Bson, Document},
+    options::FindOneOptions,
+    Database,
+};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+use crate::domain::Car;
+
+/// Kinds of assets a player owns
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssetKind {
+    Car,
+    Pilot,
+    Engine,
+    Body,
+}
+
+impl AssetKind {
+    pub const ALL: [AssetKind; 4] = [
+        AssetKind::Car,
+        AssetKind::Pilot,
+        AssetKind::Engine,
+        AssetKind::Body,
+    ];
+
+    /// Array of the player document holding this kind of asset
+    fn field(self) -> &'static str {
+        match self {
+            AssetKind::Car => "cars",
+            AssetKind::Pilot => "pilots",
+            AssetKind::Engine => "engines",
+            AssetKind::Body => "bodies",
+        }
+    }
+
+    /// Route parameter naming an asset of this kind
+    #[must_use]
+    pub fn path_param(self) -> &'static str {
+        match self {
+            AssetKind::Car => "car_uuid",
+            AssetKind::Pilot => "pilot_uuid",
+            AssetKind::Engine => "engine_uuid",
+            AssetKind::Body => "body_uuid",
+        }
+    }
+}
+
+/// UUIDs of the cars and components a player owns
+///
+/// Resolved once per request by the ownership middleware, which leaves it in
+/// the request extensions for the handler.
+#[derive(Debug, Clone, Default)]
+pub struct PlayerAssets {
+    pub player_uuid: Uuid,
+    cars: HashSet<Uuid>,
+    pilots: HashSet<Uuid>,
+    engines: HashSet<Uuid>,
+    bodies: HashSet<Uuid>,
+}
+
+impl PlayerAssets {
+    fn set(&self, kind: AssetKind) -> &HashSet<Uuid> {
+        match kind {
+            AssetKind::Car => &self.cars,
+            AssetKind::Pilot => &self.pilots,
+            AssetKind::Engine => &self.engines,
+            AssetKind::Body => &self.bodies,
+        }
+    }
+
+    #[must_use]
+    pub fn owns(&self, kind: AssetKind, uuid: Uuid) -> bool {
+        self.set(kind).contains(&uuid)
+    }
+
+    /// The first car or component of `cars` the player does not own, if any
+    #[must_use]
+    pub fn first_unowned(&self, cars: &[Car]) -> Option<(AssetKind, Uuid)> {
+        cars.iter().find_map(|car| {
+            std::iter::once((AssetKind::Car, car.uuid))
+                .chain(car.pilot_uuids.iter().map(|uuid| (AssetKind::Pilot, *uuid)))
+                .chain(car.engine_uuid.map(|uuid| (AssetKind::Engine, uuid)))
+                .chain(car.body_uuid.map(|uuid| (AssetKind::Body, uuid)))
+                .find(|(kind, uuid)| !self.owns(*kind, *uuid))
+        })
+    }
+}
+
+/// Looks up which assets players own, shared by the ownership middleware and
+/// the handlers so a request reads them once
+#[derive(Clone)]
+pub struct OwnershipResolver {
+    database: Database,
+}
+
+impl OwnershipResolver {
+    #[must_use]
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+
+    /// Assets of `player_uuid`, or `None` when there is no such player
+    ///
+    /// Only the asset UUIDs are read from the player document.
+    pub async fn assets_of(
+        &self,
+        player_uuid: Uuid,
+    ) -> Result<Option<PlayerAssets>, mongodb::error::Error> {
+        let mut projection = Document::new();
+        for kind in AssetKind::ALL {
+            projection.insert(format!("{}.uuid", kind.field()), 1);
+        }
+        let options = FindOneOptions::builder().projection(projection).build();
+        let player = self
+            .database
+            .collection::<Document>("players")
+            .find_one(doc! { "uuid": player_uuid.to_string() }, options)
+            .await?;
+        Ok(player.map(|player| assets_from_document(player_uuid, &player)))
+    }
+}
+
+fn assets_from_document(player_uuid: Uuid, player: &Document) -> PlayerAssets {
+    let uuids = |kind: AssetKind| -> HashSet<Uuid> {
+        player
+            .get_array(kind.field())
+            .map(|assets| {
+                assets
+                    .iter()
+                    .filter_map(Bson::as_document)
+                    .filter_map(|asset| asset.get_str("uuid").ok())
+                    .filter_map(|uuid| Uuid::parse_str(uuid).ok())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    PlayerAssets {
+        player_uuid,
+        cars: uuids(AssetKind::Car),
+        pilots: uuids(AssetKind::Pilot),
+        engines: uuids(AssetKind::Engine),
+        bodies: uuids(AssetKind::Body),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::CarName;
+
+    #[test]
+    fn configurations_may_only_use_owned_components() {
+        let player_uuid = Uuid::new_v4();
+        let (car, pilot, engine, stolen_body) = (
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        );
+        let document = doc! {
+            "uuid": player_uuid.to_string(),
+            "cars": [{ "uuid": car.to_string() }],
+            "pilots": [{ "uuid": pilot.to_string() }],
+            "engines": [{ "uuid": engine.to_string() }],
+        };
+        let assets = assets_from_document(player_uuid, &document);
+        assert!(assets.owns(AssetKind::Car, car));
+        assert!(!assets.owns(AssetKind::Pilot, car));
+
+        let mut configured = Car::new(CarName::parse("Racer").unwrap(), None).unwrap();
+        configured.uuid = car;
+        configured.pilot_uuids = vec![pilot];
+        configured.engine_uuid = Some(engine);
+        assert_eq!(assets.first_unowned(&[configured.clone()]), None);
+
+        configured.body_uuid = Some(stolen_body);
+        assert_eq!(
+            assets.first_unowned(&[configured]),
+            Some((AssetKind::Body, stolen_body))
+        );
+    }
+}
@@ -0,0 +1,152 @@
+use mongodb::bson::DateTime as BsonDateTime;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::configuration::PackDefinition;
+use crate::domain::{ComponentRarity, CraftableComponent};
+
+/// One engine or body drawn from a pack
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct PackDrop {
+    pub component: CraftableComponent,
+    pub rarity: ComponentRarity,
+    pub straight_value: u8,
+    pub curve_value: u8,
+}
+
+/// Collection keeping every opened pack
+pub const PACK_OPENINGS: &str = "pack_openings";
+
+/// Opened pack, as stored in the `pack_openings` collection
+///
+/// `seed` and `pity_before` replay the drops through [`open_pack`] with the
+/// pack's drop table.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct PackOpening {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub id: Option<mongodb::bson::oid::ObjectId>,
+    pub uuid: String,
+    pub player_uuid: String,
+    pub pack_id: String,
+    pub seed: u64,
+    /// Credits paid
+    pub price: u32,
+    pub pity_before: u32,
+    pub pity_after: u32,
+    pub drops: Vec<PackDrop>,
+    /// Engines and bodies created from `drops`, in the same order
+    pub component_uuids: Vec<String>,
+    #[schema(value_type = String, format = "date-time")]
+    pub opened_at: BsonDateTime,
+}
+
+/// Drops of one opened pack and the pity counter it leaves
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackDraw {
+    pub drops: Vec<PackDrop>,
+    pub pity_counter: u32,
+}
+
+/// Draw the contents of `pack` from `seed`
+///
+/// The same pack, seed and pity counter always give the same draw, so a
+/// stored opening can be replayed. `pity_counter` counts the drops in a row
+/// below the pack's pity rarity.
+pub fn open_pack(pack: &PackDefinition, seed: u64, pity_counter: u32) -> Result<PackDraw, String> {
+    let weights = WeightedIndex::new(pack.drop_rates.iter().map(|rate| rate.weight))
+        .map_err(|e| format!("Pack {} has no usable drop rates: {e}", pack.id))?;
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut pity_counter = pity_counter;
+
+    let drops = (0..pack.components)
+        .map(|_| {
+            let mut rarity = pack.drop_rates[weights.sample(&mut rng)].rarity;
+            if let Some(pity) = &pack.pity {
+                if rarity >= pity.min_rarity {
+                    pity_counter = 0;
+                } else if pity_counter >= pity.after {
+                    rarity = pity.min_rarity;
+                    pity_counter = 0;
+                } else {
+                    pity_counter += 1;
+                }
+            }
+
+            let component = if rng.gen_bool(0.5) {
+                CraftableComponent::Engine
+            } else {
+                CraftableComponent::Body
+            };
+            // Stats land in the top half of what the rarity allows
+            let max = rarity.get_max_values();
+            PackDrop {
+                component,
+                rarity,
+                straight_value: rng.gen_range(max / 2..=max),
+                curve_value: rng.gen_range(max / 2..=max),
+            }
+        })
+        .collect();
+
+    Ok(PackDraw {
+        drops,
+        pity_counter,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configuration::{DropRate, PackSettings, PitySettings};
+
+    #[test]
+    fn draws_are_reproducible_from_the_seed() {
+        let pack = &PackSettings::default().catalog[0];
+
+        let draw = open_pack(pack, 42, 0).unwrap();
+
+        assert_eq!(draw.drops.len(), 3);
+        assert_eq!(open_pack(pack, 42, 0).unwrap(), draw);
+        for drop in &draw.drops {
+            assert!(drop.straight_value <= drop.rarity.get_max_values());
+            assert!(drop.curve_value <= drop.rarity.get_max_values());
+        }
+    }
+
+    #[test]
+    fn pity_guarantees_the_rarity_after_a_bad_run() {
+        let pack = PackDefinition {
+            id: "commons".to_string(),
+            name: "Commons".to_string(),
+            price: 0,
+            components: 5,
+            drop_rates: vec![DropRate {
+                rarity: ComponentRarity::Common,
+                weight: 1,
+            }],
+            pity: Some(PitySettings {
+                after: 3,
+                min_rarity: ComponentRarity::Epic,
+            }),
+        };
+
+        let draw = open_pack(&pack, 7, 1).unwrap();
+
+        let rarities: Vec<_> = draw.drops.iter().map(|drop| drop.rarity).collect();
+        assert_eq!(
+            rarities,
+            [
+                ComponentRarity::Common,
+                ComponentRarity::Common,
+                ComponentRarity::Epic,
+                ComponentRarity::Common,
+                ComponentRarity::Common,
+            ]
+        );
+        assert_eq!(draw.pity_counter, 2);
+    }
+}
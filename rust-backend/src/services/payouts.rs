@@ -0,0 +1,273 @@
+use mongodb::{
+    bson::{doc, DateTime as BsonDateTime},
+    options::{FindOneAndUpdateOptions, FindOptions},
+    Database,
+};
+use secrecy::{ExposeSecret, Secret};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
+use uuid::Uuid;
+
+use crate::configuration::PayoutSettings;
+use crate::database::is_duplicate_key;
+use crate::domain::{race_payouts, PayoutStatus, Player, Race, RacePayout, RaceStatus};
+
+pub const RACE_PAYOUTS: &str = "race_payouts";
+
+/// Answer of the signing service
+#[derive(Debug, Deserialize)]
+struct SignedTransfer {
+    signature: String,
+}
+
+/// Queues the prizes of finished races and pays them as Solana transfers
+#[derive(Clone)]
+pub struct PayoutSender {
+    client: reqwest::Client,
+    signing_service_url: String,
+    signing_service_token: Option<Secret<String>>,
+    max_attempts: u32,
+    retry_base: Duration,
+    timeout: Duration,
+}
+
+impl PayoutSender {
+    pub fn from_settings(settings: &PayoutSettings) -> Result<Self, reqwest::Error> {
+        let timeout = Duration::from_secs(settings.timeout_seconds);
+        Ok(Self {
+            client: reqwest::Client::builder().timeout(timeout).build()?,
+            signing_service_url: settings.signing_service_url.clone(),
+            signing_service_token: settings.signing_service_token.clone(),
+            max_attempts: settings.max_attempts.max(1),
+            retry_base: Duration::from_secs(settings.retry_base_seconds),
+            timeout,
+        })
+    }
+
+    /// Wait before attempt `attempts + 1`, doubling after every failure
+    #[must_use]
+    pub fn retry_delay(&self, attempts: u32) -> Duration {
+        self.retry_base
+            .saturating_mul(1 << attempts.saturating_sub(1).min(16))
+    }
+
+    /// Queue the payouts of up to `batch_size` finished prize races, then
+    /// attempt up to `batch_size` due transfers
+    /// Returns the number of races queued and transfers attempted.
+    pub async fn run(
+        &self,
+        database: &Database,
+        batch_size: u32,
+    ) -> Result<u64, mongodb::error::Error> {
+        let queued = queue_race_payouts(database, batch_size).await?;
+        let mut attempted = 0;
+        while attempted < u64::from(batch_size) {
+            let Some(payout) = self.claim_due(database).await? else {
+                break;
+            };
+            self.attempt(database, payout).await?;
+            attempted += 1;
+        }
+        Ok(queued + attempted)
+    }
+
+    /// Take the oldest due payout, pushing it back while it is attempted so
+    /// another server does not pay it too
+    async fn claim_due(
+        &self,
+        database: &Database,
+    ) -> Result<Option<RacePayout>, mongodb::error::Error> {
+        let now = SystemTime::now();
+        let lease = BsonDateTime::from_system_time(now + self.timeout + Duration::from_secs(30));
+        let options = FindOneAndUpdateOptions::builder()
+            .sort(doc! { "next_attempt_at": 1 })
+            .build();
+        database
+            .collection::<RacePayout>(RACE_PAYOUTS)
+            .find_one_and_update(
+                doc! {
+                    "status": mongodb::bson::to_bson(&PayoutStatus::Pending)?,
+                    "next_attempt_at": { "$lte": BsonDateTime::from_system_time(now) },
+                },
+                doc! { "$set": { "next_attempt_at": lease } },
+                options,
+            )
+            .await
+    }
+
+    async fn attempt(
+        &self,
+        database: &Database,
+        payout: RacePayout,
+    ) -> Result<(), mongodb::error::Error> {
+        let attempts = payout.attempts + 1;
+        let update = match self.transfer(&payout).await {
+            Ok(signature) => {
+                tracing::info!(
+                    "Paid payout {} of race {} in transaction {}",
+                    payout.uuid,
+                    payout.race_uuid,
+                    signature
+                );
+                doc! {
+                    "status": mongodb::bson::to_bson(&PayoutStatus::Paid)?,
+                    "attempts": attempts,
+                    "last_error": null,
+                    "transaction_signature": signature,
+                    "paid_at": BsonDateTime::now(),
+                }
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Payout {} failed (attempt {}): {}",
+                    payout.uuid,
+                    attempts,
+                    e
+                );
+                let status = if attempts < self.max_attempts {
+                    PayoutStatus::Pending
+                } else {
+                    PayoutStatus::Failed
+                };
+                let next_attempt_at =
+                    BsonDateTime::from_system_time(SystemTime::now() + self.retry_delay(attempts));
+                doc! {
+                    "status": mongodb::bson::to_bson(&status)?,
+                    "attempts": attempts,
+                    "last_error": e.to_string(),
+                    "next_attempt_at": next_attempt_at,
+                }
+            }
+        };
+        database
+            .collection::<RacePayout>(RACE_PAYOUTS)
+            .update_one(
+                doc! { "uuid": payout.uuid.to_string() },
+                doc! { "$set": update },
+                None,
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Have the signing service send the prize; returns the transaction signature
+    ///
+    /// A timed-out request may still have been sent, and is retried with the
+    /// same `payout_uuid`: the signing service must make at most one transfer
+    /// per payout and answer repeats with the first transfer's signature.
+    async fn transfer(&self, payout: &RacePayout) -> Result<String, reqwest::Error> {
+        let mut request = self
+            .client
+            .post(&self.signing_service_url)
+            .header("Idempotency-Key", payout.uuid.to_string())
+            .json(&serde_json::json!({
+                "payout_uuid": payout.uuid.to_string(),
+                "recipient": payout.recipient,
+                "mint": payout.mint,
+                "amount": payout.amount,
+            }));
+        if let Some(token) = &self.signing_service_token {
+            request = request.bearer_auth(token.expose_secret());
+        }
+        let signed: SignedTransfer = request.send().await?.error_for_status()?.json().await?;
+        Ok(signed.signature)
+    }
+}
+
+/// Queue the payouts of up to `batch_size` finished races with a prize pool
+///
+/// Payouts are unique per race and player, so a run cut short is picked up
+/// by the next one without paying anyone twice. Returns the number of races
+/// queued.
+pub async fn queue_race_payouts(
+    database: &Database,
+    batch_size: u32,
+) -> Result<u64, mongodb::error::Error> {
+    let races = database.collection::<Race>("races");
+    let options = FindOptions::builder()
+        .sort(doc! { "updated_at": 1 })
+        .limit(i64::from(batch_size))
+        .build();
+    let mut cursor = races
+        .find(
+            doc! {
+                "status": mongodb::bson::to_bson(&RaceStatus::Finished)?,
+                "prize_pool.amounts": { "$exists": true },
+                "payouts_queued": { "$ne": true },
+            },
+            options,
+        )
+        .await?;
+
+    let payouts = database.collection::<RacePayout>(RACE_PAYOUTS);
+    let mut queued = 0;
+    while cursor.advance().await? {
+        let race: Race = cursor.deserialize_current()?;
+        let Some(prize_pool) = &race.prize_pool else {
+            continue;
+        };
+        let wallets = player_wallets(database, &race).await?;
+        for payout in race_payouts(&race, prize_pool, &wallets) {
+            if let Err(e) = payouts.insert_one(&payout, None).await {
+                if !is_duplicate_key(&e) {
+                    return Err(e);
+                }
+            }
+        }
+        races
+            .update_one(
+                doc! { "uuid": race.uuid.to_string() },
+                doc! { "$set": { "payouts_queued": true } },
+                None,
+            )
+            .await?;
+        tracing::info!("Queued prize payouts of race {}", race.uuid);
+        queued += 1;
+    }
+    Ok(queued)
+}
+
+/// Wallets the participants connected, which only they (or an admin) can
+/// change
+async fn player_wallets(
+    database: &Database,
+    race: &Race,
+) -> Result<HashMap<Uuid, String>, mongodb::error::Error> {
+    let uuids: Vec<String> = race
+        .participants
+        .iter()
+        .map(|p| p.player_uuid.to_string())
+        .collect();
+    let mut cursor = database
+        .collection::<Player>("players")
+        .find(doc! { "uuid": { "$in": uuids } }, None)
+        .await?;
+    let mut wallets = HashMap::new();
+    while cursor.advance().await? {
+        let player: Player = cursor.deserialize_current()?;
+        if let Some(wallet) = player.wallet_address {
+            wallets.insert(player.uuid, wallet.as_ref().to_string());
+        }
+    }
+    Ok(wallets)
+}
+
+/// Payouts of a race, by finishing position
+pub async fn find_race_payouts(
+    database: &Database,
+    race_uuid: Uuid,
+) -> Result<Vec<RacePayout>, mongodb::error::Error> {
+    let options = FindOptions::builder()
+        .sort(doc! { "finish_position": 1 })
+        .build();
+    let mut cursor = database
+        .collection::<RacePayout>(RACE_PAYOUTS)
+        .find(doc! { "race_uuid": race_uuid.to_string() }, options)
+        .await?;
+    let mut payouts = Vec::new();
+    while cursor.advance().await? {
+        payouts.push(cursor.deserialize_current()?);
+    }
+    Ok(payouts)
+}
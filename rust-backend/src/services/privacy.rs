@@ -0,0 +1,478 @@
+use futures_util::TryStreamExt;
+use mongodb::{
+    bson::{doc, spec::BinarySubtype, Binary, Bson, DateTime as BsonDateTime, Document},
+    options::FindOptions,
+    Database,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::{Player, Race};
+use crate::routes::api_tokens::API_TOKENS;
+use crate::routes::players::find_races_with_archive;
+use crate::routes::spectators::SPECTATORS_COLLECTION;
+use crate::services::anticheat::CHEAT_FLAGS;
+use crate::services::audit::AUDIT_LOG;
+use crate::services::packs::PACK_OPENINGS;
+use crate::services::payouts::RACE_PAYOUTS;
+use crate::services::push::DEVICE_TOKENS;
+use crate::services::webhooks::WEBHOOKS;
+use crate::services::{record_audit, AuditEntry};
+
+/// What erasing a player does to one kind of record
+enum Erasure {
+    Delete,
+    /// Kept for the other players or for the books, as it names the player
+    /// only by UUID
+    Keep,
+    /// Kept without these fields, which may hold copies of the profile
+    Unset(&'static [&'static str]),
+    /// Kept for their owners, with the player's UUID taken out of the
+    /// record's `fields`
+    Pull,
+}
+
+/// Records about a player kept outside their profile
+struct PlayerRecords {
+    /// Name of the records in an export
+    name: &'static str,
+    collection: &'static str,
+    /// Fields holding the player's UUID
+    fields: &'static [&'static str],
+    /// Fields left out of exports
+    hidden: &'static [&'static str],
+    erasure: Erasure,
+}
+
+/// Every kind of record naming a player, besides their profile and races
+///
+/// Blocks and reports are only those the player made, as the others would
+/// tell who blocked or reported them. Predictions are likewise those the
+/// player made, not those picking them.
+const PLAYER_RECORDS: &[PlayerRecords] = &[
+    PlayerRecords {
+        name: "notifications",
+        collection: "notifications",
+        fields: &["player_uuid"],
+        hidden: &[],
+        erasure: Erasure::Delete,
+    },
+    PlayerRecords {
+        name: "notification_preferences",
+        collection: "notification_preferences",
+        fields: &["player_uuid"],
+        hidden: &[],
+        erasure: Erasure::Delete,
+    },
+    PlayerRecords {
+        name: "devices",
+        collection: DEVICE_TOKENS,
+        fields: &["player_uuid"],
+        hidden: &[],
+        erasure: Erasure::Delete,
+    },
+    PlayerRecords {
+        name: "friendships",
+        collection: "friendships",
+        fields: &["requester_uuid", "addressee_uuid"],
+        hidden: &[],
+        erasure: Erasure::Delete,
+    },
+    PlayerRecords {
+        name: "blocks",
+        collection: "player_blocks",
+        fields: &["blocker_uuid"],
+        hidden: &[],
+        erasure: Erasure::Delete,
+    },
+    PlayerRecords {
+        name: "reports",
+        collection: "reports",
+        fields: &["reporter_uuid"],
+        hidden: &[],
+        erasure: Erasure::Keep,
+    },
+    PlayerRecords {
+        name: "bans",
+        collection: "player_bans",
+        fields: &["player_uuid"],
+        hidden: &[],
+        erasure: Erasure::Delete,
+    },
+    PlayerRecords {
+        name: "cheat_flags",
+        collection: CHEAT_FLAGS,
+        fields: &["player_uuid"],
+        hidden: &[],
+        erasure: Erasure::Delete,
+    },
+    PlayerRecords {
+        name: "trades",
+        collection: "trades",
+        fields: &["offerer_uuid", "recipient_uuid"],
+        hidden: &[],
+        erasure: Erasure::Keep,
+    },
+    PlayerRecords {
+        name: "pack_openings",
+        collection: PACK_OPENINGS,
+        fields: &["player_uuid"],
+        hidden: &[],
+        erasure: Erasure::Delete,
+    },
+    PlayerRecords {
+        name: "ghosts",
+        collection: "ghosts",
+        fields: &["player_uuid"],
+        hidden: &[],
+        erasure: Erasure::Delete,
+    },
+    PlayerRecords {
+        name: "webhooks",
+        collection: WEBHOOKS,
+        fields: &["player_uuid"],
+        hidden: &["secret"],
+        erasure: Erasure::Delete,
+    },
+    PlayerRecords {
+        name: "fantasy_leagues",
+        collection: "fantasy_leagues",
+        fields: &["owner_uuid"],
+        hidden: &[],
+        erasure: Erasure::Keep,
+    },
+    PlayerRecords {
+        name: "payouts",
+        collection: RACE_PAYOUTS,
+        fields: &["player_uuid"],
+        hidden: &[],
+        erasure: Erasure::Keep,
+    },
+    PlayerRecords {
+        name: "tracks",
+        collection: "tracks",
+        fields: &["author_uuid"],
+        hidden: &[],
+        erasure: Erasure::Delete,
+    },
+    PlayerRecords {
+        name: "track_favorites_and_ratings",
+        collection: "tracks",
+        fields: &["favorited_by", "ratings.player_uuid"],
+        hidden: &["favorited_by", "ratings"],
+        erasure: Erasure::Pull,
+    },
+    PlayerRecords {
+        name: "spectated_races",
+        collection: SPECTATORS_COLLECTION,
+        fields: &["viewer_id"],
+        hidden: &[],
+        erasure: Erasure::Delete,
+    },
+    PlayerRecords {
+        name: "spectator_predictions",
+        collection: "spectator_predictions",
+        fields: &["viewer_id"],
+        hidden: &[],
+        erasure: Erasure::Delete,
+    },
+    PlayerRecords {
+        name: "api_tokens",
+        collection: API_TOKENS,
+        fields: &["owner_uuid"],
+        hidden: &["token_hash"],
+        erasure: Erasure::Delete,
+    },
+    PlayerRecords {
+        name: "audit_log",
+        collection: AUDIT_LOG,
+        fields: &["actor", "player_uuid"],
+        hidden: &[],
+        erasure: Erasure::Unset(&["previous", "new"]),
+    },
+];
+
+/// Everything stored about a player
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PlayerDataExport {
+    pub player_uuid: String,
+    pub exported_at: String,
+    /// Profile and inventory, without the password hash
+    #[schema(value_type = Object)]
+    pub profile: serde_json::Value,
+    /// Races the player took part in, with their own participation
+    #[schema(value_type = Vec<Object>)]
+    pub races: Vec<serde_json::Value>,
+    /// Other records naming the player, by kind
+    #[schema(value_type = Object)]
+    pub records: BTreeMap<String, Vec<serde_json::Value>>,
+}
+
+/// `player_uuid` as stored by either a `uuid_as_string` field or a plain
+/// `Uuid` field, which is serialized as binary
+fn stored_uuids(player_uuid: Uuid) -> Bson {
+    Bson::Array(vec![
+        Bson::String(player_uuid.to_string()),
+        Bson::Binary(Binary {
+            subtype: BinarySubtype::Generic,
+            bytes: player_uuid.as_bytes().to_vec(),
+        }),
+    ])
+}
+
+/// Filter matching the documents naming `player_uuid` in any of `fields`
+fn player_filter(fields: &[&str], player_uuid: Uuid) -> Document {
+    let clauses: Vec<Document> = fields
+        .iter()
+        .map(|field| doc! { *field: { "$in": stored_uuids(player_uuid) } })
+        .collect();
+    doc! { "$or": clauses }
+}
+
+/// Update taking `player_uuid` out of the array `fields`; a field of the
+/// array's documents, such as `ratings.player_uuid`, removes those documents
+fn pull_player(fields: &[&str], player_uuid: Uuid) -> Document {
+    let mut pull = Document::new();
+    for field in fields {
+        let condition = doc! { "$in": stored_uuids(player_uuid) };
+        match field.split_once('.') {
+            Some((array, inner)) => pull.insert(array, doc! { inner: condition }),
+            None => pull.insert(*field, condition),
+        };
+    }
+    doc! { "$pull": pull }
+}
+
+/// `document` as plain JSON, without its database id and `hidden` fields
+fn exported(mut document: Document, hidden: &[&str]) -> serde_json::Value {
+    document.remove("_id");
+    for field in hidden {
+        document.remove(*field);
+    }
+    Bson::Document(document).into_relaxed_extjson()
+}
+
+/// Everything stored about `player_uuid`, if the player exists
+pub async fn export_player_data(
+    database: &Database,
+    player_uuid: Uuid,
+) -> Result<Option<PlayerDataExport>, mongodb::error::Error> {
+    let Some(profile) = database
+        .collection::<Document>("players")
+        .find_one(doc! { "uuid": player_uuid.to_string() }, None)
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    let races = find_races_with_archive(
+        database,
+        doc! { "participants.player_uuid": player_uuid.to_string() },
+        doc! { "created_at": -1 },
+        None,
+    )
+    .await?
+    .iter()
+    .map(|race| race_participation(race, player_uuid))
+    .collect();
+
+    let mut records = BTreeMap::new();
+    for kind in PLAYER_RECORDS {
+        let documents: Vec<Document> = database
+            .collection::<Document>(kind.collection)
+            .find(player_filter(kind.fields, player_uuid), None)
+            .await?
+            .try_collect()
+            .await?;
+        records.insert(
+            kind.name.to_string(),
+            documents
+                .into_iter()
+                .map(|document| exported(document, kind.hidden))
+                .collect(),
+        );
+    }
+
+    Ok(Some(PlayerDataExport {
+        player_uuid: player_uuid.to_string(),
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        profile: exported(profile, &["password_hash"]),
+        races,
+        records,
+    }))
+}
+
+/// A race as shown in an export: its outline and the player's own entry
+fn race_participation(race: &Race, player_uuid: Uuid) -> serde_json::Value {
+    serde_json::json!({
+        "race_uuid": race.uuid.to_string(),
+        "name": race.name,
+        "status": race.status,
+        "mode": race.mode,
+        "total_laps": race.total_laps,
+        "created_at": race.created_at.try_to_rfc3339_string().ok(),
+        "participation": race
+            .participants
+            .iter()
+            .find(|p| p.player_uuid == player_uuid),
+    })
+}
+
+/// Erase the players whose erasure was requested more than `grace` ago, at
+/// most `batch_size`
+///
+/// Returns the number of players erased.
+pub async fn erase_due_players(
+    database: &Database,
+    grace: Duration,
+    batch_size: u32,
+) -> Result<u64, mongodb::error::Error> {
+    let due = BsonDateTime::from_system_time(
+        SystemTime::now()
+            .checked_sub(grace)
+            .unwrap_or(SystemTime::UNIX_EPOCH),
+    );
+    let options = FindOptions::builder()
+        .sort(doc! { "erasure_requested_at": 1 })
+        .limit(i64::from(batch_size))
+        .build();
+    let players: Vec<Player> = database
+        .collection::<Player>("players")
+        .find(doc! { "erasure_requested_at": { "$lte": due } }, options)
+        .await?
+        .try_collect()
+        .await?;
+
+    for player in &players {
+        erase_player(database, player.uuid).await?;
+    }
+    Ok(u64::try_from(players.len()).unwrap_or(u64::MAX))
+}
+
+/// Purge a player's profile and personal records
+///
+/// Race results keep the player's UUID, which nothing ties to a person once
+/// the profile is gone, so classifications, event logs and anchored hashes
+/// stay intact. The profile goes last, so an interrupted erasure is picked
+/// up by the next run.
+pub async fn erase_player(
+    database: &Database,
+    player_uuid: Uuid,
+) -> Result<(), mongodb::error::Error> {
+    for kind in PLAYER_RECORDS {
+        let collection = database.collection::<Document>(kind.collection);
+        let filter = player_filter(kind.fields, player_uuid);
+        match kind.erasure {
+            Erasure::Delete => {
+                collection.delete_many(filter, None).await?;
+            }
+            Erasure::Keep => {}
+            Erasure::Unset(fields) => {
+                let unset: Document = fields.iter().map(|f| (f.to_string(), "".into())).collect();
+                collection
+                    .update_many(filter, doc! { "$unset": unset }, None)
+                    .await?;
+            }
+            Erasure::Pull => {
+                collection
+                    .update_many(filter, pull_player(kind.fields, player_uuid), None)
+                    .await?;
+            }
+        }
+    }
+    database
+        .collection::<Player>("players")
+        .delete_one(doc! { "uuid": player_uuid.to_string() }, None)
+        .await?;
+
+    record_audit(
+        database,
+        AuditEntry::new("player.erase").player(player_uuid),
+    )
+    .await;
+    tracing::info!("Erased player {}", player_uuid);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exports_drop_database_ids_and_hidden_fields() {
+        let player_uuid = Uuid::new_v4();
+        let webhook = doc! {
+            "_id": mongodb::bson::oid::ObjectId::new(),
+            "player_uuid": player_uuid.to_string(),
+            "url": "https://example.com/hook",
+            "secret": "whsec",
+        };
+
+        let value = exported(webhook, &["secret"]);
+
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "player_uuid": player_uuid.to_string(),
+                "url": "https://example.com/hook",
+            })
+        );
+        assert_eq!(
+            player_filter(&["requester_uuid", "addressee_uuid"], player_uuid),
+            doc! { "$or": [
+                { "requester_uuid": { "$in": stored_uuids(player_uuid) } },
+                { "addressee_uuid": { "$in": stored_uuids(player_uuid) } },
+            ] }
+        );
+    }
+
+    #[test]
+    fn erasure_pulls_the_player_out_of_shared_arrays() {
+        let player_uuid = Uuid::new_v4();
+
+        assert_eq!(
+            pull_player(&["favorited_by", "ratings.player_uuid"], player_uuid),
+            doc! { "$pull": {
+                "favorited_by": { "$in": stored_uuids(player_uuid) },
+                "ratings": { "player_uuid": { "$in": stored_uuids(player_uuid) } },
+            } }
+        );
+    }
+
+    /// Collections whose documents name players only as race results or not
+    /// at all, so they are neither exported nor erased
+    const NO_PLAYER_RECORDS: &[&str] = &[
+        "players",
+        "races",
+        "races_archive",
+        "race_events",
+        "race_event_archives",
+        "race_stats",
+        "race_snapshots",
+        "webhook_deliveries",
+    ];
+
+    #[test]
+    fn every_indexed_collection_is_covered_by_exports() {
+        for spec in crate::database::index_specs() {
+            let covered = PLAYER_RECORDS
+                .iter()
+                .any(|kind| kind.collection == spec.collection);
+            assert!(
+                covered || NO_PLAYER_RECORDS.contains(&spec.collection),
+                "{} holds no player records listed for exports and erasure",
+                spec.collection
+            );
+        }
+        for kind in PLAYER_RECORDS {
+            assert!(
+                !kind.fields.is_empty(),
+                "{} names no player field",
+                kind.name
+            );
+        }
+    }
+}
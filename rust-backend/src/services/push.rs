@@ -0,0 +1,472 @@
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use mongodb::{
+    bson::{doc, DateTime as BsonDateTime},
+    options::{FindOneAndUpdateOptions, FindOptions},
+    Database,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::configuration::{ApnsSettings, PushSettings};
+use crate::domain::{
+    DevicePlatform, DeviceToken, Notification, NotificationKind, Race, RaceStatus,
+};
+
+pub const DEVICE_TOKENS: &str = "device_tokens";
+const NOTIFICATIONS: &str = "notifications";
+/// Until when a notifier owns a notification being pushed
+const CLAIM_FIELD: &str = "push_claimed_until";
+/// Notifications older than this are not pushed any more, e.g. after push was
+/// disabled for a while
+const MAX_PUSH_AGE: Duration = Duration::from_hours(1);
+const FCM_SCOPE: &str = "https://www.googleapis.com/auth/firebase.messaging";
+
+/// Why a push was not delivered
+#[derive(Debug)]
+enum PushError {
+    /// The platform no longer knows the device token
+    Unregistered,
+    Failed(String),
+}
+
+impl From<reqwest::Error> for PushError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Failed(error.to_string())
+    }
+}
+
+/// Pushes pending notifications to the players' devices through FCM and APNs
+///
+/// A platform without credentials is skipped, so devices on it only see the
+/// notifications in their inbox.
+pub struct PushSender {
+    client: reqwest::Client,
+    fcm: Option<Fcm>,
+    apns: Option<Apns>,
+}
+
+impl PushSender {
+    pub fn from_settings(settings: &PushSettings) -> Result<Self, anyhow::Error> {
+        Ok(Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()?,
+            fcm: settings
+                .fcm_service_account_file
+                .as_deref()
+                .map(Fcm::from_file)
+                .transpose()?,
+            apns: settings
+                .apns
+                .as_ref()
+                .map(Apns::from_settings)
+                .transpose()?,
+        })
+    }
+
+    /// Push up to `batch_size` pending notifications
+    /// Returns the number of notifications handled.
+    pub async fn send_pending(
+        &self,
+        database: &Database,
+        batch_size: u32,
+    ) -> Result<u64, mongodb::error::Error> {
+        let notifications = database.collection::<Notification>(NOTIFICATIONS);
+        let mut handled = 0;
+        while handled < u64::from(batch_size) {
+            let Some(notification) = claim_pending(database).await? else {
+                break;
+            };
+            let age = SystemTime::now()
+                .duration_since(notification.created_at.to_system_time())
+                .unwrap_or_default();
+            if age < MAX_PUSH_AGE {
+                self.push(database, &notification).await?;
+            }
+            notifications
+                .update_one(
+                    doc! { "uuid": notification.uuid.to_string() },
+                    doc! { "$set": { "push_pending": false }, "$unset": { CLAIM_FIELD: "" } },
+                    None,
+                )
+                .await?;
+            handled += 1;
+        }
+        Ok(handled)
+    }
+
+    /// Send to every device of the player, forgetting the ones the platform
+    /// reports as unregistered
+    async fn push(
+        &self,
+        database: &Database,
+        notification: &Notification,
+    ) -> Result<(), mongodb::error::Error> {
+        let devices = database.collection::<DeviceToken>(DEVICE_TOKENS);
+        let mut cursor = devices
+            .find(
+                doc! { "player_uuid": notification.player_uuid.to_string() },
+                None,
+            )
+            .await?;
+        let mut unregistered = Vec::new();
+        while cursor.advance().await? {
+            let device: DeviceToken = cursor.deserialize_current()?;
+            let sent = match device.platform {
+                DevicePlatform::Fcm => match &self.fcm {
+                    Some(fcm) => fcm.send(&self.client, &device.token, notification).await,
+                    None => Ok(()),
+                },
+                DevicePlatform::Apns => match &self.apns {
+                    Some(apns) => apns.send(&self.client, &device.token, notification).await,
+                    None => Ok(()),
+                },
+            };
+            match sent {
+                Ok(()) => {}
+                Err(PushError::Unregistered) => unregistered.push(device.token),
+                Err(PushError::Failed(e)) => tracing::warn!(
+                    "Push of notification {} to a {:?} device failed: {}",
+                    notification.uuid,
+                    device.platform,
+                    e
+                ),
+            }
+        }
+        if !unregistered.is_empty() {
+            devices
+                .delete_many(doc! { "token": { "$in": unregistered } }, None)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+/// Take the oldest notification waiting to be pushed
+async fn claim_pending(database: &Database) -> Result<Option<Notification>, mongodb::error::Error> {
+    let now = SystemTime::now();
+    let options = FindOneAndUpdateOptions::builder()
+        .sort(doc! { "created_at": 1 })
+        .build();
+    database
+        .collection::<Notification>(NOTIFICATIONS)
+        .find_one_and_update(
+            doc! {
+                "push_pending": true,
+                CLAIM_FIELD: { "$not": { "$gt": BsonDateTime::from_system_time(now) } },
+            },
+            doc! { "$set": {
+                CLAIM_FIELD: BsonDateTime::from_system_time(now + Duration::from_mins(1)),
+            } },
+            options,
+        )
+        .await
+}
+
+/// Remind players of the laps that have waited on them for `waited`
+///
+/// Looks at `YourTurn` notifications older than that and, when the player
+/// still has not acted on that lap, leaves a `TurnReminder`. Each turn gets at
+/// most one reminder. Returns the number of reminders left.
+pub async fn remind_waiting_players(
+    database: &Database,
+    waited: Duration,
+    batch_size: u32,
+) -> Result<u64, mongodb::error::Error> {
+    let notifications = database.collection::<Notification>(NOTIFICATIONS);
+    let opened_before = BsonDateTime::from_system_time(SystemTime::now() - waited);
+    let your_turn = mongodb::bson::to_bson(&NotificationKind::YourTurn)?;
+    let options = FindOptions::builder()
+        .sort(doc! { "created_at": 1 })
+        .limit(i64::from(batch_size))
+        .build();
+    let mut cursor = notifications
+        .find(
+            doc! {
+                "kind": your_turn.clone(),
+                "reminder_sent": false,
+                "created_at": { "$lt": opened_before },
+            },
+            options,
+        )
+        .await?;
+    let mut turns: Vec<Notification> = Vec::new();
+    while cursor.advance().await? {
+        turns.push(cursor.deserialize_current()?);
+    }
+
+    let mut reminded = 0;
+    for turn in turns {
+        // Whoever flips the flag owns the reminder
+        let claimed = notifications
+            .update_one(
+                doc! { "uuid": turn.uuid.to_string(), "reminder_sent": false },
+                doc! { "$set": { "reminder_sent": true } },
+                None,
+            )
+            .await?;
+        if claimed.modified_count == 0 {
+            continue;
+        }
+        let race = database
+            .collection::<Race>("races")
+            .find_one(doc! { "uuid": turn.race_uuid.to_string() }, None)
+            .await?;
+        if let Some(race) = race.filter(|race| still_waiting_on(race, &turn)) {
+            let waited_minutes = waited.as_secs() / 60;
+            notifications
+                .insert_one(
+                    Notification::turn_reminder(turn.player_uuid, &race, waited_minutes),
+                    None,
+                )
+                .await?;
+            reminded += 1;
+        }
+    }
+    Ok(reminded)
+}
+
+/// Whether the race is still on the notified lap without the player's action
+fn still_waiting_on(race: &Race, turn: &Notification) -> bool {
+    race.status == RaceStatus::InProgress
+        && Some(race.current_lap) == turn.lap
+        && race
+            .participants
+            .iter()
+            .any(|p| p.player_uuid == turn.player_uuid && !p.is_finished)
+        && !race
+            .pending_actions
+            .iter()
+            .any(|action| action.player_uuid == turn.player_uuid)
+        && !race.action_submissions.contains_key(&turn.player_uuid)
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// Bearer token reused until shortly before it expires
+#[derive(Default)]
+struct CachedToken(Mutex<Option<(String, Instant)>>);
+
+impl CachedToken {
+    fn get(&self) -> Option<String> {
+        let cached = self.0.lock().unwrap();
+        cached
+            .as_ref()
+            .filter(|(_, expires)| *expires > Instant::now() + Duration::from_mins(1))
+            .map(|(token, _)| token.clone())
+    }
+
+    fn set(&self, token: String, valid_for: Duration) {
+        *self.0.lock().unwrap() = Some((token, Instant::now() + valid_for));
+    }
+}
+
+/// Fields of a Google service account key file used here
+#[derive(Deserialize)]
+struct ServiceAccount {
+    project_id: String,
+    client_email: String,
+    private_key: String,
+    token_uri: String,
+}
+
+#[derive(Serialize)]
+struct ServiceAccountClaims<'a> {
+    iss: &'a str,
+    scope: &'a str,
+    aud: &'a str,
+    iat: u64,
+    exp: u64,
+}
+
+#[derive(Deserialize)]
+struct AccessToken {
+    access_token: String,
+    expires_in: u64,
+}
+
+/// Firebase Cloud Messaging HTTP v1 client, authenticated as a service account
+struct Fcm {
+    account: ServiceAccount,
+    key: EncodingKey,
+    access_token: CachedToken,
+}
+
+impl Fcm {
+    fn from_file(path: &str) -> Result<Self, anyhow::Error> {
+        let account: ServiceAccount = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        let key = EncodingKey::from_rsa_pem(account.private_key.as_bytes())?;
+        Ok(Self {
+            account,
+            key,
+            access_token: CachedToken::default(),
+        })
+    }
+
+    async fn access_token(&self, client: &reqwest::Client) -> Result<String, PushError> {
+        if let Some(token) = self.access_token.get() {
+            return Ok(token);
+        }
+        let now = unix_time();
+        let claims = ServiceAccountClaims {
+            iss: &self.account.client_email,
+            scope: FCM_SCOPE,
+            aud: &self.account.token_uri,
+            iat: now,
+            exp: now + 3600,
+        };
+        let assertion = encode(&Header::new(Algorithm::RS256), &claims, &self.key)
+            .map_err(|e| PushError::Failed(e.to_string()))?;
+        let granted: AccessToken = client
+            .post(&self.account.token_uri)
+            .form(&[
+                ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+                ("assertion", assertion.as_str()),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        self.access_token.set(
+            granted.access_token.clone(),
+            Duration::from_secs(granted.expires_in),
+        );
+        Ok(granted.access_token)
+    }
+
+    async fn send(
+        &self,
+        client: &reqwest::Client,
+        device_token: &str,
+        notification: &Notification,
+    ) -> Result<(), PushError> {
+        let url = format!(
+            "https://fcm.googleapis.com/v1/projects/{}/messages:send",
+            self.account.project_id
+        );
+        let body = json!({ "message": {
+            "token": device_token,
+            "notification": { "title": notification.kind.title(), "body": notification.message },
+            "data": {
+                "kind": notification.kind,
+                "race_uuid": notification.race_uuid.to_string(),
+            },
+        } });
+        let response = client
+            .post(url)
+            .bearer_auth(self.access_token(client).await?)
+            .json(&body)
+            .send()
+            .await?;
+        match response.status().as_u16() {
+            200..=299 => Ok(()),
+            // UNREGISTERED: the app was uninstalled or the token rotated
+            404 => Err(PushError::Unregistered),
+            status => Err(PushError::Failed(format!("FCM answered {status}"))),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ProviderClaims<'a> {
+    iss: &'a str,
+    iat: u64,
+}
+
+/// Apple Push Notification service client using a token-based (.p8) key
+struct Apns {
+    key: EncodingKey,
+    key_id: String,
+    team_id: String,
+    topic: String,
+    host: &'static str,
+    provider_token: CachedToken,
+}
+
+impl Apns {
+    fn from_settings(settings: &ApnsSettings) -> Result<Self, anyhow::Error> {
+        let key = EncodingKey::from_ec_pem(&std::fs::read(&settings.key_file)?)?;
+        Ok(Self {
+            key,
+            key_id: settings.key_id.clone(),
+            team_id: settings.team_id.clone(),
+            topic: settings.topic.clone(),
+            host: if settings.sandbox {
+                "api.sandbox.push.apple.com"
+            } else {
+                "api.push.apple.com"
+            },
+            provider_token: CachedToken::default(),
+        })
+    }
+
+    /// Provider token; Apple accepts each one for an hour
+    fn provider_token(&self) -> Result<String, PushError> {
+        if let Some(token) = self.provider_token.get() {
+            return Ok(token);
+        }
+        let mut header = Header::new(Algorithm::ES256);
+        header.kid = Some(self.key_id.clone());
+        let claims = ProviderClaims {
+            iss: &self.team_id,
+            iat: unix_time(),
+        };
+        let token =
+            encode(&header, &claims, &self.key).map_err(|e| PushError::Failed(e.to_string()))?;
+        self.provider_token
+            .set(token.clone(), Duration::from_mins(50));
+        Ok(token)
+    }
+
+    async fn send(
+        &self,
+        client: &reqwest::Client,
+        device_token: &str,
+        notification: &Notification,
+    ) -> Result<(), PushError> {
+        let body = json!({
+            "aps": {
+                "alert": { "title": notification.kind.title(), "body": notification.message },
+                "sound": "default",
+            },
+            "kind": notification.kind,
+            "race_uuid": notification.race_uuid.to_string(),
+        });
+        let response = client
+            .post(format!("https://{}/3/device/{device_token}", self.host))
+            .bearer_auth(self.provider_token()?)
+            .header("apns-topic", &self.topic)
+            .header("apns-push-type", "alert")
+            .json(&body)
+            .send()
+            .await?;
+        match response.status().as_u16() {
+            200..=299 => Ok(()),
+            // BadDeviceToken, or Unregistered since the app was removed
+            400 | 410 => Err(PushError::Unregistered),
+            status => Err(PushError::Failed(format!("APNs answered {status}"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cached_token_is_dropped_before_it_expires() {
+        let cached = CachedToken::default();
+        cached.set("fresh".to_string(), Duration::from_mins(30));
+        assert_eq!(cached.get().as_deref(), Some("fresh"));
+
+        cached.set("stale".to_string(), Duration::from_secs(30));
+        assert_eq!(cached.get(), None);
+    }
+}
@@ -0,0 +1,243 @@
+use axum::{
+    extract::{Request, State},
+    http::Method,
+    middleware::Next,
+    response::Response,
+};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use crate::configuration::{CacheBackend, CacheSettings};
+use crate::domain::Race;
+
+/// Cache of race documents keyed by race UUID
+///
+/// Used by the frequently polled race endpoints. Entries are dropped whenever a
+/// request writes to the race (see [`invalidate_race_on_write`]) and expire
+/// after the configured TTL in any case. A disabled cache never stores anything.
+#[derive(Clone, Default)]
+pub struct RaceCache {
+    store: Option<Store>,
+    ttl: Duration,
+}
+
+#[derive(Clone)]
+enum Store {
+    Memory(Arc<RwLock<HashMap<Uuid, (Instant, Race)>>>),
+    #[cfg(feature = "redis")]
+    Redis(Box<redis::aio::ConnectionManager>),
+}
+
+// Only the Redis store awaits anything
+#[cfg_attr(not(feature = "redis"), allow(clippy::unused_async))]
+impl RaceCache {
+    /// Cache that always misses
+    #[must_use]
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn in_memory(ttl: Duration) -> Self {
+        Self {
+            store: Some(Store::Memory(Arc::default())),
+            ttl,
+        }
+    }
+
+    #[cfg(feature = "redis")]
+    pub async fn redis(url: &str, ttl: Duration) -> Result<Self, redis::RedisError> {
+        let client = redis::Client::open(url)?;
+        let connection = redis::aio::ConnectionManager::new(client).await?;
+        Ok(Self {
+            store: Some(Store::Redis(Box::new(connection))),
+            ttl,
+        })
+    }
+
+    pub async fn from_settings(settings: &CacheSettings) -> Result<Self, anyhow::Error> {
+        let ttl = Duration::from_secs(settings.ttl_seconds);
+        match settings.backend {
+            CacheBackend::None => Ok(Self::disabled()),
+            CacheBackend::Memory => Ok(Self::in_memory(ttl)),
+            #[cfg(feature = "redis")]
+            CacheBackend::Redis => Ok(Self::redis(&settings.redis_url, ttl).await?),
+            #[cfg(not(feature = "redis"))]
+            CacheBackend::Redis => {
+                anyhow::bail!("The redis cache requires building with `--features redis`")
+            }
+        }
+    }
+
+    /// Cached race, if present and not expired
+    /// Redis errors are logged and treated as a miss.
+    pub async fn get(&self, race_uuid: Uuid) -> Option<Race> {
+        match self.store.as_ref()? {
+            Store::Memory(entries) => {
+                let entries = entries.read().unwrap();
+                entries
+                    .get(&race_uuid)
+                    .filter(|(stored_at, _)| stored_at.elapsed() < self.ttl)
+                    .map(|(_, race)| race.clone())
+            }
+            #[cfg(feature = "redis")]
+            Store::Redis(connection) => {
+                use redis::AsyncCommands;
+
+                let mut connection = (**connection).clone();
+                let cached: Option<String> = connection
+                    .get(redis_key(race_uuid))
+                    .await
+                    .map_err(|e| tracing::warn!("Race cache read failed: {}", e))
+                    .ok()?;
+                cached.and_then(|json| serde_json::from_str(&json).ok())
+            }
+        }
+    }
+
+    pub async fn put(&self, race: &Race) {
+        let Some(store) = &self.store else {
+            return;
+        };
+        match store {
+            Store::Memory(entries) => {
+                let mut entries = entries.write().unwrap();
+                entries.retain(|_, (stored_at, _)| stored_at.elapsed() < self.ttl);
+                entries.insert(race.uuid, (Instant::now(), race.clone()));
+            }
+            #[cfg(feature = "redis")]
+            Store::Redis(connection) => {
+                use redis::AsyncCommands;
+
+                let Ok(json) = serde_json::to_string(race) else {
+                    return;
+                };
+                let mut connection = (**connection).clone();
+                let result: redis::RedisResult<()> = connection
+                    .set_ex(redis_key(race.uuid), json, self.ttl.as_secs().max(1))
+                    .await;
+                if let Err(e) = result {
+                    tracing::warn!("Race cache write failed: {}", e);
+                }
+            }
+        }
+    }
+
+    pub async fn invalidate(&self, race_uuid: Uuid) {
+        let Some(store) = &self.store else {
+            return;
+        };
+        match store {
+            Store::Memory(entries) => {
+                entries.write().unwrap().remove(&race_uuid);
+            }
+            #[cfg(feature = "redis")]
+            Store::Redis(connection) => {
+                use redis::AsyncCommands;
+
+                let mut connection = (**connection).clone();
+                let result: redis::RedisResult<()> = connection.del(redis_key(race_uuid)).await;
+                if let Err(e) = result {
+                    tracing::warn!("Race cache invalidation failed: {}", e);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "redis")]
+fn redis_key(race_uuid: Uuid) -> String {
+    format!("race:{race_uuid}")
+}
+
+/// UUID following `collection` in a path, e.g. the race in `/races/:race_uuid/turn`
+pub(crate) fn uuid_from_path(path: &str, collection: &str) -> Option<Uuid> {
+    let mut segments = path.split('/');
+    segments.find(|segment| *segment == collection)?;
+    segments
+        .next()
+        .and_then(|segment| Uuid::parse_str(segment).ok())
+}
+
+/// Middleware dropping the cached race after any request that may have changed it
+pub async fn invalidate_race_on_write(
+    State(cache): State<RaceCache>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let race_uuid = match *request.method() {
+        Method::GET | Method::HEAD | Method::OPTIONS => None,
+        _ => uuid_from_path(request.uri().path(), "races"),
+    };
+
+    let response = next.run(request).await;
+
+    if let Some(race_uuid) = race_uuid {
+        cache.invalidate(race_uuid).await;
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Race, Track};
+
+    fn race() -> Race {
+        let track = Track {
+            uuid: Uuid::new_v4(),
+            name: "Track".to_string(),
+            sectors: Vec::new(),
+        };
+        Race::new("Cached".to_string(), track, 3)
+    }
+
+    #[tokio::test]
+    async fn memory_cache_returns_stored_race_until_invalidated() {
+        let cache = RaceCache::in_memory(Duration::from_mins(1));
+        let race = race();
+
+        assert!(cache.get(race.uuid).await.is_none());
+        cache.put(&race).await;
+        assert_eq!(cache.get(race.uuid).await.unwrap().name, "Cached");
+
+        cache.invalidate(race.uuid).await;
+        assert!(cache.get(race.uuid).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn expired_entries_are_not_returned() {
+        let cache = RaceCache::in_memory(Duration::ZERO);
+        let race = race();
+
+        cache.put(&race).await;
+        assert!(cache.get(race.uuid).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn disabled_cache_never_stores() {
+        let cache = RaceCache::disabled();
+        let race = race();
+
+        cache.put(&race).await;
+        assert!(cache.get(race.uuid).await.is_none());
+    }
+
+    #[test]
+    fn race_uuid_is_read_from_nested_paths() {
+        let race_uuid = Uuid::new_v4();
+
+        assert_eq!(
+            uuid_from_path(&format!("/api/v1/races/{race_uuid}/turn"), "races"),
+            Some(race_uuid)
+        );
+        assert_eq!(
+            uuid_from_path(&format!("/races/{race_uuid}"), "races"),
+            Some(race_uuid)
+        );
+        assert_eq!(uuid_from_path("/races/practice", "races"), None);
+        assert_eq!(uuid_from_path("/players", "races"), None);
+    }
+}
@@ -0,0 +1,117 @@
+use chrono::{DateTime, Utc};
+use futures_util::Stream;
+use mongodb::{
+    bson::{doc, oid::ObjectId, DateTime as BsonDateTime},
+    options::FindOptions,
+    Database,
+};
+use std::collections::VecDeque;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::domain::{RaceChange, RaceEvent, RaceStatus};
+
+/// How often the race event log is read for new changes
+const RACE_EVENTS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Most changes read from the log at once
+const RACE_EVENTS_BATCH: i64 = 100;
+
+/// Reader following the changes of one race in the race event log
+///
+/// Used by the streaming APIs; the log is polled, so changes arrive up to a
+/// second after they are recorded.
+pub struct RaceEventLog {
+    database: Database,
+    race_uuid: Uuid,
+    /// Changes recorded together share a timestamp, so the id breaks ties
+    after: (BsonDateTime, Option<ObjectId>),
+    pending: VecDeque<RaceEvent>,
+    race_over: bool,
+}
+
+impl RaceEventLog {
+    /// Reader of the changes recorded after `since`
+    #[must_use]
+    pub fn new(database: Database, race_uuid: Uuid, since: DateTime<Utc>) -> Self {
+        Self {
+            database,
+            race_uuid,
+            after: (BsonDateTime::from_millis(since.timestamp_millis()), None),
+            pending: VecDeque::new(),
+            race_over: false,
+        }
+    }
+
+    /// Every change in order, ending once the race has finished or been cancelled
+    pub fn into_stream(self) -> impl Stream<Item = RaceEvent> + Send {
+        futures_util::stream::unfold(self, |mut log| async move {
+            let event = log.next().await?;
+            Some((event, log))
+        })
+    }
+
+    /// Next change, waiting for it to be recorded; `None` after the race ends
+    pub async fn next(&mut self) -> Option<RaceEvent> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                if let RaceChange::PhaseChanged {
+                    status: RaceStatus::Finished | RaceStatus::Cancelled,
+                    ..
+                } = event.change
+                {
+                    self.race_over = true;
+                }
+                return Some(event);
+            }
+            if self.race_over {
+                return None;
+            }
+
+            match self.fetch().await {
+                Ok(events) if !events.is_empty() => self.pending.extend(events),
+                Ok(_) => tokio::time::sleep(RACE_EVENTS_POLL_INTERVAL).await,
+                Err(e) => {
+                    tracing::warn!("Failed to read changes of race {}: {}", self.race_uuid, e);
+                    tokio::time::sleep(RACE_EVENTS_POLL_INTERVAL).await;
+                }
+            }
+        }
+    }
+
+    async fn fetch(&mut self) -> Result<Vec<RaceEvent>, mongodb::error::Error> {
+        let (occurred_at, id) = self.after;
+        let mut filter = doc! { "race_uuid": self.race_uuid.to_string() };
+        match id {
+            Some(id) => {
+                filter.insert(
+                    "$or",
+                    vec![
+                        doc! { "occurred_at": { "$gt": occurred_at } },
+                        doc! { "occurred_at": occurred_at, "_id": { "$gt": id } },
+                    ],
+                );
+            }
+            None => {
+                filter.insert("occurred_at", doc! { "$gt": occurred_at });
+            }
+        }
+        let options = FindOptions::builder()
+            .sort(doc! { "occurred_at": 1, "_id": 1 })
+            .limit(RACE_EVENTS_BATCH)
+            .build();
+        let mut cursor = self
+            .database
+            .collection::<RaceEvent>("race_events")
+            .find(filter, options)
+            .await?;
+
+        let mut events = Vec::new();
+        while cursor.advance().await? {
+            events.push(cursor.deserialize_current()?);
+        }
+        if let Some(last) = events.last() {
+            self.after = (last.occurred_at, last.id);
+        }
+        Ok(events)
+    }
+}
@@ -1,3 +1,4 @@
+use axum::http::HeaderMap;
 use chrono::{DateTime, Duration, Utc};
 use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
@@ -8,8 +9,12 @@ use std::{
 };
 use uuid::Uuid;
 
+use crate::configuration::SessionSettings;
 use crate::repositories::SessionRepository;
 
+/// Header carrying the fingerprint of the client's device
+pub const DEVICE_FINGERPRINT_HEADER: &str = "x-device-fingerprint";
+
 /// Session configuration
 #[derive(Debug, Clone)]
 pub struct SessionConfig {
@@ -17,6 +22,20 @@ pub struct SessionConfig {
     pub session_timeout: StdDuration,
     pub blacklist_cleanup_interval: StdDuration,
     pub cache_size_limit: usize,
+    /// Whether sensitive operations must come from the device a session was
+    /// opened on
+    pub bind_to_device: bool,
+}
+
+impl SessionConfig {
+    #[must_use]
+    pub fn from_settings(settings: &SessionSettings) -> Self {
+        Self {
+            max_sessions_per_user: settings.max_per_player.max(1),
+            bind_to_device: settings.bind_to_device,
+            ..Self::default()
+        }
+    }
 }
 
 impl Default for SessionConfig {
@@ -26,6 +45,7 @@ impl Default for SessionConfig {
             session_timeout: StdDuration::from_secs(24 * 60 * 60), // 24 hours
             blacklist_cleanup_interval: StdDuration::from_secs(60 * 60), // 1 hour
             cache_size_limit: 10000,
+            bind_to_device: false,
         }
     }
 }
@@ -44,6 +64,9 @@ pub struct Session {
     pub user_agent: Option<String>,
     pub is_active: bool,
     pub updated_at: DateTime<Utc>,
+    /// Fingerprint of the device the session was opened on, if it sent one
+    #[serde(default)]
+    pub device_fingerprint: Option<String>,
 }
 
 /// Blacklisted token data structure
@@ -59,10 +82,30 @@ pub struct BlacklistedToken {
 }
 
 /// Session metadata for creation
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct SessionMetadata {
     pub ip_address: Option<String>,
     pub user_agent: Option<String>,
+    pub device_fingerprint: Option<String>,
+}
+
+impl SessionMetadata {
+    /// Metadata of the request opening a session
+    #[must_use]
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|h| h.to_str().ok())
+                .map(std::string::ToString::to_string)
+        };
+        Self {
+            ip_address: header("x-forwarded-for").or_else(|| header("x-real-ip")),
+            user_agent: header("user-agent"),
+            device_fingerprint: header(DEVICE_FINGERPRINT_HEADER)
+                .filter(|fingerprint| !fingerprint.is_empty()),
+        }
+    }
 }
 
 /// In-memory cache for sessions and blacklisted tokens
@@ -134,14 +177,16 @@ impl<R: SessionRepository> SessionManager<R> {
             + Duration::from_std(self.config.session_timeout)
                 .map_err(|e| SessionError::Cache(e.to_string()))?;
 
-        // Check if user has too many sessions
-        let user_session_count = self
+        // Make room for the new session by ending the oldest ones
+        let active = self
             .repository
-            .count_active_for_user(user_uuid)
+            .find_active_for_user(user_uuid, now)
             .await
             .map_err(|e| SessionError::Database(mongodb::error::Error::custom(e.to_string())))?;
-        if user_session_count >= self.config.max_sessions_per_user {
-            return Err(SessionError::TooManySessions);
+        let excess = (active.len() + 1).saturating_sub(self.config.max_sessions_per_user);
+        for oldest in active.iter().take(excess) {
+            self.invalidate_session(&oldest.token, "session_limit")
+                .await?;
         }
 
         let session = Session {
@@ -155,6 +200,7 @@ impl<R: SessionRepository> SessionManager<R> {
             user_agent: metadata.user_agent,
             is_active: true,
             updated_at: now,
+            device_fingerprint: metadata.device_fingerprint,
         };
 
         // Store in repository
@@ -202,6 +248,17 @@ impl<R: SessionRepository> SessionManager<R> {
         }
     }
 
+    /// Active, unexpired session of a token ID
+    pub async fn find_session(&self, token_id: &str) -> Result<Option<Session>, SessionError> {
+        if let Some(session) = self.get_session_from_cache(token_id) {
+            return Ok(Some(session));
+        }
+        self.repository
+            .find_by_token(token_id)
+            .await
+            .map_err(|e| SessionError::Database(mongodb::error::Error::custom(e.to_string())))
+    }
+
     /// Invalidate a session
     pub async fn invalidate_session(
         &self,
@@ -376,6 +433,7 @@ mod tests {
             user_agent: Some("test-agent".to_string()),
             is_active: true,
             updated_at: now,
+            device_fingerprint: None,
         };
 
         assert_eq!(session.user_uuid, user_uuid);
@@ -410,6 +468,7 @@ mod tests {
         let metadata = SessionMetadata {
             ip_address: Some("192.168.1.1".to_string()),
             user_agent: Some("Mozilla/5.0".to_string()),
+            device_fingerprint: None,
         };
 
         assert_eq!(metadata.ip_address, Some("192.168.1.1".to_string()));
@@ -447,6 +506,7 @@ mod tests {
             user_agent: Some("test-agent".to_string()),
             is_active: true,
             updated_at: now,
+            device_fingerprint: None,
         };
 
         // Test caching
@@ -467,6 +527,32 @@ mod tests {
         assert!(cached_session_after_removal.is_none());
     }
 
+    #[tokio::test]
+    async fn oldest_sessions_are_evicted_over_the_limit() {
+        let mock_repo = Arc::new(MockSessionRepository::new());
+        let config = SessionConfig {
+            max_sessions_per_user: 2,
+            ..SessionConfig::default()
+        };
+        let session_manager = SessionManager::new(mock_repo, config);
+        let user_uuid = Uuid::new_v4();
+
+        for token_id in ["first", "second", "third"] {
+            session_manager
+                .create_session(user_uuid, token_id.to_string(), SessionMetadata::default())
+                .await
+                .unwrap();
+        }
+
+        assert!(session_manager
+            .find_session("first")
+            .await
+            .unwrap()
+            .is_none());
+        assert!(session_manager.validate_session("second").await.unwrap());
+        assert!(session_manager.validate_session("third").await.unwrap());
+    }
+
     #[test]
     fn session_error_types_work() {
         let db_error = SessionError::Database(mongodb::error::Error::custom("test error"));
@@ -0,0 +1,196 @@
+use crate::domain::{
+    Body, BodyName, Car, CarName, ComponentRarity, Email, Engine, EngineName, HashedPassword,
+    Pilot, PilotClass, PilotName, PilotPerformance, PilotRarity, PilotSkills, Player, TeamName,
+};
+
+/// Cars, pilots, engines and bodies granted to every new player
+///
+/// Both cars come with an engine, a body and three pilots, so a new account
+/// can register for a race straight away.
+pub struct StarterPack {
+    pub cars: Vec<Car>,
+    pub pilots: Vec<Pilot>,
+    pub engines: Vec<Engine>,
+    pub bodies: Vec<Body>,
+}
+
+impl StarterPack {
+    pub fn new() -> Result<Self, String> {
+        let [pilot1, pilot2, pilot3, pilot4, pilot5, pilot6] = starter_pilots()?;
+
+        // Create 2 starter cars
+        let mut car1 = Car::new(CarName::parse("Car 1").unwrap(), None)
+            .map_err(|e| format!("Failed to create starter car 1: {e}"))?;
+
+        let mut car2 = Car::new(CarName::parse("Car 2").unwrap(), None)
+            .map_err(|e| format!("Failed to create starter car 2: {e}"))?;
+
+        // Assign pilots to cars (3 pilots per car)
+        car1.assign_pilots(vec![pilot1.uuid, pilot2.uuid, pilot3.uuid])
+            .map_err(|e| format!("Failed to assign pilots to car 1: {e}"))?;
+
+        car2.assign_pilots(vec![pilot4.uuid, pilot5.uuid, pilot6.uuid])
+            .map_err(|e| format!("Failed to assign pilots to car 2: {e}"))?;
+
+        // Create 2 starter engines with different characteristics
+        let engine1 = Engine::new(
+            EngineName::parse("Basic Engine 1").unwrap(),
+            ComponentRarity::Common,
+            7, // straight_value - good for straights (0-10 range)
+            5, // curve_value
+            None,
+        )
+        .map_err(|e| format!("Failed to create starter engine 1: {e}"))?;
+
+        let engine2 = Engine::new(
+            EngineName::parse("Basic Engine 2").unwrap(),
+            ComponentRarity::Common,
+            5, // straight_value
+            7, // curve_value - good for curves
+            None,
+        )
+        .map_err(|e| format!("Failed to create starter engine 2: {e}"))?;
+
+        // Create 2 starter bodies with different characteristics
+        let body1 = Body::new(
+            BodyName::parse("Basic Body 1").unwrap(),
+            ComponentRarity::Common,
+            5, // straight_value (0-10 range)
+            7, // curve_value - good for curves
+            None,
+        )
+        .map_err(|e| format!("Failed to create starter body 1: {e}"))?;
+
+        let body2 = Body::new(
+            BodyName::parse("Basic Body 2").unwrap(),
+            ComponentRarity::Common,
+            7, // straight_value - good for straights
+            5, // curve_value
+            None,
+        )
+        .map_err(|e| format!("Failed to create starter body 2: {e}"))?;
+
+        // Equip each car with its own engine and body so both are ready to race
+        car1.assign_engine(engine1.uuid);
+        car1.assign_body(body1.uuid);
+        car2.assign_engine(engine2.uuid);
+        car2.assign_body(body2.uuid);
+
+        Ok(Self {
+            cars: vec![car1, car2],
+            pilots: vec![pilot1, pilot2, pilot3, pilot4, pilot5, pilot6],
+            engines: vec![engine1, engine2],
+            bodies: vec![body1, body2],
+        })
+    }
+
+    /// New player account owning the starter pack
+    pub fn grant(
+        self,
+        email: Email,
+        password_hash: HashedPassword,
+        team_name: TeamName,
+    ) -> Result<Player, String> {
+        Player::new_with_assets(
+            email,
+            password_hash,
+            team_name,
+            self.cars,
+            self.pilots,
+            self.engines,
+            self.bodies,
+        )
+    }
+}
+
+/// Six pilots of different classes and rarities, three for each starter car
+fn starter_pilots() -> Result<[Pilot; 6], String> {
+    let pilot1 = Pilot::new(
+        PilotName::parse("Speedster Ace").unwrap(),
+        PilotClass::Speedster,
+        PilotRarity::Rookie,
+        PilotSkills::new(7, 5, 6, 4).unwrap(),
+        PilotPerformance::new(8, 5).unwrap(),
+        None,
+    )
+    .map_err(|e| format!("Failed to create pilot 1: {e}"))?;
+
+    let pilot2 = Pilot::new(
+        PilotName::parse("Tech Master").unwrap(),
+        PilotClass::Technician,
+        PilotRarity::Rookie,
+        PilotSkills::new(5, 8, 7, 5).unwrap(),
+        PilotPerformance::new(5, 8).unwrap(),
+        None,
+    )
+    .map_err(|e| format!("Failed to create pilot 2: {e}"))?;
+
+    let pilot3 = Pilot::new(
+        PilotName::parse("Endurance Pro").unwrap(),
+        PilotClass::Endurance,
+        PilotRarity::Rookie,
+        PilotSkills::new(4, 6, 8, 9).unwrap(),
+        PilotPerformance::new(6, 7).unwrap(),
+        None,
+    )
+    .map_err(|e| format!("Failed to create pilot 3: {e}"))?;
+
+    let pilot4 = Pilot::new(
+        PilotName::parse("All-Round Rookie").unwrap(),
+        PilotClass::AllRounder,
+        PilotRarity::Rookie,
+        PilotSkills::new(6, 6, 6, 6).unwrap(),
+        PilotPerformance::new(6, 6).unwrap(),
+        None,
+    )
+    .map_err(|e| format!("Failed to create pilot 4: {e}"))?;
+
+    let pilot5 = Pilot::new(
+        PilotName::parse("Speed Demon").unwrap(),
+        PilotClass::Speedster,
+        PilotRarity::Professional,
+        PilotSkills::new(8, 4, 5, 3).unwrap(),
+        PilotPerformance::new(9, 4).unwrap(),
+        None,
+    )
+    .map_err(|e| format!("Failed to create pilot 5: {e}"))?;
+
+    let pilot6 = Pilot::new(
+        PilotName::parse("Precision Driver").unwrap(),
+        PilotClass::Technician,
+        PilotRarity::Professional,
+        PilotSkills::new(4, 9, 8, 6).unwrap(),
+        PilotPerformance::new(4, 9).unwrap(),
+        None,
+    )
+    .map_err(|e| format!("Failed to create pilot 6: {e}"))?;
+
+    Ok([pilot1, pilot2, pilot3, pilot4, pilot5, pilot6])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Password;
+    use crate::services::car_validation::CarValidationService;
+
+    #[test]
+    fn every_starter_car_is_ready_to_race() {
+        let player = StarterPack::new()
+            .unwrap()
+            .grant(
+                Email::parse("rookie@example.com").unwrap(),
+                Password::new("RookiePass123".to_string())
+                    .unwrap()
+                    .hash()
+                    .unwrap(),
+                TeamName::parse("Rookies").unwrap(),
+            )
+            .unwrap();
+
+        assert_eq!(player.cars.len(), 2);
+        for car in &player.cars {
+            assert!(CarValidationService::validate_owned_car(&player, car.uuid).is_ok());
+        }
+    }
+}
@@ -0,0 +1,437 @@
+use hmac::{Hmac, Mac};
+use mongodb::{
+    bson::{doc, DateTime as BsonDateTime},
+    options::FindOneAndUpdateOptions,
+    Database,
+};
+use sha2::{Digest, Sha256};
+use std::net::{IpAddr, SocketAddr};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+use crate::configuration::WebhookSettings;
+use crate::database::is_duplicate_key;
+use crate::domain::{DeliveryStatus, Webhook, WebhookDelivery, WebhookEvent};
+
+pub const WEBHOOKS: &str = "webhooks";
+pub const WEBHOOK_DELIVERIES: &str = "webhook_deliveries";
+
+/// Why the server will not post to a webhook URL
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum WebhookUrlError {
+    #[error("url must be an absolute http or https URL")]
+    Invalid,
+    #[error("url host does not resolve")]
+    Unresolved,
+    #[error("url resolves to {0}, which is not a public address")]
+    NotPublic(IpAddr),
+}
+
+/// Whether the server may post to `ip` for a race creator
+///
+/// Loopback, private, link-local (where cloud metadata services live),
+/// shared, multicast and unspecified addresses all reach inside the network.
+#[must_use]
+pub fn is_public_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [first, second, ..] = ip.octets();
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                || first == 0
+                // 100.64.0.0/10, shared by carrier-grade NATs
+                || (first == 100 && second & 0xc0 == 64))
+        }
+        IpAddr::V6(ip) => {
+            if let Some(mapped) = ip.to_ipv4_mapped() {
+                return is_public_address(IpAddr::V4(mapped));
+            }
+            let first = ip.segments()[0];
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                // fc00::/7 unique local and fe80::/10 link-local
+                || first & 0xfe00 == 0xfc00
+                || first & 0xffc0 == 0xfe80)
+        }
+    }
+}
+
+/// Webhook URL host and the public addresses it resolved to when checked
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VettedUrl {
+    pub host: String,
+    pub addresses: Vec<SocketAddr>,
+}
+
+/// Check that `url` is an http or https URL whose host only resolves to
+/// public addresses, and return those addresses
+///
+/// Checked when a webhook is registered and again before every delivery, as
+/// the host may resolve elsewhere by then. Deliveries connect to the returned
+/// addresses rather than resolving the host again.
+pub async fn check_webhook_url(url: &str) -> Result<VettedUrl, WebhookUrlError> {
+    let url = reqwest::Url::parse(url).map_err(|_| WebhookUrlError::Invalid)?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(WebhookUrlError::Invalid);
+    }
+    let (Some(host), Some(port)) = (url.host_str(), url.port_or_known_default()) else {
+        return Err(WebhookUrlError::Invalid);
+    };
+
+    // IPv6 hosts keep their brackets in URLs
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let addresses: Vec<SocketAddr> = match host.parse::<IpAddr>() {
+        Ok(ip) => vec![SocketAddr::new(ip, port)],
+        Err(_) => tokio::net::lookup_host((host, port))
+            .await
+            .map_err(|_| WebhookUrlError::Unresolved)?
+            .collect(),
+    };
+    if addresses.is_empty() {
+        return Err(WebhookUrlError::Unresolved);
+    }
+    match addresses
+        .iter()
+        .find(|address| !is_public_address(address.ip()))
+    {
+        Some(address) => Err(WebhookUrlError::NotPublic(address.ip())),
+        None => Ok(VettedUrl {
+            host: host.to_string(),
+            addresses,
+        }),
+    }
+}
+
+/// `X-Webhook-Signature` value for a delivery
+///
+/// HMAC-SHA256 keyed with the webhook secret over `"{timestamp}.{body}"`, so a
+/// receiver can reject replayed or tampered requests.
+#[must_use]
+pub fn sign_payload(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(format!("{timestamp}.{body}").as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Queue `body` for every webhook of the race subscribed to `event`
+///
+/// Deliveries get an id derived from `message_uuid` and the webhook, so
+/// queueing the same outbox message again adds nothing. Returns the number of
+/// deliveries added.
+pub async fn enqueue_webhook_deliveries(
+    database: &Database,
+    race_uuid: Uuid,
+    message_uuid: Uuid,
+    event: WebhookEvent,
+    body: &str,
+) -> Result<u64, mongodb::error::Error> {
+    let mut cursor = database
+        .collection::<Webhook>(WEBHOOKS)
+        .find(doc! { "race_uuid": race_uuid.to_string() }, None)
+        .await?;
+    let deliveries = database.collection::<WebhookDelivery>(WEBHOOK_DELIVERIES);
+    let mut queued = 0;
+    while cursor.advance().await? {
+        let webhook: Webhook = cursor.deserialize_current()?;
+        if !webhook.events.contains(&event) {
+            continue;
+        }
+        let mut delivery = WebhookDelivery::new(&webhook, event, body.to_string());
+        delivery.uuid = delivery_uuid(message_uuid, webhook.uuid);
+        match deliveries.insert_one(&delivery, None).await {
+            Ok(_) => queued += 1,
+            Err(e) if is_duplicate_key(&e) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(queued)
+}
+
+fn delivery_uuid(message_uuid: Uuid, webhook_uuid: Uuid) -> Uuid {
+    let digest = Sha256::new()
+        .chain_update(message_uuid.as_bytes())
+        .chain_update(webhook_uuid.as_bytes())
+        .finalize();
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&digest[..16]);
+    uuid::Builder::from_custom_bytes(bytes).into_uuid()
+}
+
+/// Sends queued deliveries, retrying failures with exponential backoff
+#[derive(Clone)]
+pub struct WebhookSender {
+    max_attempts: u32,
+    retry_base: Duration,
+    timeout: Duration,
+}
+
+impl WebhookSender {
+    pub fn from_settings(settings: &WebhookSettings) -> Result<Self, reqwest::Error> {
+        Ok(Self {
+            max_attempts: settings.max_attempts.max(1),
+            retry_base: Duration::from_secs(settings.retry_base_seconds),
+            timeout: Duration::from_secs(settings.timeout_seconds),
+        })
+    }
+
+    /// Client that can only reach the vetted addresses of `target`, so the
+    /// host cannot be made to resolve to an internal address between the
+    /// check and the connection
+    fn client_for(&self, target: &VettedUrl) -> Result<reqwest::Client, reqwest::Error> {
+        reqwest::Client::builder()
+            .timeout(self.timeout)
+            // A public endpoint must not bounce deliveries to an internal one
+            .redirect(reqwest::redirect::Policy::none())
+            .resolve_to_addrs(&target.host, &target.addresses)
+            .build()
+    }
+
+    /// Wait before attempt `attempts + 1`, doubling after every failure
+    #[must_use]
+    pub fn retry_delay(&self, attempts: u32) -> Duration {
+        self.retry_base
+            .saturating_mul(1 << attempts.saturating_sub(1).min(16))
+    }
+
+    /// Attempt up to `batch_size` deliveries that are due
+    /// Returns the number of attempts made.
+    pub async fn deliver_due(
+        &self,
+        database: &Database,
+        batch_size: u32,
+    ) -> Result<u64, mongodb::error::Error> {
+        let mut attempted = 0;
+        while attempted < u64::from(batch_size) {
+            let Some(delivery) = self.claim_due(database).await? else {
+                break;
+            };
+            self.attempt(database, delivery).await?;
+            attempted += 1;
+        }
+        Ok(attempted)
+    }
+
+    /// Take the oldest due delivery, pushing it back while it is attempted so
+    /// another server does not send it too
+    async fn claim_due(
+        &self,
+        database: &Database,
+    ) -> Result<Option<WebhookDelivery>, mongodb::error::Error> {
+        let now = SystemTime::now();
+        let lease = BsonDateTime::from_system_time(now + self.timeout + Duration::from_secs(30));
+        let options = FindOneAndUpdateOptions::builder()
+            .sort(doc! { "next_attempt_at": 1 })
+            .build();
+        database
+            .collection::<WebhookDelivery>(WEBHOOK_DELIVERIES)
+            .find_one_and_update(
+                doc! {
+                    "status": mongodb::bson::to_bson(&DeliveryStatus::Pending)?,
+                    "next_attempt_at": { "$lte": BsonDateTime::from_system_time(now) },
+                },
+                doc! { "$set": { "next_attempt_at": lease } },
+                options,
+            )
+            .await
+    }
+
+    async fn attempt(
+        &self,
+        database: &Database,
+        delivery: WebhookDelivery,
+    ) -> Result<(), mongodb::error::Error> {
+        let webhook = database
+            .collection::<Webhook>(WEBHOOKS)
+            .find_one(doc! { "uuid": delivery.webhook_uuid.to_string() }, None)
+            .await?;
+        let (outcome, retry) = match webhook {
+            Some(webhook) => (self.send(&webhook, &delivery).await, true),
+            None => (Err((None, "Webhook was removed".to_string())), false),
+        };
+
+        let attempts = delivery.attempts + 1;
+        let update = match outcome {
+            Ok(status_code) => doc! {
+                "status": mongodb::bson::to_bson(&DeliveryStatus::Delivered)?,
+                "attempts": attempts,
+                "last_status_code": i32::from(status_code),
+                "last_error": null,
+                "delivered_at": BsonDateTime::now(),
+            },
+            Err((status_code, error)) => {
+                tracing::warn!(
+                    "Webhook delivery {} failed (attempt {}): {}",
+                    delivery.uuid,
+                    attempts,
+                    error
+                );
+                let status = if retry && attempts < self.max_attempts {
+                    DeliveryStatus::Pending
+                } else {
+                    DeliveryStatus::Failed
+                };
+                let next_attempt_at =
+                    BsonDateTime::from_system_time(SystemTime::now() + self.retry_delay(attempts));
+                doc! {
+                    "status": mongodb::bson::to_bson(&status)?,
+                    "attempts": attempts,
+                    "last_status_code": status_code.map(i32::from),
+                    "last_error": error,
+                    "next_attempt_at": next_attempt_at,
+                }
+            }
+        };
+        database
+            .collection::<WebhookDelivery>(WEBHOOK_DELIVERIES)
+            .update_one(
+                doc! { "uuid": delivery.uuid.to_string() },
+                doc! { "$set": update },
+                None,
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// POST the delivery; a 2xx answer is a success
+    async fn send(
+        &self,
+        webhook: &Webhook,
+        delivery: &WebhookDelivery,
+    ) -> Result<u16, (Option<u16>, String)> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| {
+                i64::try_from(elapsed.as_secs()).unwrap_or(i64::MAX)
+            });
+        let event = serde_json::to_value(delivery.event)
+            .ok()
+            .and_then(|event| event.as_str().map(str::to_string))
+            .unwrap_or_default();
+        let target = check_webhook_url(&webhook.url)
+            .await
+            .map_err(|e| (None, e.to_string()))?;
+        let client = self
+            .client_for(&target)
+            .map_err(|e| (None, e.to_string()))?;
+
+        let response = client
+            .post(&webhook.url)
+            .header("Content-Type", "application/json")
+            .header("X-Webhook-Event", event)
+            .header("X-Webhook-Delivery", delivery.uuid.to_string())
+            .header("X-Webhook-Timestamp", timestamp.to_string())
+            .header(
+                "X-Webhook-Signature",
+                sign_payload(&webhook.secret, timestamp, &delivery.payload),
+            )
+            .body(delivery.payload.clone())
+            .send()
+            .await
+            .map_err(|e| (None, e.to_string()))?;
+
+        let status = response.status();
+        if status.is_success() {
+            Ok(status.as_u16())
+        } else {
+            Err((Some(status.as_u16()), format!("Endpoint answered {status}")))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_covers_timestamp_and_body() {
+        let signature = sign_payload("secret", 1_700_000_000, r#"{"event":"RaceStarted"}"#);
+
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(signature.len(), "sha256=".len() + 64);
+        assert_ne!(
+            signature,
+            sign_payload("secret", 1_700_000_001, r#"{"event":"RaceStarted"}"#)
+        );
+        assert_ne!(
+            signature,
+            sign_payload("other", 1_700_000_000, r#"{"event":"RaceStarted"}"#)
+        );
+    }
+
+    #[test]
+    fn delivery_ids_are_stable_per_message_and_webhook() {
+        let (message, webhook) = (Uuid::new_v4(), Uuid::new_v4());
+
+        assert_eq!(
+            delivery_uuid(message, webhook),
+            delivery_uuid(message, webhook)
+        );
+        assert_ne!(
+            delivery_uuid(message, webhook),
+            delivery_uuid(message, Uuid::new_v4())
+        );
+    }
+
+    #[test]
+    fn retries_back_off_exponentially() {
+        let sender = WebhookSender::from_settings(&WebhookSettings::default()).unwrap();
+        let base = sender.retry_base;
+
+        assert_eq!(sender.retry_delay(1), base);
+        assert_eq!(sender.retry_delay(2), base * 2);
+        assert_eq!(sender.retry_delay(4), base * 8);
+    }
+
+    #[tokio::test]
+    async fn internal_addresses_are_not_webhook_targets() {
+        for url in [
+            "http://127.0.0.1/hook",
+            "http://10.1.2.3/hook",
+            "http://192.168.0.10:8080/hook",
+            "http://169.254.169.254/latest/meta-data",
+            "http://[::1]/hook",
+            "http://[fd00::1]/hook",
+            "http://[::ffff:10.0.0.1]/hook",
+        ] {
+            assert!(
+                matches!(
+                    check_webhook_url(url).await,
+                    Err(WebhookUrlError::NotPublic(_))
+                ),
+                "{url} was accepted"
+            );
+        }
+        assert!(matches!(
+            check_webhook_url("http://localhost:8080/hook").await,
+            Err(WebhookUrlError::NotPublic(ip)) if ip.is_loopback()
+        ));
+        assert_eq!(
+            check_webhook_url("ftp://203.0.113.1/hook").await,
+            Err(WebhookUrlError::Invalid)
+        );
+        assert!(is_public_address("93.184.216.34".parse().unwrap()));
+        assert!(is_public_address("2606:2800:220:1::".parse().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn deliveries_connect_to_the_checked_addresses() {
+        let vetted = check_webhook_url("https://93.184.216.34/hook")
+            .await
+            .unwrap();
+
+        assert_eq!(vetted.host, "93.184.216.34");
+        assert_eq!(
+            vetted.addresses,
+            vec!["93.184.216.34:443".parse::<SocketAddr>().unwrap()]
+        );
+        let sender = WebhookSender::from_settings(&WebhookSettings::default()).unwrap();
+        assert!(sender.client_for(&vetted).is_ok());
+    }
+}
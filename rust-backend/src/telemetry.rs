@@ -3,7 +3,10 @@ use tracing::Subscriber;
 use tracing_bunyan_formatter::{BunyanFormattingLayer, JsonStorageLayer};
 use tracing_log::LogTracer;
 use tracing_subscriber::fmt::MakeWriter;
-use tracing_subscriber::{layer::SubscriberExt, EnvFilter, Registry};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{layer::SubscriberExt, EnvFilter, Layer, Registry};
+
+use crate::configuration::TelemetrySettings;
 
 /// Compose multiple layers into a `tracing`'s subscriber.
 ///
@@ -16,7 +19,7 @@ pub fn get_subscriber<Sink>(
     name: String,
     env_filter: String,
     sink: Sink,
-) -> impl Subscriber + Send + Sync
+) -> impl Subscriber + for<'a> LookupSpan<'a> + Send + Sync
 where
     Sink: for<'a> MakeWriter<'a> + Send + Sync + 'static,
 {
@@ -36,3 +39,117 @@ pub fn init_subscriber(subscriber: impl Subscriber + Send + Sync) {
     LogTracer::init().expect("Failed to set logger");
     set_global_default(subscriber).expect("Failed to set subscriber");
 }
+
+/// Layer exporting spans to the OTLP collector in `settings`, if one is set
+///
+/// Add it to the subscriber from [`get_subscriber`] so handler, car
+/// validation and `MongoDB` spans reach Jaeger or Tempo as one trace.
+/// Must be called inside the Tokio runtime, which sends the batches.
+#[cfg(feature = "otlp")]
+pub fn get_otlp_layer<S>(
+    name: &str,
+    settings: &TelemetrySettings,
+) -> Result<Option<impl Layer<S>>, anyhow::Error>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry::KeyValue;
+    use opentelemetry_otlp::WithExportConfig;
+    use opentelemetry_sdk::{runtime, trace::TracerProvider, Resource};
+
+    let Some(endpoint) = &settings.otlp_endpoint else {
+        return Ok(None);
+    };
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(endpoint)
+        .build()?;
+    let provider = TracerProvider::builder()
+        .with_batch_exporter(exporter, runtime::Tokio)
+        .with_resource(Resource::new([KeyValue::new(
+            "service.name",
+            name.to_string(),
+        )]))
+        .build();
+    let tracer = provider.tracer(name.to_string());
+    opentelemetry::global::set_tracer_provider(provider);
+    Ok(Some(tracing_opentelemetry::layer().with_tracer(tracer)))
+}
+
+/// Without the `otlp` feature no layer is added, and configuring an
+/// endpoint is an error rather than silently dropping traces
+#[cfg(not(feature = "otlp"))]
+pub fn get_otlp_layer<S>(
+    _name: &str,
+    settings: &TelemetrySettings,
+) -> Result<Option<impl Layer<S>>, anyhow::Error>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    if settings.otlp_endpoint.is_some() {
+        anyhow::bail!("telemetry.otlp_endpoint requires building with `--features otlp`");
+    }
+    Ok(None::<tracing_subscriber::layer::Identity>)
+}
+
+/// Flush the spans still waiting to be exported
+pub fn shutdown_otlp() {
+    #[cfg(feature = "otlp")]
+    opentelemetry::global::shutdown_tracer_provider();
+}
+
+/// Keeps the Sentry client alive; dropping it flushes pending reports
+pub struct SentryGuard {
+    #[cfg(feature = "sentry")]
+    _client: Option<sentry::ClientInitGuard>,
+}
+
+/// Start reporting panics to the Sentry project in `settings`, if one is set
+///
+/// Call it before anything else, so panics during startup are reported too.
+#[cfg(feature = "sentry")]
+pub fn init_sentry(settings: &TelemetrySettings) -> Result<SentryGuard, anyhow::Error> {
+    let client = settings.sentry_dsn.as_deref().map(|dsn| {
+        sentry::init((
+            dsn,
+            sentry::ClientOptions {
+                release: sentry::release_name!(),
+                ..Default::default()
+            },
+        ))
+    });
+    Ok(SentryGuard { _client: client })
+}
+
+/// Without the `sentry` feature nothing is reported, and configuring a DSN is
+/// an error
+#[cfg(not(feature = "sentry"))]
+pub fn init_sentry(settings: &TelemetrySettings) -> Result<SentryGuard, anyhow::Error> {
+    if settings.sentry_dsn.is_some() {
+        anyhow::bail!("telemetry.sentry_dsn requires building with `--features sentry`");
+    }
+    Ok(SentryGuard {})
+}
+
+/// Layer sending error events to Sentry, with the warnings and info logged
+/// before them as breadcrumbs
+///
+/// Error events carry the error chain the handlers log, tagged by
+/// [`crate::middleware::tag_error_reports`].
+#[must_use]
+pub fn get_sentry_layer<S>(settings: &TelemetrySettings) -> Option<impl Layer<S>>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    #[cfg(feature = "sentry")]
+    return settings
+        .sentry_dsn
+        .is_some()
+        .then(sentry_tracing::layer::<S>);
+    #[cfg(not(feature = "sentry"))]
+    {
+        let _ = settings;
+        None::<tracing_subscriber::layer::Identity>
+    }
+}
@@ -27,6 +27,8 @@ impl TestAppState {
         // Initialize JWT service with test configuration
         let jwt_config = JwtConfig {
             secret: "test-secret-key-for-testing-only".to_string(),
+            key_id: None,
+            previous_keys: std::collections::HashMap::new(),
             access_token_expiry: std::time::Duration::from_secs(30 * 60), // 30 minutes
             refresh_token_expiry: std::time::Duration::from_secs(30 * 24 * 60 * 60), // 30 days
             issuer: "racing-game-test".to_string(),
@@ -60,6 +62,8 @@ impl TestAppState {
 
         let jwt_config = JwtConfig {
             secret: "test-secret-key-for-testing-only".to_string(),
+            key_id: None,
+            previous_keys: std::collections::HashMap::new(),
             access_token_expiry: std::time::Duration::from_secs(30 * 60),
             refresh_token_expiry: std::time::Duration::from_secs(30 * 24 * 60 * 60),
             issuer: "racing-game-test".to_string(),
@@ -0,0 +1,104 @@
+use axum::Router;
+use futures_util::future::BoxFuture;
+use tokio::net::TcpListener;
+
+use crate::configuration::TlsSettings;
+
+/// Serve `app` over HTTPS on `listener`
+///
+/// HTTP/2 is offered through ALPN next to HTTP/1.1, and upgrades are
+/// supported so the chat WebSocket works as over plain HTTP. A client failing
+/// the handshake only loses its own connection.
+#[cfg(feature = "tls")]
+pub fn serve_tls(
+    listener: TcpListener,
+    app: Router,
+    settings: &TlsSettings,
+) -> Result<BoxFuture<'static, Result<(), std::io::Error>>, anyhow::Error> {
+    let acceptor =
+        tokio_rustls::TlsAcceptor::from(std::sync::Arc::new(load_server_config(settings)?));
+    Ok(Box::pin(accept_connections(listener, acceptor, app)))
+}
+
+/// Without the `tls` feature an HTTPS listener cannot be built
+#[cfg(not(feature = "tls"))]
+pub fn serve_tls(
+    _listener: TcpListener,
+    _app: Router,
+    _settings: &TlsSettings,
+) -> Result<BoxFuture<'static, Result<(), std::io::Error>>, anyhow::Error> {
+    anyhow::bail!("`application.tls` requires building with `--features tls`")
+}
+
+#[cfg(feature = "tls")]
+fn load_server_config(
+    settings: &TlsSettings,
+) -> Result<tokio_rustls::rustls::ServerConfig, anyhow::Error> {
+    use anyhow::Context;
+    use tokio_rustls::rustls::{
+        crypto::ring,
+        pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer},
+        ServerConfig,
+    };
+
+    let certificates = CertificateDer::pem_file_iter(&settings.cert_file)
+        .and_then(Iterator::collect::<Result<Vec<_>, _>>)
+        .with_context(|| format!("Failed to read certificates from {}", settings.cert_file))?;
+    let key = PrivateKeyDer::from_pem_file(&settings.key_file)
+        .with_context(|| format!("Failed to read the private key from {}", settings.key_file))?;
+
+    let mut config =
+        ServerConfig::builder_with_provider(std::sync::Arc::new(ring::default_provider()))
+            .with_safe_default_protocol_versions()?
+            .with_no_client_auth()
+            .with_single_cert(certificates, key)
+            .context("The certificate does not match the private key")?;
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(config)
+}
+
+#[cfg(feature = "tls")]
+async fn accept_connections(
+    listener: TcpListener,
+    acceptor: tokio_rustls::TlsAcceptor,
+    app: Router,
+) -> Result<(), std::io::Error> {
+    use axum::{extract::ConnectInfo, Extension};
+    use hyper_util::{
+        rt::{TokioExecutor, TokioIo},
+        server::conn::auto::Builder,
+        service::TowerToHyperService,
+    };
+    use tower::Layer;
+
+    loop {
+        let (stream, remote_address) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                // e.g. out of file descriptors; give connections time to close
+                tracing::warn!("Failed to accept a connection: {}", e);
+                tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                continue;
+            }
+        };
+        let acceptor = acceptor.clone();
+        // What `into_make_service_with_connect_info` does for plain HTTP
+        let service =
+            TowerToHyperService::new(Extension(ConnectInfo(remote_address)).layer(app.clone()));
+        tokio::spawn(async move {
+            let stream = match acceptor.accept(stream).await {
+                Ok(stream) => stream,
+                Err(e) => {
+                    tracing::debug!("TLS handshake with {} failed: {}", remote_address, e);
+                    return;
+                }
+            };
+            if let Err(e) = Builder::new(TokioExecutor::new())
+                .serve_connection_with_upgrades(TokioIo::new(stream), service)
+                .await
+            {
+                tracing::debug!("Connection with {} closed: {}", remote_address, e);
+            }
+        });
+    }
+}
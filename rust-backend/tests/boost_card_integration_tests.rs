@@ -73,7 +73,7 @@ impl TestApp {
                     "min_value": 10,
                     "max_value": 20,
                     "slot_capacity": null,
-                    "sector_type": "Straight"
+                    "sector_type": "Start"
                 },
                 {
                     "id": 1,
@@ -81,7 +81,7 @@ impl TestApp {
                     "min_value": 15,
                     "max_value": 25,
                     "slot_capacity": null,
-                    "sector_type": "Curve"
+                    "sector_type": "Finish"
                 }
             ],
             "total_laps": 3
@@ -167,27 +167,17 @@ impl TestApp {
             .expect("Failed to apply lap action")
     }
 
-    // Helper to get detailed race status
+    // Helper to get detailed race status, as the player signed in with `cookies`
     pub async fn get_race_status_detailed(
         &self,
         race_uuid: &str,
-        player_uuid: Option<&str>,
         cookies: &str,
     ) -> reqwest::Response {
-        let url = if let Some(player_uuid) = player_uuid {
-            format!(
-                "{}/api/v1/races/{}/status-detailed?player_uuid={}",
-                &self.address, race_uuid, player_uuid
-            )
-        } else {
-            format!(
+        self.client
+            .get(format!(
                 "{}/api/v1/races/{}/status-detailed",
                 &self.address, race_uuid
-            )
-        };
-
-        self.client
-            .get(&url)
+            ))
             .header("Cookie", cookies)
             .send()
             .await
@@ -288,9 +278,7 @@ async fn test_boost_hand_initializes_with_all_cards_available() {
     assert_eq!(200, start_response.status().as_u16());
 
     // Get detailed status
-    let status_response = app
-        .get_race_status_detailed(&race_uuid, Some(&player_uuid), &cookies)
-        .await;
+    let status_response = app.get_race_status_detailed(&race_uuid, &cookies).await;
     assert_eq!(200, status_response.status().as_u16());
 
     let status_data: Value = status_response
@@ -420,9 +408,7 @@ async fn test_boost_hand_replenishes_after_all_cards_used() {
     }
 
     // Get status after using all cards
-    let status_response = app
-        .get_race_status_detailed(&race_uuid, Some(&player_uuid), &cookies)
-        .await;
+    let status_response = app.get_race_status_detailed(&race_uuid, &cookies).await;
     assert_eq!(200, status_response.status().as_u16());
 
     let status_data: Value = status_response
@@ -466,9 +452,7 @@ async fn test_boost_hand_state_persists_in_database() {
         .await;
 
     // Get status (which reads from database)
-    let status_response = app
-        .get_race_status_detailed(&race_uuid, Some(&player_uuid), &cookies)
-        .await;
+    let status_response = app.get_race_status_detailed(&race_uuid, &cookies).await;
     assert_eq!(200, status_response.status().as_u16());
 
     let status_data: Value = status_response
@@ -511,9 +495,7 @@ async fn test_boost_usage_history_tracks_all_usages() {
     }
 
     // Get status
-    let status_response = app
-        .get_race_status_detailed(&race_uuid, Some(&player_uuid), &cookies)
-        .await;
+    let status_response = app.get_race_status_detailed(&race_uuid, &cookies).await;
     let status_data: Value = status_response
         .json()
         .await
@@ -586,9 +568,7 @@ async fn test_boost_impact_preview_shows_only_available_cards() {
         .await;
 
     // Get status
-    let status_response = app
-        .get_race_status_detailed(&race_uuid, Some(&player_uuid), &cookies)
-        .await;
+    let status_response = app.get_race_status_detailed(&race_uuid, &cookies).await;
     let status_data: Value = status_response
         .json()
         .await
@@ -640,9 +620,7 @@ async fn test_multiple_cycles_track_correctly() {
         .await;
 
     // Get status
-    let status_response = app
-        .get_race_status_detailed(&race_uuid, Some(&player_uuid), &cookies)
-        .await;
+    let status_response = app.get_race_status_detailed(&race_uuid, &cookies).await;
     let status_data: Value = status_response
         .json()
         .await
@@ -693,9 +671,7 @@ async fn test_boost_cycle_summaries_calculated_correctly() {
     }
 
     // Get status
-    let status_response = app
-        .get_race_status_detailed(&race_uuid, Some(&player_uuid), &cookies)
-        .await;
+    let status_response = app.get_race_status_detailed(&race_uuid, &cookies).await;
     let status_data: Value = status_response
         .json()
         .await
@@ -762,7 +738,7 @@ async fn test_concurrent_lap_submissions_handle_boost_cards_correctly() {
 
     // Verify each player's boost hand is independent
     let status1_response = app
-        .get_race_status_detailed(&race_uuid, Some(&player1_uuid), &player1_cookies)
+        .get_race_status_detailed(&race_uuid, &player1_cookies)
         .await;
     let status1_data: Value = status1_response
         .json()
@@ -772,7 +748,7 @@ async fn test_concurrent_lap_submissions_handle_boost_cards_correctly() {
     assert_eq!(boost1["cards_remaining"], 4);
 
     let status2_response = app
-        .get_race_status_detailed(&race_uuid, Some(&player2_uuid), &player2_cookies)
+        .get_race_status_detailed(&race_uuid, &player2_cookies)
         .await;
     let status2_data: Value = status2_response
         .json()
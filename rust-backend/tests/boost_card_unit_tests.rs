@@ -22,6 +22,8 @@ fn create_test_track() -> Track {
                 max_value: 20,
                 slot_capacity: Some(5),
                 sector_type: SectorType::Straight,
+                capacity_schedule: Vec::new(),
+                modifiers: None,
             },
             Sector {
                 id: 1,
@@ -30,6 +32,8 @@ fn create_test_track() -> Track {
                 max_value: 25,
                 slot_capacity: Some(5),
                 sector_type: SectorType::Curve,
+                capacity_schedule: Vec::new(),
+                modifiers: None,
             },
         ],
     }
@@ -223,6 +227,7 @@ fn test_boost_usage_history_tracks_all_usages() {
             cycle_number: participant.boost_hand.current_cycle,
             cards_remaining_after: participant.boost_hand.cards_remaining,
             replenishment_occurred: false,
+            lap_characteristic: None,
         };
         participant.boost_usage_history.push(usage_record);
     }
@@ -360,6 +365,7 @@ fn test_boost_cycle_summaries_calculated_correctly() {
             cycle_number: 1, // First cycle
             cards_remaining_after: participant.boost_hand.cards_remaining,
             replenishment_occurred: lap_number == 4, // Last card triggers replenishment
+            lap_characteristic: None,
         };
         participant.boost_usage_history.push(usage_record);
     }
@@ -1,6 +1,7 @@
 //! Test to verify that the integration test environment setup works correctly
 
-use rust_backend::configuration::get_configuration;
+use rust_backend::configuration::{get_configuration, DatabaseBackend};
+use rust_backend::startup::Application;
 use secrecy::ExposeSecret;
 
 #[tokio::test]
@@ -28,3 +29,124 @@ async fn test_integration_test_environment_setup() {
     println!("   Database: {}", config.database.database_name);
     println!("   Connection: {connection_string}");
 }
+
+#[tokio::test]
+async fn test_memory_backend_serves_auth_without_mongodb() {
+    std::env::set_var("APP_ENVIRONMENT", "test");
+
+    let mut config = get_configuration().expect("Failed to read configuration");
+    assert_eq!(config.database.backend, DatabaseBackend::Mongodb);
+
+    // Point at a port nothing listens on to prove no connection is made
+    config.database.backend = DatabaseBackend::Memory;
+    config.database.port = 1;
+    config.application.port = 0;
+
+    let application = Application::build(config)
+        .await
+        .expect("Failed to build application");
+    let address = format!("http://127.0.0.1:{}", application.port());
+    tokio::spawn(application.run_until_stopped());
+
+    let client = reqwest::Client::new();
+    let response = client
+        .post(format!("{address}/api/v1/auth/register"))
+        .json(&serde_json::json!({
+            "email": "memory@example.com",
+            "password": "MemoryPass123",
+            "team_name": "Memory Team"
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(201, response.status().as_u16());
+}
+
+#[cfg(not(feature = "tls"))]
+#[tokio::test]
+async fn test_tls_listener_requires_the_tls_feature() {
+    use rust_backend::configuration::TlsSettings;
+
+    std::env::set_var("APP_ENVIRONMENT", "test");
+
+    let mut config = get_configuration().expect("Failed to read configuration");
+    config.database.backend = DatabaseBackend::Memory;
+    config.application.port = 0;
+    config.application.tls = Some(TlsSettings {
+        cert_file: "cert.pem".to_string(),
+        key_file: "key.pem".to_string(),
+    });
+
+    let error = Application::build(config)
+        .await
+        .err()
+        .expect("HTTPS should not be served without the tls feature");
+    assert!(error.to_string().contains("--features tls"));
+}
+
+#[cfg(not(feature = "grpc"))]
+#[tokio::test]
+async fn test_grpc_port_requires_the_grpc_feature() {
+    std::env::set_var("APP_ENVIRONMENT", "test");
+
+    let mut config = get_configuration().expect("Failed to read configuration");
+    config.database.backend = DatabaseBackend::Memory;
+    config.application.port = 0;
+    config.grpc.port = Some(0);
+
+    let error = Application::build(config)
+        .await
+        .err()
+        .expect("gRPC should not be served without the grpc feature");
+    assert!(error.to_string().contains("--features grpc"));
+}
+
+#[tokio::test]
+async fn test_cors_origins_come_from_configuration() {
+    std::env::set_var("APP_ENVIRONMENT", "test");
+
+    let mut config = get_configuration().expect("Failed to read configuration");
+    config.database.backend = DatabaseBackend::Memory;
+    config.application.port = 0;
+    config.cors.allowed_origins = vec!["https://play.example.com".to_string()];
+
+    let application = Application::build(config.clone())
+        .await
+        .expect("Failed to build application");
+    let address = format!("http://127.0.0.1:{}", application.port());
+    tokio::spawn(application.run_until_stopped());
+
+    let client = reqwest::Client::new();
+    let preflight = |origin: &'static str| {
+        client
+            .request(
+                reqwest::Method::OPTIONS,
+                format!("{address}/api/v1/auth/register"),
+            )
+            .header("Origin", origin)
+            .header("Access-Control-Request-Method", "POST")
+            .header("Access-Control-Request-Headers", "content-type")
+            .send()
+    };
+
+    let allowed = preflight("https://play.example.com").await.unwrap();
+    assert_eq!(
+        allowed.headers()["access-control-allow-origin"],
+        "https://play.example.com"
+    );
+    assert_eq!(
+        allowed.headers()["access-control-allow-credentials"],
+        "true"
+    );
+
+    let refused = preflight("http://localhost:5173").await.unwrap();
+    assert!(refused
+        .headers()
+        .get("access-control-allow-origin")
+        .is_none());
+
+    // Browsers reject credentials for a wildcard origin
+    config.cors.allowed_origins = vec!["*".to_string()];
+    assert!(Application::build(config).await.is_err());
+}
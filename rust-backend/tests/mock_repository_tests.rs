@@ -2,8 +2,15 @@
 //! These tests demonstrate how to use mock repositories instead of real `MongoDB`
 //! for fast, isolated testing without external dependencies.
 
-use rust_backend::domain::{Email, HashedPassword, Player, TeamName};
-use rust_backend::repositories::{MockPlayerRepository, PlayerRepository};
+use chrono::{Duration, Utc};
+use rust_backend::domain::{
+    builtin_track_templates, Email, Friendship, HashedPassword, Player, TeamName, TrackDesign,
+};
+use rust_backend::repositories::{
+    FriendshipRepository, MockFriendshipRepository, MockPlayerRepository, MockSessionRepository,
+    MockTrackRepository, PlayerRepository, SessionRepository, TrackRepository,
+};
+use rust_backend::services::session::Session;
 use uuid::Uuid;
 
 // ============================================================================
@@ -131,6 +138,95 @@ async fn mock_player_repository_delete_works() {
     assert!(found_after.is_none());
 }
 
+#[tokio::test]
+async fn mock_track_repository_crud_works() {
+    // Arrange
+    let repo = MockTrackRepository::new();
+    let mut track = create_test_track(true);
+    let private_track = create_test_track(false);
+
+    // Act - Create tracks
+    repo.create(&track).await.unwrap();
+    repo.create(&private_track).await.unwrap();
+
+    // Assert - Only the public track is listed, both can be found by UUID
+    let public_tracks = repo.find_public().await.unwrap();
+    assert_eq!(public_tracks.len(), 1);
+    assert_eq!(public_tracks[0].uuid, track.uuid);
+    assert!(repo
+        .find_by_uuid(private_track.uuid)
+        .await
+        .unwrap()
+        .is_some());
+
+    // Act - Update and delete
+    track.name = "Renamed Track".to_string();
+    let updated = repo.update(&track).await.unwrap().unwrap();
+    let deleted = repo.delete_by_uuid(private_track.uuid).await.unwrap();
+
+    // Assert
+    assert_eq!(updated.name, "Renamed Track");
+    assert!(deleted);
+    assert!(repo
+        .find_by_uuid(private_track.uuid)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(repo.update(&private_track).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn mock_track_repository_prevents_duplicate_uuids() {
+    // Arrange
+    let repo = MockTrackRepository::new();
+    let track = create_test_track(true);
+
+    // Act
+    repo.create(&track).await.unwrap();
+    let result = repo.create(&track).await;
+
+    // Assert
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn mock_friendship_repository_request_accept_remove_works() {
+    // Arrange
+    let repo = MockFriendshipRepository::new();
+    let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+    repo.create(&Friendship::request(alice, bob)).await.unwrap();
+
+    // Act & Assert: one friendship per pair, accepted only by the addressee
+    assert!(repo.create(&Friendship::request(bob, alice)).await.is_err());
+    assert!(repo.accept(bob, alice).await.unwrap().is_none());
+    let accepted = repo.accept(alice, bob).await.unwrap().unwrap();
+    assert!(accepted.is_accepted());
+    assert_eq!(repo.find_for_player(bob).await.unwrap().len(), 1);
+
+    assert!(repo.delete_between(bob, alice).await.unwrap());
+    assert!(repo.find_between(alice, bob).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn mock_session_repository_counts_active_sessions_of_all_users() {
+    // Arrange
+    let repo = MockSessionRepository::new();
+    let now = Utc::now();
+    repo.create(&create_test_session("active", now + Duration::hours(1)))
+        .await
+        .unwrap();
+    repo.create(&create_test_session("expired", now - Duration::hours(1)))
+        .await
+        .unwrap();
+    repo.create(&create_test_session("logged_out", now + Duration::hours(1)))
+        .await
+        .unwrap();
+    repo.deactivate("logged_out").await.unwrap();
+
+    // Act & Assert
+    assert_eq!(repo.count_active(now).await.unwrap(), 1);
+}
+
 // ============================================================================
 // HELPER FUNCTIONS
 // ============================================================================
@@ -149,3 +245,33 @@ fn create_test_player(email: &str, team_name: &str) -> Player {
     )
     .unwrap()
 }
+
+fn create_test_session(token: &str, expires_at: chrono::DateTime<Utc>) -> Session {
+    let now = Utc::now();
+    Session {
+        id: None,
+        user_uuid: Uuid::new_v4(),
+        token: token.to_string(),
+        created_at: now,
+        last_activity: now,
+        expires_at,
+        ip_address: None,
+        user_agent: None,
+        is_active: true,
+        updated_at: now,
+        device_fingerprint: None,
+    }
+}
+
+fn create_test_track(is_public: bool) -> TrackDesign {
+    let template = &builtin_track_templates()[0];
+    let mut track = TrackDesign::new(
+        "Test Track".to_string(),
+        String::new(),
+        template.sectors.clone(),
+        Some(Uuid::new_v4()),
+    )
+    .unwrap();
+    track.is_public = is_public;
+    track
+}
@@ -0,0 +1,232 @@
+//! Integration tests for trade settlement against `MongoDB`
+//! These tests verify that escrow moves are applied exactly once, that a
+//! settlement cut short is finished by repeating it, and that cancelling
+//! gives the offerer's items back.
+
+use mongodb::bson::doc;
+use mongodb::Database;
+use rust_backend::configuration::get_configuration;
+use rust_backend::domain::{
+    Email, Escrow, Password, Player, TeamName, TradeItems, TradeOffer, TradeStatus,
+};
+use rust_backend::repositories::{MongoTradeRepository, TradeRepository};
+use rust_backend::services::starter_pack::StarterPack;
+use rust_backend::startup::get_connection_pool;
+use uuid::Uuid;
+
+struct TradeTest {
+    database: Database,
+    repository: MongoTradeRepository,
+    offerer: Player,
+    recipient: Player,
+}
+
+impl TradeTest {
+    // Two players in a fresh database: the offerer with a spare engine and
+    // 100 credits, the recipient with 300 credits
+    async fn new() -> Self {
+        std::env::set_var("APP_ENVIRONMENT", "test");
+        let mut configuration = get_configuration().expect("Failed to read configuration.");
+        configuration.database.database_name = Uuid::new_v4().to_string();
+        let database = get_connection_pool(&configuration.database)
+            .await
+            .expect("Failed to connect to database");
+
+        let mut offerer = player("offerer@example.com");
+        offerer.cars[0].unassign_engine();
+        offerer.credits = 100;
+        let mut recipient = player("recipient@example.com");
+        recipient.credits = 300;
+        database
+            .collection::<Player>("players")
+            .insert_many([&offerer, &recipient], None)
+            .await
+            .expect("Failed to store players");
+
+        Self {
+            repository: MongoTradeRepository::new(&database),
+            database,
+            offerer,
+            recipient,
+        }
+    }
+
+    // The offerer's spare engine for 200 of the recipient's credits
+    async fn open(&self) -> TradeOffer {
+        let offer = TradeOffer::new(
+            self.offerer.uuid,
+            self.recipient.uuid,
+            TradeItems {
+                engine_uuids: vec![self.engine_uuid()],
+                ..TradeItems::default()
+            },
+            TradeItems {
+                credits: 200,
+                ..TradeItems::default()
+            },
+        );
+        self.repository
+            .open(offer)
+            .await
+            .expect("Failed to open trade")
+    }
+
+    fn engine_uuid(&self) -> Uuid {
+        self.offerer.engines[0].uuid
+    }
+
+    async fn stored(&self, player_uuid: Uuid) -> Player {
+        self.database
+            .collection::<Player>("players")
+            .find_one(doc! { "uuid": player_uuid.to_string() }, None)
+            .await
+            .expect("Failed to load player")
+            .expect("Player not found")
+    }
+
+    async fn engine_count(&self, player_uuid: Uuid) -> usize {
+        let engine_uuid = self.engine_uuid();
+        self.stored(player_uuid)
+            .await
+            .engines
+            .iter()
+            .filter(|engine| engine.uuid == engine_uuid)
+            .count()
+    }
+}
+
+fn player(email: &str) -> Player {
+    StarterPack::new()
+        .unwrap()
+        .grant(
+            Email::parse(email).unwrap(),
+            Password::new("TraderPass123".to_string())
+                .unwrap()
+                .hash()
+                .unwrap(),
+            TeamName::parse("Traders").unwrap(),
+        )
+        .unwrap()
+}
+
+#[tokio::test]
+async fn accepted_trade_swaps_the_items() {
+    let test = TradeTest::new().await;
+    let trade = test.open().await;
+    assert_eq!(test.engine_count(test.offerer.uuid).await, 0);
+
+    let settled = test
+        .repository
+        .accept(trade.uuid, test.recipient.uuid)
+        .await
+        .expect("Failed to accept trade")
+        .expect("Trade not open");
+
+    assert_eq!(settled.status, TradeStatus::Completed);
+    assert_eq!(test.engine_count(test.recipient.uuid).await, 1);
+    let offerer = test.stored(test.offerer.uuid).await;
+    let recipient = test.stored(test.recipient.uuid).await;
+    assert_eq!(offerer.credits, 300);
+    assert_eq!(recipient.credits, 100);
+    assert!(offerer.trade_steps.is_empty());
+    assert!(recipient.trade_steps.is_empty());
+}
+
+#[tokio::test]
+async fn settlement_cut_short_is_resumed_without_moving_items_twice() {
+    let test = TradeTest::new().await;
+    let trade = test.open().await;
+
+    // The recipient's credits went into escrow and they received the engine,
+    // then the server stopped before paying the offerer
+    let recipient_escrow = Escrow {
+        credits: 200,
+        ..Escrow::default()
+    };
+    test.database
+        .collection::<TradeOffer>("trades")
+        .update_one(
+            doc! { "uuid": trade.uuid.to_string() },
+            doc! { "$set": {
+                "status": "Settling",
+                "recipient_escrow": mongodb::bson::to_bson(&recipient_escrow).unwrap(),
+            } },
+            None,
+        )
+        .await
+        .unwrap();
+    let engines = mongodb::bson::to_bson(&trade.offerer_escrow.unwrap().engines).unwrap();
+    test.database
+        .collection::<Player>("players")
+        .update_one(
+            doc! { "uuid": test.recipient.uuid.to_string() },
+            doc! {
+                "$inc": { "credits": -200_i64 },
+                "$push": {
+                    "engines": { "$each": engines },
+                    "trade_steps": {
+                        "$each": [
+                            format!("{}:escrow-recipient", trade.uuid),
+                            format!("{}:deliver-recipient", trade.uuid),
+                        ]
+                    },
+                },
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    let settled = test
+        .repository
+        .accept(trade.uuid, test.recipient.uuid)
+        .await
+        .expect("Failed to resume trade")
+        .expect("Trade not settling");
+
+    assert_eq!(settled.status, TradeStatus::Completed);
+    assert_eq!(test.engine_count(test.recipient.uuid).await, 1);
+    assert_eq!(test.stored(test.recipient.uuid).await.credits, 100);
+    assert_eq!(test.stored(test.offerer.uuid).await.credits, 300);
+}
+
+#[tokio::test]
+async fn cancelled_trade_refunds_the_offerer_once() {
+    let test = TradeTest::new().await;
+    let trade = test.open().await;
+
+    let cancelled = test
+        .repository
+        .cancel(trade.uuid, test.recipient.uuid)
+        .await
+        .expect("Failed to cancel trade")
+        .expect("Trade not open");
+    assert_eq!(cancelled.status, TradeStatus::Cancelled);
+    assert_eq!(test.engine_count(test.offerer.uuid).await, 1);
+
+    let again = test
+        .repository
+        .cancel(trade.uuid, test.offerer.uuid)
+        .await
+        .expect("Failed to cancel trade");
+    assert!(again.is_none());
+    assert_eq!(test.engine_count(test.offerer.uuid).await, 1);
+    assert_eq!(test.stored(test.offerer.uuid).await.credits, 100);
+}
+
+#[tokio::test]
+async fn refund_finding_no_offerer_is_an_error() {
+    let test = TradeTest::new().await;
+    let trade = test.open().await;
+    test.database
+        .collection::<Player>("players")
+        .delete_one(doc! { "uuid": test.offerer.uuid.to_string() }, None)
+        .await
+        .unwrap();
+
+    assert!(test
+        .repository
+        .cancel(trade.uuid, test.recipient.uuid)
+        .await
+        .is_err());
+}